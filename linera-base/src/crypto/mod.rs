@@ -6,6 +6,7 @@
 
 mod ed25519;
 mod hash;
+mod multisig;
 #[allow(dead_code)]
 mod secp256k1;
 pub mod signer;
@@ -17,6 +18,7 @@ use custom_debug_derive::Debug;
 pub use ed25519::{Ed25519PublicKey, Ed25519SecretKey, Ed25519Signature};
 pub use hash::*;
 use linera_witty::{WitLoad, WitStore, WitType};
+pub use multisig::MultisigPolicy;
 pub use secp256k1::{
     evm::{EvmPublicKey, EvmSecretKey, EvmSignature},
     Secp256k1PublicKey, Secp256k1SecretKey, Secp256k1Signature,
@@ -76,7 +78,7 @@ pub enum AccountSecretKey {
 }
 
 /// The signature of a chain owner.
-#[derive(Eq, PartialEq, Copy, Clone, Debug, Serialize, Deserialize, Allocative)]
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, Allocative)]
 pub enum AccountSignature {
     /// Ed25519 signature.
     Ed25519 {
@@ -106,6 +108,13 @@ pub enum AccountSignature {
         #[allocative(visit = visit_allocative_simple)]
         address: [u8; 20],
     },
+    /// Signatures of a threshold of the members of a multi-signature owner.
+    Multisig {
+        /// The policy, which determines the owner.
+        policy: MultisigPolicy,
+        /// The members' signatures, sorted by signer.
+        signatures: Vec<AccountSignature>,
+    },
 }
 
 impl AccountSecretKey {
@@ -247,6 +256,7 @@ impl AccountSignature {
                 signature.check_with_recover(value, *sender_address)?;
                 Ok(())
             }
+            AccountSignature::Multisig { policy, signatures } => policy.check(value, signatures),
         }
     }
 
@@ -266,6 +276,7 @@ impl AccountSignature {
             AccountSignature::Ed25519 { public_key, .. } => AccountOwner::from(*public_key),
             AccountSignature::Secp256k1 { public_key, .. } => AccountOwner::from(*public_key),
             AccountSignature::EvmSecp256k1 { address, .. } => AccountOwner::Address20(*address),
+            AccountSignature::Multisig { policy, .. } => policy.owner(),
         }
     }
 }
@@ -285,6 +296,13 @@ impl Display for AccountPublicKey {
     }
 }
 
+// Signatures are equal if and only if their serializations are.
+impl std::hash::Hash for AccountSignature {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.to_bytes().hash(state);
+    }
+}
+
 impl TryFrom<&[u8]> for AccountSignature {
     type Error = CryptoError;
 
@@ -334,6 +352,12 @@ pub enum CryptoError {
     PublicKeyParseError(bcs::Error),
     #[error("could not parse signature: {0}")]
     SignatureParseError(bcs::Error),
+    #[error("invalid multi-signature: {0}")]
+    InvalidMultisigPolicy(&'static str),
+    #[error("multi-signature has {collected} signatures but requires {threshold}")]
+    InsufficientMultisigSignatures { collected: usize, threshold: u16 },
+    #[error("{0} is not a member of the multi-signature owner")]
+    UnexpectedMultisigSigner(AccountOwner),
 }
 
 #[cfg(with_getrandom)]
@@ -476,6 +500,8 @@ pub fn u64_array_to_be_bytes(integers: [u64; 4]) -> [u8; 32] {
 
 #[cfg(test)]
 mod tests {
+    use assert_matches::assert_matches;
+
     use super::*;
     use crate::crypto::{ed25519::Ed25519SecretKey, secp256k1::Secp256k1KeyPair};
 
@@ -547,6 +573,68 @@ mod tests {
         roundtrip_test(&AccountSecretKey::EvmSecp256k1(EvmSecretKey::generate()));
     }
 
+    #[test]
+    fn multisig_requires_threshold_of_distinct_members() {
+        let secrets = [
+            AccountSecretKey::Ed25519(Ed25519SecretKey::generate()),
+            AccountSecretKey::Secp256k1(Secp256k1KeyPair::generate().secret_key),
+            AccountSecretKey::EvmSecp256k1(EvmSecretKey::generate()),
+        ];
+        let policy = MultisigPolicy::new(2, secrets.iter().map(AccountSecretKey::public)).unwrap();
+        let value = TestString::new("test");
+
+        let signature = policy
+            .combine(secrets[1..].iter().map(|secret| secret.sign(&value)))
+            .unwrap();
+        assert_eq!(signature.owner(), policy.owner());
+        signature.verify(&value).unwrap();
+        assert!(signature.verify(&TestString::new("other")).is_err());
+        let parsed = AccountSignature::from_slice(&signature.to_bytes()).unwrap();
+        assert_eq!(signature, parsed);
+
+        assert_matches!(
+            policy.combine([secrets[0].sign(&value)]),
+            Err(CryptoError::InsufficientMultisigSignatures {
+                collected: 1,
+                threshold: 2
+            })
+        );
+
+        let repeated = AccountSignature::Multisig {
+            policy: policy.clone(),
+            signatures: vec![secrets[0].sign(&value), secrets[0].sign(&value)],
+        };
+        assert_matches!(
+            repeated.verify(&value),
+            Err(CryptoError::InvalidMultisigPolicy(_))
+        );
+
+        let outsider = AccountSecretKey::Ed25519(Ed25519SecretKey::generate());
+        let mut signatures = vec![secrets[0].sign(&value), outsider.sign(&value)];
+        signatures.sort_by_key(AccountSignature::owner);
+        let forged = AccountSignature::Multisig { policy, signatures };
+        assert_matches!(
+            forged.verify(&value),
+            Err(CryptoError::UnexpectedMultisigSigner(owner)) if owner == outsider.public().into()
+        );
+    }
+
+    #[test]
+    fn multisig_policy_is_canonical() {
+        let keys = [AccountPublicKey::test_key(1), AccountPublicKey::test_key(2)];
+        let policy = MultisigPolicy::new(1, keys).unwrap();
+        let reversed = MultisigPolicy::new(1, keys.into_iter().rev().chain([keys[0]])).unwrap();
+        assert_eq!(policy.owner(), reversed.owner());
+        assert_matches!(
+            MultisigPolicy::new(0, keys),
+            Err(CryptoError::InvalidMultisigPolicy(_))
+        );
+        assert_matches!(
+            MultisigPolicy::new(3, keys),
+            Err(CryptoError::InvalidMultisigPolicy(_))
+        );
+    }
+
     #[test]
     fn roundtrip_display_from_str_pk() {
         fn test(secret: &AccountSecretKey) {
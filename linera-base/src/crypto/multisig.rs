@@ -0,0 +1,171 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Threshold owners: accounts and chains controlled by `k` out of `n` public keys.
+
+use std::collections::BTreeMap;
+
+use allocative::Allocative;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    AccountPublicKey, AccountSignature, BcsHashable, BcsSignable, CryptoError, CryptoHash,
+};
+use crate::identifiers::AccountOwner;
+
+/// A `k`-of-`n` signature policy over account public keys, which may mix Ed25519,
+/// secp256k1 and EVM keys.
+///
+/// The policy is an owner in its own right: its [`AccountOwner`] is derived from the hash
+/// of the policy, so it can be used wherever a single-key owner can, e.g. as a chain owner
+/// or as the owner of an account. A [`AccountSignature::Multisig`] carries the policy
+/// together with the signatures of at least `threshold` distinct members.
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, Allocative)]
+pub struct MultisigPolicy {
+    /// The number of members that need to sign.
+    threshold: u16,
+    /// The public keys of the members, sorted and without duplicates.
+    public_keys: Vec<AccountPublicKey>,
+}
+
+impl BcsHashable<'_> for MultisigPolicy {}
+
+impl MultisigPolicy {
+    /// The maximum number of members of a policy.
+    pub const MAX_PUBLIC_KEYS: usize = 32;
+
+    /// Creates a policy requiring `threshold` signatures from the given `public_keys`.
+    ///
+    /// Keys are sorted and deduplicated, so that the same set of keys always yields the
+    /// same owner.
+    pub fn new(
+        threshold: u16,
+        public_keys: impl IntoIterator<Item = AccountPublicKey>,
+    ) -> Result<Self, CryptoError> {
+        let mut public_keys = public_keys.into_iter().collect::<Vec<_>>();
+        public_keys.sort();
+        public_keys.dedup();
+        let policy = MultisigPolicy {
+            threshold,
+            public_keys,
+        };
+        policy.check_well_formed()?;
+        Ok(policy)
+    }
+
+    /// Returns the number of members that need to sign.
+    pub fn threshold(&self) -> u16 {
+        self.threshold
+    }
+
+    /// Returns the public keys of the members.
+    pub fn public_keys(&self) -> &[AccountPublicKey] {
+        &self.public_keys
+    }
+
+    /// Returns the [`AccountOwner`] controlled by this policy.
+    pub fn owner(&self) -> AccountOwner {
+        AccountOwner::Address32(CryptoHash::new(self))
+    }
+
+    /// Returns the owners corresponding to the members' public keys.
+    pub fn members(&self) -> impl Iterator<Item = AccountOwner> + '_ {
+        self.public_keys.iter().map(|key| AccountOwner::from(*key))
+    }
+
+    /// Returns whether `owner` is one of the members of this policy.
+    pub fn is_member(&self, owner: &AccountOwner) -> bool {
+        self.members().any(|member| member == *owner)
+    }
+
+    /// Combines the given member signatures into a [`AccountSignature::Multisig`].
+    ///
+    /// Signatures from non-members and duplicates are ignored, and only `threshold`
+    /// signatures are kept. The signatures themselves are not verified here.
+    pub fn combine(
+        &self,
+        signatures: impl IntoIterator<Item = AccountSignature>,
+    ) -> Result<AccountSignature, CryptoError> {
+        let by_signer = signatures
+            .into_iter()
+            .filter(|signature| self.is_member(&signature.owner()))
+            .map(|signature| (signature.owner(), signature))
+            .collect::<BTreeMap<_, _>>();
+        if by_signer.len() < usize::from(self.threshold) {
+            return Err(CryptoError::InsufficientMultisigSignatures {
+                collected: by_signer.len(),
+                threshold: self.threshold,
+            });
+        }
+        let signatures = by_signer
+            .into_values()
+            .take(usize::from(self.threshold))
+            .collect();
+        Ok(AccountSignature::Multisig {
+            policy: self.clone(),
+            signatures,
+        })
+    }
+
+    /// Verifies that `signatures` contains valid signatures of `value` from at least
+    /// `threshold` distinct members, sorted by signer.
+    pub(crate) fn check<'de, T>(
+        &self,
+        value: &T,
+        signatures: &[AccountSignature],
+    ) -> Result<(), CryptoError>
+    where
+        T: BcsSignable<'de> + std::fmt::Debug,
+    {
+        self.check_well_formed()?;
+        if signatures.len() < usize::from(self.threshold) {
+            return Err(CryptoError::InsufficientMultisigSignatures {
+                collected: signatures.len(),
+                threshold: self.threshold,
+            });
+        }
+        let mut previous_signer = None;
+        for signature in signatures {
+            if matches!(signature, AccountSignature::Multisig { .. }) {
+                return Err(CryptoError::InvalidMultisigPolicy(
+                    "multi-signatures cannot be nested",
+                ));
+            }
+            let signer = signature.owner();
+            if !self.is_member(&signer) {
+                return Err(CryptoError::UnexpectedMultisigSigner(signer));
+            }
+            // Requiring a strict order rules out counting the same member twice.
+            if previous_signer.is_some_and(|previous| previous >= signer) {
+                return Err(CryptoError::InvalidMultisigPolicy(
+                    "signatures must be sorted by signer and not repeat any signer",
+                ));
+            }
+            previous_signer = Some(signer);
+            signature.verify(value)?;
+        }
+        Ok(())
+    }
+
+    fn check_well_formed(&self) -> Result<(), CryptoError> {
+        if self.threshold == 0 {
+            return Err(CryptoError::InvalidMultisigPolicy(
+                "the threshold must be at least 1",
+            ));
+        }
+        if usize::from(self.threshold) > self.public_keys.len() {
+            return Err(CryptoError::InvalidMultisigPolicy(
+                "the threshold exceeds the number of public keys",
+            ));
+        }
+        if self.public_keys.len() > Self::MAX_PUBLIC_KEYS {
+            return Err(CryptoError::InvalidMultisigPolicy("too many public keys"));
+        }
+        if !self.public_keys.is_sorted_by(|a, b| a < b) {
+            return Err(CryptoError::InvalidMultisigPolicy(
+                "public keys must be sorted and unique",
+            ));
+        }
+        Ok(())
+    }
+}
@@ -66,7 +66,9 @@ mod in_mem {
     #[cfg(with_getrandom)]
     use crate::crypto::{AccountPublicKey, CryptoRng};
     use crate::{
-        crypto::{AccountSecretKey, AccountSignature, CryptoHash, Signer},
+        crypto::{
+            AccountSecretKey, AccountSignature, CryptoError, CryptoHash, MultisigPolicy, Signer,
+        },
         identifiers::AccountOwner,
     };
 
@@ -74,6 +76,10 @@ mod in_mem {
     pub enum Error {
         #[error("no key found for the given owner")]
         NoSuchOwner,
+        #[error("{0} is not a member of the multi-signature owner")]
+        NotAMember(AccountOwner),
+        #[error(transparent)]
+        Multisig(#[from] CryptoError),
     }
 
     /// In-memory signer.
@@ -131,11 +137,70 @@ mod in_mem {
         pub fn forget_key(&self, owner: &AccountOwner) -> bool {
             self.0.write().unwrap().keys.remove(owner).is_some()
         }
+
+        /// Registers a multi-signature owner, returning its [`AccountOwner`].
+        ///
+        /// Signing as that owner then combines the signatures of the members whose keys
+        /// this signer holds with the partial signatures added by
+        /// [`InMemorySigner::add_partial_signature`].
+        pub fn add_multisig_policy(&self, policy: MultisigPolicy) -> AccountOwner {
+            let owner = policy.owner();
+            self.0
+                .write()
+                .unwrap()
+                .multisig_policies
+                .insert(owner, policy);
+            owner
+        }
+
+        /// Returns the policy of the given multi-signature owner, if it is registered.
+        pub fn multisig_policy(&self, owner: &AccountOwner) -> Option<MultisigPolicy> {
+            self.0.read().unwrap().multisig_policies.get(owner).cloned()
+        }
+
+        /// Records a member's signature of `value`, collected from a co-signer of the
+        /// multi-signature `owner`. The signature itself is checked by validators when the
+        /// combined signature is used.
+        pub fn add_partial_signature(
+            &self,
+            owner: AccountOwner,
+            value: CryptoHash,
+            signature: AccountSignature,
+        ) -> Result<(), Error> {
+            let mut inner = self.0.write().unwrap();
+            let policy = inner
+                .multisig_policies
+                .get(&owner)
+                .ok_or(Error::NoSuchOwner)?;
+            let signer = signature.owner();
+            if !policy.is_member(&signer) {
+                return Err(Error::NotAMember(signer));
+            }
+            inner
+                .partial_signatures
+                .entry((owner, value))
+                .or_default()
+                .insert(signer, signature);
+            Ok(())
+        }
+
+        /// Discards the partial signatures collected for the multi-signature `owner`.
+        pub fn clear_partial_signatures(&self, owner: &AccountOwner) {
+            self.0
+                .write()
+                .unwrap()
+                .partial_signatures
+                .retain(|(multisig_owner, _), _| multisig_owner != owner);
+        }
     }
 
     #[derive(Debug, Deserialize, Serialize)]
     struct Inner {
         keys: Vec<(AccountOwner, String)>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        multisig_policies: Vec<MultisigPolicy>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        partial_signatures: Vec<(AccountOwner, CryptoHash, AccountSignature)>,
         #[cfg(with_getrandom)]
         prng_seed: Option<u64>,
     }
@@ -143,10 +208,16 @@ mod in_mem {
     /// In-memory signer.
     struct InMemSignerInner {
         keys: BTreeMap<AccountOwner, AccountSecretKey>,
+        multisig_policies: BTreeMap<AccountOwner, MultisigPolicy>,
+        partial_signatures: PartialSignatures,
         #[cfg(with_getrandom)]
         rng_state: RngState,
     }
 
+    /// Signatures collected from co-signers, by multi-signature owner and signed value.
+    type PartialSignatures =
+        BTreeMap<(AccountOwner, CryptoHash), BTreeMap<AccountOwner, AccountSignature>>;
+
     #[cfg(with_getrandom)]
     struct RngState {
         prng: Box<dyn CryptoRng>,
@@ -173,6 +244,8 @@ mod in_mem {
         pub fn new(prng_seed: Option<u64>) -> Self {
             InMemSignerInner {
                 keys: BTreeMap::new(),
+                multisig_policies: BTreeMap::new(),
+                partial_signatures: BTreeMap::new(),
                 rng_state: RngState::new(prng_seed),
             }
         }
@@ -182,6 +255,8 @@ mod in_mem {
         pub fn new() -> Self {
             InMemSignerInner {
                 keys: BTreeMap::new(),
+                multisig_policies: BTreeMap::new(),
+                partial_signatures: BTreeMap::new(),
             }
         }
 
@@ -194,6 +269,25 @@ mod in_mem {
                 })
                 .collect()
         }
+
+        /// Combines the available member signatures of `value` for a multi-signature owner.
+        fn sign_multisig(
+            &self,
+            owner: &AccountOwner,
+            policy: &MultisigPolicy,
+            value: &CryptoHash,
+        ) -> Result<AccountSignature, Error> {
+            let collected = self
+                .partial_signatures
+                .get(&(*owner, *value))
+                .into_iter()
+                .flat_map(|signatures| signatures.values().cloned());
+            let local = policy
+                .members()
+                .filter_map(|member| self.keys.get(&member))
+                .map(|secret| secret.sign_prehash(*value));
+            Ok(policy.combine(local.chain(collected))?)
+        }
     }
 
     impl Signer for InMemorySigner {
@@ -209,6 +303,8 @@ mod in_mem {
             if let Some(secret) = inner.keys.get(owner) {
                 let signature = secret.sign_prehash(*value);
                 Ok(signature)
+            } else if let Some(policy) = inner.multisig_policies.get(owner) {
+                inner.sign_multisig(owner, policy, value)
             } else {
                 Err(Error::NoSuchOwner)
             }
@@ -216,7 +312,8 @@ mod in_mem {
 
         /// Returns whether the given `owner` is a known signer.
         async fn contains_key(&self, owner: &AccountOwner) -> Result<bool, Error> {
-            Ok(self.0.read().unwrap().keys.contains_key(owner))
+            let inner = self.0.read().unwrap();
+            Ok(inner.keys.contains_key(owner) || inner.multisig_policies.contains_key(owner))
         }
     }
 
@@ -227,6 +324,8 @@ mod in_mem {
         {
             InMemorySigner(Arc::new(RwLock::new(InMemSignerInner {
                 keys: BTreeMap::from_iter(input),
+                multisig_policies: BTreeMap::new(),
+                partial_signatures: BTreeMap::new(),
                 #[cfg(with_getrandom)]
                 rng_state: RngState::new(None),
             })))
@@ -277,8 +376,21 @@ mod in_mem {
                 .map(|(owner, bytes)| (owner, hex::encode(bytes)))
                 .collect::<Vec<_>>();
 
+            let multisig_policies = self.multisig_policies.values().cloned().collect();
+            let partial_signatures = self
+                .partial_signatures
+                .iter()
+                .flat_map(|((owner, value), signatures)| {
+                    signatures
+                        .values()
+                        .map(|signature| (*owner, *value, signature.clone()))
+                })
+                .collect();
+
             let inner = Inner {
                 keys: keys_as_strings,
+                multisig_policies,
+                partial_signatures,
                 #[cfg(with_getrandom)]
                 prng_seed,
             };
@@ -306,8 +418,23 @@ mod in_mem {
                 })
                 .collect::<Result<BTreeMap<_, _>, _>>()?;
 
+            let multisig_policies = inner
+                .multisig_policies
+                .into_iter()
+                .map(|policy| (policy.owner(), policy))
+                .collect();
+            let mut partial_signatures = PartialSignatures::new();
+            for (owner, value, signature) in inner.partial_signatures {
+                partial_signatures
+                    .entry((owner, value))
+                    .or_default()
+                    .insert(signature.owner(), signature);
+            }
+
             let signer = InMemSignerInner {
                 keys,
+                multisig_policies,
+                partial_signatures,
                 #[cfg(with_getrandom)]
                 rng_state: RngState::new(inner.prng_seed),
             };
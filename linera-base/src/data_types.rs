@@ -29,7 +29,7 @@ use tracing::instrument;
 #[cfg(with_metrics)]
 use crate::prometheus_util::MeasureLatency as _;
 use crate::{
-    crypto::{BcsHashable, CryptoError, CryptoHash},
+    crypto::{BcsHashable, BcsSignable, CryptoError, CryptoHash},
     doc_scalar, hex_debug, http,
     identifiers::{
         AccountOwner, ApplicationId, BlobId, BlobType, ChainId, EventId, GenericApplicationId,
@@ -1292,6 +1292,234 @@ pub struct NetworkDescription {
     pub admin_chain_id: ChainId,
 }
 
+/// Restrictions on an allowance granted to a spender of native tokens.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    WitType,
+    WitLoad,
+    WitStore,
+    SimpleObject,
+    Allocative,
+)]
+pub struct AllowanceLimits {
+    /// If set, the allowance cannot be used any more from this time on.
+    #[debug(skip_if = Option::is_none)]
+    pub expiry: Option<Timestamp>,
+    /// If set, the spender can transfer at most this much per period.
+    #[debug(skip_if = Option::is_none)]
+    pub period_cap: Option<SpendingCap>,
+}
+
+/// A maximum amount that can be spent per period of time.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    WitType,
+    WitLoad,
+    WitStore,
+    SimpleObject,
+    Allocative,
+)]
+pub struct SpendingCap {
+    /// The maximum amount per period.
+    pub amount: Amount,
+    /// The length of a period. Periods start when the allowance is approved.
+    pub period: TimeDelta,
+}
+
+/// The [`AllowanceLimits`] of an allowance, together with what was spent in the current
+/// period.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, SimpleObject, Allocative)]
+pub struct AllowanceUsage {
+    /// The limits set when the allowance was approved.
+    pub limits: AllowanceLimits,
+    /// The start of the current period.
+    pub period_start: Timestamp,
+    /// The amount spent since `period_start`.
+    pub spent_in_period: Amount,
+}
+
+/// An error when using an allowance beyond its [`AllowanceLimits`].
+#[derive(Clone, Copy, Debug, Error, PartialEq, Eq)]
+pub enum AllowanceLimitError {
+    /// The allowance has expired.
+    #[error("the allowance expired at {0}")]
+    Expired(Timestamp),
+    /// The amount exceeds what is left of the cap in the current period.
+    #[error("only {remaining} can be spent until {next_period}")]
+    PeriodCapExceeded {
+        /// The amount that can still be spent in the current period.
+        remaining: Amount,
+        /// The start of the next period.
+        next_period: Timestamp,
+    },
+    /// A period cap must have a non-zero period.
+    #[error("the period of a spending cap must not be zero")]
+    EmptyPeriod,
+}
+
+impl AllowanceUsage {
+    /// Starts tracking an allowance with the given `limits`, approved at time `now`.
+    pub fn new(limits: AllowanceLimits, now: Timestamp) -> Result<Self, AllowanceLimitError> {
+        if limits
+            .period_cap
+            .is_some_and(|cap| cap.period == TimeDelta::ZERO)
+        {
+            return Err(AllowanceLimitError::EmptyPeriod);
+        }
+        Ok(AllowanceUsage {
+            limits,
+            period_start: now,
+            spent_in_period: Amount::ZERO,
+        })
+    }
+
+    /// Records that `amount` is spent at time `now`, or returns an error if that exceeds
+    /// the limits.
+    pub fn record_spending(
+        &mut self,
+        now: Timestamp,
+        amount: Amount,
+    ) -> Result<(), AllowanceLimitError> {
+        if let Some(expiry) = self.limits.expiry {
+            if now >= expiry {
+                return Err(AllowanceLimitError::Expired(expiry));
+            }
+        }
+        let Some(cap) = self.limits.period_cap else {
+            return Ok(());
+        };
+        let period = cap.period.as_micros();
+        if period == 0 {
+            return Err(AllowanceLimitError::EmptyPeriod);
+        }
+        let elapsed = now.delta_since(self.period_start).as_micros();
+        if elapsed >= period {
+            // Move to the start of the period that contains `now`.
+            self.period_start = Timestamp::from(now.micros() - elapsed % period);
+            self.spent_in_period = Amount::ZERO;
+        }
+        let remaining = cap.amount.saturating_sub(self.spent_in_period);
+        if amount > remaining {
+            return Err(AllowanceLimitError::PeriodCapExceeded {
+                remaining,
+                next_period: self.period_start.saturating_add(cap.period),
+            });
+        }
+        self.spent_in_period.saturating_add_assign(amount);
+        Ok(())
+    }
+}
+
+/// A schedule according to which locked tokens become spendable: nothing is released
+/// before the `cliff`, and the tokens are released linearly between `start` and `end`, so
+/// that a share proportional to the time elapsed since `start` is released at the cliff.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    SimpleObject,
+    InputObject,
+    Allocative,
+)]
+#[graphql(input_name = "VestingScheduleInput")]
+pub struct VestingSchedule {
+    /// The time from which the tokens start vesting.
+    pub start: Timestamp,
+    /// No tokens are released before this time.
+    pub cliff: Timestamp,
+    /// All tokens are released from this time on.
+    pub end: Timestamp,
+}
+
+impl VestingSchedule {
+    /// Returns a schedule that releases all tokens at once at the given time.
+    pub fn until(unlock_time: Timestamp) -> Self {
+        VestingSchedule {
+            start: unlock_time,
+            cliff: unlock_time,
+            end: unlock_time,
+        }
+    }
+
+    /// Returns whether `start <= cliff <= end`.
+    pub fn is_valid(&self) -> bool {
+        self.start <= self.cliff && self.cliff <= self.end
+    }
+}
+
+/// An amount of tokens in an account that is locked according to a [`VestingSchedule`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, SimpleObject, Allocative)]
+pub struct LockedTranche {
+    /// The total amount of tokens locked in this tranche initially.
+    pub amount: Amount,
+    /// The schedule according to which the tokens are released.
+    pub schedule: VestingSchedule,
+}
+
+impl LockedTranche {
+    /// Returns the amount of this tranche that is still locked at time `now`.
+    pub fn locked_amount(&self, now: Timestamp) -> Amount {
+        let VestingSchedule { start, cliff, end } = self.schedule;
+        if now >= end {
+            return Amount::ZERO;
+        }
+        if now < cliff {
+            return self.amount;
+        }
+        // Here `start <= cliff <= now < end`, so `duration` is positive and larger than
+        // `elapsed`. Splitting the amount avoids overflowing `u128`.
+        let duration = u128::from(end.delta_since(start).as_micros());
+        let elapsed = u128::from(now.delta_since(start).as_micros());
+        let attos = self.amount.to_attos();
+        let released = (attos / duration) * elapsed + (attos % duration) * elapsed / duration;
+        Amount::from_attos(attos - released)
+    }
+
+    /// Returns whether all tokens of this tranche are released at time `now`.
+    pub fn is_fully_vested(&self, now: Timestamp) -> bool {
+        now >= self.schedule.end
+    }
+}
+
+/// An offer by the `sponsor` to pay the fees of blocks on the chain `chain_id`, from its
+/// account on that chain. The sponsor pays at most `max_fees` in total, and only for blocks
+/// with a timestamp earlier than the `expiry`.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, SimpleObject, Allocative,
+)]
+pub struct FeeSponsorship {
+    /// The owner of the account that pays the fees.
+    pub sponsor: AccountOwner,
+    /// The chain whose blocks are sponsored.
+    pub chain_id: ChainId,
+    /// The maximum total amount of fees paid under this sponsorship.
+    pub max_fees: Amount,
+    /// The sponsorship can only be used by blocks before this time.
+    pub expiry: Timestamp,
+}
+
+impl BcsSignable<'_> for FeeSponsorship {}
+
 /// Permissions for applications on a chain.
 #[derive(
     Default,
@@ -2140,7 +2368,9 @@ mod tests {
 
     use alloy_primitives::U256;
 
-    use super::{Amount, ApplicationDescription, BlobContent};
+    use super::{
+        Amount, ApplicationDescription, BlobContent, LockedTranche, Timestamp, VestingSchedule,
+    };
     use crate::{
         crypto::CryptoHash,
         data_types::BlockHeight,
@@ -2148,6 +2378,92 @@ mod tests {
         vm::VmRuntime,
     };
 
+    #[test]
+    fn allowance_usage_enforces_expiry_and_period_cap() {
+        use super::{
+            AllowanceLimitError, AllowanceLimits, AllowanceUsage, SpendingCap, TimeDelta, Timestamp,
+        };
+
+        let limits = AllowanceLimits {
+            expiry: Some(Timestamp::from(1_000)),
+            period_cap: Some(SpendingCap {
+                amount: Amount::from_tokens(10),
+                period: TimeDelta::from_micros(100),
+            }),
+        };
+        let mut usage = AllowanceUsage::new(limits, Timestamp::from(50)).unwrap();
+        usage
+            .record_spending(Timestamp::from(60), Amount::from_tokens(6))
+            .unwrap();
+        assert_eq!(
+            usage.record_spending(Timestamp::from(149), Amount::from_tokens(5)),
+            Err(AllowanceLimitError::PeriodCapExceeded {
+                remaining: Amount::from_tokens(4),
+                next_period: Timestamp::from(150),
+            })
+        );
+        // A new period starts every 100 microseconds after the approval.
+        usage
+            .record_spending(Timestamp::from(370), Amount::from_tokens(10))
+            .unwrap();
+        assert_eq!(usage.period_start, Timestamp::from(350));
+        assert_eq!(
+            usage.record_spending(Timestamp::from(1_000), Amount::ONE),
+            Err(AllowanceLimitError::Expired(Timestamp::from(1_000)))
+        );
+
+        let no_period = AllowanceLimits {
+            expiry: None,
+            period_cap: Some(SpendingCap {
+                amount: Amount::ONE,
+                period: TimeDelta::ZERO,
+            }),
+        };
+        assert_eq!(
+            AllowanceUsage::new(no_period, Timestamp::from(0)),
+            Err(AllowanceLimitError::EmptyPeriod)
+        );
+    }
+
+    #[test]
+    fn locked_tranche_vests_linearly_after_cliff() {
+        let tranche = LockedTranche {
+            amount: Amount::from_tokens(100),
+            schedule: VestingSchedule {
+                start: Timestamp::from(1_000),
+                cliff: Timestamp::from(1_250),
+                end: Timestamp::from(2_000),
+            },
+        };
+        assert!(tranche.schedule.is_valid());
+        let locked_at = |micros| tranche.locked_amount(Timestamp::from(micros));
+        assert_eq!(locked_at(0), Amount::from_tokens(100));
+        assert_eq!(locked_at(1_249), Amount::from_tokens(100));
+        assert_eq!(locked_at(1_250), Amount::from_tokens(75));
+        assert_eq!(locked_at(1_500), Amount::from_tokens(50));
+        assert_eq!(locked_at(1_999), Amount::from_millis(100));
+        assert_eq!(locked_at(2_000), Amount::ZERO);
+        assert!(!tranche.is_fully_vested(Timestamp::from(1_999)));
+        assert!(tranche.is_fully_vested(Timestamp::from(2_000)));
+
+        let time_lock = LockedTranche {
+            amount: Amount::MAX,
+            schedule: VestingSchedule::until(Timestamp::from(u64::MAX - 1)),
+        };
+        assert_eq!(time_lock.locked_amount(Timestamp::from(0)), Amount::MAX);
+        assert_eq!(
+            time_lock.locked_amount(Timestamp::from(u64::MAX - 1)),
+            Amount::ZERO
+        );
+
+        let invalid = VestingSchedule {
+            start: Timestamp::from(2),
+            cliff: Timestamp::from(1),
+            end: Timestamp::from(3),
+        };
+        assert!(!invalid.is_valid());
+    }
+
     #[test]
     fn non_canonical_btree_map_serializes_like_vec() {
         use std::collections::BTreeMap;
@@ -10,7 +10,10 @@ use test_case::test_case;
 
 use crate::{
     crypto::{AccountPublicKey, CryptoHash},
-    data_types::{Amount, BlockHeight, Resources, SendMessageRequest, TimeDelta, Timestamp},
+    data_types::{
+        AllowanceLimits, Amount, BlockHeight, Resources, SendMessageRequest, SpendingCap,
+        TimeDelta, Timestamp,
+    },
     identifiers::{Account, AccountOwner, ApplicationId, ChainId, ModuleId},
     ownership::{ChainOwnership, TimeoutConfig},
     vm::VmRuntime,
@@ -31,6 +34,7 @@ use crate::{
 #[test_case(&module_id_test_case(); "of_module_id")]
 #[test_case(&timeout_config_test_case(); "of_timeout_config")]
 #[test_case(&chain_ownership_test_case(); "of_chain_ownership")]
+#[test_case(&allowance_limits_test_case(); "of_allowance_limits")]
 #[test_case(&[5u8; 20]; "array20")]
 fn test_wit_roundtrip<T>(input: &T)
 where
@@ -155,6 +159,17 @@ fn chain_ownership_test_case() -> ChainOwnership {
     }
 }
 
+/// Creates a dummy [`AllowanceLimits`] instance to use for the WIT roundtrip test.
+fn allowance_limits_test_case() -> AllowanceLimits {
+    AllowanceLimits {
+        expiry: Some(Timestamp::from(1_700_000)),
+        period_cap: Some(SpendingCap {
+            amount: Amount::from_tokens(10),
+            period: TimeDelta::from_secs(86_400),
+        }),
+    }
+}
+
 #[test]
 fn account_owner_debug_format() {
     assert_eq!(&format!("{:?}", AccountOwner::Reserved(10)), "Reserved(10)");
@@ -9,13 +9,13 @@ use linera_base::prometheus_util::MeasureLatency;
 use linera_base::{
     data_types::{Amount, ArithmeticError, Blob, BlockHeight, Event, OracleResponse, Timestamp},
     ensure,
-    identifiers::{AccountOwner, BlobId, ChainId, StreamId},
+    identifiers::{AccountOwner, BlobId, ChainId, GenericApplicationId, StreamId},
 };
 use linera_execution::{
-    execution_state_actor::ExecutionStateActor, ExecutionRuntimeContext, ExecutionStateView,
-    Message, MessageContext, MessageKind, OperationContext, OutgoingMessage, PreparedCheckpoint,
-    ResourceController, ResourceTracker, SystemExecutionStateView, TransactionOutcome,
-    TransactionTracker,
+    evm::trace::EvmTraceCollector, execution_state_actor::ExecutionStateActor,
+    ExecutionRuntimeContext, ExecutionStateView, MessageContext, MessageKind, OperationContext,
+    OutgoingMessage, PreparedCheckpoint, ResourceController, ResourceTracker,
+    SystemExecutionStateView, TransactionOutcome, TransactionTracker, WasmProfile,
 };
 use linera_views::context::Context;
 use tracing::instrument;
@@ -69,6 +69,16 @@ pub struct BlockExecutionTracker<'resources, 'blobs> {
     // operation handler when it runs.
     #[debug(skip_if = Option::is_none)]
     prepared_checkpoint: Option<PreparedCheckpoint>,
+
+    // The index of the transaction whose EVM executions are traced, and where to record
+    // the traces.
+    #[debug(skip_if = Option::is_none)]
+    evm_tracing: Option<(u32, EvmTraceCollector)>,
+
+    // The index of the transaction whose Wasm contract executions are profiled, and where
+    // to record the profile.
+    #[debug(skip_if = Option::is_none)]
+    wasm_profiling: Option<(u32, WasmProfile)>,
 }
 
 impl<'resources, 'blobs> BlockExecutionTracker<'resources, 'blobs> {
@@ -107,6 +117,8 @@ impl<'resources, 'blobs> BlockExecutionTracker<'resources, 'blobs> {
             transaction_index: 0,
             published_blobs,
             prepared_checkpoint: None,
+            evm_tracing: None,
+            wasm_profiling: None,
         })
     }
 
@@ -116,6 +128,18 @@ impl<'resources, 'blobs> BlockExecutionTracker<'resources, 'blobs> {
         self.prepared_checkpoint = Some(prepared);
     }
 
+    /// Traces the EVM executions of the transaction with the given index, recording the
+    /// traces in `evm_traces`.
+    pub fn trace_evm_transaction(&mut self, transaction_index: u32, evm_traces: EvmTraceCollector) {
+        self.evm_tracing = Some((transaction_index, evm_traces));
+    }
+
+    /// Profiles the Wasm contract executions of the transaction with the given index,
+    /// recording the profile in `wasm_profile`.
+    pub fn profile_wasm_transaction(&mut self, transaction_index: u32, wasm_profile: WasmProfile) {
+        self.wasm_profiling = Some((transaction_index, wasm_profile));
+    }
+
     /// Executes a transaction in the context of the block.
     #[instrument(skip_all, fields(
         chain_id = %self.chain_id,
@@ -210,6 +234,40 @@ impl<'resources, 'blobs> BlockExecutionTracker<'resources, 'blobs> {
         Ok(())
     }
 
+    /// Drops a due scheduled operation that failed to execute, instead of executing it. The
+    /// operation is removed from the chain's state and its transaction has an empty outcome, so
+    /// that it doesn't fail every later block.
+    pub async fn drop_scheduled_operation<C>(
+        &mut self,
+        transaction: &Transaction,
+        chain: &mut ExecutionStateView<C>,
+    ) -> Result<(), ChainError>
+    where
+        C: Context + Clone + 'static,
+        C::Extra: ExecutionRuntimeContext,
+    {
+        let chain_execution_context = self.chain_execution_context(transaction);
+        let id = transaction.scheduled_operation_id().ok_or_else(|| {
+            ChainError::InternalError("only scheduled operations can be dropped".to_string())
+        })?;
+        chain
+            .system
+            .drop_due_scheduled_operation(id, self.timestamp)
+            .await
+            .with_execution_context(chain_execution_context)?;
+        let progress = chain.system.progress.get_mut();
+        progress.num_operations = progress
+            .num_operations
+            .checked_add(1)
+            .ok_or(ArithmeticError::Overflow)?;
+        let txn_outcome = self
+            .new_transaction_tracker()?
+            .into_outcome()
+            .with_execution_context(chain_execution_context)?;
+        self.process_txn_outcome(txn_outcome, &mut chain.system, chain_execution_context)
+            .await
+    }
+
     /// Returns a new TransactionTracker for the current transaction.
     fn new_transaction_tracker(&self) -> Result<TransactionTracker, ChainError> {
         let mut tracker = TransactionTracker::new(
@@ -224,6 +282,16 @@ impl<'resources, 'blobs> BlockExecutionTracker<'resources, 'blobs> {
         if let Some(prepared) = self.prepared_checkpoint.as_ref() {
             tracker.set_prepared_checkpoint(prepared.clone());
         }
+        if let Some((transaction_index, evm_traces)) = &self.evm_tracing {
+            if *transaction_index == self.transaction_index {
+                tracker.trace_evm(evm_traces.clone());
+            }
+        }
+        if let Some((transaction_index, wasm_profile)) = &self.wasm_profiling {
+            if *transaction_index == self.transaction_index {
+                tracker.profile_wasm(wasm_profile.clone());
+            }
+        }
         Ok(tracker)
     }
 
@@ -364,8 +432,9 @@ impl<'resources, 'blobs> BlockExecutionTracker<'resources, 'blobs> {
             if message_out.kind == MessageKind::Bouncing {
                 continue; // Bouncing messages are free.
             }
-            if let Message::User { application_id, .. } = &message_out.message {
-                if resource_controller.policy().is_free_app(application_id) {
+            if let GenericApplicationId::User(application_id) = message_out.message.application_id()
+            {
+                if resource_controller.policy().is_free_app(&application_id) {
                     continue; // Outgoing message fees are waived for free apps.
                 }
             }
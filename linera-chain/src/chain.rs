@@ -20,9 +20,9 @@ use linera_base::{
     time::{Duration, Instant},
 };
 use linera_execution::{
-    committee::Committee, ExecutionRuntimeContext, ExecutionStateView, Message, Operation,
-    PreparedCheckpoint, Query, QueryContext, QueryOutcome, ResourceController, ResourceTracker,
-    ServiceRuntimeEndpoint, TransactionTracker,
+    committee::Committee, evm::trace::EvmTraceCollector, ExecutionRuntimeContext,
+    ExecutionStateView, Operation, PreparedCheckpoint, Query, QueryContext, QueryOutcome,
+    ResourceController, ResourceTracker, ServiceRuntimeEndpoint, TransactionTracker, WasmProfile,
 };
 use linera_views::{
     context::Context,
@@ -107,6 +107,26 @@ pub enum BlockExecution {
         /// The oracle responses recorded in the certificate, replayed to reproduce the outcome.
         oracle_responses: Vec<Vec<OracleResponse>>,
     },
+    /// Re-executing a confirmed block, like [`HandleConfirmed`], to trace the EVM executions
+    /// of one of its transactions.
+    TraceConfirmed {
+        /// The oracle responses recorded in the certificate, replayed to reproduce the outcome.
+        oracle_responses: Vec<Vec<OracleResponse>>,
+        /// The index of the traced transaction.
+        transaction_index: u32,
+        /// Where to record the traces.
+        evm_traces: EvmTraceCollector,
+    },
+    /// Re-executing a confirmed block, like [`HandleConfirmed`], to profile the Wasm contract
+    /// executions of one of its transactions.
+    ProfileConfirmed {
+        /// The oracle responses recorded in the certificate, replayed to reproduce the outcome.
+        oracle_responses: Vec<Vec<OracleResponse>>,
+        /// The index of the profiled transaction.
+        transaction_index: u32,
+        /// Where to record the profile.
+        wasm_profile: WasmProfile,
+    },
 }
 
 impl BlockExecution {
@@ -115,7 +135,35 @@ impl BlockExecution {
         match self {
             BlockExecution::StageProposal { .. } => BlockExecutionPhase::StageProposal,
             BlockExecution::HandleProposal => BlockExecutionPhase::HandleProposal,
-            BlockExecution::HandleConfirmed { .. } => BlockExecutionPhase::HandleConfirmed,
+            BlockExecution::HandleConfirmed { .. }
+            | BlockExecution::TraceConfirmed { .. }
+            | BlockExecution::ProfileConfirmed { .. } => BlockExecutionPhase::HandleConfirmed,
+        }
+    }
+
+    /// Returns the index of the transaction whose EVM executions are traced, if any, and
+    /// where to record the traces.
+    fn evm_tracing(&self) -> Option<(u32, EvmTraceCollector)> {
+        match self {
+            BlockExecution::TraceConfirmed {
+                transaction_index,
+                evm_traces,
+                ..
+            } => Some((*transaction_index, evm_traces.clone())),
+            _ => None,
+        }
+    }
+
+    /// Returns the index of the transaction whose Wasm contract executions are profiled, if
+    /// any, and where to record the profile.
+    fn wasm_profiling(&self) -> Option<(u32, WasmProfile)> {
+        match self {
+            BlockExecution::ProfileConfirmed {
+                transaction_index,
+                wasm_profile,
+                ..
+            } => Some((*transaction_index, wasm_profile.clone())),
+            _ => None,
         }
     }
 
@@ -124,9 +172,13 @@ impl BlockExecution {
         match self {
             BlockExecution::StageProposal { policy } => (None, policy),
             BlockExecution::HandleProposal => (None, BundleExecutionPolicy::committed()),
-            BlockExecution::HandleConfirmed { oracle_responses } => {
-                (Some(oracle_responses), BundleExecutionPolicy::committed())
+            BlockExecution::HandleConfirmed { oracle_responses }
+            | BlockExecution::TraceConfirmed {
+                oracle_responses, ..
             }
+            | BlockExecution::ProfileConfirmed {
+                oracle_responses, ..
+            } => (Some(oracle_responses), BundleExecutionPolicy::committed()),
         }
     }
 }
@@ -871,6 +923,8 @@ where
         checkpoint_origin_cursors: Vec<(ChainId, Cursor)>,
         checkpoint_inbox_cursors: Vec<(ChainId, Cursor)>,
         checkpoint_outbox_block_hashes: Vec<CryptoHash>,
+        evm_tracing: Option<(u32, EvmTraceCollector)>,
+        wasm_profiling: Option<(u32, WasmProfile)>,
     ) -> Result<(BlockExecutionOutcome, ResourceTracker, HashSet<ChainId>), ChainError> {
         #[cfg(with_metrics)]
         let block_execution_latency =
@@ -925,6 +979,29 @@ where
             block.authenticated_owner,
         );
 
+        // If the block uses a fee sponsorship, the sponsor pays its fees from the start.
+        let sponsorships = block
+            .operations()
+            .filter_map(Operation::fee_sponsorship)
+            .collect::<Vec<_>>();
+        match sponsorships.as_slice() {
+            [] => {}
+            [(sponsorship, signature)] => {
+                let sponsor = chain
+                    .system
+                    .fee_sponsor(sponsorship, *signature)
+                    .await
+                    .with_execution_context(ChainExecutionContext::Block)?;
+                resource_controller.sponsor = Some(sponsor);
+            }
+            _ => {
+                return Err(ChainError::ExecutionError(
+                    Box::new(ExecutionError::MultipleFeeSponsorships),
+                    ChainExecutionContext::Block,
+                ));
+            }
+        }
+
         for blob in published_blobs {
             let blob_id = blob.id();
             resource_controller
@@ -948,6 +1025,12 @@ where
         if let Some(prepared) = prepared_checkpoint {
             block_execution_tracker.set_prepared_checkpoint(prepared);
         }
+        if let Some((transaction_index, evm_traces)) = evm_tracing {
+            block_execution_tracker.trace_evm_transaction(transaction_index, evm_traces);
+        }
+        if let Some((transaction_index, wasm_profile)) = wasm_profiling {
+            block_execution_tracker.profile_wasm_transaction(transaction_index, wasm_profile);
+        }
 
         // Extract failure-policy parameters from exec_policy.
         let (max_failures, never_reject_application_ids) = match &exec_policy.on_failure {
@@ -969,6 +1052,7 @@ where
             let transaction = &mut block.transactions[i];
             let is_bundle = matches!(transaction, Transaction::ReceiveMessages(_));
             let is_stream_update = transaction.is_update_stream();
+            let is_scheduled_operation = transaction.scheduled_operation_id().is_some();
 
             // If we have a time budget and it's been exceeded, discard remaining bundles.
             if is_bundle && time_budget.is_some_and(|budget| cumulative_bundle_time >= budget) {
@@ -981,14 +1065,17 @@ where
                 continue;
             }
 
-            let checkpoint = if auto_retry && (is_bundle || is_stream_update) {
-                Some((
-                    chain.clone_unchecked()?,
-                    block_execution_tracker.create_checkpoint(),
-                ))
-            } else {
-                None
-            };
+            // Scheduled operations are checkpointed regardless of the policy: a failed one is
+            // dropped by every node that executes the block, not only by the proposer.
+            let checkpoint =
+                if (auto_retry && (is_bundle || is_stream_update)) || is_scheduled_operation {
+                    Some((
+                        chain.clone_unchecked()?,
+                        block_execution_tracker.create_checkpoint(),
+                    ))
+                } else {
+                    None
+                };
 
             let bundle_start = if is_bundle && time_budget.is_some() {
                 Some(Instant::now())
@@ -1121,6 +1208,34 @@ where
                     }
                     // Do not increment i - the next transaction is now at i.
                 }
+                (
+                    Err(ChainError::ExecutionError(error, _context)),
+                    transaction,
+                    Some((saved_chain, saved_tracker)),
+                ) if is_scheduled_operation && !error.is_transient_error() => {
+                    // Restore checkpoint.
+                    *chain = saved_chain;
+                    block_execution_tracker.restore_checkpoint(&saved_tracker);
+                    if auto_retry && error.is_limit_error() && i > 0 {
+                        info!(
+                            %error,
+                            index = i,
+                            "Scheduled operation exceeded block limits, discarding for retry"
+                        );
+                        block.transactions.remove(i);
+                        // Do not increment i - the next transaction is now at i.
+                    } else {
+                        warn!(
+                            %error,
+                            index = i,
+                            "Scheduled operation failed to execute and will be dropped"
+                        );
+                        block_execution_tracker
+                            .drop_scheduled_operation(transaction, chain)
+                            .await?;
+                        i += 1;
+                    }
+                }
                 (Err(e), _, _) => return Err(e),
             };
         }
@@ -1362,6 +1477,8 @@ where
         };
 
         let phase = execution.phase();
+        let evm_tracing = execution.evm_tracing();
+        let wasm_profiling = execution.wasm_profiling();
         let (replaying_oracle_responses, policy) = execution.into_oracle_and_policy();
         let (outcome, tracker, never_reject_origins) = Self::execute_block_inner(
             &mut self.execution_state,
@@ -1376,6 +1493,8 @@ where
             origin_cursors,
             inbox_cursors,
             outbox_block_hashes,
+            evm_tracing,
+            wasm_profiling,
         )
         .await?;
 
@@ -1479,6 +1598,94 @@ where
         Ok(())
     }
 
+    /// Replaces the chain's state with the execution state snapshot of the checkpoint block
+    /// at `height`, and rebuilds the parts of the chain state that re-executing that block
+    /// depends on. Returns the new tip, just below the checkpoint block.
+    ///
+    /// The snapshot is written to storage directly, and the view is reloaded from there.
+    pub async fn install_checkpoint_snapshot(
+        &mut self,
+        snapshot: &[u8],
+        height: BlockHeight,
+        previous_block_hash: Option<CryptoHash>,
+        outbox_block_hashes: Vec<CryptoHash>,
+        inbox_cursors: Vec<(ChainId, Cursor)>,
+        tracked: Option<&Hashed<ChainIdSet>>,
+    ) -> Result<ChainTipState, ChainError> {
+        self.execution_state.restore_from_content(snapshot).await?;
+        // `restore_from_content` writes directly to storage and leaves the
+        // in-memory view in an undefined state — reload from storage.
+        *self = Self::load(self.context().clone()).await?;
+        // Re-populate `block_hashes` for every pre-checkpoint sender block the
+        // chain still needs. The heights live in the just-restored execution
+        // state (`unfinalized_message_blocks`); the matching hashes are the
+        // ones the producer recorded in the oracle response, certified by the
+        // checkpoint cert. Without this, re-executing the checkpoint to verify
+        // its outcome would fail because `collect_unfinalized_block_hashes`
+        // looks these up.
+        let heights = self.collect_unfinalized_heights().await?;
+        ensure!(
+            heights.len() == outbox_block_hashes.len(),
+            ChainError::InternalError(format!(
+                "checkpoint oracle response has {} outbox block hashes but the \
+                 restored state references {} distinct heights",
+                outbox_block_hashes.len(),
+                heights.len(),
+            ))
+        );
+        for (height, hash) in heights.into_iter().zip(outbox_block_hashes) {
+            self.block_hashes.insert(&height, hash)?;
+        }
+        // Rebuild the off-chain outbox state (queues, counters,
+        // nonempty_outboxes) from the on-chain unfinalized map so that this
+        // node can resume pushing pre-checkpoint messages forward. The outbox
+        // isn't part of the certified checkpoint blob, so without this a
+        // bootstrapped validator would silently stop delivering pending
+        // messages.
+        self.restore_outboxes_from_unfinalized(tracked).await?;
+        for (origin, cursor) in inbox_cursors {
+            let mut inbox = self.inboxes.try_load_entry_mut(&origin).await?;
+            inbox.restore_from_checkpoint(cursor).await?;
+        }
+        // Seed `next_expected_events` from the restored per-stream event counts. Re-executing
+        // the checkpoint re-emits each summarized stream's summary event; without this seed
+        // the summary's index would look like a gap on the freshly-restored node, so the
+        // tracker would never advance and future events on that stream would never be
+        // delivered to subscribers. The counts are contiguous, so this matches the producer's
+        // tracker as of just before the checkpoint block.
+        for (stream_id, count) in self
+            .execution_state
+            .system
+            .stream_event_counts
+            .index_values()
+            .await?
+        {
+            // Just after restoring, every event predates the checkpoint, so the readable floor
+            // is the count itself.
+            self.next_expected_events.insert(
+                &stream_id,
+                StreamCounts {
+                    first_index: count,
+                    next_index: count,
+                },
+            )?;
+        }
+        // We reset `execution_state` (via restore), `tip_state`, `block_hashes`
+        // (for outbox-referenced pre-checkpoint heights), and the outbox views.
+        // The other fields are either (a) already default for a fresh bootstrap
+        // node (`inboxes`, `received_log`, …), (b) about to be overwritten by
+        // `apply_confirmed_block` when the cert is applied (`manager`,
+        // `block_hashes` for height `height`), or (c) outside the protocol state
+        // hash so divergence from the producer is fine (inboxes; subsequent
+        // blocks reconcile by anticipation if needed).
+        let tip = ChainTipState {
+            block_hash: previous_block_hash,
+            next_block_height: height,
+        };
+        self.tip_state.set(tip.clone());
+        Ok(tip)
+    }
+
     /// Collects the hashes of every block on this chain still listed in the on-chain
     /// `unfinalized_message_blocks` map. The checkpoint pre-block hook calls this to
     /// build the oracle response's `outbox_block_hashes`, so the checkpoint
@@ -1598,6 +1805,11 @@ where
                 {
                     mandatory.clear()
                 }
+                // The application of a scheduled operation is only known from the execution
+                // state, so it is checked when the operation is scheduled and when it runs.
+                // It does not count as an operation of a mandatory application.
+                Transaction::ExecuteOperation(operation)
+                    if operation.scheduled_operation_id().is_some() => {}
                 Transaction::ExecuteOperation(operation) => {
                     ensure!(
                         app_permissions.can_execute_operations(&operation.application_id()),
@@ -1613,8 +1825,10 @@ where
                     if incoming_bundle.action == MessageAction::Accept =>
                 {
                     for pending in incoming_bundle.messages() {
-                        if let Message::User { application_id, .. } = &pending.message {
-                            mandatory.remove(application_id);
+                        if let GenericApplicationId::User(application_id) =
+                            pending.message.application_id()
+                        {
+                            mandatory.remove(&application_id);
                         }
                     }
                 }
@@ -10,7 +10,7 @@
 use async_graphql::SimpleObject;
 use linera_base::{
     crypto::CryptoHash,
-    data_types::{Amount, ApplicationPermissions, Cursor},
+    data_types::{Amount, ApplicationPermissions, Cursor, FeeSponsorship, VestingSchedule},
     hex,
     identifiers::{Account, AccountOwner, ApplicationId, ChainId},
     ownership::{ChainOwnership, TimeoutConfig},
@@ -110,6 +110,16 @@ pub struct SystemOperationMetadata {
     pub epoch: Option<i32>,
     /// `UpdateStream` operation details
     pub update_stream: Option<UpdateStreamMetadata>,
+    /// Scheduled operation ID (`ExecuteScheduledOperation`)
+    pub scheduled_operation_id: Option<i32>,
+    /// Lock tokens operation details
+    pub lock_tokens: Option<LockTokensOperationMetadata>,
+    /// Upgrade application operation details
+    pub upgrade_application: Option<UpgradeApplicationOperationMetadata>,
+    /// Application ID (`ProcessApplicationUpgrade`)
+    pub process_application_upgrade: Option<ApplicationId>,
+    /// Use fee sponsorship operation details
+    pub use_fee_sponsorship: Option<UseFeeSponsorshipOperationMetadata>,
 }
 
 impl SystemOperationMetadata {
@@ -129,6 +139,11 @@ impl SystemOperationMetadata {
             publish_module: None,
             epoch: None,
             update_stream: None,
+            scheduled_operation_id: None,
+            lock_tokens: None,
+            upgrade_application: None,
+            process_application_upgrade: None,
+            use_fee_sponsorship: None,
         }
     }
 }
@@ -157,6 +172,19 @@ pub struct ClaimOperationMetadata {
     pub amount: Amount,
 }
 
+/// Lock tokens operation metadata.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, SimpleObject)]
+pub struct LockTokensOperationMetadata {
+    /// The account owner whose balance is debited.
+    pub owner: AccountOwner,
+    /// The account owner on this chain that receives the locked tokens.
+    pub beneficiary: AccountOwner,
+    /// The amount of tokens to lock.
+    pub amount: Amount,
+    /// The schedule according to which the tokens are released.
+    pub schedule: VestingSchedule,
+}
+
 /// Open chain operation metadata.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, SimpleObject)]
 pub struct OpenChainOperationMetadata {
@@ -225,6 +253,26 @@ pub struct CreateApplicationOperationMetadata {
     pub instantiation_argument_hex: String,
     /// The applications this application depends on and requires to be present.
     pub required_application_ids: Vec<ApplicationId>,
+    /// The owner or application allowed to upgrade the application, if any.
+    pub upgrade_authority: Option<AccountOwner>,
+}
+
+/// Upgrade application operation metadata.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, SimpleObject)]
+pub struct UpgradeApplicationOperationMetadata {
+    /// The application being upgraded.
+    pub application_id: ApplicationId,
+    /// The ID of the module the application is upgraded to.
+    pub module_id: String,
+}
+
+/// Use fee sponsorship operation metadata.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, SimpleObject)]
+pub struct UseFeeSponsorshipOperationMetadata {
+    /// The sponsorship under which the block's fees are paid.
+    pub sponsorship: FeeSponsorship,
+    /// The sponsor's signature of the sponsorship, as a hex string, if any.
+    pub signature: Option<String>,
 }
 
 /// Publish data blob operation metadata.
@@ -398,12 +446,14 @@ impl From<&SystemOperation> for SystemOperationMetadata {
                 parameters,
                 instantiation_argument,
                 required_application_ids,
+                upgrade_authority,
             } => SystemOperationMetadata {
                 create_application: Some(CreateApplicationOperationMetadata {
                     module_id: module_id.to_string(),
                     parameters_hex: hex::encode(parameters),
                     instantiation_argument_hex: hex::encode(instantiation_argument),
                     required_application_ids: required_application_ids.clone(),
+                    upgrade_authority: *upgrade_authority,
                 }),
                 ..SystemOperationMetadata::new("CreateApplication")
             },
@@ -446,6 +496,52 @@ impl From<&SystemOperation> for SystemOperationMetadata {
                 ..SystemOperationMetadata::new("UpdateStream")
             },
             SystemOperation::Checkpoint => SystemOperationMetadata::new("Checkpoint"),
+            SystemOperation::ExecuteScheduledOperation { id } => SystemOperationMetadata {
+                scheduled_operation_id: Some(*id as i32),
+                ..SystemOperationMetadata::new("ExecuteScheduledOperation")
+            },
+            SystemOperation::LockTokens {
+                owner,
+                beneficiary,
+                amount,
+                schedule,
+            } => SystemOperationMetadata {
+                lock_tokens: Some(LockTokensOperationMetadata {
+                    owner: *owner,
+                    beneficiary: *beneficiary,
+                    amount: *amount,
+                    schedule: *schedule,
+                }),
+                ..SystemOperationMetadata::new("LockTokens")
+            },
+            SystemOperation::UpgradeApplication {
+                application_id,
+                module_id,
+            } => SystemOperationMetadata {
+                upgrade_application: Some(UpgradeApplicationOperationMetadata {
+                    application_id: *application_id,
+                    module_id: module_id.to_string(),
+                }),
+                ..SystemOperationMetadata::new("UpgradeApplication")
+            },
+            SystemOperation::ProcessApplicationUpgrade { application_id } => {
+                SystemOperationMetadata {
+                    process_application_upgrade: Some(*application_id),
+                    ..SystemOperationMetadata::new("ProcessApplicationUpgrade")
+                }
+            }
+            SystemOperation::UseFeeSponsorship {
+                sponsorship,
+                signature,
+            } => SystemOperationMetadata {
+                use_fee_sponsorship: Some(UseFeeSponsorshipOperationMetadata {
+                    sponsorship: *sponsorship,
+                    signature: signature
+                        .as_ref()
+                        .map(|signature| hex::encode(signature.to_bytes())),
+                }),
+                ..SystemOperationMetadata::new("UseFeeSponsorship")
+            },
         }
     }
 }
@@ -484,6 +580,11 @@ impl From<&Message> for MessageMetadata {
             Message::User {
                 application_id,
                 bytes,
+            }
+            | Message::VersionedUser {
+                application_id,
+                bytes,
+                ..
             } => MessageMetadata {
                 message_type: "User".to_string(),
                 application_id: Some(*application_id),
@@ -189,6 +189,15 @@ impl Transaction {
         )
     }
 
+    /// Returns the ID of the scheduled operation, if this transaction executes a
+    /// `SystemOperation::ExecuteScheduledOperation`.
+    pub fn scheduled_operation_id(&self) -> Option<u32> {
+        match self {
+            Transaction::ExecuteOperation(op) => op.scheduled_operation_id(),
+            Transaction::ReceiveMessages(_) => None,
+        }
+    }
+
     /// Returns whether this transaction executes a `SystemOperation::Checkpoint`.
     pub fn is_checkpoint(&self) -> bool {
         matches!(
@@ -810,6 +819,7 @@ impl PostedMessage {
         let message_size = match &self.message {
             Message::System(_) => 256, // conservative estimate for system messages
             Message::User { bytes, .. } => 64 + bytes.len(),
+            Message::VersionedUser { bytes, .. } => 68 + bytes.len(),
         };
         overhead + message_size
     }
@@ -958,11 +968,7 @@ impl BlockProposal {
 
     /// Returns the `AccountOwner` that proposed the block.
     pub fn owner(&self) -> AccountOwner {
-        match self.signature {
-            AccountSignature::Ed25519 { public_key, .. } => public_key.into(),
-            AccountSignature::Secp256k1 { public_key, .. } => public_key.into(),
-            AccountSignature::EvmSecp256k1 { address, .. } => AccountOwner::Address20(address),
-        }
+        self.signature.owner()
     }
 
     /// Verifies the signature on this proposal.
@@ -489,7 +489,7 @@ where
             Some(OriginalProposal::Fast(signature)) => {
                 if self.locking_block.get().is_none() {
                     let original_proposal = BlockProposal {
-                        signature: *signature,
+                        signature: signature.clone(),
                         ..proposal.clone()
                     };
                     self.update_locking(LockingBlock::Fast(original_proposal), blobs.clone())?;
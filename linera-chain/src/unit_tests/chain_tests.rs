@@ -12,10 +12,12 @@ use std::{
 use assert_matches::assert_matches;
 use axum::{routing::get, Router};
 use linera_base::{
-    crypto::{AccountPublicKey, CryptoHash, ValidatorPublicKey},
+    crypto::{
+        AccountPublicKey, AccountSecretKey, CryptoHash, Ed25519SecretKey, ValidatorPublicKey,
+    },
     data_types::{
         Amount, ApplicationDescription, ApplicationPermissions, Blob, BlockHeight, Bytecode,
-        ChainDescription, ChainOrigin, Epoch, InitialChainConfig, Timestamp,
+        ChainDescription, ChainOrigin, Epoch, FeeSponsorship, InitialChainConfig, Timestamp,
     },
     http,
     identifiers::{Account, AccountOwner, ApplicationId, ChainId, ModuleId},
@@ -28,12 +30,13 @@ use linera_execution::{
     test_utils::{ExpectedCall, MockApplication},
     BaseRuntime, ContractRuntime, ExecutionError, ExecutionRuntimeConfig, ExecutionRuntimeContext,
     Message, MessageKind, Operation, ResourceControlPolicy, ResourceTracker, ServiceRuntime,
-    SystemOperation, TestExecutionRuntimeContext,
+    SystemExecutionStateView, SystemOperation, TestExecutionRuntimeContext,
 };
 use linera_views::{
     batch::Batch,
     context::{Context as _, MemoryContext, ViewContext},
     memory::MemoryStore,
+    proof::{ProofError, ProofPosition},
     views::{RootView as _, View},
 };
 use test_case::test_case;
@@ -395,6 +398,384 @@ async fn test_application_permissions() -> anyhow::Result<()> {
     let value = ConfirmedBlock::new(outcome.with(valid_block));
     chain.apply_confirmed_block(&value, time, None).await?;
 
+    // Executing a scheduled operation doesn't exempt a block from the mandatory applications.
+    let invalid_block = make_child_block(&value)
+        .with_operation(SystemOperation::ExecuteScheduledOperation { id: 0 });
+    let result = chain
+        .execute_test_block_simple(invalid_block, time, &[])
+        .await;
+    assert_matches!(result, Err(ChainError::MissingMandatoryApplications(_)));
+
+    Ok(())
+}
+
+/// Tests that due scheduled operations that fail, because their application was revoked or
+/// because they return an error, are dropped instead of failing every later block.
+#[tokio::test]
+async fn test_failed_scheduled_operations_are_dropped() -> anyhow::Result<()> {
+    let mut env = TestEnvironment::new();
+    let time = Timestamp::from(0);
+    let due_time = Timestamp::from(10);
+
+    let (revoked_app, revoked_contract, revoked_service) = env.make_app_description();
+    let revoked_id = ApplicationId::from(&revoked_app);
+    let (failing_app, failing_contract, failing_service) = env.make_app_from_bytecodes(
+        &Bytecode::new(b"contractB".into()),
+        &Bytecode::new(b"serviceB".into()),
+    );
+    let failing_id = ApplicationId::from(&failing_app);
+    let application = MockApplication::default();
+
+    let chain_desc = env.make_child_chain_description_with_config(3, env.make_open_chain_config());
+    let chain_id = chain_desc.id();
+    let mut chain = ChainStateView::new(chain_id).await;
+    let extra = chain.context().extra().clone();
+    {
+        let pinned = extra.user_contracts().pin();
+        pinned.insert(revoked_id, application.clone().into());
+        pinned.insert(failing_id, application.clone().into());
+    }
+    extra
+        .add_blobs([committee_blob(Default::default())])
+        .await?;
+    extra.add_blobs(env.description_blobs()).await?;
+    extra
+        .add_blobs([
+            revoked_contract,
+            revoked_service,
+            Blob::new_application_description(&revoked_app),
+            failing_contract,
+            failing_service,
+            Blob::new_application_description(&failing_app),
+        ])
+        .await?;
+    chain.initialize_if_needed(time).await?;
+
+    let system = &mut chain.execution_state.system;
+    for application_id in [revoked_id, failing_id] {
+        system
+            .schedule_operation(application_id, due_time, b"op".to_vec())
+            .await?;
+    }
+    // Only the failing application may still execute operations.
+    system.application_permissions.set(ApplicationPermissions {
+        execute_operations: Some(vec![failing_id]),
+        ..ApplicationPermissions::default()
+    });
+    assert_eq!(system.due_scheduled_operations(due_time, 10).await?, [0, 1]);
+    assert_eq!(system.due_scheduled_operations(due_time, 1).await?, [0]);
+    assert!(system.due_scheduled_operations(time, 10).await?.is_empty());
+
+    application.expect_call(ExpectedCall::execute_operation(|_, _| {
+        Err(ExecutionError::UserError("failed".to_string()))
+    }));
+    let block = make_first_block(chain_id)
+        .with_timestamp(due_time)
+        .with_operation(SystemOperation::ExecuteScheduledOperation { id: 0 })
+        .with_operation(SystemOperation::ExecuteScheduledOperation { id: 1 });
+    let (block, outcome, _) = chain
+        .execute_test_block_simple(block, due_time, &[])
+        .await?;
+    assert_eq!(block.transactions.len(), 2);
+    let value = ConfirmedBlock::new(outcome.with(block));
+    chain.apply_confirmed_block(&value, due_time, None).await?;
+    let system = &chain.execution_state.system;
+    assert!(system.scheduled_operations.indices().await?.is_empty());
+    assert!(system
+        .due_scheduled_operations(due_time, 10)
+        .await?
+        .is_empty());
+
+    // A dropped operation can't be executed or dropped again.
+    let block = make_child_block(&value)
+        .with_timestamp(due_time)
+        .with_operation(SystemOperation::ExecuteScheduledOperation { id: 1 });
+    let result = chain.execute_test_block_simple(block, due_time, &[]).await;
+    assert_matches!(
+        result,
+        Err(ChainError::ExecutionError(error, _))
+            if matches!(*error, ExecutionError::ScheduledOperationNotFound(1))
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fee_sponsorship() -> anyhow::Result<()> {
+    let mut env = TestEnvironment::new();
+    let time = Timestamp::from(0);
+    let config = InitialChainConfig {
+        balance: Amount::ZERO,
+        ..env.make_open_chain_config()
+    };
+    let chain_desc = env.make_child_chain_description_with_config(3, config);
+    let chain_id = chain_desc.id();
+    let owner = AccountOwner::from(AccountPublicKey::test_key(0));
+    let sponsor_secret = AccountSecretKey::Ed25519(Ed25519SecretKey::generate());
+    let sponsor = sponsor_secret.public().into();
+
+    let mut chain = ChainStateView::new(chain_id).await;
+    let policy = ResourceControlPolicy {
+        operation: Amount::from_micros(1),
+        ..ResourceControlPolicy::default()
+    };
+    let extra = chain.context().extra().clone();
+    extra.add_blobs([committee_blob(policy)]).await?;
+    extra.add_blobs(env.description_blobs()).await?;
+    chain.initialize_if_needed(time).await?;
+    let balances = &mut chain.execution_state.system.balances;
+    balances.insert(&owner, Amount::from_tokens(5))?;
+    balances.insert(&sponsor, Amount::from_tokens(1))?;
+
+    // The sponsor pays at most three operation fees.
+    let sponsorship = FeeSponsorship {
+        sponsor,
+        chain_id,
+        max_fees: Amount::from_micros(3),
+        expiry: Timestamp::from(1000),
+    };
+    let sponsorship_hash = CryptoHash::new(&sponsorship);
+    let signature = sponsor_secret.sign(&sponsorship);
+    let use_sponsorship = |signature| SystemOperation::UseFeeSponsorship {
+        sponsorship,
+        signature,
+    };
+
+    // The first block uses the signed sponsorship: the sponsor pays for both operations,
+    // and the owner's account only pays for the transfer.
+    let block = make_first_block(chain_id)
+        .with_authenticated_owner(Some(owner))
+        .with_operation(use_sponsorship(Some(signature.clone())))
+        .with_transfer(owner, Account::chain(chain_id), Amount::ONE);
+    let (block, outcome, _) = chain.execute_test_block_simple(block, time, &[]).await?;
+    let value = ConfirmedBlock::new(outcome.with(block));
+    chain.apply_confirmed_block(&value, time, None).await?;
+    let system = &chain.execution_state.system;
+    assert_eq!(
+        system.balances.get(&sponsor).await?,
+        Some(Amount::from_tokens(1).try_sub(Amount::from_micros(2))?)
+    );
+    assert_eq!(
+        system.balances.get(&owner).await?,
+        Some(Amount::from_tokens(4))
+    );
+    assert_eq!(*system.balance.get(), Amount::ONE);
+    assert_eq!(
+        system.fee_sponsorships.get(&sponsorship_hash).await?,
+        Some(Amount::from_micros(2))
+    );
+
+    // Once used, the sponsorship doesn't need a signature anymore. When the cap is reached,
+    // the chain account pays the remaining fees.
+    let block = make_child_block(&value)
+        .with_authenticated_owner(Some(owner))
+        .with_operation(use_sponsorship(None))
+        .with_transfer(owner, Account::chain(chain_id), Amount::ONE);
+    let (block, outcome, _) = chain.execute_test_block_simple(block, time, &[]).await?;
+    let value = ConfirmedBlock::new(outcome.with(block));
+    chain.apply_confirmed_block(&value, time, None).await?;
+    let system = &chain.execution_state.system;
+    assert_eq!(
+        system.balances.get(&sponsor).await?,
+        Some(Amount::from_tokens(1).try_sub(Amount::from_micros(3))?)
+    );
+    assert_eq!(
+        *system.balance.get(),
+        Amount::from_tokens(2).try_sub(Amount::from_micros(1))?
+    );
+    assert_eq!(
+        system.fee_sponsorships.get(&sponsorship_hash).await?,
+        Some(Amount::from_micros(3))
+    );
+
+    // A block can't use more than one sponsorship.
+    let block = make_child_block(&value)
+        .with_operation(use_sponsorship(None))
+        .with_operation(use_sponsorship(None));
+    let result = chain.execute_test_block_simple(block, time, &[]).await;
+    assert_matches!(
+        result,
+        Err(ChainError::ExecutionError(error, ChainExecutionContext::Block))
+            if matches!(*error, ExecutionError::MultipleFeeSponsorships)
+    );
+
+    // A new sponsorship must be signed by the sponsor.
+    let other_sponsorship = FeeSponsorship {
+        max_fees: Amount::ONE,
+        ..sponsorship
+    };
+    let owner_signature = AccountSecretKey::generate().sign(&other_sponsorship);
+    for signature in [None, Some(owner_signature), Some(signature)] {
+        let block = make_child_block(&value).with_operation(SystemOperation::UseFeeSponsorship {
+            sponsorship: other_sponsorship,
+            signature,
+        });
+        let result = chain.execute_test_block_simple(block, time, &[]).await;
+        assert_matches!(
+            result,
+            Err(ChainError::ExecutionError(error, ChainExecutionContext::Block))
+                if matches!(*error, ExecutionError::UnauthorizedFeeSponsorship(account)
+                    if account == sponsor)
+        );
+    }
+
+    // Sponsorships can't be used after they expire.
+    let block = make_child_block(&value)
+        .with_timestamp(1000)
+        .with_operation(use_sponsorship(None));
+    let result = chain
+        .execute_test_block_simple(block, Timestamp::from(1000), &[])
+        .await;
+    assert_matches!(
+        result,
+        Err(ChainError::ExecutionError(error, ChainExecutionContext::Block))
+            if matches!(*error, ExecutionError::FeeSponsorshipExpired(_))
+    );
+
+    Ok(())
+}
+
+/// Tests that a sponsor only pays the fees a block actually uses, even though executing an
+/// application reserves the whole available balance, and that the chain account pays nothing.
+#[tokio::test]
+async fn test_fee_sponsorship_pays_only_used_fees() -> anyhow::Result<()> {
+    let mut env = TestEnvironment::new();
+    let time = Timestamp::from(0);
+    let (app_description, contract, service) = env.make_app_description();
+    let application_id = ApplicationId::from(&app_description);
+    let application = MockApplication::default();
+    let chain_desc = env.make_child_chain_description_with_config(3, env.make_open_chain_config());
+    let chain_id = chain_desc.id();
+    let sponsor_secret = AccountSecretKey::Ed25519(Ed25519SecretKey::generate());
+    let sponsor = sponsor_secret.public().into();
+
+    let mut chain = ChainStateView::new(chain_id).await;
+    let policy = ResourceControlPolicy {
+        operation: Amount::from_micros(1),
+        wasm_fuel_unit: Amount::from_micros(1),
+        ..ResourceControlPolicy::default()
+    };
+    let extra = chain.context().extra().clone();
+    extra
+        .user_contracts()
+        .pin()
+        .insert(application_id, application.clone().into());
+    extra.add_blobs([committee_blob(policy)]).await?;
+    extra.add_blobs(env.description_blobs()).await?;
+    extra
+        .add_blobs([
+            contract,
+            service,
+            Blob::new_application_description(&app_description),
+        ])
+        .await?;
+    chain.initialize_if_needed(time).await?;
+    let chain_balance = *chain.execution_state.system.balance.get();
+    chain
+        .execution_state
+        .system
+        .balances
+        .insert(&sponsor, Amount::ONE)?;
+
+    let sponsorship = FeeSponsorship {
+        sponsor,
+        chain_id,
+        max_fees: Amount::from_micros(100),
+        expiry: Timestamp::from(1000),
+    };
+    let sponsorship_hash = CryptoHash::new(&sponsorship);
+    let signature = sponsor_secret.sign(&sponsorship);
+
+    application.expect_call(ExpectedCall::execute_operation(|runtime, _| {
+        runtime.consume_fuel(10, VmRuntime::Wasm)?;
+        Ok(vec![])
+    }));
+    application.expect_call(ExpectedCall::default_finalize());
+    let block = make_first_block(chain_id)
+        .with_operation(SystemOperation::UseFeeSponsorship {
+            sponsorship,
+            signature: Some(signature),
+        })
+        .with_operation(Operation::User {
+            application_id,
+            bytes: vec![],
+        });
+    let (block, outcome, _) = chain.execute_test_block_simple(block, time, &[]).await?;
+    let value = ConfirmedBlock::new(outcome.with(block));
+    chain.apply_confirmed_block(&value, time, None).await?;
+
+    // Two operations and ten units of fuel.
+    let fees = Amount::from_micros(12);
+    let system = &chain.execution_state.system;
+    assert_eq!(
+        system.balances.get(&sponsor).await?,
+        Some(Amount::ONE.try_sub(fees)?)
+    );
+    assert_eq!(*system.balance.get(), chain_balance);
+    assert_eq!(
+        system.fee_sponsorships.get(&sponsorship_hash).await?,
+        Some(fees)
+    );
+    Ok(())
+}
+
+/// Tests that proofs for the system state can be checked against the state hash of a
+/// confirmed block.
+#[tokio::test]
+async fn test_system_state_proof_matches_confirmed_block() -> anyhow::Result<()> {
+    let env = TestEnvironment::new();
+    let time = Timestamp::from(0);
+    let chain_id = env.admin_chain_id();
+    let owner = AccountOwner::from(AccountPublicKey::test_key(1));
+
+    let mut chain = ChainStateView::new(chain_id).await;
+    let extra = chain.context().extra().clone();
+    extra
+        .add_blobs([committee_blob(ResourceControlPolicy::default())])
+        .await?;
+    extra.add_blobs(env.description_blobs()).await?;
+    chain.initialize_if_needed(time).await?;
+
+    let block = make_first_block(chain_id)
+        .with_authenticated_owner(Some(AccountPublicKey::test_key(0).into()))
+        .with_transfer(
+            AccountOwner::CHAIN,
+            Account::new(chain_id, owner),
+            Amount::ONE,
+        );
+    let (block, outcome, _) = chain.execute_test_block_simple(block, time, &[]).await?;
+    let value = ConfirmedBlock::new(outcome.with(block));
+    chain.apply_confirmed_block(&value, time, None).await?;
+    let state_hash = value.block().header.state_hash;
+
+    type System = SystemExecutionStateView<MemoryContext<TestExecutionRuntimeContext>>;
+    let positions = [ProofPosition::field::<System>("balances").unwrap()];
+    let proof = chain.execution_state.system.balances.proof().await?;
+    let proof = chain
+        .execution_state
+        .system_proof(proof, "balances")
+        .await?;
+    assert_eq!(
+        proof.verify_entries::<AccountOwner, Amount>(&state_hash, &positions)?,
+        vec![(owner, Amount::ONE)]
+    );
+    let positions = [ProofPosition::field::<System>("balance").unwrap()];
+    let proof = chain.execution_state.system.balance.proof()?;
+    let proof = chain.execution_state.system_proof(proof, "balance").await?;
+    assert_eq!(
+        proof.verify_value::<Amount>(&state_hash, &positions)?,
+        Amount::from_tokens(9)
+    );
+
+    // The proof doesn't match the state hash of another block.
+    let block = make_child_block(&value)
+        .with_authenticated_owner(Some(AccountPublicKey::test_key(0).into()))
+        .with_simple_transfer(chain_id, Amount::ONE);
+    let (block, outcome, _) = chain.execute_test_block_simple(block, time, &[]).await?;
+    let next_value = ConfirmedBlock::new(outcome.with(block));
+    assert_matches!(
+        proof.verify_value::<Amount>(&next_value.block().header.state_hash, &positions),
+        Err(ProofError::HashMismatch)
+    );
     Ok(())
 }
 
@@ -71,6 +71,12 @@ pub struct Options {
     #[arg(long = "staging-bundles-time-budget-ms", value_parser = util::parse_millis)]
     pub staging_bundles_time_budget: Option<Duration>,
 
+    /// The maximum number of due scheduled operations to include in a block proposal.
+    ///
+    /// The remaining ones are executed in later blocks.
+    #[arg(long, default_value = "10")]
+    pub max_scheduled_operations: usize,
+
     /// Comma-separated list of chain IDs whose incoming bundles should be processed first.
     #[arg(long, value_parser = util::parse_chain_set)]
     pub prioritize_bundles_from: Option<HashSet<ChainId>>,
@@ -360,6 +366,7 @@ impl Options {
             max_pending_message_bundles: self.max_pending_message_bundles,
             max_block_limit_errors: self.max_block_limit_errors,
             staging_bundles_time_budget: self.staging_bundles_time_budget,
+            max_scheduled_operations: self.max_scheduled_operations,
             priority_bundle_origins: self.prioritize_bundles_from.clone().unwrap_or_default(),
             message_policy,
             cross_chain_message_delivery,
@@ -59,6 +59,9 @@ pub struct ChainWorkerConfig {
     /// mechanisms. If `None`, every chain is eligible (subject to the
     /// respective feature flag). If `Some`, only chains in the set are.
     pub recovery_whitelist: Option<HashSet<ChainId>>,
+    /// The maximum number of blocks replayed to re-execute a confirmed block, e.g. to trace
+    /// one of its transactions.
+    pub max_reexecuted_blocks: u64,
 }
 
 impl ChainWorkerConfig {
@@ -102,6 +105,7 @@ impl Default for ChainWorkerConfig {
             allow_revert_confirm: false,
             reset_on_corrupted_chain_state: None,
             recovery_whitelist: None,
+            max_reexecuted_blocks: 10_000,
         }
     }
 }
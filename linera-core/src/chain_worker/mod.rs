@@ -7,12 +7,16 @@ mod config;
 mod delivery_notifier;
 pub(crate) mod export;
 pub(crate) mod handle;
+mod reexecution;
 pub(crate) mod state;
 
 pub(super) use self::delivery_notifier::DeliveryNotifier;
-pub(crate) use self::state::{BlockOutcome, CrossChainUpdateResult, EventSubscriptionsResult};
 pub use self::{
     config::ChainWorkerConfig,
     export::{spawn_block_export_queue, BlockExportConfig, BlockExportHandle},
     state::ProcessConfirmedBlockMode,
 };
+pub(crate) use self::{
+    reexecution::BlockReexecution,
+    state::{BlockOutcome, CrossChainUpdateResult, EventSubscriptionsResult},
+};
@@ -0,0 +1,276 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Re-execution of a chain's confirmed blocks on a scratch copy of its state, to trace or
+//! profile their transactions.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, LazyLock, Mutex},
+};
+
+use linera_base::{
+    crypto::CryptoHash,
+    data_types::{Blob, BlockHeight, OracleResponse},
+    ensure,
+    identifiers::{BlobId, BlobType, ChainId},
+};
+use linera_cache::Arc as CacheArc;
+use linera_chain::{BlockExecution, ChainError, ChainStateView};
+use linera_execution::{
+    evm::trace::{EvmTrace, EvmTraceCollector},
+    WasmProfile,
+};
+use linera_storage::Storage;
+use linera_views::{
+    batch::Batch, context::Context, store::WritableKeyValueStore as _, views::View as _, ViewError,
+};
+use tracing::instrument;
+
+use crate::worker::WorkerError;
+
+/// The locks held by the re-executions of each chain, since they share the chain's scratch
+/// prefix. Entries are removed when no re-execution of the chain is running.
+static REEXECUTION_LOCKS: LazyLock<Mutex<BTreeMap<ChainId, Arc<tokio::sync::Mutex<()>>>>> =
+    LazyLock::new(Default::default);
+
+/// The confirmed blocks to replay in order to re-execute one of them.
+///
+/// They are collected while holding the chain worker's lock, so that the replay itself
+/// doesn't block the chain: it only reads certificates and blobs, which never change.
+pub(crate) struct BlockReexecution<StorageClient>
+where
+    StorageClient: Storage,
+{
+    storage: StorageClient,
+    /// The context of the chain's state, next to which the scratch copy is created.
+    context: StorageClient::Context,
+    chain_id: ChainId,
+    /// The height of the first block to replay: the chain's latest checkpoint, or `0`.
+    start: BlockHeight,
+    /// The hashes of the blocks to replay. The last one is the block to re-execute.
+    block_hashes: Vec<CryptoHash>,
+}
+
+impl<StorageClient> BlockReexecution<StorageClient>
+where
+    StorageClient: Storage + Clone + 'static,
+{
+    pub(crate) fn new(
+        storage: StorageClient,
+        context: StorageClient::Context,
+        chain_id: ChainId,
+        start: BlockHeight,
+        block_hashes: Vec<CryptoHash>,
+    ) -> Self {
+        Self {
+            storage,
+            context,
+            chain_id,
+            start,
+            block_hashes,
+        }
+    }
+
+    /// Returns the traces of the EVM executions of the transaction with the given index in
+    /// the re-executed block.
+    #[instrument(skip_all, fields(
+        chain_id = %self.chain_id,
+        transaction_index = %transaction_index
+    ))]
+    pub(crate) async fn trace_evm_transaction(
+        self,
+        transaction_index: u32,
+    ) -> Result<Vec<EvmTrace>, WorkerError> {
+        let evm_traces = EvmTraceCollector::default();
+        self.run(|oracle_responses| BlockExecution::TraceConfirmed {
+            oracle_responses,
+            transaction_index,
+            evm_traces: evm_traces.clone(),
+        })
+        .await?;
+        Ok(evm_traces.take())
+    }
+
+    /// Returns the profile of the Wasm contract executions of the transaction with the given
+    /// index in the re-executed block.
+    #[instrument(skip_all, fields(
+        chain_id = %self.chain_id,
+        transaction_index = %transaction_index
+    ))]
+    pub(crate) async fn profile_wasm_transaction(
+        self,
+        transaction_index: u32,
+    ) -> Result<WasmProfile, WorkerError> {
+        let wasm_profile = WasmProfile::default();
+        self.run(|oracle_responses| BlockExecution::ProfileConfirmed {
+            oracle_responses,
+            transaction_index,
+            wasm_profile: wasm_profile.clone(),
+        })
+        .await?;
+        Ok(wasm_profile)
+    }
+
+    /// Replays the blocks on a scratch copy of the chain state, executing the last one as
+    /// described by `execution` given the block's oracle responses.
+    ///
+    /// The copy lives under its own prefix next to the chain state: the views of the chain
+    /// state have tags below `u8::MAX`, so nothing else is stored there. It is only written
+    /// to when a checkpoint snapshot is installed, and is cleared before and after the replay,
+    /// so that a copy left behind by a crashed process is deleted by the next re-execution.
+    /// The re-executions of a chain therefore run one at a time.
+    async fn run(
+        self,
+        execution: impl FnOnce(Vec<Vec<OracleResponse>>) -> BlockExecution,
+    ) -> Result<(), WorkerError> {
+        let lock = REEXECUTION_LOCKS
+            .lock()
+            .unwrap()
+            .entry(self.chain_id)
+            .or_default()
+            .clone();
+        let guard = lock.lock().await;
+        let mut base_key = self.context.base_key().bytes.clone();
+        base_key.push(u8::MAX);
+        let context = self.context.clone_with_base_key(base_key);
+        let result = async {
+            clear_scratch_copy(&context).await?;
+            let result = self.replay(context.clone(), execution).await;
+            let cleared = clear_scratch_copy(&context).await;
+            result?;
+            Ok(cleared?)
+        }
+        .await;
+        drop(guard);
+        let mut locks = REEXECUTION_LOCKS.lock().unwrap();
+        // One reference is in the map, and one is ours.
+        if Arc::strong_count(&lock) == 2 {
+            locks.remove(&self.chain_id);
+        }
+        result
+    }
+
+    async fn replay(
+        &self,
+        context: StorageClient::Context,
+        execution: impl FnOnce(Vec<Vec<OracleResponse>>) -> BlockExecution,
+    ) -> Result<(), WorkerError> {
+        let chain_id = self.chain_id;
+        let mut chain = ChainStateView::load(context).await?;
+        let mut execution = Some(execution);
+        let last_index = self.block_hashes.len().saturating_sub(1);
+        for (index, hash) in self.block_hashes.iter().enumerate() {
+            let current_height = BlockHeight(self.start.0 + index as u64);
+            let certificate = self.storage.read_certificate(*hash).await?.ok_or_else(|| {
+                WorkerError::LocalBlockNotFound {
+                    height: current_height,
+                    chain_id,
+                }
+            })?;
+            let block = certificate.block();
+            if index == 0 && block.body.starts_with_checkpoint() {
+                // The blocks below the checkpoint are not replayed: its snapshot is the state
+                // they left behind.
+                let Some(OracleResponse::Checkpoint {
+                    execution_state_blobs,
+                    outbox_block_hashes,
+                    inbox_cursors,
+                    ..
+                }) = block.body.oracle_responses.first().and_then(|r| r.first())
+                else {
+                    return Err(ChainError::InternalError(
+                        "Checkpoint block missing OracleResponse::Checkpoint".into(),
+                    )
+                    .into());
+                };
+                let blob_ids = execution_state_blobs
+                    .iter()
+                    .map(|hash| BlobId::new(*hash, BlobType::CheckpointExecutionState))
+                    .collect::<Vec<_>>();
+                let mut snapshot = Vec::new();
+                for blob in self.read_blobs(&blob_ids).await? {
+                    snapshot.extend_from_slice(blob.bytes());
+                }
+                chain
+                    .install_checkpoint_snapshot(
+                        &snapshot,
+                        current_height,
+                        block.header.previous_block_hash,
+                        outbox_block_hashes.clone(),
+                        inbox_cursors.clone(),
+                        None,
+                    )
+                    .await?;
+            }
+            let blob_ids = block.published_blob_ids().into_iter().collect::<Vec<_>>();
+            let published_blobs = self
+                .read_blobs(&blob_ids)
+                .await?
+                .into_iter()
+                .map(CacheArc::unwrap_or_clone)
+                .collect::<Vec<_>>();
+            // The block's own timestamp keeps the re-execution independent of the clock.
+            let local_time = block.header.timestamp;
+            let (proposed_block, outcome) = block.clone().into_proposal();
+            let oracle_responses = outcome.oracle_responses;
+            if index == last_index {
+                let execution = execution.take().expect("the last block is executed once");
+                chain
+                    .execute_block(
+                        proposed_block,
+                        local_time,
+                        None,
+                        &published_blobs,
+                        execution(oracle_responses),
+                    )
+                    .await?;
+                break;
+            }
+            chain
+                .execute_block(
+                    proposed_block,
+                    local_time,
+                    None,
+                    &published_blobs,
+                    BlockExecution::HandleConfirmed { oracle_responses },
+                )
+                .await?;
+            chain
+                .apply_confirmed_block(certificate.value(), local_time, None)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Reads the given blobs from storage, failing if any of them is missing.
+    async fn read_blobs(&self, blob_ids: &[BlobId]) -> Result<Vec<CacheArc<Blob>>, WorkerError> {
+        let maybe_blobs = self.storage.read_blobs(blob_ids).await?;
+        let missing_blob_ids = blob_ids
+            .iter()
+            .zip(&maybe_blobs)
+            .filter(|(_, maybe_blob)| maybe_blob.is_none())
+            .map(|(blob_id, _)| *blob_id)
+            .collect::<Vec<_>>();
+        ensure!(
+            missing_blob_ids.is_empty(),
+            WorkerError::BlobsNotFound(missing_blob_ids)
+        );
+        Ok(maybe_blobs.into_iter().flatten().collect())
+    }
+}
+
+/// Deletes everything stored under the scratch copy's prefix.
+async fn clear_scratch_copy<C: Context>(context: &C) -> Result<(), ViewError> {
+    let mut batch = Batch::new();
+    batch.delete_key_prefix(context.base_key().bytes.clone());
+    context
+        .store()
+        .write_batch(batch)
+        .await
+        .map_err(|error| ViewError::StoreError {
+            backend: "BlockReexecution::clear_scratch_copy",
+            error: Box::new(error),
+            must_reload_view: false,
+        })
+}
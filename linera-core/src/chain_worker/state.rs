@@ -20,7 +20,10 @@ use linera_base::{
     },
     ensure,
     hashed::Hashed,
-    identifiers::{AccountOwner, ApplicationId, BlobId, BlobType, ChainId, EventId, StreamId},
+    identifiers::{
+        AccountOwner, ApplicationId, BlobId, BlobType, ChainId, EventId, GenericApplicationId,
+        StreamId,
+    },
 };
 use linera_cache::{Arc as CacheArc, UniqueValueCache, ValueCache};
 use linera_chain::{
@@ -37,7 +40,10 @@ use linera_chain::{
     ExecutionResultExt as _, StreamCounts,
 };
 use linera_execution::{
-    system::{EpochEventData, EventSubscriptions, EPOCH_STREAM_NAME},
+    system::{
+        ApplicationUpgradeEventData, EpochEventData, EventSubscriptions,
+        APPLICATION_UPGRADE_STREAM_PREFIX, EPOCH_STREAM_NAME,
+    },
     ExecutionRuntimeContext as _, ExecutionStateView, Query, QueryContext, QueryOutcome,
     ResourceTracker, ServiceRuntimeEndpoint,
 };
@@ -53,7 +59,8 @@ use tracing::{debug, info, instrument, trace, warn};
 
 use crate::{
     chain_worker::{
-        export::BlockExportHandle, handle::AtomicTimestamp, ChainWorkerConfig, DeliveryNotifier,
+        export::BlockExportHandle, handle::AtomicTimestamp, BlockReexecution, ChainWorkerConfig,
+        DeliveryNotifier,
     },
     client::{ChainModes, ListeningMode},
     data_types::{ChainInfo, ChainInfoQuery, ChainInfoResponse, CrossChainRequest},
@@ -1009,6 +1016,7 @@ where
                 .flatten()
                 .map(|event| (event.id(chain_id), event.value.clone()));
             self.storage.write_events(events).await?;
+            self.evict_upgraded_application_code(block).await?;
         }
 
         // Update the blob state with last used certificate hash.
@@ -1059,6 +1067,40 @@ where
         }
     }
 
+    /// Removes the cached code of the modules that applications were upgraded away from
+    /// in the given block.
+    async fn evict_upgraded_application_code(&self, block: &Block) -> Result<(), WorkerError> {
+        for event in block.body.events.iter().flatten() {
+            if event.stream_id.application_id != GenericApplicationId::System
+                || !event
+                    .stream_id
+                    .stream_name
+                    .0
+                    .starts_with(APPLICATION_UPGRADE_STREAM_PREFIX)
+            {
+                continue;
+            }
+            let event_data: ApplicationUpgradeEventData = bcs::from_bytes(&event.value)?;
+            let application_id = event_data.application_id;
+            let Some(blob) = self
+                .storage
+                .read_blob(application_id.description_blob_id())
+                .await?
+            else {
+                continue;
+            };
+            let mut description: ApplicationDescription = bcs::from_bytes(blob.bytes())?;
+            description.module_id = event_data.previous_module_id;
+            // The code cache is keyed by the description the code was loaded from.
+            let cache_key = ApplicationId::from(&description);
+            let context = self.chain.execution_state.context();
+            let extra = context.extra();
+            extra.user_contracts().pin().remove(&cache_key);
+            extra.user_services().pin().remove(&cache_key);
+        }
+        Ok(())
+    }
+
     /// Preprocesses a confirmed block: updates outboxes and event streams without
     /// executing it, and does not advance the chain tip.
     async fn preprocess_certified_block(
@@ -1108,7 +1150,7 @@ where
         blobs: BTreeMap<BlobId, Blob>,
         notify_when_messages_are_delivered: Option<oneshot::Sender<()>>,
     ) -> Result<(ChainInfoResponse, NetworkActions, BlockOutcome), WorkerError> {
-        let (bytes, chain_id, height, previous_block_hash, outbox_block_hashes, inbox_cursors) = {
+        let (bytes, height, previous_block_hash, outbox_block_hashes, inbox_cursors) = {
             let block = certificate.block();
             let Some(OracleResponse::Checkpoint {
                 execution_state_blobs,
@@ -1134,7 +1176,6 @@ where
             ensure!(missing.is_empty(), WorkerError::BlobsNotFound(missing));
             (
                 bytes,
-                block.header.chain_id,
                 block.header.height,
                 block.header.previous_block_hash,
                 outbox_block_hashes.clone(),
@@ -1161,84 +1202,18 @@ where
             self.save().await?;
             return Err(WorkerError::BlocksNotFound(missing_blocks));
         }
-        self.chain
-            .execution_state
-            .restore_from_content(&bytes)
-            .await?;
-        // `restore_from_content` writes directly to storage and leaves the
-        // in-memory view in an undefined state — reload from storage.
-        self.chain = self.storage.load_chain(chain_id).await?;
-        // Re-populate `block_hashes` for every pre-checkpoint sender block the
-        // chain still needs. The heights live in the just-restored execution
-        // state (`unfinalized_message_blocks`); the matching hashes are the
-        // ones the producer recorded in the oracle response, certified by the
-        // checkpoint cert we already trust. Without this, the next step
-        // (re-executing the checkpoint to verify its outcome) would fail
-        // because `collect_unfinalized_block_hashes` looks these up.
-        let heights = self.chain.collect_unfinalized_heights().await?;
-        ensure!(
-            heights.len() == outbox_block_hashes.len(),
-            ChainError::InternalError(format!(
-                "checkpoint oracle response has {} outbox block hashes but the \
-                 restored state references {} distinct heights",
-                outbox_block_hashes.len(),
-                heights.len(),
-            ))
-        );
-        for (height, hash) in heights.into_iter().zip(outbox_block_hashes) {
-            self.chain.block_hashes.insert(&height, hash)?;
-        }
-        // Rebuild the off-chain outbox state (queues, counters,
-        // nonempty_outboxes) from the on-chain unfinalized map so that this
-        // node can resume pushing pre-checkpoint messages forward. The outbox
-        // isn't part of the certified checkpoint blob, so without this a
-        // bootstrapped validator would silently stop delivering pending
-        // messages.
         let tracked = self.tracked_full_chains();
-        self.chain
-            .restore_outboxes_from_unfinalized(tracked.as_deref())
-            .await?;
-        for (origin, cursor) in inbox_cursors {
-            let mut inbox = self.chain.inboxes.try_load_entry_mut(&origin).await?;
-            inbox.restore_from_checkpoint(cursor).await?;
-        }
-        // Seed `next_expected_events` from the restored per-stream event counts. Re-executing
-        // the checkpoint re-emits each summarized stream's summary event; without this seed
-        // the summary's index would look like a gap on the freshly-restored node, so the
-        // tracker would never advance and future events on that stream would never be
-        // delivered to subscribers. The counts are contiguous, so this matches the producer's
-        // tracker as of just before the checkpoint block.
-        for (stream_id, count) in self
+        let new_tip = self
             .chain
-            .execution_state
-            .system
-            .stream_event_counts
-            .index_values()
-            .await?
-        {
-            // Just after restoring, every event predates the checkpoint, so the readable floor
-            // is the count itself.
-            self.chain.next_expected_events.insert(
-                &stream_id,
-                StreamCounts {
-                    first_index: count,
-                    next_index: count,
-                },
-            )?;
-        }
-        // We reset `execution_state` (via restore), `tip_state`, `block_hashes`
-        // (for outbox-referenced pre-checkpoint heights), and the outbox views.
-        // The other `ChainStateView` fields are either (a) already default for
-        // a fresh bootstrap node (`inboxes`, `received_log`, …), (b) about to
-        // be overwritten by `apply_confirmed_block` when the cert is applied
-        // (`manager`, `block_hashes` for height `height`), or (c) outside the
-        // protocol state hash so divergence from the producer is fine
-        // (inboxes; subsequent blocks reconcile by anticipation if needed).
-        let new_tip = ChainTipState {
-            block_hash: previous_block_hash,
-            next_block_height: height,
-        };
-        self.chain.tip_state.set(new_tip.clone());
+            .install_checkpoint_snapshot(
+                &bytes,
+                height,
+                previous_block_hash,
+                outbox_block_hashes,
+                inbox_cursors,
+                tracked.as_deref(),
+            )
+            .await?;
         // Installing a snapshot establishes the chain's state from scratch (block 0 is never
         // executed here), so record it as the initialization time for the reset cooldown.
         self.chain
@@ -2113,6 +2088,21 @@ where
             .await?)
     }
 
+    /// Gets the IDs of at most `limit` scheduled operations that are due at the given
+    /// timestamp.
+    pub(crate) async fn get_due_scheduled_operations(
+        &self,
+        timestamp: Timestamp,
+        limit: usize,
+    ) -> Result<Vec<u32>, WorkerError> {
+        Ok(self
+            .chain
+            .execution_state
+            .system
+            .due_scheduled_operations(timestamp, limit)
+            .await?)
+    }
+
     /// Gets a stream's [`StreamCounts`]: the next expected event index and the lowest readable
     /// index (the first event published since the most recent checkpoint). Both default to 0 for
     /// a stream with no events yet. They come from the same `next_expected_events` entry, so they
@@ -2371,6 +2361,61 @@ where
         }
     }
 
+    /// Returns the confirmed blocks to replay in order to re-execute the one at the given
+    /// height, so that the replay can run without holding the chain worker's lock.
+    ///
+    /// Past execution states are not stored, so the replay starts from the chain's latest
+    /// checkpoint, or from its first block. Blocks below the latest checkpoint cannot be
+    /// re-executed, and at most `limit` blocks are replayed.
+    #[instrument(skip_all, fields(
+        chain_id = %self.chain_id(),
+        height = %height
+    ))]
+    pub(crate) async fn prepare_reexecution(
+        &self,
+        height: BlockHeight,
+        limit: u64,
+    ) -> Result<BlockReexecution<StorageClient>, WorkerError> {
+        let chain_id = self.chain_id();
+        let start = (*self.chain.latest_checkpoint_height.get()).unwrap_or(BlockHeight::ZERO);
+        ensure!(
+            height >= start,
+            WorkerError::ReexecutionBeforeCheckpoint {
+                chain_id,
+                height,
+                checkpoint_height: start,
+            }
+        );
+        let block_count = (height.0 - start.0).saturating_add(1);
+        ensure!(
+            block_count <= limit,
+            WorkerError::ReexecutionTooLong {
+                chain_id,
+                height,
+                block_count,
+                limit,
+            }
+        );
+        let heights = (start.0..=height.0).map(BlockHeight).collect::<Vec<_>>();
+        let block_hashes = heights
+            .iter()
+            .zip(self.chain.block_hashes.multi_get(&heights).await?)
+            .map(|(height, hash)| {
+                hash.ok_or(WorkerError::BlockHashNotFound {
+                    height: *height,
+                    chain_id,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(BlockReexecution::new(
+            self.storage.clone(),
+            self.chain.context().clone(),
+            chain_id,
+            start,
+            block_hashes,
+        ))
+    }
+
     /// Returns an application's description by reading the blob directly from storage.
     ///
     /// Does not track blob usage (which requires `&mut self`), making it safe for
@@ -2551,7 +2596,7 @@ where
                         round: Round::Fast,
                         outcome: None,
                     },
-                    signature: *signature,
+                    signature: signature.clone(),
                     original_proposal: None,
                 };
                 let super_owner = original_proposal.owner();
@@ -18,10 +18,11 @@ use futures::{
 use linera_base::prometheus_util::MeasureLatency as _;
 use linera_base::{
     abi::Abi,
-    crypto::{signer, CryptoHash, Signer, ValidatorPublicKey},
+    crypto::{signer, AccountSignature, CryptoHash, Signer, ValidatorPublicKey},
     data_types::{
         Amount, ApplicationDescription, ApplicationPermissions, ArithmeticError, Blob, BlobContent,
-        BlockHeight, ChainDescription, Epoch, MessagePolicy, Round, TimeDelta, Timestamp,
+        BlockHeight, ChainDescription, Epoch, FeeSponsorship, MessagePolicy, Round, TimeDelta,
+        Timestamp, VestingSchedule,
     },
     ensure,
     identifiers::{
@@ -36,7 +37,7 @@ use linera_base::{data_types::Bytecode, vm::VmRuntime};
 use linera_chain::{
     data_types::{
         BlockProposal, BundleExecutionPolicy, BundleFailurePolicy, ChainAndHeight, IncomingBundle,
-        ProposedBlock, Transaction,
+        ProposalContent, ProposedBlock, Transaction,
     },
     manager::LockingBlock,
     types::{
@@ -47,11 +48,12 @@ use linera_chain::{
 };
 use linera_execution::{
     committee::Committee,
+    evm::trace::EvmTrace,
     system::{
         AdminOperation, OpenChainConfig, SystemOperation, EPOCH_STREAM_NAME,
         REMOVED_EPOCH_STREAM_NAME,
     },
-    ExecutionError, Operation, Query, QueryOutcome,
+    ExecutionError, Operation, Query, QueryOutcome, ResourceTracker, WasmProfile,
 };
 use linera_storage::{Arc as CacheArc, Clock as _, Storage as _};
 use linera_views::ViewError;
@@ -92,6 +94,8 @@ pub struct Options {
     /// Time budget for staging message bundles. When set, limits bundle execution by
     /// wall-clock time, in addition to the count limit from `max_pending_message_bundles`.
     pub staging_bundles_time_budget: Option<Duration>,
+    /// Maximum number of due scheduled operations executed at a time in a block.
+    pub max_scheduled_operations: usize,
     /// The policy for automatically handling incoming messages.
     pub message_policy: MessagePolicy,
     /// Chain IDs whose incoming bundles should be processed first when proposing a block.
@@ -163,6 +167,7 @@ impl Options {
             max_pending_message_bundles: 10,
             max_block_limit_errors: 3,
             staging_bundles_time_budget: None,
+            max_scheduled_operations: 10,
             message_policy: MessagePolicy::default(),
             priority_bundle_origins: HashSet::new(),
             cross_chain_message_delivery: CrossChainMessageDelivery::NonBlocking,
@@ -724,6 +729,24 @@ impl<Env: Environment> ChainClient<Env> {
             .collect::<Vec<_>>())
     }
 
+    /// Returns the operations to execute up to `self.options.max_scheduled_operations` of the
+    /// chain's scheduled operations that are due at the current local time.
+    async fn collect_due_scheduled_operations(&self) -> Result<Vec<Operation>, Error> {
+        let local_time = self.storage_client().clock().current_time();
+        Ok(self
+            .client
+            .local_node
+            .get_due_scheduled_operations(
+                self.chain_id,
+                local_time,
+                self.options.max_scheduled_operations,
+            )
+            .await?
+            .into_iter()
+            .map(|id| SystemOperation::ExecuteScheduledOperation { id }.into())
+            .collect())
+    }
+
     /// Obtains the committee for the current epoch of the local chain.
     #[instrument(level = "trace")]
     pub async fn local_committee(&self) -> Result<Arc<Committee>, Error> {
@@ -1289,6 +1312,24 @@ impl<Env: Environment> ChainClient<Env> {
         .await
     }
 
+    /// Sends money to an owner on this chain, locked until it vests according to `schedule`.
+    #[instrument(level = "trace")]
+    pub async fn lock_tokens(
+        &self,
+        owner: AccountOwner,
+        beneficiary: AccountOwner,
+        amount: Amount,
+        schedule: VestingSchedule,
+    ) -> Result<ClientOutcome<ConfirmedBlockCertificate>, Error> {
+        self.execute_operation(SystemOperation::LockTokens {
+            owner,
+            beneficiary,
+            amount,
+            schedule,
+        })
+        .await
+    }
+
     /// Verify if a data blob is readable from storage.
     // TODO(#2490): Consider removing or renaming this.
     #[instrument(level = "trace")]
@@ -1379,6 +1420,49 @@ impl<Env: Environment> ChainClient<Env> {
             .await
     }
 
+    /// Executes a list of operations in a new block on a throwaway copy of the chain state,
+    /// without proposing it, and returns the resources the block would consume, including
+    /// the fees charged under the current policy.
+    ///
+    /// If an operation fails, the returned error carries the index of the failing
+    /// transaction.
+    #[instrument(level = "trace", skip(operations, blobs))]
+    pub async fn estimate_block(
+        &self,
+        operations: Vec<Operation>,
+        blobs: Vec<Blob>,
+    ) -> Result<ResourceTracker, Error> {
+        let info = self.chain_info().await?;
+        let transactions = operations
+            .into_iter()
+            .map(Transaction::ExecuteOperation)
+            .collect::<Vec<_>>();
+        let timestamp = self.next_timestamp(&transactions, info.timestamp);
+        let proposed_block = ProposedBlock {
+            epoch: info.epoch,
+            chain_id: self.chain_id,
+            transactions,
+            previous_block_hash: info.block_hash,
+            height: info.next_block_height,
+            authenticated_owner: self.preferred_owner,
+            timestamp,
+        };
+        let round = match &self.preferred_owner {
+            Some(owner) => self.round_for_oracle(&info, owner).await?,
+            None => None,
+        };
+        let (_, _, resource_tracker, _) = self
+            .client
+            .stage_block_execution_with_resources(
+                proposed_block,
+                round,
+                blobs,
+                self.options.bundle_execution_policy(),
+            )
+            .await?;
+        Ok(resource_tracker)
+    }
+
     /// Executes a list of operations.
     #[instrument(level = "trace", skip(operations, blobs))]
     pub async fn execute_operations(
@@ -1551,7 +1635,8 @@ impl<Env: Environment> ChainClient<Env> {
 
     /// Returns `Committed` if the committed block reflects our request — same
     /// authenticated owner, and our `operations`. All other transactions must be ones that
-    /// are added automatically, to process messages, streams or new epochs.
+    /// are added automatically, to process messages, streams, new epochs or scheduled
+    /// operations.
     fn classify_committed(
         &self,
         certificate: ConfirmedBlockCertificate,
@@ -1573,7 +1658,9 @@ impl<Env: Environment> ChainClient<Env> {
                 }
                 Transaction::ExecuteOperation(Operation::System(op)) => matches!(
                     **op,
-                    SystemOperation::ProcessNewEpoch(_) | SystemOperation::UpdateStream { .. }
+                    SystemOperation::ProcessNewEpoch(_)
+                        | SystemOperation::UpdateStream { .. }
+                        | SystemOperation::ExecuteScheduledOperation { .. }
                 ),
                 Transaction::ExecuteOperation(Operation::User { .. }) => false,
             };
@@ -1589,8 +1676,8 @@ impl<Env: Environment> ChainClient<Env> {
     }
 
     /// Creates a vector of transactions which, in addition to the provided operations,
-    /// also contains the next pending epoch change, receiving message bundles and event
-    /// stream updates (if there are any to be processed).
+    /// also contains the next pending epoch change, receiving message bundles, event
+    /// stream updates and due scheduled operations (if there are any to be processed).
     /// This should be called when executing a block, in order to make sure that any pending
     /// messages or events are included in it.
     #[instrument(level = "trace", skip(operations))]
@@ -1600,6 +1687,7 @@ impl<Env: Environment> ChainClient<Env> {
     ) -> Result<Vec<Transaction>, Error> {
         let incoming_bundles = self.pending_message_bundles().await?;
         let stream_updates = self.collect_stream_updates().await?;
+        let scheduled_operations = self.collect_due_scheduled_operations().await?;
         Ok(self
             .next_epoch_change()
             .await?
@@ -1615,6 +1703,11 @@ impl<Env: Environment> ChainClient<Env> {
                     .into_iter()
                     .map(Transaction::ExecuteOperation),
             )
+            .chain(
+                scheduled_operations
+                    .into_iter()
+                    .map(Transaction::ExecuteOperation),
+            )
             .chain(operations.into_iter().map(Transaction::ExecuteOperation))
             .collect::<Vec<_>>())
     }
@@ -1732,6 +1825,42 @@ impl<Env: Environment> ChainClient<Env> {
         }
     }
 
+    /// Returns the traces of the EVM executions of the transaction with the given index in
+    /// this chain's confirmed block at the given height.
+    ///
+    /// The chain is re-executed locally up to that block, so this gets slower as the block
+    /// gets higher.
+    #[instrument(level = "trace")]
+    pub async fn trace_evm_transaction(
+        &self,
+        height: BlockHeight,
+        transaction_index: u32,
+    ) -> Result<Vec<EvmTrace>, Error> {
+        Ok(self
+            .client
+            .local_node
+            .trace_evm_transaction(self.chain_id, height, transaction_index)
+            .await?)
+    }
+
+    /// Returns the profile of the Wasm contract executions of the transaction with the
+    /// given index in this chain's confirmed block at the given height.
+    ///
+    /// The chain is re-executed locally up to that block, so this gets slower as the block
+    /// gets higher.
+    #[instrument(level = "trace")]
+    pub async fn profile_wasm_transaction(
+        &self,
+        height: BlockHeight,
+        transaction_index: u32,
+    ) -> Result<WasmProfile, Error> {
+        Ok(self
+            .client
+            .local_node
+            .profile_wasm_transaction(self.chain_id, height, transaction_index)
+            .await?)
+    }
+
     /// Queries a system application.
     #[cfg(with_testing)]
     #[instrument(level = "trace", skip(query))]
@@ -2039,6 +2168,81 @@ impl<Env: Environment> ChainClient<Env> {
             .await
     }
 
+    /// Returns the owner and the content of the proposal this client would make for its
+    /// pending block, or `None` if there is no pending block, a locking block has to be
+    /// re-proposed first, or the client has to wait for a timeout.
+    ///
+    /// If the owner is a multi-signature owner, this is what its co-signers have to sign,
+    /// e.g. with [`ChainClient::sign_proposal_content`], before the pending block can be
+    /// proposed. The content includes the round, so signatures collected for one round
+    /// cannot be reused in another.
+    #[instrument(level = "trace", skip(self))]
+    pub async fn pending_proposal_content(
+        &self,
+    ) -> Result<Option<(AccountOwner, ProposalContent)>, Error> {
+        let Some(pending) = self.pending_proposal().await else {
+            return Ok(None);
+        };
+        let info = self.chain_info_with_manager_values().await?;
+        if info.manager.requested_locking.is_some() {
+            return Ok(None);
+        }
+        let owner = match pending.block.authenticated_owner {
+            Some(owner) => owner,
+            None => self.identity().await?,
+        };
+        let round = self.round_for_oracle(&info, &owner).await?;
+        let (block, _, _) = self
+            .client
+            .stage_block_execution(
+                pending.block.clone(),
+                round,
+                pending.blobs,
+                BundleExecutionPolicy::committed(),
+            )
+            .await?;
+        let round = match self
+            .round_for_new_proposal(&info, &owner, block.has_oracle_responses())
+            .await?
+        {
+            Either::Left(round) => round,
+            Either::Right(_) => return Ok(None),
+        };
+        let content = ProposalContent {
+            round,
+            block: pending.block,
+            outcome: None,
+        };
+        Ok(Some((owner, content)))
+    }
+
+    /// Signs the `content` of a block proposal as `owner`, e.g. as one of the members of a
+    /// multi-signature owner of this chain.
+    #[instrument(level = "trace", skip(self, content))]
+    pub async fn sign_proposal_content(
+        &self,
+        owner: &AccountOwner,
+        content: &ProposalContent,
+    ) -> Result<AccountSignature, Error> {
+        self.signer()
+            .sign(owner, &CryptoHash::new(content))
+            .await
+            .map_err(Error::signer_failure)
+    }
+
+    /// Signs a fee sponsorship as its sponsor, so that blocks on the sponsored chain can have
+    /// the sponsor pay their fees.
+    #[instrument(level = "trace", skip(self))]
+    pub async fn sign_fee_sponsorship(
+        &self,
+        sponsorship: &FeeSponsorship,
+    ) -> Result<AccountSignature, Error> {
+        self.signer()
+            .sign(&sponsorship.sponsor, &CryptoHash::new(sponsorship))
+            .await
+            .map_err(Error::signer_failure)
+    }
+
     /// Processes the last pending block. Assumes that the local chain is up to date.
     ///
     /// The caller must hold the proposal mutex via `proposal_guard`. The pending proposal
@@ -2794,12 +2998,43 @@ impl<Env: Environment> ChainClient<Env> {
         parameters: Vec<u8>,
         instantiation_argument: Vec<u8>,
         required_application_ids: Vec<ApplicationId>,
+    ) -> Result<ClientOutcome<(ApplicationId, ConfirmedBlockCertificate)>, Error> {
+        self.create_upgradeable_application_untyped(
+            module_id,
+            parameters,
+            instantiation_argument,
+            required_application_ids,
+            None,
+        )
+        .await
+    }
+
+    /// Creates an application by instantiating some bytecode, allowing `upgrade_authority`
+    /// (if any) to later upgrade it to a different module.
+    #[instrument(
+        level = "trace",
+        skip(
+            self,
+            module_id,
+            parameters,
+            instantiation_argument,
+            required_application_ids
+        )
+    )]
+    pub async fn create_upgradeable_application_untyped(
+        &self,
+        module_id: ModuleId,
+        parameters: Vec<u8>,
+        instantiation_argument: Vec<u8>,
+        required_application_ids: Vec<ApplicationId>,
+        upgrade_authority: Option<AccountOwner>,
     ) -> Result<ClientOutcome<(ApplicationId, ConfirmedBlockCertificate)>, Error> {
         self.execute_operation(SystemOperation::CreateApplication {
             module_id,
             parameters,
             instantiation_argument,
             required_application_ids,
+            upgrade_authority,
         })
         .await?
         .try_map(|certificate| {
@@ -2823,6 +3058,51 @@ impl<Env: Environment> ChainClient<Env> {
         })
     }
 
+    /// Upgrades an application created on this chain to a different module.
+    #[instrument(level = "trace")]
+    pub async fn upgrade_application(
+        &self,
+        application_id: ApplicationId,
+        module_id: ModuleId,
+    ) -> Result<ClientOutcome<ConfirmedBlockCertificate>, Error> {
+        self.execute_operation(SystemOperation::UpgradeApplication {
+            application_id,
+            module_id,
+        })
+        .await
+    }
+
+    /// Adopts the next upgrade published by the creator chain of an application.
+    #[instrument(level = "trace")]
+    pub async fn process_application_upgrade(
+        &self,
+        application_id: ApplicationId,
+    ) -> Result<ClientOutcome<ConfirmedBlockCertificate>, Error> {
+        self.execute_operation(SystemOperation::ProcessApplicationUpgrade { application_id })
+            .await
+    }
+
+    /// Executes a list of operations in a block whose fees are paid by the sponsor of the
+    /// given `sponsorship`. The `signature` can be omitted if the sponsorship was approved on
+    /// this chain.
+    #[instrument(level = "trace", skip(self, signature, operations, blobs))]
+    pub async fn execute_sponsored_operations(
+        &self,
+        sponsorship: FeeSponsorship,
+        signature: Option<AccountSignature>,
+        operations: Vec<Operation>,
+        blobs: Vec<Blob>,
+    ) -> Result<ClientOutcome<ConfirmedBlockCertificate>, Error> {
+        let sponsorship_operation = Operation::system(SystemOperation::UseFeeSponsorship {
+            sponsorship,
+            signature,
+        });
+        let operations = iter::once(sponsorship_operation)
+            .chain(operations)
+            .collect();
+        self.execute_operations(operations, blobs).await
+    }
+
     /// Creates a new committee and starts using it (admin chains only).
     #[instrument(level = "trace", skip(committee))]
     pub async fn stage_new_committee(
@@ -2881,8 +3161,8 @@ impl<Env: Environment> ChainClient<Env> {
         let mut certificates = Vec::new();
         loop {
             // We provide no operations - this means that the only operations executed
-            // will be epoch changes, receiving messages and processing event stream
-            // updates, if any are pending.
+            // will be epoch changes, receiving messages, processing event stream
+            // updates and due scheduled operations, if any are pending.
             match self.execute_block(vec![], vec![]).await {
                 Ok(ClientOutcome::Committed(certificate)) => certificates.push(certificate),
                 Ok(ClientOutcome::Conflict(certificate)) => certificates.push(*certificate),
@@ -0,0 +1,647 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A client that follows chains without executing their blocks.
+//!
+//! Unlike [`ChainClient::synchronize_chain_state`](super::ChainClient::synchronize_chain_state),
+//! which downloads every block into the local node and re-executes it, the [`LightClient`] only
+//! checks the signatures of each [`ConfirmedBlockCertificate`] against the committee of its
+//! epoch, and keeps the block headers. The execution outcome of a block is trusted because a
+//! quorum of validators signed it. What the light client knows about each chain it follows is:
+//!
+//! * The headers of the chain's blocks, linked by their previous block hashes.
+//! * The message bundles sent to the chain and not yet received by it. Each one is taken from
+//!   the certificate of the sending block, so a validator can withhold a bundle but not forge
+//!   one.
+//! * The balances of the tracked owners. Execution state is not part of a certificate, so a
+//!   balance is accepted once validators with at least the validity threshold of the voting
+//!   weight sign the same balance for the state hash of the latest header. At least one
+//!   honest validator is then among them.
+//!
+//! Committees are learned from the admin chain, which the light client always follows: each
+//! new epoch is announced by an event in an admin chain block, certified by the committee of the
+//! previous epoch, so every committee is verified starting from the genesis committee. Removing
+//! a committee is announced the same way. Like the workers, the light client then only accepts
+//! a certificate of that epoch if a later certificate of the same chain, in an epoch that is
+//! still trusted, extends it; a bundle sent in a revoked epoch is left out of the inbox.
+//!
+//! The balances are an interim design: validators do not yet serve proofs that a value is part
+//! of the state with a given hash, so the light client relies on the answers of enough
+//! validators instead. Once such state proofs exist, a single validator's answer can be checked
+//! against the state hash of the latest verified header.
+//!
+//! The light client only follows chains: it cannot propose blocks, which requires executing
+//! them, so it does not replace the local node of a [`ChainClient`](super::ChainClient).
+//! Neither `ChainClient` nor `linera-web` uses it. A wallet that only needs to watch chains
+//! creates a [`LightClient`] itself, calls [`LightClient::follow_chain`] or
+//! [`LightClient::track_owner`] for each chain, and then calls
+//! [`LightClient::synchronize_followed_chains`] whenever it wants fresh state, for example
+//! after a notification.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use futures::future::join_all;
+use linera_base::{
+    crypto::CryptoHash,
+    data_types::{Amount, ArithmeticError, BlockHeight, Epoch},
+    ensure,
+    identifiers::{AccountOwner, BlobId, BlobType, ChainId, StreamId},
+};
+use linera_chain::{
+    data_types::MessageBundle,
+    types::{Block, BlockHeader, ConfirmedBlockCertificate},
+    ChainError,
+};
+use linera_execution::{
+    committee::Committee,
+    system::{EpochEventData, EPOCH_STREAM_NAME, REMOVED_EPOCH_STREAM_NAME},
+};
+use thiserror::Error;
+use tracing::{debug, instrument, warn};
+
+use super::DEFAULT_CERTIFICATE_DOWNLOAD_BATCH_SIZE;
+use crate::{
+    data_types::ChainInfoQuery,
+    node::{NodeError, ValidatorNode as _, ValidatorNodeProvider},
+    remote_node::RemoteNode,
+};
+
+/// Error type for [`LightClient`].
+#[derive(Debug, Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Remote node operation failed: {0}")]
+    RemoteNodeError(#[from] NodeError),
+
+    #[error("Invalid certificate: {0}")]
+    ChainError(#[from] ChainError),
+
+    #[error("Failed to deserialize admin chain data: {0}")]
+    BcsError(#[from] bcs::Error),
+
+    #[error("Chain {0} is not followed by this light client")]
+    ChainNotFollowed(ChainId),
+
+    #[error("No committee is known for epoch {0}")]
+    UnknownEpoch(Epoch),
+
+    #[error("The certificate at height {height} does not extend chain {chain_id}")]
+    UnexpectedCertificate {
+        chain_id: ChainId,
+        height: BlockHeight,
+    },
+
+    #[error("Epoch {0} has been revoked")]
+    RevokedEpoch(Epoch),
+
+    #[error("Arithmetic error: {0}")]
+    ArithmeticError(#[from] ArithmeticError),
+
+    #[error("The validator did not provide the committee blob {0}")]
+    MissingCommitteeBlob(BlobId),
+}
+
+/// What the light client knows about a chain it follows.
+#[derive(Clone, Debug, Default)]
+pub struct LightChainState {
+    /// The headers of the verified blocks, with the hashes of their certificates.
+    headers: BTreeMap<BlockHeight, (CryptoHash, BlockHeader)>,
+    /// The balances of the tracked owners, with the next block height at which they were
+    /// attested, if they ever were.
+    balances: BTreeMap<AccountOwner, Option<(BlockHeight, Amount)>>,
+    /// The verified bundles sent to this chain and not yet received, by origin, height and
+    /// transaction index.
+    inbox: BTreeMap<(ChainId, BlockHeight, u32), MessageBundle>,
+    /// The height and transaction index of the latest bundle received from each origin.
+    received: BTreeMap<ChainId, (BlockHeight, u32)>,
+}
+
+impl LightChainState {
+    /// Returns the height of the next block, i.e. the number of verified blocks.
+    pub fn next_block_height(&self) -> BlockHeight {
+        self.headers
+            .last_key_value()
+            .map_or(BlockHeight::ZERO, |(height, _)| {
+                BlockHeight(height.0.saturating_add(1))
+            })
+    }
+
+    /// Returns the certificate hash of the latest verified block, if any.
+    pub fn tip_hash(&self) -> Option<CryptoHash> {
+        self.headers.last_key_value().map(|(_, (hash, _))| *hash)
+    }
+
+    /// Returns the header of the latest verified block, if any.
+    pub fn tip_header(&self) -> Option<&BlockHeader> {
+        self.headers.last_key_value().map(|(_, (_, header))| header)
+    }
+
+    /// Returns the header of the block at the given height, if it was verified.
+    pub fn header(&self, height: BlockHeight) -> Option<&BlockHeader> {
+        self.headers.get(&height).map(|(_, header)| header)
+    }
+
+    /// Returns the verified headers in increasing height order, with their certificate hashes.
+    pub fn headers(&self) -> impl Iterator<Item = (CryptoHash, &BlockHeader)> {
+        self.headers.values().map(|(hash, header)| (*hash, header))
+    }
+
+    /// Returns the balance of a tracked owner after the latest verified block, if validators
+    /// attested it.
+    pub fn balance(&self, owner: &AccountOwner) -> Option<Amount> {
+        let (height, amount) = (*self.balances.get(owner)?)?;
+        (height == self.next_block_height()).then_some(amount)
+    }
+
+    /// Returns the verified bundles sent to this chain and not yet received, with their origins.
+    pub fn inbox(&self) -> impl Iterator<Item = (ChainId, &MessageBundle)> {
+        self.inbox
+            .iter()
+            .map(|((origin, _, _), bundle)| (*origin, bundle))
+    }
+
+    /// Returns whether the bundle at `height` and `transaction_index` from `origin` has been
+    /// received by this chain, according to the verified blocks.
+    fn is_received(&self, origin: ChainId, height: BlockHeight, transaction_index: u32) -> bool {
+        self.received
+            .get(&origin)
+            .is_some_and(|latest| (height, transaction_index) <= *latest)
+    }
+
+    /// Adds a verified block at the end of the chain, and removes the bundles it received from
+    /// the inbox.
+    fn push_block(&mut self, hash: CryptoHash, block: &Block) {
+        for incoming_bundle in block.body.incoming_bundles() {
+            let key = (
+                incoming_bundle.bundle.height,
+                incoming_bundle.bundle.transaction_index,
+            );
+            let latest = self.received.entry(incoming_bundle.origin).or_insert(key);
+            *latest = key.max(*latest);
+        }
+        let received = &self.received;
+        self.inbox.retain(|(origin, height, index), _| {
+            received
+                .get(origin)
+                .is_none_or(|latest| (*height, *index) > *latest)
+        });
+        self.headers
+            .insert(block.header.height, (hash, block.header.clone()));
+    }
+}
+
+/// A client that follows chains by verifying certificates, without executing blocks.
+pub struct LightClient<P: ValidatorNodeProvider> {
+    /// Creates the nodes of the validators of each new committee.
+    node_provider: P,
+    /// The admin chain ID.
+    admin_chain_id: ChainId,
+    /// The verified committees, by epoch.
+    committees: BTreeMap<Epoch, Arc<Committee>>,
+    /// The epochs whose committees the admin chain revoked.
+    revoked_epochs: BTreeSet<Epoch>,
+    /// The validators of the latest committee.
+    validators: Vec<RemoteNode<P::Node>>,
+    /// The state of the followed chains.
+    chains: BTreeMap<ChainId, LightChainState>,
+    /// The maximum number of certificates downloaded in one request.
+    certificate_download_batch_size: u64,
+}
+
+impl<P: ValidatorNodeProvider> LightClient<P> {
+    /// Creates a light client that trusts the given genesis committee, and follows the admin
+    /// chain to learn the later ones.
+    pub fn new(
+        node_provider: P,
+        admin_chain_id: ChainId,
+        genesis_committee: Committee,
+    ) -> Result<Self, Error> {
+        let mut client = Self {
+            node_provider,
+            admin_chain_id,
+            committees: BTreeMap::new(),
+            revoked_epochs: BTreeSet::new(),
+            validators: Vec::new(),
+            chains: BTreeMap::new(),
+            certificate_download_batch_size: DEFAULT_CERTIFICATE_DOWNLOAD_BATCH_SIZE,
+        };
+        client.add_committee(Epoch::ZERO, genesis_committee)?;
+        client.follow_chain(admin_chain_id);
+        Ok(client)
+    }
+
+    /// Sets the maximum number of certificates downloaded in one request.
+    pub fn with_certificate_download_batch_size(mut self, batch_size: u64) -> Self {
+        self.certificate_download_batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Returns the admin chain ID.
+    pub fn admin_chain_id(&self) -> ChainId {
+        self.admin_chain_id
+    }
+
+    /// Returns the verified committee of the given epoch, if known.
+    pub fn committee(&self, epoch: Epoch) -> Option<&Arc<Committee>> {
+        self.committees.get(&epoch)
+    }
+
+    /// Returns whether the admin chain revoked the committee of the given epoch.
+    pub fn is_epoch_revoked(&self, epoch: Epoch) -> bool {
+        self.revoked_epochs.contains(&epoch)
+    }
+
+    /// Returns the latest verified epoch.
+    pub fn latest_epoch(&self) -> Epoch {
+        self.committees
+            .last_key_value()
+            .map_or(Epoch::ZERO, |(epoch, _)| *epoch)
+    }
+
+    /// Starts following a chain. Its state is empty until it is synchronized.
+    pub fn follow_chain(&mut self, chain_id: ChainId) {
+        self.chains.entry(chain_id).or_default();
+    }
+
+    /// Starts tracking the balance of `owner` on a chain, following the chain if needed.
+    /// `AccountOwner::CHAIN` tracks the chain's own balance.
+    pub fn track_owner(&mut self, chain_id: ChainId, owner: AccountOwner) {
+        self.chains
+            .entry(chain_id)
+            .or_default()
+            .balances
+            .entry(owner)
+            .or_default();
+    }
+
+    /// Returns the state of a followed chain.
+    pub fn chain_state(&self, chain_id: ChainId) -> Option<&LightChainState> {
+        self.chains.get(&chain_id)
+    }
+
+    /// Returns the IDs of the followed chains.
+    pub fn followed_chains(&self) -> impl Iterator<Item = ChainId> + '_ {
+        self.chains.keys().copied()
+    }
+
+    /// Synchronizes all followed chains, starting with the admin chain. A chain that fails to
+    /// synchronize is skipped, and its error returned with its ID.
+    pub async fn synchronize_followed_chains(&mut self) -> Vec<(ChainId, Error)> {
+        let mut chain_ids = vec![self.admin_chain_id];
+        chain_ids.extend(
+            self.chains
+                .keys()
+                .filter(|chain_id| **chain_id != self.admin_chain_id),
+        );
+        let mut errors = Vec::new();
+        for chain_id in chain_ids {
+            if let Err(error) = self.synchronize_chain(chain_id).await {
+                errors.push((chain_id, error));
+            }
+        }
+        errors
+    }
+
+    /// Downloads and verifies the new blocks of a followed chain, then updates its inbox and the
+    /// balances of its tracked owners.
+    #[instrument(level = "trace", skip(self))]
+    pub async fn synchronize_chain(
+        &mut self,
+        chain_id: ChainId,
+    ) -> Result<&LightChainState, Error> {
+        ensure!(
+            self.chains.contains_key(&chain_id),
+            Error::ChainNotFollowed(chain_id)
+        );
+        match self.synchronize_headers_and_inbox(chain_id).await {
+            Err(Error::UnknownEpoch(epoch)) if chain_id != self.admin_chain_id => {
+                debug!(%chain_id, %epoch, "synchronizing the admin chain to learn a committee");
+                self.synchronize_headers(self.admin_chain_id).await?;
+                self.synchronize_headers_and_inbox(chain_id).await?;
+            }
+            result => result?,
+        }
+        self.synchronize_balances(chain_id).await;
+        Ok(&self.chains[&chain_id])
+    }
+
+    async fn synchronize_headers_and_inbox(&mut self, chain_id: ChainId) -> Result<(), Error> {
+        self.synchronize_headers(chain_id).await?;
+        self.synchronize_inbox(chain_id).await
+    }
+
+    /// Downloads the new certificates of a chain from the validator that reports the highest
+    /// block height, falling back to the next one if a certificate fails verification or if
+    /// the validator does not serve the blocks up to the height it reported.
+    async fn synchronize_headers(&mut self, chain_id: ChainId) -> Result<(), Error> {
+        let validators = self.validators.clone();
+        let query = ChainInfoQuery::new(chain_id);
+        let mut heights = join_all(validators.iter().map(|remote_node| {
+            let query = query.clone();
+            async move {
+                let info = remote_node.handle_chain_info_query(query).await.ok()?;
+                Some((info.next_block_height, remote_node))
+            }
+        }))
+        .await
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+        heights.sort_by_key(|(height, _)| std::cmp::Reverse(*height));
+        for (stop, remote_node) in heights {
+            if stop <= self.chains[&chain_id].next_block_height() {
+                break;
+            }
+            match self.download_headers(chain_id, remote_node, stop).await {
+                Ok(()) if self.chains[&chain_id].next_block_height() >= stop => break,
+                Ok(()) => warn!(
+                    %chain_id,
+                    validator = %remote_node.public_key,
+                    %stop,
+                    "validator did not serve the blocks up to the height it reported",
+                ),
+                Err(error @ Error::UnknownEpoch(_)) => return Err(error),
+                Err(error @ Error::RevokedEpoch(_)) => {
+                    debug!(
+                        %chain_id,
+                        validator = %remote_node.public_key,
+                        %error,
+                        "no trusted certificate extends the latest blocks yet",
+                    );
+                }
+                Err(error) => warn!(
+                    %chain_id,
+                    validator = %remote_node.public_key,
+                    %error,
+                    "failed to synchronize from validator",
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    /// Downloads and verifies the certificates of a chain from its next block height up to
+    /// `stop`, excluded.
+    ///
+    /// A certificate signed by a revoked committee is only recorded once a later certificate of
+    /// the same chain, signed by a committee that is still trusted, extends it: that certificate
+    /// re-certifies all preceding blocks through their previous block hashes.
+    async fn download_headers(
+        &mut self,
+        chain_id: ChainId,
+        remote_node: &RemoteNode<P::Node>,
+        stop: BlockHeight,
+    ) -> Result<(), Error> {
+        let state = &self.chains[&chain_id];
+        let mut height = state.next_block_height();
+        let mut tip_hash = state.tip_hash();
+        let mut pending = Vec::new();
+        while height < stop {
+            let limit = (stop.0 - height.0).min(self.certificate_download_batch_size);
+            let heights = (height.0..height.0 + limit).map(BlockHeight).collect();
+            let certificates = remote_node
+                .download_certificates_by_heights(chain_id, heights)
+                .await?;
+            if certificates.is_empty() {
+                break;
+            }
+            for certificate in certificates {
+                let is_trusted =
+                    self.check_certificate(&certificate, chain_id, height, tip_hash)?;
+                height = certificate.block().header.height.try_add_one()?;
+                tip_hash = Some(certificate.hash());
+                pending.push(certificate);
+                if is_trusted {
+                    for certificate in pending.drain(..) {
+                        self.record_certificate(remote_node, &certificate).await?;
+                    }
+                }
+            }
+        }
+        match pending.first() {
+            Some(certificate) => Err(Error::RevokedEpoch(certificate.block().header.epoch)),
+            None => Ok(()),
+        }
+    }
+
+    /// Verifies that a certificate extends a chain with the given next block height and tip,
+    /// and is signed by the committee of its epoch. Returns whether that epoch is still trusted.
+    fn check_certificate(
+        &self,
+        certificate: &ConfirmedBlockCertificate,
+        chain_id: ChainId,
+        next_block_height: BlockHeight,
+        tip_hash: Option<CryptoHash>,
+    ) -> Result<bool, Error> {
+        let header = &certificate.block().header;
+        ensure!(
+            header.chain_id == chain_id
+                && header.height == next_block_height
+                && header.previous_block_hash == tip_hash,
+            Error::UnexpectedCertificate {
+                chain_id,
+                height: header.height,
+            }
+        );
+        let committee = self
+            .committees
+            .get(&header.epoch)
+            .ok_or(Error::UnknownEpoch(header.epoch))?;
+        certificate.check(committee)?;
+        Ok(!self.revoked_epochs.contains(&header.epoch))
+    }
+
+    /// Records the header of a verified certificate, and the committee changes of admin chain
+    /// blocks.
+    async fn record_certificate(
+        &mut self,
+        remote_node: &RemoteNode<P::Node>,
+        certificate: &ConfirmedBlockCertificate,
+    ) -> Result<(), Error> {
+        let block = certificate.block();
+        let chain_id = block.header.chain_id;
+        if chain_id == self.admin_chain_id {
+            self.process_committee_events(remote_node, block).await?;
+        }
+        self.chains
+            .get_mut(&chain_id)
+            .ok_or(Error::ChainNotFollowed(chain_id))?
+            .push_block(certificate.hash(), block);
+        Ok(())
+    }
+
+    /// Adds the committees created, and revokes the ones removed, by a verified admin chain
+    /// block.
+    async fn process_committee_events(
+        &mut self,
+        remote_node: &RemoteNode<P::Node>,
+        block: &Block,
+    ) -> Result<(), Error> {
+        let stream_id = StreamId::system(EPOCH_STREAM_NAME);
+        let removed_stream_id = StreamId::system(REMOVED_EPOCH_STREAM_NAME);
+        for event in block.body.events.iter().flatten() {
+            if event.stream_id == removed_stream_id {
+                debug!(epoch = %Epoch(event.index), "committee revoked");
+                self.revoked_epochs.insert(Epoch(event.index));
+                continue;
+            }
+            if event.stream_id != stream_id {
+                continue;
+            }
+            let event_data: EpochEventData = bcs::from_bytes(&event.value)?;
+            let blob_id = BlobId::new(event_data.blob_hash, BlobType::Committee);
+            let blob = remote_node
+                .download_blob(blob_id)
+                .await?
+                .ok_or(Error::MissingCommitteeBlob(blob_id))?;
+            let committee = bcs::from_bytes(blob.bytes())?;
+            self.add_committee(Epoch(event.index), committee)?;
+        }
+        Ok(())
+    }
+
+    /// Adds a verified committee, and switches to its validators if it is the latest one.
+    fn add_committee(&mut self, epoch: Epoch, committee: Committee) -> Result<(), Error> {
+        if epoch >= self.latest_epoch() {
+            self.validators = self
+                .node_provider
+                .make_nodes(&committee)?
+                .map(|(public_key, node)| RemoteNode { public_key, node })
+                .collect();
+        }
+        self.committees.insert(epoch, Arc::new(committee));
+        Ok(())
+    }
+
+    /// Adds the bundles pending in the inbox of a chain, according to the first validator that
+    /// answers. Each bundle is taken from the verified certificate of its sending block.
+    async fn synchronize_inbox(&mut self, chain_id: ChainId) -> Result<(), Error> {
+        let validators = self.validators.clone();
+        let query = ChainInfoQuery::new(chain_id).with_pending_message_bundles();
+        for remote_node in &validators {
+            let info = match remote_node.handle_chain_info_query(query.clone()).await {
+                Ok(info) => info,
+                Err(error) => {
+                    warn!(%chain_id, validator = %remote_node.public_key, %error, "failed to query the inbox");
+                    continue;
+                }
+            };
+            let state = &self.chains[&chain_id];
+            let hashes = info
+                .requested_pending_message_bundles
+                .iter()
+                .filter(|incoming| {
+                    let bundle = &incoming.bundle;
+                    let key = (incoming.origin, bundle.height, bundle.transaction_index);
+                    !state.inbox.contains_key(&key)
+                        && !state.is_received(
+                            incoming.origin,
+                            bundle.height,
+                            bundle.transaction_index,
+                        )
+                })
+                .map(|incoming| incoming.bundle.certificate_hash)
+                .collect::<BTreeSet<_>>();
+            if hashes.is_empty() {
+                return Ok(());
+            }
+            let certificates = match remote_node
+                .node
+                .download_certificates(hashes.iter().copied().collect())
+                .await
+            {
+                Ok(certificates) => certificates,
+                Err(error) => {
+                    warn!(%chain_id, validator = %remote_node.public_key, %error, "failed to download the sending certificates");
+                    continue;
+                }
+            };
+            for certificate in certificates {
+                if !hashes.contains(&certificate.hash()) {
+                    continue;
+                }
+                let epoch = certificate.block().header.epoch;
+                let committee = self
+                    .committees
+                    .get(&epoch)
+                    .ok_or(Error::UnknownEpoch(epoch))?;
+                if let Err(error) = certificate.check(committee) {
+                    warn!(%chain_id, validator = %remote_node.public_key, %error, "invalid sending certificate");
+                    continue;
+                }
+                if self.revoked_epochs.contains(&epoch) {
+                    debug!(%chain_id, %epoch, "ignoring a bundle sent in a revoked epoch");
+                    continue;
+                }
+                let origin = certificate.block().header.chain_id;
+                let state = self
+                    .chains
+                    .get_mut(&chain_id)
+                    .expect("the chain is followed");
+                for (_, bundle) in certificate.message_bundles_for(chain_id) {
+                    if !state.is_received(origin, bundle.height, bundle.transaction_index) {
+                        let key = (origin, bundle.height, bundle.transaction_index);
+                        state.inbox.insert(key, bundle);
+                    }
+                }
+            }
+            return Ok(());
+        }
+        Ok(())
+    }
+
+    /// Updates the balances of the tracked owners of a chain, if validators with at least the
+    /// validity threshold of the voting weight sign the same balance for its latest header.
+    ///
+    /// Each answer is signed by its validator, but only attests the balance, not its inclusion
+    /// in the state: this stands in for a state proof until validators can serve one.
+    async fn synchronize_balances(&mut self, chain_id: ChainId) {
+        let state = &self.chains[&chain_id];
+        let next_block_height = state.next_block_height();
+        let tip_hash = state.tip_hash();
+        let state_hash = state.tip_header().map(|header| header.state_hash);
+        let owners = state.balances.keys().copied().collect::<Vec<_>>();
+        let committee = self.committees[&self.latest_epoch()].clone();
+        for owner in owners {
+            let mut query = ChainInfoQuery::new(chain_id);
+            query.request_owner_balance = owner;
+            let responses = join_all(self.validators.iter().map(|remote_node| {
+                let query = query.clone();
+                async move {
+                    let info = remote_node.handle_chain_info_query(query).await.ok()?;
+                    Some((remote_node.public_key, info))
+                }
+            }))
+            .await;
+            let mut weights = BTreeMap::<Amount, u64>::new();
+            for (public_key, info) in responses.into_iter().flatten() {
+                if info.next_block_height != next_block_height
+                    || info.block_hash != tip_hash
+                    || state_hash.is_some_and(|hash| info.state_hash != Some(hash))
+                {
+                    continue;
+                }
+                let amount = info.requested_owner_balance.unwrap_or(Amount::ZERO);
+                *weights.entry(amount).or_default() += committee.weight(&public_key);
+            }
+            let attested = weights
+                .into_iter()
+                .find(|(_, weight)| *weight >= committee.validity_threshold());
+            match attested {
+                Some((amount, _)) => {
+                    let state = self
+                        .chains
+                        .get_mut(&chain_id)
+                        .expect("the chain is followed");
+                    state
+                        .balances
+                        .insert(owner, Some((next_block_height, amount)));
+                }
+                None => debug!(%chain_id, %owner, "no balance attested by enough validators"),
+            }
+        }
+    }
+}
@@ -42,7 +42,7 @@ use linera_chain::{
     },
     ChainError, ChainIdSet,
 };
-use linera_execution::{committee::Committee, ExecutionError};
+use linera_execution::{committee::Committee, ExecutionError, ResourceTracker};
 use linera_storage::{Arc as CacheArc, Clock as _, ResultReadCertificates, Storage as _};
 use rand::seq::SliceRandom;
 use received_log::ReceivedLogs;
@@ -71,6 +71,9 @@ pub use crate::data_types::ClientOutcome;
 #[cfg(test)]
 #[path = "../unit_tests/client_tests.rs"]
 mod client_tests;
+/// A client that follows chains by verifying certificates, without executing blocks.
+pub mod light_client;
+pub use light_client::LightClient;
 pub mod requests_scheduler;
 
 pub use requests_scheduler::{RequestsScheduler, RequestsSchedulerConfig, ScoringWeights};
@@ -758,6 +761,78 @@ impl<Env: Environment> Client<Env> {
             return Ok(last_info);
         }
 
+        // Stream the certificates if the validator supports it. An interrupted stream is
+        // resumed from the next missing height as long as it makes progress; if it fails
+        // without any, fall back to the batched downloads below.
+        let chunk_size = usize::try_from(self.options.certificate_download_batch_size)
+            .unwrap_or(usize::MAX)
+            .max(1);
+        loop {
+            let start_height = next_height;
+            let mut failed = false;
+            match remote_node
+                .stream_chain_history(chain_id, next_height, stop)
+                .await
+            {
+                Ok(stream) => {
+                    let mut chunks = stream.ready_chunks(chunk_size);
+                    while let Some(results) = chunks.next().await {
+                        let mut certificates = Vec::with_capacity(results.len());
+                        for result in results {
+                            match result {
+                                Ok(certificate) => certificates.push(certificate),
+                                Err(error) => {
+                                    debug!(
+                                        remote_node = remote_node.address(),
+                                        %error,
+                                        "chain history stream interrupted",
+                                    );
+                                    failed = true;
+                                    break;
+                                }
+                            }
+                        }
+                        if !certificates.is_empty() {
+                            let Some(info) = self
+                                .process_certificates(
+                                    slice::from_ref(remote_node),
+                                    certificates,
+                                    until_block_time,
+                                    ProcessConfirmedBlockMode::Execute,
+                                )
+                                .await?
+                            else {
+                                return Ok(last_info);
+                            };
+                            next_height = info.next_block_height;
+                            last_info = info;
+                        }
+                        if failed {
+                            break;
+                        }
+                    }
+                }
+                Err(error) => {
+                    debug!(
+                        remote_node = remote_node.address(),
+                        %error,
+                        "failed to stream the chain history",
+                    );
+                    failed = true;
+                }
+            }
+            if next_height >= stop {
+                return Ok(last_info);
+            }
+            if next_height > start_height {
+                continue;
+            }
+            if failed {
+                break;
+            }
+            return Ok(last_info);
+        }
+
         // Download remaining certificates from the remote node using a pipelined
         // sliding window. A background task downloads up to `max_concurrent_batch_downloads`
         // batches concurrently and sends them through a channel for sequential processing.
@@ -2547,6 +2622,31 @@ impl<Env: Environment> Client<Env> {
         published_blobs: Vec<Blob>,
         policy: BundleExecutionPolicy,
     ) -> Result<(Block, ChainInfoResponse, HashSet<ChainId>), chain_client::Error> {
+        let (executed_block, response, _resource_tracker, never_reject_origins) = self
+            .stage_block_execution_with_resources(block, round, published_blobs, policy)
+            .await?;
+        let notification = Notification {
+            chain_id: executed_block.header.chain_id,
+            reason: Reason::BlockExecuted {
+                height: executed_block.header.height,
+                hash: executed_block.hash(),
+            },
+        };
+        self.notifier.notify(&[notification]);
+        Ok((executed_block, response, never_reject_origins))
+    }
+
+    /// Executes the block locally like [`Self::stage_block_execution`], without notifying
+    /// listeners, and also returns the resources the execution consumed.
+    #[instrument(level = "trace", skip(self, block))]
+    async fn stage_block_execution_with_resources(
+        &self,
+        block: ProposedBlock,
+        round: Option<u32>,
+        published_blobs: Vec<Blob>,
+        policy: BundleExecutionPolicy,
+    ) -> Result<(Block, ChainInfoResponse, ResourceTracker, HashSet<ChainId>), chain_client::Error>
+    {
         let mut events = EventSetDownloader::new(self);
         loop {
             let result = self
@@ -2570,25 +2670,14 @@ impl<Env: Environment> Client<Env> {
                 }
                 // All reported events were already downloaded; don't loop forever.
             }
-            if let Ok((_, executed_block, _, _, _)) = &result {
-                let hash = executed_block.hash();
-                let notification = Notification {
-                    chain_id: executed_block.header.chain_id,
-                    reason: Reason::BlockExecuted {
-                        height: executed_block.header.height,
-                        hash,
-                    },
-                };
-                self.notifier.notify(&[notification]);
-            }
-            let (
-                _modified_block,
+            let (_modified_block, executed_block, response, resource_tracker, never_reject_origins) =
+                result?;
+            return Ok((
                 executed_block,
                 response,
-                _resource_tracker,
+                resource_tracker,
                 never_reject_origins,
-            ) = result?;
-            return Ok((executed_block, response, never_reject_origins));
+            ));
         }
     }
 }
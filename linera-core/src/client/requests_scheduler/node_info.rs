@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use custom_debug_derive::Debug;
+use linera_base::data_types::Timestamp;
 
 use super::scoring::ScoringWeights;
 use crate::{environment::Environment, remote_node::RemoteNode};
@@ -37,6 +38,9 @@ pub(super) struct NodeInfo<Env: Environment> {
 
     /// Maximum expected latency in milliseconds for score normalization
     max_expected_latency_ms: f64,
+
+    /// Until when the node asked us not to send requests, if it rate-limited us
+    rate_limited_until: Option<Timestamp>,
 }
 
 impl<Env: Environment> NodeInfo<Env> {
@@ -56,6 +60,7 @@ impl<Env: Environment> NodeInfo<Env> {
             weights,
             alpha,
             max_expected_latency_ms,
+            rate_limited_until: None,
         }
     }
 
@@ -109,6 +114,18 @@ impl<Env: Environment> NodeInfo<Env> {
         self.total_requests += 1;
     }
 
+    /// Records that the node rate-limited us and should not be sent requests before `until`.
+    ///
+    /// This is not counted as a failure: the node is healthy, only busy.
+    pub(super) fn set_rate_limited_until(&mut self, until: Timestamp) {
+        self.rate_limited_until = Some(self.rate_limited_until.map_or(until, |t| t.max(until)));
+    }
+
+    /// Returns whether the node still rate-limits us at time `now`.
+    pub(super) fn is_rate_limited(&self, now: Timestamp) -> bool {
+        self.rate_limited_until.is_some_and(|until| now < until)
+    }
+
     /// Returns the total number of requests processed.
     pub(super) fn total_requests(&self) -> u64 {
         self.total_requests
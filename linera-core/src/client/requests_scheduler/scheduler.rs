@@ -7,7 +7,7 @@ use custom_debug_derive::Debug;
 use futures::stream::{FuturesUnordered, StreamExt};
 use linera_base::{
     crypto::ValidatorPublicKey,
-    data_types::{Blob, BlobContent, BlockHeight},
+    data_types::{Blob, BlobContent, BlockHeight, TimeDelta},
     identifiers::{BlobId, ChainId},
     time::Duration,
 };
@@ -68,6 +68,14 @@ pub(crate) mod metrics {
                 &["validator", "address"],
             );
 
+        /// Counter of requests rejected by the rate limiter of each validator
+        pub(super) static VALIDATOR_REQUEST_RATE_LIMITED: IntCounterVec =
+            register_int_counter_vec(
+                "requests_scheduler_request_rate_limited",
+                "Number of requests to each validator rejected by its rate limiter",
+                &["validator", "address"],
+            );
+
         /// Counter for requests that were resolved from the response cache.
         pub(super) static REQUEST_CACHE_DEDUPLICATION: IntCounter =
             register_int_counter(
@@ -524,12 +532,25 @@ impl<Env: Environment> RequestsScheduler<Env> {
         let result = operation.await;
 
         // Update metrics and release slot
-        let response_time_ms = clock.current_time().delta_since(start_time).as_micros() / 1000;
+        let now = clock.current_time();
+        let response_time_ms = now.delta_since(start_time).as_micros() / 1000;
         let is_success = result.is_ok();
+        let retry_after_ms = match &result {
+            Err(NodeError::RateLimited { retry_after_ms }) => Some(*retry_after_ms),
+            _ => None,
+        };
         {
             let mut nodes_guard = nodes.write().await;
             if let Some(info) = nodes_guard.get_mut(&public_key) {
-                info.update_metrics(is_success, response_time_ms);
+                if let Some(retry_after_ms) = retry_after_ms {
+                    // The node is overloaded rather than faulty: leave it alone for a while
+                    // instead of lowering its score.
+                    info.set_rate_limited_until(
+                        now.saturating_add(TimeDelta::from_millis(retry_after_ms)),
+                    );
+                } else {
+                    info.update_metrics(is_success, response_time_ms);
+                }
                 let score = info.calculate_score().await;
                 tracing::trace!(
                     node = %public_key,
@@ -559,6 +580,11 @@ impl<Env: Environment> RequestsScheduler<Env> {
                     .with_label_values(&[&validator_name, &address])
                     .inc();
             }
+            if retry_after_ms.is_some() {
+                metrics::VALIDATOR_REQUEST_RATE_LIMITED
+                    .with_label_values(&[&validator_name, &address])
+                    .inc();
+            }
         }
 
         result
@@ -787,18 +813,24 @@ impl<Env: Environment> RequestsScheduler<Env> {
 
     /// Returns all peers ordered by their score (highest first).
     ///
-    /// Only includes peers that can currently accept requests. Each peer is paired
-    /// with its calculated score based on latency, success rate, and availability.
+    /// Only includes peers that can currently accept requests, i.e. that are not rate-limiting
+    /// us, unless all of them are. Each peer is paired with its calculated score based on
+    /// latency, success rate, and availability.
     ///
     /// # Returns
     /// A vector of `(score, peer)` tuples sorted by score in descending order.
     /// Returns an empty vector if no peers can accept requests.
     async fn peers_by_score(&self) -> Vec<(f64, RemoteNode<Env::ValidatorNode>)> {
+        let now = self.clock.current_time();
         let nodes = self.nodes.read().await;
+        let all_rate_limited = nodes.values().all(|info| info.is_rate_limited(now));
 
         // Filter nodes that can accept requests and calculate their scores
         let mut scored_nodes = Vec::new();
         for info in nodes.values() {
+            if info.is_rate_limited(now) && !all_rate_limited {
+                continue;
+            }
             let score = info.calculate_score().await;
             scored_nodes.push((score, info.node.clone()));
         }
@@ -1425,4 +1457,66 @@ mod tests {
             "Retry should have reached the working peer (node 2)"
         );
     }
+
+    #[tokio::test]
+    async fn test_rate_limited_peer_is_avoided() {
+        use crate::test_utils::{MemoryStorageBuilder, TestBuilder};
+
+        let mut builder = TestBuilder::new(
+            MemoryStorageBuilder::default(),
+            2,
+            0,
+            InMemorySigner::new(None),
+        )
+        .await
+        .unwrap();
+        let nodes: Vec<_> = (0..2)
+            .map(|i| {
+                let node = builder.node(i);
+                let public_key = node.name();
+                RemoteNode { public_key, node }
+            })
+            .collect();
+        let manager: RequestsScheduler<TestEnvironment> = RequestsScheduler::with_config(
+            nodes.clone(),
+            ScoringWeights::default(),
+            0.1,
+            1000.0,
+            Duration::from_secs(60),
+            100,
+            Duration::from_millis(MAX_REQUEST_TTL_MS),
+            Duration::from_millis(STAGGERED_DELAY_MS),
+            TestClock::new(),
+        );
+        let peer_keys = |peers: Vec<(f64, RemoteNode<_>)>| {
+            peers
+                .into_iter()
+                .map(|(_, peer)| peer.public_key)
+                .collect::<Vec<_>>()
+        };
+
+        // The first node rejects a request, asking for a one-second pause.
+        let result: Result<(), NodeError> = RequestsScheduler::<TestEnvironment>::track_request(
+            manager.nodes.clone(),
+            nodes[0].clone(),
+            async {
+                Err(NodeError::RateLimited {
+                    retry_after_ms: 1000,
+                })
+            },
+            &manager.clock,
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(
+            peer_keys(manager.peers_by_score().await),
+            vec![nodes[1].public_key]
+        );
+
+        // It is back once the pause is over, and the rejection did not lower its score.
+        manager.clock.add(TimeDelta::from_millis(1000));
+        let peers = manager.peers_by_score().await;
+        assert_eq!(peers.len(), 2);
+        assert_eq!(peers[0].0, peers[1].0);
+    }
 }
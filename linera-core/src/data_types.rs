@@ -341,6 +341,17 @@ pub struct CertificatesByHeightRequest {
     pub heights: Vec<BlockHeight>,
 }
 
+/// Request for streaming the certificates of a chain over a range of heights.
+#[derive(Clone, Debug)]
+pub struct ChainHistoryRequest {
+    /// The chain whose certificates are requested.
+    pub chain_id: ChainId,
+    /// The height of the first requested certificate.
+    pub from_height: BlockHeight,
+    /// The height after the last requested certificate.
+    pub to_height: BlockHeight,
+}
+
 /// Wrapper for displaying a sorted slice of [`BlockHeight`] as compressed ranges.
 ///
 /// Contiguous heights are shown as `start..end` (inclusive), with gaps producing
@@ -10,7 +10,7 @@ use std::{
 use futures::{stream::FuturesUnordered, TryStreamExt as _};
 use linera_base::{
     crypto::{CryptoHash, ValidatorPublicKey},
-    data_types::{ArithmeticError, Blob, BlockHeight},
+    data_types::{ArithmeticError, Blob, BlockHeight, Timestamp},
     identifiers::{BlobId, ChainId, EventId, StreamId},
 };
 use linera_chain::{
@@ -18,7 +18,10 @@ use linera_chain::{
     types::{Block, ConfirmedBlockCertificate},
     ChainError, ChainExecutionContext, StreamCounts,
 };
-use linera_execution::{BlobState, ExecutionError, Query, QueryOutcome, ResourceTracker};
+use linera_execution::{
+    evm::trace::EvmTrace, BlobState, ExecutionError, Query, QueryOutcome, ResourceTracker,
+    WasmProfile,
+};
 use linera_storage::{Arc as CacheArc, Storage};
 use linera_views::ViewError;
 use thiserror::Error;
@@ -329,6 +332,37 @@ where
         Ok(result)
     }
 
+    /// Returns the traces of the EVM executions of a transaction in a confirmed block.
+    #[instrument(level = "trace", skip(self))]
+    pub async fn trace_evm_transaction(
+        &self,
+        chain_id: ChainId,
+        height: BlockHeight,
+        transaction_index: u32,
+    ) -> Result<Vec<EvmTrace>, LocalNodeError> {
+        Ok(self
+            .node
+            .state
+            .trace_evm_transaction(chain_id, height, transaction_index)
+            .await?)
+    }
+
+    /// Returns the profile of the Wasm contract executions of a transaction in a confirmed
+    /// block.
+    #[instrument(level = "trace", skip(self))]
+    pub async fn profile_wasm_transaction(
+        &self,
+        chain_id: ChainId,
+        height: BlockHeight,
+        transaction_index: u32,
+    ) -> Result<WasmProfile, LocalNodeError> {
+        Ok(self
+            .node
+            .state
+            .profile_wasm_transaction(chain_id, height, transaction_index)
+            .await?)
+    }
+
     /// Handles any pending local cross-chain requests.
     ///
     /// Does not initialize the sender chain's execution state, so it is safe to
@@ -455,6 +489,21 @@ where
         Ok(self.node.state.get_event_subscriptions(chain_id).await?)
     }
 
+    /// Gets the IDs of at most `limit` of the chain's scheduled operations that are due at the
+    /// given timestamp.
+    pub async fn get_due_scheduled_operations(
+        &self,
+        chain_id: ChainId,
+        timestamp: Timestamp,
+        limit: usize,
+    ) -> Result<Vec<u32>, LocalNodeError> {
+        Ok(self
+            .node
+            .state
+            .get_due_scheduled_operations(chain_id, timestamp, limit)
+            .await?)
+    }
+
     /// Gets a stream's [`StreamCounts`]: its next expected event index and its lowest readable
     /// index, read from a single chain state view so they are mutually consistent.
     pub async fn get_stream_indices(
@@ -43,6 +43,9 @@ pub type NotificationStream = BoxStream<'static, Notification>;
 /// A pinned [`Stream`] of blob contents returned by batch downloads.
 pub type BlobStream = BoxStream<'static, Result<BlobContent, NodeError>>;
 
+/// A pinned [`Stream`] of the certificates of a chain, in increasing height order.
+pub type CertificateStream = BoxStream<'static, Result<ConfirmedBlockCertificate, NodeError>>;
+
 /// Whether to wait for the delivery of outgoing cross-chain messages.
 #[derive(Debug, Default, Clone, Copy)]
 #[allow(missing_docs)]
@@ -173,6 +176,17 @@ pub trait ValidatorNode {
         heights: Vec<BlockHeight>,
     ) -> Result<Vec<ConfirmedBlockCertificate>, NodeError>;
 
+    /// Streams the certificates of a chain from `from_height` up to `to_height` (excluded), in
+    /// increasing height order. The stream ends early at the first height the validator does
+    /// not have. Requests are independent, so an interrupted stream is resumed by requesting
+    /// again from the next missing height.
+    async fn stream_chain_history(
+        &self,
+        chain_id: ChainId,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> Result<CertificateStream, NodeError>;
+
     /// Returns the hash of the `Certificate` that last used a blob.
     async fn blob_last_used_by(&self, blob_id: BlobId) -> Result<CryptoHash, NodeError>;
 
@@ -366,6 +380,9 @@ pub enum NodeError {
 
     #[error("No validators available to handle the request")]
     NoValidators,
+
+    #[error("The validator is rate-limiting this client; retry in {retry_after_ms} ms")]
+    RateLimited { retry_after_ms: u64 },
 }
 
 impl NodeError {
@@ -378,7 +395,7 @@ impl NodeError {
     pub fn is_expected(&self) -> bool {
         match self {
             // Expected: validators return these during normal operation and the client
-            // handles them automatically by supplying missing data and retrying.
+            // handles them automatically by supplying missing data and retrying, or by backing off.
             NodeError::BlobsNotFound(_)
             | NodeError::BlocksNotFound(_)
             | NodeError::EventsNotFound(_)
@@ -387,7 +404,8 @@ impl NodeError {
             | NodeError::UnexpectedBlockHeight { .. }
             | NodeError::InactiveChain(_)
             | NodeError::InvalidTimestamp { .. }
-            | NodeError::MissingCertificateValue => true,
+            | NodeError::MissingCertificateValue
+            | NodeError::RateLimited { .. } => true,
 
             // Unexpected: network issues, validator misbehavior, or internal problems.
             NodeError::CryptoError { .. }
@@ -4,7 +4,10 @@
 use std::collections::{HashSet, VecDeque};
 
 use custom_debug_derive::Debug;
-use futures::future::try_join_all;
+use futures::{
+    future::{self, try_join_all},
+    StreamExt as _,
+};
 use linera_base::{
     crypto::ValidatorPublicKey,
     data_types::{Blob, BlockHeight},
@@ -23,7 +26,7 @@ use tracing::{debug, info, instrument};
 
 use crate::{
     data_types::{ChainInfo, ChainInfoQuery, ChainInfoResponse},
-    node::{CrossChainMessageDelivery, NodeError, ValidatorNode},
+    node::{CertificateStream, CrossChainMessageDelivery, NodeError, ValidatorNode},
 };
 
 /// A validator node together with the validator's name.
@@ -282,6 +285,35 @@ impl<N: ValidatorNode> RemoteNode<N> {
         Ok(certificates)
     }
 
+    /// Streams the certificates of a chain from `from_height` up to `to_height` (excluded),
+    /// checking that they come in order.
+    #[instrument(level = "trace")]
+    pub async fn stream_chain_history(
+        &self,
+        chain_id: ChainId,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> Result<CertificateStream, NodeError> {
+        let stream = self
+            .node
+            .stream_chain_history(chain_id, from_height, to_height)
+            .await?;
+        let checked = stream.scan(from_height, move |expected_height, result| {
+            let result = result.and_then(|certificate| {
+                ensure!(
+                    certificate.inner().chain_id() == chain_id
+                        && certificate.inner().height() == *expected_height
+                        && *expected_height < to_height,
+                    NodeError::UnexpectedCertificateValue
+                );
+                *expected_height = expected_height.try_add_one()?;
+                Ok(certificate)
+            });
+            future::ready(Some(result))
+        });
+        Ok(Box::pin(checked))
+    }
+
     /// Checks that requesting these blobs when trying to handle this certificate is legitimate,
     /// i.e. that there are no duplicates and the blobs are actually required.
     pub fn check_blobs_not_found<C: Certified>(
@@ -13,7 +13,7 @@ use std::collections::{BTreeMap, BTreeSet};
 use assert_matches::assert_matches;
 use futures::StreamExt;
 use linera_base::{
-    crypto::{AccountSecretKey, CryptoHash, InMemorySigner},
+    crypto::{AccountSecretKey, CryptoHash, InMemorySigner, MultisigPolicy, Signer as _},
     data_types::*,
     identifiers::{Account, AccountOwner, ApplicationId, BlobId, BlobType, GenericApplicationId},
     ownership::{ChainOwnership, TimeoutConfig},
@@ -29,6 +29,12 @@ use linera_execution::{
     QueryOutcome, ResourceControlPolicy, SystemMessage, SystemQuery, SystemResponse,
 };
 use linera_storage::Storage;
+use linera_views::{
+    batch::Batch,
+    context::Context as _,
+    store::{ReadableKeyValueStore as _, WritableKeyValueStore as _},
+    views::View as _,
+};
 use rand::Rng;
 use test_case::test_case;
 use test_helpers::{
@@ -384,6 +390,84 @@ where
     Ok(())
 }
 
+/// A chain and an account owned by a 2-of-3 multi-signature owner can only be used once a
+/// co-signer's partial signature has been collected.
+#[test_case(MemoryStorageBuilder::default(); "memory")]
+#[cfg_attr(feature = "storage-service", test_case(ServiceStorageBuilder::new(); "storage_service"))]
+#[cfg_attr(feature = "rocksdb", test_case(RocksDbStorageBuilder::new().await; "rocks_db"))]
+#[cfg_attr(feature = "scylladb", test_case(ScyllaDbStorageBuilder::default(); "scylla_db"))]
+#[test_log::test(tokio::test)]
+async fn test_multisig_owner<B>(storage_builder: B) -> anyhow::Result<()>
+where
+    B: StorageBuilder,
+{
+    let signer = InMemorySigner::new(None);
+    let mut builder = TestBuilder::new(storage_builder, 4, 1, signer)
+        .await?
+        .with_policy(ResourceControlPolicy::only_fuel());
+    let mut sender = builder.add_root_chain(1, Amount::from_tokens(4)).await?;
+    let chain_id = sender.chain_id();
+
+    // One member key is held locally, the other two by a co-signer.
+    let local_key = builder.signer.generate_new();
+    let mut co_signer = InMemorySigner::new(None);
+    let co_signer_keys = [co_signer.generate_new(), co_signer.generate_new()];
+    let policy = MultisigPolicy::new(2, [local_key, co_signer_keys[0], co_signer_keys[1]])?;
+    let multisig_owner = builder.signer.add_multisig_policy(policy);
+
+    let multisig_account = Account::new(chain_id, multisig_owner);
+    sender
+        .transfer(
+            AccountOwner::CHAIN,
+            Amount::from_tokens(2),
+            multisig_account,
+        )
+        .await
+        .unwrap_ok_committed();
+    sender
+        .transfer_ownership(multisig_owner)
+        .await
+        .unwrap_ok_committed();
+    sender.set_preferred_owner(multisig_owner);
+
+    // A single local signature is not enough.
+    let recipient = Account::chain(chain_id);
+    assert_matches!(
+        sender
+            .transfer(multisig_owner, Amount::ONE, recipient)
+            .await,
+        Err(chain_client::Error::Signer(_))
+    );
+    let (owner, content) = sender
+        .pending_proposal_content()
+        .await?
+        .expect("the transfer should be pending");
+    assert_eq!(owner, multisig_owner);
+
+    // The co-signer signs the proposal content, and we collect the partial signature.
+    let value = CryptoHash::new(&content);
+    let partial_signature = co_signer
+        .sign(&AccountOwner::from(co_signer_keys[0]), &value)
+        .await?;
+    builder
+        .signer
+        .add_partial_signature(multisig_owner, value, partial_signature)?;
+    let certificate = sender
+        .process_pending_block()
+        .await
+        .unwrap_ok_committed()
+        .expect("the transfer should be committed");
+    assert_eq!(
+        certificate.block().header.authenticated_owner,
+        Some(multisig_owner)
+    );
+    assert_eq!(
+        sender.local_owner_balance(multisig_owner).await?,
+        Amount::ONE
+    );
+    Ok(())
+}
+
 #[test_case(MemoryStorageBuilder::default(); "memory")]
 #[cfg_attr(feature = "storage-service", test_case(ServiceStorageBuilder::new(); "storage_service"))]
 #[cfg_attr(feature = "rocksdb", test_case(RocksDbStorageBuilder::new().await; "rocks_db"))]
@@ -1706,6 +1790,50 @@ where
     Ok(())
 }
 
+#[test_case(MemoryStorageBuilder::default(); "memory")]
+#[cfg_attr(feature = "storage-service", test_case(ServiceStorageBuilder::new(); "storage_service"))]
+#[test_log::test(tokio::test)]
+async fn test_estimate_block<B>(storage_builder: B) -> anyhow::Result<()>
+where
+    B: StorageBuilder,
+{
+    let signer = InMemorySigner::new(None);
+    let mut policy = ResourceControlPolicy::only_fuel();
+    policy.operation = Amount::from_micros(1);
+    let mut builder = TestBuilder::new(storage_builder, 4, 1, signer)
+        .await?
+        .with_policy(policy);
+    let sender = builder.add_root_chain(1, Amount::from_tokens(3)).await?;
+    let burn = |amount| {
+        Operation::system(SystemOperation::Transfer {
+            owner: AccountOwner::CHAIN,
+            recipient: Account::burn_address(sender.chain_id()),
+            amount,
+        })
+    };
+
+    let resources = sender
+        .estimate_block(vec![burn(Amount::ONE), burn(Amount::ONE)], vec![])
+        .await?;
+    assert_eq!(resources.operations, 2);
+    assert_eq!(resources.fees, Amount::from_micros(2));
+
+    // Nothing was proposed or executed.
+    let info = sender.chain_info().await?;
+    assert_eq!(info.next_block_height, BlockHeight::ZERO);
+    assert_eq!(info.chain_balance, Amount::from_tokens(3));
+
+    // Errors report the index of the failing operation.
+    let obtained_error = sender
+        .estimate_block(
+            vec![burn(Amount::ONE), burn(Amount::from_tokens(4))],
+            vec![],
+        )
+        .await;
+    assert_insufficient_balance_during_operation(obtained_error, 1);
+    Ok(())
+}
+
 /// A chain whose initial balance went to an owner's account has no chain balance: only blocks
 /// that owner authenticates can pay for fees there.
 #[test_case(MemoryStorageBuilder::default(); "memory")]
@@ -4960,6 +5088,77 @@ where
     Ok(())
 }
 
+/// Verifies that re-executing a confirmed block to trace it starts from the chain's latest
+/// checkpoint, that blocks below the checkpoint can't be re-executed, and that the replay
+/// leaves the chain state untouched and deletes any scratch copy left behind by a crash.
+#[test_case(MemoryStorageBuilder::default(); "memory")]
+#[cfg_attr(feature = "rocksdb", test_case(RocksDbStorageBuilder::new().await; "rocks_db"))]
+#[test_log::test(tokio::test)]
+async fn test_reexecution_starts_from_latest_checkpoint<B>(storage_builder: B) -> anyhow::Result<()>
+where
+    B: StorageBuilder,
+{
+    let signer = InMemorySigner::new(None);
+    let mut builder = TestBuilder::new(storage_builder, 4, 1, signer).await?;
+    let chain = builder.add_root_chain(1, Amount::from_tokens(7)).await?;
+    let target = builder.add_root_chain(2, Amount::ZERO).await?;
+    let chain_id = chain.chain_id();
+
+    // Height 0: a burn. Height 1: the checkpoint. Height 2: a transfer.
+    chain
+        .burn(AccountOwner::CHAIN, Amount::ONE)
+        .await
+        .unwrap_ok_committed();
+    chain.checkpoint().await.unwrap().unwrap();
+    chain
+        .transfer_to_account(
+            AccountOwner::CHAIN,
+            Amount::ONE,
+            Account::chain(target.chain_id()),
+        )
+        .await
+        .unwrap_ok_committed();
+    let state_hash_before = chain.chain_info().await?.state_hash;
+
+    // A scratch copy left behind by an earlier process.
+    let context = chain
+        .client
+        .storage_client()
+        .load_chain(chain_id)
+        .await?
+        .context()
+        .clone();
+    let mut leftover_key = context.base_key().bytes.clone();
+    leftover_key.push(u8::MAX);
+    leftover_key.extend(7u64.to_be_bytes());
+    let mut batch = Batch::new();
+    batch.put_key_value_bytes(leftover_key.clone(), vec![1]);
+    context.store().write_batch(batch).await?;
+
+    let local_node = &chain.client.local_node;
+    assert_matches!(
+        local_node
+            .trace_evm_transaction(chain_id, BlockHeight::ZERO, 0)
+            .await,
+        Err(LocalNodeError::WorkerError(
+            WorkerError::ReexecutionBeforeCheckpoint {
+                checkpoint_height,
+                ..
+            }
+        )) if checkpoint_height == BlockHeight::from(1)
+    );
+    for height in [1, 2] {
+        let traces = local_node
+            .trace_evm_transaction(chain_id, BlockHeight::from(height), 0)
+            .await?;
+        assert!(traces.is_empty());
+    }
+
+    assert!(!context.store().contains_key(&leftover_key).await?);
+    assert_eq!(chain.chain_info().await?.state_hash, state_hash_before);
+    Ok(())
+}
+
 /// Regression test for #5664: when the chain advances (e.g. a notification or background
 /// sync commits another owner's block at our height) while a client is in the middle of
 /// `execute_block`, the staged pending proposal is cleared without committing ours. This
@@ -5664,3 +5863,183 @@ where
     );
     Ok(())
 }
+
+/// Tests that a light client follows chains across a committee change, with their balances and
+/// inboxes, without executing any block.
+#[test_case(MemoryStorageBuilder::default(); "memory")]
+#[cfg_attr(feature = "storage-service", test_case(ServiceStorageBuilder::new(); "storage_service"))]
+#[cfg_attr(feature = "rocksdb", test_case(RocksDbStorageBuilder::new().await; "rocks_db"))]
+#[cfg_attr(feature = "scylladb", test_case(ScyllaDbStorageBuilder::default(); "scylla_db"))]
+#[test_log::test(tokio::test)]
+async fn test_light_client<B>(storage_builder: B) -> anyhow::Result<()>
+where
+    B: StorageBuilder,
+{
+    let signer = InMemorySigner::new(None);
+    let mut builder = TestBuilder::new(storage_builder, 4, 0, signer).await?;
+    let admin = builder.add_root_chain(0, Amount::from_tokens(1000)).await?;
+    let sender = builder.add_root_chain(1, Amount::from_tokens(10)).await?;
+    let receiver = builder.add_root_chain(2, Amount::ZERO).await?;
+
+    // Move the sender to a new epoch, so that its latest blocks are signed by a committee that
+    // the light client must learn from the admin chain.
+    admin
+        .stage_new_committee(builder.initial_committee.clone())
+        .await
+        .unwrap();
+    sender.synchronize_from_validators().await?;
+    sender.process_inbox().await?;
+    let certificate = sender
+        .transfer_to_account(
+            AccountOwner::CHAIN,
+            Amount::from_tokens(3),
+            Account::chain(receiver.chain_id()),
+        )
+        .await
+        .unwrap_ok_committed();
+    assert_eq!(certificate.block().header.epoch, Epoch::from(1));
+
+    let mut light_client = crate::client::LightClient::new(
+        builder.make_node_provider(),
+        builder.admin_chain_id(),
+        builder.initial_committee.clone(),
+    )?;
+    light_client.track_owner(sender.chain_id(), AccountOwner::CHAIN);
+    light_client.track_owner(receiver.chain_id(), AccountOwner::CHAIN);
+
+    let sender_info = sender.chain_info().await?;
+    let state = light_client.synchronize_chain(sender.chain_id()).await?;
+    assert_eq!(state.next_block_height(), sender_info.next_block_height);
+    assert_eq!(state.tip_hash(), sender_info.block_hash);
+    assert_eq!(
+        state.balance(&AccountOwner::CHAIN),
+        Some(sender.local_balance().await?)
+    );
+    assert_eq!(light_client.latest_epoch(), Epoch::from(1));
+
+    // The transfer waits in the receiver's inbox until the receiver picks it up.
+    let state = light_client.synchronize_chain(receiver.chain_id()).await?;
+    assert_eq!(state.next_block_height(), BlockHeight::ZERO);
+    assert_eq!(state.balance(&AccountOwner::CHAIN), Some(Amount::ZERO));
+    let bundles = state.inbox().collect::<Vec<_>>();
+    assert_eq!(bundles.len(), 1);
+    assert_eq!(bundles[0].0, sender.chain_id());
+    assert_eq!(bundles[0].1.certificate_hash, certificate.hash());
+
+    receiver.synchronize_from_validators().await?;
+    receiver.process_inbox().await?;
+    let receiver_info = receiver.chain_info().await?;
+    let state = light_client.synchronize_chain(receiver.chain_id()).await?;
+    assert_eq!(state.next_block_height(), receiver_info.next_block_height);
+    assert_eq!(state.inbox().count(), 0);
+    assert_eq!(
+        state.balance(&AccountOwner::CHAIN),
+        Some(Amount::from_tokens(3))
+    );
+    Ok(())
+}
+
+/// Tests that a light client synchronizes from the other validators if the one reporting the
+/// highest block height serves no blocks.
+#[test_case(MemoryStorageBuilder::default(); "memory")]
+#[cfg_attr(feature = "storage-service", test_case(ServiceStorageBuilder::new(); "storage_service"))]
+#[cfg_attr(feature = "rocksdb", test_case(RocksDbStorageBuilder::new().await; "rocks_db"))]
+#[cfg_attr(feature = "scylladb", test_case(ScyllaDbStorageBuilder::default(); "scylla_db"))]
+#[test_log::test(tokio::test)]
+async fn test_light_client_lying_validator<B>(storage_builder: B) -> anyhow::Result<()>
+where
+    B: StorageBuilder,
+{
+    let signer = InMemorySigner::new(None);
+    let mut builder = TestBuilder::new(storage_builder, 4, 0, signer).await?;
+    let sender = builder.add_root_chain(1, Amount::from_tokens(10)).await?;
+    sender
+        .burn(AccountOwner::CHAIN, Amount::ONE)
+        .await
+        .unwrap_ok_committed();
+    builder.set_fault_type([0], FaultType::InflatedHeight);
+
+    let mut light_client = crate::client::LightClient::new(
+        builder.make_node_provider(),
+        builder.admin_chain_id(),
+        builder.initial_committee.clone(),
+    )?;
+    light_client.follow_chain(sender.chain_id());
+    let sender_info = sender.chain_info().await?;
+    let state = light_client.synchronize_chain(sender.chain_id()).await?;
+    assert_eq!(state.next_block_height(), sender_info.next_block_height);
+    assert_eq!(state.tip_hash(), sender_info.block_hash);
+    Ok(())
+}
+
+/// Tests that a light client only records blocks of a revoked epoch once a block in a trusted
+/// epoch extends them, and leaves bundles sent in a revoked epoch out of the inbox.
+#[test_case(MemoryStorageBuilder::default(); "memory")]
+#[cfg_attr(feature = "storage-service", test_case(ServiceStorageBuilder::new(); "storage_service"))]
+#[cfg_attr(feature = "rocksdb", test_case(RocksDbStorageBuilder::new().await; "rocks_db"))]
+#[cfg_attr(feature = "scylladb", test_case(ScyllaDbStorageBuilder::default(); "scylla_db"))]
+#[test_log::test(tokio::test)]
+async fn test_light_client_revoked_epoch<B>(storage_builder: B) -> anyhow::Result<()>
+where
+    B: StorageBuilder,
+{
+    let signer = InMemorySigner::new(None);
+    let mut builder = TestBuilder::new(storage_builder, 4, 0, signer).await?;
+    let admin = builder.add_root_chain(0, Amount::from_tokens(1000)).await?;
+    let sender = builder.add_root_chain(1, Amount::from_tokens(10)).await?;
+    let receiver = builder.add_root_chain(2, Amount::ZERO).await?;
+
+    // The sender's first two blocks are in epoch 0, which is revoked afterwards.
+    sender
+        .transfer_to_account(
+            AccountOwner::CHAIN,
+            Amount::ONE,
+            Account::chain(receiver.chain_id()),
+        )
+        .await
+        .unwrap_ok_committed();
+    admin
+        .stage_new_committee(builder.initial_committee.clone())
+        .await
+        .unwrap();
+    sender.synchronize_from_validators().await?;
+    sender.process_inbox().await?;
+    assert_eq!(sender.chain_info().await?.epoch, Epoch::from(1));
+    admin.revoke_epochs(Epoch::ZERO).await.unwrap();
+
+    let mut light_client = crate::client::LightClient::new(
+        builder.make_node_provider(),
+        builder.admin_chain_id(),
+        builder.initial_committee.clone(),
+    )?;
+    light_client.follow_chain(sender.chain_id());
+    light_client.follow_chain(receiver.chain_id());
+    let errors = light_client.synchronize_followed_chains().await;
+    assert!(errors.is_empty(), "{errors:?}");
+    assert!(light_client.is_epoch_revoked(Epoch::ZERO));
+    assert!(!light_client.is_epoch_revoked(Epoch::from(1)));
+    let state = light_client.chain_state(sender.chain_id()).unwrap();
+    assert_eq!(state.next_block_height(), BlockHeight::ZERO);
+    let state = light_client.chain_state(receiver.chain_id()).unwrap();
+    assert_eq!(state.inbox().count(), 0);
+
+    // A block in epoch 1 re-certifies the earlier ones.
+    let certificate = sender
+        .transfer_to_account(
+            AccountOwner::CHAIN,
+            Amount::ONE,
+            Account::chain(receiver.chain_id()),
+        )
+        .await
+        .unwrap_ok_committed();
+    assert_eq!(certificate.block().header.epoch, Epoch::from(1));
+    let sender_info = sender.chain_info().await?;
+    let state = light_client.synchronize_chain(sender.chain_id()).await?;
+    assert_eq!(state.next_block_height(), sender_info.next_block_height);
+    assert_eq!(state.tip_hash(), sender_info.block_hash);
+    let state = light_client.synchronize_chain(receiver.chain_id()).await?;
+    let bundles = state.inbox().collect::<Vec<_>>();
+    assert_eq!(bundles.len(), 1);
+    assert_eq!(bundles[0].1.certificate_hash, certificate.hash());
+    Ok(())
+}
@@ -0,0 +1,133 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! EVM specific worker tests.
+//!
+//! These tests only run if the `revm` feature flag is enabled.
+
+#![cfg(feature = "revm")]
+
+use assert_matches::assert_matches;
+use linera_base::{
+    crypto::AccountSecretKey,
+    data_types::{Amount, ApplicationDescription, Blob, BlockHeight, Bytecode},
+    identifiers::ModuleId,
+    vm::{EvmInstantiation, EvmOperation, VmRuntime},
+};
+use linera_chain::test::{make_child_block, make_first_block, BlockTestExt};
+use linera_execution::{system::SystemOperation, Operation};
+use linera_storage::Storage;
+
+use super::TestEnvironment;
+use crate::{
+    test_utils::{MemoryStorageBuilder, StorageBuilder},
+    worker::WorkerError,
+};
+
+/// The creation code of a contract whose runtime code is `PUSH1 0x2a PUSH1 0 SSTORE STOP`,
+/// i.e. that stores 42 in slot 0 whatever it is called with. It is hand-assembled so that
+/// the test doesn't depend on a Solidity compiler.
+const STORE_42_CREATION_CODE: &[u8] = &[
+    0x60, 0x06, // PUSH1 6: the size of the runtime code
+    0x60, 0x0c, // PUSH1 12: the offset of the runtime code
+    0x60, 0x00, // PUSH1 0
+    0x39, // CODECOPY
+    0x60, 0x06, // PUSH1 6
+    0x60, 0x00, // PUSH1 0
+    0xf3, // RETURN
+    0x60, 0x2a, // PUSH1 42
+    0x60, 0x00, // PUSH1 0
+    0x55, // SSTORE
+    0x00, // STOP
+];
+
+#[test_log::test(tokio::test)]
+async fn test_memory_trace_evm_transaction() -> anyhow::Result<()> {
+    run_test_trace_evm_transaction(MemoryStorageBuilder::default()).await
+}
+
+async fn run_test_trace_evm_transaction<B>(mut storage_builder: B) -> anyhow::Result<()>
+where
+    B: StorageBuilder,
+{
+    let owner = AccountSecretKey::generate().public().into();
+    let mut env = TestEnvironment::new(&mut storage_builder, false, false).await?;
+    let chain = env.add_root_chain(1, owner, Amount::ZERO).await;
+
+    let bytecode = Bytecode::new(STORE_42_CREATION_CODE.to_vec());
+    let evm_blob = Blob::new_evm_bytecode(bytecode.compress());
+    let module_id = ModuleId::new(evm_blob.id().hash, evm_blob.id().hash, VmRuntime::Evm);
+    let blobs = [evm_blob];
+    env.executing_worker().storage.write_blobs(&blobs).await?;
+    env.write_blobs(&blobs).await?;
+
+    // Publish the module, create the application and call it, in three blocks.
+    let publish_block = make_first_block(chain.id())
+        .with_timestamp(1)
+        .with_operation(SystemOperation::PublishModule { module_id });
+    let publish_certificate = env.execute_proposal(publish_block, blobs.to_vec()).await?;
+
+    let parameters = serde_json::to_vec(&Vec::<u8>::new())?;
+    let create_block = make_child_block(publish_certificate.value())
+        .with_timestamp(2)
+        .with_operation(SystemOperation::CreateApplication {
+            module_id,
+            parameters: parameters.clone(),
+            instantiation_argument: serde_json::to_vec(&EvmInstantiation::default())?,
+            required_application_ids: vec![],
+            upgrade_authority: None,
+        });
+    let create_certificate = env.execute_proposal(create_block, vec![]).await?;
+    let application_description = ApplicationDescription {
+        module_id,
+        creator_chain_id: chain.id(),
+        block_height: BlockHeight(1),
+        application_index: 0,
+        parameters,
+        required_application_ids: vec![],
+    };
+    let application_id = From::from(&application_description);
+
+    let call_block = make_child_block(create_certificate.value())
+        .with_timestamp(3)
+        .with_operation(Operation::User {
+            application_id,
+            bytes: EvmOperation::new(Amount::ZERO, vec![1, 2, 3, 4]).to_bytes()?,
+        });
+    env.execute_proposal(call_block, vec![]).await?;
+
+    // Replaying the chain traces the call, including the storage write.
+    let traces = env
+        .executing_worker()
+        .trace_evm_transaction(chain.id(), BlockHeight(2), 0)
+        .await?;
+    assert_eq!(traces.len(), 1);
+    let trace = &traces[0];
+    assert_eq!(trace.call_trace.kind, "CALL");
+    assert_eq!(trace.call_trace.error, None);
+    let ops = trace
+        .struct_logs
+        .iter()
+        .map(|log| log.op.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(ops, ["PUSH1", "PUSH1", "SSTORE", "STOP"]);
+    assert!(!trace.struct_logs_truncated);
+    assert_eq!(trace.storage_accesses.len(), 1);
+    assert_eq!(trace.storage_accesses[0].op, "SSTORE");
+    assert_eq!(trace.storage_accesses[0].value, format!("0x{:064x}", 42));
+
+    // The replay is bounded.
+    assert_matches!(
+        env.executing_worker()
+            .clone()
+            .with_max_reexecuted_blocks(2)
+            .trace_evm_transaction(chain.id(), BlockHeight(2), 0)
+            .await,
+        Err(WorkerError::ReexecutionTooLong {
+            block_count: 3,
+            limit: 2,
+            ..
+        })
+    );
+    Ok(())
+}
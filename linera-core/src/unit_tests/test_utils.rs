@@ -14,7 +14,7 @@ use async_trait::async_trait;
 use futures::{
     future::Either,
     lock::{Mutex, MutexGuard},
-    Future,
+    stream, Future, StreamExt as _,
 };
 use linera_base::{
     crypto::{
@@ -57,7 +57,7 @@ use crate::{
     data_types::*,
     environment::{TestSigner, TestWallet},
     node::{
-        CrossChainMessageDelivery, NodeError, NotificationStream, ValidatorNode,
+        CertificateStream, CrossChainMessageDelivery, NodeError, NotificationStream, ValidatorNode,
         ValidatorNodeProvider,
     },
     notifier::ChannelNotifier,
@@ -78,6 +78,8 @@ pub enum FaultType {
     DontSendConfirmVote,
     DontProcessValidated,
     DontSendValidateVote,
+    /// Claims a much higher block height than it has, and serves no certificates.
+    InflatedHeight,
 }
 
 /// A validator used for testing. "Faulty" validators ignore block proposals (but not
@@ -284,6 +286,38 @@ where
         .await
     }
 
+    async fn stream_chain_history(
+        &self,
+        chain_id: ChainId,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> Result<CertificateStream, NodeError> {
+        const BATCH_SIZE: u64 = 10;
+        let this = self.clone();
+        let batches = stream::unfold(Some(from_height), move |next_height| {
+            let this = this.clone();
+            async move {
+                let start = next_height.filter(|height| *height < to_height)?;
+                let end = to_height.0.min(start.0.saturating_add(BATCH_SIZE));
+                let heights = (start.0..end).map(BlockHeight).collect::<Vec<_>>();
+                let requested = heights.len();
+                match this
+                    .download_certificates_by_heights(chain_id, heights)
+                    .await
+                {
+                    Ok(certificates) => {
+                        let next_height =
+                            (certificates.len() == requested).then_some(BlockHeight(end));
+                        let items = certificates.into_iter().map(Ok).collect::<Vec<_>>();
+                        Some((stream::iter(items), next_height))
+                    }
+                    Err(error) => Some((stream::iter(vec![Err(error)]), None)),
+                }
+            }
+        });
+        Ok(Box::pin(batches.flatten()))
+    }
+
     async fn blob_last_used_by(&self, blob_id: BlobId) -> Result<CryptoHash, NodeError> {
         self.spawn_and_receive(move |validator, sender| {
             validator.do_blob_last_used_by(blob_id, sender)
@@ -401,7 +435,8 @@ where
             FaultType::DontSendValidateVote
             | FaultType::Honest
             | FaultType::DontSendConfirmVote
-            | FaultType::DontProcessValidated => {
+            | FaultType::DontProcessValidated
+            | FaultType::InflatedHeight => {
                 let (response_result, _actions) = self
                     .client
                     .lock()
@@ -461,7 +496,8 @@ where
             FaultType::Honest
             | FaultType::DontSendConfirmVote
             | FaultType::DontProcessValidated
-            | FaultType::DontSendValidateVote => {
+            | FaultType::DontSendValidateVote
+            | FaultType::InflatedHeight => {
                 let result = validator
                     .state
                     .fully_handle_certificate_with_notifications(certificate, &validator.notifier)
@@ -515,6 +551,18 @@ where
                 .handle_chain_info_query(query)
                 .await
                 .map_err(Into::into),
+            FaultType::InflatedHeight => validator
+                .state
+                .handle_chain_info_query(query)
+                .await
+                .map(|mut response| {
+                    response.info.next_block_height = BlockHeight(u64::MAX / 2);
+                    if let Some(secret_key) = validator.state.secret_key() {
+                        response.sign(secret_key);
+                    }
+                    response
+                })
+                .map_err(Into::into),
         };
         sender.send(result)
     }
@@ -654,6 +702,9 @@ where
         heights: Vec<BlockHeight>,
         sender: oneshot::Sender<Result<Vec<ConfirmedBlockCertificate>, NodeError>>,
     ) -> Result<(), Result<Vec<ConfirmedBlockCertificate>, NodeError>> {
+        if self.fault_type == FaultType::InflatedHeight {
+            return sender.send(Ok(Vec::new()));
+        }
         // First, use do_handle_chain_info_query to get the certificate hashes
         let (query_sender, query_receiver) = oneshot::channel();
         let query = ChainInfoQuery::new(chain_id).with_sent_certificate_hashes_by_heights(heights);
@@ -165,6 +165,7 @@ where
         parameters: parameters_bytes.clone(),
         instantiation_argument: initial_value_bytes.clone(),
         required_application_ids: vec![],
+        upgrade_authority: None,
     };
     let application_description = ApplicationDescription {
         module_id,
@@ -345,6 +346,7 @@ where
         parameters: counter_params.clone(),
         instantiation_argument: counter_init,
         required_application_ids: vec![],
+        upgrade_authority: None,
     };
     let counter_app_desc = ApplicationDescription {
         module_id: counter_module_id,
@@ -371,6 +373,7 @@ where
         parameters: meta_params.clone(),
         instantiation_argument: meta_init,
         required_application_ids: vec![counter_app_id],
+        upgrade_authority: None,
     };
     let meta_app_desc = ApplicationDescription {
         module_id: meta_module_id,
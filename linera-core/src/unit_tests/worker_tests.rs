@@ -26,6 +26,8 @@ macro_rules! assert_outcome_matches {
     }};
 }
 
+#[path = "./evm_worker_tests.rs"]
+mod evm;
 #[path = "./wasm_worker_tests.rs"]
 mod wasm;
 
@@ -2197,6 +2199,70 @@ where
     Ok(())
 }
 
+#[test_case(MemoryStorageBuilder::default(); "memory")]
+#[cfg_attr(feature = "rocksdb", test_case(RocksDbStorageBuilder::new().await; "rocks_db"))]
+#[cfg_attr(feature = "scylladb", test_case(ScyllaDbStorageBuilder::default(); "scylla_db"))]
+#[test_log::test(tokio::test)]
+async fn test_trace_evm_transaction_replays_confirmed_blocks<B>(
+    mut storage_builder: B,
+) -> anyhow::Result<()>
+where
+    B: StorageBuilder,
+{
+    let key_pair = AccountSecretKey::generate();
+    let owner = key_pair.public().into();
+    let mut env = TestEnvironment::new(&mut storage_builder, false, false).await?;
+    let chain_1_desc = env.add_root_chain(1, owner, Amount::ONE).await;
+    let chain_1 = chain_1_desc.id();
+
+    let certificate0 = env
+        .make_simple_transfer_certificate(
+            chain_1,
+            key_pair.public(),
+            chain_1,
+            Amount::ONE,
+            Vec::new(),
+            None,
+        )
+        .await;
+    let certificate1 = env
+        .make_simple_transfer_certificate(
+            chain_1,
+            key_pair.public(),
+            chain_1,
+            Amount::ONE,
+            Vec::new(),
+            Some(&certificate0),
+        )
+        .await;
+    for certificate in [certificate0, certificate1.clone()] {
+        env.executing_worker()
+            .fully_handle_certificate_with_notifications(certificate, &())
+            .await?;
+    }
+
+    // Both blocks are replayed without a mismatch, and there are no EVM executions.
+    let traces = env
+        .executing_worker()
+        .trace_evm_transaction(chain_1, BlockHeight(1), 0)
+        .await?;
+    assert!(traces.is_empty());
+
+    // Blocks that were not confirmed yet cannot be traced.
+    assert_matches!(
+        env.executing_worker()
+            .trace_evm_transaction(chain_1, BlockHeight(2), 0)
+            .await,
+        Err(WorkerError::BlockHashNotFound { height, .. }) if height == BlockHeight(2)
+    );
+
+    // The replay leaves the chain state untouched.
+    let chain = env.executing_worker().chain_state_view(chain_1).await?;
+    assert_eq!(chain.tip_state.get().next_block_height, BlockHeight(2));
+    assert_eq!(Some(certificate1.hash()), chain.tip_state.get().block_hash);
+    Ok(())
+}
+
 #[test_case(MemoryStorageBuilder::default(); "memory")]
 #[cfg_attr(feature = "rocksdb", test_case(RocksDbStorageBuilder::new().await; "rocks_db"))]
 #[cfg_attr(feature = "scylladb", test_case(ScyllaDbStorageBuilder::default(); "scylla_db"))]
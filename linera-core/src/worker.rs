@@ -35,7 +35,10 @@ use linera_chain::{
     },
     ChainError, ChainStateView, StreamCounts,
 };
-use linera_execution::{ExecutionError, ExecutionStateView, Query, QueryOutcome, ResourceTracker};
+use linera_execution::{
+    evm::trace::EvmTrace, ExecutionError, ExecutionStateView, Query, QueryOutcome, ResourceTracker,
+    WasmProfile,
+};
 use linera_storage::{Clock as _, Storage};
 use linera_views::{context::InactiveContext, ViewError};
 use serde::{Deserialize, Serialize};
@@ -65,8 +68,8 @@ impl<S: Storage> std::ops::Deref for ChainStateViewReadGuard<S> {
 pub(crate) use crate::chain_worker::EventSubscriptionsResult;
 use crate::{
     chain_worker::{
-        handle, state::ChainWorkerState, BlockExportHandle, BlockOutcome, ChainWorkerConfig,
-        CrossChainUpdateResult, DeliveryNotifier, ProcessConfirmedBlockMode,
+        handle, state::ChainWorkerState, BlockExportHandle, BlockOutcome, BlockReexecution,
+        ChainWorkerConfig, CrossChainUpdateResult, DeliveryNotifier, ProcessConfirmedBlockMode,
     },
     client::{ChainModes, ListeningMode},
     data_types::{ChainInfoQuery, ChainInfoResponse, CrossChainRequest},
@@ -411,6 +414,25 @@ pub enum WorkerError {
         height: BlockHeight,
         chain_id: ChainId,
     },
+    #[error(
+        "Block {height} of chain {chain_id} precedes the chain's latest checkpoint at height \
+        {checkpoint_height} and cannot be re-executed"
+    )]
+    ReexecutionBeforeCheckpoint {
+        chain_id: ChainId,
+        height: BlockHeight,
+        checkpoint_height: BlockHeight,
+    },
+    #[error(
+        "Re-executing block {height} of chain {chain_id} would replay {block_count} blocks, \
+        more than the limit of {limit}"
+    )]
+    ReexecutionTooLong {
+        chain_id: ChainId,
+        height: BlockHeight,
+        block_count: u64,
+        limit: u64,
+    },
     #[error("The block proposal is invalid: {0}")]
     InvalidBlockProposal(String),
     #[error("Blob was not required by any pending block")]
@@ -450,6 +472,8 @@ impl WorkerError {
             | WorkerError::InvalidBlockProposal(_)
             | WorkerError::UnexpectedBlob
             | WorkerError::TooManyPublishedBlobs(_)
+            | WorkerError::ReexecutionBeforeCheckpoint { .. }
+            | WorkerError::ReexecutionTooLong { .. }
             | WorkerError::ViewError(ViewError::NotFound(_)) => false,
             WorkerError::BcsError(_)
             | WorkerError::InvalidCrossChainRequest
@@ -760,6 +784,13 @@ where
         self
     }
 
+    /// Returns an instance with the given limit on the number of blocks replayed to
+    /// re-execute a confirmed block.
+    pub fn with_max_reexecuted_blocks(mut self, limit: u64) -> Self {
+        self.chain_worker_config.max_reexecuted_blocks = limit;
+        self
+    }
+
     /// Returns the worker's nickname.
     #[instrument(level = "trace", skip(self))]
     pub fn nickname(&self) -> &str {
@@ -1394,6 +1425,48 @@ where
         .await
     }
 
+    /// Returns the traces of the EVM executions of a transaction in a confirmed block,
+    /// by re-executing the chain up to that block without saving anything.
+    #[instrument(level = "trace", skip(self))]
+    pub async fn trace_evm_transaction(
+        &self,
+        chain_id: ChainId,
+        height: BlockHeight,
+        transaction_index: u32,
+    ) -> Result<Vec<EvmTrace>, WorkerError> {
+        let reexecution = self.prepare_reexecution(chain_id, height).await?;
+        reexecution.trace_evm_transaction(transaction_index).await
+    }
+
+    /// Returns the profile of the Wasm contract executions of a transaction in a confirmed
+    /// block, by re-executing the chain up to that block without saving anything.
+    #[instrument(level = "trace", skip(self))]
+    pub async fn profile_wasm_transaction(
+        &self,
+        chain_id: ChainId,
+        height: BlockHeight,
+        transaction_index: u32,
+    ) -> Result<WasmProfile, WorkerError> {
+        let reexecution = self.prepare_reexecution(chain_id, height).await?;
+        reexecution
+            .profile_wasm_transaction(transaction_index)
+            .await
+    }
+
+    /// Collects the blocks to replay to re-execute the given one. The chain worker's lock is
+    /// released before the replay starts.
+    async fn prepare_reexecution(
+        &self,
+        chain_id: ChainId,
+        height: BlockHeight,
+    ) -> Result<BlockReexecution<StorageClient>, WorkerError> {
+        let state = self.get_or_create_chain_worker(chain_id).await?;
+        let guard = handle::read_lock_initialized(&state).await?;
+        guard
+            .prepare_reexecution(height, self.chain_worker_config.max_reexecuted_blocks)
+            .await
+    }
+
     #[instrument(level = "trace", skip(self, chain_id, application_id), fields(
         nickname = %self.nickname(),
         chain_id = %chain_id,
@@ -2033,6 +2106,20 @@ where
         .await
     }
 
+    /// Gets the IDs of at most `limit` of the chain's scheduled operations that are due at the
+    /// given timestamp.
+    pub async fn get_due_scheduled_operations(
+        &self,
+        chain_id: ChainId,
+        timestamp: Timestamp,
+        limit: usize,
+    ) -> Result<Vec<u32>, WorkerError> {
+        self.chain_read(chain_id, |guard| async move {
+            guard.get_due_scheduled_operations(timestamp, limit).await
+        })
+        .await
+    }
+
     /// Gets a stream's [`StreamCounts`]: its next expected event index and its lowest readable
     /// index, read from a single chain state view so they are mutually consistent.
     pub async fn get_stream_indices(
@@ -2139,4 +2226,10 @@ where
             )
             .public()
     }
+
+    /// Gets the validator's [`ValidatorSecretKey`](linera_base::crypto::ValidatorSecretKey),
+    /// if it has one.
+    pub fn secret_key(&self) -> Option<&linera_base::crypto::ValidatorSecretKey> {
+        self.chain_worker_config.key_pair()
+    }
 }
@@ -177,6 +177,65 @@ library Linera {
         return ChainOwnership(super_owners, owners, entry.multi_leader_rounds, entry.open_multi_leader_rounds, timeoutconfig_from(entry.timeout_config));
     }
 
+    function timedelta_to(Linera.TimeDelta memory entry)
+        internal
+        pure
+        returns (LineraTypes.TimeDelta memory)
+    {
+        return LineraTypes.TimeDelta(entry.value);
+    }
+
+    function opt_timedelta_to(Linera.opt_TimeDelta memory entry)
+        internal
+        pure
+        returns (LineraTypes.opt_TimeDelta memory)
+    {
+        return LineraTypes.opt_TimeDelta(entry.has_value, LineraTypes.TimeDelta(entry.value));
+    }
+
+    function timeoutconfig_to(Linera.TimeoutConfig memory entry)
+        internal
+        pure
+        returns (LineraTypes.TimeoutConfig memory)
+    {
+        return LineraTypes.TimeoutConfig(opt_timedelta_to(entry.fast_round_duration),
+                                         timedelta_to(entry.base_timeout),
+                                         timedelta_to(entry.timeout_increment),
+                                         timedelta_to(entry.fallback_duration));
+    }
+
+    function accountownerweight_to(Linera.AccountOwnerWeight memory entry)
+        internal
+        pure
+        returns (LineraTypes.key_values_AccountOwner_uint64 memory)
+    {
+        return LineraTypes.key_values_AccountOwner_uint64(account_owner_to(entry.account_owner), entry.weight);
+    }
+
+    // The owners must be sorted by their BCS serialization, as required for maps.
+    // The resulting ownership has no designated first leader.
+    function chainownership_to(Linera.ChainOwnership memory entry)
+        internal
+        pure
+        returns (LineraTypes.ChainOwnership memory)
+    {
+        uint256 len1 = entry.super_owners.length;
+        LineraTypes.AccountOwner[] memory super_owners;
+        super_owners = new LineraTypes.AccountOwner[](len1);
+        for (uint256 i=0; i<len1; i++) {
+            super_owners[i] = account_owner_to(entry.super_owners[i]);
+        }
+        uint256 len2 = entry.owners.length;
+        LineraTypes.key_values_AccountOwner_uint64[] memory owners;
+        owners = new LineraTypes.key_values_AccountOwner_uint64[](len2);
+        for (uint256 i=0; i<len2; i++) {
+            owners[i] = accountownerweight_to(entry.owners[i]);
+        }
+        LineraTypes.AccountOwner memory no_owner;
+        LineraTypes.OptionAccountOwner memory first_leader = LineraTypes.OptionAccountOwner(LineraTypes.opt_AccountOwner(false, no_owner));
+        return LineraTypes.ChainOwnership(super_owners, owners, first_leader, entry.multi_leader_rounds, entry.open_multi_leader_rounds, timeoutconfig_to(entry.timeout_config));
+    }
+
     struct opt_uint32 {
         bool has_value;
         uint32 value;
@@ -613,6 +672,53 @@ library Linera {
         require(output.length == 0);
     }
 
+    function open_chain(Linera.ChainOwnership memory ownership, Linera.AccountOwner memory account, uint256 balance) internal returns (Linera.ChainId memory) {
+        address precompile = address(0x0b);
+        LineraTypes.ChainOwnership memory ownership1 = chainownership_to(ownership);
+        LineraTypes.AccountOwner memory account1 = account_owner_to(account);
+        LineraTypes.Amount memory balance1 = LineraTypes.Amount(bytes32(balance));
+        LineraTypes.ContractRuntimePrecompile_OpenChain memory open_chain_ = LineraTypes.ContractRuntimePrecompile_OpenChain(ownership1, account1, balance1);
+        LineraTypes.ContractRuntimePrecompile memory contract_ = LineraTypes.ContractRuntimePrecompile_case_open_chain(open_chain_);
+        LineraTypes.RuntimePrecompile memory input1 = LineraTypes.RuntimePrecompile_case_contract(contract_);
+        bytes memory input2 = LineraTypes.bcs_serialize_RuntimePrecompile(input1);
+        (bool success, bytes memory output1) = precompile.call(input2);
+        require(success);
+        LineraTypes.ChainId memory output2 = LineraTypes.bcs_deserialize_ChainId(output1);
+        return chain_id_from(output2);
+    }
+
+    function close_chain() internal {
+        address precompile = address(0x0b);
+        LineraTypes.ContractRuntimePrecompile memory contract_ = LineraTypes.ContractRuntimePrecompile_case_close_chain();
+        LineraTypes.RuntimePrecompile memory input1 = LineraTypes.RuntimePrecompile_case_contract(contract_);
+        bytes memory input2 = LineraTypes.bcs_serialize_RuntimePrecompile(input1);
+        (bool success, bytes memory output) = precompile.call(input2);
+        require(success);
+        require(output.length == 0);
+    }
+
+    function change_ownership(Linera.ChainOwnership memory ownership) internal {
+        address precompile = address(0x0b);
+        LineraTypes.ChainOwnership memory ownership1 = chainownership_to(ownership);
+        LineraTypes.ContractRuntimePrecompile memory contract_ = LineraTypes.ContractRuntimePrecompile_case_change_ownership(ownership1);
+        LineraTypes.RuntimePrecompile memory input1 = LineraTypes.RuntimePrecompile_case_contract(contract_);
+        bytes memory input2 = LineraTypes.bcs_serialize_RuntimePrecompile(input1);
+        (bool success, bytes memory output) = precompile.call(input2);
+        require(success);
+        require(output.length == 0);
+    }
+
+    function create_data_blob(bytes memory data) internal returns (bytes32) {
+        address precompile = address(0x0b);
+        LineraTypes.ContractRuntimePrecompile memory contract_ = LineraTypes.ContractRuntimePrecompile_case_create_data_blob(data);
+        LineraTypes.RuntimePrecompile memory input1 = LineraTypes.RuntimePrecompile_case_contract(contract_);
+        bytes memory input2 = LineraTypes.bcs_serialize_RuntimePrecompile(input1);
+        (bool success, bytes memory output1) = precompile.call(input2);
+        require(success);
+        LineraTypes.DataBlobHash memory output2 = LineraTypes.bcs_deserialize_DataBlobHash(output1);
+        return output2.value.value;
+    }
+
     // ServiceRuntime functions.
 
     function try_query_application(bytes32 universal_address, bytes memory argument) internal returns (bytes memory) {
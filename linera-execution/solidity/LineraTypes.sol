@@ -630,6 +630,13 @@ library LineraTypes {
         // choice=12 corresponds to Transfer
         ContractRuntimePrecompile_Transfer transfer_;
         // choice=13 corresponds to MessageOriginTimestamp
+        // choice=14 corresponds to OpenChain
+        ContractRuntimePrecompile_OpenChain open_chain;
+        // choice=15 corresponds to CloseChain
+        // choice=16 corresponds to ChangeOwnership
+        ChainOwnership change_ownership;
+        // choice=17 corresponds to CreateDataBlob
+        bytes create_data_blob;
     }
 
     function ContractRuntimePrecompile_case_authenticated_owner()
@@ -645,7 +652,10 @@ library LineraTypes {
         ContractRuntimePrecompile_UnsubscribeFromEvents memory unsubscribe_from_events;
         ContractRuntimePrecompile_QueryService memory query_service;
         ContractRuntimePrecompile_Transfer memory transfer_;
-        return ContractRuntimePrecompile(uint8(0), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_);
+        ContractRuntimePrecompile_OpenChain memory open_chain;
+        ChainOwnership memory change_ownership;
+        bytes memory create_data_blob;
+        return ContractRuntimePrecompile(uint8(0), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_, open_chain, change_ownership, create_data_blob);
     }
 
     function ContractRuntimePrecompile_case_message_origin_chain_id()
@@ -661,7 +671,10 @@ library LineraTypes {
         ContractRuntimePrecompile_UnsubscribeFromEvents memory unsubscribe_from_events;
         ContractRuntimePrecompile_QueryService memory query_service;
         ContractRuntimePrecompile_Transfer memory transfer_;
-        return ContractRuntimePrecompile(uint8(1), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_);
+        ContractRuntimePrecompile_OpenChain memory open_chain;
+        ChainOwnership memory change_ownership;
+        bytes memory create_data_blob;
+        return ContractRuntimePrecompile(uint8(1), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_, open_chain, change_ownership, create_data_blob);
     }
 
     function ContractRuntimePrecompile_case_message_is_bouncing()
@@ -677,7 +690,10 @@ library LineraTypes {
         ContractRuntimePrecompile_UnsubscribeFromEvents memory unsubscribe_from_events;
         ContractRuntimePrecompile_QueryService memory query_service;
         ContractRuntimePrecompile_Transfer memory transfer_;
-        return ContractRuntimePrecompile(uint8(2), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_);
+        ContractRuntimePrecompile_OpenChain memory open_chain;
+        ChainOwnership memory change_ownership;
+        bytes memory create_data_blob;
+        return ContractRuntimePrecompile(uint8(2), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_, open_chain, change_ownership, create_data_blob);
     }
 
     function ContractRuntimePrecompile_case_authenticated_caller_id()
@@ -693,7 +709,10 @@ library LineraTypes {
         ContractRuntimePrecompile_UnsubscribeFromEvents memory unsubscribe_from_events;
         ContractRuntimePrecompile_QueryService memory query_service;
         ContractRuntimePrecompile_Transfer memory transfer_;
-        return ContractRuntimePrecompile(uint8(3), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_);
+        ContractRuntimePrecompile_OpenChain memory open_chain;
+        ChainOwnership memory change_ownership;
+        bytes memory create_data_blob;
+        return ContractRuntimePrecompile(uint8(3), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_, open_chain, change_ownership, create_data_blob);
     }
 
     function ContractRuntimePrecompile_case_send_message(ContractRuntimePrecompile_SendMessage memory send_message)
@@ -708,7 +727,10 @@ library LineraTypes {
         ContractRuntimePrecompile_UnsubscribeFromEvents memory unsubscribe_from_events;
         ContractRuntimePrecompile_QueryService memory query_service;
         ContractRuntimePrecompile_Transfer memory transfer_;
-        return ContractRuntimePrecompile(uint8(4), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_);
+        ContractRuntimePrecompile_OpenChain memory open_chain;
+        ChainOwnership memory change_ownership;
+        bytes memory create_data_blob;
+        return ContractRuntimePrecompile(uint8(4), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_, open_chain, change_ownership, create_data_blob);
     }
 
     function ContractRuntimePrecompile_case_try_call_application(ContractRuntimePrecompile_TryCallApplication memory try_call_application)
@@ -723,7 +745,10 @@ library LineraTypes {
         ContractRuntimePrecompile_UnsubscribeFromEvents memory unsubscribe_from_events;
         ContractRuntimePrecompile_QueryService memory query_service;
         ContractRuntimePrecompile_Transfer memory transfer_;
-        return ContractRuntimePrecompile(uint8(5), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_);
+        ContractRuntimePrecompile_OpenChain memory open_chain;
+        ChainOwnership memory change_ownership;
+        bytes memory create_data_blob;
+        return ContractRuntimePrecompile(uint8(5), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_, open_chain, change_ownership, create_data_blob);
     }
 
     function ContractRuntimePrecompile_case_emit(ContractRuntimePrecompile_Emit memory emit_)
@@ -738,7 +763,10 @@ library LineraTypes {
         ContractRuntimePrecompile_UnsubscribeFromEvents memory unsubscribe_from_events;
         ContractRuntimePrecompile_QueryService memory query_service;
         ContractRuntimePrecompile_Transfer memory transfer_;
-        return ContractRuntimePrecompile(uint8(6), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_);
+        ContractRuntimePrecompile_OpenChain memory open_chain;
+        ChainOwnership memory change_ownership;
+        bytes memory create_data_blob;
+        return ContractRuntimePrecompile(uint8(6), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_, open_chain, change_ownership, create_data_blob);
     }
 
     function ContractRuntimePrecompile_case_read_event(ContractRuntimePrecompile_ReadEvent memory read_event)
@@ -753,7 +781,10 @@ library LineraTypes {
         ContractRuntimePrecompile_UnsubscribeFromEvents memory unsubscribe_from_events;
         ContractRuntimePrecompile_QueryService memory query_service;
         ContractRuntimePrecompile_Transfer memory transfer_;
-        return ContractRuntimePrecompile(uint8(7), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_);
+        ContractRuntimePrecompile_OpenChain memory open_chain;
+        ChainOwnership memory change_ownership;
+        bytes memory create_data_blob;
+        return ContractRuntimePrecompile(uint8(7), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_, open_chain, change_ownership, create_data_blob);
     }
 
     function ContractRuntimePrecompile_case_subscribe_to_events(ContractRuntimePrecompile_SubscribeToEvents memory subscribe_to_events)
@@ -768,7 +799,10 @@ library LineraTypes {
         ContractRuntimePrecompile_UnsubscribeFromEvents memory unsubscribe_from_events;
         ContractRuntimePrecompile_QueryService memory query_service;
         ContractRuntimePrecompile_Transfer memory transfer_;
-        return ContractRuntimePrecompile(uint8(8), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_);
+        ContractRuntimePrecompile_OpenChain memory open_chain;
+        ChainOwnership memory change_ownership;
+        bytes memory create_data_blob;
+        return ContractRuntimePrecompile(uint8(8), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_, open_chain, change_ownership, create_data_blob);
     }
 
     function ContractRuntimePrecompile_case_unsubscribe_from_events(ContractRuntimePrecompile_UnsubscribeFromEvents memory unsubscribe_from_events)
@@ -783,7 +817,10 @@ library LineraTypes {
         ContractRuntimePrecompile_SubscribeToEvents memory subscribe_to_events;
         ContractRuntimePrecompile_QueryService memory query_service;
         ContractRuntimePrecompile_Transfer memory transfer_;
-        return ContractRuntimePrecompile(uint8(9), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_);
+        ContractRuntimePrecompile_OpenChain memory open_chain;
+        ChainOwnership memory change_ownership;
+        bytes memory create_data_blob;
+        return ContractRuntimePrecompile(uint8(9), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_, open_chain, change_ownership, create_data_blob);
     }
 
     function ContractRuntimePrecompile_case_query_service(ContractRuntimePrecompile_QueryService memory query_service)
@@ -798,7 +835,10 @@ library LineraTypes {
         ContractRuntimePrecompile_SubscribeToEvents memory subscribe_to_events;
         ContractRuntimePrecompile_UnsubscribeFromEvents memory unsubscribe_from_events;
         ContractRuntimePrecompile_Transfer memory transfer_;
-        return ContractRuntimePrecompile(uint8(10), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_);
+        ContractRuntimePrecompile_OpenChain memory open_chain;
+        ChainOwnership memory change_ownership;
+        bytes memory create_data_blob;
+        return ContractRuntimePrecompile(uint8(10), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_, open_chain, change_ownership, create_data_blob);
     }
 
     function ContractRuntimePrecompile_case_validation_round()
@@ -814,7 +854,10 @@ library LineraTypes {
         ContractRuntimePrecompile_UnsubscribeFromEvents memory unsubscribe_from_events;
         ContractRuntimePrecompile_QueryService memory query_service;
         ContractRuntimePrecompile_Transfer memory transfer_;
-        return ContractRuntimePrecompile(uint8(11), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_);
+        ContractRuntimePrecompile_OpenChain memory open_chain;
+        ChainOwnership memory change_ownership;
+        bytes memory create_data_blob;
+        return ContractRuntimePrecompile(uint8(11), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_, open_chain, change_ownership, create_data_blob);
     }
 
     function ContractRuntimePrecompile_case_transfer(ContractRuntimePrecompile_Transfer memory transfer_)
@@ -829,7 +872,10 @@ library LineraTypes {
         ContractRuntimePrecompile_SubscribeToEvents memory subscribe_to_events;
         ContractRuntimePrecompile_UnsubscribeFromEvents memory unsubscribe_from_events;
         ContractRuntimePrecompile_QueryService memory query_service;
-        return ContractRuntimePrecompile(uint8(12), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_);
+        ContractRuntimePrecompile_OpenChain memory open_chain;
+        ChainOwnership memory change_ownership;
+        bytes memory create_data_blob;
+        return ContractRuntimePrecompile(uint8(12), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_, open_chain, change_ownership, create_data_blob);
     }
 
     function ContractRuntimePrecompile_case_message_origin_timestamp()
@@ -845,7 +891,83 @@ library LineraTypes {
         ContractRuntimePrecompile_UnsubscribeFromEvents memory unsubscribe_from_events;
         ContractRuntimePrecompile_QueryService memory query_service;
         ContractRuntimePrecompile_Transfer memory transfer_;
-        return ContractRuntimePrecompile(uint8(13), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_);
+        ContractRuntimePrecompile_OpenChain memory open_chain;
+        ChainOwnership memory change_ownership;
+        bytes memory create_data_blob;
+        return ContractRuntimePrecompile(uint8(13), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_, open_chain, change_ownership, create_data_blob);
+    }
+
+    function ContractRuntimePrecompile_case_open_chain(ContractRuntimePrecompile_OpenChain memory open_chain)
+        internal
+        pure
+        returns (ContractRuntimePrecompile memory)
+    {
+        ContractRuntimePrecompile_SendMessage memory send_message;
+        ContractRuntimePrecompile_TryCallApplication memory try_call_application;
+        ContractRuntimePrecompile_Emit memory emit_;
+        ContractRuntimePrecompile_ReadEvent memory read_event;
+        ContractRuntimePrecompile_SubscribeToEvents memory subscribe_to_events;
+        ContractRuntimePrecompile_UnsubscribeFromEvents memory unsubscribe_from_events;
+        ContractRuntimePrecompile_QueryService memory query_service;
+        ContractRuntimePrecompile_Transfer memory transfer_;
+        ChainOwnership memory change_ownership;
+        bytes memory create_data_blob;
+        return ContractRuntimePrecompile(uint8(14), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_, open_chain, change_ownership, create_data_blob);
+    }
+
+    function ContractRuntimePrecompile_case_close_chain()
+        internal
+        pure
+        returns (ContractRuntimePrecompile memory)
+    {
+        ContractRuntimePrecompile_SendMessage memory send_message;
+        ContractRuntimePrecompile_TryCallApplication memory try_call_application;
+        ContractRuntimePrecompile_Emit memory emit_;
+        ContractRuntimePrecompile_ReadEvent memory read_event;
+        ContractRuntimePrecompile_SubscribeToEvents memory subscribe_to_events;
+        ContractRuntimePrecompile_UnsubscribeFromEvents memory unsubscribe_from_events;
+        ContractRuntimePrecompile_QueryService memory query_service;
+        ContractRuntimePrecompile_Transfer memory transfer_;
+        ContractRuntimePrecompile_OpenChain memory open_chain;
+        ChainOwnership memory change_ownership;
+        bytes memory create_data_blob;
+        return ContractRuntimePrecompile(uint8(15), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_, open_chain, change_ownership, create_data_blob);
+    }
+
+    function ContractRuntimePrecompile_case_change_ownership(ChainOwnership memory change_ownership)
+        internal
+        pure
+        returns (ContractRuntimePrecompile memory)
+    {
+        ContractRuntimePrecompile_SendMessage memory send_message;
+        ContractRuntimePrecompile_TryCallApplication memory try_call_application;
+        ContractRuntimePrecompile_Emit memory emit_;
+        ContractRuntimePrecompile_ReadEvent memory read_event;
+        ContractRuntimePrecompile_SubscribeToEvents memory subscribe_to_events;
+        ContractRuntimePrecompile_UnsubscribeFromEvents memory unsubscribe_from_events;
+        ContractRuntimePrecompile_QueryService memory query_service;
+        ContractRuntimePrecompile_Transfer memory transfer_;
+        ContractRuntimePrecompile_OpenChain memory open_chain;
+        bytes memory create_data_blob;
+        return ContractRuntimePrecompile(uint8(16), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_, open_chain, change_ownership, create_data_blob);
+    }
+
+    function ContractRuntimePrecompile_case_create_data_blob(bytes memory create_data_blob)
+        internal
+        pure
+        returns (ContractRuntimePrecompile memory)
+    {
+        ContractRuntimePrecompile_SendMessage memory send_message;
+        ContractRuntimePrecompile_TryCallApplication memory try_call_application;
+        ContractRuntimePrecompile_Emit memory emit_;
+        ContractRuntimePrecompile_ReadEvent memory read_event;
+        ContractRuntimePrecompile_SubscribeToEvents memory subscribe_to_events;
+        ContractRuntimePrecompile_UnsubscribeFromEvents memory unsubscribe_from_events;
+        ContractRuntimePrecompile_QueryService memory query_service;
+        ContractRuntimePrecompile_Transfer memory transfer_;
+        ContractRuntimePrecompile_OpenChain memory open_chain;
+        ChainOwnership memory change_ownership;
+        return ContractRuntimePrecompile(uint8(17), send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_, open_chain, change_ownership, create_data_blob);
     }
 
     function bcs_serialize_ContractRuntimePrecompile(ContractRuntimePrecompile memory input)
@@ -877,6 +999,15 @@ library LineraTypes {
         if (input.choice == 12) {
             return abi.encodePacked(input.choice, bcs_serialize_ContractRuntimePrecompile_Transfer(input.transfer_));
         }
+        if (input.choice == 14) {
+            return abi.encodePacked(input.choice, bcs_serialize_ContractRuntimePrecompile_OpenChain(input.open_chain));
+        }
+        if (input.choice == 16) {
+            return abi.encodePacked(input.choice, bcs_serialize_ChainOwnership(input.change_ownership));
+        }
+        if (input.choice == 17) {
+            return abi.encodePacked(input.choice, bcs_serialize_bytes(input.create_data_blob));
+        }
         return abi.encodePacked(input.choice);
     }
 
@@ -920,8 +1051,20 @@ library LineraTypes {
         if (choice == 12) {
             (new_pos, transfer_) = bcs_deserialize_offset_ContractRuntimePrecompile_Transfer(new_pos, input);
         }
-        require(choice < 14);
-        return (new_pos, ContractRuntimePrecompile(choice, send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_));
+        ContractRuntimePrecompile_OpenChain memory open_chain;
+        if (choice == 14) {
+            (new_pos, open_chain) = bcs_deserialize_offset_ContractRuntimePrecompile_OpenChain(new_pos, input);
+        }
+        ChainOwnership memory change_ownership;
+        if (choice == 16) {
+            (new_pos, change_ownership) = bcs_deserialize_offset_ChainOwnership(new_pos, input);
+        }
+        bytes memory create_data_blob;
+        if (choice == 17) {
+            (new_pos, create_data_blob) = bcs_deserialize_offset_bytes(new_pos, input);
+        }
+        require(choice < 18);
+        return (new_pos, ContractRuntimePrecompile(choice, send_message, try_call_application, emit_, read_event, subscribe_to_events, unsubscribe_from_events, query_service, transfer_, open_chain, change_ownership, create_data_blob));
     }
 
     function bcs_deserialize_ContractRuntimePrecompile(bytes memory input)
@@ -975,6 +1118,49 @@ library LineraTypes {
         return value;
     }
 
+    struct ContractRuntimePrecompile_OpenChain {
+        ChainOwnership ownership;
+        AccountOwner account;
+        Amount balance;
+    }
+
+    function bcs_serialize_ContractRuntimePrecompile_OpenChain(ContractRuntimePrecompile_OpenChain memory input)
+        internal
+        pure
+        returns (bytes memory)
+    {
+        bytes memory result = bcs_serialize_ChainOwnership(input.ownership);
+        result = abi.encodePacked(result, bcs_serialize_AccountOwner(input.account));
+        return abi.encodePacked(result, bcs_serialize_Amount(input.balance));
+    }
+
+    function bcs_deserialize_offset_ContractRuntimePrecompile_OpenChain(uint256 pos, bytes memory input)
+        internal
+        pure
+        returns (uint256, ContractRuntimePrecompile_OpenChain memory)
+    {
+        uint256 new_pos;
+        ChainOwnership memory ownership;
+        (new_pos, ownership) = bcs_deserialize_offset_ChainOwnership(pos, input);
+        AccountOwner memory account;
+        (new_pos, account) = bcs_deserialize_offset_AccountOwner(new_pos, input);
+        Amount memory balance;
+        (new_pos, balance) = bcs_deserialize_offset_Amount(new_pos, input);
+        return (new_pos, ContractRuntimePrecompile_OpenChain(ownership, account, balance));
+    }
+
+    function bcs_deserialize_ContractRuntimePrecompile_OpenChain(bytes memory input)
+        internal
+        pure
+        returns (ContractRuntimePrecompile_OpenChain memory)
+    {
+        uint256 new_pos;
+        ContractRuntimePrecompile_OpenChain memory value;
+        (new_pos, value) = bcs_deserialize_offset_ContractRuntimePrecompile_OpenChain(0, input);
+        require(new_pos == input.length, "incomplete deserialization");
+        return value;
+    }
+
     struct ContractRuntimePrecompile_QueryService {
         ApplicationId application_id;
         bytes query;
@@ -49,6 +49,8 @@ alloy_sol_types::sol! {
     function process_streams(InternalStreamUpdate[] internal_streams);
 
     function summarize_events(InternalStreamUpdate[] internal_streams);
+
+    function migrate();
 }
 
 fn crypto_hash_to_internal_crypto_hash(hash: CryptoHash) -> B256 {
@@ -161,6 +163,10 @@ pub(crate) const PROCESS_STREAMS_SELECTOR: &[u8] =
 pub(crate) const SUMMARIZE_EVENTS_SELECTOR: &[u8] =
     &<summarize_eventsCall as alloy_sol_types::SolCall>::SELECTOR;
 
+/// This is the selector of `migrate`, which is called by the system once after the
+/// application was upgraded and never from a submitted operation.
+pub(crate) const MIGRATE_SELECTOR: &[u8] = &<migrateCall as alloy_sol_types::SolCall>::SELECTOR;
+
 /// This is the selector of `instantiate` that should be called
 /// only when creating a new instance of a shared contract
 pub(crate) const INSTANTIATE_SELECTOR: &[u8] = &[156, 163, 60, 158];
@@ -178,6 +184,10 @@ pub(crate) fn forbid_execute_operation_origin(vec: &[u8]) -> Result<(), EvmExecu
         vec != SUMMARIZE_EVENTS_SELECTOR,
         EvmExecutionError::IllegalOperationCall("function summarize_events".to_string(),)
     );
+    ensure!(
+        vec != MIGRATE_SELECTOR,
+        EvmExecutionError::IllegalOperationCall("function migrate".to_string(),)
+    );
     ensure!(
         vec != INSTANTIATE_SELECTOR,
         EvmExecutionError::IllegalOperationCall("function instantiate".to_string(),)
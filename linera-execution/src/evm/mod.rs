@@ -5,19 +5,29 @@
 //!
 //! We are using Revm for implementing it.
 
-#![cfg(with_revm)]
-
+#[cfg(with_revm)]
 mod data_types;
+#[cfg(with_revm)]
 mod database;
+#[cfg(with_revm)]
 pub mod inputs;
+#[cfg(with_revm)]
 pub mod revm;
+pub mod trace;
+#[cfg(with_revm)]
+mod tracer;
 
+#[cfg(with_revm)]
 use linera_base::data_types::AmountConversionError;
+#[cfg(with_revm)]
 use revm_context::result::HaltReason;
+#[cfg(with_revm)]
 use revm_primitives::{Address, U256};
+#[cfg(with_revm)]
 use thiserror::Error;
 
 /// An error that occurred while executing an EVM application.
+#[cfg(with_revm)]
 #[derive(Debug, Error)]
 #[allow(missing_docs)]
 pub enum EvmExecutionError {
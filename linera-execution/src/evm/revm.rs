@@ -33,6 +33,7 @@ use core::ops::Range;
 use std::{
     collections::BTreeSet,
     convert::TryFrom,
+    mem,
     ops::DerefMut,
     sync::{Arc, Mutex},
 };
@@ -42,11 +43,12 @@ use linera_base::prometheus_util::MeasureLatency as _;
 use linera_base::{
     crypto::CryptoHash,
     data_types::{
-        Amount, ApplicationDescription, ArithmeticError, Bytecode, Resources, SendMessageRequest,
-        StreamUpdate,
+        Amount, ApplicationDescription, ApplicationPermissions, ArithmeticError, Bytecode,
+        Resources, SendMessageRequest, StreamUpdate,
     },
     ensure,
     identifiers::{self, Account, AccountOwner, ApplicationId, ChainId, ModuleId, StreamName},
+    ownership::ChainOwnership,
     vm::{EvmInstantiation, EvmOperation, EvmQuery, VmRuntime},
 };
 use revm::{primitives::Bytes, InspectCommitEvm, InspectEvm, Inspector};
@@ -60,7 +62,7 @@ use revm_handler::{
 };
 use revm_interpreter::{
     CallInput, CallInputs, CallOutcome, CallValue, CreateInputs, CreateOutcome, CreateScheme, Gas,
-    InputsImpl, InstructionResult, InterpreterResult,
+    InputsImpl, InstructionResult, Interpreter, InterpreterResult,
 };
 use revm_primitives::{hardfork::SpecId, Address, Log, TxKind, U256};
 use revm_state::EvmState;
@@ -74,9 +76,11 @@ use crate::{
             ensure_message_length, ensure_selector_presence, forbid_execute_operation_origin,
             get_revm_execute_message_bytes, get_revm_instantiation_bytes,
             get_revm_process_streams_bytes, get_revm_summarize_events_bytes, has_selector,
-            EXECUTE_MESSAGE_SELECTOR, FAUCET_ADDRESS, INSTANTIATE_SELECTOR, PRECOMPILE_ADDRESS,
-            PROCESS_STREAMS_SELECTOR, SERVICE_ADDRESS, SUMMARIZE_EVENTS_SELECTOR, ZERO_ADDRESS,
+            EXECUTE_MESSAGE_SELECTOR, FAUCET_ADDRESS, INSTANTIATE_SELECTOR, MIGRATE_SELECTOR,
+            PRECOMPILE_ADDRESS, PROCESS_STREAMS_SELECTOR, SERVICE_ADDRESS,
+            SUMMARIZE_EVENTS_SELECTOR, ZERO_ADDRESS,
         },
+        tracer::EvmTracer,
     },
     BaseRuntime, ContractRuntime, ContractSyncRuntimeHandle, DataBlobHash, EvmExecutionError,
     EvmRuntime, ExecutionError, ServiceRuntime, ServiceSyncRuntimeHandle, UserContract,
@@ -355,6 +359,18 @@ enum ContractRuntimePrecompile {
     },
     /// Calling `message_origin_timestamp` of `ContractRuntime`
     MessageOriginTimestamp,
+    /// Calling `open_chain` of `ContractRuntime`, with the default application permissions
+    OpenChain {
+        ownership: ChainOwnership,
+        account: AccountOwner,
+        balance: AmountU256,
+    },
+    /// Calling `close_chain` of `ContractRuntime`
+    CloseChain,
+    /// Calling `change_ownership` of `ContractRuntime`
+    ChangeOwnership(ChainOwnership),
+    /// Calling `create_data_blob` of `ContractRuntime`
+    CreateDataBlob(Vec<u8>),
 }
 
 /// Some functionalities from the ServiceRuntime not in BaseRuntime
@@ -685,6 +701,33 @@ impl<'a> ContractPrecompile {
                 }
                 Ok(vec![])
             }
+            ContractRuntimePrecompile::OpenChain {
+                ownership,
+                account,
+                balance,
+            } => {
+                let balance = Amount::try_from(balance.0).map_err(EvmExecutionError::from)?;
+                let application_permissions = ApplicationPermissions::default();
+                let mut runtime = context.db().0.lock_runtime();
+                let chain_id =
+                    runtime.open_chain(ownership, application_permissions, account, balance)?;
+                Ok(bcs::to_bytes(&chain_id)?)
+            }
+            ContractRuntimePrecompile::CloseChain => {
+                let mut runtime = context.db().0.lock_runtime();
+                runtime.close_chain()?;
+                Ok(vec![])
+            }
+            ContractRuntimePrecompile::ChangeOwnership(ownership) => {
+                let mut runtime = context.db().0.lock_runtime();
+                runtime.change_ownership(ownership)?;
+                Ok(vec![])
+            }
+            ContractRuntimePrecompile::CreateDataBlob(bytes) => {
+                let mut runtime = context.db().0.lock_runtime();
+                let hash = runtime.create_data_blob(bytes)?;
+                Ok(bcs::to_bytes(&hash)?)
+            }
         }
     }
 
@@ -854,6 +897,8 @@ struct CallInterceptorContract<Runtime> {
     contract_address: Address,
     precompile_addresses: BTreeSet<Address>,
     error: Arc<Mutex<Option<U256>>>,
+    // The tracer recording the execution, if it is traced.
+    tracer: Option<Arc<Mutex<EvmTracer>>>,
 }
 
 impl<Runtime> Clone for CallInterceptorContract<Runtime> {
@@ -863,6 +908,7 @@ impl<Runtime> Clone for CallInterceptorContract<Runtime> {
             contract_address: self.contract_address,
             precompile_addresses: self.precompile_addresses.clone(),
             error: self.error.clone(),
+            tracer: self.tracer.clone(),
         }
     }
 }
@@ -875,18 +921,73 @@ impl<'a, Runtime: ContractRuntime> Inspector<ContractCtx<'a, Runtime>>
         context: &mut ContractCtx<'a, Runtime>,
         inputs: &mut CreateInputs,
     ) -> Option<CreateOutcome> {
+        if let Some(tracer) = &self.tracer {
+            tracer.lock().unwrap().create(inputs);
+        }
         let result = self.create_or_fail(context, inputs);
         map_result_create_outcome(&self.db.inner, result)
     }
 
+    fn create_end(
+        &mut self,
+        _context: &mut ContractCtx<'a, Runtime>,
+        _inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+    ) {
+        if let Some(tracer) = &self.tracer {
+            tracer
+                .lock()
+                .unwrap()
+                .create_end(&outcome.result, outcome.address);
+        }
+    }
+
     fn call(
         &mut self,
         context: &mut ContractCtx<'a, Runtime>,
         inputs: &mut CallInputs,
     ) -> Option<CallOutcome> {
+        if let Some(tracer) = &self.tracer {
+            let input = get_argument(context, &inputs.input);
+            tracer.lock().unwrap().call(inputs, &input);
+        }
         let result = self.call_or_fail(context, inputs);
         map_result_call_outcome(&self.db.inner, result)
     }
+
+    fn call_end(
+        &mut self,
+        _context: &mut ContractCtx<'a, Runtime>,
+        _inputs: &CallInputs,
+        outcome: &mut CallOutcome,
+    ) {
+        if let Some(tracer) = &self.tracer {
+            tracer.lock().unwrap().call_end(&outcome.result);
+        }
+    }
+
+    fn step(&mut self, interpreter: &mut Interpreter, _context: &mut ContractCtx<'a, Runtime>) {
+        if let Some(tracer) = &self.tracer {
+            tracer.lock().unwrap().step(interpreter);
+        }
+    }
+
+    fn step_end(&mut self, interpreter: &mut Interpreter, _context: &mut ContractCtx<'a, Runtime>) {
+        if let Some(tracer) = &self.tracer {
+            tracer.lock().unwrap().step_end(interpreter);
+        }
+    }
+
+    fn log(
+        &mut self,
+        _interpreter: &mut Interpreter,
+        _context: &mut ContractCtx<'a, Runtime>,
+        log: Log,
+    ) {
+        if let Some(tracer) = &self.tracer {
+            tracer.lock().unwrap().log(&log);
+        }
+    }
 }
 
 impl<Runtime: ContractRuntime> CallInterceptorContract<Runtime> {
@@ -1354,6 +1455,19 @@ where
         self.execute_no_return_operation(operation, "summarize_events", value, caller)
     }
 
+    fn migrate(&mut self) -> Result<(), ExecutionError> {
+        // Contracts without a `migrate` function have nothing to migrate.
+        if !has_selector(&self.module, MIGRATE_SELECTOR) {
+            return Ok(());
+        }
+        self.db.inner.set_contract_address()?;
+        let operation = MIGRATE_SELECTOR.to_vec();
+        // For migrate, authenticated_owner and authenticated_called_id are None.
+        let caller = Address::ZERO;
+        let value = U256::ZERO;
+        self.execute_no_return_operation(operation, "migrate", value, caller)
+    }
+
     fn finalize(&mut self) -> Result<(), ExecutionError> {
         Ok(())
     }
@@ -1490,19 +1604,22 @@ where
             EvmTxKind::Create => TxKind::Create,
             EvmTxKind::Call => TxKind::Call(contract_address),
         };
-        let inspector = CallInterceptorContract {
-            db: self.db.clone(),
-            contract_address,
-            precompile_addresses: precompile_addresses(),
-            error: Arc::new(Mutex::new(None)),
-        };
         let block_env = self.db.get_block_env()?;
-        let (max_size_evm_contract, gas_limit) = {
+        let (max_size_evm_contract, gas_limit, is_tracing) = {
             let mut runtime = self.db.lock_runtime();
+            let is_tracing = runtime.is_tracing_evm()?;
             let gas_limit = runtime.remaining_fuel(VmRuntime::Evm)?;
             let max_size_evm_contract =
                 usize::try_from(runtime.maximum_blob_size()?).unwrap_or(usize::MAX);
-            (max_size_evm_contract, gas_limit)
+            (max_size_evm_contract, gas_limit, is_tracing)
+        };
+        let tracer = is_tracing.then(|| Arc::new(Mutex::new(EvmTracer::default())));
+        let inspector = CallInterceptorContract {
+            db: self.db.clone(),
+            contract_address,
+            precompile_addresses: precompile_addresses(),
+            error: Arc::new(Mutex::new(None)),
+            tracer: tracer.clone(),
         };
         let nonce = self.db.get_nonce(&caller)?;
         let result = {
@@ -1542,7 +1659,14 @@ where
                 let error = format!("{error:?}");
                 EvmExecutionError::TransactCommitError(error)
             })
-        }?;
+        };
+        // The trace is recorded even if the execution failed, since this is when it is
+        // most useful.
+        if let Some(tracer) = tracer {
+            let trace = mem::take(&mut *tracer.lock().unwrap()).finish();
+            self.db.lock_runtime().record_evm_trace(trace)?;
+        }
+        let result = result?;
         self.db.inner.process_any_error()?;
         self.db.commit_changes()?;
         Ok(process_execution_result(result)?)
@@ -0,0 +1,143 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Traces of EVM executions, in the JSON format used by geth's `debug_traceTransaction`.
+//!
+//! The types are available even without the `revm` feature so that traces can be passed
+//! around and served by components that do not execute EVM code themselves. Numbers and
+//! byte strings are hex-encoded with a `0x` prefix, as geth does.
+
+use std::{
+    mem,
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// The maximum number of executed opcodes recorded in the `struct_logs` of an [`EvmTrace`].
+/// The opcodes executed after that are not recorded, which bounds the memory used by the
+/// trace of a long-running transaction.
+pub const MAX_STRUCT_LOGS: usize = 100_000;
+
+/// Collects the traces of the EVM executions of a transaction, in the order in which they
+/// complete.
+///
+/// Clones share the same traces, so that they can still be read after a failed execution.
+#[derive(Clone, Debug, Default)]
+pub struct EvmTraceCollector(Arc<Mutex<Vec<EvmTrace>>>);
+
+impl EvmTraceCollector {
+    /// Records the trace of an EVM execution.
+    pub fn record(&self, trace: EvmTrace) {
+        self.0.lock().unwrap().push(trace);
+    }
+
+    /// Takes the traces recorded so far.
+    pub fn take(&self) -> Vec<EvmTrace> {
+        mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+/// The trace of one EVM transaction run by an EVM application.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvmTrace {
+    /// The tree of calls, in the format of geth's `callTracer`.
+    pub call_trace: EvmCallFrame,
+    /// The executed opcodes, in the format of geth's default struct logger.
+    pub struct_logs: Vec<EvmStructLog>,
+    /// Whether `struct_logs` stops early because more than [`MAX_STRUCT_LOGS`] opcodes were
+    /// executed.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub struct_logs_truncated: bool,
+    /// The storage reads and writes, in execution order.
+    pub storage_accesses: Vec<EvmStorageAccess>,
+}
+
+/// A call, or contract creation, in the call tree of an [`EvmTrace`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvmCallFrame {
+    /// The kind of call: `CALL`, `STATICCALL`, `DELEGATECALL`, `CALLCODE`, `CREATE` or
+    /// `CREATE2`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// The caller's address.
+    pub from: String,
+    /// The callee's address, or the address of the created contract.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+    /// The transferred value, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// The gas made available to the call.
+    pub gas: String,
+    /// The gas used by the call.
+    pub gas_used: String,
+    /// The call data, or the init code of a contract creation.
+    pub input: String,
+    /// The returned data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// Why the call failed, if it did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The decoded revert reason, if the call reverted with one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+    /// The nested calls, in execution order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<EvmCallFrame>,
+    /// The logs emitted by this call itself.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub logs: Vec<EvmCallLog>,
+}
+
+/// A log emitted during an [`EvmCallFrame`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvmCallLog {
+    /// The address of the contract emitting the log.
+    pub address: String,
+    /// The log topics.
+    pub topics: Vec<String>,
+    /// The log data.
+    pub data: String,
+    /// The number of nested calls made by the frame before the log was emitted.
+    pub position: String,
+}
+
+/// One executed opcode.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvmStructLog {
+    /// The program counter.
+    pub pc: u64,
+    /// The name of the opcode.
+    pub op: String,
+    /// The gas remaining before the opcode.
+    pub gas: u64,
+    /// The gas consumed by the opcode.
+    pub gas_cost: u64,
+    /// The call depth, starting at 1.
+    pub depth: u64,
+    /// Why the opcode failed, if it did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A read or write of a storage slot.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvmStorageAccess {
+    /// The opcode accessing the storage: `SLOAD` or `SSTORE`.
+    pub op: String,
+    /// The address of the contract whose storage is accessed.
+    pub address: String,
+    /// The storage slot.
+    pub slot: String,
+    /// The value read or written.
+    pub value: String,
+    /// The call depth, starting at 1.
+    pub depth: u64,
+}
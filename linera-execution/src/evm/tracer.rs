@@ -0,0 +1,234 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Recording of [`EvmTrace`]s from the hooks of a Revm inspector.
+
+use std::fmt::LowerHex;
+
+use alloy_primitives::hex;
+use revm::bytecode::opcode::{OpCode, SLOAD, SSTORE};
+use revm_interpreter::{
+    interpreter_types::{InputsTr as _, Jumps as _, LoopControl as _},
+    CallInputs, CallScheme, CreateInputs, CreateScheme, Interpreter, InterpreterResult,
+};
+use revm_primitives::{Address, Log, U256};
+
+use crate::evm::trace::{
+    EvmCallFrame, EvmCallLog, EvmStorageAccess, EvmStructLog, EvmTrace, MAX_STRUCT_LOGS,
+};
+
+/// Builds the [`EvmTrace`] of a transaction, one inspector hook at a time.
+#[derive(Default)]
+pub(crate) struct EvmTracer {
+    /// The frames that were entered and not exited yet, innermost last.
+    frames: Vec<EvmCallFrame>,
+    /// The outermost frame, once it was exited.
+    root: Option<EvmCallFrame>,
+    struct_logs: Vec<EvmStructLog>,
+    struct_logs_truncated: bool,
+    storage_accesses: Vec<EvmStorageAccess>,
+    /// The opcode being executed, between `step` and `step_end`.
+    current_step: Option<CurrentStep>,
+}
+
+struct CurrentStep {
+    log: EvmStructLog,
+    /// The contract and slot read by an `SLOAD`, whose value is only on the stack once
+    /// the opcode is executed.
+    loaded_slot: Option<(Address, U256)>,
+}
+
+impl EvmTracer {
+    /// Enters the frame of a call.
+    pub(crate) fn call(&mut self, inputs: &CallInputs, input: &[u8]) {
+        let kind = match inputs.scheme {
+            CallScheme::Call => "CALL",
+            CallScheme::CallCode => "CALLCODE",
+            CallScheme::DelegateCall => "DELEGATECALL",
+            CallScheme::StaticCall => "STATICCALL",
+            CallScheme::ExtCall => "EXTCALL",
+            CallScheme::ExtStaticCall => "EXTSTATICCALL",
+            CallScheme::ExtDelegateCall => "EXTDELEGATECALL",
+        };
+        self.frames.push(EvmCallFrame {
+            kind: kind.to_string(),
+            from: hex::encode_prefixed(inputs.caller),
+            to: Some(hex::encode_prefixed(inputs.bytecode_address)),
+            value: inputs.value.transfer().map(quantity),
+            gas: quantity(inputs.gas_limit),
+            input: hex::encode_prefixed(input),
+            ..EvmCallFrame::default()
+        });
+    }
+
+    /// Exits the frame of a call.
+    pub(crate) fn call_end(&mut self, result: &InterpreterResult) {
+        self.exit_frame(result, None);
+    }
+
+    /// Enters the frame of a contract creation.
+    pub(crate) fn create(&mut self, inputs: &CreateInputs) {
+        let kind = match inputs.scheme {
+            CreateScheme::Create2 { .. } => "CREATE2",
+            CreateScheme::Create | CreateScheme::Custom { .. } => "CREATE",
+        };
+        self.frames.push(EvmCallFrame {
+            kind: kind.to_string(),
+            from: hex::encode_prefixed(inputs.caller),
+            value: Some(quantity(inputs.value)),
+            gas: quantity(inputs.gas_limit),
+            input: hex::encode_prefixed(&inputs.init_code),
+            ..EvmCallFrame::default()
+        });
+    }
+
+    /// Exits the frame of a contract creation.
+    pub(crate) fn create_end(&mut self, result: &InterpreterResult, address: Option<Address>) {
+        self.exit_frame(result, address);
+    }
+
+    /// Records a log emitted by the current frame.
+    pub(crate) fn log(&mut self, log: &Log) {
+        let Some(frame) = self.frames.last_mut() else {
+            return;
+        };
+        let position = quantity(frame.calls.len());
+        frame.logs.push(EvmCallLog {
+            address: hex::encode_prefixed(log.address),
+            topics: log.topics().iter().map(hex::encode_prefixed).collect(),
+            data: hex::encode_prefixed(&log.data.data),
+            position,
+        });
+    }
+
+    /// Starts recording the opcode about to be executed.
+    pub(crate) fn step(&mut self, interpreter: &Interpreter) {
+        let opcode = interpreter.bytecode.opcode();
+        let depth = self.depth();
+        let mut loaded_slot = None;
+        if opcode == SLOAD {
+            if let Ok(slot) = interpreter.stack.peek(0) {
+                loaded_slot = Some((interpreter.input.target_address(), slot));
+            }
+        } else if opcode == SSTORE {
+            if let (Ok(slot), Ok(value)) = (interpreter.stack.peek(0), interpreter.stack.peek(1)) {
+                self.storage_accesses.push(EvmStorageAccess {
+                    op: "SSTORE".to_string(),
+                    address: hex::encode_prefixed(interpreter.input.target_address()),
+                    slot: word(slot),
+                    value: word(value),
+                    depth,
+                });
+            }
+        }
+        self.current_step = Some(CurrentStep {
+            log: EvmStructLog {
+                pc: interpreter.bytecode.pc() as u64,
+                op: OpCode::name_by_op(opcode).to_string(),
+                gas: interpreter.control.gas().remaining(),
+                gas_cost: 0,
+                depth,
+                error: None,
+            },
+            loaded_slot,
+        });
+    }
+
+    /// Finishes recording the opcode that was just executed.
+    pub(crate) fn step_end(&mut self, interpreter: &Interpreter) {
+        let Some(CurrentStep {
+            mut log,
+            loaded_slot,
+        }) = self.current_step.take()
+        else {
+            return;
+        };
+        log.gas_cost = log
+            .gas
+            .saturating_sub(interpreter.control.gas().remaining());
+        let result = interpreter.control.instruction_result();
+        if result.is_error() {
+            log.error = Some(format!("{result:?}"));
+        }
+        if let (Some((address, slot)), Ok(value)) = (loaded_slot, interpreter.stack.peek(0)) {
+            if !result.is_error() {
+                self.storage_accesses.push(EvmStorageAccess {
+                    op: "SLOAD".to_string(),
+                    address: hex::encode_prefixed(address),
+                    slot: word(slot),
+                    value: word(value),
+                    depth: log.depth,
+                });
+            }
+        }
+        if self.struct_logs.len() < MAX_STRUCT_LOGS {
+            self.struct_logs.push(log);
+        } else {
+            self.struct_logs_truncated = true;
+        }
+    }
+
+    /// Returns the trace. Frames that were never exited, because the execution was
+    /// aborted, are closed without a result.
+    pub(crate) fn finish(mut self) -> EvmTrace {
+        while let Some(frame) = self.frames.pop() {
+            self.attach(frame);
+        }
+        EvmTrace {
+            call_trace: self.root.unwrap_or_default(),
+            struct_logs: self.struct_logs,
+            struct_logs_truncated: self.struct_logs_truncated,
+            storage_accesses: self.storage_accesses,
+        }
+    }
+
+    fn depth(&self) -> u64 {
+        self.frames.len() as u64
+    }
+
+    fn exit_frame(&mut self, result: &InterpreterResult, address: Option<Address>) {
+        let Some(mut frame) = self.frames.pop() else {
+            return;
+        };
+        if let Some(address) = address {
+            frame.to = Some(hex::encode_prefixed(address));
+        }
+        frame.gas_used = quantity(result.gas.spent());
+        if !result.output.is_empty() {
+            frame.output = Some(hex::encode_prefixed(&result.output));
+        }
+        if !result.result.is_ok() {
+            if result.result.is_revert() {
+                frame.error = Some("execution reverted".to_string());
+                frame.revert_reason = alloy_sol_types::decode_revert_reason(&result.output);
+            } else {
+                frame.error = Some(format!("{:?}", result.result));
+            }
+            // As in geth, the logs of a failed call are discarded with its state changes.
+            clear_logs(&mut frame);
+        }
+        self.attach(frame);
+    }
+
+    fn attach(&mut self, frame: EvmCallFrame) {
+        match self.frames.last_mut() {
+            Some(parent) => parent.calls.push(frame),
+            None => self.root = Some(frame),
+        }
+    }
+}
+
+fn clear_logs(frame: &mut EvmCallFrame) {
+    frame.logs.clear();
+    frame.calls.iter_mut().for_each(clear_logs);
+}
+
+/// Formats a number as a hex quantity, e.g. `0x1f`.
+fn quantity(value: impl LowerHex) -> String {
+    format!("{value:#x}")
+}
+
+/// Formats a 256-bit word as 32 hex-encoded bytes.
+fn word(value: U256) -> String {
+    hex::encode_prefixed(value.to_be_bytes::<32>())
+}
@@ -17,15 +17,17 @@ use linera_base::{
     time::Instant,
 };
 use linera_views::{
+    common::HasherOutput,
     context::Context,
     historical_hash_wrapper::HistoricallyHashableView,
     key_value_store_view::KeyValueStoreView,
     map_view::MapView,
+    proof::{ProofError, ProofPosition, ViewProof},
     reentrant_collection_view::ReentrantCollectionView,
-    views::{ClonableView, ReplaceContext, View},
+    views::{ClonableView, HashableView as _, ReplaceContext, View},
     ViewError,
 };
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 #[cfg(with_testing)]
 use {
     crate::{
@@ -39,7 +41,7 @@ use super::{execution_state_actor::ExecutionRequest, runtime::ServiceRuntimeRequ
 use crate::{
     execution_state_actor::ExecutionStateActor,
     resources::ResourceController,
-    system::{SystemExecutionStateView, SystemMessage},
+    system::{is_application_upgrade_stream, SystemExecutionStateView, SystemMessage},
     transaction_tracker::PreparedCheckpoint,
     ApplicationDescription, ApplicationId, ExecutionError, ExecutionRuntimeContext, JsVec, Message,
     MessageContext, OperationContext, OutgoingMessage, ProcessStreamsContext, Query, QueryContext,
@@ -76,6 +78,79 @@ impl<C: Context, C2: Context> ReplaceContext<C2> for ExecutionStateViewInner<C>
     }
 }
 
+/// The hashes that the state hash of a block commits to.
+#[derive(Serialize, Deserialize)]
+struct ExecutionStateHashes {
+    /// The hash of the history of the changes of the execution state.
+    historical_hash: [u8; 32],
+    /// The content hash of the system state.
+    system_hash: [u8; 32],
+}
+
+impl BcsHashable<'_> for ExecutionStateHashes {}
+
+impl ExecutionStateHashes {
+    fn new(historical_hash: HasherOutput, system_hash: HasherOutput) -> Self {
+        ExecutionStateHashes {
+            historical_hash: historical_hash.into(),
+            system_hash: system_hash.into(),
+        }
+    }
+
+    fn crypto_hash(&self) -> CryptoHash {
+        CryptoHash::new(self)
+    }
+}
+
+/// A proof that some data is part of the system state of a chain, that can be checked
+/// against the state hash of one of its blocks.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionStateProof {
+    /// The hash of the history of the changes of the execution state.
+    pub historical_hash: HasherOutput,
+    /// The content hash of the system state.
+    pub system_hash: HasherOutput,
+    /// The proof for the content of the system state.
+    pub proof: ViewProof,
+}
+
+impl ExecutionStateProof {
+    /// Checks that the proof is for a register of values of type `T` in the system state
+    /// with the given hash, at the given positions, outermost first, and returns its value.
+    pub fn verify_value<T: DeserializeOwned>(
+        &self,
+        state_hash: &CryptoHash,
+        positions: &[ProofPosition],
+    ) -> Result<T, ProofError> {
+        self.check_state_hash(state_hash)?;
+        self.proof.verify_value(&self.system_hash, positions)
+    }
+
+    /// Checks that the proof is for a map from `I` to `V` in the system state with the
+    /// given hash, at the given positions, outermost first, and returns its entries.
+    pub fn verify_entries<I, V>(
+        &self,
+        state_hash: &CryptoHash,
+        positions: &[ProofPosition],
+    ) -> Result<Vec<(I, V)>, ProofError>
+    where
+        I: DeserializeOwned,
+        V: DeserializeOwned,
+    {
+        self.check_state_hash(state_hash)?;
+        self.proof.verify_entries(&self.system_hash, positions)
+    }
+
+    fn check_state_hash(&self, state_hash: &CryptoHash) -> Result<(), ProofError> {
+        let hashes = ExecutionStateHashes::new(self.historical_hash, self.system_hash);
+        ensure!(
+            hashes.crypto_hash() == *state_hash,
+            ProofError::HashMismatch
+        );
+        Ok(())
+    }
+}
+
 /// A view accessing the execution state of a chain.
 #[derive(Debug, ClonableView, View, Allocative)]
 #[allocative(bound = "C")]
@@ -103,12 +178,32 @@ where
     C::Extra: ExecutionRuntimeContext,
 {
     /// Computes the cryptographic hash of the execution state.
+    ///
+    /// It commits to the history of the changes of the whole execution state, and to the
+    /// content of the system state, so that an [`ExecutionStateProof`] for the latter can
+    /// be checked against the state hash of a block. Unlike the historical hash, the
+    /// content hash is recomputed from every entry of the modified system views.
     pub async fn crypto_hash_mut(&mut self) -> Result<CryptoHash, ViewError> {
-        #[derive(Serialize, Deserialize)]
-        struct ExecutionStateViewHash([u8; 32]);
-        impl BcsHashable<'_> for ExecutionStateViewHash {}
-        let hash = self.inner.historical_hash().await?;
-        Ok(CryptoHash::new(&ExecutionStateViewHash(hash.into())))
+        let historical_hash = self.inner.historical_hash().await?;
+        let system_hash = self.inner.system.hash_mut().await?;
+        Ok(ExecutionStateHashes::new(historical_hash, system_hash).crypto_hash())
+    }
+
+    /// Extends a proof for the field named `field` of the system state into a proof that
+    /// can be checked against the state hash of a block.
+    pub async fn system_proof(
+        &mut self,
+        proof: ViewProof,
+        field: &str,
+    ) -> Result<ExecutionStateProof, ViewError> {
+        let historical_hash = self.inner.historical_hash().await?;
+        let system_hash = self.inner.system.hash_mut().await?;
+        let proof = proof.in_field(&self.inner.system, field).await?;
+        Ok(ExecutionStateProof {
+            historical_hash,
+            system_hash,
+            proof,
+        })
     }
 
     /// Validates the execution-state-level preconditions for a `SystemOperation::Checkpoint`
@@ -130,12 +225,16 @@ where
     ) -> Result<Vec<Blob>, ExecutionError> {
         // User event streams are summarized and pruned by the checkpoint itself (see
         // `ExecutionStateActor`'s checkpoint handler), so they do not block checkpointing.
-        // System event streams (e.g. the epoch streams on the admin chain) are not
-        // summarized, so a chain that published any is still not allowed to checkpoint.
+        // Application upgrade streams are never pruned, since other chains adopt upgrades
+        // by reading their events, so they don't block it either. Other system event
+        // streams (e.g. the epoch streams on the admin chain) are not summarized, so a
+        // chain that published any is still not allowed to checkpoint.
         let mut had_system_event_block = false;
         self.previous_event_blocks
             .for_each_index_while(|stream_id| {
-                if matches!(stream_id.application_id, GenericApplicationId::System) {
+                if matches!(stream_id.application_id, GenericApplicationId::System)
+                    && !is_application_upgrade_stream(&stream_id)
+                {
                     had_system_event_block = true;
                     Ok(false)
                 } else {
@@ -314,6 +413,7 @@ pub enum UserAction {
     Message(MessageContext, Vec<u8>),
     ProcessStreams(ProcessStreamsContext, Vec<StreamUpdate>),
     SummarizeEvents(ProcessStreamsContext, Vec<StreamUpdate>),
+    Migrate(OperationContext),
 }
 
 impl UserAction {
@@ -323,6 +423,7 @@ impl UserAction {
             UserAction::Operation(context, _) => context.authenticated_owner,
             UserAction::ProcessStreams(_, _) => None,
             UserAction::SummarizeEvents(_, _) => None,
+            UserAction::Migrate(_) => None,
             UserAction::Message(context, _) => context.authenticated_owner,
         }
     }
@@ -333,6 +434,7 @@ impl UserAction {
             UserAction::Operation(context, _) => context.height,
             UserAction::ProcessStreams(context, _) => context.height,
             UserAction::SummarizeEvents(context, _) => context.height,
+            UserAction::Migrate(context) => context.height,
             UserAction::Message(context, _) => context.height,
         }
     }
@@ -343,6 +445,7 @@ impl UserAction {
             UserAction::Operation(context, _) => context.round,
             UserAction::ProcessStreams(context, _) => context.round,
             UserAction::SummarizeEvents(context, _) => context.round,
+            UserAction::Migrate(context) => context.round,
             UserAction::Message(context, _) => context.round,
         }
     }
@@ -353,6 +456,7 @@ impl UserAction {
             UserAction::Operation(context, _) => context.timestamp,
             UserAction::ProcessStreams(context, _) => context.timestamp,
             UserAction::SummarizeEvents(context, _) => context.timestamp,
+            UserAction::Migrate(context) => context.timestamp,
             UserAction::Message(context, _) => context.timestamp,
         }
     }
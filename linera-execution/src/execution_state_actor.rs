@@ -14,8 +14,8 @@ use futures::{channel::mpsc, StreamExt as _};
 use linera_base::prometheus_util::MeasureLatency as _;
 use linera_base::{
     data_types::{
-        Amount, ApplicationPermissions, ArithmeticError, BlobContent, BlockHeight, OracleResponse,
-        StreamUpdate, Timestamp,
+        AllowanceLimits, Amount, ApplicationPermissions, ArithmeticError, BlobContent, BlockHeight,
+        FeeSponsorship, OracleResponse, StreamUpdate, Timestamp,
     },
     ensure, hex_debug, hex_vec_debug, http,
     identifiers::{
@@ -25,15 +25,16 @@ use linera_base::{
     ownership::ChainOwnership,
     time::Instant,
 };
-use linera_views::{batch::Batch, context::Context, views::View};
+use linera_views::{batch::Batch, context::Context, store::KeyRange, views::View};
 use oneshot::Sender;
 use reqwest::{header::HeaderMap, Client, Url};
 use tracing::{info_span, instrument, Instrument as _};
 
 use crate::{
+    evm::trace::EvmTrace,
     execution::UserAction,
     runtime::ContractSyncRuntime,
-    system::{CreateApplicationResult, OpenChainConfig},
+    system::{CreateApplicationResult, OpenChainConfig, ScheduledOperation},
     util::{OracleResponseExt as _, RespondExt as _},
     ApplicationDescription, ApplicationId, ExecutionError, ExecutionRuntimeContext,
     ExecutionStateView, JsVec, Message, MessageContext, MessageKind, ModuleId, Operation,
@@ -112,11 +113,12 @@ where
                     .await?
             }
         };
+        let code_description = self.state.system.code_description(id, &description).await?;
         let code = self
             .state
             .context()
             .extra()
-            .get_user_contract(&description, self.txn_tracker)
+            .get_user_contract(&code_description, self.txn_tracker)
             .await?;
         Ok((code, description))
     }
@@ -137,11 +139,12 @@ where
                     .await?
             }
         };
+        let code_description = self.state.system.code_description(id, &description).await?;
         let code = self
             .state
             .context()
             .extra()
-            .get_user_service(&description, self.txn_tracker)
+            .get_user_service(&code_description, self.txn_tracker)
             .await?;
         Ok((code, description))
     }
@@ -267,13 +270,27 @@ where
                 owner,
                 spender,
                 amount,
+                limits,
                 signer,
                 application_id,
                 callback,
             } => {
                 self.state
                     .system
-                    .approve(signer, Some(application_id), owner, spender, amount)
+                    .approve(signer, Some(application_id), owner, spender, amount, limits)
+                    .await?;
+                callback.respond(());
+            }
+
+            ApproveFeeSponsorship {
+                sponsorship,
+                signer,
+                application_id,
+                callback,
+            } => {
+                self.state
+                    .system
+                    .approve_fee_sponsorship(signer, Some(application_id), sponsorship)
                     .await?;
                 callback.respond(());
             }
@@ -405,6 +422,34 @@ where
                 callback.respond(result);
             }
 
+            FindKeysInRange {
+                id,
+                key_prefix,
+                range,
+                callback,
+            } => {
+                let view = self.state.users.try_load_entry(&id).await?;
+                let result = match view {
+                    Some(view) => view.find_keys_in_range(&key_prefix, &range).await?,
+                    None => Vec::new(),
+                };
+                callback.respond(result);
+            }
+
+            FindKeyValuesInRange {
+                id,
+                key_prefix,
+                range,
+                callback,
+            } => {
+                let view = self.state.users.try_load_entry(&id).await?;
+                let result = match view {
+                    Some(view) => view.find_key_values_in_range(&key_prefix, &range).await?,
+                    None => Vec::new(),
+                };
+                callback.respond(result);
+            }
+
             WriteBatch {
                 id,
                 batch,
@@ -485,6 +530,44 @@ where
                 callback.respond(index)
             }
 
+            IsTracingEvm { callback } => {
+                callback.respond(self.txn_tracker.evm_traces().is_some());
+            }
+
+            RecordEvmTrace { trace, callback } => {
+                if let Some(evm_traces) = self.txn_tracker.evm_traces() {
+                    evm_traces.record(*trace);
+                }
+                callback.respond(());
+            }
+
+            ScheduleOperation {
+                application_id,
+                timestamp,
+                bytes,
+                callback,
+            } => {
+                let id = self
+                    .state
+                    .system
+                    .schedule_operation(application_id, timestamp, bytes)
+                    .await?;
+                callback.respond(id)
+            }
+
+            CancelScheduledOperation {
+                application_id,
+                id,
+                callback,
+            } => {
+                let result = self
+                    .state
+                    .system
+                    .cancel_scheduled_operation(application_id, id)
+                    .await;
+                callback.respond(result)
+            }
+
             CreateApplication {
                 chain_id,
                 block_height,
@@ -502,12 +585,35 @@ where
                         module_id,
                         parameters,
                         required_application_ids,
+                        None,
                         self.txn_tracker,
                     )
                     .await?;
                 callback.respond(create_application_result);
             }
 
+            UpgradeApplication {
+                chain_id,
+                caller_id,
+                application_id,
+                module_id,
+                callback,
+            } => {
+                let result = self
+                    .state
+                    .system
+                    .upgrade_application(
+                        chain_id,
+                        None,
+                        Some(caller_id),
+                        application_id,
+                        module_id,
+                        self.txn_tracker,
+                    )
+                    .await;
+                callback.respond(result)
+            }
+
             PerformHttpRequest {
                 request,
                 http_responses_are_oracle_responses,
@@ -758,7 +864,33 @@ where
                 callback.respond(bytes);
             }
 
-            AddOutgoingMessage { message, callback } => {
+            AddOutgoingMessage {
+                mut message,
+                callback,
+            } => {
+                if let Message::User {
+                    application_id,
+                    bytes,
+                } = message.message
+                {
+                    let version = self
+                        .state
+                        .system
+                        .application_version(&application_id)
+                        .await?;
+                    message.message = if version == 0 {
+                        Message::User {
+                            application_id,
+                            bytes,
+                        }
+                    } else {
+                        Message::VersionedUser {
+                            application_id,
+                            version,
+                            bytes,
+                        }
+                    };
+                }
                 self.txn_tracker.add_outgoing_message(message);
                 callback.respond(());
             }
@@ -946,8 +1078,10 @@ where
                 });
         }
 
-        // Drop every pre-checkpoint anchor. Only user streams are present, since
-        // `prepare_checkpoint` rejects chains that published system events.
+        // Drop every pre-checkpoint anchor. Besides user streams, only application upgrade
+        // streams can be present, since `prepare_checkpoint` rejects chains that published
+        // other system events. Upgrade events are never pruned, so the next upgrade simply
+        // starts a new anchor.
         self.state.previous_event_blocks.clear();
 
         let process_context = ProcessStreamsContext::from(context);
@@ -1140,6 +1274,54 @@ where
                         .await?;
                     self.summarize_events_at_checkpoint(context).await?;
                 }
+                SystemOperation::ExecuteScheduledOperation { id } => {
+                    let ScheduledOperation {
+                        application_id,
+                        bytes,
+                        ..
+                    } = self
+                        .state
+                        .system
+                        .take_due_scheduled_operation(id, context.timestamp)
+                        .await?;
+                    // Scheduled operations are executed on behalf of the application, not
+                    // of whoever proposed the block.
+                    let context = OperationContext {
+                        authenticated_owner: None,
+                        ..context
+                    };
+                    self.run_user_action(
+                        application_id,
+                        UserAction::Operation(context, bytes),
+                        context.refund_grant_to(),
+                        None,
+                    )
+                    .await?;
+                }
+                SystemOperation::UpgradeApplication {
+                    application_id,
+                    module_id,
+                } => {
+                    self.state
+                        .system
+                        .upgrade_application(
+                            context.chain_id,
+                            context.authenticated_owner,
+                            None,
+                            application_id,
+                            module_id,
+                            self.txn_tracker,
+                        )
+                        .await?;
+                    self.migrate_application(application_id, context).await?;
+                }
+                SystemOperation::ProcessApplicationUpgrade { application_id } => {
+                    self.state
+                        .system
+                        .process_application_upgrade(application_id, self.txn_tracker)
+                        .await?;
+                    self.migrate_application(application_id, context).await?;
+                }
                 op => {
                     let new_application = self
                         .state
@@ -1175,6 +1357,59 @@ where
         Ok(())
     }
 
+    /// Calls the `migrate` entrypoint of an application that was just upgraded on this chain.
+    async fn migrate_application(
+        &mut self,
+        application_id: ApplicationId,
+        context: OperationContext,
+    ) -> Result<(), ExecutionError> {
+        // Migrations are executed on behalf of the application, not of whoever proposed the
+        // block.
+        let context = OperationContext {
+            authenticated_owner: None,
+            ..context
+        };
+        self.run_user_action(
+            application_id,
+            UserAction::Migrate(context),
+            context.refund_grant_to(),
+            None,
+        )
+        .await
+    }
+
+    /// Adopts the upgrades of the given application published by its creator chain until
+    /// this chain runs at least `version`, so that a message is never executed by older
+    /// code than the one that sent it.
+    async fn adopt_application_upgrades(
+        &mut self,
+        application_id: ApplicationId,
+        version: u32,
+        context: MessageContext,
+    ) -> Result<(), ExecutionError> {
+        let context = OperationContext {
+            chain_id: context.chain_id,
+            authenticated_owner: None,
+            height: context.height,
+            round: context.round,
+            timestamp: context.timestamp,
+        };
+        while self
+            .state
+            .system
+            .application_version(&application_id)
+            .await?
+            < version
+        {
+            self.state
+                .system
+                .process_application_upgrade(application_id, self.txn_tracker)
+                .await?;
+            self.migrate_application(application_id, context).await?;
+        }
+        Ok(())
+    }
+
     #[instrument(skip_all, fields(
         chain_id = %context.chain_id,
         block_height = %context.height,
@@ -1207,6 +1442,25 @@ where
                 )
                 .await?;
             }
+            Message::VersionedUser {
+                application_id,
+                version,
+                bytes,
+            } => {
+                let this = &mut *self;
+                Box::pin(async move {
+                    this.adopt_application_upgrades(application_id, version, context)
+                        .await?;
+                    this.run_user_action(
+                        application_id,
+                        UserAction::Message(context, bytes),
+                        context.refund_grant_to,
+                        grant,
+                    )
+                    .await
+                })
+                .await?;
+            }
         }
         self.process_subscriptions(context.into()).await?;
         Ok(())
@@ -1395,6 +1649,16 @@ pub enum ExecutionRequest {
         owner: AccountOwner,
         spender: AccountOwner,
         amount: Amount,
+        limits: AllowanceLimits,
+        #[debug(skip_if = Option::is_none)]
+        signer: Option<AccountOwner>,
+        application_id: ApplicationId,
+        #[debug(skip)]
+        callback: Sender<()>,
+    },
+
+    ApproveFeeSponsorship {
+        sponsorship: FeeSponsorship,
         #[debug(skip_if = Option::is_none)]
         signer: Option<AccountOwner>,
         application_id: ApplicationId,
@@ -1481,6 +1745,24 @@ pub enum ExecutionRequest {
         callback: Sender<Vec<(Vec<u8>, Vec<u8>)>>,
     },
 
+    FindKeysInRange {
+        id: ApplicationId,
+        #[debug(with = hex_debug)]
+        key_prefix: Vec<u8>,
+        range: Box<KeyRange>,
+        #[debug(skip)]
+        callback: Sender<Vec<Vec<u8>>>,
+    },
+
+    FindKeyValuesInRange {
+        id: ApplicationId,
+        #[debug(with = hex_debug)]
+        key_prefix: Vec<u8>,
+        range: Box<KeyRange>,
+        #[debug(skip)]
+        callback: Sender<Vec<(Vec<u8>, Vec<u8>)>>,
+    },
+
     WriteBatch {
         id: ApplicationId,
         batch: Batch,
@@ -1522,6 +1804,34 @@ pub enum ExecutionRequest {
         callback: Sender<u32>,
     },
 
+    IsTracingEvm {
+        #[debug(skip)]
+        callback: Sender<bool>,
+    },
+
+    RecordEvmTrace {
+        #[debug(skip)]
+        trace: Box<EvmTrace>,
+        #[debug(skip)]
+        callback: Sender<()>,
+    },
+
+    ScheduleOperation {
+        application_id: ApplicationId,
+        timestamp: Timestamp,
+        #[debug(with = hex_debug)]
+        bytes: Vec<u8>,
+        #[debug(skip)]
+        callback: Sender<u32>,
+    },
+
+    CancelScheduledOperation {
+        application_id: ApplicationId,
+        id: u32,
+        #[debug(skip)]
+        callback: Sender<Result<bool, ExecutionError>>,
+    },
+
     CreateApplication {
         chain_id: ChainId,
         block_height: BlockHeight,
@@ -1532,6 +1842,15 @@ pub enum ExecutionRequest {
         callback: Sender<CreateApplicationResult>,
     },
 
+    UpgradeApplication {
+        chain_id: ChainId,
+        caller_id: ApplicationId,
+        application_id: ApplicationId,
+        module_id: ModuleId,
+        #[debug(skip)]
+        callback: Sender<Result<(), ExecutionError>>,
+    },
+
     PerformHttpRequest {
         request: http::Request,
         http_responses_are_oracle_responses: bool,
@@ -5,9 +5,9 @@ use std::collections::BTreeMap;
 
 use linera_base::{
     crypto::{CryptoHash, ValidatorPublicKey},
-    data_types::{Amount, ChainDescription, Epoch, Timestamp},
+    data_types::{AllowanceUsage, Amount, ChainDescription, Epoch, LockedTranche, Timestamp},
     doc_scalar,
-    identifiers::{AccountOwner, ChainId},
+    identifiers::{AccountOwner, ApplicationId, ChainId, OwnerSpender},
     ownership::ChainOwnership,
 };
 use linera_views::{context::Context, map_view::MapView};
@@ -15,7 +15,7 @@ use linera_views::{context::Context, map_view::MapView};
 use crate::{
     committee::{Committee, ValidatorState},
     policy::ResourceControlPolicy,
-    system::UserData,
+    system::{ApplicationUpgrade, ScheduledOperation, UserData},
     ExecutionStateView, SystemExecutionStateView,
 };
 
@@ -100,8 +100,60 @@ impl<C: Send + Sync + Context> SystemExecutionStateView<C> {
         &self.balances
     }
 
+    #[graphql(derived(name = "locked_balances"))]
+    async fn _locked_balances(&self) -> &MapView<C, AccountOwner, Vec<LockedTranche>> {
+        &self.locked_balances
+    }
+
+    /// The part of the owner's balance that is still locked as of the latest block.
+    #[graphql(derived(name = "locked_balance"))]
+    async fn _locked_balance(&self, owner: AccountOwner) -> Result<Amount, async_graphql::Error> {
+        Ok(self.locked_balance(&owner).await?)
+    }
+
+    /// The part of the owner's balance that can be spent as of the latest block.
+    #[graphql(derived(name = "spendable_balance"))]
+    async fn _spendable_balance(
+        &self,
+        owner: AccountOwner,
+    ) -> Result<Amount, async_graphql::Error> {
+        let balance = self.balances.get(&owner).await?.unwrap_or_default();
+        let locked = self.locked_balance(&owner).await?;
+        Ok(balance.saturating_sub(locked))
+    }
+
+    #[graphql(derived(name = "allowances"))]
+    async fn _allowances(&self) -> &MapView<C, OwnerSpender, Amount> {
+        &self.allowances
+    }
+
+    #[graphql(derived(name = "allowance_limits"))]
+    async fn _allowance_limits(&self) -> &MapView<C, OwnerSpender, AllowanceUsage> {
+        &self.allowance_limits
+    }
+
+    #[graphql(derived(name = "fee_sponsorships"))]
+    async fn _fee_sponsorships(&self) -> &MapView<C, CryptoHash, Amount> {
+        &self.fee_sponsorships
+    }
+
     #[graphql(derived(name = "timestamp"))]
     async fn _timestamp(&self) -> &Timestamp {
         &self.progress.get().timestamp
     }
+
+    #[graphql(derived(name = "scheduled_operations"))]
+    async fn _scheduled_operations(&self) -> &MapView<C, u32, ScheduledOperation> {
+        &self.scheduled_operations
+    }
+
+    #[graphql(derived(name = "application_upgrades"))]
+    async fn _application_upgrades(&self) -> &MapView<C, ApplicationId, ApplicationUpgrade> {
+        &self.application_upgrades
+    }
+
+    #[graphql(derived(name = "upgrade_authorities"))]
+    async fn _upgrade_authorities(&self) -> &MapView<C, ApplicationId, AccountOwner> {
+        &self.upgrade_authorities
+    }
 }
@@ -36,11 +36,11 @@ use derive_more::Display;
 use js_sys::wasm_bindgen::JsValue;
 use linera_base::{
     abi::Abi,
-    crypto::{BcsHashable, CryptoHash},
+    crypto::{AccountSignature, BcsHashable, CryptoHash},
     data_types::{
-        Amount, ApplicationDescription, ApplicationPermissions, ArithmeticError, Blob, BlockHeight,
-        Bytecode, DecompressionError, Epoch, NetworkDescription, SendMessageRequest, StreamUpdate,
-        Timestamp,
+        AllowanceLimitError, AllowanceLimits, Amount, ApplicationDescription,
+        ApplicationPermissions, ArithmeticError, Blob, BlockHeight, Bytecode, DecompressionError,
+        Epoch, FeeSponsorship, NetworkDescription, SendMessageRequest, StreamUpdate, Timestamp,
     },
     doc_scalar, ensure, hex_debug, http,
     identifiers::{
@@ -50,7 +50,7 @@ use linera_base::{
     ownership::ChainOwnership,
     vm::VmRuntime,
 };
-use linera_views::{batch::Batch, ViewError};
+use linera_views::{batch::Batch, store::KeyRange, ViewError};
 use serde::{Deserialize, Serialize};
 use system::AdminOperation;
 use thiserror::Error;
@@ -59,11 +59,12 @@ use web_thread_select as web_thread;
 
 #[cfg(with_revm)]
 use crate::evm::EvmExecutionError;
-use crate::system::{EpochEventData, EPOCH_STREAM_NAME};
 #[cfg(with_testing)]
 use crate::test_utils::dummy_chain_description;
 #[cfg(all(with_testing, with_wasm_runtime))]
 pub use crate::wasm::test as wasm_test;
+#[cfg(all(with_wasm_runtime, not(web)))]
+pub use crate::wasm::ProfiledWasmContractModule;
 #[cfg(with_wasm_runtime)]
 pub use crate::wasm::{
     BaseRuntimeApi, ContractEntrypoints, ContractRuntimeApi, RuntimeApiData, ServiceEntrypoints,
@@ -71,19 +72,24 @@ pub use crate::wasm::{
 };
 pub use crate::{
     committee::{Committee, SharedCommittees},
-    execution::{ExecutionStateView, ServiceRuntimeEndpoint},
+    execution::{ExecutionStateProof, ExecutionStateView, ServiceRuntimeEndpoint},
     execution_state_actor::{ExecutionRequest, ExecutionStateActor},
     policy::{ProtocolFlag, ResourceControlPolicy},
-    resources::{BalanceHolder, ResourceController, ResourceTracker},
+    resources::{BalanceHolder, FeeSponsor, ResourceController, ResourceTracker},
     runtime::{
         ContractSyncRuntimeHandle, ServiceRuntimeRequest, ServiceSyncRuntime,
         ServiceSyncRuntimeHandle,
     },
     system::{
-        ChainProgress, SystemExecutionStateView, SystemMessage, SystemOperation, SystemQuery,
-        SystemResponse,
+        ChainProgress, ScheduledOperation, SystemExecutionStateView, SystemMessage,
+        SystemOperation, SystemQuery, SystemResponse,
     },
     transaction_tracker::{PreparedCheckpoint, TransactionOutcome, TransactionTracker},
+    wasm::profile::{WasmProfile, WasmProfileSample},
+};
+use crate::{
+    evm::trace::EvmTrace,
+    system::{EpochEventData, EPOCH_STREAM_NAME},
 };
 
 /// The `Linera.sol` library code to be included in solidity smart
@@ -349,8 +355,31 @@ pub enum ExecutionError {
         balance: Amount,
         account: AccountOwner,
     },
+    #[error(
+        "The transferred amount must not exceed the unlocked balance of account {account}: \
+         {balance}, of which {locked} are locked"
+    )]
+    InsufficientUnlockedBalance {
+        balance: Amount,
+        locked: Amount,
+        account: AccountOwner,
+    },
+    #[error("Invalid vesting schedule: the cliff must be between the start and the end")]
+    InvalidVestingSchedule,
+    #[error("Tokens can only be locked in an owner's account, not in the chain balance")]
+    LockedChainBalance,
+    #[error("Account {0} already has the maximum number of locked tranches")]
+    TooManyLockedTranches(AccountOwner),
     #[error("Required execution fees exceeded the total funding available. Fees {fees}, available balance: {balance}")]
     FeesExceedFunding { fees: Amount, balance: Amount },
+    #[error("The fee sponsorship is for chain {0}, not for this one")]
+    FeeSponsorshipForOtherChain(ChainId),
+    #[error("The fee sponsorship expired at {0}")]
+    FeeSponsorshipExpired(Timestamp),
+    #[error("The fee sponsorship is neither signed nor approved by the sponsor {0}")]
+    UnauthorizedFeeSponsorship(AccountOwner),
+    #[error("A block can use at most one fee sponsorship")]
+    MultipleFeeSponsorships,
     #[error("Claim must have positive amount")]
     IncorrectClaimAmount,
     #[error("Claim must be authenticated by the right owner")]
@@ -361,6 +390,12 @@ pub enum ExecutionError {
         owner: AccountOwner,
         spender: AccountOwner,
     },
+    #[error("Cannot use the allowance of spender {spender} from owner {owner}: {error}")]
+    AllowanceLimit {
+        owner: AccountOwner,
+        spender: AccountOwner,
+        error: AllowanceLimitError,
+    },
     #[error("Admin operations are only allowed on the admin chain.")]
     AdminOperationOnNonAdminChain,
     #[error("Failed to create new committee: expected {expected}, but got {provided}")]
@@ -379,6 +414,33 @@ pub enum ExecutionError {
     UnsubscribedUpdateStream,
     #[error("Checkpoint precondition failed: {0}")]
     CheckpointPreconditionFailed(&'static str),
+    #[error("Scheduled operation {0} not found")]
+    ScheduledOperationNotFound(u32),
+    #[error(
+        "Scheduled operation {id} is due at {scheduled}, but the block timestamp is {block_timestamp}"
+    )]
+    ScheduledOperationNotDue {
+        id: u32,
+        scheduled: Timestamp,
+        block_timestamp: Timestamp,
+    },
+    #[error("Application {0} is not allowed to execute operations on this chain")]
+    ScheduledOperationNotPermitted(ApplicationId),
+    #[error("Application {application_id} cannot cancel scheduled operation {id} of another application")]
+    UnauthorizedScheduledOperationCancellation {
+        id: u32,
+        application_id: ApplicationId,
+    },
+    #[error("Application {0} has no upgrade authority or was not upgraded by it")]
+    UnauthorizedApplicationUpgrade(Box<ApplicationId>),
+    #[error("Application {0} can only be upgraded on the chain that created it")]
+    ApplicationUpgradeOnNonCreatorChain(Box<ApplicationId>),
+    #[error("Application {0} cannot be upgraded to a module for a different VM runtime")]
+    ApplicationUpgradeVmMismatch(Box<ApplicationId>),
+    #[error("Application {0} cannot be upgraded while it is in use by the current transaction")]
+    ApplicationUpgradeWhileLoaded(Box<ApplicationId>),
+    #[error("Upgrade event does not belong to application {0}")]
+    InvalidApplicationUpgradeEvent(Box<ApplicationId>),
 }
 
 impl ExecutionError {
@@ -422,10 +484,19 @@ impl ExecutionError {
             | ExecutionError::IncorrectTransferAmount
             | ExecutionError::UnauthenticatedTransferOwner
             | ExecutionError::InsufficientBalance { .. }
+            | ExecutionError::InsufficientUnlockedBalance { .. }
+            | ExecutionError::InvalidVestingSchedule
+            | ExecutionError::LockedChainBalance
+            | ExecutionError::TooManyLockedTranches(_)
             | ExecutionError::FeesExceedFunding { .. }
+            | ExecutionError::FeeSponsorshipForOtherChain(_)
+            | ExecutionError::FeeSponsorshipExpired(_)
+            | ExecutionError::UnauthorizedFeeSponsorship(_)
+            | ExecutionError::MultipleFeeSponsorships
             | ExecutionError::IncorrectClaimAmount
             | ExecutionError::UnauthenticatedClaimOwner
             | ExecutionError::InsufficientAllowance { .. }
+            | ExecutionError::AllowanceLimit { .. }
             | ExecutionError::AdminOperationOnNonAdminChain
             | ExecutionError::InvalidCommitteeEpoch { .. }
             | ExecutionError::InvalidCommitteeRemoval
@@ -434,6 +505,15 @@ impl ExecutionError {
             | ExecutionError::OutdatedUpdateStream
             | ExecutionError::UnsubscribedUpdateStream
             | ExecutionError::CheckpointPreconditionFailed(_)
+            | ExecutionError::ScheduledOperationNotFound(_)
+            | ExecutionError::ScheduledOperationNotDue { .. }
+            | ExecutionError::ScheduledOperationNotPermitted(_)
+            | ExecutionError::UnauthorizedScheduledOperationCancellation { .. }
+            | ExecutionError::UnauthorizedApplicationUpgrade(_)
+            | ExecutionError::ApplicationUpgradeOnNonCreatorChain(_)
+            | ExecutionError::ApplicationUpgradeVmMismatch(_)
+            | ExecutionError::ApplicationUpgradeWhileLoaded(_)
+            | ExecutionError::InvalidApplicationUpgradeEvent(_)
             | ExecutionError::ViewError(ViewError::NotFound(_)) => false,
             #[cfg(with_wasm_runtime)]
             ExecutionError::WasmError(_) => false,
@@ -502,6 +582,10 @@ pub trait UserContract {
     /// that published events since the previous checkpoint.
     fn summarize_events(&mut self, updates: Vec<StreamUpdate>) -> Result<(), ExecutionError>;
 
+    /// Migrates the application's state after the application was upgraded to this
+    /// module.
+    fn migrate(&mut self) -> Result<(), ExecutionError>;
+
     /// Finishes execution of the current transaction.
     fn finalize(&mut self) -> Result<(), ExecutionError>;
 }
@@ -909,6 +993,14 @@ pub trait BaseRuntime {
         promise: &Self::FindKeysByPrefix,
     ) -> Result<Vec<Vec<u8>>, ExecutionError>;
 
+    /// Creates the promise to access the keys having a specific prefix whose suffixes are in
+    /// a range. The promise is resolved by [`Self::find_keys_by_prefix_wait`].
+    fn find_keys_in_range_new(
+        &mut self,
+        key_prefix: Vec<u8>,
+        range: KeyRange,
+    ) -> Result<Self::FindKeysByPrefix, ExecutionError>;
+
     /// Reads the data from the key/values having a specific prefix.
     #[cfg(feature = "test")]
     #[expect(clippy::type_complexity)]
@@ -933,6 +1025,14 @@ pub trait BaseRuntime {
         promise: &Self::FindKeyValuesByPrefix,
     ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ExecutionError>;
 
+    /// Creates the promise to access the key/values having a specific prefix whose suffixes
+    /// are in a range. The promise is resolved by [`Self::find_key_values_by_prefix_wait`].
+    fn find_key_values_in_range_new(
+        &mut self,
+        key_prefix: Vec<u8>,
+        range: KeyRange,
+    ) -> Result<Self::FindKeyValuesByPrefix, ExecutionError>;
+
     /// Makes an HTTP request to the given URL and returns the answer, if any.
     fn perform_http_request(
         &mut self,
@@ -1032,12 +1132,23 @@ pub trait ContractRuntime: BaseRuntime {
         amount: Amount,
     ) -> Result<(), ExecutionError>;
 
-    /// Approves spender to withdraw amount from owner's account.
+    /// Approves spender to withdraw amount from owner's account, subject to the given
+    /// limits.
     fn approve(
         &mut self,
         owner: AccountOwner,
         spender: AccountOwner,
         amount: Amount,
+        limits: AllowanceLimits,
+    ) -> Result<(), ExecutionError>;
+
+    /// Approves a fee sponsorship on the current chain, so that blocks can use it to have
+    /// the sponsor pay their fees, up to `max_fees` in total and until the `expiry`.
+    fn approve_fee_sponsorship(
+        &mut self,
+        sponsor: AccountOwner,
+        max_fees: Amount,
+        expiry: Timestamp,
     ) -> Result<(), ExecutionError>;
 
     /// Transfers amount from owner to destination using spender's allowance.
@@ -1124,6 +1235,15 @@ pub trait ContractRuntime: BaseRuntime {
         required_application_ids: Vec<ApplicationId>,
     ) -> Result<ApplicationId, ExecutionError>;
 
+    /// Switches an application created on the current chain to a new module, and calls the
+    /// `migrate` entrypoint of the new module. The current application must be the upgrade
+    /// authority of the upgraded one.
+    fn upgrade_application(
+        &mut self,
+        application_id: ApplicationId,
+        module_id: ModuleId,
+    ) -> Result<(), ExecutionError>;
+
     /// Returns the next application index, which is equal to the number of
     /// new applications created so far in this block.
     fn peek_application_index(&mut self) -> Result<u32, ExecutionError>;
@@ -1144,8 +1264,27 @@ pub trait ContractRuntime: BaseRuntime {
     /// Returns the multi-leader round in which this block was validated.
     fn validation_round(&mut self) -> Result<Option<u32>, ExecutionError>;
 
+    /// Schedules a serialized operation of the current application to be executed on this
+    /// chain in the first block whose timestamp is at least `timestamp`. Returns the ID of
+    /// the scheduled operation.
+    fn schedule_operation_at(
+        &mut self,
+        timestamp: Timestamp,
+        operation: Vec<u8>,
+    ) -> Result<u32, ExecutionError>;
+
+    /// Cancels an operation previously scheduled by the current application. Returns
+    /// `false` if the operation was not found, e.g. because it was already executed.
+    fn cancel_scheduled_operation(&mut self, id: u32) -> Result<bool, ExecutionError>;
+
     /// Writes a batch of changes.
     fn write_batch(&mut self, batch: Batch) -> Result<(), ExecutionError>;
+
+    /// Returns whether the EVM executions of the current transaction are traced.
+    fn is_tracing_evm(&mut self) -> Result<bool, ExecutionError>;
+
+    /// Records the trace of an EVM execution of the current transaction.
+    fn record_evm_trace(&mut self, trace: EvmTrace) -> Result<(), ExecutionError>;
 }
 
 /// An operation to be executed in a block.
@@ -1184,6 +1323,19 @@ pub enum Message {
         #[debug(with = "hex_debug")]
         bytes: Vec<u8>,
     },
+    /// A user message (in serialized form) sent by an application that was upgraded on the
+    /// sender chain. The receiver adopts the application's upgrades up to `version` before
+    /// executing it, so that it never runs older code than the sender.
+    VersionedUser {
+        /// The ID of the application this message targets.
+        application_id: ApplicationId,
+        /// The number of upgrades of the application adopted by the sender chain.
+        version: u32,
+        /// The serialized message.
+        #[serde(with = "serde_bytes")]
+        #[debug(with = "hex_debug")]
+        bytes: Vec<u8>,
+    },
 }
 
 /// An query to be sent and possibly executed in the receiver's block.
@@ -1568,10 +1720,25 @@ impl Operation {
         };
         matches!(
             **system_op,
-            SystemOperation::ProcessNewEpoch(_) | SystemOperation::UpdateStream { .. }
+            SystemOperation::ProcessNewEpoch(_)
+                | SystemOperation::UpdateStream { .. }
+                | SystemOperation::ProcessApplicationUpgrade { .. }
+                | SystemOperation::UseFeeSponsorship { .. }
         )
     }
 
+    /// Returns the ID of the scheduled operation, if this is an `ExecuteScheduledOperation`
+    /// operation.
+    pub fn scheduled_operation_id(&self) -> Option<u32> {
+        let Operation::System(system_op) = self else {
+            return None;
+        };
+        match **system_op {
+            SystemOperation::ExecuteScheduledOperation { id } => Some(id),
+            _ => None,
+        }
+    }
+
     /// Returns whether this operation is an `UpdateStream` operation.
     pub fn is_update_stream(&self) -> bool {
         let Operation::System(system_op) = self else {
@@ -1587,6 +1754,21 @@ impl Operation {
         };
         matches!(**system_op, SystemOperation::Checkpoint)
     }
+
+    /// Returns the sponsorship and signature of a `UseFeeSponsorship` operation.
+    pub fn fee_sponsorship(&self) -> Option<(&FeeSponsorship, Option<&AccountSignature>)> {
+        let Operation::System(system_op) = self else {
+            return None;
+        };
+        let SystemOperation::UseFeeSponsorship {
+            sponsorship,
+            signature,
+        } = &**system_op
+        else {
+            return None;
+        };
+        Some((sponsorship, signature.as_ref()))
+    }
 }
 
 impl From<SystemMessage> for Message {
@@ -1624,7 +1806,9 @@ impl Message {
     pub fn application_id(&self) -> GenericApplicationId {
         match self {
             Self::System(_) => GenericApplicationId::System,
-            Self::User { application_id, .. } => GenericApplicationId::User(*application_id),
+            Self::User { application_id, .. } | Self::VersionedUser { application_id, .. } => {
+                GenericApplicationId::User(*application_id)
+            }
         }
     }
 }
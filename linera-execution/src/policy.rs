@@ -46,6 +46,9 @@ use crate::ExecutionError;
 pub enum ProtocolFlag {
     #[doc(hidden)]
     _Reserved = 0,
+    /// Fees charged to an owner's account may be paid with tokens that are still locked in a
+    /// vesting tranche. Otherwise, only the spendable part of the balance can pay fees.
+    FeesFromLockedBalances = 1,
 }
 
 /// A collection of prices and limits associated with block execution.
@@ -85,6 +88,9 @@ pub struct ResourceControlPolicy {
     pub service_as_oracle_query: Amount,
     /// The price for a performing an HTTP request.
     pub http_request: Amount,
+    /// The base price of scheduling an operation to be executed in a later block. The
+    /// operation's bytes are additionally charged at the `operation_byte` price.
+    pub scheduled_operation: Amount,
 
     // TODO(#1538): Cap the number of transactions per block and the total size of their
     // arguments.
@@ -143,6 +149,7 @@ impl fmt::Display for ResourceControlPolicy {
             message_byte,
             service_as_oracle_query,
             http_request,
+            scheduled_operation,
             maximum_wasm_fuel_per_block,
             maximum_evm_fuel_per_block,
             maximum_service_oracle_execution_ms,
@@ -180,6 +187,7 @@ impl fmt::Display for ResourceControlPolicy {
             {message:.2} per outgoing messages\n\
             {message_byte:.2} per byte in the argument of an outgoing messages\n\
             {http_request:.2} per HTTP request performed\n\
+            {scheduled_operation:.2} per scheduled operation\n\
             {maximum_wasm_fuel_per_block} maximum Wasm fuel per block\n\
             {maximum_evm_fuel_per_block} maximum EVM fuel per block\n\
             {maximum_service_oracle_execution_ms} ms maximum service-as-oracle execution time per \
@@ -231,6 +239,7 @@ impl ResourceControlPolicy {
             message_byte: Amount::ZERO,
             service_as_oracle_query: Amount::ZERO,
             http_request: Amount::ZERO,
+            scheduled_operation: Amount::ZERO,
             maximum_wasm_fuel_per_block: u64::MAX,
             maximum_evm_fuel_per_block: u64::MAX,
             maximum_service_oracle_execution_ms: u64::MAX,
@@ -292,6 +301,7 @@ impl ResourceControlPolicy {
             message: Amount::from_attos(10),
             message_byte: Amount::from_attos(1),
             http_request: Amount::from_micros(1),
+            scheduled_operation: Amount::from_attos(10),
             ..Self::no_fees()
         }
     }
@@ -316,6 +326,7 @@ impl ResourceControlPolicy {
             message: Amount::from_micros(10),
             service_as_oracle_query: Amount::from_millis(10),
             http_request: Amount::from_micros(50),
+            scheduled_operation: Amount::from_micros(10),
             maximum_wasm_fuel_per_block: 100_000_000,
             maximum_evm_fuel_per_block: 100_000_000,
             maximum_service_oracle_execution_ms: 10_000,
@@ -419,6 +430,12 @@ impl ResourceControlPolicy {
         self.http_request.try_mul(count as u128)
     }
 
+    /// Returns how much it costs to schedule an operation of the given size.
+    pub(crate) fn scheduled_operation_price(&self, size: u64) -> Result<Amount, ArithmeticError> {
+        self.operation_bytes_price(size)?
+            .try_add(self.scheduled_operation)
+    }
+
     fn fuel_unit_price(&self, vm_runtime: VmRuntime) -> Amount {
         match vm_runtime {
             VmRuntime::Wasm => self.wasm_fuel_unit,
@@ -7,6 +7,7 @@ use std::{fmt, sync::Arc, time::Duration};
 
 use custom_debug_derive::Debug;
 use linera_base::{
+    crypto::CryptoHash,
     data_types::{Amount, ApplicationDescription, ArithmeticError, Blob},
     ensure,
     identifiers::AccountOwner,
@@ -16,7 +17,10 @@ use linera_base::{
 use linera_views::{context::Context, ViewError};
 use serde::Serialize;
 
-use crate::{ExecutionError, Message, Operation, ResourceControlPolicy, SystemExecutionStateView};
+use crate::{
+    ExecutionError, Message, Operation, ProtocolFlag, ResourceControlPolicy,
+    SystemExecutionStateView,
+};
 
 /// Tracks and controls the resources used during execution, charging fees against an account.
 #[derive(Clone, Debug, Default)]
@@ -29,6 +33,25 @@ pub struct ResourceController<Account = Amount, Tracker = ResourceTracker> {
     pub account: Account,
     /// When true, balance deductions are skipped (fees waived for free apps).
     pub is_free: bool,
+    /// The sponsor paying the fees of the current block, if any.
+    #[debug(skip_if = Option::is_none)]
+    pub sponsor: Option<FeeSponsor>,
+}
+
+/// An account paying the fees of a block under a
+/// [`FeeSponsorship`](linera_base::data_types::FeeSponsorship).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeeSponsor {
+    /// The owner of the account paying the fees.
+    pub owner: AccountOwner,
+    /// The hash of the sponsorship, under which the fees paid so far are recorded.
+    pub sponsorship_hash: CryptoHash,
+    /// The maximum total amount of fees paid under the sponsorship.
+    pub max_fees: Amount,
+    /// The fees paid by the sponsor in the current block.
+    pub paid: Amount,
+    /// The fees paid by the other sources in the current block.
+    pub paid_by_others: Amount,
 }
 
 impl<Account, Tracker> ResourceController<Account, Tracker> {
@@ -39,6 +62,7 @@ impl<Account, Tracker> ResourceController<Account, Tracker> {
             tracker,
             account,
             is_free: false,
+            sponsor: None,
         }
     }
 
@@ -193,6 +217,8 @@ pub struct ResourceTracker {
     pub operations: u32,
     /// The total size of the arguments of user operations.
     pub operation_bytes: u64,
+    /// The number of operations scheduled for execution in a later block.
+    pub scheduled_operations: u32,
     /// The number of outgoing messages created (system and user).
     pub messages: u32,
     /// The total size of the arguments of outgoing user messages.
@@ -205,6 +231,8 @@ pub struct ResourceTracker {
     pub service_oracle_execution: Duration,
     /// The amount allocated to message grants.
     pub grants: Amount,
+    /// The total amount debited so far, for fees and message grants.
+    pub fees: Amount,
 }
 
 impl ResourceTracker {
@@ -230,6 +258,12 @@ impl fmt::Display for ResourceTracker {
         if self.operation_bytes != 0 {
             block_parts.push(format!("operation_bytes={}", self.operation_bytes));
         }
+        if self.scheduled_operations != 0 {
+            block_parts.push(format!(
+                "scheduled_operations={}",
+                self.scheduled_operations
+            ));
+        }
         if !block_parts.is_empty() {
             lines.push(format!("block: {}", block_parts.join(", ")));
         }
@@ -330,6 +364,10 @@ impl fmt::Display for ResourceTracker {
             lines.push(format!("http/service: {}", http_service_parts.join(", ")));
         }
 
+        if self.fees != Amount::ZERO {
+            lines.push(format!("fees: {}", self.fees));
+        }
+
         let mut lines_iter = lines.into_iter();
         if let Some(first) = lines_iter.next() {
             write!(f, "{first}")?;
@@ -396,6 +434,7 @@ where
                 fees,
                 balance: self.balance().unwrap_or(Amount::MAX),
             })?;
+        self.tracker.as_mut().fees.try_add_assign(fees)?;
         Ok(())
     }
 
@@ -454,7 +493,7 @@ where
         self.update_balance(self.policy.message)?;
         match message {
             Message::System(_) => Ok(()),
-            Message::User { bytes, .. } => {
+            Message::User { bytes, .. } | Message::VersionedUser { bytes, .. } => {
                 let size = bytes.len();
                 self.tracker.as_mut().message_bytes = self
                     .tracker
@@ -479,6 +518,17 @@ where
         self.update_balance(self.policy.http_request)
     }
 
+    /// Tracks an operation scheduled for execution in a later block.
+    pub fn track_scheduled_operation(&mut self, size: u64) -> Result<(), ExecutionError> {
+        self.tracker.as_mut().scheduled_operations = self
+            .tracker
+            .as_ref()
+            .scheduled_operations
+            .checked_add(1)
+            .ok_or(ArithmeticError::Overflow)?;
+        self.update_balance(self.policy.scheduled_operation_price(size)?)
+    }
+
     /// Tracks a number of fuel units used.
     pub(crate) fn track_fuel(
         &mut self,
@@ -849,9 +899,9 @@ impl ResourceController<Option<AccountOwner>, ResourceTracker> {
     /// Provides a reference to the current execution state and obtains a temporary object
     /// where the accounting functions of [`ResourceController`] are available.
     pub async fn with_state<'a, C>(
-        &mut self,
+        &'a mut self,
         view: &'a mut SystemExecutionStateView<C>,
-    ) -> Result<ResourceController<Sources<'a>, &mut ResourceTracker>, ViewError>
+    ) -> Result<ResourceController<Sources<'a>, &'a mut ResourceTracker>, ViewError>
     where
         C: Context + Clone + 'static,
     {
@@ -862,34 +912,72 @@ impl ResourceController<Option<AccountOwner>, ResourceTracker> {
     /// and obtains a temporary object where the accounting functions of
     /// [`ResourceController`] are available.
     pub async fn with_state_and_grant<'a, C>(
-        &mut self,
+        &'a mut self,
         view: &'a mut SystemExecutionStateView<C>,
         grant: Option<&'a mut Amount>,
-    ) -> Result<ResourceController<Sources<'a>, &mut ResourceTracker>, ViewError>
+    ) -> Result<ResourceController<Sources<'a>, &'a mut ResourceTracker>, ViewError>
     where
         C: Context + Clone + 'static,
     {
+        // Fees paid with a grant (e.g. for messages) are not sponsored.
+        let sponsor = self.sponsor.as_mut().filter(|_| grant.is_none());
+        // Unless the policy allows it, tokens locked in vesting tranches can't pay fees.
+        let locked = match sponsor
+            .as_ref()
+            .map(|sponsor| sponsor.owner)
+            .or(self.account)
+        {
+            Some(owner)
+                if !self
+                    .policy
+                    .flags
+                    .contains(&ProtocolFlag::FeesFromLockedBalances) =>
+            {
+                view.locked_balance(&owner).await?
+            }
+            _ => Amount::ZERO,
+        };
         let mut sources = Vec::new();
         // First, use the grant (e.g. for messages) and otherwise use the chain account
         // (e.g. for blocks and operations).
         if let Some(grant) = grant {
-            sources.push(grant);
+            sources.push((grant, Amount::ZERO));
         } else {
-            sources.push(view.balance.get_mut());
+            sources.push((view.balance.get_mut(), Amount::ZERO));
         }
-        // Then the local account, if any. Currently, any negative fee (e.g. storage
-        // refund) goes preferably to this account.
-        if let Some(owner) = &self.account {
+        let mut sponsor_source = None;
+        if let Some(sponsor) = sponsor {
+            // A sponsor pays before the chain account, up to the sponsorship's cap. The
+            // local account is not used in that case.
+            if let Some(balance) = view.balances.get_mut(&sponsor.owner).await? {
+                let spent = view
+                    .fee_sponsorships
+                    .get_mut_or_default(&sponsor.sponsorship_hash)
+                    .await?;
+                sponsor_source = Some(SponsorSource {
+                    balance,
+                    locked,
+                    spent,
+                    sponsor,
+                });
+            }
+        } else if let Some(owner) = &self.account {
+            // Then the local account, if any. Currently, any negative fee (e.g. storage
+            // refund) goes preferably to this account.
             if let Some(balance) = view.balances.get_mut(owner).await? {
-                sources.push(balance);
+                sources.push((balance, locked));
             }
         }
 
         Ok(ResourceController {
             policy: self.policy.clone(),
             tracker: &mut self.tracker,
-            account: Sources { sources },
+            account: Sources {
+                sponsor: sponsor_source,
+                sources,
+            },
             is_free: self.is_free,
+            sponsor: None,
         })
     }
 }
@@ -925,32 +1013,103 @@ impl AsRef<ResourceTracker> for ResourceTracker {
 
 /// A temporary object holding a number of references to funding sources.
 pub struct Sources<'a> {
-    sources: Vec<&'a mut Amount>,
+    /// The sponsor's account, which is used before the other sources.
+    sponsor: Option<SponsorSource<'a>>,
+    /// Each source, together with the part of it that must not be used to pay fees.
+    sources: Vec<(&'a mut Amount, Amount)>,
+}
+
+/// The account of a [`FeeSponsor`], together with the fees paid under its sponsorship.
+struct SponsorSource<'a> {
+    /// The sponsor's balance.
+    balance: &'a mut Amount,
+    /// The part of the balance that must not be used to pay fees.
+    locked: Amount,
+    /// The fees paid under the sponsorship so far.
+    spent: &'a mut Amount,
+    /// The sponsor, with the fees paid in the current block.
+    sponsor: &'a mut FeeSponsor,
+}
+
+impl SponsorSource<'_> {
+    /// Returns how much the sponsor can still pay.
+    fn available(&self) -> Amount {
+        self.balance
+            .saturating_sub(self.locked)
+            .min(self.sponsor.max_fees.saturating_sub(*self.spent))
+    }
+
+    /// Returns the sponsor's part of a refund of `amount`, in proportion to the fees it
+    /// paid in the current block, rounded down.
+    fn refund_share(&self, amount: Amount) -> Amount {
+        let paid = self.sponsor.paid.to_attos();
+        let total = self
+            .sponsor
+            .paid
+            .saturating_add(self.sponsor.paid_by_others)
+            .to_attos();
+        if total == 0 {
+            return Amount::ZERO;
+        }
+        let amount = amount.to_attos();
+        // Splitting the amount avoids overflowing `u128` in the common case.
+        let share = (amount / total)
+            .saturating_mul(paid)
+            .saturating_add((amount % total).saturating_mul(paid) / total);
+        Amount::from_attos(share).min(self.sponsor.paid)
+    }
 }
 
 impl BalanceHolder for Sources<'_> {
     fn balance(&self) -> Result<Amount, ArithmeticError> {
-        let mut amount = Amount::ZERO;
-        for source in &self.sources {
-            amount.try_add_assign(**source)?;
+        let mut amount = self
+            .sponsor
+            .as_ref()
+            .map_or(Amount::ZERO, SponsorSource::available);
+        for (source, reserved) in &self.sources {
+            amount.try_add_assign(source.saturating_sub(*reserved))?;
         }
         Ok(amount)
     }
 
-    fn try_add_assign(&mut self, other: Amount) -> Result<(), ArithmeticError> {
+    fn try_add_assign(&mut self, mut other: Amount) -> Result<(), ArithmeticError> {
+        // A sponsor gets back its share of the fees paid in the current block.
+        if let Some(sponsor) = &mut self.sponsor {
+            let amount = sponsor.refund_share(other);
+            sponsor.balance.try_add_assign(amount)?;
+            *sponsor.spent = sponsor.spent.saturating_sub(amount);
+            sponsor.sponsor.paid.try_sub_assign(amount)?;
+            other.try_sub_assign(amount).expect("amount <= other");
+            sponsor.sponsor.paid_by_others = sponsor.sponsor.paid_by_others.saturating_sub(other);
+        }
         // Try to credit the owner account first.
         // TODO(#1648): This may need some additional design work.
-        let source = self.sources.last_mut().expect("at least one source");
+        let (source, _) = self.sources.last_mut().expect("at least one source");
         source.try_add_assign(other)
     }
 
     fn try_sub_assign(&mut self, mut other: Amount) -> Result<(), ArithmeticError> {
-        for source in &mut self.sources {
-            if source.try_sub_assign(other).is_ok() {
+        if let Some(sponsor) = &mut self.sponsor {
+            let amount = sponsor.available().min(other);
+            sponsor
+                .balance
+                .try_sub_assign(amount)
+                .expect("amount <= *balance");
+            sponsor.spent.try_add_assign(amount)?;
+            sponsor.sponsor.paid.try_add_assign(amount)?;
+            other.try_sub_assign(amount).expect("amount <= other");
+            sponsor.sponsor.paid_by_others.try_add_assign(other)?;
+        }
+        for (source, reserved) in &mut self.sources {
+            let available = source.saturating_sub(*reserved);
+            if other <= available {
+                source.try_sub_assign(other).expect("other <= *source");
                 return Ok(());
             }
-            other.try_sub_assign(**source).expect("*source < other");
-            **source = Amount::ZERO;
+            other.try_sub_assign(available).expect("available < other");
+            source
+                .try_sub_assign(available)
+                .expect("available <= *source");
         }
         if other > Amount::ZERO {
             Err(ArithmeticError::Underflow)
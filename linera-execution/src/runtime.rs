@@ -11,8 +11,8 @@ use std::{
 use custom_debug_derive::Debug;
 use linera_base::{
     data_types::{
-        Amount, ApplicationPermissions, ArithmeticError, Blob, BlockHeight, Bytecode,
-        SendMessageRequest, Timestamp,
+        AllowanceLimits, Amount, ApplicationPermissions, ArithmeticError, Blob, BlockHeight,
+        Bytecode, FeeSponsorship, SendMessageRequest, Timestamp,
     },
     ensure, http,
     identifiers::{
@@ -22,11 +22,12 @@ use linera_base::{
     time::Instant,
     vm::VmRuntime,
 };
-use linera_views::batch::Batch;
+use linera_views::{batch::Batch, store::KeyRange};
 use oneshot::Receiver;
 use tracing::instrument;
 
 use crate::{
+    evm::trace::EvmTrace,
     execution::UserAction,
     execution_state_actor::{ExecutionRequest, ExecutionStateSender},
     resources::ResourceController,
@@ -898,6 +899,26 @@ where
         Ok(keys)
     }
 
+    fn find_keys_in_range_new(
+        &mut self,
+        key_prefix: Vec<u8>,
+        range: KeyRange,
+    ) -> Result<Self::FindKeysByPrefix, ExecutionError> {
+        let mut this = self.inner();
+        let id = this.current_application().id;
+        this.resource_controller.track_read_operation()?;
+        let receiver = this.execution_state_sender.send_request(move |callback| {
+            ExecutionRequest::FindKeysInRange {
+                id,
+                key_prefix,
+                range: Box::new(range),
+                callback,
+            }
+        })?;
+        let state = this.view_user_states.entry(id).or_default();
+        state.find_keys_queries.register(receiver)
+    }
+
     fn find_key_values_by_prefix_new(
         &mut self,
         key_prefix: Vec<u8>,
@@ -933,6 +954,26 @@ where
         Ok(key_values)
     }
 
+    fn find_key_values_in_range_new(
+        &mut self,
+        key_prefix: Vec<u8>,
+        range: KeyRange,
+    ) -> Result<Self::FindKeyValuesByPrefix, ExecutionError> {
+        let mut this = self.inner();
+        let id = this.current_application().id;
+        this.resource_controller.track_read_operation()?;
+        let receiver = this.execution_state_sender.send_request(move |callback| {
+            ExecutionRequest::FindKeyValuesInRange {
+                id,
+                key_prefix,
+                range: Box::new(range),
+                callback,
+            }
+        })?;
+        let state = this.view_user_states.entry(id).or_default();
+        state.find_key_values_queries.register(receiver)
+    }
+
     fn perform_http_request(
         &mut self,
         request: http::Request,
@@ -1143,6 +1184,7 @@ impl ContractSyncRuntimeHandle {
             UserAction::SummarizeEvents(_context, updates) => {
                 code.summarize_events(updates).map(|()| None)
             }
+            UserAction::Migrate(_context) => code.migrate().map(|()| None),
         };
 
         let result = self.execute(application_id, signer, closure)?;
@@ -1368,6 +1410,7 @@ impl ContractRuntime for ContractSyncRuntimeHandle {
         owner: AccountOwner,
         spender: AccountOwner,
         amount: Amount,
+        limits: AllowanceLimits,
     ) -> Result<(), ExecutionError> {
         let this = self.inner();
         let current_application = this.current_application();
@@ -1379,6 +1422,35 @@ impl ContractRuntime for ContractSyncRuntimeHandle {
                 owner,
                 spender,
                 amount,
+                limits,
+                signer,
+                application_id,
+                callback,
+            })?
+            .recv_response()?;
+        Ok(())
+    }
+
+    fn approve_fee_sponsorship(
+        &mut self,
+        sponsor: AccountOwner,
+        max_fees: Amount,
+        expiry: Timestamp,
+    ) -> Result<(), ExecutionError> {
+        let this = self.inner();
+        let current_application = this.current_application();
+        let application_id = current_application.id;
+        let signer = current_application.signer;
+        let sponsorship = FeeSponsorship {
+            sponsor,
+            chain_id: this.chain_id,
+            max_fees,
+            expiry,
+        };
+
+        this.execution_state_sender
+            .send_request(|callback| ExecutionRequest::ApproveFeeSponsorship {
+                sponsorship,
                 signer,
                 application_id,
                 callback,
@@ -1682,6 +1754,47 @@ impl ContractRuntime for ContractSyncRuntimeHandle {
         Ok(app_id)
     }
 
+    fn upgrade_application(
+        &mut self,
+        application_id: ApplicationId,
+        module_id: ModuleId,
+    ) -> Result<(), ExecutionError> {
+        let (chain_id, caller_id) = {
+            let mut this = self.inner();
+            // An instance of the old code cannot be replaced in the middle of a transaction.
+            ensure!(
+                !this.loaded_applications.contains_key(&application_id),
+                ExecutionError::ApplicationUpgradeWhileLoaded(Box::new(application_id))
+            );
+            this.preloaded_applications.remove(&application_id);
+            (this.chain_id, this.current_application().id)
+        };
+
+        self.inner()
+            .execution_state_sender
+            .send_request(move |callback| ExecutionRequest::UpgradeApplication {
+                chain_id,
+                caller_id,
+                application_id,
+                module_id,
+                callback,
+            })?
+            .recv_response()??;
+
+        let contract = self
+            .inner()
+            .prepare_for_call(self.clone(), false, application_id)?;
+
+        contract
+            .try_lock()
+            .expect("Applications should not have reentrant calls")
+            .migrate()?;
+
+        self.inner().finish_call();
+
+        Ok(())
+    }
+
     fn create_data_blob(&mut self, bytes: Vec<u8>) -> Result<DataBlobHash, ExecutionError> {
         let blob = Blob::new_data(bytes);
         let blob_id = blob.id();
@@ -1722,6 +1835,37 @@ impl ContractRuntime for ContractSyncRuntimeHandle {
             .recv_response()
     }
 
+    fn schedule_operation_at(
+        &mut self,
+        timestamp: Timestamp,
+        operation: Vec<u8>,
+    ) -> Result<u32, ExecutionError> {
+        let mut this = self.inner();
+        let application_id = this.current_application().id;
+        this.resource_controller
+            .track_scheduled_operation(operation.len() as u64)?;
+        this.execution_state_sender
+            .send_request(|callback| ExecutionRequest::ScheduleOperation {
+                application_id,
+                timestamp,
+                bytes: operation,
+                callback,
+            })?
+            .recv_response()
+    }
+
+    fn cancel_scheduled_operation(&mut self, id: u32) -> Result<bool, ExecutionError> {
+        let this = self.inner();
+        let application_id = this.current_application().id;
+        this.execution_state_sender
+            .send_request(|callback| ExecutionRequest::CancelScheduledOperation {
+                application_id,
+                id,
+                callback,
+            })?
+            .recv_response()?
+    }
+
     fn write_batch(&mut self, batch: Batch) -> Result<(), ExecutionError> {
         let mut this = self.inner();
         let id = this.current_application().id;
@@ -1744,6 +1888,21 @@ impl ContractRuntime for ContractSyncRuntimeHandle {
             .recv_response()?;
         Ok(())
     }
+
+    fn is_tracing_evm(&mut self) -> Result<bool, ExecutionError> {
+        self.inner()
+            .execution_state_sender
+            .send_request(|callback| ExecutionRequest::IsTracingEvm { callback })?
+            .recv_response()
+    }
+
+    fn record_evm_trace(&mut self, trace: EvmTrace) -> Result<(), ExecutionError> {
+        let trace = Box::new(trace);
+        self.inner()
+            .execution_state_sender
+            .send_request(|callback| ExecutionRequest::RecordEvmTrace { trace, callback })?
+            .recv_response()
+    }
 }
 
 impl ServiceSyncRuntime {
@@ -7,32 +7,36 @@
 mod tests;
 
 use std::{
+    borrow::Cow,
     collections::{BTreeMap, BTreeSet},
     sync::Arc,
 };
 
 use allocative::Allocative;
+use async_graphql::SimpleObject;
 use custom_debug_derive::Debug;
 use linera_base::{
-    crypto::CryptoHash,
+    crypto::{AccountSignature, CryptoHash},
     data_types::{
-        Amount, ApplicationPermissions, ArithmeticError, Blob, BlobContent, BlockHeight,
-        ChainDescription, ChainOrigin, Cursor, Epoch, InitialChainConfig, OracleResponse,
-        Timestamp,
+        AllowanceLimits, AllowanceUsage, Amount, ApplicationPermissions, ArithmeticError, Blob,
+        BlobContent, BlockHeight, ChainDescription, ChainOrigin, Cursor, Epoch, FeeSponsorship,
+        InitialChainConfig, LockedTranche, OracleResponse, Timestamp, VestingSchedule,
     },
     ensure, hex_debug,
     identifiers::{
-        Account, AccountOwner, BlobId, BlobType, ChainId, EventId, ModuleId, OwnerSpender, StreamId,
+        Account, AccountOwner, BlobId, BlobType, ChainId, EventId, GenericApplicationId, ModuleId,
+        OwnerSpender, StreamId, StreamName,
     },
     ownership::{ChainOwnership, TimeoutConfig},
 };
 use linera_views::{
+    common::CustomSerialize,
     context::Context,
     lazy_register_view::LazyRegisterView,
     map_view::MapView,
     register_view::RegisterView,
-    set_view::SetView,
-    views::{ClonableView, ReplaceContext, View},
+    set_view::{CustomSetView, SetView},
+    views::{ClonableView, HashableView, ReplaceContext, View},
     ViewError,
 };
 use serde::{Deserialize, Serialize};
@@ -40,15 +44,45 @@ use serde::{Deserialize, Serialize};
 #[cfg(test)]
 use crate::test_utils::SystemExecutionState;
 use crate::{
-    committee::Committee, util::OracleResponseExt as _, ApplicationDescription, ApplicationId,
-    ExecutionError, ExecutionRuntimeContext, MessageContext, MessageKind, OperationContext,
-    OutgoingMessage, QueryContext, QueryOutcome, ResourceController, TransactionTracker,
+    committee::Committee, resources::FeeSponsor, util::OracleResponseExt as _,
+    ApplicationDescription, ApplicationId, ExecutionError, ExecutionRuntimeContext, MessageContext,
+    MessageKind, OperationContext, OutgoingMessage, QueryContext, QueryOutcome, ResourceController,
+    TransactionTracker,
 };
 
 /// The event stream name for new epochs and committees.
 pub static EPOCH_STREAM_NAME: &[u8] = &[0];
 /// The event stream name for removed epochs.
 pub static REMOVED_EPOCH_STREAM_NAME: &[u8] = &[1];
+/// The prefix of the event stream names for application upgrades. Each application has its
+/// own stream on its creator chain, named by this prefix followed by the application ID.
+pub static APPLICATION_UPGRADE_STREAM_PREFIX: &[u8] = &[2];
+
+/// The maximum number of tranches with distinct vesting schedules that can be locked in a
+/// single account at a time. Tranches with identical schedules are merged.
+pub const MAX_LOCKED_TRANCHES: usize = 16;
+
+/// Returns whether the given stream is the upgrade stream of some application.
+pub fn is_application_upgrade_stream(stream_id: &StreamId) -> bool {
+    matches!(stream_id.application_id, GenericApplicationId::System)
+        && stream_id
+            .stream_name
+            .0
+            .starts_with(APPLICATION_UPGRADE_STREAM_PREFIX)
+}
+
+/// Returns the name of the system event stream on which the creator chain of the given
+/// application publishes its upgrades.
+pub fn application_upgrade_stream_name(application_id: ApplicationId) -> StreamName {
+    let hash = application_id.application_description_hash;
+    StreamName(
+        [
+            APPLICATION_UPGRADE_STREAM_PREFIX,
+            hash.as_bytes().as_slice(),
+        ]
+        .concat(),
+    )
+}
 
 /// The data stored in an epoch creation event.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,6 +93,17 @@ pub struct EpochEventData {
     pub timestamp: Timestamp,
 }
 
+/// The data stored in an application upgrade event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplicationUpgradeEventData {
+    /// The upgraded application.
+    pub application_id: ApplicationId,
+    /// The module the application was upgraded to.
+    pub module_id: ModuleId,
+    /// The module the application ran before this upgrade.
+    pub previous_module_id: ModuleId,
+}
+
 /// The number of times the [`SystemOperation::OpenChain`] was executed.
 #[cfg(with_metrics)]
 pub(crate) mod metrics {
@@ -91,7 +136,7 @@ pub struct ChainProgress {
 }
 
 /// A view accessing the execution state of the system of a chain.
-#[derive(Debug, ClonableView, View, Allocative)]
+#[derive(Debug, ClonableView, HashableView, Allocative)]
 #[allocative(bound = "C")]
 pub struct SystemExecutionStateView<C> {
     /// How the chain was created. May be unknown for inactive chains.
@@ -109,8 +154,17 @@ pub struct SystemExecutionStateView<C> {
     pub balance: RegisterView<C, Amount>,
     /// Balances attributed to a given owner.
     pub balances: MapView<C, AccountOwner, Amount>,
+    /// The parts of the owners' balances that are locked until they vest. Tranches are
+    /// removed once fully vested.
+    pub locked_balances: MapView<C, AccountOwner, Vec<LockedTranche>>,
     /// Allowances for spending from one account by another.
     pub allowances: MapView<C, OwnerSpender, Amount>,
+    /// The expiry and per-period caps of the allowances that have any, and how much was
+    /// spent in the current period.
+    pub allowance_limits: MapView<C, OwnerSpender, AllowanceUsage>,
+    /// The fees paid so far under each fee sponsorship that was used or approved on this
+    /// chain, indexed by the hash of the sponsorship.
+    pub fee_sponsorships: MapView<C, CryptoHash, Amount>,
     /// Whether this chain has been closed.
     pub closed: RegisterView<C, bool>,
     /// Permissions for applications on this chain.
@@ -142,6 +196,18 @@ pub struct SystemExecutionStateView<C> {
     pub pending_checkpoint_ack_targets: SetView<C, ChainId>,
     /// The most recent block's timestamp and cumulative transaction/message counts.
     pub progress: RegisterView<C, ChainProgress>,
+    /// Operations scheduled by applications to be executed once the block timestamp
+    /// reaches a given time, indexed by their ID.
+    pub scheduled_operations: MapView<C, u32, ScheduledOperation>,
+    /// The ID that will be assigned to the next scheduled operation.
+    pub next_scheduled_operation_id: RegisterView<C, u32>,
+    /// The keys of the scheduled operations, ordered by scheduled time and then by ID.
+    pub scheduled_operation_queue: CustomSetView<C, ScheduledOperationKey>,
+    /// The current modules of the applications that were upgraded on this chain.
+    pub application_upgrades: MapView<C, ApplicationId, ApplicationUpgrade>,
+    /// The owners or governing applications allowed to upgrade the applications created on
+    /// this chain. Applications without an entry can never be upgraded.
+    pub upgrade_authorities: MapView<C, ApplicationId, AccountOwner>,
 }
 
 impl<C: Context, C2: Context> ReplaceContext<C2> for SystemExecutionStateView<C> {
@@ -159,7 +225,10 @@ impl<C: Context, C2: Context> ReplaceContext<C2> for SystemExecutionStateView<C>
             ownership: self.ownership.with_context(ctx.clone()).await,
             balance: self.balance.with_context(ctx.clone()).await,
             balances: self.balances.with_context(ctx.clone()).await,
+            locked_balances: self.locked_balances.with_context(ctx.clone()).await,
             allowances: self.allowances.with_context(ctx.clone()).await,
+            allowance_limits: self.allowance_limits.with_context(ctx.clone()).await,
+            fee_sponsorships: self.fee_sponsorships.with_context(ctx.clone()).await,
             closed: self.closed.with_context(ctx.clone()).await,
             application_permissions: self.application_permissions.with_context(ctx.clone()).await,
             used_blobs: self.used_blobs.with_context(ctx.clone()).await,
@@ -174,6 +243,17 @@ impl<C: Context, C2: Context> ReplaceContext<C2> for SystemExecutionStateView<C>
                 .with_context(ctx.clone())
                 .await,
             progress: self.progress.with_context(ctx.clone()).await,
+            scheduled_operations: self.scheduled_operations.with_context(ctx.clone()).await,
+            next_scheduled_operation_id: self
+                .next_scheduled_operation_id
+                .with_context(ctx.clone())
+                .await,
+            scheduled_operation_queue: self
+                .scheduled_operation_queue
+                .with_context(ctx.clone())
+                .await,
+            application_upgrades: self.application_upgrades.with_context(ctx.clone()).await,
+            upgrade_authorities: self.upgrade_authorities.with_context(ctx.clone()).await,
         }
     }
 }
@@ -210,6 +290,60 @@ impl EventSubscriptions {
     }
 }
 
+/// A user operation that an application scheduled to be executed on its chain in the
+/// first block whose timestamp is at least `timestamp`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Allocative, SimpleObject)]
+pub struct ScheduledOperation {
+    /// The application that scheduled the operation and that will execute it.
+    pub application_id: ApplicationId,
+    /// The earliest block timestamp at which the operation can be executed.
+    pub timestamp: Timestamp,
+    /// The serialized operation.
+    #[serde(with = "serde_bytes")]
+    #[debug(with = "hex_debug")]
+    pub bytes: Vec<u8>,
+}
+
+/// The position of a scheduled operation in the chain's queue: operations are due in the order
+/// of their scheduled time, and then of their ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ScheduledOperationKey {
+    /// The earliest block timestamp at which the operation can be executed.
+    pub timestamp: Timestamp,
+    /// The ID of the operation.
+    pub id: u32,
+}
+
+impl CustomSerialize for ScheduledOperationKey {
+    fn to_custom_bytes(&self) -> Result<Vec<u8>, ViewError> {
+        let mut bytes = self.timestamp.micros().to_be_bytes().to_vec();
+        bytes.extend_from_slice(&self.id.to_be_bytes());
+        Ok(bytes)
+    }
+
+    fn from_custom_bytes(bytes: &[u8]) -> Result<Self, ViewError> {
+        let (timestamp, id) = bytes
+            .split_first_chunk::<8>()
+            .ok_or(ViewError::PostLoadValuesError)?;
+        let id: [u8; 4] = id.try_into().map_err(|_| ViewError::PostLoadValuesError)?;
+        Ok(Self {
+            timestamp: Timestamp::from(u64::from_be_bytes(*timestamp)),
+            id: u32::from_be_bytes(id),
+        })
+    }
+}
+
+/// The module that an upgraded application runs on a chain, replacing the one in its
+/// description.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Allocative, SimpleObject)]
+pub struct ApplicationUpgrade {
+    /// The module the application was last upgraded to.
+    pub module_id: ModuleId,
+    /// The number of upgrades applied so far. This is also the index of the next upgrade
+    /// event in the application's upgrade stream.
+    pub version: u32,
+}
+
 /// The initial configuration for a new chain.
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize, Allocative)]
 pub struct OpenChainConfig {
@@ -302,6 +436,9 @@ pub enum SystemOperation {
         instantiation_argument: Vec<u8>,
         #[debug(skip_if = Vec::is_empty)]
         required_application_ids: Vec<ApplicationId>,
+        /// The owner or governing application allowed to upgrade the application.
+        #[debug(skip_if = Option::is_none)]
+        upgrade_authority: Option<AccountOwner>,
     },
     /// Operations that are only allowed on the admin chain.
     Admin(AdminOperation),
@@ -322,6 +459,40 @@ pub enum SystemOperation {
     /// future nodes to bootstrap from the snapshot instead of replaying the chain's
     /// history. Subject to a strict set of preconditions on the chain's state.
     Checkpoint,
+    /// Executes an operation previously scheduled by an application with
+    /// `schedule_operation_at`. The block timestamp must not be earlier than the
+    /// scheduled time.
+    ExecuteScheduledOperation { id: u32 },
+    /// Transfers `amount` units of value from the given owner's account to the
+    /// `beneficiary`'s account on this chain, where they remain locked until they vest
+    /// according to the `schedule`. A beneficiary can have at most [`MAX_LOCKED_TRANCHES`]
+    /// distinct schedules at a time; amounts locked with an identical schedule are merged.
+    LockTokens {
+        owner: AccountOwner,
+        beneficiary: AccountOwner,
+        amount: Amount,
+        schedule: VestingSchedule,
+    },
+    /// Switches an application created on this chain to a new module and calls its
+    /// `migrate` entrypoint. This must be authorized by the application's upgrade
+    /// authority. Other chains adopt the upgrade by executing
+    /// [`SystemOperation::ProcessApplicationUpgrade`], or automatically before executing a
+    /// message sent by the upgraded application.
+    UpgradeApplication {
+        application_id: ApplicationId,
+        module_id: ModuleId,
+    },
+    /// Adopts the next upgrade of an application published by its creator chain, and calls
+    /// the application's `migrate` entrypoint.
+    ProcessApplicationUpgrade { application_id: ApplicationId },
+    /// Makes the sponsor pay the fees of this block, under the given `sponsorship`. Unless
+    /// the sponsor approved the sponsorship on this chain before, it must be signed by the
+    /// sponsor. A block can contain at most one such operation.
+    UseFeeSponsorship {
+        sponsorship: FeeSponsorship,
+        #[debug(skip_if = Option::is_none)]
+        signature: Option<AccountSignature>,
+    },
 }
 
 /// Operations that are only allowed on the admin chain.
@@ -390,6 +561,37 @@ pub struct CreateApplicationResult {
     pub app_id: ApplicationId,
 }
 
+impl<C: Context> SystemExecutionStateView<C> {
+    /// Returns the part of `owner`'s balance that is still locked at the current block's
+    /// timestamp.
+    pub async fn locked_balance(&self, owner: &AccountOwner) -> Result<Amount, ViewError> {
+        let Some(tranches) = self.locked_balances.get(owner).await? else {
+            return Ok(Amount::ZERO);
+        };
+        Ok(total_locked(&tranches, self.progress.get().timestamp))
+    }
+
+    /// Returns the description from which the application's code is loaded on this chain:
+    /// if the application was upgraded here, its module is replaced by the current one.
+    /// Since code caches are keyed by the hash of this description, each module of an
+    /// upgraded application is cached separately.
+    pub async fn code_description<'a>(
+        &self,
+        application_id: ApplicationId,
+        description: &'a ApplicationDescription,
+    ) -> Result<Cow<'a, ApplicationDescription>, ViewError> {
+        Ok(
+            match self.application_upgrades.get(&application_id).await? {
+                Some(upgrade) => Cow::Owned(ApplicationDescription {
+                    module_id: upgrade.module_id,
+                    ..description.clone()
+                }),
+                None => Cow::Borrowed(description),
+            },
+        )
+    }
+}
+
 impl<C> SystemExecutionStateView<C>
 where
     C: Context + Clone + 'static,
@@ -556,6 +758,7 @@ where
                 parameters,
                 instantiation_argument,
                 required_application_ids,
+                upgrade_authority,
             } => {
                 let CreateApplicationResult { app_id } = self
                     .create_application(
@@ -564,6 +767,7 @@ where
                         module_id,
                         parameters,
                         required_application_ids,
+                        upgrade_authority,
                         txn_tracker,
                     )
                     .await?;
@@ -673,6 +877,45 @@ where
                     "SystemOperation::Checkpoint must be dispatched at ExecutionStateView level",
                 ));
             }
+            LockTokens {
+                owner,
+                beneficiary,
+                amount,
+                schedule,
+            } => {
+                self.lock_tokens(
+                    context.authenticated_owner,
+                    owner,
+                    beneficiary,
+                    amount,
+                    schedule,
+                )
+                .await?;
+            }
+            ExecuteScheduledOperation { .. } => {
+                return Err(ExecutionError::InternalError(
+                    "SystemOperation::ExecuteScheduledOperation must be dispatched at \
+                     ExecutionStateView level",
+                ));
+            }
+            UpgradeApplication { .. } | ProcessApplicationUpgrade { .. } => {
+                return Err(ExecutionError::InternalError(
+                    "Application upgrades must be dispatched at ExecutionStateView level",
+                ));
+            }
+            UseFeeSponsorship { sponsorship, .. } => {
+                // The sponsor was already set up when the block execution started.
+                let sponsorship_hash = CryptoHash::new(&sponsorship);
+                ensure!(
+                    resource_controller
+                        .sponsor
+                        .is_some_and(|sponsor| sponsor.sponsorship_hash == sponsorship_hash),
+                    ExecutionError::InternalError(
+                        "SystemOperation::UseFeeSponsorship must be applied at the start of \
+                         the block",
+                    )
+                );
+            }
         }
 
         Ok(new_application)
@@ -756,6 +999,70 @@ where
         self.credit_or_send_message(source, recipient, amount).await
     }
 
+    /// Transfers `amount` from `source` to `beneficiary` on this chain and locks it there
+    /// until it vests according to `schedule`.
+    pub async fn lock_tokens(
+        &mut self,
+        authenticated_owner: Option<AccountOwner>,
+        source: AccountOwner,
+        beneficiary: AccountOwner,
+        amount: Amount,
+        schedule: VestingSchedule,
+    ) -> Result<(), ExecutionError> {
+        ensure!(
+            beneficiary != AccountOwner::CHAIN,
+            ExecutionError::LockedChainBalance
+        );
+        ensure!(schedule.is_valid(), ExecutionError::InvalidVestingSchedule);
+        self.prune_locked_balance(&beneficiary).await?;
+        let tranches = self
+            .locked_balances
+            .get(&beneficiary)
+            .await?
+            .unwrap_or_default();
+        ensure!(
+            tranches.len() < MAX_LOCKED_TRANCHES
+                || tranches.iter().any(|tranche| tranche.schedule == schedule),
+            ExecutionError::TooManyLockedTranches(beneficiary)
+        );
+        self.transfer(
+            authenticated_owner,
+            None,
+            source,
+            Account::new(self.context().extra().chain_id(), beneficiary),
+            amount,
+        )
+        .await?;
+        let tranches = self
+            .locked_balances
+            .get_mut_or_default(&beneficiary)
+            .await?;
+        match tranches
+            .iter_mut()
+            .find(|tranche| tranche.schedule == schedule)
+        {
+            Some(tranche) => tranche.amount.try_add_assign(amount)?,
+            None => tranches.push(LockedTranche { amount, schedule }),
+        }
+        Ok(())
+    }
+
+    /// Removes `owner`'s fully vested tranches, and returns the amount that is still locked.
+    async fn prune_locked_balance(&mut self, owner: &AccountOwner) -> Result<Amount, ViewError> {
+        let Some(mut tranches) = self.locked_balances.get(owner).await? else {
+            return Ok(Amount::ZERO);
+        };
+        let now = self.progress.get().timestamp;
+        let tranche_count = tranches.len();
+        tranches.retain(|tranche| !tranche.is_fully_vested(now));
+        if tranches.is_empty() {
+            self.locked_balances.remove(owner)?;
+        } else if tranches.len() < tranche_count {
+            self.locked_balances.insert(owner, tranches.clone())?;
+        }
+        Ok(total_locked(&tranches, now))
+    }
+
     /// Claims `amount` from `source`'s account on `target_id` and transfers it to `recipient`.
     pub async fn claim(
         &mut self,
@@ -792,7 +1099,75 @@ where
         }
     }
 
-    /// Sets the allowance that `spender` may transfer on behalf of `owner` to `amount`.
+    /// Approves the fee `sponsorship` on this chain, so that blocks can use it without the
+    /// sponsor's signature.
+    pub async fn approve_fee_sponsorship(
+        &mut self,
+        authenticated_owner: Option<AccountOwner>,
+        authenticated_application_id: Option<ApplicationId>,
+        sponsorship: FeeSponsorship,
+    ) -> Result<(), ExecutionError> {
+        ensure!(
+            authenticated_owner == Some(sponsorship.sponsor)
+                || authenticated_application_id.map(AccountOwner::from)
+                    == Some(sponsorship.sponsor),
+            ExecutionError::UnauthorizedFeeSponsorship(sponsorship.sponsor)
+        );
+        let chain_id = self.context().extra().chain_id();
+        ensure!(
+            sponsorship.chain_id == chain_id,
+            ExecutionError::FeeSponsorshipForOtherChain(sponsorship.chain_id)
+        );
+        self.fee_sponsorships
+            .get_mut_or_default(&CryptoHash::new(&sponsorship))
+            .await?;
+        Ok(())
+    }
+
+    /// Checks that the `sponsorship` can be used by the current block, i.e. that it is for
+    /// this chain, has not expired, and was either approved on this chain or signed by the
+    /// sponsor. Returns the [`FeeSponsor`] that pays the block's fees.
+    pub async fn fee_sponsor(
+        &mut self,
+        sponsorship: &FeeSponsorship,
+        signature: Option<&AccountSignature>,
+    ) -> Result<FeeSponsor, ExecutionError> {
+        let chain_id = self.context().extra().chain_id();
+        ensure!(
+            sponsorship.chain_id == chain_id,
+            ExecutionError::FeeSponsorshipForOtherChain(sponsorship.chain_id)
+        );
+        ensure!(
+            self.progress.get().timestamp < sponsorship.expiry,
+            ExecutionError::FeeSponsorshipExpired(sponsorship.expiry)
+        );
+        let sponsorship_hash = CryptoHash::new(sponsorship);
+        if !self
+            .fee_sponsorships
+            .contains_key(&sponsorship_hash)
+            .await?
+        {
+            let is_signed = signature.is_some_and(|signature| {
+                signature.owner() == sponsorship.sponsor && signature.verify(sponsorship).is_ok()
+            });
+            ensure!(
+                is_signed,
+                ExecutionError::UnauthorizedFeeSponsorship(sponsorship.sponsor)
+            );
+            self.fee_sponsorships
+                .insert(&sponsorship_hash, Amount::ZERO)?;
+        }
+        Ok(FeeSponsor {
+            owner: sponsorship.sponsor,
+            sponsorship_hash,
+            max_fees: sponsorship.max_fees,
+            paid: Amount::ZERO,
+            paid_by_others: Amount::ZERO,
+        })
+    }
+
+    /// Sets the allowance that `spender` may transfer on behalf of `owner` to `amount`,
+    /// replacing any previous allowance and its `limits`.
     pub async fn approve(
         &mut self,
         authenticated_owner: Option<AccountOwner>,
@@ -800,6 +1175,7 @@ where
         owner: AccountOwner,
         spender: AccountOwner,
         amount: Amount,
+        limits: AllowanceLimits,
     ) -> Result<(), ExecutionError> {
         ensure!(
             authenticated_owner == Some(owner)
@@ -810,8 +1186,22 @@ where
         let owner_spender = OwnerSpender::new(owner, spender);
         if amount == Amount::ZERO {
             self.allowances.remove(&owner_spender)?;
+            self.allowance_limits.remove(&owner_spender)?;
             return Ok(());
         }
+        if limits == AllowanceLimits::default() {
+            self.allowance_limits.remove(&owner_spender)?;
+        } else {
+            let now = self.progress.get().timestamp;
+            let usage = AllowanceUsage::new(limits, now).map_err(|error| {
+                ExecutionError::AllowanceLimit {
+                    owner,
+                    spender,
+                    error,
+                }
+            })?;
+            self.allowance_limits.insert(&owner_spender, usage)?;
+        }
         let allowance = self.allowances.get_mut_or_default(&owner_spender).await?;
         *allowance = amount;
 
@@ -838,8 +1228,21 @@ where
             ExecutionError::IncorrectTransferAmount
         );
 
-        // Debit from allowance
+        // Check and record the spending against the allowance's limits, if any.
         let owner_spender = OwnerSpender::new(owner, spender);
+        if let Some(mut usage) = self.allowance_limits.get(&owner_spender).await? {
+            let now = self.progress.get().timestamp;
+            usage
+                .record_spending(now, amount)
+                .map_err(|error| ExecutionError::AllowanceLimit {
+                    owner,
+                    spender,
+                    error,
+                })?;
+            self.allowance_limits.insert(&owner_spender, usage)?;
+        }
+
+        // Debit from allowance
         let allowance = self.allowances.get_mut_or_default(&owner_spender).await?;
 
         allowance
@@ -852,6 +1255,7 @@ where
 
         if allowance.is_zero() {
             self.allowances.remove(&owner_spender)?;
+            self.allowance_limits.remove(&owner_spender)?;
         }
 
         // Debit from owner's balance
@@ -867,6 +1271,11 @@ where
         account: &AccountOwner,
         amount: Amount,
     ) -> Result<(), ExecutionError> {
+        let locked = if account == &AccountOwner::CHAIN {
+            Amount::ZERO
+        } else {
+            self.prune_locked_balance(account).await?
+        };
         let balance = if account == &AccountOwner::CHAIN {
             self.balance.get_mut()
         } else {
@@ -878,12 +1287,22 @@ where
             })?
         };
 
-        balance
-            .try_sub_assign(amount)
-            .map_err(|_| ExecutionError::InsufficientBalance {
+        let new_balance =
+            balance
+                .try_sub(amount)
+                .map_err(|_| ExecutionError::InsufficientBalance {
+                    balance: *balance,
+                    account: *account,
+                })?;
+        ensure!(
+            new_balance >= locked,
+            ExecutionError::InsufficientUnlockedBalance {
                 balance: *balance,
+                locked,
                 account: *account,
-            })?;
+            }
+        );
+        *balance = new_balance;
 
         if account != &AccountOwner::CHAIN && balance.is_zero() {
             self.balances.remove(account)?;
@@ -1040,7 +1459,149 @@ where
         self.closed.set(true);
     }
 
+    /// Stores an operation of `application_id` to be executed once the block timestamp
+    /// reaches `timestamp`, and returns its ID.
+    pub async fn schedule_operation(
+        &mut self,
+        application_id: ApplicationId,
+        timestamp: Timestamp,
+        bytes: Vec<u8>,
+    ) -> Result<u32, ExecutionError> {
+        self.check_scheduled_operation_permitted(application_id)
+            .await?;
+        let id = *self.next_scheduled_operation_id.get();
+        let next_id = id.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+        self.next_scheduled_operation_id.set(next_id);
+        let operation = ScheduledOperation {
+            application_id,
+            timestamp,
+            bytes,
+        };
+        self.scheduled_operations.insert(&id, operation)?;
+        self.scheduled_operation_queue
+            .insert(&ScheduledOperationKey { timestamp, id })?;
+        Ok(id)
+    }
+
+    /// Removes the scheduled operation with the given ID. Only the application that
+    /// scheduled it may cancel it. Returns `false` if there was no such operation.
+    pub async fn cancel_scheduled_operation(
+        &mut self,
+        application_id: ApplicationId,
+        id: u32,
+    ) -> Result<bool, ExecutionError> {
+        let Some(operation) = self.scheduled_operations.get(&id).await? else {
+            return Ok(false);
+        };
+        ensure!(
+            operation.application_id == application_id,
+            ExecutionError::UnauthorizedScheduledOperationCancellation { id, application_id }
+        );
+        self.remove_scheduled_operation(id, &operation)?;
+        Ok(true)
+    }
+
+    /// Removes and returns the scheduled operation with the given ID, provided that it is
+    /// due at the given block timestamp.
+    pub async fn take_due_scheduled_operation(
+        &mut self,
+        id: u32,
+        timestamp: Timestamp,
+    ) -> Result<ScheduledOperation, ExecutionError> {
+        let operation = self.due_scheduled_operation(id, timestamp).await?;
+        // The chain's permissions may have changed since the operation was scheduled.
+        self.check_scheduled_operation_permitted(operation.application_id)
+            .await?;
+        self.remove_scheduled_operation(id, &operation)?;
+        Ok(operation)
+    }
+
+    /// Removes the scheduled operation with the given ID without executing it, provided that
+    /// it is due at the given block timestamp. This is how a block drops a due operation that
+    /// failed, so that it doesn't fail every later block.
+    pub async fn drop_due_scheduled_operation(
+        &mut self,
+        id: u32,
+        timestamp: Timestamp,
+    ) -> Result<(), ExecutionError> {
+        let operation = self.due_scheduled_operation(id, timestamp).await?;
+        self.remove_scheduled_operation(id, &operation)?;
+        Ok(())
+    }
+
+    /// Returns the scheduled operation with the given ID, provided that it is due at the given
+    /// block timestamp.
+    async fn due_scheduled_operation(
+        &self,
+        id: u32,
+        timestamp: Timestamp,
+    ) -> Result<ScheduledOperation, ExecutionError> {
+        let operation = self
+            .scheduled_operations
+            .get(&id)
+            .await?
+            .ok_or(ExecutionError::ScheduledOperationNotFound(id))?;
+        ensure!(
+            operation.timestamp <= timestamp,
+            ExecutionError::ScheduledOperationNotDue {
+                id,
+                scheduled: operation.timestamp,
+                block_timestamp: timestamp,
+            }
+        );
+        Ok(operation)
+    }
+
+    fn remove_scheduled_operation(
+        &mut self,
+        id: u32,
+        operation: &ScheduledOperation,
+    ) -> Result<(), ViewError> {
+        self.scheduled_operations.remove(&id)?;
+        self.scheduled_operation_queue
+            .remove(&ScheduledOperationKey {
+                timestamp: operation.timestamp,
+                id,
+            })
+    }
+
+    /// Checks that the application permissions of the chain allow the application to
+    /// execute operations, as a scheduled operation runs like an operation of that
+    /// application.
+    async fn check_scheduled_operation_permitted(
+        &self,
+        application_id: ApplicationId,
+    ) -> Result<(), ExecutionError> {
+        let app_permissions = self.application_permissions.get().await?;
+        ensure!(
+            app_permissions.can_execute_operations(&GenericApplicationId::User(application_id)),
+            ExecutionError::ScheduledOperationNotPermitted(application_id)
+        );
+        Ok(())
+    }
+
+    /// Returns the IDs of at most `limit` scheduled operations that are due at the given
+    /// timestamp, ordered by scheduled time and then by ID.
+    pub async fn due_scheduled_operations(
+        &self,
+        timestamp: Timestamp,
+        limit: usize,
+    ) -> Result<Vec<u32>, ViewError> {
+        let mut due = Vec::new();
+        self.scheduled_operation_queue
+            .for_each_index_while(|key| {
+                if key.timestamp > timestamp || due.len() >= limit {
+                    return Ok(false);
+                }
+                due.push(key.id);
+                Ok(true)
+            })
+            .await?;
+        Ok(due)
+    }
+
     /// Creates a new application from the given module and arguments, returning its ID.
+    #[expect(clippy::too_many_arguments)]
     pub async fn create_application(
         &mut self,
         chain_id: ChainId,
@@ -1048,6 +1609,7 @@ where
         module_id: ModuleId,
         parameters: Vec<u8>,
         required_application_ids: Vec<ApplicationId>,
+        upgrade_authority: Option<AccountOwner>,
         txn_tracker: &mut TransactionTracker,
     ) -> Result<CreateApplicationResult, ExecutionError> {
         let application_index = txn_tracker.next_application_index();
@@ -1074,9 +1636,138 @@ where
         self.used_blobs.insert(&blob.id())?;
         txn_tracker.add_created_blob(blob);
 
-        Ok(CreateApplicationResult {
-            app_id: ApplicationId::from(&application_description),
-        })
+        let app_id = ApplicationId::from(&application_description);
+        // The authority is kept out of the description so that it doesn't affect the
+        // application ID.
+        if let Some(upgrade_authority) = upgrade_authority {
+            self.upgrade_authorities
+                .insert(&app_id, upgrade_authority)?;
+        }
+        Ok(CreateApplicationResult { app_id })
+    }
+
+    /// Switches an application created on this chain to `module_id`, and publishes the
+    /// upgrade on the application's upgrade stream so that other chains can adopt it.
+    ///
+    /// Unlike other system streams, upgrade streams don't prevent the creator chain from
+    /// being checkpointed: their events are never pruned.
+    pub async fn upgrade_application(
+        &mut self,
+        chain_id: ChainId,
+        authenticated_owner: Option<AccountOwner>,
+        authenticated_application_id: Option<ApplicationId>,
+        application_id: ApplicationId,
+        module_id: ModuleId,
+        txn_tracker: &mut TransactionTracker,
+    ) -> Result<(), ExecutionError> {
+        let description = self
+            .describe_application(application_id, txn_tracker)
+            .await?;
+        ensure!(
+            description.creator_chain_id == chain_id,
+            ExecutionError::ApplicationUpgradeOnNonCreatorChain(Box::new(application_id))
+        );
+        let authority = self.upgrade_authorities.get(&application_id).await?;
+        ensure!(
+            authority.is_some()
+                && (authenticated_owner == authority
+                    || authenticated_application_id.map(AccountOwner::from) == authority),
+            ExecutionError::UnauthorizedApplicationUpgrade(Box::new(application_id))
+        );
+        let previous_module_id = self
+            .code_description(application_id, &description)
+            .await?
+            .module_id;
+        let index = self
+            .apply_application_upgrade(application_id, &description, module_id, txn_tracker)
+            .await?;
+        let event_data = ApplicationUpgradeEventData {
+            application_id,
+            module_id,
+            previous_module_id,
+        };
+        let stream_id = StreamId::system(application_upgrade_stream_name(application_id));
+        let next_index = index.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+        self.stream_event_counts.insert(&stream_id, next_index)?;
+        txn_tracker.add_event(stream_id, index, bcs::to_bytes(&event_data)?);
+        Ok(())
+    }
+
+    /// Adopts the next upgrade of the given application, as published on the upgrade
+    /// stream of its creator chain. Upgrades are adopted one at a time and in order.
+    pub async fn process_application_upgrade(
+        &mut self,
+        application_id: ApplicationId,
+        txn_tracker: &mut TransactionTracker,
+    ) -> Result<(), ExecutionError> {
+        let description = self
+            .describe_application(application_id, txn_tracker)
+            .await?;
+        let index = self.application_version(&application_id).await?;
+        let event_id = EventId {
+            chain_id: description.creator_chain_id,
+            stream_id: StreamId::system(application_upgrade_stream_name(application_id)),
+            index,
+        };
+        let bytes = txn_tracker
+            .oracle(|| async {
+                let bytes = self.get_event(event_id.clone()).await?;
+                Ok(OracleResponse::Event(
+                    event_id.clone(),
+                    Arc::unwrap_or_clone(bytes),
+                ))
+            })
+            .await?
+            .to_event(&event_id)?;
+        let event_data: ApplicationUpgradeEventData = bcs::from_bytes(&bytes)?;
+        ensure!(
+            event_data.application_id == application_id,
+            ExecutionError::InvalidApplicationUpgradeEvent(Box::new(application_id))
+        );
+        self.apply_application_upgrade(
+            application_id,
+            &description,
+            event_data.module_id,
+            txn_tracker,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the number of upgrades of the given application adopted on this chain.
+    pub async fn application_version(
+        &self,
+        application_id: &ApplicationId,
+    ) -> Result<u32, ViewError> {
+        Ok(self
+            .application_upgrades
+            .get(application_id)
+            .await?
+            .map_or(0, |upgrade| upgrade.version))
+    }
+
+    /// Records that the application now runs `module_id` on this chain, and returns the
+    /// index of this upgrade.
+    async fn apply_application_upgrade(
+        &mut self,
+        application_id: ApplicationId,
+        description: &ApplicationDescription,
+        module_id: ModuleId,
+        txn_tracker: &mut TransactionTracker,
+    ) -> Result<u32, ExecutionError> {
+        ensure!(
+            module_id.vm_runtime == description.module_id.vm_runtime,
+            ExecutionError::ApplicationUpgradeVmMismatch(Box::new(application_id))
+        );
+        let blob_ids = self.check_bytecode_blobs(&module_id, txn_tracker).await?;
+        for blob_id in blob_ids {
+            self.blob_used(txn_tracker, blob_id).await?;
+        }
+        let index = self.application_version(&application_id).await?;
+        let version = index.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+        self.application_upgrades
+            .insert(&application_id, ApplicationUpgrade { module_id, version })?;
+        Ok(index)
     }
 
     async fn check_required_applications(
@@ -1191,3 +1882,10 @@ where
         Ok(blob_ids)
     }
 }
+
+/// Returns the amount of the `tranches` that is still locked at time `now`.
+fn total_locked(tranches: &[LockedTranche], now: Timestamp) -> Amount {
+    tranches.iter().fold(Amount::ZERO, |locked, tranche| {
+        locked.saturating_add(tranche.locked_amount(now))
+    })
+}
@@ -137,6 +137,8 @@ type SummarizeEventsHandler = Box<
         + Send
         + Sync,
 >;
+type MigrateHandler =
+    Box<dyn FnOnce(&mut ContractSyncRuntimeHandle) -> Result<(), ExecutionError> + Send + Sync>;
 type FinalizeHandler =
     Box<dyn FnOnce(&mut ContractSyncRuntimeHandle) -> Result<(), ExecutionError> + Send + Sync>;
 type HandleQueryHandler = Box<
@@ -158,6 +160,8 @@ pub enum ExpectedCall {
     ProcessStreams(#[debug(skip)] ProcessStreamHandler),
     /// An expected call to [`UserContract::summarize_events`].
     SummarizeEvents(#[debug(skip)] SummarizeEventsHandler),
+    /// An expected call to [`UserContract::migrate`].
+    Migrate(#[debug(skip)] MigrateHandler),
     /// An expected call to [`UserContract::finalize`].
     Finalize(#[debug(skip)] FinalizeHandler),
     /// An expected call to [`UserService::handle_query`].
@@ -172,6 +176,7 @@ impl Display for ExpectedCall {
             ExpectedCall::ExecuteMessage(_) => "execute_message",
             ExpectedCall::ProcessStreams(_) => "process_streams",
             ExpectedCall::SummarizeEvents(_) => "summarize_events",
+            ExpectedCall::Migrate(_) => "migrate",
             ExpectedCall::Finalize(_) => "finalize",
             ExpectedCall::HandleQuery(_) => "handle_query",
         };
@@ -240,6 +245,17 @@ impl ExpectedCall {
         ExpectedCall::SummarizeEvents(Box::new(handler))
     }
 
+    /// Creates an [`ExpectedCall`] to the [`MockApplicationInstance`]'s [`UserContract::migrate`]
+    /// implementation, which is handled by the provided `handler`.
+    pub fn migrate(
+        handler: impl FnOnce(&mut ContractSyncRuntimeHandle) -> Result<(), ExecutionError>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        ExpectedCall::Migrate(Box::new(handler))
+    }
+
     /// Creates an [`ExpectedCall`] to the [`MockApplicationInstance`]'s [`UserContract::finalize`]
     /// implementation, which is handled by the provided `handler`.
     pub fn finalize(
@@ -348,6 +364,16 @@ impl UserContract for MockApplicationInstance<ContractSyncRuntimeHandle> {
         }
     }
 
+    fn migrate(&mut self) -> Result<(), ExecutionError> {
+        match self.next_expected_call() {
+            Some(ExpectedCall::Migrate(handler)) => handler(&mut self.runtime),
+            Some(unexpected_call) => {
+                panic!("Expected a call to `migrate`, got a call to `{unexpected_call}` instead.")
+            }
+            None => panic!("Unexpected call to `migrate`"),
+        }
+    }
+
     fn finalize(&mut self) -> Result<(), ExecutionError> {
         match self.next_expected_call() {
             Some(ExpectedCall::Finalize(handler)) => handler(&mut self.runtime),
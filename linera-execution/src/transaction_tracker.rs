@@ -15,7 +15,7 @@ use linera_base::{
     identifiers::{ApplicationId, BlobId, ChainId, StreamId},
 };
 
-use crate::{ExecutionError, OutgoingMessage};
+use crate::{evm::trace::EvmTraceCollector, ExecutionError, OutgoingMessage, WasmProfile};
 
 /// Maps a (publishing chain, stream) to its `(previous_index, first_index, next_index)`.
 type AppStreamUpdates = BTreeMap<(ChainId, StreamId), (u32, u32, u32)>;
@@ -65,6 +65,13 @@ pub struct TransactionTracker {
     /// hand them off through this tracker.
     #[debug(skip_if = Option::is_none)]
     prepared_checkpoint: Option<PreparedCheckpoint>,
+    /// Where to record the traces of the EVM executions, if this transaction is traced.
+    #[debug(skip_if = Option::is_none)]
+    evm_traces: Option<EvmTraceCollector>,
+    /// Where to record the profile of the Wasm contract executions, if this transaction is
+    /// profiled.
+    #[debug(skip_if = Option::is_none)]
+    wasm_profile: Option<WasmProfile>,
 }
 
 /// Pre-block-computed inputs for a `SystemOperation::Checkpoint` transaction.
@@ -160,6 +167,29 @@ impl TransactionTracker {
         self.prepared_checkpoint.take()
     }
 
+    /// Enables tracing of the EVM executions of this transaction, recording the traces in
+    /// `evm_traces`.
+    pub fn trace_evm(&mut self, evm_traces: EvmTraceCollector) {
+        self.evm_traces = Some(evm_traces);
+    }
+
+    /// Returns where to record the traces of the EVM executions, if they are traced.
+    pub fn evm_traces(&self) -> Option<&EvmTraceCollector> {
+        self.evm_traces.as_ref()
+    }
+
+    /// Enables profiling of the Wasm contract executions of this transaction, recording
+    /// the profile in `wasm_profile`.
+    pub fn profile_wasm(&mut self, wasm_profile: WasmProfile) {
+        self.wasm_profile = Some(wasm_profile);
+    }
+
+    /// Returns where to record the profile of the Wasm contract executions, if they are
+    /// profiled.
+    pub fn wasm_profile(&self) -> Option<&WasmProfile> {
+        self.wasm_profile.as_ref()
+    }
+
     /// Returns the local time recorded by the tracker.
     pub fn local_time(&self) -> Timestamp {
         self.local_time
@@ -383,6 +413,8 @@ impl TransactionTracker {
             blobs_published,
             free_blob_ids,
             prepared_checkpoint: _,
+            evm_traces: _,
+            wasm_profile: _,
         } = self;
         ensure!(
             streams_to_process.is_empty(),
@@ -3,6 +3,7 @@
 
 use std::collections::BTreeMap;
 
+use assert_matches::assert_matches;
 use linera_base::data_types::{Blob, BlockHeight, Bytecode};
 #[cfg(with_testing)]
 use linera_base::vm::VmRuntime;
@@ -10,8 +11,9 @@ use linera_views::context::MemoryContext;
 
 use super::*;
 use crate::{
-    test_utils::dummy_chain_description, ExecutionStateView, Message, MessageContext,
-    PreparedCheckpoint, TestExecutionRuntimeContext,
+    test_utils::dummy_chain_description, ExecutionStateView, Message, MessageContext, Operation,
+    PreparedCheckpoint, ProtocolFlag, ResourceControlPolicy, ResourceTracker,
+    TestExecutionRuntimeContext,
 };
 
 /// Returns an execution state view and a matching operation context, for epoch 1, with root
@@ -72,6 +74,7 @@ async fn application_message_index() -> anyhow::Result<()> {
         parameters: vec![],
         instantiation_argument: vec![],
         required_application_ids: vec![],
+        upgrade_authority: None,
     };
     let mut txn_tracker = TransactionTracker::default();
     view.context()
@@ -89,6 +92,48 @@ async fn application_message_index() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Tests that the upgrade authority is recorded without changing the application ID.
+#[tokio::test]
+async fn application_upgrade_authority_keeps_application_id() -> anyhow::Result<()> {
+    let (mut view, context) = new_view_and_context().await;
+    let contract = Bytecode::new(b"contract".into());
+    let service = Bytecode::new(b"service".into());
+    let contract_blob = Blob::new_contract_bytecode(contract.compress());
+    let service_blob = Blob::new_service_bytecode(service.compress());
+    let module_id = ModuleId::new(
+        contract_blob.id().hash,
+        service_blob.id().hash,
+        VmRuntime::Wasm,
+    );
+    let authority = AccountOwner::from(CryptoHash::test_hash("upgrade authority"));
+
+    let operation = SystemOperation::CreateApplication {
+        module_id,
+        parameters: vec![],
+        instantiation_argument: vec![],
+        required_application_ids: vec![],
+        upgrade_authority: Some(authority),
+    };
+    let mut txn_tracker = TransactionTracker::default();
+    view.context()
+        .extra()
+        .add_blobs([contract_blob, service_blob])
+        .await?;
+    let mut controller = ResourceController::default();
+    let new_application = view
+        .system
+        .execute_operation(context, operation, &mut txn_tracker, &mut controller)
+        .await?;
+    let id = expected_application_id(&context, &module_id, vec![], vec![], 0);
+    assert_eq!(new_application, Some((id, vec![])));
+    assert_eq!(
+        view.system.upgrade_authorities.get(&id).await?,
+        Some(authority)
+    );
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn open_chain_message_index() {
     let (mut view, context) = new_view_and_context().await;
@@ -136,6 +181,242 @@ async fn empty_accounts_are_removed() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Tests that locked tokens can only be transferred once they vested.
+#[tokio::test]
+async fn locked_tokens_vest_over_time() -> anyhow::Result<()> {
+    let (mut view, context) = new_view_and_context().await;
+    let owner = AccountOwner::from(CryptoHash::test_hash("owner"));
+    let beneficiary = AccountOwner::from(CryptoHash::test_hash("beneficiary"));
+    let recipient = Account::new(context.chain_id, owner);
+    view.system
+        .balances
+        .insert(&owner, Amount::from_tokens(100))?;
+
+    let invalid_schedule = VestingSchedule {
+        start: Timestamp::from(100),
+        cliff: Timestamp::from(0),
+        end: Timestamp::from(1_000),
+    };
+    let result = view
+        .system
+        .lock_tokens(
+            Some(owner),
+            owner,
+            beneficiary,
+            Amount::ONE,
+            invalid_schedule,
+        )
+        .await;
+    assert_matches!(result, Err(ExecutionError::InvalidVestingSchedule));
+
+    let schedule = VestingSchedule {
+        start: Timestamp::from(0),
+        cliff: Timestamp::from(100),
+        end: Timestamp::from(1_000),
+    };
+    view.system
+        .lock_tokens(
+            Some(owner),
+            owner,
+            beneficiary,
+            Amount::from_tokens(60),
+            schedule,
+        )
+        .await?;
+    assert_eq!(
+        view.system.balances.get(&beneficiary).await?,
+        Some(Amount::from_tokens(60))
+    );
+
+    view.system.progress.get_mut().timestamp = Timestamp::from(50);
+    assert_eq!(
+        view.system.locked_balance(&beneficiary).await?,
+        Amount::from_tokens(60)
+    );
+    let result = view
+        .system
+        .transfer(Some(beneficiary), None, beneficiary, recipient, Amount::ONE)
+        .await;
+    assert_matches!(
+        result,
+        Err(ExecutionError::InsufficientUnlockedBalance { locked, .. })
+            if locked == Amount::from_tokens(60)
+    );
+
+    view.system.progress.get_mut().timestamp = Timestamp::from(500);
+    view.system
+        .transfer(
+            Some(beneficiary),
+            None,
+            beneficiary,
+            recipient,
+            Amount::from_tokens(30),
+        )
+        .await?;
+    let result = view
+        .system
+        .transfer(Some(beneficiary), None, beneficiary, recipient, Amount::ONE)
+        .await;
+    assert_matches!(
+        result,
+        Err(ExecutionError::InsufficientUnlockedBalance { .. })
+    );
+
+    view.system.progress.get_mut().timestamp = Timestamp::from(1_000);
+    view.system
+        .transfer(
+            Some(beneficiary),
+            None,
+            beneficiary,
+            recipient,
+            Amount::from_tokens(30),
+        )
+        .await?;
+    assert_eq!(view.system.balances.get(&beneficiary).await?, None);
+    assert!(view.system.locked_balances.indices().await?.is_empty());
+
+    Ok(())
+}
+
+/// Tests that tranches with identical schedules are merged, and that the number of
+/// distinct schedules per account is bounded.
+#[tokio::test]
+async fn locked_tranches_are_merged_and_bounded() -> anyhow::Result<()> {
+    let (mut view, _) = new_view_and_context().await;
+    let owner = AccountOwner::from(CryptoHash::test_hash("owner"));
+    let beneficiary = AccountOwner::from(CryptoHash::test_hash("beneficiary"));
+    view.system
+        .balances
+        .insert(&owner, Amount::from_tokens(100))?;
+
+    let schedule = VestingSchedule::until(Timestamp::from(1_000));
+    for _ in 0..3 {
+        view.system
+            .lock_tokens(Some(owner), owner, beneficiary, Amount::ONE, schedule)
+            .await?;
+    }
+    let tranches = view
+        .system
+        .locked_balances
+        .get(&beneficiary)
+        .await?
+        .unwrap();
+    assert_eq!(tranches.len(), 1);
+    assert_eq!(tranches[0].amount, Amount::from_tokens(3));
+
+    for end in 1..MAX_LOCKED_TRANCHES as u64 {
+        let schedule = VestingSchedule::until(Timestamp::from(1_000 + end));
+        view.system
+            .lock_tokens(Some(owner), owner, beneficiary, Amount::ONE, schedule)
+            .await?;
+    }
+    let schedule = VestingSchedule::until(Timestamp::from(2_000));
+    let result = view
+        .system
+        .lock_tokens(Some(owner), owner, beneficiary, Amount::ONE, schedule)
+        .await;
+    assert_matches!(
+        result,
+        Err(ExecutionError::TooManyLockedTranches(account)) if account == beneficiary
+    );
+    assert_eq!(
+        view.system.balances.get(&owner).await?,
+        Some(Amount::from_tokens(82))
+    );
+
+    // Once a tranche has vested, its slot can be reused.
+    view.system.progress.get_mut().timestamp = Timestamp::from(1_000);
+    view.system
+        .lock_tokens(Some(owner), owner, beneficiary, Amount::ONE, schedule)
+        .await?;
+
+    Ok(())
+}
+
+/// Tests that locked tokens only pay fees if the policy allows it.
+#[tokio::test]
+async fn locked_tokens_pay_fees_only_if_allowed() -> anyhow::Result<()> {
+    let (mut view, _) = new_view_and_context().await;
+    let owner = AccountOwner::from(CryptoHash::test_hash("owner"));
+    view.system.balances.insert(&owner, Amount::ONE)?;
+    view.system
+        .lock_tokens(
+            Some(owner),
+            owner,
+            owner,
+            Amount::ONE,
+            VestingSchedule::until(Timestamp::from(1_000)),
+        )
+        .await?;
+    let operation = Operation::system(SystemOperation::CloseChain);
+
+    for (flags, pays) in [
+        (BTreeSet::new(), false),
+        (BTreeSet::from([ProtocolFlag::FeesFromLockedBalances]), true),
+    ] {
+        let policy = ResourceControlPolicy {
+            operation: Amount::ONE,
+            flags,
+            ..ResourceControlPolicy::default()
+        };
+        let mut controller =
+            ResourceController::new(Arc::new(policy), ResourceTracker::default(), Some(owner));
+        let result = controller
+            .with_state(&mut view.system)
+            .await?
+            .track_operation(&operation);
+        assert_eq!(result.is_ok(), pays);
+    }
+    assert_eq!(view.system.balances.get(&owner).await?, Some(Amount::ZERO));
+
+    Ok(())
+}
+
+/// Tests that refunds go back to a fee sponsor in proportion to the fees it paid.
+#[tokio::test]
+async fn refunds_are_shared_with_the_fee_sponsor() -> anyhow::Result<()> {
+    let (mut view, _) = new_view_and_context().await;
+    let sponsor = AccountOwner::from(CryptoHash::test_hash("sponsor"));
+    let sponsorship_hash = CryptoHash::test_hash("sponsorship");
+    view.system.balance.set(Amount::from_micros(100));
+    view.system.balances.insert(&sponsor, Amount::ONE)?;
+    let mut controller = ResourceController::new(
+        Arc::new(ResourceControlPolicy::default()),
+        ResourceTracker::default(),
+        None,
+    );
+    controller.sponsor = Some(FeeSponsor {
+        owner: sponsor,
+        sponsorship_hash,
+        max_fees: Amount::from_micros(30),
+        paid: Amount::ZERO,
+        paid_by_others: Amount::ZERO,
+    });
+
+    // The sponsor pays up to its cap, and the chain pays the rest.
+    controller
+        .with_state(&mut view.system)
+        .await?
+        .merge_balance(Amount::from_micros(40), Amount::ZERO)?;
+    assert_eq!(*view.system.balance.get(), Amount::from_micros(90));
+
+    // Three quarters of the fees were paid by the sponsor, so it gets three quarters back.
+    controller
+        .with_state(&mut view.system)
+        .await?
+        .merge_balance(Amount::ZERO, Amount::from_micros(20))?;
+    assert_eq!(
+        view.system.balances.get(&sponsor).await?,
+        Some(Amount::ONE.try_sub(Amount::from_micros(15))?)
+    );
+    assert_eq!(
+        view.system.fee_sponsorships.get(&sponsorship_hash).await?,
+        Some(Amount::from_micros(15))
+    );
+    assert_eq!(*view.system.balance.get(), Amount::from_micros(95));
+    Ok(())
+}
+
 #[tokio::test]
 async fn execute_checkpoint_publishes_blob_and_records_oracle_response() -> anyhow::Result<()> {
     use linera_base::data_types::OracleResponse;
@@ -433,3 +714,29 @@ async fn execute_checkpoint_rejects_chain_with_published_events() -> anyhow::Res
     ));
     Ok(())
 }
+
+#[tokio::test]
+async fn execute_checkpoint_allows_published_application_upgrades() -> anyhow::Result<()> {
+    use linera_base::identifiers::StreamId;
+    use linera_views::{batch::Batch, store::WritableKeyValueStore as _, views::View as _};
+
+    let mut view = SystemExecutionState {
+        description: Some(dummy_chain_description(0)),
+        ..SystemExecutionState::default()
+    }
+    .into_view()
+    .await;
+
+    let application_id = ApplicationId::new(CryptoHash::test_hash("application"));
+    let stream_id = StreamId::system(application_upgrade_stream_name(application_id));
+    assert!(is_application_upgrade_stream(&stream_id));
+    view.previous_event_blocks
+        .insert(&stream_id, BlockHeight::from(0))?;
+    let mut batch = Batch::new();
+    view.pre_save(&mut batch)?;
+    view.context().store().write_batch(batch).await?;
+    view.post_save();
+
+    view.prepare_checkpoint(u64::MAX).await?;
+    Ok(())
+}
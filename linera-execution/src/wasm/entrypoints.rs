@@ -18,6 +18,7 @@ pub trait ContractEntrypoints {
     fn execute_message(message: Vec<u8>);
     fn process_streams(streams: Vec<StreamUpdate>);
     fn summarize_events(streams: Vec<StreamUpdate>);
+    fn migrate();
     fn finalize();
 }
 
@@ -7,11 +7,17 @@
 //!
 //! - `wasmer` enables the [Wasmer](https://wasmer.io/) runtime
 //! - `wasmtime` enables the [Wasmtime](https://wasmtime.dev/) runtime
+//!
+//! Only the [`profile`] types are available without a runtime.
 
-#![cfg(with_wasm_runtime)]
-
+#[cfg(with_wasm_runtime)]
 mod entrypoints;
+#[cfg(with_wasm_runtime)]
 mod module_cache;
+pub mod profile;
+#[cfg(all(with_wasm_runtime, not(web)))]
+mod profiler;
+#[cfg(with_wasm_runtime)]
 #[macro_use]
 mod runtime_api;
 #[cfg(with_wasmer)]
@@ -19,28 +25,39 @@ mod wasmer;
 #[cfg(with_wasmtime)]
 mod wasmtime;
 
-#[cfg(with_fs)]
+#[cfg(all(with_fs, with_wasm_runtime))]
 use std::path::Path;
+#[cfg(all(with_wasm_runtime, not(web)))]
+use std::sync::Arc;
 
+#[cfg(with_wasm_runtime)]
 use linera_base::data_types::Bytecode;
-#[cfg(with_metrics)]
+#[cfg(all(with_metrics, with_wasm_runtime))]
 use linera_base::prometheus_util::MeasureLatency as _;
+#[cfg(with_wasm_runtime)]
 use thiserror::Error;
 #[cfg(with_wasmer)]
 use wasmer::{WasmerContractInstance, WasmerServiceInstance};
 #[cfg(with_wasmtime)]
 use wasmtime::{WasmtimeContractInstance, WasmtimeServiceInstance};
 
+#[cfg(with_wasm_runtime)]
 pub use self::{
     entrypoints::{ContractEntrypoints, ServiceEntrypoints},
     runtime_api::{BaseRuntimeApi, ContractRuntimeApi, RuntimeApiData, ServiceRuntimeApi},
 };
+#[cfg(all(with_wasm_runtime, not(web)))]
+use self::{
+    profile::WasmProfile,
+    profiler::{ProfiledFunction, WasmProfiler},
+};
+#[cfg(with_wasm_runtime)]
 use crate::{
     ContractSyncRuntimeHandle, ExecutionError, ServiceSyncRuntimeHandle, UserContractInstance,
     UserContractModule, UserServiceInstance, UserServiceModule, WasmRuntime,
 };
 
-#[cfg(with_metrics)]
+#[cfg(all(with_metrics, with_wasm_runtime))]
 pub(crate) mod metrics {
     use linera_base::prometheus_util::{
         exponential_bucket_interval, exponential_bucket_latencies, register_histogram_vec,
@@ -75,6 +92,7 @@ pub(crate) mod metrics {
 }
 
 /// A user contract in a compiled WebAssembly module.
+#[cfg(with_wasm_runtime)]
 #[derive(Clone)]
 #[allow(missing_docs)]
 pub enum WasmContractModule {
@@ -87,6 +105,7 @@ pub enum WasmContractModule {
     Wasmtime { module: ::wasmtime::Module },
 }
 
+#[cfg(with_wasm_runtime)]
 impl WasmContractModule {
     /// Creates a new [`WasmContractModule`] using the WebAssembly module with the provided bytecode.
     pub async fn new(
@@ -118,6 +137,7 @@ impl WasmContractModule {
     }
 }
 
+#[cfg(with_wasm_runtime)]
 impl UserContractModule for WasmContractModule {
     fn instantiate(
         &self,
@@ -141,7 +161,72 @@ impl UserContractModule for WasmContractModule {
     }
 }
 
+/// A user contract in a WebAssembly module instrumented to record a [`WasmProfile`].
+///
+/// The module is compiled without being cached, and every instance records into the same
+/// profile.
+#[cfg(all(with_wasm_runtime, not(web)))]
+#[derive(Clone)]
+pub struct ProfiledWasmContractModule {
+    module: WasmContractModule,
+    functions: Arc<[ProfiledFunction]>,
+    profile: WasmProfile,
+}
+
+#[cfg(all(with_wasm_runtime, not(web)))]
+impl ProfiledWasmContractModule {
+    /// Creates a new [`ProfiledWasmContractModule`] using the WebAssembly module with the
+    /// provided bytecode, recording into `profile`.
+    pub fn new(
+        contract_bytecode: Bytecode,
+        runtime: WasmRuntime,
+        profile: WasmProfile,
+    ) -> Result<Self, WasmExecutionError> {
+        let metered_bytecode = add_metering(&contract_bytecode)?;
+        let (profiled_bytecode, functions) = profiler::add_profiling(&metered_bytecode)?;
+        let module = match runtime {
+            #[cfg(with_wasmer)]
+            WasmRuntime::Wasmer => WasmContractModule::compile_wasmer(profiled_bytecode)?,
+            #[cfg(with_wasmtime)]
+            WasmRuntime::Wasmtime => WasmContractModule::compile_wasmtime(profiled_bytecode)?,
+        };
+        Ok(ProfiledWasmContractModule {
+            module,
+            functions,
+            profile,
+        })
+    }
+}
+
+#[cfg(all(with_wasm_runtime, not(web)))]
+impl UserContractModule for ProfiledWasmContractModule {
+    fn instantiate(
+        &self,
+        runtime: ContractSyncRuntimeHandle,
+    ) -> Result<UserContractInstance, ExecutionError> {
+        let profiler = WasmProfiler::new(self.functions.clone(), self.profile.clone());
+        let instance: UserContractInstance = match &self.module {
+            #[cfg(with_wasmtime)]
+            WasmContractModule::Wasmtime { module } => Box::new(
+                WasmtimeContractInstance::prepare_with_profiler(module, runtime, profiler)?,
+            ),
+            #[cfg(with_wasmer)]
+            WasmContractModule::Wasmer { engine, module } => {
+                Box::new(WasmerContractInstance::prepare_with_profiler(
+                    engine.clone(),
+                    module,
+                    runtime,
+                    profiler,
+                )?)
+            }
+        };
+
+        Ok(instance)
+    }
+}
+
 /// A user service in a compiled WebAssembly module.
+#[cfg(with_wasm_runtime)]
 #[derive(Clone)]
 #[allow(missing_docs)]
 pub enum WasmServiceModule {
@@ -151,6 +236,7 @@ pub enum WasmServiceModule {
     Wasmtime { module: ::wasmtime::Module },
 }
 
+#[cfg(with_wasm_runtime)]
 impl WasmServiceModule {
     /// Creates a new [`WasmServiceModule`] using the WebAssembly module with the provided bytecode.
     pub async fn new(
@@ -182,6 +268,7 @@ impl WasmServiceModule {
     }
 }
 
+#[cfg(with_wasm_runtime)]
 impl UserServiceModule for WasmServiceModule {
     fn instantiate(
         &self,
@@ -206,6 +293,7 @@ impl UserServiceModule for WasmServiceModule {
 }
 
 /// Instrument the [`Bytecode`] to add fuel metering.
+#[cfg(with_wasm_runtime)]
 pub fn add_metering(bytecode: &Bytecode) -> Result<Bytecode, WasmExecutionError> {
     pub struct Costs;
     impl walrus_meter::Costs for Costs {
@@ -228,7 +316,7 @@ pub fn add_metering(bytecode: &Bytecode) -> Result<Bytecode, WasmExecutionError>
     Ok(Bytecode::new(instrumented_module))
 }
 
-#[cfg(web)]
+#[cfg(all(web, with_wasm_runtime))]
 const _: () = {
     use js_sys::wasm_bindgen::JsValue;
     use web_thread_select as web_thread;
@@ -286,6 +374,7 @@ const _: () = {
 };
 
 /// Errors that can occur when executing a user application in a WebAssembly module.
+#[cfg(with_wasm_runtime)]
 #[derive(Debug, Error)]
 #[allow(missing_docs)]
 pub enum WasmExecutionError {
@@ -325,7 +414,7 @@ impl From<::wasmer::InstantiationError> for WasmExecutionError {
 }
 
 /// This assumes that the current directory is one of the crates.
-#[cfg(with_testing)]
+#[cfg(all(with_testing, with_wasm_runtime))]
 pub mod test {
     use std::{path::Path, sync::LazyLock};
 
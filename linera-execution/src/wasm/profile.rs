@@ -0,0 +1,216 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Profiles of Wasm contract executions, attributing the consumed fuel and the time spent in
+//! host functions to the call stacks of the guest functions.
+//!
+//! The types are available even without a Wasm runtime so that profiles can be passed around
+//! by components that do not execute Wasm code themselves.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write as _,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+#[cfg(all(with_wasm_runtime, not(web)))]
+use linera_base::time::Instant;
+
+/// The fuel and host-call time of the Wasm contract executions of a transaction, attributed
+/// to the call stacks in which they were used.
+///
+/// Guest functions are named after the module's name section, and host functions after
+/// their import, e.g. `linera:app/contract-runtime-api#send-message`. Calls into other
+/// applications are nested below the host function that made them.
+///
+/// Clones share the same profile.
+#[derive(Clone, Debug, Default)]
+pub struct WasmProfile(Arc<Mutex<CallTree>>);
+
+/// The costs attributed to one call stack of a [`WasmProfile`], not including the costs of
+/// the calls made from it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WasmProfileSample {
+    /// The fuel consumed by the function.
+    pub fuel: u64,
+    /// The time spent in the function, if it is a host function.
+    pub host_time: Duration,
+}
+
+/// The calls recorded so far, as a tree whose root is a placeholder for the caller of the
+/// entry points.
+#[derive(Debug)]
+struct CallTree {
+    nodes: Vec<CallNode>,
+    /// The calls that have not returned yet, innermost last.
+    #[cfg(all(with_wasm_runtime, not(web)))]
+    active_calls: Vec<ActiveCall>,
+}
+
+#[derive(Debug)]
+struct CallNode {
+    name: Arc<str>,
+    parent: usize,
+    /// The children, keyed by the address of their name, which is shared by all the
+    /// calls to the same function of a module.
+    #[cfg_attr(not(all(with_wasm_runtime, not(web))), allow(dead_code))]
+    children: HashMap<usize, usize>,
+    sample: WasmProfileSample,
+}
+
+#[cfg(all(with_wasm_runtime, not(web)))]
+#[derive(Debug)]
+struct ActiveCall {
+    node: usize,
+    is_host: bool,
+    start: Instant,
+    /// The time spent in the calls made from this one.
+    nested_time: Duration,
+}
+
+impl Default for CallTree {
+    fn default() -> Self {
+        CallTree {
+            nodes: vec![CallNode {
+                name: Arc::from(""),
+                parent: 0,
+                children: HashMap::new(),
+                sample: WasmProfileSample::default(),
+            }],
+            #[cfg(all(with_wasm_runtime, not(web)))]
+            active_calls: Vec::new(),
+        }
+    }
+}
+
+impl WasmProfile {
+    /// Returns the costs attributed to each call stack, outermost function first.
+    pub fn samples(&self) -> BTreeMap<Vec<String>, WasmProfileSample> {
+        let tree = self.0.lock().unwrap();
+        let mut samples = BTreeMap::<_, WasmProfileSample>::new();
+        for index in 1..tree.nodes.len() {
+            let node = &tree.nodes[index];
+            let entry = samples.entry(tree.stack(index)).or_default();
+            entry.fuel += node.sample.fuel;
+            entry.host_time += node.sample.host_time;
+        }
+        samples
+    }
+
+    /// Returns the total fuel consumed.
+    pub fn total_fuel(&self) -> u64 {
+        let tree = self.0.lock().unwrap();
+        tree.nodes.iter().map(|node| node.sample.fuel).sum()
+    }
+
+    /// Returns the fuel consumed by each function itself, not including the functions it
+    /// called.
+    pub fn fuel_by_function(&self) -> BTreeMap<String, u64> {
+        let tree = self.0.lock().unwrap();
+        let mut fuel = BTreeMap::<_, u64>::new();
+        for node in &tree.nodes[1..] {
+            *fuel.entry(node.name.to_string()).or_default() += node.sample.fuel;
+        }
+        fuel
+    }
+
+    /// Returns the consumed fuel in the folded-stack format read by flamegraph tools, with
+    /// one `caller;callee fuel` line per call stack.
+    pub fn folded_fuel(&self) -> String {
+        Self::folded(self.samples(), |sample| sample.fuel)
+    }
+
+    /// Returns the host-call time in the folded-stack format read by flamegraph tools, in
+    /// microseconds.
+    pub fn folded_host_time(&self) -> String {
+        Self::folded(self.samples(), |sample| {
+            u64::try_from(sample.host_time.as_micros()).unwrap_or(u64::MAX)
+        })
+    }
+
+    fn folded(
+        samples: BTreeMap<Vec<String>, WasmProfileSample>,
+        value: impl Fn(&WasmProfileSample) -> u64,
+    ) -> String {
+        let mut folded = String::new();
+        for (stack, sample) in samples {
+            let value = value(&sample);
+            if value == 0 {
+                continue;
+            }
+            // Semicolons separate the frames, so they can't appear in names like `[u8; 32]`.
+            let frames = stack
+                .iter()
+                .map(|name| name.replace(';', ","))
+                .collect::<Vec<_>>();
+            writeln!(folded, "{} {value}", frames.join(";")).expect("writing to a string");
+        }
+        folded
+    }
+}
+
+#[cfg(all(with_wasm_runtime, not(web)))]
+impl WasmProfile {
+    /// Records a call to the function with the given name.
+    pub(crate) fn enter(&self, name: &Arc<str>, is_host: bool) {
+        let mut tree = self.0.lock().unwrap();
+        let parent = tree.active_calls.last().map_or(0, |call| call.node);
+        let key = Arc::as_ptr(name) as *const u8 as usize;
+        let node = match tree.nodes[parent].children.get(&key) {
+            Some(node) => *node,
+            None => {
+                let node = tree.nodes.len();
+                tree.nodes.push(CallNode {
+                    name: name.clone(),
+                    parent,
+                    children: HashMap::new(),
+                    sample: WasmProfileSample::default(),
+                });
+                tree.nodes[parent].children.insert(key, node);
+                node
+            }
+        };
+        tree.active_calls.push(ActiveCall {
+            node,
+            is_host,
+            start: Instant::now(),
+            nested_time: Duration::ZERO,
+        });
+    }
+
+    /// Records that the innermost call returned.
+    pub(crate) fn exit(&self) {
+        let mut tree = self.0.lock().unwrap();
+        let Some(call) = tree.active_calls.pop() else {
+            return;
+        };
+        let elapsed = call.start.elapsed();
+        if call.is_host {
+            tree.nodes[call.node].sample.host_time += elapsed.saturating_sub(call.nested_time);
+        }
+        if let Some(caller) = tree.active_calls.last_mut() {
+            caller.nested_time += elapsed;
+        }
+    }
+
+    /// Attributes fuel to the innermost call.
+    pub(crate) fn consume_fuel(&self, fuel: u64) {
+        let mut tree = self.0.lock().unwrap();
+        let node = tree.active_calls.last().map_or(0, |call| call.node);
+        tree.nodes[node].sample.fuel += fuel;
+    }
+}
+
+impl CallTree {
+    /// Returns the names of the functions on the call stack leading to a node.
+    fn stack(&self, mut node: usize) -> Vec<String> {
+        let mut stack = Vec::new();
+        while node != 0 {
+            stack.push(self.nodes[node].name.to_string());
+            node = self.nodes[node].parent;
+        }
+        stack.reverse();
+        stack
+    }
+}
@@ -0,0 +1,245 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Instrumentation of Wasm modules to record a [`WasmProfile`] of their executions.
+//!
+//! Every function of an instrumented module reports when it is entered and exited, and so
+//! does every call to an imported function, except for fuel metering. The host keeps track of
+//! the call stack, so that the fuel consumed and the time spent in host functions can be
+//! attributed to it. Guest functions are named after the module's name section, with Rust
+//! symbols demangled.
+
+use std::{
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+    mem,
+    sync::Arc,
+};
+
+use linera_base::data_types::Bytecode;
+use linera_witty::{wit_export, Instance};
+use walrus::{
+    ir::{Block, Call, Const, Instr, InstrSeq, InstrSeqId, InstrSeqType, Value, VisitorMut},
+    FunctionId, FunctionKind, InstrLocId, ValType,
+};
+
+use super::{profile::WasmProfile, runtime_api::RuntimeApiData, WasmExecutionError};
+
+/// The interface of the host functions exported by [`ProfilingApi`], named after the type.
+const PROFILING_API: &str = "linera:app/profiling-api";
+
+/// A function of an instrumented module, as reported to `enter-function`.
+#[derive(Clone, Debug)]
+pub(crate) struct ProfiledFunction {
+    name: Arc<str>,
+    is_host: bool,
+}
+
+/// Instruments the [`Bytecode`] to report the calls of its functions.
+///
+/// Returns the instrumented bytecode and the functions of the module, indexed by the number
+/// each of them reports to `enter-function`.
+pub(crate) fn add_profiling(
+    bytecode: &Bytecode,
+) -> Result<(Bytecode, Arc<[ProfiledFunction]>), WasmExecutionError> {
+    let mut module = walrus::Module::from_buffer(&bytecode.bytes)
+        .map_err(|_| WasmExecutionError::InstrumentModule)?;
+
+    let mut functions = Vec::new();
+    let mut indices = HashMap::new();
+    for (index, function) in module.funcs.iter().enumerate() {
+        let profiled_function = match &function.kind {
+            FunctionKind::Import(imported) => {
+                let import = module.imports.get(imported.import);
+                ProfiledFunction {
+                    name: format!("{}#{}", import.module, import.name).into(),
+                    is_host: true,
+                }
+            }
+            _ => ProfiledFunction {
+                name: match &function.name {
+                    Some(name) => format!("{:#}", rustc_demangle::demangle(name)).into(),
+                    None => format!("function[{index}]").into(),
+                },
+                is_host: false,
+            },
+        };
+        functions.push(profiled_function);
+        indices.insert(
+            function.id(),
+            i32::try_from(index).map_err(|_| WasmExecutionError::InstrumentModule)?,
+        );
+    }
+
+    let consume_fuel = module
+        .imports
+        .get_func("linera:app/contract-runtime-api", "consume-fuel")
+        .ok();
+    let host_functions = module
+        .funcs
+        .iter()
+        .filter(|function| matches!(function.kind, FunctionKind::Import(_)))
+        .map(|function| function.id())
+        .filter(|function| Some(*function) != consume_fuel)
+        .collect::<HashSet<_>>();
+
+    let enter_type = module.types.add(&[ValType::I32], &[]);
+    let (enter, _) = module.add_import_func(PROFILING_API, "enter-function", enter_type);
+    let exit_type = module.types.add(&[], &[]);
+    let (exit, _) = module.add_import_func(PROFILING_API, "exit-function", exit_type);
+
+    for (id, function) in module.funcs.iter_local_mut() {
+        // The body is moved into a block, so that leaving the block, even by branching to
+        // the outermost label, always goes through the exit call placed after it.
+        let entry = function.entry_block();
+        // The type of the entry block is only used internally and isn't emitted, so the
+        // block needs a regular type with the same results.
+        let body_type = match function.block(entry).ty {
+            InstrSeqType::MultiValue(entry_type) => {
+                let results = module.types.get(entry_type).results().to_vec();
+                InstrSeqType::new(&mut module.types, &[], &results)
+            }
+            simple_type => simple_type,
+        };
+        let body = function.builder_mut().dangling_instr_seq(body_type).id();
+        function.block_mut(body).instrs = mem::take(&mut function.block_mut(entry).instrs);
+
+        let mut instrumenter = Instrumenter {
+            entry,
+            body,
+            enter,
+            exit,
+            indices: &indices,
+            host_functions: &host_functions,
+        };
+        walrus::ir::dfs_pre_order_mut(&mut instrumenter, function, body);
+
+        function.block_mut(entry).instrs = vec![
+            (
+                Const {
+                    value: Value::I32(indices[&id]),
+                }
+                .into(),
+                InstrLocId::default(),
+            ),
+            (Call { func: enter }.into(), InstrLocId::default()),
+            (Block { seq: body }.into(), InstrLocId::default()),
+            (Call { func: exit }.into(), InstrLocId::default()),
+        ];
+    }
+
+    Ok((Bytecode::new(module.emit_wasm()), functions.into()))
+}
+
+/// Adds the profiling calls to the instructions of a function.
+struct Instrumenter<'a> {
+    entry: InstrSeqId,
+    body: InstrSeqId,
+    enter: FunctionId,
+    exit: FunctionId,
+    indices: &'a HashMap<FunctionId, i32>,
+    host_functions: &'a HashSet<FunctionId>,
+}
+
+impl VisitorMut for Instrumenter<'_> {
+    fn start_instr_seq_mut(&mut self, seq: &mut InstrSeq) {
+        let exit_function = self.exit;
+        let exit = || {
+            (
+                Call {
+                    func: exit_function,
+                }
+                .into(),
+                InstrLocId::default(),
+            )
+        };
+        let mut instrs = Vec::with_capacity(seq.instrs.len());
+        for (instr, location) in mem::take(&mut seq.instrs) {
+            match &instr {
+                Instr::Return(_)
+                | Instr::ReturnCall(_)
+                | Instr::ReturnCallIndirect(_)
+                | Instr::ReturnCallRef(_) => {
+                    instrs.push(exit());
+                    instrs.push((instr, location));
+                }
+                Instr::Call(Call { func }) if self.host_functions.contains(func) => {
+                    let index = self.indices[func];
+                    instrs.push((
+                        Const {
+                            value: Value::I32(index),
+                        }
+                        .into(),
+                        InstrLocId::default(),
+                    ));
+                    instrs.push((Call { func: self.enter }.into(), InstrLocId::default()));
+                    instrs.push((instr, location));
+                    instrs.push(exit());
+                }
+                _ => instrs.push((instr, location)),
+            }
+        }
+        seq.instrs = instrs;
+    }
+
+    fn visit_instr_seq_id_mut(&mut self, instr_seq_id: &mut InstrSeqId) {
+        if *instr_seq_id == self.entry {
+            *instr_seq_id = self.body;
+        }
+    }
+}
+
+/// The host-side state of an instrumented instance, recording its calls into a
+/// [`WasmProfile`].
+pub(crate) struct WasmProfiler {
+    functions: Arc<[ProfiledFunction]>,
+    profile: WasmProfile,
+}
+
+impl WasmProfiler {
+    /// Creates a [`WasmProfiler`] for an instance of a module instrumented by
+    /// [`add_profiling`].
+    pub(crate) fn new(functions: Arc<[ProfiledFunction]>, profile: WasmProfile) -> Self {
+        WasmProfiler { functions, profile }
+    }
+
+    /// Attributes fuel to the function being executed.
+    pub(crate) fn consume_fuel(&self, fuel: u64) {
+        self.profile.consume_fuel(fuel);
+    }
+
+    fn enter_function(&self, index: u32) {
+        if let Some(function) = self.functions.get(index as usize) {
+            self.profile.enter(&function.name, function.is_host);
+        }
+    }
+
+    fn exit_function(&self) {
+        self.profile.exit();
+    }
+}
+
+/// The host functions called by instrumented modules to report their calls.
+#[derive(Default)]
+pub struct ProfilingApi<Caller>(PhantomData<Caller>);
+
+#[wit_export(package = "linera:app")]
+impl<Caller, Runtime> ProfilingApi<Caller>
+where
+    Caller: Instance<UserData = RuntimeApiData<Runtime>>,
+    Runtime: 'static,
+{
+    /// Records a call to the function with the given index.
+    fn enter_function(caller: &mut Caller, function: u32) {
+        if let Some(profiler) = caller.user_data_mut().profiler() {
+            profiler.enter_function(function);
+        }
+    }
+
+    /// Records that the innermost call returned.
+    fn exit_function(caller: &mut Caller) {
+        if let Some(profiler) = caller.user_data_mut().profiler() {
+            profiler.exit_function();
+        }
+    }
+}
@@ -5,18 +5,23 @@ use std::{any::Any, collections::HashMap, marker::PhantomData};
 
 use linera_base::{
     data_types::{
-        Amount, ApplicationDescription, ApplicationPermissions, BlockHeight, Bytecode,
-        SendMessageRequest, Timestamp,
+        AllowanceLimits, Amount, ApplicationDescription, ApplicationPermissions, BlockHeight,
+        Bytecode, SendMessageRequest, Timestamp,
     },
     http,
     identifiers::{Account, AccountOwner, ApplicationId, ChainId, StreamName},
     ownership::{ChainOwnership, ManageChainError},
     vm::VmRuntime,
 };
-use linera_views::batch::{Batch, WriteOperation};
+use linera_views::{
+    batch::{Batch, WriteOperation},
+    store::KeyRange,
+};
 use linera_witty::{wit_export, Instance, RuntimeError};
 use tracing::log;
 
+#[cfg(not(web))]
+use super::profiler::WasmProfiler;
 use super::WasmExecutionError;
 use crate::{BaseRuntime, ContractRuntime, DataBlobHash, ExecutionError, ModuleId, ServiceRuntime};
 
@@ -25,6 +30,8 @@ pub struct RuntimeApiData<Runtime> {
     runtime: Runtime,
     active_promises: HashMap<u32, Box<dyn Any + Send + Sync>>,
     promise_counter: u32,
+    #[cfg(not(web))]
+    profiler: Option<WasmProfiler>,
 }
 
 impl<Runtime> RuntimeApiData<Runtime> {
@@ -34,9 +41,24 @@ impl<Runtime> RuntimeApiData<Runtime> {
             runtime,
             active_promises: HashMap::new(),
             promise_counter: 0,
+            #[cfg(not(web))]
+            profiler: None,
         }
     }
 
+    /// Records the calls of the instance into a profile, using `profiler`.
+    #[cfg(not(web))]
+    pub(crate) fn with_profiler(mut self, profiler: WasmProfiler) -> Self {
+        self.profiler = Some(profiler);
+        self
+    }
+
+    /// Returns the profiler recording the calls of the instance, if it is profiled.
+    #[cfg(not(web))]
+    pub(crate) fn profiler(&self) -> Option<&WasmProfiler> {
+        self.profiler.as_ref()
+    }
+
     /// Returns a mutable reference the system API `Runtime`.
     pub fn runtime_mut(&mut self) -> &mut Runtime {
         &mut self.runtime
@@ -425,6 +447,22 @@ where
             .map_err(|error| RuntimeError::Custom(error.into()))
     }
 
+    /// Creates a new promise to search for keys that start with the `key_prefix` and whose
+    /// suffixes are in the `range`. The promise is resolved by `find_keys_wait`.
+    fn find_keys_in_range_new(
+        caller: &mut Caller,
+        key_prefix: Vec<u8>,
+        range: KeyRange,
+    ) -> Result<u32, RuntimeError> {
+        let mut data = caller.user_data_mut();
+        let promise = data
+            .runtime
+            .find_keys_in_range_new(key_prefix, range)
+            .map_err(|error| RuntimeError::Custom(error.into()))?;
+
+        Ok(data.register_promise(promise))
+    }
+
     /// Creates a new promise to search for entries whose keys that start with the `key_prefix`.
     fn find_key_values_new(caller: &mut Caller, key_prefix: Vec<u8>) -> Result<u32, RuntimeError> {
         let mut data = caller.user_data_mut();
@@ -449,6 +487,23 @@ where
             .find_key_values_by_prefix_wait(&promise)
             .map_err(|error| RuntimeError::Custom(error.into()))
     }
+
+    /// Creates a new promise to search for entries whose keys start with the `key_prefix` and
+    /// whose key suffixes are in the `range`. The promise is resolved by
+    /// `find_key_values_wait`.
+    fn find_key_values_in_range_new(
+        caller: &mut Caller,
+        key_prefix: Vec<u8>,
+        range: KeyRange,
+    ) -> Result<u32, RuntimeError> {
+        let mut data = caller.user_data_mut();
+        let promise = data
+            .runtime
+            .find_key_values_in_range_new(key_prefix, range)
+            .map_err(|error| RuntimeError::Custom(error.into()))?;
+
+        Ok(data.register_promise(promise))
+    }
 }
 
 /// An implementation of the system API made available to contracts.
@@ -561,7 +616,38 @@ where
         caller
             .user_data_mut()
             .runtime
-            .approve(owner, spender, amount)
+            .approve(owner, spender, amount, AllowanceLimits::default())
+            .map_err(|error| RuntimeError::Custom(error.into()))
+    }
+
+    /// Approves a `spender` to withdraw an `amount` of native tokens from the `owner`'s account,
+    /// subject to an expiry and a per-period cap.
+    fn approve_with_limits(
+        caller: &mut Caller,
+        owner: AccountOwner,
+        spender: AccountOwner,
+        amount: Amount,
+        limits: AllowanceLimits,
+    ) -> Result<(), RuntimeError> {
+        caller
+            .user_data_mut()
+            .runtime
+            .approve(owner, spender, amount, limits)
+            .map_err(|error| RuntimeError::Custom(error.into()))
+    }
+
+    /// Approves a fee sponsorship by the `sponsor` on the current chain, up to `max_fees` in
+    /// total and until the `expiry`.
+    fn approve_fee_sponsorship(
+        caller: &mut Caller,
+        sponsor: AccountOwner,
+        max_fees: Amount,
+        expiry: Timestamp,
+    ) -> Result<(), RuntimeError> {
+        caller
+            .user_data_mut()
+            .runtime
+            .approve_fee_sponsorship(sponsor, max_fees, expiry)
             .map_err(|error| RuntimeError::Custom(error.into()))
     }
 
@@ -659,6 +745,20 @@ where
             .map_err(|error| RuntimeError::Custom(error.into()))
     }
 
+    /// Switches an application created on the current chain to a new module, and calls its
+    /// `migrate` entrypoint. The current application must be the upgrade authority.
+    fn upgrade_application(
+        caller: &mut Caller,
+        application_id: ApplicationId,
+        module_id: ModuleId,
+    ) -> Result<(), RuntimeError> {
+        caller
+            .user_data_mut()
+            .runtime
+            .upgrade_application(application_id, module_id)
+            .map_err(|error| RuntimeError::Custom(error.into()))
+    }
+
     /// Creates a new data blob and returns its hash.
     fn create_data_blob(caller: &mut Caller, bytes: Vec<u8>) -> Result<DataBlobHash, RuntimeError> {
         caller
@@ -764,16 +864,44 @@ where
             .map_err(|error| RuntimeError::Custom(error.into()))
     }
 
+    /// Schedules an operation of the current application to be executed in the first block
+    /// whose timestamp is at least `timestamp`. Returns the ID of the scheduled operation.
+    fn schedule_operation_at(
+        caller: &mut Caller,
+        timestamp: Timestamp,
+        operation: Vec<u8>,
+    ) -> Result<u32, RuntimeError> {
+        caller
+            .user_data_mut()
+            .runtime
+            .schedule_operation_at(timestamp, operation)
+            .map_err(|error| RuntimeError::Custom(error.into()))
+    }
+
+    /// Cancels an operation previously scheduled by the current application. Returns `false`
+    /// if the operation was not found.
+    fn cancel_scheduled_operation(caller: &mut Caller, id: u32) -> Result<bool, RuntimeError> {
+        caller
+            .user_data_mut()
+            .runtime
+            .cancel_scheduled_operation(id)
+            .map_err(|error| RuntimeError::Custom(error.into()))
+    }
+
     /// Consume some fuel.
     ///
     /// This is intended for the metering instrumentation, but if the user wants to donate
     /// some extra fuel, more power to them!
     fn consume_fuel(caller: &mut Caller, fuel: u64) -> Result<(), RuntimeError> {
-        caller
-            .user_data_mut()
-            .runtime_mut()
+        let mut data = caller.user_data_mut();
+        data.runtime_mut()
             .consume_fuel(fuel, VmRuntime::Wasm)
-            .map_err(|e| RuntimeError::Custom(e.into()))
+            .map_err(|e| RuntimeError::Custom(e.into()))?;
+        #[cfg(not(web))]
+        if let Some(profiler) = data.profiler() {
+            profiler.consume_fuel(fuel);
+        }
+        Ok(())
     }
 
     /// Returns the amount of execution fuel remaining before execution is aborted.
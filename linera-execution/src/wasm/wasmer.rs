@@ -13,6 +13,8 @@ use linera_witty::{
 use tokio::sync::Mutex;
 use tracing::instrument;
 
+#[cfg(not(web))]
+use super::profiler::{ProfilingApi, WasmProfiler};
 use super::{
     add_metering,
     module_cache::ModuleCache,
@@ -87,6 +89,19 @@ impl WasmContractModule {
             module,
         })
     }
+
+    /// Compiles an already instrumented contract with Wasmer, without caching it.
+    #[cfg(not(web))]
+    pub(super) fn compile_wasmer(
+        instrumented_bytecode: Bytecode,
+    ) -> Result<Self, WasmExecutionError> {
+        let module = wasmer::Module::new(&*CONTRACT_ENGINE, instrumented_bytecode)
+            .map_err(|error| WasmExecutionError::LoadContractModule(error.into()))?;
+        Ok(WasmContractModule::Wasmer {
+            engine: CONTRACT_ENGINE.clone(),
+            module,
+        })
+    }
 }
 
 impl<Runtime> WasmerContractInstance<Runtime>
@@ -99,11 +114,43 @@ where
         contract_module: &wasmer::Module,
         runtime: Runtime,
     ) -> Result<Self, WasmExecutionError> {
-        let system_api_data = RuntimeApiData::new(runtime);
+        Self::prepare_with_data(
+            contract_engine,
+            contract_module,
+            RuntimeApiData::new(runtime),
+        )
+    }
+
+    /// Prepares a runtime instance to call into a Wasm contract instrumented for profiling,
+    /// recording its calls with `profiler`.
+    #[cfg(not(web))]
+    pub fn prepare_with_profiler(
+        contract_engine: wasmer::Engine,
+        contract_module: &wasmer::Module,
+        runtime: Runtime,
+        profiler: WasmProfiler,
+    ) -> Result<Self, WasmExecutionError> {
+        let system_api_data = RuntimeApiData::new(runtime).with_profiler(profiler);
+        Self::prepare_with_data(contract_engine, contract_module, system_api_data)
+    }
+
+    fn prepare_with_data(
+        contract_engine: wasmer::Engine,
+        contract_module: &wasmer::Module,
+        system_api_data: RuntimeApiData<Runtime>,
+    ) -> Result<Self, WasmExecutionError> {
+        #[cfg(not(web))]
+        let is_profiled = system_api_data.profiler().is_some();
         let mut instance_builder = InstanceBuilder::new(contract_engine, system_api_data);
 
         BaseRuntimeApi::export_to(&mut instance_builder)?;
         ContractRuntimeApi::export_to(&mut instance_builder)?;
+        // Only the instrumented bytecode may call the profiler: a contract importing
+        // `profiling-api` otherwise fails to load.
+        #[cfg(not(web))]
+        if is_profiled {
+            ProfilingApi::export_to(&mut instance_builder)?;
+        }
 
         let instance = instance_builder.instantiate(contract_module)?;
 
@@ -188,6 +235,14 @@ where
         Ok(())
     }
 
+    #[instrument(skip_all)]
+    fn migrate(&mut self) -> Result<(), ExecutionError> {
+        ContractEntrypoints::new(&mut self.instance)
+            .migrate()
+            .map_err(WasmExecutionError::from)?;
+        Ok(())
+    }
+
     #[instrument(skip_all)]
     fn finalize(&mut self) -> Result<(), ExecutionError> {
         ContractEntrypoints::new(&mut self.instance)
@@ -14,6 +14,7 @@ use wasmtime::{Config, Engine, Linker, Module, Store};
 use super::{
     add_metering,
     module_cache::ModuleCache,
+    profiler::{ProfilingApi, WasmProfiler},
     runtime_api::{BaseRuntimeApi, ContractRuntimeApi, RuntimeApiData, ServiceRuntimeApi},
     ContractEntrypoints, ServiceEntrypoints, WasmExecutionError,
 };
@@ -69,6 +70,15 @@ impl WasmContractModule {
             .map_err(WasmExecutionError::LoadContractModule)?;
         Ok(WasmContractModule::Wasmtime { module })
     }
+
+    /// Compiles an already instrumented contract with Wasmtime, without caching it.
+    pub(super) fn compile_wasmtime(
+        instrumented_bytecode: Bytecode,
+    ) -> Result<Self, WasmExecutionError> {
+        let module = Module::new(&CONTRACT_ENGINE, instrumented_bytecode)
+            .map_err(WasmExecutionError::LoadContractModule)?;
+        Ok(WasmContractModule::Wasmtime { module })
+    }
 }
 
 impl<Runtime> WasmtimeContractInstance<Runtime>
@@ -77,12 +87,34 @@ where
 {
     /// Prepares a runtime instance to call into the Wasm contract.
     pub fn prepare(contract_module: &Module, runtime: Runtime) -> Result<Self, WasmExecutionError> {
+        Self::prepare_with_data(contract_module, RuntimeApiData::new(runtime))
+    }
+
+    /// Prepares a runtime instance to call into a Wasm contract instrumented for profiling,
+    /// recording its calls with `profiler`.
+    pub fn prepare_with_profiler(
+        contract_module: &Module,
+        runtime: Runtime,
+        profiler: WasmProfiler,
+    ) -> Result<Self, WasmExecutionError> {
+        let user_data = RuntimeApiData::new(runtime).with_profiler(profiler);
+        Self::prepare_with_data(contract_module, user_data)
+    }
+
+    fn prepare_with_data(
+        contract_module: &Module,
+        user_data: RuntimeApiData<Runtime>,
+    ) -> Result<Self, WasmExecutionError> {
         let mut linker = Linker::new(&CONTRACT_ENGINE);
 
         BaseRuntimeApi::export_to(&mut linker)?;
         ContractRuntimeApi::export_to(&mut linker)?;
+        // Only the instrumented bytecode may call the profiler: a contract importing
+        // `profiling-api` otherwise fails to load.
+        if user_data.profiler().is_some() {
+            ProfilingApi::export_to(&mut linker)?;
+        }
 
-        let user_data = RuntimeApiData::new(runtime);
         let mut store = Store::new(&CONTRACT_ENGINE, user_data);
         let instance = linker
             .instantiate(&mut store, contract_module)
@@ -174,6 +206,14 @@ where
         Ok(())
     }
 
+    #[instrument(skip_all)]
+    fn migrate(&mut self) -> Result<(), ExecutionError> {
+        ContractEntrypoints::new(&mut self.instance)
+            .migrate()
+            .map_err(WasmExecutionError::from)?;
+        Ok(())
+    }
+
     #[instrument(skip_all)]
     fn finalize(&mut self) -> Result<(), ExecutionError> {
         ContractEntrypoints::new(&mut self.instance)
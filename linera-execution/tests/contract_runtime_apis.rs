@@ -11,11 +11,12 @@ use assert_matches::assert_matches;
 use linera_base::{
     crypto::{AccountPublicKey, CryptoHash},
     data_types::{
-        Amount, ApplicationDescription, ApplicationPermissions, Blob, BlockHeight, Bytecode,
-        CompressedBytecode, OracleResponse,
+        AllowanceLimitError, AllowanceLimits, Amount, ApplicationDescription,
+        ApplicationPermissions, Blob, BlockHeight, Bytecode, CompressedBytecode, FeeSponsorship,
+        OracleResponse, SpendingCap, TimeDelta, Timestamp,
     },
     http,
-    identifiers::{Account, AccountOwner, ApplicationId, DataBlobHash, ModuleId},
+    identifiers::{Account, AccountOwner, ApplicationId, DataBlobHash, ModuleId, OwnerSpender},
     ownership::ChainOwnership,
     vm::VmRuntime,
 };
@@ -26,8 +27,8 @@ use linera_execution::{
         RegisterMockApplication, SystemExecutionState,
     },
     BaseRuntime, ContractRuntime, ExecutionError, ExecutionStateActor, Message, MessageContext,
-    Operation, OperationContext, ResourceController, SystemExecutionStateView,
-    TestExecutionRuntimeContext, TransactionOutcome, TransactionTracker,
+    Operation, OperationContext, ResourceController, ScheduledOperation, SystemExecutionStateView,
+    SystemOperation, TestExecutionRuntimeContext, TransactionOutcome, TransactionTracker,
 };
 use linera_views::context::MemoryContext;
 use test_case::{test_case, test_matrix};
@@ -1181,3 +1182,405 @@ async fn test_unauthorized_change_ownership_system_api() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Tests that an application can schedule an operation, and that it is executed once the
+/// block timestamp reaches the scheduled time.
+#[test_log::test(tokio::test)]
+async fn test_schedule_operation_system_api() -> anyhow::Result<()> {
+    let (state, chain_id) = SystemExecutionState::dummy_chain_state(0);
+    let mut view = state.into_view().await;
+
+    let (application_id, application, blobs) = view.register_mock_application(0).await?;
+    let scheduled_time = Timestamp::from(1_000);
+
+    application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _operation| {
+            assert_eq!(
+                runtime.schedule_operation_at(scheduled_time, vec![1, 2, 3])?,
+                0
+            );
+            Ok(vec![])
+        },
+    ));
+    application.expect_call(ExpectedCall::default_finalize());
+
+    let mut controller = ResourceController::default();
+    let mut txn_tracker = TransactionTracker::new_replaying_blobs(blobs.iter());
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(
+            create_dummy_operation_context(chain_id),
+            Operation::User {
+                application_id,
+                bytes: vec![],
+            },
+        )
+        .await?;
+
+    assert_eq!(
+        view.system.scheduled_operations.get(&0).await?,
+        Some(ScheduledOperation {
+            application_id,
+            timestamp: scheduled_time,
+            bytes: vec![1, 2, 3],
+        })
+    );
+
+    let execute_scheduled = Operation::system(SystemOperation::ExecuteScheduledOperation { id: 0 });
+    let context = OperationContext {
+        authenticated_owner: Some(AccountOwner::from(AccountPublicKey::test_key(1))),
+        ..create_dummy_operation_context(chain_id)
+    };
+
+    // The operation is not due yet.
+    let early_context = OperationContext {
+        timestamp: Timestamp::from(999),
+        ..context
+    };
+    let mut txn_tracker = TransactionTracker::new_replaying_blobs(blobs.iter());
+    let result = ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(early_context, execute_scheduled.clone())
+        .await;
+    assert_matches!(
+        result,
+        Err(ExecutionError::ScheduledOperationNotDue { id: 0, .. })
+    );
+
+    application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, operation| {
+            assert_eq!(operation, vec![1, 2, 3]);
+            assert_eq!(runtime.authenticated_owner()?, None);
+            Ok(vec![])
+        },
+    ));
+    application.expect_call(ExpectedCall::default_finalize());
+
+    let due_context = OperationContext {
+        timestamp: scheduled_time,
+        ..context
+    };
+    let mut txn_tracker = TransactionTracker::new_replaying_blobs(blobs.iter());
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(due_context, execute_scheduled.clone())
+        .await?;
+    assert!(view.system.scheduled_operations.indices().await?.is_empty());
+
+    // The operation cannot be executed twice.
+    let mut txn_tracker = TransactionTracker::new_replaying_blobs(blobs.iter());
+    let result = ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(due_context, execute_scheduled)
+        .await;
+    assert_matches!(result, Err(ExecutionError::ScheduledOperationNotFound(0)));
+
+    Ok(())
+}
+
+/// Tests that only the application that scheduled an operation can cancel it.
+#[test_log::test(tokio::test)]
+async fn test_cancel_scheduled_operation_system_api() -> anyhow::Result<()> {
+    let (state, chain_id) = SystemExecutionState::dummy_chain_state(0);
+    let mut view = state.into_view().await;
+
+    let (scheduler_id, scheduler, scheduler_blobs) = view.register_mock_application(0).await?;
+    let (other_id, other, other_blobs) = view.register_mock_application(1).await?;
+
+    scheduler.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _operation| {
+            runtime.schedule_operation_at(Timestamp::from(1_000), vec![])?;
+            Ok(vec![])
+        },
+    ));
+    scheduler.expect_call(ExpectedCall::default_finalize());
+    other.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _operation| {
+            assert_matches!(
+                runtime.cancel_scheduled_operation(0),
+                Err(ExecutionError::UnauthorizedScheduledOperationCancellation { id: 0, .. })
+            );
+            Ok(vec![])
+        },
+    ));
+    other.expect_call(ExpectedCall::default_finalize());
+    scheduler.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _operation| {
+            assert!(runtime.cancel_scheduled_operation(0)?);
+            assert!(!runtime.cancel_scheduled_operation(0)?);
+            Ok(vec![])
+        },
+    ));
+    scheduler.expect_call(ExpectedCall::default_finalize());
+
+    let mut controller = ResourceController::default();
+    for (application_id, blobs) in [
+        (scheduler_id, &scheduler_blobs),
+        (other_id, &other_blobs),
+        (scheduler_id, &scheduler_blobs),
+    ] {
+        let mut txn_tracker = TransactionTracker::new_replaying_blobs(blobs.iter());
+        ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+            .execute_operation(
+                create_dummy_operation_context(chain_id),
+                Operation::User {
+                    application_id,
+                    bytes: vec![],
+                },
+            )
+            .await?;
+    }
+
+    assert!(view.system.scheduled_operations.indices().await?.is_empty());
+
+    Ok(())
+}
+
+/// Tests that an application can only schedule operations, and have them executed, if the
+/// application permissions of the chain allow it to execute operations.
+#[test_log::test(tokio::test)]
+async fn test_scheduled_operation_application_permissions() -> anyhow::Result<()> {
+    let (state, chain_id) = SystemExecutionState::dummy_chain_state(0);
+    let mut view = state.into_view().await;
+
+    let (allowed_id, allowed, allowed_blobs) = view.register_mock_application(0).await?;
+    let (restricted_id, restricted, restricted_blobs) = view.register_mock_application(1).await?;
+    view.system
+        .application_permissions
+        .set(ApplicationPermissions::new_single(allowed_id));
+    let scheduled_time = Timestamp::from(1_000);
+
+    for application in [&restricted, &allowed] {
+        application.expect_call(ExpectedCall::execute_operation(
+            move |runtime, _operation| {
+                runtime.schedule_operation_at(scheduled_time, vec![])?;
+                Ok(vec![])
+            },
+        ));
+        application.expect_call(ExpectedCall::default_finalize());
+    }
+
+    let mut controller = ResourceController::default();
+    let mut results = Vec::new();
+    for (application_id, blobs) in [
+        (restricted_id, &restricted_blobs),
+        (allowed_id, &allowed_blobs),
+    ] {
+        let mut txn_tracker = TransactionTracker::new_replaying_blobs(blobs.iter());
+        results.push(
+            ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+                .execute_operation(
+                    create_dummy_operation_context(chain_id),
+                    Operation::User {
+                        application_id,
+                        bytes: vec![],
+                    },
+                )
+                .await,
+        );
+    }
+    assert_matches!(
+        &results[0],
+        Err(ExecutionError::ScheduledOperationNotPermitted(id)) if *id == restricted_id
+    );
+    assert_matches!(results[1], Ok(()));
+    assert_eq!(view.system.scheduled_operations.indices().await?, vec![0]);
+
+    // The permissions are checked again when the operation runs.
+    view.system
+        .application_permissions
+        .set(ApplicationPermissions::new_single(restricted_id));
+    let context = OperationContext {
+        timestamp: scheduled_time,
+        ..create_dummy_operation_context(chain_id)
+    };
+    let mut txn_tracker = TransactionTracker::new_replaying_blobs(allowed_blobs.iter());
+    let result = ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(
+            context,
+            Operation::system(SystemOperation::ExecuteScheduledOperation { id: 0 }),
+        )
+        .await;
+    assert_matches!(
+        result,
+        Err(ExecutionError::ScheduledOperationNotPermitted(id)) if id == allowed_id
+    );
+    assert_eq!(view.system.scheduled_operations.indices().await?, vec![0]);
+
+    Ok(())
+}
+
+/// Tests that an allowance approved with limits can only be used before it expires and up
+/// to its cap in each period.
+#[test_log::test(tokio::test)]
+async fn test_approve_with_limits_system_api() -> anyhow::Result<()> {
+    let (state, chain_id) = SystemExecutionState::dummy_chain_state(0);
+    let mut view = state.into_view().await;
+    let owner = AccountOwner::from(AccountPublicKey::test_key(1));
+    view.system
+        .balances
+        .insert(&owner, Amount::from_tokens(100))?;
+
+    let (application_id, application, blobs) = view.register_mock_application(0).await?;
+    let spender = AccountOwner::from(application_id);
+    let recipient = Account::new(chain_id, AccountOwner::from(AccountPublicKey::test_key(2)));
+    let limits = AllowanceLimits {
+        expiry: Some(Timestamp::from(10_000)),
+        period_cap: Some(SpendingCap {
+            amount: Amount::from_tokens(10),
+            period: TimeDelta::from_micros(1_000),
+        }),
+    };
+
+    application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _operation| {
+            runtime.approve(owner, spender, Amount::from_tokens(50), limits)?;
+            Ok(vec![])
+        },
+    ));
+    application.expect_call(ExpectedCall::default_finalize());
+    let steps = [
+        (0, None),
+        (500, Some((6, true))),
+        (500, Some((5, false))),
+        (1_500, Some((10, true))),
+        (10_000, Some((1, false))),
+    ];
+
+    let context = OperationContext {
+        authenticated_owner: Some(owner),
+        ..create_dummy_operation_context(chain_id)
+    };
+    let mut controller = ResourceController::default();
+    for (timestamp, transfer) in steps {
+        if let Some((tokens, succeeds)) = transfer {
+            application.expect_call(ExpectedCall::execute_operation(
+                move |runtime, _operation| {
+                    runtime.transfer_from(
+                        owner,
+                        spender,
+                        recipient,
+                        Amount::from_tokens(tokens),
+                    )?;
+                    Ok(vec![])
+                },
+            ));
+            if succeeds {
+                application.expect_call(ExpectedCall::default_finalize());
+            }
+        }
+        view.system.progress.get_mut().timestamp = Timestamp::from(timestamp);
+        let mut txn_tracker = TransactionTracker::new_replaying_blobs(blobs.iter());
+        let result = ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+            .execute_operation(
+                context,
+                Operation::User {
+                    application_id,
+                    bytes: vec![],
+                },
+            )
+            .await;
+        match (timestamp, transfer) {
+            (_, None | Some((_, true))) => {
+                result?;
+            }
+            (500, _) => assert_matches!(
+                result,
+                Err(ExecutionError::AllowanceLimit {
+                    error: AllowanceLimitError::PeriodCapExceeded { .. },
+                    ..
+                })
+            ),
+            _ => assert_matches!(
+                result,
+                Err(ExecutionError::AllowanceLimit {
+                    error: AllowanceLimitError::Expired(_),
+                    ..
+                })
+            ),
+        }
+    }
+
+    let owner_spender = OwnerSpender::new(owner, spender);
+    assert_eq!(
+        view.system.allowances.get(&owner_spender).await?,
+        Some(Amount::from_tokens(34))
+    );
+    let usage = view
+        .system
+        .allowance_limits
+        .get(&owner_spender)
+        .await?
+        .expect("the allowance should still have limits");
+    assert_eq!(usage.period_start, Timestamp::from(1_000));
+    assert_eq!(usage.spent_in_period, Amount::from_tokens(10));
+    assert_eq!(
+        view.system.balances.get(&owner).await?,
+        Some(Amount::from_tokens(84))
+    );
+
+    Ok(())
+}
+
+/// Tests that an application can approve fee sponsorships paid from its own account, which
+/// blocks can then use without a signature.
+#[test_log::test(tokio::test)]
+async fn test_approve_fee_sponsorship_system_api() -> anyhow::Result<()> {
+    let (state, chain_id) = SystemExecutionState::dummy_chain_state(0);
+    let mut view = state.into_view().await;
+    let (application_id, application, blobs) = view.register_mock_application(0).await?;
+    let sponsor = AccountOwner::from(application_id);
+    let other_owner = AccountOwner::from(AccountPublicKey::test_key(1));
+    let max_fees = Amount::from_tokens(2);
+    let expiry = Timestamp::from(1_000);
+
+    application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _operation| {
+            runtime.approve_fee_sponsorship(sponsor, max_fees, expiry)?;
+            Ok(vec![])
+        },
+    ));
+    application.expect_call(ExpectedCall::default_finalize());
+    // The application can't approve sponsorships paid by other accounts.
+    application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _operation| {
+            runtime.approve_fee_sponsorship(other_owner, max_fees, expiry)?;
+            Ok(vec![])
+        },
+    ));
+
+    let context = create_dummy_operation_context(chain_id);
+    let mut controller = ResourceController::default();
+    let operation = Operation::User {
+        application_id,
+        bytes: vec![],
+    };
+    let mut txn_tracker = TransactionTracker::new_replaying_blobs(blobs.iter());
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(context, operation.clone())
+        .await?;
+    let mut txn_tracker = TransactionTracker::new_replaying_blobs(blobs.iter());
+    let result = ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(context, operation)
+        .await;
+    assert_matches!(
+        result,
+        Err(ExecutionError::UnauthorizedFeeSponsorship(owner)) if owner == other_owner
+    );
+
+    let sponsorship = FeeSponsorship {
+        sponsor,
+        chain_id,
+        max_fees,
+        expiry,
+    };
+    let fee_sponsor = view.system.fee_sponsor(&sponsorship, None).await?;
+    assert_eq!(fee_sponsor.owner, sponsor);
+    assert_eq!(fee_sponsor.max_fees, max_fees);
+    let unapproved = FeeSponsorship {
+        sponsor: other_owner,
+        ..sponsorship
+    };
+    assert_matches!(
+        view.system.fee_sponsor(&unapproved, None).await,
+        Err(ExecutionError::UnauthorizedFeeSponsorship(owner)) if owner == other_owner
+    );
+
+    Ok(())
+}
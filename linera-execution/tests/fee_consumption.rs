@@ -181,6 +181,17 @@ use test_case::test_case;
     Some(Amount::from_tokens(1_000));
     "with all fee spend operations"
 )]
+#[test_case(
+    vec![
+        FeeSpend::ScheduleOperation(vec![1, 2, 3]),
+        FeeSpend::Fuel(13),
+        FeeSpend::ScheduleOperation(vec![]),
+    ],
+    Amount::from_tokens(2),
+    Some(Amount::from_tokens(1)),
+    Some(Amount::from_tokens(1_000));
+    "with scheduled operations"
+)]
 // TODO(#1601): Add more test cases
 #[tokio::test]
 async fn test_fee_consumption(
@@ -237,6 +248,7 @@ async fn test_fee_consumption(
         blob_published: Amount::from_tokens(97),
         blob_byte_read: Amount::from_tokens(101),
         blob_byte_published: Amount::from_tokens(103),
+        scheduled_operation: Amount::from_tokens(107),
         http_request_allow_list: BTreeSet::new(),
         free_application_ids: BTreeSet::new(),
         flags: BTreeSet::new(),
@@ -371,13 +383,18 @@ pub enum FeeSpend {
     HttpRequest,
     /// Byte from runtime.
     Runtime(u32),
+    /// Schedules an operation with the given bytes for a later block.
+    ScheduleOperation(Vec<u8>),
 }
 
 impl FeeSpend {
     /// Returns the [`OracleResponse`]s necessary for executing this runtime operation.
     pub fn expected_oracle_responses(&self) -> Vec<OracleResponse> {
         match self {
-            FeeSpend::Fuel(_) | FeeSpend::Read(_, _) | FeeSpend::Runtime(_) => vec![],
+            FeeSpend::Fuel(_)
+            | FeeSpend::Read(_, _)
+            | FeeSpend::Runtime(_)
+            | FeeSpend::ScheduleOperation(_) => vec![],
             FeeSpend::QueryServiceOracle => {
                 vec![OracleResponse::Service(vec![])]
             }
@@ -399,6 +416,9 @@ impl FeeSpend {
             FeeSpend::QueryServiceOracle => policy.service_as_oracle_query,
             FeeSpend::HttpRequest => policy.http_request,
             FeeSpend::Runtime(bytes) => policy.byte_runtime.saturating_mul(*bytes as u128),
+            FeeSpend::ScheduleOperation(bytes) => policy
+                .scheduled_operation
+                .saturating_add(policy.operation_byte.saturating_mul(bytes.len() as u128)),
         }
     }
 
@@ -422,6 +442,10 @@ impl FeeSpend {
                 runtime.perform_http_request(http::Request::get("http://dummy.url"))?;
                 Ok(())
             }
+            FeeSpend::ScheduleOperation(bytes) => {
+                runtime.schedule_operation_at(Timestamp::from(1_000), bytes)?;
+                Ok(())
+            }
         }
     }
 }
@@ -0,0 +1,44 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+pragma solidity ^0.8.0;
+
+import "./Linera.sol";
+
+contract SystemOperations {
+
+    function single_owner_ownership(bytes32 owner) internal pure returns (Linera.ChainOwnership memory) {
+        Linera.AccountOwner[] memory super_owners = new Linera.AccountOwner[](1);
+        super_owners[0] = Linera.AccountOwner(1, 0, owner, bytes20(0));
+        Linera.AccountOwnerWeight[] memory owners = new Linera.AccountOwnerWeight[](0);
+        Linera.opt_TimeDelta memory fast_round_duration = Linera.opt_TimeDelta(false, 0);
+        Linera.TimeoutConfig memory timeout_config = Linera.TimeoutConfig(
+            fast_round_duration,
+            Linera.TimeDelta(10000000),
+            Linera.TimeDelta(1000000),
+            Linera.TimeDelta(86400000000));
+        return Linera.ChainOwnership(super_owners, owners, 7, false, timeout_config);
+    }
+
+    function test_create_data_blob(bytes memory data) external {
+        bytes32 hash = Linera.create_data_blob(data);
+        require(hash != bytes32(0));
+    }
+
+    function test_change_ownership(bytes32 owner) external {
+        Linera.change_ownership(single_owner_ownership(owner));
+        Linera.ChainOwnership memory ownership = Linera.chain_ownership();
+        require(ownership.super_owners.length == 1);
+        require(ownership.super_owners[0].address32 == owner);
+        require(ownership.multi_leader_rounds == 7);
+    }
+
+    function test_open_chain(bytes32 owner) external {
+        Linera.AccountOwner memory account = Linera.AccountOwner(0, 0, bytes32(0), bytes20(0));
+        Linera.ChainId memory chain_id = Linera.open_chain(single_owner_ownership(owner), account, 0);
+        require(chain_id.value != Linera.chain_id().value);
+    }
+
+    function test_close_chain() external {
+        Linera.close_chain();
+    }
+}
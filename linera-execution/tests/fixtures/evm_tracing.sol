@@ -0,0 +1,18 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+pragma solidity ^0.8.0;
+
+contract Tracing {
+    uint256 value;
+
+    event ValueSet(uint256 value);
+
+    function set_value(uint256 new_value) external {
+        value = new_value;
+        emit ValueSet(new_value);
+    }
+
+    function fail_with_reason() external pure {
+        require(false, "always fails");
+    }
+}
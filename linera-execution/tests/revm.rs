@@ -3,15 +3,21 @@
 
 #![cfg(with_revm)]
 
-use std::sync::Arc;
+use std::{collections::BTreeSet, sync::Arc};
 
+use alloy_primitives::U256;
 use alloy_sol_types::{sol, SolCall, SolValue};
 use linera_base::{
-    data_types::{Amount, Blob, BlockHeight, Timestamp},
+    crypto::CryptoHash,
+    data_types::{Amount, ApplicationPermissions, Blob, BlockHeight, ChainDescription, Timestamp},
+    identifiers::{AccountOwner, ApplicationId, BlobType},
     vm::{EvmInstantiation, EvmOperation, EvmQuery},
 };
 use linera_execution::{
-    evm::revm::{EvmContractModule, EvmServiceModule},
+    evm::{
+        revm::{EvmContractModule, EvmServiceModule},
+        trace::EvmTraceCollector,
+    },
     test_utils::{
         create_dummy_user_application_description, dummy_chain_description,
         solidity::{load_solidity_example, read_evm_u64_entry},
@@ -463,3 +469,288 @@ async fn test_basic_evm_features() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_evm_system_operations() -> anyhow::Result<()> {
+    let module = load_solidity_example("tests/fixtures/evm_system_operations.sol")?;
+
+    sol! {
+        function test_create_data_blob(bytes data);
+        function test_change_ownership(bytes32 owner);
+        function test_open_chain(bytes32 owner);
+        function test_close_chain();
+    }
+
+    let constructor_argument = Vec::<u8>::new();
+    let constructor_argument = serde_json::to_string(&constructor_argument)?.into_bytes();
+    let instantiation_argument = EvmInstantiation::default();
+    let instantiation_argument = serde_json::to_string(&instantiation_argument)?.into_bytes();
+    let (mut app_desc, contract_blob, service_blob) = create_dummy_user_application_description(1);
+    app_desc.parameters = constructor_argument;
+    let chain_id = app_desc.creator_chain_id;
+    let app_id = ApplicationId::from(&app_desc);
+    let state = SystemExecutionState {
+        description: Some(dummy_chain_description(0)),
+        application_permissions: ApplicationPermissions {
+            manage_chain: vec![app_id],
+            ..ApplicationPermissions::default()
+        },
+        ..Default::default()
+    };
+    let mut view = state
+        .into_view_with(chain_id, ExecutionRuntimeConfig::default())
+        .await;
+    let app_desc_blob_id = Blob::new_application_description(&app_desc).id();
+    let contract_blob_id = contract_blob.id();
+    let service_blob_id = service_blob.id();
+
+    let contract = EvmContractModule::Revm {
+        module: module.clone(),
+    };
+    {
+        let context = view.context();
+        let pinned = context.extra().user_contracts().pin();
+        pinned.insert(app_id, contract.clone().into());
+    }
+
+    let service = EvmServiceModule::Revm { module };
+    {
+        let context = view.context();
+        let pinned = context.extra().user_services().pin();
+        pinned.insert(app_id, service.into());
+    }
+
+    view.simulate_instantiation(
+        contract.into(),
+        Timestamp::from(2),
+        app_desc,
+        instantiation_argument,
+        contract_blob,
+        service_blob,
+    )
+    .await?;
+
+    let operation_context = OperationContext {
+        chain_id,
+        height: BlockHeight(0),
+        round: Some(0),
+        authenticated_owner: None,
+        timestamp: Default::default(),
+    };
+    let mut controller = ResourceController::new(
+        Arc::new(ResourceControlPolicy::default()),
+        ResourceTracker::default(),
+        None,
+    );
+    let mut txn_tracker = TransactionTracker::new_replaying_blobs([
+        app_desc_blob_id,
+        contract_blob_id,
+        service_blob_id,
+    ]);
+
+    let owner_hash = CryptoHash::test_hash("owner");
+    let owner = AccountOwner::Address32(owner_hash);
+
+    // Creating a data blob from an EVM contract.
+    let data = b"Some data blob".to_vec();
+    let operation = test_create_data_blobCall {
+        data: data.clone().into(),
+    };
+    let bytes = operation_to_bytes(&operation)?;
+    let operation = Operation::User {
+        application_id: app_id,
+        bytes,
+    };
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(operation_context, operation)
+        .await?;
+    assert!(txn_tracker
+        .created_blobs()
+        .contains_key(&Blob::new_data(data).id()));
+
+    // Opening a child chain owned by `owner`.
+    let operation = test_open_chainCall {
+        owner: *owner_hash.as_bytes(),
+    };
+    let bytes = operation_to_bytes(&operation)?;
+    let operation = Operation::User {
+        application_id: app_id,
+        bytes,
+    };
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(operation_context, operation)
+        .await?;
+    let child_description = txn_tracker
+        .created_blobs()
+        .values()
+        .find(|content| content.blob_type() == BlobType::ChainDescription)
+        .expect("the child chain description should have been created");
+    let child_description: ChainDescription = bcs::from_bytes(child_description.bytes())?;
+    assert_eq!(
+        child_description.config().ownership.super_owners,
+        BTreeSet::from([owner])
+    );
+
+    // Handing the current chain over to `owner`.
+    let operation = test_change_ownershipCall {
+        owner: *owner_hash.as_bytes(),
+    };
+    let bytes = operation_to_bytes(&operation)?;
+    let operation = Operation::User {
+        application_id: app_id,
+        bytes,
+    };
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(operation_context, operation)
+        .await?;
+    let ownership = view.system.ownership.get().await?;
+    assert_eq!(ownership.super_owners, BTreeSet::from([owner]));
+    assert_eq!(ownership.multi_leader_rounds, 7);
+
+    // Closing the current chain.
+    let operation = test_close_chainCall {};
+    let bytes = operation_to_bytes(&operation)?;
+    let operation = Operation::User {
+        application_id: app_id,
+        bytes,
+    };
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(operation_context, operation)
+        .await?;
+    assert!(view.system.closed.get());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_evm_tracing() -> anyhow::Result<()> {
+    let module = load_solidity_example("tests/fixtures/evm_tracing.sol")?;
+
+    sol! {
+        function set_value(uint256 new_value);
+        function fail_with_reason();
+    }
+
+    let constructor_argument = Vec::<u8>::new();
+    let constructor_argument = serde_json::to_string(&constructor_argument)?.into_bytes();
+    let instantiation_argument = EvmInstantiation::default();
+    let instantiation_argument = serde_json::to_string(&instantiation_argument)?.into_bytes();
+    let (mut app_desc, contract_blob, service_blob) = create_dummy_user_application_description(1);
+    app_desc.parameters = constructor_argument;
+    let chain_id = app_desc.creator_chain_id;
+    let app_id = ApplicationId::from(&app_desc);
+    let state = SystemExecutionState {
+        description: Some(dummy_chain_description(0)),
+        ..Default::default()
+    };
+    let mut view = state
+        .into_view_with(chain_id, ExecutionRuntimeConfig::default())
+        .await;
+    let app_desc_blob_id = Blob::new_application_description(&app_desc).id();
+    let contract_blob_id = contract_blob.id();
+    let service_blob_id = service_blob.id();
+
+    let contract = EvmContractModule::Revm {
+        module: module.clone(),
+    };
+    {
+        let context = view.context();
+        let pinned = context.extra().user_contracts().pin();
+        pinned.insert(app_id, contract.clone().into());
+    }
+
+    let service = EvmServiceModule::Revm { module };
+    {
+        let context = view.context();
+        let pinned = context.extra().user_services().pin();
+        pinned.insert(app_id, service.into());
+    }
+
+    view.simulate_instantiation(
+        contract.into(),
+        Timestamp::from(2),
+        app_desc,
+        instantiation_argument,
+        contract_blob,
+        service_blob,
+    )
+    .await?;
+
+    let operation_context = OperationContext {
+        chain_id,
+        height: BlockHeight(0),
+        round: Some(0),
+        authenticated_owner: None,
+        timestamp: Default::default(),
+    };
+    let mut controller = ResourceController::new(
+        Arc::new(ResourceControlPolicy::default()),
+        ResourceTracker::default(),
+        None,
+    );
+    let mut txn_tracker = TransactionTracker::new_replaying_blobs([
+        app_desc_blob_id,
+        contract_blob_id,
+        service_blob_id,
+    ]);
+
+    // Without tracing, nothing is recorded.
+    let evm_traces = EvmTraceCollector::default();
+    let operation = set_valueCall {
+        new_value: U256::from(41),
+    };
+    let operation = Operation::User {
+        application_id: app_id,
+        bytes: operation_to_bytes(&operation)?,
+    };
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(operation_context, operation)
+        .await?;
+    assert!(evm_traces.take().is_empty());
+
+    // A successful call records its storage accesses and logs.
+    txn_tracker.trace_evm(evm_traces.clone());
+    let operation = set_valueCall {
+        new_value: U256::from(42),
+    };
+    let operation = Operation::User {
+        application_id: app_id,
+        bytes: operation_to_bytes(&operation)?,
+    };
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(operation_context, operation)
+        .await?;
+    let traces = evm_traces.take();
+    assert_eq!(traces.len(), 1);
+    let trace = &traces[0];
+    assert_eq!(trace.call_trace.kind, "CALL");
+    assert_eq!(trace.call_trace.error, None);
+    assert_eq!(trace.call_trace.logs.len(), 1);
+    assert!(trace.struct_logs.iter().any(|log| log.op == "SSTORE"));
+    let new_value = format!("0x{:064x}", 42);
+    assert!(trace
+        .storage_accesses
+        .iter()
+        .any(|access| access.op == "SSTORE" && access.value == new_value));
+
+    // A reverted call is traced too, with its revert reason.
+    let operation = fail_with_reasonCall {};
+    let operation = Operation::User {
+        application_id: app_id,
+        bytes: operation_to_bytes(&operation)?,
+    };
+    let result = ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(operation_context, operation)
+        .await;
+    assert!(result.is_err());
+    let traces = evm_traces.take();
+    assert_eq!(traces.len(), 1);
+    let call_trace = &traces[0].call_trace;
+    assert_eq!(call_trace.error.as_deref(), Some("execution reverted"));
+    assert_eq!(call_trace.revert_reason.as_deref(), Some("always fails"));
+    let json = serde_json::to_value(call_trace)?;
+    assert_eq!(json["type"], "CALL");
+    assert_eq!(json["revertReason"], "always fails");
+
+    Ok(())
+}
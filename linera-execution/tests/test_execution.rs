@@ -10,22 +10,28 @@ use linera_base::{
         Amount, ApplicationPermissions, Blob, BlockHeight, ChainDescription, ChainOrigin,
         InitialChainConfig, Resources, SendMessageRequest, Timestamp,
     },
-    identifiers::{Account, AccountOwner, BlobType},
+    identifiers::{Account, AccountOwner, ApplicationId, BlobType, EventId, ModuleId, StreamId},
     ownership::ChainOwnership,
 };
 use linera_execution::{
     committee::Committee,
+    system::{application_upgrade_stream_name, ApplicationUpgrade, ApplicationUpgradeEventData},
     test_utils::{
         blob_oracle_responses, create_dummy_message_context, create_dummy_operation_context,
-        create_dummy_user_application_registrations, dummy_chain_description,
-        dummy_chain_description_with_ownership_and_balance, ExpectedCall, RegisterMockApplication,
-        SystemExecutionState,
+        create_dummy_user_application_description, create_dummy_user_application_registrations,
+        dummy_chain_description, dummy_chain_description_with_ownership_and_balance, ExpectedCall,
+        MockApplication, RegisterMockApplication, SystemExecutionState,
     },
     BaseRuntime, ContractRuntime, ExecutionError, ExecutionRuntimeContext, ExecutionStateActor,
-    Message, Operation, OperationContext, OutgoingMessage, Query, QueryContext, QueryOutcome,
-    QueryResponse, ResourceController, SystemOperation, TransactionTracker,
+    ExecutionStateView, Message, MessageContext, Operation, OperationContext, OutgoingMessage,
+    Query, QueryContext, QueryOutcome, QueryResponse, ResourceController, SystemOperation,
+    TestExecutionRuntimeContext, TransactionTracker,
+};
+use linera_views::{
+    batch::Batch,
+    context::{Context, MemoryContext},
+    views::View,
 };
-use linera_views::{batch::Batch, context::Context, views::View};
 use test_case::test_case;
 
 #[tokio::test]
@@ -1476,3 +1482,297 @@ async fn test_read_application_description() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Registers an upgradeable mock application created on `dummy_chain_description(1)`,
+/// together with a mock for the module it can be upgraded to.
+async fn register_upgradeable_mock_application(
+    view: &mut ExecutionStateView<MemoryContext<TestExecutionRuntimeContext>>,
+    upgrade_authority: AccountOwner,
+) -> anyhow::Result<(ApplicationId, ModuleId, MockApplication, MockApplication)> {
+    let (mut description, contract, service) = create_dummy_user_application_description(0);
+    let (application_id, application) = view
+        .register_mock_application_with(description.clone(), contract, service)
+        .await?;
+    view.system
+        .upgrade_authorities
+        .insert(&application_id, upgrade_authority)?;
+
+    let (new_description, new_contract, new_service) = create_dummy_user_application_description(1);
+    let new_module_id = new_description.module_id;
+    description.module_id = new_module_id;
+    let (_, upgraded_application) = view
+        .register_mock_application_with(description, new_contract, new_service)
+        .await?;
+
+    Ok((
+        application_id,
+        new_module_id,
+        application,
+        upgraded_application,
+    ))
+}
+
+/// Tests that the upgrade authority can upgrade an application on its creator chain.
+#[tokio::test]
+async fn test_upgrade_application() -> anyhow::Result<()> {
+    let (state, chain_id) = SystemExecutionState::dummy_chain_state(1);
+    let mut view = state.into_view().await;
+    let owner = AccountOwner::from(AccountPublicKey::test_key(0));
+    let (application_id, new_module_id, _application, upgraded_application) =
+        register_upgradeable_mock_application(&mut view, owner).await?;
+
+    upgraded_application.expect_call(ExpectedCall::migrate(|_runtime| Ok(())));
+    upgraded_application.expect_call(ExpectedCall::default_finalize());
+
+    let context = OperationContext {
+        authenticated_owner: Some(owner),
+        ..create_dummy_operation_context(chain_id)
+    };
+    let mut controller = ResourceController::default();
+    let mut txn_tracker = TransactionTracker::default();
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(
+            context,
+            Operation::system(SystemOperation::UpgradeApplication {
+                application_id,
+                module_id: new_module_id,
+            }),
+        )
+        .await?;
+
+    let upgrade = view
+        .system
+        .application_upgrades
+        .get(&application_id)
+        .await?
+        .expect("the upgrade should be recorded");
+    assert_eq!(upgrade.module_id, new_module_id);
+    assert_eq!(upgrade.version, 1);
+
+    let events = txn_tracker.into_outcome()?.events;
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        events[0].stream_id,
+        StreamId::system(application_upgrade_stream_name(application_id))
+    );
+    assert_eq!(events[0].index, 0);
+    Ok(())
+}
+
+/// Tests that only the upgrade authority can upgrade an application.
+#[tokio::test]
+async fn test_unauthorized_application_upgrade() -> anyhow::Result<()> {
+    let (state, chain_id) = SystemExecutionState::dummy_chain_state(1);
+    let mut view = state.into_view().await;
+    let owner = AccountOwner::from(AccountPublicKey::test_key(0));
+    let (application_id, new_module_id, _application, _upgraded_application) =
+        register_upgradeable_mock_application(&mut view, owner).await?;
+
+    let context = OperationContext {
+        authenticated_owner: Some(AccountOwner::from(AccountPublicKey::test_key(1))),
+        ..create_dummy_operation_context(chain_id)
+    };
+    let mut controller = ResourceController::default();
+    let mut txn_tracker = TransactionTracker::default();
+    let result = ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(
+            context,
+            Operation::system(SystemOperation::UpgradeApplication {
+                application_id,
+                module_id: new_module_id,
+            }),
+        )
+        .await;
+
+    assert_matches!(
+        result,
+        Err(ExecutionError::UnauthorizedApplicationUpgrade(id)) if *id == application_id
+    );
+    assert!(view
+        .system
+        .application_upgrades
+        .get(&application_id)
+        .await?
+        .is_none());
+    Ok(())
+}
+
+/// Tests that another chain adopts an upgrade published by the creator chain.
+#[tokio::test]
+async fn test_process_application_upgrade() -> anyhow::Result<()> {
+    let (state, chain_id) = SystemExecutionState::dummy_chain_state(0);
+    let mut view = state.into_view().await;
+    let owner = AccountOwner::from(AccountPublicKey::test_key(0));
+    let (application_id, new_module_id, _application, upgraded_application) =
+        register_upgradeable_mock_application(&mut view, owner).await?;
+    let creator_chain_id = dummy_chain_description(1).id();
+
+    let event_data = ApplicationUpgradeEventData {
+        application_id,
+        module_id: new_module_id,
+        previous_module_id: create_dummy_user_application_description(0).0.module_id,
+    };
+    view.context()
+        .extra()
+        .add_events([(
+            EventId {
+                chain_id: creator_chain_id,
+                stream_id: StreamId::system(application_upgrade_stream_name(application_id)),
+                index: 0,
+            },
+            bcs::to_bytes(&event_data)?,
+        )])
+        .await?;
+
+    upgraded_application.expect_call(ExpectedCall::migrate(|_runtime| Ok(())));
+    upgraded_application.expect_call(ExpectedCall::default_finalize());
+
+    let context = create_dummy_operation_context(chain_id);
+    let mut controller = ResourceController::default();
+    let mut txn_tracker = TransactionTracker::default();
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(
+            context,
+            Operation::system(SystemOperation::ProcessApplicationUpgrade { application_id }),
+        )
+        .await?;
+
+    let upgrade = view
+        .system
+        .application_upgrades
+        .get(&application_id)
+        .await?
+        .expect("the upgrade should be recorded");
+    assert_eq!(upgrade.module_id, new_module_id);
+    assert_eq!(upgrade.version, 1);
+
+    // The application now runs the upgraded module.
+    upgraded_application.expect_call(ExpectedCall::execute_operation(|_runtime, _operation| {
+        Ok(vec![])
+    }));
+    upgraded_application.expect_call(ExpectedCall::default_finalize());
+    let mut txn_tracker = TransactionTracker::default();
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(
+            context,
+            Operation::User {
+                application_id,
+                bytes: vec![],
+            },
+        )
+        .await?;
+    Ok(())
+}
+
+/// Tests that messages sent by an upgraded application carry its version.
+#[tokio::test]
+async fn test_upgraded_application_sends_versioned_messages() -> anyhow::Result<()> {
+    let (state, chain_id) = SystemExecutionState::dummy_chain_state(1);
+    let mut view = state.into_view().await;
+    let owner = AccountOwner::from(AccountPublicKey::test_key(0));
+    let (application_id, new_module_id, _application, upgraded_application) =
+        register_upgradeable_mock_application(&mut view, owner).await?;
+    view.system.application_upgrades.insert(
+        &application_id,
+        ApplicationUpgrade {
+            module_id: new_module_id,
+            version: 1,
+        },
+    )?;
+
+    let destination = dummy_chain_description(0).id();
+    upgraded_application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _operation| {
+            runtime.send_message(SendMessageRequest {
+                destination,
+                authenticated: false,
+                is_tracked: false,
+                grant: Resources::default(),
+                message: b"msg".to_vec(),
+            })?;
+            Ok(vec![])
+        },
+    ));
+    upgraded_application.expect_call(ExpectedCall::default_finalize());
+
+    let context = create_dummy_operation_context(chain_id);
+    let mut controller = ResourceController::default();
+    let mut txn_tracker = TransactionTracker::default();
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(
+            context,
+            Operation::User {
+                application_id,
+                bytes: vec![],
+            },
+        )
+        .await?;
+
+    let outgoing_messages = txn_tracker.into_outcome()?.outgoing_messages;
+    assert_eq!(outgoing_messages.len(), 1);
+    assert_eq!(
+        outgoing_messages[0].message,
+        Message::VersionedUser {
+            application_id,
+            version: 1,
+            bytes: b"msg".to_vec(),
+        }
+    );
+    Ok(())
+}
+
+/// Tests that a chain adopts the upgrades of an application before executing a message
+/// sent by a newer version of it.
+#[tokio::test]
+async fn test_versioned_message_adopts_application_upgrade() -> anyhow::Result<()> {
+    let (state, chain_id) = SystemExecutionState::dummy_chain_state(0);
+    let mut view = state.into_view().await;
+    let owner = AccountOwner::from(AccountPublicKey::test_key(0));
+    let (application_id, new_module_id, _application, upgraded_application) =
+        register_upgradeable_mock_application(&mut view, owner).await?;
+    let creator_chain_id = dummy_chain_description(1).id();
+
+    let event_data = ApplicationUpgradeEventData {
+        application_id,
+        module_id: new_module_id,
+        previous_module_id: create_dummy_user_application_description(0).0.module_id,
+    };
+    view.context()
+        .extra()
+        .add_events([(
+            EventId {
+                chain_id: creator_chain_id,
+                stream_id: StreamId::system(application_upgrade_stream_name(application_id)),
+                index: 0,
+            },
+            bcs::to_bytes(&event_data)?,
+        )])
+        .await?;
+
+    // The upgrade is adopted first, and the message is then handled by the new module.
+    upgraded_application.expect_call(ExpectedCall::migrate(|_runtime| Ok(())));
+    upgraded_application.expect_call(ExpectedCall::default_finalize());
+    upgraded_application.expect_call(ExpectedCall::execute_message(|_runtime, _message| Ok(())));
+    upgraded_application.expect_call(ExpectedCall::default_finalize());
+
+    let context = MessageContext {
+        origin: creator_chain_id,
+        ..create_dummy_message_context(chain_id, None)
+    };
+    let mut controller = ResourceController::default();
+    let mut txn_tracker = TransactionTracker::default();
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_message(
+            context,
+            Message::VersionedUser {
+                application_id,
+                version: 1,
+                bytes: vec![],
+            },
+            None,
+        )
+        .await?;
+
+    assert_eq!(view.system.application_version(&application_id).await?, 1);
+    Ok(())
+}
@@ -5,15 +5,15 @@
 
 use std::sync::Arc;
 
-use linera_base::data_types::{Amount, Blob, BlockHeight, Timestamp};
+use linera_base::data_types::{Amount, Blob, BlockHeight, Bytecode, Timestamp};
 use linera_execution::{
     test_utils::{
         create_dummy_user_application_description, dummy_chain_description, SystemExecutionState,
     },
     ExecutionRuntimeConfig, ExecutionRuntimeContext, ExecutionStateActor, Operation,
-    OperationContext, Query, QueryContext, QueryOutcome, QueryResponse, ResourceControlPolicy,
-    ResourceController, ResourceTracker, TransactionTracker, WasmContractModule, WasmRuntime,
-    WasmServiceModule,
+    OperationContext, ProfiledWasmContractModule, Query, QueryContext, QueryOutcome, QueryResponse,
+    ResourceControlPolicy, ResourceController, ResourceTracker, TransactionTracker,
+    WasmContractModule, WasmProfile, WasmRuntime, WasmServiceModule,
 };
 use linera_views::{context::Context as _, views::View};
 use serde_json::json;
@@ -146,3 +146,144 @@ async fn test_fuel_for_counter_wasm_application(
     assert!(operations.is_empty());
     Ok(())
 }
+
+/// Test that profiling the "counter" example application attributes all of the consumed fuel
+/// to the call stacks of its functions.
+#[cfg_attr(with_wasmer, test_case(WasmRuntime::Wasmer; "wasmer"))]
+#[cfg_attr(with_wasmtime, test_case(WasmRuntime::Wasmtime; "wasmtime"))]
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn test_profile_counter_wasm_application(wasm_runtime: WasmRuntime) -> anyhow::Result<()> {
+    let chain_description = dummy_chain_description(0);
+    let chain_id = chain_description.id();
+    let state = SystemExecutionState {
+        description: Some(chain_description),
+        ..Default::default()
+    };
+    let mut view = state
+        .into_view_with(chain_id, ExecutionRuntimeConfig::default())
+        .await;
+    let (app_desc, contract_blob, service_blob) = create_dummy_user_application_description(1);
+    let app_id = From::from(&app_desc);
+    let blob_ids = vec![
+        Blob::new_application_description(&app_desc).id(),
+        contract_blob.id(),
+        service_blob.id(),
+    ];
+
+    let profile = WasmProfile::default();
+    let bytecode = Bytecode::load_from_file("tests/fixtures/counter_contract.wasm").await?;
+    let contract = ProfiledWasmContractModule::new(bytecode, wasm_runtime, profile.clone())?;
+    {
+        let context = view.context();
+        let pinned = context.extra().user_contracts().pin();
+        pinned.insert(app_id, contract.into());
+    }
+    view.context()
+        .extra()
+        .add_blobs([
+            contract_blob,
+            service_blob,
+            Blob::new_application_description(&app_desc),
+        ])
+        .await?;
+
+    let context = OperationContext {
+        chain_id,
+        height: BlockHeight(0),
+        round: Some(0),
+        authenticated_owner: None,
+        timestamp: Default::default(),
+    };
+    let mut controller = ResourceController::default();
+    let mut txn_tracker = TransactionTracker::new_replaying_blobs(blob_ids);
+    txn_tracker.profile_wasm(profile.clone());
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(
+            context,
+            Operation::user_without_abi(app_id, &5_u64).unwrap(),
+        )
+        .await?;
+
+    assert!(controller.tracker.wasm_fuel > 0);
+    assert_eq!(profile.total_fuel(), controller.tracker.wasm_fuel);
+    let samples = profile.samples();
+    assert!(samples
+        .keys()
+        .any(|stack| stack[0] == "linera:app/contract-entrypoints#execute-operation"));
+    assert!(samples
+        .keys()
+        .any(|stack| stack.iter().any(|name| name.contains("counter_contract::"))));
+    let folded = profile.folded_fuel();
+    assert_eq!(
+        folded
+            .lines()
+            .map(|line| line.rsplit_once(' ').unwrap().1.parse::<u64>().unwrap())
+            .sum::<u64>(),
+        controller.tracker.wasm_fuel
+    );
+    Ok(())
+}
+
+/// Test that a contract which isn't profiled can't call the host functions reserved for the
+/// instrumentation added by the profiler.
+#[cfg_attr(with_wasmer, test_case(WasmRuntime::Wasmer; "wasmer"))]
+#[cfg_attr(with_wasmtime, test_case(WasmRuntime::Wasmtime; "wasmtime"))]
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn test_profiling_api_is_only_exported_to_profiled_contracts(
+    wasm_runtime: WasmRuntime,
+) -> anyhow::Result<()> {
+    let chain_description = dummy_chain_description(0);
+    let chain_id = chain_description.id();
+    let state = SystemExecutionState {
+        description: Some(chain_description),
+        ..Default::default()
+    };
+    let mut view = state
+        .into_view_with(chain_id, ExecutionRuntimeConfig::default())
+        .await;
+    let (app_desc, contract_blob, service_blob) = create_dummy_user_application_description(1);
+    let app_id = From::from(&app_desc);
+    let blob_ids = vec![
+        Blob::new_application_description(&app_desc).id(),
+        contract_blob.id(),
+        service_blob.id(),
+    ];
+
+    let bytecode = wasmer::wat2wasm(
+        br#"(module (import "linera:app/profiling-api" "exit-function" (func)))"#,
+    )?;
+    let contract =
+        WasmContractModule::new(Bytecode::new(bytecode.into_owned()), wasm_runtime).await?;
+    {
+        let context = view.context();
+        let pinned = context.extra().user_contracts().pin();
+        pinned.insert(app_id, contract.into());
+    }
+    view.context()
+        .extra()
+        .add_blobs([
+            contract_blob,
+            service_blob,
+            Blob::new_application_description(&app_desc),
+        ])
+        .await?;
+
+    let context = OperationContext {
+        chain_id,
+        height: BlockHeight(0),
+        round: Some(0),
+        authenticated_owner: None,
+        timestamp: Default::default(),
+    };
+    let mut controller = ResourceController::default();
+    let mut txn_tracker = TransactionTracker::new_replaying_blobs(blob_ids);
+    let result = ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(
+            context,
+            Operation::user_without_abi(app_id, &5_u64).unwrap(),
+        )
+        .await;
+    let error = result.expect_err("the contract imports a function that isn't exported");
+    assert!(error.to_string().contains("profiling-api"), "{error}");
+    Ok(())
+}
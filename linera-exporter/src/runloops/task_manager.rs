@@ -91,13 +91,18 @@ where
         F: IntoFuture<Output = ()> + Clone + Send + Sync + 'static,
         <F as IntoFuture>::IntoFuture: Future<Output = ()> + Send + Sync + 'static,
     {
+        // Register the whole committee first, so that each exporter can backfill from all the
+        // other validators.
+        self.current_committee_destinations.extend(
+            destination_ids
+                .difference(&self.startup_destinations)
+                .cloned(),
+        );
         for destination in destination_ids {
             // We treat startup destinations as "MUST" always run
             // so we skip adding them to `current_committee_destinations` as those
             // can be turned off.
             if !self.startup_destinations.contains(&destination) {
-                self.current_committee_destinations
-                    .insert(destination.clone());
                 tracing::info!(id=?destination, "starting committee exporter");
                 self.spawn(destination);
             } else {
@@ -136,7 +141,14 @@ where
         }
         let exporter_builder = &self.exporters_builder;
         let storage = self.storage.clone().expect("Failed to clone storage");
-        let join_handle = exporter_builder.spawn(id.clone(), storage);
+        let sources = self
+            .startup_destinations
+            .iter()
+            .chain(&self.current_committee_destinations)
+            .filter(|source| source.kind() == DestinationKind::Validator && **source != id)
+            .map(|source| source.address().to_owned())
+            .collect();
+        let join_handle = exporter_builder.spawn(id.clone(), storage, sources);
         self.join_handles.insert(id, join_handle);
     }
 }
@@ -179,6 +191,7 @@ where
         &self,
         id: DestinationId,
         storage: ExporterStorage<S>,
+        sources: Vec<String>,
     ) -> tokio::task::JoinHandle<anyhow::Result<()>>
     where
         S: Storage + Clone + Send + Sync + 'static,
@@ -207,6 +220,7 @@ where
                     id.clone(),
                     self.node_provider.clone(),
                     self.work_queue_size,
+                    sources,
                 );
 
                 tokio::task::spawn(async move {
@@ -11,7 +11,10 @@ use std::{
 };
 
 use futures::{future::try_join_all, stream::FuturesOrdered};
-use linera_base::identifiers::BlobId;
+use linera_base::{
+    data_types::BlockHeight,
+    identifiers::{BlobId, ChainId},
+};
 use linera_chain::types::ConfirmedBlockCertificate;
 use linera_core::node::{
     CrossChainMessageDelivery, NodeError, ValidatorNode, ValidatorNodeProvider,
@@ -27,10 +30,20 @@ use crate::{
     storage::ExporterStorage,
 };
 
+/// Exports the blocks of the canonical log to a validator, in order, starting from the index
+/// the destination last acknowledged.
+///
+/// A destination that is behind on the log, including a new one, is backfilled from the same
+/// log. The log only starts where the exporter started, though: if the destination is missing
+/// earlier blocks of a chain, it reports a lower next block height than the block it was sent.
+/// The missing blocks are then streamed with `StreamChainHistory` from the other validators
+/// the exporter exports to, and sent to the destination before the block again.
 pub(crate) struct Exporter {
     node_provider: Arc<GrpcNodeProvider>,
     destination_id: DestinationId,
     work_queue_size: usize,
+    /// The addresses of the validators to backfill missing chain history from.
+    sources: Vec<String>,
 }
 
 impl Exporter {
@@ -38,11 +51,13 @@ impl Exporter {
         destination_id: DestinationId,
         node_provider: Arc<GrpcNodeProvider>,
         work_queue_size: usize,
+        sources: Vec<String>,
     ) -> Self {
         Self {
             node_provider,
             destination_id,
             work_queue_size,
+            sources,
         }
     }
 
@@ -58,6 +73,11 @@ impl Exporter {
         let destination_state = storage.load_destination_state(&self.destination_id);
 
         let node = self.node_provider.make_node(&address)?;
+        let sources = self
+            .sources
+            .iter()
+            .map(|source| self.node_provider.make_node(source))
+            .collect::<Result<Vec<_>, _>>()?;
 
         #[expect(
             clippy::cast_possible_truncation,
@@ -67,7 +87,7 @@ impl Exporter {
         let (task_queue, task_receiver) =
             TaskQueue::new(self.work_queue_size, start, storage.clone()?);
 
-        let export_task = ExportTask::new(node, storage.clone()?, destination_state);
+        let export_task = ExportTask::new(node, sources, storage.clone()?, destination_state);
 
         tokio::select! {
 
@@ -90,6 +110,8 @@ where
     S: Storage + Clone + Send + Sync + 'static,
 {
     node: GrpcClient,
+    /// The validators to stream the chain history missing from the destination from.
+    sources: Vec<GrpcClient>,
     storage: ExporterStorage<S>,
     destination_state: Arc<AtomicU64>,
 }
@@ -100,11 +122,13 @@ where
 {
     fn new(
         node: GrpcClient,
+        sources: Vec<GrpcClient>,
         storage: ExporterStorage<S>,
         destination_state: Arc<AtomicU64>,
     ) -> ExportTask<S> {
         ExportTask {
             node,
+            sources,
             storage,
             destination_state,
         }
@@ -112,9 +136,9 @@ where
 
     async fn run(
         &self,
-        mut receiver: Receiver<(CacheArc<ConfirmedBlockCertificate>, Vec<BlobId>)>,
+        mut receiver: Receiver<CacheArc<ConfirmedBlockCertificate>>,
     ) -> anyhow::Result<()> {
-        while let Some((block, blobs_ids)) = receiver.recv().await {
+        while let Some(block) = receiver.recv().await {
             #[cfg(with_metrics)]
             #[expect(
                 clippy::cast_possible_wrap,
@@ -123,24 +147,112 @@ where
             crate::metrics::VALIDATOR_EXPORTER_QUEUE_LENGTH
                 .with_label_values(&[self.node.address()])
                 .set(receiver.len() as i64);
-            match self.dispatch_block(block.clone()).await {
-                Ok(_) => {}
+            let next_block_height = self.export_block(block.clone()).await?;
+            let header = &block.block().header;
+            if next_block_height < header.height
+                && self
+                    .backfill(header.chain_id, next_block_height, header.height)
+                    .await
+            {
+                self.export_block(block).await?;
+            }
 
-                Err(NodeError::BlobsNotFound(blobs_to_maybe_send)) => {
-                    let blobs = blobs_ids
-                        .into_iter()
-                        .filter(|id| blobs_to_maybe_send.contains(id))
-                        .collect();
-                    self.upload_blobs(blobs).await?;
-                    self.dispatch_block(block).await?
-                }
+            self.increment_destination_state();
+        }
 
-                Err(e) => Err(e)?,
+        Ok(())
+    }
+
+    /// Sends a block of the log to the destination, uploading the blobs it is missing, and
+    /// returns the next block height of the chain on the destination.
+    async fn export_block(
+        &self,
+        block: CacheArc<ConfirmedBlockCertificate>,
+    ) -> anyhow::Result<BlockHeight> {
+        match self.dispatch_block(block.clone()).await {
+            Ok(next_block_height) => Ok(next_block_height),
+
+            // A destination missing earlier blocks of the chain may also be missing the blobs
+            // they published, so upload every missing blob, not just the block's new ones.
+            Err(NodeError::BlobsNotFound(missing_blobs)) => {
+                self.upload_blobs(missing_blobs).await?;
+                Ok(self.dispatch_block(block).await?)
             }
 
-            self.increment_destination_state();
+            Err(e) => Err(e)?,
+        }
+    }
+
+    /// Sends the blocks of a chain from `from` to `to`, excluded, to the destination,
+    /// streaming them from the first sources that have them, and returns whether all of them
+    /// were sent. Otherwise, the destination keeps the gap until the next block of the chain
+    /// is exported.
+    async fn backfill(&self, chain_id: ChainId, from: BlockHeight, to: BlockHeight) -> bool {
+        let mut next_height = from;
+        for source in &self.sources {
+            if next_height >= to {
+                return true;
+            }
+            if let Err(error) = self
+                .backfill_from(source, chain_id, &mut next_height, to)
+                .await
+            {
+                tracing::warn!(
+                    destination=?self.node.address(),
+                    source=?source.address(),
+                    %chain_id,
+                    %error,
+                    "failed to backfill chain history",
+                );
+            }
+        }
+        if next_height < to {
+            tracing::error!(
+                destination=?self.node.address(),
+                %chain_id,
+                %next_height,
+                "no source has the chain history missing from the destination",
+            );
+            return false;
         }
+        true
+    }
 
+    /// Streams the blocks of a chain from `next_height` to `to`, excluded, from `source` to
+    /// the destination, advancing `next_height` past each block the destination accepted.
+    async fn backfill_from(
+        &self,
+        source: &GrpcClient,
+        chain_id: ChainId,
+        next_height: &mut BlockHeight,
+        to: BlockHeight,
+    ) -> anyhow::Result<()> {
+        let mut certificates = source
+            .stream_chain_history(chain_id, *next_height, to)
+            .await?;
+        while let Some(certificate) = certificates.next().await {
+            let certificate = certificate?;
+            let header = &certificate.block().header;
+            anyhow::ensure!(
+                header.chain_id == chain_id && header.height == *next_height,
+                "unexpected certificate at height {} of chain {}",
+                header.height,
+                header.chain_id,
+            );
+            let certificate = self.storage.cache_backfilled_block(certificate);
+            match self.dispatch_block(certificate.clone()).await {
+                Ok(_) => {}
+                Err(NodeError::BlobsNotFound(blob_ids)) => {
+                    let blobs =
+                        try_join_all(blob_ids.into_iter().map(|id| source.download_blob(id)))
+                            .await?;
+                    try_join_all(blobs.into_iter().map(|blob| self.node.upload_blob(blob))).await?;
+                    self.dispatch_block(certificate).await?;
+                }
+                Err(e) => Err(e)?,
+            }
+            *next_height = next_height.try_add_one()?;
+        }
         Ok(())
     }
 
@@ -184,10 +296,12 @@ where
         Ok(())
     }
 
+    /// Sends a certificate to the destination, and returns the next block height of its chain
+    /// on the destination.
     async fn dispatch_block(
         &self,
         certificate: CacheArc<ConfirmedBlockCertificate>,
-    ) -> Result<(), NodeError> {
+    ) -> Result<BlockHeight, NodeError> {
         let delivery = CrossChainMessageDelivery::NonBlocking;
         let block_id = BlockId::from_confirmed_block(certificate.value());
         tracing::info!(destination=?self.node.address(), ?block_id, "dispatching block");
@@ -199,11 +313,12 @@ where
         )
         .await
         {
-            Ok(_) => {
+            Ok(response) => {
                 #[cfg(with_metrics)]
                 crate::metrics::DISPATCH_BLOCK_HISTOGRAM
                     .with_label_values(&[self.node.address()])
                     .observe(start.elapsed().as_secs_f64() * 1000.0);
+                Ok(response.info.next_block_height)
             }
             Err(e) => {
                 tracing::error!(error=%e, ?block_id, "error when dispatching block");
@@ -211,11 +326,9 @@ where
                 crate::metrics::DISPATCH_BLOCK_HISTOGRAM
                     .with_label_values(&[self.node.address()])
                     .observe(start.elapsed().as_secs_f64() * 1000.0);
-                Err(e)?
+                Err(e)
             }
         }
-
-        Ok(())
     }
 }
 
@@ -226,22 +339,18 @@ where
     queue_size: usize,
     start_height: usize,
     storage: ExporterStorage<S>,
-    buffer: Sender<(CacheArc<ConfirmedBlockCertificate>, Vec<BlobId>)>,
+    buffer: Sender<CacheArc<ConfirmedBlockCertificate>>,
 }
 
 impl<S> TaskQueue<S>
 where
     S: Storage + Clone + Send + Sync + 'static,
 {
-    #[expect(clippy::type_complexity)]
     fn new(
         queue_size: usize,
         start_height: usize,
         storage: ExporterStorage<S>,
-    ) -> (
-        TaskQueue<S>,
-        Receiver<(CacheArc<ConfirmedBlockCertificate>, Vec<BlobId>)>,
-    ) {
+    ) -> (TaskQueue<S>, Receiver<CacheArc<ConfirmedBlockCertificate>>) {
         let (sender, receiver) = tokio::sync::mpsc::channel(queue_size);
 
         let queue = Self {
@@ -275,10 +384,10 @@ where
     async fn get_block_task(
         &self,
         index: usize,
-    ) -> Result<(CacheArc<ConfirmedBlockCertificate>, Vec<BlobId>), ExporterError> {
+    ) -> Result<CacheArc<ConfirmedBlockCertificate>, ExporterError> {
         loop {
             match self.storage.get_block_with_blob_ids(index).await {
-                Ok(block_with_blobs_ids) => return Ok(block_with_blobs_ids),
+                Ok((block, _blob_ids)) => return Ok(block),
                 Err(ExporterError::UnprocessedBlock) => {
                     tokio::time::sleep(Duration::from_secs(1)).await
                 }
@@ -287,3 +396,103 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{atomic::AtomicU64, Arc},
+        time::Duration,
+    };
+
+    use linera_base::{data_types::Blob, port::get_free_port};
+    use linera_chain::types::ConfirmedBlockCertificate;
+    use linera_core::node::ValidatorNodeProvider;
+    use linera_rpc::{grpc::GrpcNodeProvider, NodeOptions};
+    use linera_storage::{DbStorage, Storage};
+    use linera_views::memory::MemoryDatabase;
+    use tokio_util::sync::CancellationToken;
+
+    use super::ExportTask;
+    use crate::{
+        common::CanonicalBlock,
+        config::{Destination, LimitsConfig},
+        storage::BlockProcessorStorage,
+        test_utils::{
+            ensure_grpc_server_has_started, make_simple_state_with_blobs, DummyValidator,
+        },
+    };
+
+    async fn spawn_dummy_validator(
+        validator: &DummyValidator,
+        token: &CancellationToken,
+    ) -> anyhow::Result<String> {
+        let port = get_free_port().await?;
+        tokio::spawn(validator.clone().start(port, token.clone()));
+        ensure_grpc_server_has_started("dummy validator", port as usize).await?;
+        let destination = Destination::Validator {
+            port,
+            endpoint: "127.0.0.1".to_owned(),
+        };
+        Ok(destination.id().address().to_owned())
+    }
+
+    /// Tests that blocks missing from a destination, which are not in the exporter's log, are
+    /// streamed from another validator before the block the destination could not execute.
+    #[test_log::test(tokio::test)]
+    async fn test_backfill_from_other_validator() -> anyhow::Result<()> {
+        let storage = DbStorage::<MemoryDatabase, _>::make_test_storage(None).await;
+        let (_, state) = make_simple_state_with_blobs(&storage).await;
+        let (_, mut exporter_storage) =
+            BlockProcessorStorage::load(storage.clone(), 0, vec![], LimitsConfig::default())
+                .await?;
+
+        let mut certificates = Vec::new();
+        for CanonicalBlock { block_hash, .. } in &state {
+            certificates.push(storage.read_certificate(*block_hash).await?.unwrap());
+        }
+        let source = DummyValidator::default();
+        for certificate in &certificates {
+            for blob_id in certificate.block().required_blob_ids() {
+                source.insert_blob(Blob::clone(&storage.read_blob(blob_id).await?.unwrap()));
+            }
+            source.insert_certificate(ConfirmedBlockCertificate::clone(certificate));
+        }
+        let destination = DummyValidator::default();
+
+        let token = CancellationToken::new();
+        let source_address = spawn_dummy_validator(&source, &token).await?;
+        let destination_address = spawn_dummy_validator(&destination, &token).await?;
+        let node_provider = GrpcNodeProvider::new(NodeOptions {
+            send_timeout: Duration::from_millis(4000),
+            recv_timeout: Duration::from_millis(4000),
+            retry_delay: Duration::from_millis(1000),
+            max_retries: 10,
+            ..Default::default()
+        });
+        let destination_state = Arc::new(AtomicU64::new(0));
+        let export_task = ExportTask::new(
+            node_provider.make_node(&destination_address)?,
+            vec![node_provider.make_node(&source_address)?],
+            exporter_storage.clone()?,
+            destination_state.clone(),
+        );
+
+        // The exporter's log starts at the second block of the chain.
+        let (sender, receiver) = tokio::sync::mpsc::channel(1);
+        sender.send(certificates[1].clone()).await?;
+        drop(sender);
+        export_task.run(receiver).await?;
+
+        for CanonicalBlock { block_hash, .. } in &state {
+            assert!(destination.state.pin().contains(block_hash));
+        }
+        assert!(destination.duplicate_blocks.pin().is_empty());
+        assert_eq!(
+            destination_state.load(std::sync::atomic::Ordering::Acquire),
+            1
+        );
+
+        token.cancel();
+        Ok(())
+    }
+}
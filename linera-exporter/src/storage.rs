@@ -15,9 +15,10 @@ use linera_base::{
     data_types::{Blob, BlockHeight},
     identifiers::{BlobId, ChainId},
 };
+use linera_cache::ValueCache;
 use linera_chain::types::ConfirmedBlockCertificate;
 use linera_sdk::{ensure, views::View};
-use linera_storage::{Arc as CacheArc, Storage};
+use linera_storage::{Arc as CacheArc, Storage, DEFAULT_CLEANUP_INTERVAL_SECS};
 use linera_views::{
     batch::Batch, context::Context, log_view::LogView, store::WritableKeyValueStore as _,
     views::ClonableView,
@@ -35,6 +36,10 @@ use crate::{
 
 const NUM_OF_BLOBS: usize = 20;
 
+/// The number of certificates downloaded from validators to backfill destinations that are
+/// kept in memory.
+const BACKFILLED_BLOCKS_CACHE_SIZE: usize = 100;
+
 pub(super) struct ExporterStorage<S>
 where
     S: Storage + Clone + Send + Sync + 'static,
@@ -54,6 +59,9 @@ where
     shared_canonical_state: CanonicalState<C>,
     blobs_cache: Arc<BlobCache>,
     blocks_cache: Arc<BlockCache>,
+    /// The certificates downloaded from validators to backfill destinations, which are not
+    /// in the storage.
+    backfilled_blocks: Arc<ValueCache<CryptoHash, ConfirmedBlockCertificate>>,
 }
 
 pub(super) struct BlockProcessorStorage<S>
@@ -91,11 +99,18 @@ where
             CacheWeighter::default(),
         ));
 
+        let backfilled_blocks = Arc::new(ValueCache::new(
+            "exporter_backfilled_blocks",
+            BACKFILLED_BLOCKS_CACHE_SIZE,
+            DEFAULT_CLEANUP_INTERVAL_SECS,
+        ));
+
         Self {
             storage,
             shared_canonical_state,
             blobs_cache,
             blocks_cache,
+            backfilled_blocks,
             destination_states,
         }
     }
@@ -154,6 +169,7 @@ where
             shared_canonical_state: self.shared_canonical_state.clone()?,
             blobs_cache: self.blobs_cache.clone(),
             blocks_cache: self.blocks_cache.clone(),
+            backfilled_blocks: self.backfilled_blocks.clone(),
             destination_states: self.destination_states.clone(),
         })
     }
@@ -204,6 +220,16 @@ where
         self.shared_storage.get_blob(blob_id).await
     }
 
+    /// Keeps a certificate downloaded from a validator in memory, to send it to a destination.
+    pub(crate) fn cache_backfilled_block(
+        &self,
+        certificate: ConfirmedBlockCertificate,
+    ) -> CacheArc<ConfirmedBlockCertificate> {
+        self.shared_storage
+            .backfilled_blocks
+            .insert(&certificate.hash(), certificate)
+    }
+
     pub(crate) fn load_destination_state(&self, id: &DestinationId) -> Arc<AtomicU64> {
         self.shared_storage.destination_states.load_state(id)
     }
@@ -29,7 +29,7 @@ use linera_chain::{
     types::{CertificateValue, ConfirmedBlock, ConfirmedBlockCertificate},
 };
 use linera_core::{
-    data_types::{ChainInfo, ChainInfoResponse},
+    data_types::{ChainHistoryRequest, ChainInfo, ChainInfoResponse},
     node::NodeError,
 };
 use linera_execution::{Operation, SystemOperation};
@@ -149,6 +149,13 @@ pub(crate) struct DummyValidator {
     pub(crate) state: Arc<papaya::HashSet<CryptoHash>>,
     // Tracks whether a block has been received multiple times.
     pub(crate) duplicate_blocks: Arc<papaya::HashMap<CryptoHash, u64>>,
+    // The next block height of each chain. Blocks above it are not executed.
+    pub(crate) next_heights: Arc<papaya::HashMap<ChainId, BlockHeight>>,
+    // The executed blocks, served as chain history.
+    pub(crate) certificates:
+        Arc<papaya::HashMap<(ChainId, BlockHeight), ConfirmedBlockCertificate>>,
+    // The contents of the uploaded blobs.
+    pub(crate) blob_contents: Arc<papaya::HashMap<BlobId, Blob>>,
 }
 
 impl DummyValidator {
@@ -159,9 +166,31 @@ impl DummyValidator {
             blobs: Arc::new(papaya::HashSet::new()),
             state: Arc::new(papaya::HashSet::new()),
             duplicate_blocks: Arc::new(papaya::HashMap::new()),
+            next_heights: Arc::new(papaya::HashMap::new()),
+            certificates: Arc::new(papaya::HashMap::new()),
+            blob_contents: Arc::new(papaya::HashMap::new()),
         }
     }
 
+    /// Adds a block to the chain history, as if it had been executed.
+    pub(crate) fn insert_certificate(&self, certificate: ConfirmedBlockCertificate) {
+        let header = &certificate.block().header;
+        let (chain_id, height) = (header.chain_id, header.height);
+        self.state.pin().insert(certificate.hash());
+        self.next_heights
+            .pin()
+            .insert(chain_id, height.try_add_one().unwrap());
+        self.certificates
+            .pin()
+            .insert((chain_id, height), certificate);
+    }
+
+    /// Adds a blob, as if it had been uploaded.
+    pub(crate) fn insert_blob(&self, blob: Blob) {
+        self.blobs.pin().insert(blob.id());
+        self.blob_contents.pin().insert(blob.id(), blob);
+    }
+
     pub(crate) async fn start(
         self,
         port: u16,
@@ -191,6 +220,12 @@ impl ValidatorNode for DummyValidator {
     type DownloadBlobsStream = std::pin::Pin<
         Box<dyn futures::Stream<Item = Result<linera_rpc::grpc::api::BlobContent, Status>> + Send>,
     >;
+    type StreamChainHistoryStream = std::pin::Pin<
+        Box<
+            dyn futures::Stream<Item = Result<linera_rpc::grpc::api::RawCertificate, Status>>
+                + Send,
+        >,
+    >;
 
     async fn handle_confirmed_certificate(
         &self,
@@ -211,15 +246,33 @@ impl ValidatorNode for DummyValidator {
             }
         }
 
+        let header = &req.certificate.block().header;
+        let (chain_id, height) = (header.chain_id, header.height);
+        let next_block_height = self
+            .next_heights
+            .pin()
+            .get(&chain_id)
+            .copied()
+            .unwrap_or(BlockHeight::ZERO);
+        // Like a validator, only execute the next block of the chain.
+        let execute = missing_blobs.is_empty() && height <= next_block_height;
+        let next_block_height = if execute && height == next_block_height {
+            height
+                .try_add_one()
+                .map_err(|error| Status::internal(error.to_string()))?
+        } else {
+            next_block_height
+        };
+
         let chain_info = ChainInfo {
-            chain_id: ChainId(CryptoHash::test_hash("test")),
+            chain_id,
             epoch: Epoch::ZERO,
             description: None,
             manager: ChainManagerInfo::default().into(),
             chain_balance: Amount::ONE,
             block_hash: None,
             timestamp: Timestamp::now(),
-            next_block_height: BlockHeight::ZERO,
+            next_block_height,
             state_hash: None,
             requested_owner_balance: None,
             committee_hash: None,
@@ -231,8 +284,16 @@ impl ValidatorNode for DummyValidator {
             requested_latest_checkpoint_height: None,
         };
 
-        let response = if missing_blobs.is_empty() {
+        let response = if !missing_blobs.is_empty() {
+            NodeError::BlobsNotFound(missing_blobs).try_into()?
+        } else if !execute {
+            ChainInfoResponse::new(chain_info, None).try_into()?
+        } else {
             let response = ChainInfoResponse::new(chain_info, None).try_into()?;
+            self.next_heights.pin().insert(chain_id, next_block_height);
+            self.certificates
+                .pin()
+                .get_or_insert((chain_id, height), req.certificate.clone());
             for blob in created_blobs {
                 self.blobs.pin().insert(blob);
             }
@@ -244,8 +305,6 @@ impl ValidatorNode for DummyValidator {
             }
 
             response
-        } else {
-            NodeError::BlobsNotFound(missing_blobs).try_into()?
         };
 
         Ok(Response::new(response))
@@ -263,7 +322,7 @@ impl ValidatorNode for DummyValidator {
             request.into_inner().try_into()?;
         let blob = Blob::new(content);
         let id = blob.id();
-        self.blobs.pin().insert(id);
+        self.insert_blob(blob);
         Ok(Response::new(id.try_into()?))
     }
 
@@ -325,9 +384,16 @@ impl ValidatorNode for DummyValidator {
 
     async fn download_blob(
         &self,
-        _request: Request<linera_rpc::grpc::api::BlobId>,
+        request: Request<linera_rpc::grpc::api::BlobId>,
     ) -> Result<Response<linera_rpc::grpc::api::BlobContent>, Status> {
-        unimplemented!()
+        let id = BlobId::try_from(request.into_inner())?;
+        let blob = self
+            .blob_contents
+            .pin()
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| Status::not_found(format!("blob {id} not found")))?;
+        Ok(Response::new(blob.into_content().try_into()?))
     }
 
     async fn download_blobs(
@@ -379,6 +445,30 @@ impl ValidatorNode for DummyValidator {
         unimplemented!()
     }
 
+    async fn stream_chain_history(
+        &self,
+        request: Request<linera_rpc::grpc::api::StreamChainHistoryRequest>,
+    ) -> Result<Response<Self::StreamChainHistoryStream>, Status> {
+        let ChainHistoryRequest {
+            chain_id,
+            from_height,
+            to_height,
+        } = request.into_inner().try_into()?;
+        let certificates = self.certificates.pin();
+        let raw_certificates = (from_height.0..to_height.0)
+            .map_while(|height| certificates.get(&(chain_id, BlockHeight(height))))
+            .map(|certificate| {
+                Ok(linera_rpc::grpc::api::RawCertificate {
+                    lite_certificate: bcs::to_bytes(&certificate.lite_certificate())
+                        .map_err(|error| Status::internal(error.to_string()))?,
+                    confirmed_block: bcs::to_bytes(certificate.value())
+                        .map_err(|error| Status::internal(error.to_string()))?,
+                })
+            })
+            .collect::<Vec<_>>();
+        Ok(Response::new(Box::pin(stream::iter(raw_certificates))))
+    }
+
     async fn blob_last_used_by(
         &self,
         _request: Request<linera_rpc::grpc::api::BlobId>,
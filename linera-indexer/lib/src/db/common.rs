@@ -71,16 +71,18 @@ pub fn classify_message(message: &Message) -> MessageClassification {
                 system_recipient,
             }
         }
-        Message::User { application_id, .. } => MessageClassification {
-            message_type: "User".to_string(),
-            application_id: Some(application_id.to_string()),
-            system_message_type: None,
-            system_target: None,
-            system_amount: None,
-            system_source: None,
-            system_owner: None,
-            system_recipient: None,
-        },
+        Message::User { application_id, .. } | Message::VersionedUser { application_id, .. } => {
+            MessageClassification {
+                message_type: "User".to_string(),
+                application_id: Some(application_id.to_string()),
+                system_message_type: None,
+                system_target: None,
+                system_amount: None,
+                system_source: None,
+                system_owner: None,
+                system_recipient: None,
+            }
+        }
     }
 }
 
@@ -291,6 +291,15 @@ impl PostgresDatabase {
                     SystemOperation::ChangeOwnership { .. } => "ChangeOwnership",
                     SystemOperation::VerifyBlob { .. } => "VerifyBlob",
                     SystemOperation::Checkpoint => "Checkpoint",
+                    SystemOperation::ExecuteScheduledOperation { .. } => {
+                        "ExecuteScheduledOperation"
+                    }
+                    SystemOperation::LockTokens { .. } => "LockTokens",
+                    SystemOperation::UpgradeApplication { .. } => "UpgradeApplication",
+                    SystemOperation::ProcessApplicationUpgrade { .. } => {
+                        "ProcessApplicationUpgrade"
+                    }
+                    SystemOperation::UseFeeSponsorship { .. } => "UseFeeSponsorship",
                 };
                 ("System", None, Some(sys_op_type))
             }
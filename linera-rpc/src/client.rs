@@ -14,7 +14,10 @@ use linera_chain::{
 };
 use linera_core::{
     data_types::{ChainInfoQuery, ChainInfoResponse},
-    node::{BlobStream, CrossChainMessageDelivery, NodeError, NotificationStream, ValidatorNode},
+    node::{
+        BlobStream, CertificateStream, CrossChainMessageDelivery, NodeError, NotificationStream,
+        ValidatorNode,
+    },
 };
 use linera_storage::Arc as CacheArc;
 
@@ -288,6 +291,28 @@ impl ValidatorNode for Client {
         })
     }
 
+    async fn stream_chain_history(
+        &self,
+        chain_id: ChainId,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> Result<CertificateStream, NodeError> {
+        Ok(match self {
+            Client::Grpc(grpc_client) => {
+                grpc_client
+                    .stream_chain_history(chain_id, from_height, to_height)
+                    .await?
+            }
+
+            #[cfg(with_simple_network)]
+            Client::Simple(simple_client) => {
+                simple_client
+                    .stream_chain_history(chain_id, from_height, to_height)
+                    .await?
+            }
+        })
+    }
+
     async fn event_block_heights(
         &self,
         event_ids: Vec<EventId>,
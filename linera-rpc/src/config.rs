@@ -1,7 +1,7 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::ffi::OsString;
+use std::{ffi::OsString, path::PathBuf};
 
 use clap::Parser;
 use linera_base::{crypto::ValidatorPublicKey, identifiers::ChainId};
@@ -65,6 +65,52 @@ impl CrossChainConfig {
     }
 }
 
+/// The certificates authenticating the links between the proxies and shards of a validator,
+/// required when its internal protocol is `grpcs`.
+#[derive(Clone, Debug, Parser)]
+pub struct InternalTlsOptions {
+    /// The certificate authority, in PEM format, that signed the certificates of the proxies
+    /// and shards of the validator.
+    #[arg(long = "internal-tls-ca", env = "LINERA_INTERNAL_TLS_CA")]
+    pub ca_certificate: Option<PathBuf>,
+
+    /// The certificate chain, in PEM format, presented to the other proxies and shards.
+    #[arg(
+        long = "internal-tls-certificate",
+        env = "LINERA_INTERNAL_TLS_CERTIFICATE"
+    )]
+    pub certificate: Option<PathBuf>,
+
+    /// The private key, in PEM format, of the certificate.
+    #[arg(long = "internal-tls-key", env = "LINERA_INTERNAL_TLS_KEY")]
+    pub private_key: Option<PathBuf>,
+
+    /// The SHA-256 fingerprint, in hexadecimal, of a peer certificate to accept. If given,
+    /// the other certificates are rejected even if they are signed by the certificate authority.
+    #[arg(long = "internal-tls-pinned-certificate")]
+    pub pinned_certificates: Vec<String>,
+
+    /// How often the certificate files are checked for changes, in milliseconds.
+    #[arg(long = "internal-tls-reload-interval-ms", default_value = "60000")]
+    pub reload_interval_ms: u64,
+}
+
+impl Default for InternalTlsOptions {
+    fn default() -> Self {
+        InternalTlsOptions::parse_from::<[OsString; 1], OsString>(["".into()])
+    }
+}
+
+impl InternalTlsOptions {
+    /// Returns whether no certificate was given.
+    pub fn is_empty(&self) -> bool {
+        self.ca_certificate.is_none()
+            && self.certificate.is_none()
+            && self.private_key.is_none()
+            && self.pinned_certificates.is_empty()
+    }
+}
+
 /// The configuration for notification delivery to proxies.
 #[derive(Clone, Debug, Parser)]
 pub struct NotificationConfig {
@@ -275,7 +321,7 @@ where
         let parts = s.split(':').collect::<Vec<_>>();
         anyhow::ensure!(
             parts.len() == 3,
-            "Expecting format `(tcp|udp|grpc|grpcs):host:port`"
+            "Expecting format `(tcp|udp|quic|grpc|grpcs):host:port`"
         );
         let protocol = parts[0].parse().map_err(|s| anyhow::anyhow!("{s}"))?;
         let host = parts[1].to_owned();
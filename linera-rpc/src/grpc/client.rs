@@ -43,8 +43,11 @@ pub(crate) mod metrics {
 }
 
 use linera_core::{
-    data_types::{CertificatesByHeightRequest, ChainInfoResponse},
-    node::{BlobStream, CrossChainMessageDelivery, NodeError, NotificationStream, ValidatorNode},
+    data_types::{CertificatesByHeightRequest, ChainHistoryRequest, ChainInfoResponse},
+    node::{
+        BlobStream, CertificateStream, CrossChainMessageDelivery, NodeError, NotificationStream,
+        ValidatorNode,
+    },
     worker::Notification,
 };
 use linera_storage::Arc as CacheArc;
@@ -54,7 +57,7 @@ use tracing::{debug, instrument, trace, Level};
 
 use super::{
     api::{self, validator_node_client::ValidatorNodeClient, SubscriptionRequest},
-    transport, GRPC_MAX_MESSAGE_SIZE,
+    transport, GRPC_MAX_MESSAGE_SIZE, RETRY_AFTER_METADATA_KEY,
 };
 #[cfg(feature = "opentelemetry")]
 use crate::propagation::{get_context_with_traffic_type, inject_context};
@@ -149,6 +152,36 @@ impl GrpcClient {
         }
     }
 
+    /// Decodes a certificate sent in serialized form.
+    fn decode_raw_certificate(
+        RawCertificate {
+            lite_certificate,
+            confirmed_block,
+        }: RawCertificate,
+    ) -> Result<ConfirmedBlockCertificate, NodeError> {
+        let cert = bcs::from_bytes::<LiteCertificate>(&lite_certificate)
+            .map_err(|_| NodeError::UnexpectedCertificateValue)?;
+        let block = bcs::from_bytes::<ConfirmedBlock>(&confirmed_block)
+            .map_err(|_| NodeError::UnexpectedCertificateValue)?;
+        cert.into_confirmed_certificate(block)
+            .ok_or(NodeError::UnexpectedCertificateValue)
+    }
+
+    /// Returns how long to wait before sending the request again, if this gRPC status is a
+    /// rejection by the rate limiter of the validator.
+    fn retry_after_ms(status: &Status) -> Option<u64> {
+        if status.code() != Code::ResourceExhausted {
+            return None;
+        }
+        status
+            .metadata()
+            .get(RETRY_AFTER_METADATA_KEY)?
+            .to_str()
+            .ok()?
+            .parse()
+            .ok()
+    }
+
     async fn delegate<F, Fut, R, S>(
         &self,
         f: F,
@@ -173,6 +206,13 @@ impl GrpcClient {
             #[cfg(feature = "opentelemetry")]
             inject_context(&get_context_with_traffic_type(), request.metadata_mut());
             match f(self.client.clone(), request).await {
+                // Retrying right away would only prolong the overload: the caller backs off
+                // or asks another validator instead.
+                Err(s) if Self::retry_after_ms(&s).is_some() => {
+                    return Err(NodeError::RateLimited {
+                        retry_after_ms: Self::retry_after_ms(&s).unwrap_or_default(),
+                    });
+                }
                 Err(s) if Self::is_retryable(&s) && retry_count < self.max_retries => {
                     let delay = crate::jittered_backoff_delay(
                         self.retry_delay,
@@ -602,22 +642,7 @@ impl ValidatorNode for GrpcClient {
                 client_delegate!(self, download_raw_certificates_by_heights, request)?
                     .certificates
                     .into_iter()
-                    .map(
-                        |RawCertificate {
-                             lite_certificate,
-                             confirmed_block,
-                         }|
-                         -> Result<ConfirmedBlockCertificate, NodeError> {
-                            let cert = bcs::from_bytes::<LiteCertificate>(&lite_certificate)
-                                .map_err(|_| NodeError::UnexpectedCertificateValue)?;
-
-                            let block = bcs::from_bytes::<ConfirmedBlock>(&confirmed_block)
-                                .map_err(|_| NodeError::UnexpectedCertificateValue)?;
-
-                            cert.into_confirmed_certificate(block)
-                                .ok_or(NodeError::UnexpectedCertificateValue)
-                        },
-                    )
+                    .map(Self::decode_raw_certificate)
                     .collect::<Result<_, _>>()?;
 
             if received.is_empty() {
@@ -634,6 +659,28 @@ impl ValidatorNode for GrpcClient {
         Ok(certs_collected)
     }
 
+    #[instrument(target = "grpc_client", skip(self), err(level = Level::DEBUG), fields(address = self.address))]
+    async fn stream_chain_history(
+        &self,
+        chain_id: ChainId,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> Result<CertificateStream, NodeError> {
+        let request = ChainHistoryRequest {
+            chain_id,
+            from_height,
+            to_height,
+        };
+        let stream = client_delegate!(self, stream_chain_history, request)?;
+        let certificates = stream.map(|result| match result {
+            Ok(raw_certificate) => Self::decode_raw_certificate(raw_certificate),
+            Err(status) => Err(NodeError::GrpcError {
+                error: status.to_string(),
+            }),
+        });
+        Ok(Box::pin(certificates))
+    }
+
     #[instrument(target = "grpc_client", skip(self), err(level = Level::DEBUG), fields(address = self.address))]
     async fn blob_last_used_by(&self, blob_id: BlobId) -> Result<CryptoHash, NodeError> {
         Ok(client_delegate!(self, blob_last_used_by, blob_id)?.try_into()?)
@@ -21,7 +21,8 @@ use linera_chain::{
 };
 use linera_core::{
     data_types::{
-        CertificatesByHeightRequest, ChainInfoQuery, ChainInfoResponse, CrossChainRequest,
+        CertificatesByHeightRequest, ChainHistoryRequest, ChainInfoQuery, ChainInfoResponse,
+        CrossChainRequest,
     },
     node::NodeError,
     worker::Notification,
@@ -1111,6 +1112,34 @@ impl TryFrom<api::DownloadCertificatesByHeightsRequest> for CertificatesByHeight
     }
 }
 
+impl From<ChainHistoryRequest> for api::StreamChainHistoryRequest {
+    fn from(request: ChainHistoryRequest) -> Self {
+        Self {
+            chain_id: Some(request.chain_id.into()),
+            from_height: Some(request.from_height.into()),
+            to_height: Some(request.to_height.into()),
+        }
+    }
+}
+
+impl TryFrom<api::StreamChainHistoryRequest> for ChainHistoryRequest {
+    type Error = GrpcProtoConversionError;
+
+    fn try_from(request: api::StreamChainHistoryRequest) -> Result<Self, Self::Error> {
+        Ok(Self {
+            chain_id: try_proto_convert(request.chain_id)?,
+            from_height: request
+                .from_height
+                .ok_or(GrpcProtoConversionError::MissingField)?
+                .into(),
+            to_height: request
+                .to_height
+                .ok_or(GrpcProtoConversionError::MissingField)?
+                .into(),
+        })
+    }
+}
+
 impl From<Vec<EventId>> for api::EventBlockHeightsRequest {
     fn from(event_ids: Vec<EventId>) -> Self {
         Self {
@@ -9,6 +9,9 @@ pub mod pool;
 mod relay;
 #[cfg(with_server)]
 mod server;
+/// Mutual TLS between the proxies and shards of a validator.
+#[cfg(not(web))]
+pub mod tls;
 /// Transport-level configuration and channel construction for gRPC.
 pub mod transport;
 
@@ -38,6 +41,15 @@ pub enum GrpcError {
     #[error("failed to parse socket address: {0}")]
     SocketAddr(#[from] std::net::AddrParseError),
 
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("invalid internal TLS configuration: {0}")]
+    TlsConfiguration(String),
+
+    #[error("peer {peer} failed the TLS verification: {reason}")]
+    PeerVerification { peer: String, reason: String },
+
     #[cfg(with_server)]
     #[error(transparent)]
     Reflection(#[from] tonic_reflection::server::Error),
@@ -51,6 +63,10 @@ pub const GRPC_MAX_MESSAGE_SIZE: usize = 16 * MEBIBYTE;
 /// We leave 30% of buffer for the rest of the message and potential underestimation.
 pub const GRPC_CHUNKED_MESSAGE_FILL_LIMIT: usize = GRPC_MAX_MESSAGE_SIZE * 7 / 10;
 
+/// The metadata entry of a `RESOURCE_EXHAUSTED` status telling a rate-limited client how many
+/// milliseconds to wait before sending the request again.
+pub const RETRY_AFTER_METADATA_KEY: &str = "retry-after-ms";
+
 /// Prometheus label for the gRPC method name.
 pub const METHOD_NAME_LABEL: &str = "method_name";
 
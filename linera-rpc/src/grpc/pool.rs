@@ -11,6 +11,9 @@ use super::{transport, GrpcError};
 #[derive(Clone, Default)]
 pub struct GrpcConnectionPool {
     options: transport::Options,
+    /// The certificates authenticating the channels, if they are internal to a validator.
+    #[cfg(not(web))]
+    internal_tls: Option<super::tls::InternalTls>,
     channels: Arc<papaya::HashMap<String, transport::Channel>>,
 }
 
@@ -19,6 +22,8 @@ impl GrpcConnectionPool {
     pub fn new(options: transport::Options) -> Self {
         Self {
             options,
+            #[cfg(not(web))]
+            internal_tls: None,
             channels: Arc::new(papaya::HashMap::default()),
         }
     }
@@ -35,6 +40,14 @@ impl GrpcConnectionPool {
         self
     }
 
+    /// Authenticates the channels created by this pool with the given internal certificates,
+    /// if any.
+    #[cfg(not(web))]
+    pub fn with_internal_tls(mut self, internal_tls: Option<super::tls::InternalTls>) -> Self {
+        self.internal_tls = internal_tls;
+        self
+    }
+
     /// Obtains a channel for the current address. Either clones an existing one (thereby
     /// reusing the connection), or creates one if needed. New channels do not create a
     /// connection immediately.
@@ -43,6 +56,12 @@ impl GrpcConnectionPool {
         if let Some(channel) = pinned.get(&address) {
             return Ok(channel.clone());
         }
+        #[cfg(not(web))]
+        let channel = match &self.internal_tls {
+            Some(tls) => transport::create_internal_channel(address.clone(), &self.options, tls)?,
+            None => transport::create_channel(address.clone(), &self.options)?,
+        };
+        #[cfg(web)]
         let channel = transport::create_channel(address.clone(), &self.options)?;
         Ok(pinned.get_or_insert(address, channel).clone())
     }
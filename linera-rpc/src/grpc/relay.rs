@@ -27,8 +27,8 @@ use linera_base::{
 };
 use linera_chain::{data_types, types};
 use linera_core::node::{
-    BlobStream, CrossChainMessageDelivery, NodeError, NotificationStream, ValidatorNode,
-    ValidatorNodeProvider,
+    BlobStream, CertificateStream, CrossChainMessageDelivery, NodeError, NotificationStream,
+    ValidatorNode, ValidatorNodeProvider,
 };
 use linera_version::VersionInfo;
 use tonic::Request;
@@ -258,6 +258,15 @@ impl ValidatorNode for RelayClient {
         Err(unsupported("download_certificates"))
     }
 
+    async fn stream_chain_history(
+        &self,
+        _chain_id: ChainId,
+        _from_height: BlockHeight,
+        _to_height: BlockHeight,
+    ) -> Result<CertificateStream, NodeError> {
+        Err(unsupported("stream_chain_history"))
+    }
+
     async fn blob_last_used_by(&self, _blob_id: BlobId) -> Result<CryptoHash, NodeError> {
         Err(unsupported("blob_last_used_by"))
     }
@@ -324,6 +333,13 @@ impl RelayNodeProvider {
         }
     }
 
+    /// Authenticates the connections to the proxies with the given internal certificates, if
+    /// any.
+    pub fn with_internal_tls(mut self, internal_tls: Option<super::tls::InternalTls>) -> Self {
+        self.pool = self.pool.with_internal_tls(internal_tls);
+        self
+    }
+
     /// Returns the next proxy in the rotation.
     fn next_relay(&self) -> &str {
         let index = self.next.fetch_add(1, Ordering::Relaxed) % self.relay_addresses.len();
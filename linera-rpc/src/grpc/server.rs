@@ -36,7 +36,8 @@ use super::{
         HandlePendingBlobRequest, LiteCertificate, PendingBlobRequest, PendingBlobResult,
     },
     pool::GrpcConnectionPool,
-    GrpcError, GRPC_MAX_MESSAGE_SIZE,
+    tls::InternalTls,
+    transport, GrpcError, GRPC_MAX_MESSAGE_SIZE,
 };
 #[cfg(feature = "opentelemetry")]
 use crate::propagation::get_traffic_type_from_request;
@@ -373,6 +374,8 @@ where
     S: Storage + Clone + Send + Sync + 'static,
 {
     /// Spawns the gRPC server on the given host and port, returning a handle to the task.
+    /// With `internal_tls`, the proxies and the other shards must authenticate with it, and
+    /// so does this shard when it connects to them.
     #[expect(clippy::too_many_arguments)]
     pub fn spawn(
         host: String,
@@ -380,6 +383,7 @@ where
         state: WorkerState<S>,
         shard_id: ShardId,
         internal_network: ValidatorInternalNetworkConfig,
+        internal_tls: Option<InternalTls>,
         cross_chain_config: &CrossChainConfig,
         notification_config: &NotificationConfig,
         shutdown_signal: CancellationToken,
@@ -418,6 +422,7 @@ where
             Self::forward_cross_chain_queries(
                 state.nickname().to_string(),
                 internal_network.clone(),
+                internal_tls.clone(),
                 cross_chain_config.max_retries,
                 Duration::from_millis(cross_chain_config.retry_delay_ms),
                 Duration::from_millis(cross_chain_config.max_backoff_ms),
@@ -445,6 +450,7 @@ where
                 Self::forward_notifications(
                     state.nickname().to_string(),
                     proxy.internal_address(&internal_network.protocol),
+                    internal_tls.clone(),
                     exporter_addresses,
                     receiver,
                     notification_config.clone(),
@@ -490,16 +496,27 @@ where
                     .layer(GrpcPrometheusMetricsMiddlewareLayer)
                     .into_inner(),
             );
-            server
+            let router = server
                 // Every dialer is part of this validator (proxies, peer cross-chain
                 // forwarders), so this cap times a known connection count bounds
                 // in-flight work -- unlike hyper's 200 default, fixed at any shard size.
                 .max_concurrent_streams(Some(10_000))
                 .add_service(health_service)
                 .add_service(reflection_service)
-                .add_service(worker_node)
-                .serve_with_shutdown(server_address, shutdown_signal.cancelled_owned())
-                .await?;
+                .add_service(worker_node);
+            match internal_tls {
+                Some(tls) => {
+                    let incoming = tls.incoming(server_address).await?;
+                    router
+                        .serve_with_incoming_shutdown(incoming, shutdown_signal.cancelled_owned())
+                        .await?;
+                }
+                None => {
+                    router
+                        .serve_with_shutdown(server_address, shutdown_signal.cancelled_owned())
+                        .await?;
+                }
+            }
 
             Ok(())
         });
@@ -509,17 +526,26 @@ where
 
     /// Continuously waits for receiver to receive notifications and sends them to
     /// the proxy in batches for improved throughput.
-    #[instrument(skip(receiver, config))]
+    #[instrument(skip(internal_tls, receiver, config))]
     async fn forward_notifications(
         nickname: String,
         proxy_address: String,
+        internal_tls: Option<InternalTls>,
         exporter_addresses: Vec<String>,
         mut receiver: tokio::sync::broadcast::Receiver<Notification>,
         config: NotificationConfig,
     ) {
-        let channel = tonic::transport::Channel::from_shared(proxy_address.clone())
-            .expect("Proxy URI should be valid")
-            .connect_lazy();
+        let channel = match &internal_tls {
+            Some(tls) => transport::create_internal_channel(
+                proxy_address.clone(),
+                &transport::Options::default(),
+                tls,
+            )
+            .expect("Proxy URI and internal TLS configuration should be valid"),
+            None => tonic::transport::Channel::from_shared(proxy_address.clone())
+                .expect("Proxy URI should be valid")
+                .connect_lazy(),
+        };
         let client = NotifierServiceClient::new(channel)
             .max_encoding_message_size(GRPC_MAX_MESSAGE_SIZE)
             .max_decoding_message_size(GRPC_MAX_MESSAGE_SIZE);
@@ -631,6 +657,7 @@ where
     async fn forward_cross_chain_queries(
         nickname: String,
         network: ValidatorInternalNetworkConfig,
+        internal_tls: Option<InternalTls>,
         cross_chain_max_retries: u32,
         cross_chain_retry_delay: Duration,
         cross_chain_max_backoff: Duration,
@@ -639,7 +666,7 @@ where
         this_shard: ShardId,
         receiver: mpsc::Receiver<(linera_core::data_types::CrossChainRequest, ShardId)>,
     ) {
-        let pool = GrpcConnectionPool::default();
+        let pool = GrpcConnectionPool::default().with_internal_tls(internal_tls);
         let handle_request =
             move |shard_id: ShardId, request: linera_core::data_types::CrossChainRequest| {
                 let channel_result = pool.channel(network.shard(shard_id).http_address());
@@ -0,0 +1,699 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Mutual TLS on the internal links of a validator, i.e. from the proxies to the shards,
+//! from the shards to the proxies, and between shards.
+//!
+//! Both ends of a link present a certificate signed by the certificate authority of the
+//! validator, and optionally one of a list of pinned certificates. The certificates are read
+//! from files, which are checked for changes at most once per reload interval, so they can be
+//! rotated without restarting the processes: new connections use the new certificates.
+
+use std::{
+    fmt,
+    future::Future,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::{Arc, Mutex, RwLock},
+    task::{Context, Poll},
+    time::{Duration, Instant, SystemTime},
+};
+
+use futures::{FutureExt as _, Stream};
+use hyper_util::rt::TokioIo;
+use rustls::{
+    client::{
+        danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+        ResolvesClientCert, WebPkiServerVerifier,
+    },
+    crypto::CryptoProvider,
+    pki_types::{pem::PemObject as _, CertificateDer, PrivateKeyDer, ServerName, UnixTime},
+    server::{
+        danger::{ClientCertVerified, ClientCertVerifier},
+        ClientHello, ResolvesServerCert, WebPkiClientVerifier,
+    },
+    sign::CertifiedKey,
+    CertificateError, ClientConfig, DigitallySignedStruct, DistinguishedName, OtherError,
+    RootCertStore, ServerConfig, SignatureScheme,
+};
+use sha2::{Digest as _, Sha256};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::{server::TlsStream, TlsAcceptor, TlsConnector};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{debug, info, warn};
+
+use super::GrpcError;
+use crate::config::{InternalTlsOptions, TlsConfig};
+
+/// The maximum time given to a peer to complete the TLS handshake.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The number of accepted connections waiting for the server to serve them.
+const ACCEPT_QUEUE_SIZE: usize = 64;
+
+/// The certificates authenticating the internal links of a validator. Cloning it is cheap,
+/// and all the clones see the reloaded certificates.
+#[derive(Clone)]
+pub struct InternalTls(Arc<InternalTlsInner>);
+
+struct InternalTlsInner {
+    ca_certificate: PathBuf,
+    certificate: PathBuf,
+    private_key: PathBuf,
+    /// The lowercase hexadecimal SHA-256 fingerprints of the accepted peer certificates, or
+    /// empty to accept any certificate signed by the certificate authority.
+    pinned_certificates: Vec<String>,
+    reload_interval: Duration,
+    provider: Arc<CryptoProvider>,
+    material: RwLock<Arc<TlsMaterial>>,
+    /// When the files were last checked for changes.
+    last_check: Mutex<Instant>,
+}
+
+/// What is loaded from the certificate files.
+struct TlsMaterial {
+    /// The modification times of the files when they were loaded.
+    modified: [SystemTime; 3],
+    certified_key: Arc<CertifiedKey>,
+    server_verifier: Arc<WebPkiServerVerifier>,
+    client_verifier: Arc<dyn ClientCertVerifier>,
+}
+
+impl fmt::Debug for InternalTls {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InternalTls")
+            .field("ca_certificate", &self.0.ca_certificate)
+            .field("certificate", &self.0.certificate)
+            .field("private_key", &self.0.private_key)
+            .field("pinned_certificates", &self.0.pinned_certificates)
+            .finish()
+    }
+}
+
+impl InternalTls {
+    /// Returns the certificates to use with the internal protocol `tls`, or `None` if the
+    /// internal links are in clear text.
+    pub fn for_protocol(
+        tls: TlsConfig,
+        options: &InternalTlsOptions,
+    ) -> Result<Option<Self>, GrpcError> {
+        match tls {
+            TlsConfig::ClearText if options.is_empty() => Ok(None),
+            TlsConfig::ClearText => Err(GrpcError::TlsConfiguration(
+                "internal TLS certificates were given but the internal protocol is `grpc`; \
+                use `grpcs` instead"
+                    .to_string(),
+            )),
+            TlsConfig::Tls => Self::load(options).map(Some),
+        }
+    }
+
+    /// Loads the certificates described by `options`.
+    pub fn load(options: &InternalTlsOptions) -> Result<Self, GrpcError> {
+        let missing = |flag: &str| {
+            GrpcError::TlsConfiguration(format!(
+                "the internal protocol is `grpcs` or `quic` but `--{flag}` is missing"
+            ))
+        };
+        let ca_certificate = options
+            .ca_certificate
+            .clone()
+            .ok_or_else(|| missing("internal-tls-ca"))?;
+        let certificate = options
+            .certificate
+            .clone()
+            .ok_or_else(|| missing("internal-tls-certificate"))?;
+        let private_key = options
+            .private_key
+            .clone()
+            .ok_or_else(|| missing("internal-tls-key"))?;
+        let pinned_certificates = options
+            .pinned_certificates
+            .iter()
+            .map(|fingerprint| parse_fingerprint(fingerprint))
+            .collect::<Result<_, _>>()?;
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let material = TlsMaterial::load(&ca_certificate, &certificate, &private_key, &provider)?;
+        Ok(Self(Arc::new(InternalTlsInner {
+            ca_certificate,
+            certificate,
+            private_key,
+            pinned_certificates,
+            reload_interval: Duration::from_millis(options.reload_interval_ms),
+            provider,
+            material: RwLock::new(Arc::new(material)),
+            last_check: Mutex::new(Instant::now()),
+        })))
+    }
+
+    /// Returns the current certificates, reloading them first if the files changed and the
+    /// reload interval elapsed since the last check. If the new files cannot be loaded, e.g.
+    /// because they are being written, the previous certificates are kept.
+    fn material(&self) -> Arc<TlsMaterial> {
+        let inner = &self.0;
+        let material = inner.material.read().unwrap().clone();
+        {
+            let mut last_check = inner.last_check.lock().unwrap();
+            if last_check.elapsed() < inner.reload_interval {
+                return material;
+            }
+            *last_check = Instant::now();
+        }
+        let paths = [
+            &inner.ca_certificate,
+            &inner.certificate,
+            &inner.private_key,
+        ];
+        match modification_times(paths) {
+            Ok(modified) if modified == material.modified => return material,
+            Ok(_) => {}
+            Err(error) => {
+                warn!(%error, "cannot check the internal TLS certificates for changes");
+                return material;
+            }
+        }
+        match TlsMaterial::load(
+            &inner.ca_certificate,
+            &inner.certificate,
+            &inner.private_key,
+            &inner.provider,
+        ) {
+            Ok(reloaded) => {
+                info!("reloaded the internal TLS certificates");
+                let reloaded = Arc::new(reloaded);
+                *inner.material.write().unwrap() = reloaded.clone();
+                reloaded
+            }
+            Err(error) => {
+                warn!(%error, "keeping the previous internal TLS certificates");
+                material
+            }
+        }
+    }
+
+    /// Checks that `certificate` is pinned, if any certificate is.
+    fn check_pinned(&self, certificate: &CertificateDer<'_>) -> Result<(), rustls::Error> {
+        if self.0.pinned_certificates.is_empty() {
+            return Ok(());
+        }
+        let fingerprint = hex::encode(Sha256::digest(certificate));
+        if self.0.pinned_certificates.contains(&fingerprint) {
+            Ok(())
+        } else {
+            Err(rustls::Error::InvalidCertificate(CertificateError::Other(
+                OtherError(Arc::new(UnpinnedCertificate(fingerprint))),
+            )))
+        }
+    }
+
+    /// Returns the configuration of servers authenticating their peers, for HTTP/2.
+    pub(crate) fn server_config(&self) -> Result<ServerConfig, GrpcError> {
+        let mut config = ServerConfig::builder_with_provider(self.0.provider.clone())
+            .with_safe_default_protocol_versions()
+            .map_err(|error| GrpcError::TlsConfiguration(error.to_string()))?
+            .with_client_cert_verifier(Arc::new(self.clone()))
+            .with_cert_resolver(Arc::new(self.clone()));
+        config.alpn_protocols = vec![b"h2".to_vec()];
+        Ok(config)
+    }
+
+    /// Returns the configuration of clients authenticating their peers, for HTTP/2.
+    pub(crate) fn client_config(&self) -> Result<ClientConfig, GrpcError> {
+        let mut config = ClientConfig::builder_with_provider(self.0.provider.clone())
+            .with_safe_default_protocol_versions()
+            .map_err(|error| GrpcError::TlsConfiguration(error.to_string()))?
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(self.clone()))
+            .with_client_cert_resolver(Arc::new(self.clone()));
+        config.alpn_protocols = vec![b"h2".to_vec()];
+        Ok(config)
+    }
+
+    /// Returns a connector making authenticated connections to the other proxies and shards,
+    /// to be used with [`tonic::transport::Endpoint::connect_with_connector_lazy`].
+    pub fn connector(&self) -> Result<InternalTlsConnector, GrpcError> {
+        Ok(InternalTlsConnector(TlsConnector::from(Arc::new(
+            self.client_config()?,
+        ))))
+    }
+
+    /// Listens on `address` and returns the connections of the peers that completed the TLS
+    /// handshake, to be used with [`tonic::transport::Server::serve_with_incoming`]. The
+    /// handshakes that fail are logged and the connections dropped.
+    pub async fn incoming(
+        &self,
+        address: SocketAddr,
+    ) -> Result<
+        impl Stream<Item = Result<TlsStream<TcpStream>, std::convert::Infallible>> + Send + 'static,
+        GrpcError,
+    > {
+        let listener = TcpListener::bind(address).await?;
+        let acceptor = TlsAcceptor::from(Arc::new(self.server_config()?));
+        let (sender, receiver) = tokio::sync::mpsc::channel(ACCEPT_QUEUE_SIZE);
+        // The listener is closed once the server drops the stream.
+        tokio::spawn(async move {
+            loop {
+                let (stream, peer) = tokio::select! {
+                    () = sender.closed() => break,
+                    accepted = listener.accept() => match accepted {
+                        Ok(accepted) => accepted,
+                        Err(error) => {
+                            debug!(%error, "failed to accept a connection");
+                            continue;
+                        }
+                    },
+                };
+                let acceptor = acceptor.clone();
+                let sender = sender.clone();
+                tokio::spawn(async move {
+                    let peer = peer.to_string();
+                    let result = tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream))
+                        .await
+                        .unwrap_or_else(|_| {
+                            Err(std::io::Error::new(
+                                std::io::ErrorKind::TimedOut,
+                                "the TLS handshake timed out",
+                            ))
+                        });
+                    match result {
+                        Ok(stream) => {
+                            if sender.send(Ok(stream)).await.is_err() {
+                                debug!(peer, "the server stopped before serving a connection");
+                            }
+                        }
+                        Err(error) => {
+                            let error = handshake_error(peer, error);
+                            warn!(%error, "rejected an internal connection");
+                        }
+                    }
+                });
+            }
+        });
+        Ok(ReceiverStream::new(receiver))
+    }
+}
+
+impl TlsMaterial {
+    fn load(
+        ca_certificate: &Path,
+        certificate: &Path,
+        private_key: &Path,
+        provider: &Arc<CryptoProvider>,
+    ) -> Result<Self, GrpcError> {
+        let modified = modification_times([ca_certificate, certificate, private_key])?;
+        let mut roots = RootCertStore::empty();
+        for ca in read_certificates(ca_certificate)? {
+            roots
+                .add(ca)
+                .map_err(|error| invalid_file(ca_certificate, error))?;
+        }
+        let roots = Arc::new(roots);
+        let certificates = read_certificates(certificate)?;
+        let key = PrivateKeyDer::from_pem_file(private_key)
+            .map_err(|error| invalid_file(private_key, error))?;
+        let certified_key = CertifiedKey::from_der(certificates, key, provider)
+            .map_err(|error| invalid_file(private_key, error))?;
+        let server_verifier =
+            WebPkiServerVerifier::builder_with_provider(roots.clone(), provider.clone())
+                .build()
+                .map_err(|error| invalid_file(ca_certificate, error))?;
+        let client_verifier = WebPkiClientVerifier::builder_with_provider(roots, provider.clone())
+            .build()
+            .map_err(|error| invalid_file(ca_certificate, error))?;
+        Ok(Self {
+            modified,
+            certified_key: Arc::new(certified_key),
+            server_verifier,
+            client_verifier,
+        })
+    }
+}
+
+impl fmt::Debug for TlsMaterial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TlsMaterial")
+            .field("modified", &self.modified)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ServerCertVerifier for InternalTls {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let verified = self.material().server_verifier.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            ocsp_response,
+            now,
+        )?;
+        self.check_pinned(end_entity)?;
+        Ok(verified)
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        ServerCertVerifier::verify_tls12_signature(
+            self.material().server_verifier.as_ref(),
+            message,
+            cert,
+            dss,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        ServerCertVerifier::verify_tls13_signature(
+            self.material().server_verifier.as_ref(),
+            message,
+            cert,
+            dss,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        ServerCertVerifier::supported_verify_schemes(self.material().server_verifier.as_ref())
+    }
+}
+
+impl ClientCertVerifier for InternalTls {
+    fn root_hint_subjects(&self) -> &[DistinguishedName] {
+        // Every node has a single certificate, so there is nothing to choose from.
+        &[]
+    }
+
+    fn verify_client_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        now: UnixTime,
+    ) -> Result<ClientCertVerified, rustls::Error> {
+        let verified =
+            self.material()
+                .client_verifier
+                .verify_client_cert(end_entity, intermediates, now)?;
+        self.check_pinned(end_entity)?;
+        Ok(verified)
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.material()
+            .client_verifier
+            .verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.material()
+            .client_verifier
+            .verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.material().client_verifier.supported_verify_schemes()
+    }
+}
+
+impl ResolvesServerCert for InternalTls {
+    fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        Some(self.material().certified_key.clone())
+    }
+}
+
+impl ResolvesClientCert for InternalTls {
+    fn resolve(
+        &self,
+        _root_hint_subjects: &[&[u8]],
+        _sigschemes: &[SignatureScheme],
+    ) -> Option<Arc<CertifiedKey>> {
+        Some(self.material().certified_key.clone())
+    }
+
+    fn has_certs(&self) -> bool {
+        true
+    }
+}
+
+/// A connector opening TCP connections and authenticating them with [`InternalTls`].
+#[derive(Clone)]
+pub struct InternalTlsConnector(TlsConnector);
+
+impl tower::Service<http::Uri> for InternalTlsConnector {
+    type Response = TokioIo<tokio_rustls::client::TlsStream<TcpStream>>;
+    type Error = GrpcError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, GrpcError>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), GrpcError>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, uri: http::Uri) -> Self::Future {
+        let connector = self.0.clone();
+        async move {
+            let host = uri
+                .host()
+                .ok_or_else(|| GrpcError::TlsConfiguration(format!("no host in {uri}")))?
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .to_owned();
+            let port = uri.port_u16().unwrap_or(443);
+            let peer = format!("{host}:{port}");
+            let stream = TcpStream::connect((host.as_str(), port)).await?;
+            stream.set_nodelay(true)?;
+            let server_name =
+                ServerName::try_from(host).map_err(|error| GrpcError::PeerVerification {
+                    peer: peer.clone(),
+                    reason: error.to_string(),
+                })?;
+            let stream = connector
+                .connect(server_name, stream)
+                .await
+                .map_err(|error| handshake_error(peer, error))?;
+            Ok(TokioIo::new(stream))
+        }
+        .boxed()
+    }
+}
+
+/// The error of a peer certificate whose fingerprint is not pinned.
+#[derive(Debug, thiserror::Error)]
+#[error("the certificate with SHA-256 fingerprint {0} is not pinned")]
+struct UnpinnedCertificate(String);
+
+/// Returns the error of a failed TLS handshake with `peer`: a [`GrpcError::PeerVerification`]
+/// if TLS itself failed, e.g. because the certificate of the peer is not trusted.
+fn handshake_error(peer: String, error: std::io::Error) -> GrpcError {
+    match error
+        .get_ref()
+        .and_then(|inner| inner.downcast_ref::<rustls::Error>())
+    {
+        Some(tls_error) => GrpcError::PeerVerification {
+            peer,
+            reason: tls_error.to_string(),
+        },
+        None => GrpcError::Io(error),
+    }
+}
+
+fn parse_fingerprint(fingerprint: &str) -> Result<String, GrpcError> {
+    let normalized = fingerprint.replace(':', "").to_lowercase();
+    match hex::decode(&normalized) {
+        Ok(bytes) if bytes.len() == 32 => Ok(normalized),
+        _ => Err(GrpcError::TlsConfiguration(format!(
+            "{fingerprint:?} is not a SHA-256 fingerprint"
+        ))),
+    }
+}
+
+fn read_certificates(path: &Path) -> Result<Vec<CertificateDer<'static>>, GrpcError> {
+    let certificates = CertificateDer::pem_file_iter(path)
+        .and_then(|certificates| certificates.collect::<Result<Vec<_>, _>>())
+        .map_err(|error| invalid_file(path, error))?;
+    if certificates.is_empty() {
+        return Err(invalid_file(path, "no certificate found"));
+    }
+    Ok(certificates)
+}
+
+fn modification_times<P: AsRef<Path>>(paths: [P; 3]) -> Result<[SystemTime; 3], GrpcError> {
+    let mut times = [SystemTime::UNIX_EPOCH; 3];
+    for (time, path) in times.iter_mut().zip(paths) {
+        let path = path.as_ref();
+        *time = std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .map_err(|error| invalid_file(path, error))?;
+    }
+    Ok(times)
+}
+
+fn invalid_file(path: &Path, error: impl fmt::Display) -> GrpcError {
+    GrpcError::TlsConfiguration(format!("{}: {error}", path.display()))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::path::Path;
+
+    use rcgen::{BasicConstraints, Certificate, CertificateParams, ExtendedKeyUsagePurpose, IsCa};
+    use tower::Service as _;
+
+    use super::*;
+
+    pub(crate) fn authority() -> Certificate {
+        let mut params = CertificateParams::new(vec![]);
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        Certificate::from_params(params).unwrap()
+    }
+
+    /// Writes a certificate for `localhost` signed by `ca`, and returns its options.
+    pub(crate) fn node(dir: &Path, name: &str, ca: &Certificate) -> InternalTlsOptions {
+        let mut params = CertificateParams::new(vec!["localhost".to_string()]);
+        params.extended_key_usages = vec![
+            ExtendedKeyUsagePurpose::ServerAuth,
+            ExtendedKeyUsagePurpose::ClientAuth,
+        ];
+        let certificate = Certificate::from_params(params).unwrap();
+        let ca_path = dir.join(format!("{name}-ca.pem"));
+        let certificate_path = dir.join(format!("{name}.pem"));
+        let key_path = dir.join(format!("{name}-key.pem"));
+        std::fs::write(&ca_path, ca.serialize_pem().unwrap()).unwrap();
+        std::fs::write(
+            &certificate_path,
+            certificate.serialize_pem_with_signer(ca).unwrap(),
+        )
+        .unwrap();
+        std::fs::write(&key_path, certificate.serialize_private_key_pem()).unwrap();
+        InternalTlsOptions {
+            ca_certificate: Some(ca_path),
+            certificate: Some(certificate_path),
+            private_key: Some(key_path),
+            pinned_certificates: Vec::new(),
+            reload_interval_ms: 0,
+        }
+    }
+
+    fn fingerprint(options: &InternalTlsOptions) -> String {
+        let certificates = read_certificates(options.certificate.as_ref().unwrap()).unwrap();
+        hex::encode(Sha256::digest(&certificates[0]))
+    }
+
+    /// Accepts one connection with `server` and returns the result of connecting to it with
+    /// `client`.
+    async fn connect(server: &InternalTls, client: &InternalTls) -> Result<(), GrpcError> {
+        use futures::StreamExt as _;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        drop(listener);
+        let mut incoming = Box::pin(server.incoming(address).await.unwrap());
+        let uri = format!("http://localhost:{}", address.port())
+            .parse()
+            .unwrap();
+        let accepted = tokio::spawn(async move { incoming.next().await });
+        client.connector()?.call(uri).await?;
+        assert!(accepted.await.unwrap().is_some());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_mutual_authentication() {
+        let dir = tempfile::tempdir().unwrap();
+        let ca = authority();
+        let server_options = node(dir.path(), "server", &ca);
+        let client_options = node(dir.path(), "client", &ca);
+        let server = InternalTls::load(&server_options).unwrap();
+        let client = InternalTls::load(&client_options).unwrap();
+        connect(&server, &client).await.unwrap();
+
+        // A certificate signed by another authority is rejected.
+        let other_options = node(dir.path(), "other", &authority());
+        let other = InternalTls::load(&other_options).unwrap();
+        assert!(matches!(
+            connect(&server, &other).await,
+            Err(GrpcError::PeerVerification { .. })
+        ));
+
+        // Only the pinned certificates are accepted.
+        let pinned_client = InternalTls::load(&InternalTlsOptions {
+            pinned_certificates: vec![fingerprint(&client_options)],
+            ..client_options.clone()
+        })
+        .unwrap();
+        assert!(matches!(
+            connect(&server, &pinned_client).await,
+            Err(GrpcError::PeerVerification { .. })
+        ));
+        let pinned_client = InternalTls::load(&InternalTlsOptions {
+            pinned_certificates: vec![fingerprint(&server_options)],
+            ..client_options
+        })
+        .unwrap();
+        connect(&server, &pinned_client).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let ca = authority();
+        let server_options = node(dir.path(), "server", &ca);
+        let client_options = node(dir.path(), "client", &ca);
+        let server = InternalTls::load(&server_options).unwrap();
+        let client = InternalTls::load(&client_options).unwrap();
+
+        // Once its files are replaced by the ones of another authority, the client uses them
+        // for its new connections, and no longer trusts the server.
+        let other_options = node(dir.path(), "other", &authority());
+        for (from, to) in [
+            (
+                &other_options.ca_certificate,
+                &client_options.ca_certificate,
+            ),
+            (&other_options.certificate, &client_options.certificate),
+            (&other_options.private_key, &client_options.private_key),
+        ] {
+            std::fs::copy(from.as_ref().unwrap(), to.as_ref().unwrap()).unwrap();
+        }
+        // Make sure that the modification times differ on coarse file systems.
+        let later = SystemTime::now() + Duration::from_secs(10);
+        for path in [
+            &client_options.ca_certificate,
+            &client_options.certificate,
+            &client_options.private_key,
+        ] {
+            std::fs::File::options()
+                .write(true)
+                .open(path.as_ref().unwrap())
+                .unwrap()
+                .set_modified(later)
+                .unwrap();
+        }
+        assert!(matches!(
+            connect(&server, &client).await,
+            Err(GrpcError::PeerVerification { .. })
+        ));
+    }
+}
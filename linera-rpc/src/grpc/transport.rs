@@ -38,8 +38,32 @@ cfg_if::cfg_if! {
             address: String,
             options: &Options,
         ) -> Result<Channel, Error> {
-            let mut endpoint = tonic::transport::Endpoint::from_shared(address)?
-                .tls_config(tonic::transport::channel::ClientTlsConfig::default().with_webpki_roots())?
+            let endpoint = tonic::transport::Endpoint::from_shared(address)?
+                .tls_config(tonic::transport::channel::ClientTlsConfig::default().with_webpki_roots())?;
+            Ok(configure_endpoint(endpoint, options).connect_lazy())
+        }
+
+        /// Creates a transport channel for the given address of another proxy or shard of the
+        /// same validator, authenticated with `tls`.
+        pub fn create_internal_channel(
+            address: String,
+            options: &Options,
+            tls: &super::tls::InternalTls,
+        ) -> Result<Channel, super::GrpcError> {
+            // The connector makes the TLS handshake, so tonic must not attempt its own.
+            let address = match address.strip_prefix("https://") {
+                Some(rest) => format!("http://{rest}"),
+                None => address,
+            };
+            let endpoint = tonic::transport::Endpoint::from_shared(address)?;
+            Ok(configure_endpoint(endpoint, options).connect_with_connector_lazy(tls.connector()?))
+        }
+
+        fn configure_endpoint(
+            endpoint: tonic::transport::Endpoint,
+            options: &Options,
+        ) -> tonic::transport::Endpoint {
+            let mut endpoint = endpoint
                 .tcp_keepalive(Some(std::time::Duration::from_secs(60)))
                 .http2_keep_alive_interval(std::time::Duration::from_secs(30))
                 .keep_alive_timeout(std::time::Duration::from_secs(10))
@@ -51,7 +75,7 @@ cfg_if::cfg_if! {
             if let Some(timeout) = options.timeout {
                 endpoint = endpoint.timeout(timeout);
             }
-            Ok(endpoint.connect_lazy())
+            endpoint
         }
     }
 }
@@ -87,6 +87,10 @@ pub enum RpcMessage {
     // Notification subscription
     SubscribeNotifications(Vec<ChainId>),
     Notification(Box<Notification>),
+
+    // Chain history streaming
+    StreamChainHistory(ChainId, BlockHeight, BlockHeight),
+    ChainHistoryCertificate(Box<ConfirmedBlockCertificate>),
 }
 
 impl RpcMessage {
@@ -106,6 +110,7 @@ impl RpcMessage {
             CrossChainRequest(request) => request.target_chain_id(),
             DownloadPendingBlob(request) => request.0,
             DownloadCertificatesByHeights(chain_id, _) => *chain_id,
+            StreamChainHistory(chain_id, _, _) => *chain_id,
             HandlePendingBlob(request) => request.0,
             ShardInfoQuery(chain_id) => *chain_id,
             Vote(_)
@@ -136,7 +141,8 @@ impl RpcMessage {
             | ShardInfoResponse(_)
             | DownloadCertificatesResponse(_)
             | SubscribeNotifications(_)
-            | Notification(_) => {
+            | Notification(_)
+            | ChainHistoryCertificate(_) => {
                 return None;
             }
         };
@@ -162,7 +168,8 @@ impl RpcMessage {
             | MissingBlobIds(_)
             | EventBlockHeights(_)
             | DownloadCertificates(_)
-            | DownloadCertificatesByHeights(_, _) => true,
+            | DownloadCertificatesByHeights(_, _)
+            | StreamChainHistory(_, _, _) => true,
             BlockProposal(_)
             | LiteCertificate(_)
             | TimeoutCertificate(_)
@@ -189,7 +196,8 @@ impl RpcMessage {
             | DownloadCertificatesResponse(_)
             | DownloadCertificatesByHeightsResponse(_)
             | SubscribeNotifications(_)
-            | Notification(_) => false,
+            | Notification(_)
+            | ChainHistoryCertificate(_) => false,
         }
     }
 }
@@ -35,7 +35,7 @@ impl ValidatorNodeProvider for NodeProvider {
         let address = address.to_lowercase();
 
         #[cfg(with_simple_network)]
-        if address.starts_with("tcp") || address.starts_with("udp") {
+        if address.starts_with("tcp") || address.starts_with("udp") || address.starts_with("quic") {
             return Ok(Client::Simple(self.simple.make_node(&address)?));
         }
 
@@ -17,24 +17,32 @@ use linera_chain::{
 };
 use linera_core::{
     data_types::{ChainInfoQuery, ChainInfoResponse},
-    node::{BlobStream, CrossChainMessageDelivery, NodeError, NotificationStream, ValidatorNode},
+    node::{
+        BlobStream, CertificateStream, CrossChainMessageDelivery, NodeError, NotificationStream,
+        ValidatorNode,
+    },
 };
 use linera_storage::Arc as CacheArc;
 use linera_version::VersionInfo;
 
-use super::{codec, transport::TransportProtocol};
+use super::{
+    codec,
+    quic::QuicConnections,
+    transport::{Transport, TransportProtocol},
+};
 use crate::{
     config::ValidatorPublicNetworkPreConfig, HandleConfirmedCertificateRequest,
     HandleLiteCertRequest, HandleTimeoutCertificateRequest, HandleValidatedCertificateRequest,
     RpcMessage,
 };
 
-/// A client communicating with validators over a simple (UDP or TCP) transport.
+/// A client communicating with validators over a simple (UDP, TCP or QUIC) transport.
 #[derive(Clone)]
 pub struct SimpleClient {
     network: ValidatorPublicNetworkPreConfig<TransportProtocol>,
     send_timeout: Duration,
     recv_timeout: Duration,
+    quic_connections: QuicConnections,
 }
 
 impl SimpleClient {
@@ -42,17 +50,48 @@ impl SimpleClient {
         network: ValidatorPublicNetworkPreConfig<TransportProtocol>,
         send_timeout: Duration,
         recv_timeout: Duration,
+        quic_connections: QuicConnections,
     ) -> Self {
         Self {
             network,
             send_timeout,
             recv_timeout,
+            quic_connections,
         }
     }
 
+    async fn connect(&self) -> Result<impl Transport, std::io::Error> {
+        self.network
+            .protocol
+            .connect(
+                &self.network.host,
+                self.network.port,
+                &self.quic_connections,
+            )
+            .await
+    }
+
+    /// Sends a request whose responses are streamed back, and returns the open connection.
+    async fn send_streaming_request(
+        &self,
+        message: RpcMessage,
+    ) -> Result<impl Transport, NodeError> {
+        let mut stream = self.connect().await.map_err(|e| NodeError::ClientIoError {
+            error: e.to_string(),
+        })?;
+        timer::timeout(self.send_timeout, stream.send(message))
+            .await
+            .map_err(|timeout| NodeError::ClientIoError {
+                error: timeout.to_string(),
+            })?
+            .map_err(|e| NodeError::ClientIoError {
+                error: e.to_string(),
+            })?;
+        Ok(stream)
+    }
+
     async fn send_recv_internal(&self, message: RpcMessage) -> Result<RpcMessage, codec::Error> {
-        let address = format!("{}:{}", self.network.host, self.network.port);
-        let mut stream = self.network.protocol.connect(address).await?;
+        let mut stream = self.connect().await?;
         // Send message
         timer::timeout(self.send_timeout, stream.send(message))
             .await
@@ -152,14 +191,9 @@ impl ValidatorNode for SimpleClient {
     }
 
     async fn subscribe(&self, chains: Vec<ChainId>) -> Result<NotificationStream, NodeError> {
-        let mut stream = self
-            .network
-            .protocol
-            .connect((self.network.host.clone(), self.network.port))
-            .await
-            .map_err(|e| NodeError::ClientIoError {
-                error: e.to_string(),
-            })?;
+        let mut stream = self.connect().await.map_err(|e| NodeError::ClientIoError {
+            error: e.to_string(),
+        })?;
         // Send subscription request
         timer::timeout(
             self.send_timeout,
@@ -200,25 +234,9 @@ impl ValidatorNode for SimpleClient {
     }
 
     async fn download_blobs(&self, blob_ids: Vec<BlobId>) -> Result<BlobStream, NodeError> {
-        let mut stream = self
-            .network
-            .protocol
-            .connect((self.network.host.clone(), self.network.port))
-            .await
-            .map_err(|e| NodeError::ClientIoError {
-                error: e.to_string(),
-            })?;
-        timer::timeout(
-            self.send_timeout,
-            stream.send(RpcMessage::DownloadBlobs(blob_ids)),
-        )
-        .await
-        .map_err(|timeout| NodeError::ClientIoError {
-            error: timeout.to_string(),
-        })?
-        .map_err(|e| NodeError::ClientIoError {
-            error: e.to_string(),
-        })?;
+        let stream = self
+            .send_streaming_request(RpcMessage::DownloadBlobs(blob_ids))
+            .await?;
         let blob_stream = stream.filter_map(|result| async {
             match result {
                 Ok(RpcMessage::DownloadBlobResponse(blob)) => Some(Ok(*blob)),
@@ -232,6 +250,30 @@ impl ValidatorNode for SimpleClient {
         Ok(Box::pin(blob_stream))
     }
 
+    async fn stream_chain_history(
+        &self,
+        chain_id: ChainId,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> Result<CertificateStream, NodeError> {
+        let stream = self
+            .send_streaming_request(RpcMessage::StreamChainHistory(
+                chain_id,
+                from_height,
+                to_height,
+            ))
+            .await?;
+        let certificate_stream = stream.map(|result| match result {
+            Ok(RpcMessage::ChainHistoryCertificate(certificate)) => Ok(*certificate),
+            Ok(RpcMessage::Error(err)) => Err(*err),
+            Ok(_) => Err(NodeError::UnexpectedMessage),
+            Err(e) => Err(NodeError::ClientIoError {
+                error: e.to_string(),
+            }),
+        });
+        Ok(Box::pin(certificate_stream))
+    }
+
     async fn download_pending_blob(
         &self,
         chain_id: ChainId,
@@ -4,6 +4,7 @@
 mod client;
 mod codec;
 mod node_provider;
+mod quic;
 #[cfg(with_server)]
 mod server;
 mod transport;
@@ -11,6 +12,7 @@ mod transport;
 pub use client::*;
 pub use codec::*;
 pub use node_provider::*;
+pub use quic::*;
 #[cfg(with_server)]
 pub use server::*;
 pub use transport::*;
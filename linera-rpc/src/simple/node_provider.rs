@@ -5,17 +5,26 @@ use std::str::FromStr as _;
 
 use linera_core::node::{NodeError, ValidatorNodeProvider};
 
-use super::SimpleClient;
+use super::{QuicConnections, SimpleClient};
 use crate::{config::ValidatorPublicNetworkPreConfig, node_provider::NodeOptions};
 
 /// A client without an address - serves as a client factory.
-#[derive(Copy, Clone)]
-pub struct SimpleNodeProvider(NodeOptions);
+///
+/// The clients it creates share their QUIC connections, so that requests to the same validator
+/// reuse a single connection.
+#[derive(Clone)]
+pub struct SimpleNodeProvider {
+    options: NodeOptions,
+    quic_connections: QuicConnections,
+}
 
 impl SimpleNodeProvider {
     /// Creates a new [`SimpleNodeProvider`] with the given node options.
     pub fn new(options: NodeOptions) -> Self {
-        Self(options)
+        Self {
+            options,
+            quic_connections: QuicConnections::default(),
+        }
     }
 }
 
@@ -29,7 +38,12 @@ impl ValidatorNodeProvider for SimpleNodeProvider {
             }
         })?;
 
-        let client = SimpleClient::new(network, self.0.send_timeout, self.0.recv_timeout);
+        let client = SimpleClient::new(
+            network,
+            self.options.send_timeout,
+            self.options.recv_timeout,
+            self.quic_connections.clone(),
+        );
 
         Ok(client)
     }
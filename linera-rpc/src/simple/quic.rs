@@ -0,0 +1,548 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The QUIC transport of the simple network protocol.
+//!
+//! Every request, subscription or blob download is sent on its own bidirectional stream, framed
+//! with the same [`Codec`] as TCP, so concurrent requests to a validator share a single
+//! connection without head-of-line blocking. Clients keep the connections open in a
+//! [`QuicConnections`] cache and reuse them across requests.
+//!
+//! QUIC is always encrypted. On the internal links of a validator, both ends authenticate
+//! each other with the certificates of [`InternalTls`], as with the `grpcs` protocol. The
+//! public endpoint of a proxy presents the certificate given in its options, and the clients
+//! verify it against the web PKI certificate authorities.
+
+use std::{
+    collections::HashMap,
+    io,
+    net::SocketAddr,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures::{future, SinkExt as _};
+use linera_core::JoinSetExt as _;
+use quinn::{
+    crypto::rustls::{QuicClientConfig, QuicServerConfig},
+    ClientConfig, Connection, ConnectionError, Endpoint, Incoming, RecvStream, SendStream,
+    ServerConfig, TransportConfig,
+};
+use rustls::{
+    crypto::CryptoProvider,
+    pki_types::{pem::PemObject as _, CertificateDer, PrivateKeyDer},
+    RootCertStore,
+};
+use tokio::{net::ToSocketAddrs, task::JoinSet};
+use tokio_util::{codec::Framed, sync::CancellationToken};
+use tracing::{debug, warn};
+
+use super::{
+    codec::{self, Codec},
+    transport::{ConnectionPool, MessageHandler, TcpServer, REAP_TASKS_THRESHOLD},
+};
+use crate::{grpc::tls::InternalTls, RpcMessage};
+
+/// The application protocol negotiated by the clients and servers of the simple network.
+const ALPN_PROTOCOL: &[u8] = b"linera-simple";
+
+/// The interval between keep-alive packets, so that idle connections are kept open for reuse.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A bidirectional QUIC stream carrying the messages of a single request.
+pub type QuicStream = tokio::io::Join<RecvStream, SendStream>;
+
+/// The QUIC connections opened by a client, indexed by remote address. Cloning it is cheap,
+/// and all the clones share the same connections.
+///
+/// By default, the servers are authenticated with the web PKI certificate authorities, as the
+/// public endpoints of validators are.
+#[derive(Clone, Debug, Default)]
+pub struct QuicConnections {
+    /// The TLS configuration of the connections, or `None` for the default one.
+    tls: Option<Arc<rustls::ClientConfig>>,
+    inner: Arc<Mutex<QuicConnectionsInner>>,
+}
+
+#[derive(Debug, Default)]
+struct QuicConnectionsInner {
+    /// The local endpoint, created on first use.
+    endpoint: Option<Endpoint>,
+    connections: HashMap<SocketAddr, Connection>,
+}
+
+impl QuicConnections {
+    /// Returns an empty set of connections to the other proxies and shards of a validator,
+    /// authenticating both ends with the certificates of `tls`.
+    pub fn internal(tls: &InternalTls) -> Result<Self, io::Error> {
+        let mut config = tls.client_config().map_err(io::Error::other)?;
+        config.alpn_protocols = vec![ALPN_PROTOCOL.to_vec()];
+        Ok(Self::with_tls(config))
+    }
+
+    /// Returns an empty set of connections accepting the self-signed certificate of
+    /// [`crate::CERT_PEM`], which is only valid for `localhost`.
+    #[cfg(with_testing)]
+    pub fn self_signed() -> Result<Self, io::Error> {
+        let mut roots = RootCertStore::empty();
+        for certificate in CertificateDer::pem_slice_iter(crate::CERT_PEM.as_bytes()) {
+            roots
+                .add(certificate.map_err(io::Error::other)?)
+                .map_err(io::Error::other)?;
+        }
+        Ok(Self::with_tls(client_tls(roots)?))
+    }
+
+    fn with_tls(tls: rustls::ClientConfig) -> Self {
+        Self {
+            tls: Some(Arc::new(tls)),
+            inner: Arc::default(),
+        }
+    }
+
+    /// Opens a new stream to the server at `address`, reusing an existing connection if
+    /// there is one. The `server_name` is the host name the server's certificate must be
+    /// valid for.
+    pub async fn open_stream(
+        &self,
+        address: SocketAddr,
+        server_name: &str,
+    ) -> Result<Framed<QuicStream, Codec>, io::Error> {
+        if let Some(connection) = self.cached(address) {
+            match connection.open_bi().await {
+                Ok((send, recv)) => return Ok(Framed::new(tokio::io::join(recv, send), Codec)),
+                Err(error) => {
+                    debug!("Reconnecting to {address} after {error}");
+                    self.forget(address, &connection);
+                }
+            }
+        }
+        let connection = self.connect(address, server_name).await?;
+        let (send, recv) = connection.open_bi().await.map_err(io::Error::other)?;
+        Ok(Framed::new(tokio::io::join(recv, send), Codec))
+    }
+
+    fn cached(&self, address: SocketAddr) -> Option<Connection> {
+        let inner = self.inner.lock().unwrap();
+        let connection = inner.connections.get(&address)?;
+        connection
+            .close_reason()
+            .is_none()
+            .then(|| connection.clone())
+    }
+
+    fn forget(&self, address: SocketAddr, connection: &Connection) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner
+            .connections
+            .get(&address)
+            .is_some_and(|cached| cached.stable_id() == connection.stable_id())
+        {
+            inner.connections.remove(&address);
+        }
+    }
+
+    async fn connect(
+        &self,
+        address: SocketAddr,
+        server_name: &str,
+    ) -> Result<Connection, io::Error> {
+        let connecting = {
+            let mut inner = self.inner.lock().unwrap();
+            let endpoint = match &inner.endpoint {
+                Some(endpoint) => endpoint.clone(),
+                None => {
+                    let local_address = match address {
+                        SocketAddr::V4(_) => SocketAddr::from(([0, 0, 0, 0], 0)),
+                        SocketAddr::V6(_) => SocketAddr::from(([0u16; 8], 0)),
+                    };
+                    let tls = match &self.tls {
+                        Some(tls) => tls.clone(),
+                        None => Arc::new(client_tls(RootCertStore {
+                            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+                        })?),
+                    };
+                    let mut endpoint = Endpoint::client(local_address)?;
+                    endpoint.set_default_client_config(client_config(tls)?);
+                    inner.endpoint = Some(endpoint.clone());
+                    endpoint
+                }
+            };
+            endpoint
+                .connect(address, server_name)
+                .map_err(io::Error::other)?
+        };
+        let connection = connecting.await.map_err(io::Error::other)?;
+        self.inner
+            .lock()
+            .unwrap()
+            .connections
+            .insert(address, connection.clone());
+        Ok(connection)
+    }
+}
+
+/// An implementation of [`ConnectionPool`] based on QUIC, sending each message on its own
+/// stream.
+pub(crate) struct QuicConnectionPool {
+    connections: QuicConnections,
+}
+
+impl QuicConnectionPool {
+    pub(crate) fn new(connections: QuicConnections) -> Self {
+        Self { connections }
+    }
+}
+
+impl ConnectionPool for QuicConnectionPool {
+    fn send_message_to<'a>(
+        &'a mut self,
+        message: RpcMessage,
+        address: &'a str,
+    ) -> future::BoxFuture<'a, Result<(), codec::Error>> {
+        Box::pin(async move {
+            let (host, _) = address.rsplit_once(':').ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Invalid address: {address}"),
+                )
+            })?;
+            let socket_address = resolve(address).await?;
+            let mut stream = self.connections.open_stream(socket_address, host).await?;
+            // Closing the sink finishes the stream, once the message is delivered.
+            stream.send(message).await?;
+            stream.close().await
+        })
+    }
+}
+
+/// The TLS configuration of a QUIC server. Cloning it is cheap.
+#[derive(Clone, Debug)]
+pub struct QuicServerTls(Arc<rustls::ServerConfig>);
+
+impl QuicServerTls {
+    /// Returns the configuration of a proxy or shard accepting connections from the other
+    /// proxies and shards of its validator, authenticating both ends with the certificates
+    /// of `tls`.
+    pub fn internal(tls: &InternalTls) -> Result<Self, io::Error> {
+        let mut config = tls.server_config().map_err(io::Error::other)?;
+        config.alpn_protocols = vec![ALPN_PROTOCOL.to_vec()];
+        Ok(Self(Arc::new(config)))
+    }
+
+    /// Returns the configuration of a public endpoint, presenting the certificate chain and
+    /// private key of the given PEM files to any client.
+    pub fn from_pem_files(certificate: &Path, private_key: &Path) -> Result<Self, io::Error> {
+        let certificates = CertificateDer::pem_file_iter(certificate)
+            .and_then(Iterator::collect::<Result<Vec<_>, _>>)
+            .map_err(|error| invalid_file(certificate, error))?;
+        let key = PrivateKeyDer::from_pem_file(private_key)
+            .map_err(|error| invalid_file(private_key, error))?;
+        Self::public(certificates, key)
+    }
+
+    /// Returns the configuration of a public endpoint presenting the self-signed certificate
+    /// of [`crate::CERT_PEM`], which is only valid for `localhost`.
+    #[cfg(with_testing)]
+    pub fn self_signed() -> Result<Self, io::Error> {
+        let certificates = CertificateDer::pem_slice_iter(crate::CERT_PEM.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(io::Error::other)?;
+        let key =
+            PrivateKeyDer::from_pem_slice(crate::KEY_PEM.as_bytes()).map_err(io::Error::other)?;
+        Self::public(certificates, key)
+    }
+
+    fn public(
+        certificates: Vec<CertificateDer<'static>>,
+        key: PrivateKeyDer<'static>,
+    ) -> Result<Self, io::Error> {
+        let mut config = rustls::ServerConfig::builder_with_provider(crypto_provider())
+            .with_protocol_versions(&[&rustls::version::TLS13])
+            .map_err(io::Error::other)?
+            .with_no_client_auth()
+            .with_single_cert(certificates, key)
+            .map_err(io::Error::other)?;
+        config.alpn_protocols = vec![ALPN_PROTOCOL.to_vec()];
+        Ok(Self(Arc::new(config)))
+    }
+}
+
+/// Server implementation for QUIC.
+pub struct QuicServer;
+
+impl QuicServer {
+    /// Runs the QUIC server implementation.
+    ///
+    /// Accepts connections and serves every stream opened by the clients in its own task, the
+    /// same way as a [`TcpServer`] serves a TCP connection.
+    pub async fn run<State>(
+        address: impl ToSocketAddrs,
+        handler: State,
+        tls: QuicServerTls,
+        shutdown_signal: CancellationToken,
+    ) -> Result<(), io::Error>
+    where
+        State: MessageHandler + Send + 'static,
+    {
+        let crypto = QuicServerConfig::try_from(tls.0).map_err(io::Error::other)?;
+        let config = ServerConfig::with_crypto(Arc::new(crypto));
+        let endpoint = Endpoint::server(config, resolve(address).await?)?;
+
+        let connection_shutdown_signal = shutdown_signal.child_token();
+        let mut join_set = JoinSet::new();
+        let mut reap_countdown = REAP_TASKS_THRESHOLD;
+
+        loop {
+            tokio::select! { biased;
+                _ = shutdown_signal.cancelled() => {
+                    join_set.await_all_tasks_logging_panics().await;
+                    endpoint.close(0u32.into(), b"shutdown");
+                    return Ok(());
+                }
+                maybe_incoming = endpoint.accept() => match maybe_incoming {
+                    Some(incoming) => {
+                        join_set.spawn_task(Self::serve_connection(
+                            incoming,
+                            handler.clone(),
+                            connection_shutdown_signal.clone(),
+                        ));
+                        reap_countdown -= 1;
+                    }
+                    None => unreachable!("The endpoint is only closed on shutdown"),
+                },
+            }
+
+            if reap_countdown == 0 {
+                join_set.reap_finished_tasks();
+                reap_countdown = REAP_TASKS_THRESHOLD;
+            }
+        }
+    }
+
+    /// Serves the streams opened by a client on a single connection.
+    async fn serve_connection<State>(
+        incoming: Incoming,
+        handler: State,
+        shutdown_signal: CancellationToken,
+    ) where
+        State: MessageHandler + Send + 'static,
+    {
+        let peer = incoming.remote_address();
+        let connection = match incoming.await {
+            Ok(connection) => connection,
+            Err(error) => {
+                warn!("Failed to accept a QUIC connection from {peer}: {error}");
+                return;
+            }
+        };
+        let mut join_set = JoinSet::new();
+        let mut reap_countdown = REAP_TASKS_THRESHOLD;
+
+        loop {
+            tokio::select! { biased;
+                _ = shutdown_signal.cancelled() => break,
+                result = connection.accept_bi() => match result {
+                    Ok((send, recv)) => {
+                        let server = TcpServer::new_connection(
+                            tokio::io::join(recv, send),
+                            peer.to_string(),
+                            handler.clone(),
+                            shutdown_signal.clone(),
+                        );
+                        join_set.spawn_task(server.serve());
+                        reap_countdown -= 1;
+                    }
+                    Err(
+                        ConnectionError::ApplicationClosed(_)
+                        | ConnectionError::LocallyClosed
+                        | ConnectionError::TimedOut,
+                    ) => break,
+                    Err(error) => {
+                        warn!("QUIC connection to {peer} failed: {error}");
+                        break;
+                    }
+                },
+            }
+
+            if reap_countdown == 0 {
+                join_set.reap_finished_tasks();
+                reap_countdown = REAP_TASKS_THRESHOLD;
+            }
+        }
+
+        join_set.await_all_tasks_logging_panics().await;
+    }
+}
+
+/// Resolves `address` to its first socket address.
+async fn resolve(address: impl ToSocketAddrs) -> Result<SocketAddr, io::Error> {
+    tokio::net::lookup_host(address)
+        .await?
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Couldn't resolve address"))
+}
+
+fn crypto_provider() -> Arc<CryptoProvider> {
+    Arc::new(rustls::crypto::ring::default_provider())
+}
+
+fn invalid_file(path: &Path, error: impl std::fmt::Display) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("invalid TLS file {}: {error}", path.display()),
+    )
+}
+
+/// Returns the TLS configuration of clients authenticating the servers with `roots`.
+fn client_tls(roots: RootCertStore) -> Result<rustls::ClientConfig, io::Error> {
+    let mut tls = rustls::ClientConfig::builder_with_provider(crypto_provider())
+        .with_protocol_versions(&[&rustls::version::TLS13])
+        .map_err(io::Error::other)?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    tls.alpn_protocols = vec![ALPN_PROTOCOL.to_vec()];
+    Ok(tls)
+}
+
+fn client_config(tls: Arc<rustls::ClientConfig>) -> Result<ClientConfig, io::Error> {
+    let crypto = QuicClientConfig::try_from(tls).map_err(io::Error::other)?;
+    let mut transport = TransportConfig::default();
+    transport.keep_alive_interval(Some(KEEP_ALIVE_INTERVAL));
+    let mut config = ClientConfig::new(Arc::new(crypto));
+    config.transport_config(Arc::new(transport));
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt as _;
+    use linera_version::VersionInfo;
+
+    use super::*;
+    use crate::{
+        grpc::tls::tests::{authority, node},
+        simple::{ServerHandle, TransportProtocol},
+    };
+
+    /// A handler answering version queries only.
+    #[derive(Clone)]
+    struct VersionHandler;
+
+    #[async_trait::async_trait]
+    impl MessageHandler for VersionHandler {
+        async fn handle_message(&mut self, message: RpcMessage) -> Option<RpcMessage> {
+            matches!(message, RpcMessage::VersionInfoQuery)
+                .then(|| RpcMessage::VersionInfoResponse(VersionInfo::default().into()))
+        }
+    }
+
+    /// Sends a version query on a new stream, returning `None` if the server cannot be
+    /// reached or doesn't answer.
+    async fn try_query_version(
+        connections: &QuicConnections,
+        address: SocketAddr,
+    ) -> Option<RpcMessage> {
+        let mut stream = connections.open_stream(address, "localhost").await.ok()?;
+        stream.send(RpcMessage::VersionInfoQuery).await.ok()?;
+        stream.next().await?.ok()
+    }
+
+    async fn query_version(connections: &QuicConnections, address: SocketAddr) -> RpcMessage {
+        try_query_version(connections, address).await.unwrap()
+    }
+
+    fn spawn_server(
+        tls: QuicServerTls,
+        shutdown_signal: &CancellationToken,
+        join_set: &mut JoinSet<()>,
+    ) -> (SocketAddr, ServerHandle) {
+        let port = std::net::UdpSocket::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let address = SocketAddr::from(([127, 0, 0, 1], port));
+        let server = TransportProtocol::Quic.spawn_server(
+            address,
+            VersionHandler,
+            Some(tls),
+            shutdown_signal.clone(),
+            join_set,
+        );
+        (address, server)
+    }
+
+    #[tokio::test]
+    async fn test_requests_share_a_connection() {
+        let shutdown_signal = CancellationToken::new();
+        let mut join_set = JoinSet::new();
+        let (address, server) = spawn_server(
+            QuicServerTls::self_signed().unwrap(),
+            &shutdown_signal,
+            &mut join_set,
+        );
+
+        let connections = QuicConnections::self_signed().unwrap();
+        let (first, second) = futures::join!(
+            query_version(&connections, address),
+            query_version(&connections, address),
+        );
+        assert!(matches!(first, RpcMessage::VersionInfoResponse(_)));
+        assert!(matches!(second, RpcMessage::VersionInfoResponse(_)));
+        let connection = connections.cached(address).unwrap();
+        query_version(&connections, address).await;
+        assert_eq!(
+            connections.cached(address).unwrap().stable_id(),
+            connection.stable_id()
+        );
+
+        // The default clients only accept certificates of the web PKI.
+        let public_connections = QuicConnections::default();
+        assert!(try_query_version(&public_connections, address)
+            .await
+            .is_none());
+
+        shutdown_signal.cancel();
+        server.join().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_internal_links_are_mutually_authenticated() {
+        let dir = tempfile::tempdir().unwrap();
+        let ca = authority();
+        let load = |name, ca| InternalTls::load(&node(dir.path(), name, ca)).unwrap();
+        let server_options = node(dir.path(), "server", &ca);
+        let server_tls = InternalTls::load(&server_options).unwrap();
+        let shutdown_signal = CancellationToken::new();
+        let mut join_set = JoinSet::new();
+        let (address, server) = spawn_server(
+            QuicServerTls::internal(&server_tls).unwrap(),
+            &shutdown_signal,
+            &mut join_set,
+        );
+
+        let client = QuicConnections::internal(&load("client", &ca)).unwrap();
+        assert!(matches!(
+            query_version(&client, address).await,
+            RpcMessage::VersionInfoResponse(_)
+        ));
+
+        // A client with a certificate of another authority is rejected, and so is a client
+        // without a certificate.
+        let other = QuicConnections::internal(&load("other", &authority())).unwrap();
+        assert!(try_query_version(&other, address).await.is_none());
+        let mut roots = RootCertStore::empty();
+        for certificate in
+            CertificateDer::pem_file_iter(server_options.ca_certificate.unwrap()).unwrap()
+        {
+            roots.add(certificate.unwrap()).unwrap();
+        }
+        let anonymous = QuicConnections::with_tls(client_tls(roots).unwrap());
+        assert!(try_query_version(&anonymous, address).await.is_none());
+
+        shutdown_signal.cancel();
+        server.join().await.unwrap();
+    }
+}
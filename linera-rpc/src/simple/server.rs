@@ -18,10 +18,15 @@ use tokio_stream::wrappers::BroadcastStream;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, instrument};
 
-use super::transport::{MessageHandler, ServerHandle, TransportProtocol};
+use super::{
+    quic::{QuicConnections, QuicServerTls},
+    transport::{MessageHandler, ServerHandle, TransportProtocol},
+};
 use crate::{
     config::{CrossChainConfig, ShardId, ValidatorInternalNetworkPreConfig},
-    cross_chain_message_queue, RpcMessage,
+    cross_chain_message_queue,
+    grpc::tls::InternalTls,
+    RpcMessage,
 };
 
 /// A server handling RPC requests over a simple (UDP or TCP) transport.
@@ -36,6 +41,8 @@ where
     state: WorkerState<S>,
     shard_id: ShardId,
     cross_chain_config: CrossChainConfig,
+    /// The certificates authenticating the links to the other shards, if they use QUIC.
+    internal_tls: Option<InternalTls>,
     // Stats
     packets_processed: u64,
     user_errors: u64,
@@ -61,11 +68,19 @@ where
             state,
             shard_id,
             cross_chain_config,
+            internal_tls: None,
             packets_processed: 0,
             user_errors: 0,
         }
     }
 
+    /// Sets the certificates authenticating the links to the other proxies and shards,
+    /// which are required if the internal protocol is QUIC.
+    pub fn with_internal_tls(mut self, internal_tls: Option<InternalTls>) -> Self {
+        self.internal_tls = internal_tls;
+        self
+    }
+
     /// Returns the number of packets processed so far.
     pub fn packets_processed(&self) -> u64 {
         self.packets_processed
@@ -91,12 +106,13 @@ where
         cross_chain_sender_delay: Duration,
         cross_chain_sender_failure_rate: f32,
         this_shard: ShardId,
+        quic_connections: QuicConnections,
         receiver: mpsc::Receiver<(CrossChainRequest, ShardId)>,
     ) {
         let pool = Arc::new(Mutex::new(
             network
                 .protocol
-                .make_outgoing_connection_pool()
+                .make_outgoing_connection_pool(&quic_connections)
                 .await
                 .expect("Initialization should not fail"),
         ));
@@ -132,13 +148,21 @@ where
         mut self,
         shutdown_signal: CancellationToken,
         join_set: &mut JoinSet<()>,
-    ) -> ServerHandle {
+    ) -> Result<ServerHandle, std::io::Error> {
         info!(
             "Listening to {:?} traffic on {}:{}",
             self.network.protocol, self.host, self.port
         );
         let address = (self.host.clone(), self.port);
 
+        let (quic_connections, quic_tls) = match &self.internal_tls {
+            Some(tls) => (
+                QuicConnections::internal(tls)?,
+                Some(QuicServerTls::internal(tls)?),
+            ),
+            None => (QuicConnections::default(), None),
+        };
+
         let (cross_chain_sender, cross_chain_receiver) =
             mpsc::channel(self.cross_chain_config.queue_size);
 
@@ -170,6 +194,7 @@ where
             Duration::from_millis(self.cross_chain_config.sender_delay_ms),
             self.cross_chain_config.sender_failure_rate,
             self.shard_id,
+            quic_connections,
             cross_chain_receiver,
         ));
 
@@ -180,7 +205,7 @@ where
             notification_sender,
         };
         // Launch server for the appropriate protocol.
-        protocol.spawn_server(address, state, shutdown_signal, join_set)
+        Ok(protocol.spawn_server(address, state, quic_tls, shutdown_signal, join_set))
     }
 }
 
@@ -402,6 +427,8 @@ where
             | RpcMessage::ShardInfoResponse(_)
             | RpcMessage::DownloadBlob(_)
             | RpcMessage::DownloadBlobs(_)
+            | RpcMessage::StreamChainHistory(_, _, _)
+            | RpcMessage::ChainHistoryCertificate(_)
             | RpcMessage::DownloadBlobResponse(_)
             | RpcMessage::DownloadPendingBlobResponse(_)
             | RpcMessage::DownloadConfirmedBlock(_)
@@ -16,11 +16,14 @@ use futures::{
     stream::{self, FuturesUnordered, SplitSink, SplitStream},
     Sink, SinkExt, Stream, StreamExt, TryStreamExt,
 };
-use linera_base::identifiers::{BlobId, ChainId};
+use linera_base::{
+    data_types::BlockHeight,
+    identifiers::{BlobId, ChainId},
+};
 use linera_core::{JoinSetExt as _, TaskHandle};
 use serde::{Deserialize, Serialize};
 use tokio::{
-    io::AsyncWriteExt,
+    io::{AsyncRead, AsyncWrite, AsyncWriteExt},
     net::{lookup_host, TcpListener, TcpStream, ToSocketAddrs, UdpSocket},
     sync::Mutex,
     task::JoinSet,
@@ -28,6 +31,7 @@ use tokio::{
 use tokio_util::{codec::Framed, sync::CancellationToken, udp::UdpFramed};
 use tracing::{error, warn};
 
+use super::quic::{QuicConnectionPool, QuicConnections, QuicServer, QuicServerTls};
 use crate::{
     simple::{codec, codec::Codec},
     RpcMessage,
@@ -37,7 +41,7 @@ use crate::{
 pub const DEFAULT_MAX_DATAGRAM_SIZE: &str = "65507";
 
 /// Number of tasks to spawn before attempting to reap some finished tasks to prevent memory leaks.
-const REAP_TASKS_THRESHOLD: usize = 100;
+pub(crate) const REAP_TASKS_THRESHOLD: usize = 100;
 
 /// The transport protocols supported by the simple network.
 #[derive(clap::ValueEnum, Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -46,6 +50,8 @@ pub enum TransportProtocol {
     Udp,
     /// The TCP transport protocol.
     Tcp,
+    /// The QUIC transport protocol, with one stream per request on a reused connection.
+    Quic,
 }
 
 impl std::str::FromStr for TransportProtocol {
@@ -68,6 +74,7 @@ impl TransportProtocol {
         match self {
             TransportProtocol::Udp => "udp",
             TransportProtocol::Tcp => "tcp",
+            TransportProtocol::Quic => "quic",
         }
     }
 }
@@ -110,6 +117,19 @@ pub trait MessageHandler: Clone {
     ) -> Option<Pin<Box<dyn Stream<Item = RpcMessage> + Send>>> {
         None
     }
+
+    /// Handle a chain history request by streaming one
+    /// `RpcMessage::ChainHistoryCertificate` per block, in height order, from `from_height`
+    /// up to but excluding `to_height`. The stream may end early if a height is missing.
+    /// Returns `None` if not supported.
+    async fn handle_stream_chain_history(
+        &mut self,
+        _chain_id: ChainId,
+        _from_height: BlockHeight,
+        _to_height: BlockHeight,
+    ) -> Option<Pin<Box<dyn Stream<Item = RpcMessage> + Send>>> {
+        None
+    }
 }
 
 /// The result of spawning a server is oneshot channel to track completion, and the set of
@@ -146,12 +166,17 @@ impl<T> Transport for T where
 }
 
 impl TransportProtocol {
-    /// Creates a transport for this protocol.
+    /// Creates a transport for this protocol to the server at `host` and `port`.
+    ///
+    /// With QUIC, the transport is a new stream on the connection to the server in
+    /// `quic_connections`, which is opened if necessary. The other protocols ignore it.
     pub async fn connect(
         self,
-        address: impl ToSocketAddrs,
+        host: &str,
+        port: u16,
+        quic_connections: &QuicConnections,
     ) -> Result<impl Transport, std::io::Error> {
-        let mut addresses = lookup_host(address)
+        let mut addresses = lookup_host((host, port))
             .await
             .expect("Invalid address to connect to");
         let address = addresses
@@ -170,38 +195,57 @@ impl TransportProtocol {
             TransportProtocol::Tcp => {
                 let stream = TcpStream::connect(address).await?;
 
-                Framed::new(stream, Codec).right_stream()
+                Framed::new(stream, Codec).left_stream().right_stream()
             }
+            TransportProtocol::Quic => quic_connections
+                .open_stream(address, host)
+                .await?
+                .right_stream()
+                .right_stream(),
         };
 
         Ok(stream)
     }
 
-    /// Creates a [`ConnectionPool`] for this protocol.
+    /// Creates a [`ConnectionPool`] for this protocol. With QUIC, the pool uses the
+    /// connections of `quic_connections`; the other protocols ignore it.
     pub async fn make_outgoing_connection_pool(
         self,
+        quic_connections: &QuicConnections,
     ) -> Result<Box<dyn ConnectionPool>, std::io::Error> {
         let pool: Box<dyn ConnectionPool> = match self {
             Self::Udp => Box::new(UdpConnectionPool::new().await?),
             Self::Tcp => Box::new(TcpConnectionPool::new()),
+            Self::Quic => Box::new(QuicConnectionPool::new(quic_connections.clone())),
         };
         Ok(pool)
     }
 
     /// Runs a server for this protocol and the given message handler.
+    ///
+    /// A QUIC server presents the certificate of `quic_tls`, and fails if there is none. The
+    /// other protocols ignore it.
     pub fn spawn_server<S>(
         self,
         address: impl ToSocketAddrs + Send + 'static,
         state: S,
+        quic_tls: Option<QuicServerTls>,
         shutdown_signal: CancellationToken,
         join_set: &mut JoinSet<()>,
     ) -> ServerHandle
     where
         S: MessageHandler + Send + 'static,
     {
-        let handle = match self {
-            Self::Udp => join_set.spawn_task(UdpServer::run(address, state, shutdown_signal)),
-            Self::Tcp => join_set.spawn_task(TcpServer::run(address, state, shutdown_signal)),
+        let handle = match (self, quic_tls) {
+            (Self::Udp, _) => join_set.spawn_task(UdpServer::run(address, state, shutdown_signal)),
+            (Self::Tcp, _) => join_set.spawn_task(TcpServer::run(address, state, shutdown_signal)),
+            (Self::Quic, Some(tls)) => {
+                join_set.spawn_task(QuicServer::run(address, state, tls, shutdown_signal))
+            }
+            (Self::Quic, None) => join_set.spawn_task(future::ready(Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "a QUIC server needs a TLS certificate",
+            )))),
         };
         ServerHandle { handle }
     }
@@ -395,8 +439,12 @@ impl ConnectionPool for TcpConnectionPool {
 }
 
 /// Server implementation for TCP.
-pub struct TcpServer<State> {
-    connection: Framed<TcpStream, Codec>,
+///
+/// Each instance serves a single connection, or a single stream of a QUIC connection.
+pub struct TcpServer<State, Io = TcpStream> {
+    connection: Framed<Io, Codec>,
+    /// The address of the client, for logging.
+    peer: String,
     handler: State,
     shutdown_signal: CancellationToken,
 }
@@ -434,8 +482,12 @@ where
                 }
                 maybe_socket = accept_stream.next() => match maybe_socket {
                     Some(Ok(socket)) => {
+                        let peer = socket
+                            .peer_addr()
+                            .map_or_else(|_| "an unknown peer".to_owned(), |address| address.to_string());
                         let server = TcpServer::new_connection(
                             socket,
+                            peer,
                             handler.clone(),
                             connection_shutdown_signal.clone(),
                         );
@@ -458,32 +510,37 @@ where
             }
         }
     }
+}
 
+impl<State, Io> TcpServer<State, Io>
+where
+    State: MessageHandler + Send + 'static,
+    Io: AsyncRead + AsyncWrite + Unpin,
+{
     /// Creates a new [`TcpServer`] to serve a single connection established on the provided
-    /// [`TcpStream`].
-    fn new_connection(
-        tcp_stream: TcpStream,
+    /// stream, e.g. a [`TcpStream`].
+    pub(crate) fn new_connection(
+        stream: Io,
+        peer: String,
         handler: State,
         shutdown_signal: CancellationToken,
     ) -> Self {
         TcpServer {
-            connection: Framed::new(tcp_stream, Codec),
+            connection: Framed::new(stream, Codec),
+            peer,
             handler,
             shutdown_signal,
         }
     }
 
     /// Serves a client through a single connection.
-    async fn serve(mut self) {
+    pub(crate) async fn serve(mut self) {
         loop {
             tokio::select! { biased;
                 _ = self.shutdown_signal.cancelled() => {
-                    let mut tcp_stream = self.connection.into_inner();
-                    if let Err(error) = tcp_stream.shutdown().await {
-                        let peer = tcp_stream
-                            .peer_addr()
-                            .map_or_else(|_| "an unknown peer".to_owned(), |address| address.to_string());
-                        warn!("Failed to close connection to {peer}: {error:?}");
+                    let mut stream = self.connection.into_inner();
+                    if let Err(error) = stream.shutdown().await {
+                        warn!("Failed to close connection to {}: {error:?}", self.peer);
                     }
                     return;
                 }
@@ -496,6 +553,11 @@ where
                         self.handle_download_blobs(blob_ids).await;
                         return;
                     }
+                    Some(Ok(RpcMessage::StreamChainHistory(chain_id, from_height, to_height))) => {
+                        self.handle_stream_chain_history(chain_id, from_height, to_height)
+                            .await;
+                        return;
+                    }
                     Some(Ok(message)) => self.handle_message(message).await,
                     Some(Err(error)) => {
                         Self::handle_error(&error);
@@ -558,6 +620,36 @@ where
         }
     }
 
+    /// Handles a chain history request by streaming one response per certificate.
+    async fn handle_stream_chain_history(
+        &mut self,
+        chain_id: ChainId,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) {
+        let Some(mut stream) = self
+            .handler
+            .handle_stream_chain_history(chain_id, from_height, to_height)
+            .await
+        else {
+            return;
+        };
+        loop {
+            tokio::select! { biased;
+                _ = self.shutdown_signal.cancelled() => break,
+                msg = stream.next() => match msg {
+                    Some(message) => {
+                        if let Err(error) = self.connection.send(message).await {
+                            error!("Failed to send chain history response: {error}");
+                            break;
+                        }
+                    }
+                    None => break,
+                }
+            }
+        }
+    }
+
     /// Handles an error received while attempting to receive from the connection.
     ///
     /// Ignores a successful connection termination, while logging an unexpected connection
@@ -569,7 +661,7 @@ where
                 if error.kind() == io::ErrorKind::UnexpectedEof
                 || error.kind() == io::ErrorKind::ConnectionReset
         ) {
-            error!("Error while reading TCP stream: {error}");
+            error!("Error while reading stream: {error}");
         }
     }
 }
@@ -9,6 +9,7 @@ use linera_base::{
     http,
     identifiers::{AccountOwner, ApplicationId, ChainId, DataBlobHash},
 };
+use linera_views::store::{KeyBound, KeyRange};
 
 use crate::{
     contract::wit::base_runtime_api as base_contract_api,
@@ -136,6 +137,27 @@ macro_rules! impl_to_wit {
             }
         }
 
+        impl From<KeyRange> for $wit_base_api::KeyRange {
+            fn from(range: KeyRange) -> Self {
+                $wit_base_api::KeyRange {
+                    start: range.start.into(),
+                    end: range.end.into(),
+                    reverse: range.reverse,
+                    limit: range.limit,
+                }
+            }
+        }
+
+        impl From<KeyBound> for $wit_base_api::KeyBound {
+            fn from(bound: KeyBound) -> Self {
+                match bound {
+                    KeyBound::Unbounded => $wit_base_api::KeyBound::Unbounded,
+                    KeyBound::Included(key) => $wit_base_api::KeyBound::Included(key),
+                    KeyBound::Excluded(key) => $wit_base_api::KeyBound::Excluded(key),
+                }
+            }
+        }
+
         impl From<log::Level> for $wit_base_api::LogLevel {
             fn from(level: log::Level) -> Self {
                 match level {
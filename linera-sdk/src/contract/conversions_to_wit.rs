@@ -6,7 +6,8 @@
 use linera_base::{
     crypto::CryptoHash,
     data_types::{
-        Amount, ApplicationPermissions, Bytecode, Resources, SendMessageRequest, TimeDelta,
+        AllowanceLimits, Amount, ApplicationPermissions, Bytecode, Resources, SendMessageRequest,
+        SpendingCap, TimeDelta, Timestamp,
     },
     identifiers::{
         Account, AccountOwner, ApplicationId, ChainId, DataBlobHash, ModuleId, StreamName,
@@ -172,6 +173,32 @@ impl From<TimeDelta> for wit_contract_api::TimeDelta {
     }
 }
 
+impl From<Timestamp> for wit_contract_api::Timestamp {
+    fn from(timestamp: Timestamp) -> Self {
+        Self {
+            inner0: timestamp.micros(),
+        }
+    }
+}
+
+impl From<SpendingCap> for wit_contract_api::SpendingCap {
+    fn from(cap: SpendingCap) -> Self {
+        Self {
+            amount: cap.amount.into(),
+            period: cap.period.into(),
+        }
+    }
+}
+
+impl From<AllowanceLimits> for wit_contract_api::AllowanceLimits {
+    fn from(limits: AllowanceLimits) -> Self {
+        Self {
+            expiry: limits.expiry.map(Into::into),
+            period_cap: limits.period_cap.map(Into::into),
+        }
+    }
+}
+
 impl From<TimeoutConfig> for wit_contract_api::TimeoutConfig {
     fn from(config: TimeoutConfig) -> Self {
         let TimeoutConfig {
@@ -6,8 +6,8 @@
 use linera_base::{
     abi::{ContractAbi, ServiceAbi},
     data_types::{
-        Amount, ApplicationDescription, ApplicationPermissions, BlockHeight, Bytecode, Resources,
-        SendMessageRequest, Timestamp,
+        AllowanceLimits, Amount, ApplicationDescription, ApplicationPermissions, BlockHeight,
+        Bytecode, Resources, SendMessageRequest, Timestamp,
     },
     ensure, http,
     identifiers::{
@@ -270,6 +270,34 @@ where
         contract_wit::approve(owner.into(), spender.into(), amount.into())
     }
 
+    /// Approves `spender` to withdraw `amount` of native tokens from `owner`'s account, subject
+    /// to an expiry and a per-period spending cap.
+    pub fn approve_with_limits(
+        &mut self,
+        owner: AccountOwner,
+        spender: AccountOwner,
+        amount: Amount,
+        limits: AllowanceLimits,
+    ) {
+        contract_wit::approve_with_limits(
+            owner.into(),
+            spender.into(),
+            amount.into(),
+            limits.into(),
+        )
+    }
+
+    /// Approves a fee sponsorship on the current chain: blocks can then have the `sponsor`
+    /// pay their fees, up to `max_fees` in total and until the `expiry`.
+    pub fn approve_fee_sponsorship(
+        &mut self,
+        sponsor: AccountOwner,
+        max_fees: Amount,
+        expiry: Timestamp,
+    ) {
+        contract_wit::approve_fee_sponsorship(sponsor.into(), max_fees.into(), expiry.into())
+    }
+
     /// Transfers `amount` of native tokens from `owner` to `destination` using `spender`'s
     /// allowance.
     pub fn transfer_from(
@@ -437,6 +465,15 @@ where
         ApplicationId::from(application_id).with_abi::<Abi>()
     }
 
+    /// Switches an application created on the current chain to a new module, and calls the
+    /// `migrate` entrypoint of the new module.
+    ///
+    /// The current application must be the upgrade authority of the upgraded application,
+    /// which must not have been called yet in the current transaction.
+    pub fn upgrade_application(&mut self, application_id: ApplicationId, module_id: ModuleId) {
+        contract_wit::upgrade_application(application_id.into(), module_id.into())
+    }
+
     /// Creates a new data blob and returns its hash.
     pub fn create_data_blob(&mut self, bytes: Vec<u8>) -> DataBlobHash {
         let hash = contract_wit::create_data_blob(&bytes);
@@ -465,6 +502,27 @@ where
     pub fn validation_round(&mut self) -> Option<u32> {
         contract_wit::validation_round()
     }
+
+    /// Schedules an operation of this application to be executed on the current chain in
+    /// the first block whose timestamp is at least `timestamp`. Returns the ID of the
+    /// scheduled operation, which can be used to cancel it.
+    ///
+    /// The operation is executed without an authenticated owner.
+    pub fn schedule_operation_at(
+        &mut self,
+        timestamp: Timestamp,
+        operation: &<Application::Abi as ContractAbi>::Operation,
+    ) -> u32 {
+        let bytes = <Application::Abi as ContractAbi>::serialize_operation(operation)
+            .expect("Failed to serialize application operation");
+        contract_wit::schedule_operation_at(timestamp.into(), &bytes)
+    }
+
+    /// Cancels an operation previously scheduled by this application. Returns `false` if
+    /// there was no such operation, e.g. because it was already executed.
+    pub fn cancel_scheduled_operation(&mut self, id: u32) -> bool {
+        contract_wit::cancel_scheduled_operation(id)
+    }
 }
 
 /// A helper type that uses the builder pattern to configure how a message is sent, and then
@@ -13,8 +13,8 @@ use std::{
 use linera_base::{
     abi::{ContractAbi, ServiceAbi},
     data_types::{
-        Amount, ApplicationDescription, ApplicationPermissions, BlockHeight, Bytecode, Resources,
-        SendMessageRequest, Timestamp,
+        AllowanceLimits, AllowanceUsage, Amount, ApplicationDescription, ApplicationPermissions,
+        BlockHeight, Bytecode, FeeSponsorship, Resources, SendMessageRequest, Timestamp,
     },
     ensure, http,
     identifiers::{
@@ -44,6 +44,11 @@ struct ExpectedCreateApplicationCall {
     application_id: ApplicationId,
 }
 
+struct ExpectedUpgradeApplicationCall {
+    application_id: ApplicationId,
+    module_id: ModuleId,
+}
+
 struct ExpectedCreateDataBlobCall {
     bytes: Vec<u8>,
     blob_id: BlobId,
@@ -70,6 +75,7 @@ where
     chain_balance: Option<Amount>,
     owner_balances: Option<HashMap<AccountOwner, Amount>>,
     allowances: HashMap<OwnerSpender, Amount>,
+    allowance_limits: HashMap<OwnerSpender, AllowanceUsage>,
     chain_ownership: Option<ChainOwnership>,
     application_permissions: Option<ApplicationPermissions>,
     can_manage_chain: Option<bool>,
@@ -79,6 +85,7 @@ where
     created_events: BTreeMap<StreamName, Vec<Vec<u8>>>,
     events: BTreeMap<(ChainId, StreamName, u32), Vec<u8>>,
     claim_requests: Vec<ClaimRequest>,
+    fee_sponsorships: Vec<FeeSponsorship>,
     expected_service_queries: VecDeque<(ApplicationId, String, String)>,
     expected_http_requests: VecDeque<(http::Request, http::Response)>,
     expected_read_data_blob_requests: VecDeque<(DataBlobHash, Vec<u8>)>,
@@ -93,8 +100,11 @@ where
     )>,
     expected_publish_module_calls: VecDeque<ExpectedPublishModuleCall>,
     expected_create_application_calls: VecDeque<ExpectedCreateApplicationCall>,
+    expected_upgrade_application_calls: VecDeque<ExpectedUpgradeApplicationCall>,
     expected_create_data_blob_calls: VecDeque<ExpectedCreateDataBlobCall>,
     remaining_fuel: Option<u64>,
+    scheduled_operations: BTreeMap<u32, (Timestamp, <Application::Abi as ContractAbi>::Operation)>,
+    next_scheduled_operation_id: u32,
     key_value_store: KeyValueStore,
 }
 
@@ -130,6 +140,7 @@ where
             chain_balance: None,
             owner_balances: None,
             allowances: HashMap::new(),
+            allowance_limits: HashMap::new(),
             chain_ownership: None,
             application_permissions: None,
             can_manage_chain: None,
@@ -139,6 +150,7 @@ where
             created_events: BTreeMap::new(),
             events: BTreeMap::new(),
             claim_requests: Vec::new(),
+            fee_sponsorships: Vec::new(),
             expected_service_queries: VecDeque::new(),
             expected_http_requests: VecDeque::new(),
             expected_read_data_blob_requests: VecDeque::new(),
@@ -147,8 +159,11 @@ where
             expected_open_chain_calls: VecDeque::new(),
             expected_publish_module_calls: VecDeque::new(),
             expected_create_application_calls: VecDeque::new(),
+            expected_upgrade_application_calls: VecDeque::new(),
             expected_create_data_blob_calls: VecDeque::new(),
             remaining_fuel: None,
+            scheduled_operations: BTreeMap::new(),
+            next_scheduled_operation_id: 0,
             key_value_store: KeyValueStore::mock().to_mut(),
         }
     }
@@ -715,7 +730,49 @@ where
 
     /// Approves `spender` to withdraw `amount` of native tokens from `owner`'s account.
     pub fn approve(&mut self, owner: AccountOwner, spender: AccountOwner, amount: Amount) {
+        self.approve_with_limits(owner, spender, amount, AllowanceLimits::default());
+    }
+
+    /// Approves `spender` to withdraw `amount` of native tokens from `owner`'s account, subject
+    /// to an expiry and a per-period spending cap.
+    pub fn approve_with_limits(
+        &mut self,
+        owner: AccountOwner,
+        spender: AccountOwner,
+        amount: Amount,
+        limits: AllowanceLimits,
+    ) {
         self.set_allowance(owner, spender, amount);
+        let owner_spender = OwnerSpender::new(owner, spender);
+        if amount == Amount::ZERO || limits == AllowanceLimits::default() {
+            self.allowance_limits.remove(&owner_spender);
+        } else {
+            let usage =
+                AllowanceUsage::new(limits, self.system_time()).expect("Invalid allowance limits");
+            self.allowance_limits.insert(owner_spender, usage);
+        }
+    }
+
+    /// Approves a fee sponsorship on the current chain: blocks can then have the `sponsor`
+    /// pay their fees, up to `max_fees` in total and until the `expiry`.
+    pub fn approve_fee_sponsorship(
+        &mut self,
+        sponsor: AccountOwner,
+        max_fees: Amount,
+        expiry: Timestamp,
+    ) {
+        let chain_id = self.chain_id();
+        self.fee_sponsorships.push(FeeSponsorship {
+            sponsor,
+            chain_id,
+            max_fees,
+            expiry,
+        });
+    }
+
+    /// Returns the list of fee sponsorships approved so far.
+    pub fn fee_sponsorships(&self) -> &[FeeSponsorship] {
+        &self.fee_sponsorships
     }
 
     /// Transfers `amount` of native tokens from `owner` to `destination` using `spender`'s
@@ -736,8 +793,19 @@ where
             .try_sub(amount)
             .expect("Insufficient allowance for transfer_from");
 
+        if let Some(limits) = self.allowance_limits.get_mut(&owner_spender) {
+            let now = self.timestamp.expect(
+                "System time has not been mocked, \
+                please call `MockContractRuntime::set_system_time` first",
+            );
+            limits
+                .record_spending(now, amount)
+                .expect("Allowance limits exceeded for transfer_from");
+        }
+
         if remaining_allowance == Amount::ZERO {
             self.allowances.remove(&owner_spender);
+            self.allowance_limits.remove(&owner_spender);
         } else {
             self.allowances.insert(owner_spender, remaining_allowance);
         }
@@ -959,6 +1027,19 @@ where
             });
     }
 
+    /// Adds a new expected call to `upgrade_application`.
+    pub fn add_expected_upgrade_application_call(
+        &mut self,
+        application_id: ApplicationId,
+        module_id: ModuleId,
+    ) {
+        self.expected_upgrade_application_calls
+            .push_back(ExpectedUpgradeApplicationCall {
+                application_id,
+                module_id,
+            });
+    }
+
     /// Adds a new expected call to `create_data_blob`.
     pub fn add_expected_create_data_blob_call(&mut self, bytes: Vec<u8>, blob_id: BlobId) {
         self.expected_create_data_blob_calls
@@ -1028,6 +1109,19 @@ where
         application_id.with_abi::<Abi>()
     }
 
+    /// Switches an application created on the current chain to a new module.
+    pub fn upgrade_application(&mut self, application_id: ApplicationId, module_id: ModuleId) {
+        let ExpectedUpgradeApplicationCall {
+            application_id: expected_application_id,
+            module_id: expected_module_id,
+        } = self
+            .expected_upgrade_application_calls
+            .pop_front()
+            .expect("Unexpected upgrade_application call");
+        assert_eq!(application_id, expected_application_id);
+        assert_eq!(module_id, expected_module_id);
+    }
+
     /// Creates a new data blob and returns its hash.
     pub fn create_data_blob(&mut self, bytes: Vec<u8>) -> DataBlobHash {
         let ExpectedCreateDataBlobCall {
@@ -1247,6 +1341,38 @@ where
         self.round
     }
 
+    /// Schedules an operation of this application to be executed on the current chain in
+    /// the first block whose timestamp is at least `timestamp`. Returns the ID of the
+    /// scheduled operation.
+    pub fn schedule_operation_at(
+        &mut self,
+        timestamp: Timestamp,
+        operation: &<Application::Abi as ContractAbi>::Operation,
+    ) -> u32 {
+        let bytes = <Application::Abi as ContractAbi>::serialize_operation(operation)
+            .expect("Failed to serialize application operation");
+        let operation = <Application::Abi as ContractAbi>::deserialize_operation(bytes)
+            .expect("Failed to deserialize application operation");
+        let id = self.next_scheduled_operation_id;
+        self.next_scheduled_operation_id += 1;
+        self.scheduled_operations.insert(id, (timestamp, operation));
+        id
+    }
+
+    /// Cancels an operation previously scheduled by this application. Returns `false` if
+    /// there was no such operation.
+    pub fn cancel_scheduled_operation(&mut self, id: u32) -> bool {
+        self.scheduled_operations.remove(&id).is_some()
+    }
+
+    /// Returns the operations scheduled during the test that haven't been cancelled, with
+    /// their IDs and scheduled timestamps.
+    pub fn scheduled_operations(
+        &self,
+    ) -> &BTreeMap<u32, (Timestamp, <Application::Abi as ContractAbi>::Operation)> {
+        &self.scheduled_operations
+    }
+
     /// Configures the remaining fuel to return during the test.
     pub fn with_remaining_fuel(mut self, remaining_fuel: u64) -> Self {
         self.remaining_fuel = Some(remaining_fuel);
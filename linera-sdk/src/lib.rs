@@ -145,6 +145,14 @@ pub trait Contract: WithContractAbi + ContractAbi + Sized {
     /// is not summarized at a checkpoint is effectively closed.
     async fn summarize_events(&mut self, _updates: Vec<StreamUpdate>) {}
 
+    /// Migrates the application's state after an upgrade.
+    ///
+    /// This is called once on each chain, right after the application was switched to this
+    /// contract's module: on the application's creator chain when it is upgraded, and on
+    /// other chains when they adopt the upgrade. Intermediate upgrades are never skipped, so
+    /// the state was always last written by the previous module.
+    async fn migrate(&mut self) {}
+
     /// Finishes the execution of the current transaction.
     ///
     /// This is called once at the end of the transaction, to allow all applications that
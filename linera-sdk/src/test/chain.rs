@@ -27,7 +27,7 @@ use linera_chain::{types::ConfirmedBlockCertificate, ChainExecutionContext};
 use linera_core::{data_types::ChainInfoQuery, worker::WorkerError};
 use linera_execution::{
     system::{SystemOperation, SystemQuery, SystemResponse},
-    ExecutionError, Operation, Query, QueryOutcome, QueryResponse, ResourceTracker,
+    ExecutionError, Operation, Query, QueryOutcome, QueryResponse, ResourceTracker, WasmProfile,
 };
 use linera_storage::Storage as _;
 use serde::Serialize;
@@ -539,6 +539,23 @@ impl ActiveChain {
             .height
     }
 
+    /// Re-executes a transaction of the block at `height` on this microchain, profiling its
+    /// Wasm contracts.
+    ///
+    /// The profile can be written to a file with [`WasmProfile::folded_fuel`] and rendered
+    /// by flamegraph tools.
+    pub async fn profile_wasm_transaction(
+        &self,
+        height: BlockHeight,
+        transaction_index: u32,
+    ) -> WasmProfile {
+        Box::pin(
+            self.validator
+                .profile_wasm_transaction(self.id(), height, transaction_index),
+        )
+        .await
+    }
+
     /// Creates an application on this microchain, using the module referenced by `module_id`.
     ///
     /// Returns the [`ApplicationId`] of the created application.
@@ -570,6 +587,7 @@ impl ActiveChain {
                 parameters: parameters.clone(),
                 instantiation_argument,
                 required_application_ids: required_application_ids.clone(),
+                upgrade_authority: None,
             });
         }))
         .await;
@@ -614,6 +632,7 @@ impl ActiveChain {
                     parameters: parameters.clone(),
                     instantiation_argument,
                     required_application_ids: required_application_ids.clone(),
+                    upgrade_authority: None,
                 });
             })
             .await?;
@@ -24,7 +24,9 @@ pub use {
         data_types::MessageAction, test::HttpServer, ChainError, ChainExecutionContext,
     },
     linera_core::worker::WorkerError,
-    linera_execution::{ExecutionError, QueryOutcome, ResourceTracker, WasmExecutionError},
+    linera_execution::{
+        ExecutionError, QueryOutcome, ResourceTracker, WasmExecutionError, WasmProfile,
+    },
 };
 
 #[cfg(with_testing)]
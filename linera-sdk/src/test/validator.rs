@@ -15,8 +15,8 @@ use futures::{
 use linera_base::{
     crypto::{AccountSecretKey, CryptoHash, ValidatorKeypair, ValidatorSecretKey},
     data_types::{
-        Amount, ApplicationPermissions, Blob, BlobContent, ChainDescription, ChainOrigin, Epoch,
-        InitialChainConfig, NetworkDescription, Timestamp,
+        Amount, ApplicationPermissions, Blob, BlobContent, BlockHeight, ChainDescription,
+        ChainOrigin, Epoch, InitialChainConfig, NetworkDescription, Timestamp,
     },
     identifiers::{AccountOwner, ApplicationId, ChainId, ModuleId},
     ownership::ChainOwnership,
@@ -25,7 +25,7 @@ use linera_core::{worker::WorkerState, ChainWorkerConfig};
 use linera_execution::{
     committee::Committee,
     system::{AdminOperation, OpenChainConfig, SystemOperation},
-    ResourceControlPolicy, WasmRuntime,
+    ResourceControlPolicy, WasmProfile, WasmRuntime,
 };
 use linera_storage::{DbStorage, Storage, TestClock};
 use linera_views::memory::MemoryDatabase;
@@ -212,6 +212,24 @@ impl TestValidator {
         self.worker.clone()
     }
 
+    /// Re-executes a transaction of a confirmed block on the chain with the given ID,
+    /// profiling its Wasm contracts.
+    ///
+    /// Returns the fuel consumed and the time spent in host functions by each call stack.
+    pub async fn profile_wasm_transaction(
+        &self,
+        chain_id: ChainId,
+        height: BlockHeight,
+        transaction_index: u32,
+    ) -> WasmProfile {
+        Box::pin(
+            self.worker
+                .profile_wasm_transaction(chain_id, height, transaction_index),
+        )
+        .await
+        .expect("Failed to profile the transaction")
+    }
+
     /// Returns the [`TestClock`] of this validator.
     pub fn clock(&self) -> &TestClock {
         &self.clock
@@ -36,6 +36,11 @@ pub type CustomMapView<K, V> = linera_views::map_view::CustomMapView<ViewStorage
 /// WebAssembly-specific [`ViewStorageContext`].
 pub type CustomSetView<W> = linera_views::set_view::CustomSetView<ViewStorageContext, W>;
 
+/// An alias to [`linera_views::indexed_map_view::IndexedMapView`] that uses the
+/// WebAssembly-specific [`ViewStorageContext`].
+pub type IndexedMapView<K, V, X> =
+    linera_views::indexed_map_view::IndexedMapView<ViewStorageContext, K, V, X>;
+
 /// An alias to [`linera_views::log_view::LogView`] that uses the WebAssembly-specific
 /// [`ViewStorageContext`].
 pub type LogView<T> = linera_views::log_view::LogView<ViewStorageContext, T>;
@@ -59,3 +64,8 @@ pub type RegisterView<T> = linera_views::register_view::RegisterView<ViewStorage
 /// An alias to [`linera_views::set_view::SetView`] that uses the WebAssembly-specific
 /// [`ViewStorageContext`].
 pub type SetView<W> = linera_views::set_view::SetView<ViewStorageContext, W>;
+
+/// An alias to [`linera_views::sorted_set_view::SortedSetView`] that uses the
+/// WebAssembly-specific [`ViewStorageContext`].
+pub type SortedSetView<K, S> =
+    linera_views::sorted_set_view::SortedSetView<ViewStorageContext, K, S>;
@@ -15,7 +15,7 @@ use futures::FutureExt as _;
 use linera_views::{
     batch::Batch,
     memory::MemoryStore,
-    store::{ReadableKeyValueStore, WritableKeyValueStore},
+    store::{KeyRange, ReadableKeyValueStore, WritableKeyValueStore},
 };
 
 /// A mock [`KeyValueStore`] implementation using a [`MemoryStore`].
@@ -153,6 +153,18 @@ impl MockKeyValueStore {
         )
     }
 
+    /// Finds keys in the storage that start with `key_prefix` and whose suffixes are in the
+    /// `range`, returning a promise to retrieve the final value with [`find_keys_wait`].
+    pub(crate) fn find_keys_in_range_new(&self, key_prefix: &[u8], range: &KeyRange) -> u32 {
+        self.find_keys_promises.register(
+            self.store
+                .find_keys_in_range(key_prefix, range)
+                .now_or_never()
+                .expect("Memory store should never wait for anything")
+                .expect("Memory store should never fail"),
+        )
+    }
+
     /// Returns the keys found in storage by the respective [`find_keys_new`] call.
     pub(crate) fn find_keys_wait(&self, promise: u32) -> Vec<Vec<u8>> {
         self.find_keys_promises.take(promise)
@@ -170,6 +182,19 @@ impl MockKeyValueStore {
         )
     }
 
+    /// Finds key-value pairs in the storage in which the key starts with `key_prefix` and
+    /// its suffix is in the `range`, returning a promise to retrieve the final value with
+    /// [`find_key_values_wait`].
+    pub(crate) fn find_key_values_in_range_new(&self, key_prefix: &[u8], range: &KeyRange) -> u32 {
+        self.find_key_values_promises.register(
+            self.store
+                .find_key_values_in_range(key_prefix, range)
+                .now_or_never()
+                .expect("Memory store should never wait for anything")
+                .expect("Memory store should never fail"),
+        )
+    }
+
     /// Returns the key-value pairs found in storage by the respective [`find_key_values_new`]
     /// call.
     pub(crate) fn find_key_values_wait(&self, promise: u32) -> Vec<(Vec<u8>, Vec<u8>)> {
@@ -18,8 +18,8 @@ pub use linera_views::{
 pub use self::{
     aliases::{
         ByteCollectionView, ByteMapView, ByteSetView, CollectionView, CustomCollectionView,
-        CustomMapView, CustomSetView, LogView, MapView, QueueView, ReadGuardedView, RegisterView,
-        SetView,
+        CustomMapView, CustomSetView, IndexedMapView, LogView, MapView, QueueView, ReadGuardedView,
+        RegisterView, SetView, SortedSetView,
     },
     system_api::{KeyValueStore, ViewStorageContext},
 };
@@ -9,7 +9,7 @@ use std::sync::Arc;
 use linera_base::ensure;
 use linera_views::{
     batch::Batch,
-    store::{ReadableKeyValueStore, WithError, WritableKeyValueStore},
+    store::{KeyRange, ReadableKeyValueStore, WithError, WritableKeyValueStore},
 };
 use thiserror::Error;
 
@@ -176,6 +176,32 @@ impl ReadableKeyValueStore for KeyValueStore {
         let promise = self.wit_api.find_key_values_new(key_prefix);
         Ok(self.wit_api.find_key_values_wait(promise))
     }
+
+    async fn find_keys_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<Vec<u8>>, KeyValueStoreError> {
+        ensure!(
+            key_prefix.len() <= Self::MAX_KEY_SIZE,
+            KeyValueStoreError::KeyTooLong
+        );
+        let promise = self.wit_api.find_keys_in_range_new(key_prefix, range);
+        Ok(self.wit_api.find_keys_wait(promise))
+    }
+
+    async fn find_key_values_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, KeyValueStoreError> {
+        ensure!(
+            key_prefix.len() <= Self::MAX_KEY_SIZE,
+            KeyValueStoreError::KeyTooLong
+        );
+        let promise = self.wit_api.find_key_values_in_range_new(key_prefix, range);
+        Ok(self.wit_api.find_key_values_wait(promise))
+    }
 }
 
 impl WritableKeyValueStore for KeyValueStore {
@@ -308,6 +334,21 @@ impl WitInterface {
         }
     }
 
+    /// Creates a promise for finding the keys having a specified prefix and a suffix in a
+    /// range in the key-value store
+    fn find_keys_in_range_new(&self, key_prefix: &[u8], range: &KeyRange) -> u32 {
+        match self {
+            WitInterface::Contract => {
+                contract_wit::find_keys_in_range_new(key_prefix, &range.clone().into())
+            }
+            WitInterface::Service => {
+                service_wit::find_keys_in_range_new(key_prefix, &range.clone().into())
+            }
+            #[cfg(with_testing)]
+            WitInterface::Mock { store, .. } => store.find_keys_in_range_new(key_prefix, range),
+        }
+    }
+
     /// Creates a promise for finding the key/values having a specified prefix in the key-value store
     fn find_key_values_new(&self, key_prefix: &[u8]) -> u32 {
         match self {
@@ -328,6 +369,23 @@ impl WitInterface {
         }
     }
 
+    /// Creates a promise for finding the key/values having a specified prefix and a key suffix
+    /// in a range in the key-value store
+    fn find_key_values_in_range_new(&self, key_prefix: &[u8], range: &KeyRange) -> u32 {
+        match self {
+            WitInterface::Contract => {
+                contract_wit::find_key_values_in_range_new(key_prefix, &range.clone().into())
+            }
+            WitInterface::Service => {
+                service_wit::find_key_values_in_range_new(key_prefix, &range.clone().into())
+            }
+            #[cfg(with_testing)]
+            WitInterface::Mock { store, .. } => {
+                store.find_key_values_in_range_new(key_prefix, range)
+            }
+        }
+    }
+
     /// Calls the `write_batch` WIT function.
     fn write_batch(&self, batch: Batch) {
         match self {
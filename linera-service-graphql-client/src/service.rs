@@ -15,7 +15,10 @@
 use graphql_client::GraphQLQuery;
 use linera_base::{
     crypto::CryptoHash,
-    data_types::{Amount, Blob, BlockHeight, ChainDescription, OracleResponse, Round, Timestamp},
+    data_types::{
+        Amount, Blob, BlockHeight, ChainDescription, OracleResponse, Round, Timestamp,
+        VestingSchedule,
+    },
     identifiers::{AccountOwner, BlobId, ChainId, GenericApplicationId, StreamName},
 };
 use thiserror::Error;
@@ -175,7 +178,8 @@ pub enum ConversionError {
 #[cfg(not(target_arch = "wasm32"))]
 mod from {
     use linera_base::{
-        data_types::{ApplicationPermissions, Event, TimeDelta},
+        crypto::AccountSignature,
+        data_types::{ApplicationPermissions, Event, FeeSponsorship, TimeDelta},
         identifiers::{Account, ApplicationId as RealApplicationId, ModuleId, StreamId},
         ownership::{ChainOwnership, TimeoutConfig},
     };
@@ -422,6 +426,7 @@ mod from {
                     parameters,
                     instantiation_argument,
                     required_application_ids,
+                    upgrade_authority: create_application.upgrade_authority,
                 })
             }
             "Admin" => {
@@ -507,6 +512,102 @@ mod from {
                     next_index: stream.next_index as u32,
                 })
             }
+            "ExecuteScheduledOperation" => {
+                let id = system_op.scheduled_operation_id.ok_or_else(|| {
+                    ConversionError::UnexpectedCertificateType(
+                        "Missing scheduled_operation_id for ExecuteScheduledOperation operation"
+                            .to_string(),
+                    )
+                })?;
+                Ok(SystemOperation::ExecuteScheduledOperation { id: id as u32 })
+            }
+            "LockTokens" => {
+                let lock_tokens = system_op.lock_tokens.ok_or_else(|| {
+                    ConversionError::UnexpectedCertificateType(
+                        "Missing lock_tokens metadata for LockTokens operation".to_string(),
+                    )
+                })?;
+                Ok(SystemOperation::LockTokens {
+                    owner: lock_tokens.owner,
+                    beneficiary: lock_tokens.beneficiary,
+                    amount: lock_tokens.amount,
+                    schedule: VestingSchedule {
+                        start: lock_tokens.schedule.start,
+                        cliff: lock_tokens.schedule.cliff,
+                        end: lock_tokens.schedule.end,
+                    },
+                })
+            }
+            "UpgradeApplication" => {
+                let upgrade_application = system_op.upgrade_application.ok_or_else(|| {
+                    ConversionError::UnexpectedCertificateType(
+                        "Missing upgrade_application metadata for UpgradeApplication operation"
+                            .to_string(),
+                    )
+                })?;
+                let application_id: RealApplicationId =
+                    upgrade_application.application_id.parse().map_err(|_| {
+                        ConversionError::UnexpectedCertificateType(
+                            "Invalid application_id format".to_string(),
+                        )
+                    })?;
+                let module_id: ModuleId = upgrade_application.module_id.parse().map_err(|e| {
+                    ConversionError::UnexpectedCertificateType(format!(
+                        "Invalid module_id format: {e}"
+                    ))
+                })?;
+                Ok(SystemOperation::UpgradeApplication {
+                    application_id,
+                    module_id,
+                })
+            }
+            "ProcessApplicationUpgrade" => {
+                let application_id = system_op.process_application_upgrade.ok_or_else(|| {
+                    ConversionError::UnexpectedCertificateType(
+                        "Missing application ID for ProcessApplicationUpgrade operation"
+                            .to_string(),
+                    )
+                })?;
+                let application_id: RealApplicationId = application_id.parse().map_err(|_| {
+                    ConversionError::UnexpectedCertificateType(
+                        "Invalid application_id format".to_string(),
+                    )
+                })?;
+                Ok(SystemOperation::ProcessApplicationUpgrade { application_id })
+            }
+            "UseFeeSponsorship" => {
+                let use_fee_sponsorship = system_op.use_fee_sponsorship.ok_or_else(|| {
+                    ConversionError::UnexpectedCertificateType(
+                        "Missing use_fee_sponsorship metadata for UseFeeSponsorship operation"
+                            .to_string(),
+                    )
+                })?;
+                let sponsorship = use_fee_sponsorship.sponsorship;
+                let signature = use_fee_sponsorship
+                    .signature
+                    .map(|signature| {
+                        let bytes = hex::decode(signature).map_err(|e| {
+                            ConversionError::UnexpectedCertificateType(format!(
+                                "Invalid signature format: {e}"
+                            ))
+                        })?;
+                        AccountSignature::from_slice(&bytes).map_err(|e| {
+                            ConversionError::UnexpectedCertificateType(format!(
+                                "Invalid signature: {e}"
+                            ))
+                        })
+                    })
+                    .transpose()?;
+                Ok(SystemOperation::UseFeeSponsorship {
+                    sponsorship: FeeSponsorship {
+                        sponsor: sponsorship.sponsor,
+                        chain_id: sponsorship.chain_id,
+                        max_fees: sponsorship.max_fees,
+                        expiry: sponsorship.expiry,
+                    },
+                    signature,
+                })
+            }
             _ => Err(ConversionError::UnexpectedCertificateType(format!(
                 "Unknown system operation type: {}",
                 system_op.system_operation_type
@@ -21,7 +21,8 @@ use linera_client::{
 use linera_rpc::config::CrossChainConfig;
 
 use crate::{
-    cli::validator, query_subscription::parse_subscription_ttl, task_processor::parse_operator,
+    cli::validator, query_subscription::parse_subscription_ttl, storage::StorageConfig,
+    task_processor::parse_operator,
 };
 
 const DEFAULT_TOKENS_PER_CHAIN: Amount = Amount::from_millis(100);
@@ -283,6 +284,10 @@ pub struct ResourceControlPolicyOverrides {
     #[arg(long)]
     pub http_request: Option<Amount>,
 
+    /// Set the base price of scheduling an operation for a later block.
+    #[arg(long)]
+    pub scheduled_operation: Option<Amount>,
+
     /// Set the maximum amount of Wasm fuel per block.
     #[arg(long)]
     pub maximum_wasm_fuel_per_block: Option<u64>,
@@ -689,6 +694,10 @@ pub enum ClientCommand {
         #[arg(long)]
         http_request_price: Option<Amount>,
 
+        /// Set the base price of scheduling an operation for a later block.
+        #[arg(long)]
+        scheduled_operation_price: Option<Amount>,
+
         /// Set the maximum amount of Wasm fuel per block.
         /// (This will overwrite value from `--policy-config`)
         #[arg(long)]
@@ -1085,6 +1094,49 @@ pub enum ClientCommand {
         chain_id: Option<ChainId>,
     },
 
+    /// Estimate the resources and fees of executing operations in a new block, without
+    /// proposing it.
+    Estimate {
+        /// The operations to execute, as a JSON list.
+        #[arg(long)]
+        json_operations: Option<String>,
+
+        /// Path to a JSON file containing the list of operations to execute.
+        #[arg(long)]
+        json_operations_path: Option<PathBuf>,
+
+        /// Chain ID to execute the operations on. Defaults to the wallet's default chain.
+        #[arg(long)]
+        chain_id: Option<ChainId>,
+    },
+
+    /// Re-execute a transaction of a confirmed block, profiling its Wasm contracts, and
+    /// write the fuel consumed by each call stack in the folded-stack format read by
+    /// flamegraph tools.
+    ///
+    /// The chain is re-executed locally up to that block, without saving anything.
+    ProfileTransaction {
+        /// The height of the block.
+        height: BlockHeight,
+
+        /// The index of the transaction (an incoming bundle or an operation) in the block.
+        #[arg(long, default_value = "0")]
+        transaction_index: u32,
+
+        /// The chain of the block. Defaults to the wallet's default chain.
+        #[arg(long)]
+        chain_id: Option<ChainId>,
+
+        /// Where to write the folded stacks of the consumed fuel.
+        #[arg(long)]
+        output: PathBuf,
+
+        /// Where to also write the folded stacks of the time spent in host functions, in
+        /// microseconds.
+        #[arg(long)]
+        host_time_output: Option<PathBuf>,
+    },
+
     /// Show the contents of the wallet.
     #[command(subcommand)]
     Wallet(WalletCommand),
@@ -1174,7 +1226,9 @@ impl ClientCommand {
             | ClientCommand::Validator { .. }
             | ClientCommand::RetryPendingBlock { .. }
             | ClientCommand::QueryApplication { .. } => "client".into(),
-            ClientCommand::ExecuteOperation { .. } => "client".into(),
+            ClientCommand::ExecuteOperation { .. }
+            | ClientCommand::Estimate { .. }
+            | ClientCommand::ProfileTransaction { .. } => "client".into(),
             ClientCommand::Benchmark(BenchmarkCommand::Single { .. }) => "single-benchmark".into(),
             ClientCommand::Benchmark(BenchmarkCommand::Multi { .. }) => "multi-benchmark".into(),
             ClientCommand::Net { .. } => "net".into(),
@@ -1220,6 +1274,73 @@ pub enum DatabaseToolCommand {
 
     /// List the event IDs in the database
     ListEventIds,
+
+    /// Export the content of the namespace to an archive file. The data is not encrypted in
+    /// the archive, even if the storage is. The export fails if the namespace is written to
+    /// in the meantime, e.g. by a validator that is not idle
+    Export {
+        /// The path of the archive to create.
+        #[arg(long)]
+        output: PathBuf,
+    },
+
+    /// Import the content of an archive file into the namespace, which must be empty unless
+    /// the import is resumed
+    Import {
+        /// The path of the archive to import.
+        #[arg(long)]
+        input: PathBuf,
+
+        /// The file recording the progress of the import. If the import is interrupted,
+        /// running it again with the same file resumes it.
+        #[arg(long)]
+        checkpoint: Option<PathBuf>,
+    },
+
+    /// Copy the content of a namespace to another storage, e.g. to move from RocksDB to
+    /// ScyllaDB. The common storage options apply to both storages
+    Migrate {
+        /// The storage configuration of the namespace to copy.
+        #[arg(long)]
+        from: StorageConfig,
+
+        /// The storage configuration of the namespace receiving the copy, which must be empty
+        /// unless the migration is resumed.
+        #[arg(long)]
+        to: StorageConfig,
+
+        /// The file recording the progress of the migration. If the migration is
+        /// interrupted, running it again with the same file resumes it.
+        #[arg(long)]
+        checkpoint: Option<PathBuf>,
+
+        /// Skip the comparison of the chain states of both storages after the copy.
+        #[arg(long)]
+        skip_verification: bool,
+    },
+
+    /// Rewrite the encrypted data with the current encryption key, so that the previous
+    /// keys are no longer needed
+    #[cfg(feature = "encryption")]
+    RotateEncryptionKeys,
+
+    /// Remove the certificates and events of every chain that are no longer needed
+    /// below its latest checkpoint, keeping `--storage-pruning-retention` blocks
+    Prune {
+        /// Only report the space that pruning would reclaim, without removing anything.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Check that the state of every chain is consistent with the stored certificates and
+    /// blobs, and that no write was interrupted, e.g. after a crash. Print a JSON report and
+    /// exit with a non-zero code if some issues remain
+    Check {
+        /// Repair the issues that do not require any external data: resolve the pending
+        /// journals and rebuild the indices of the inboxes and outboxes.
+        #[arg(long)]
+        repair: bool,
+    },
 }
 
 #[expect(clippy::large_enum_variant)]
@@ -90,8 +90,17 @@ impl CommonCliOptions {
                     inner_storage_config,
                     namespace,
                 })
+            } else if #[cfg(feature = "redb")] {
+                let inner_storage_config = crate::storage::InnerStorageConfig::Redb {
+                    path: linera_wallet_json::paths::config_dir()?.join("wallet.redb"),
+                };
+                let namespace = linera_storage::DEFAULT_NAMESPACE.to_string();
+                Ok(StorageConfig {
+                    inner_storage_config,
+                    namespace,
+                })
             } else {
-                bail!("Cannot apply default storage because the feature 'rocksdb' was not selected");
+                bail!("Cannot apply default storage because neither the feature 'rocksdb' nor 'redb' was selected");
             }
         }
     }
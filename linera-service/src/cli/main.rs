@@ -23,7 +23,7 @@ pub static MALLOC_CONF: &[u8] = b"prof:true,prof_active:false,lg_prof_sample:19\
 mod options;
 use std::{
     collections::{BTreeMap, BTreeSet},
-    env,
+    env, io,
     path::PathBuf,
     process,
     sync::Arc,
@@ -83,7 +83,10 @@ use linera_service::{
     util,
 };
 use linera_storage::{DbStorage, Storage};
-use linera_views::store::{KeyValueDatabase, KeyValueStore};
+use linera_views::{
+    archive,
+    store::{KeyValueDatabase, KeyValueStore},
+};
 use options::Options;
 use serde_json::Value;
 use tempfile::NamedTempFile;
@@ -620,6 +623,7 @@ impl Runnable for Job {
                                             message_byte,
                                             service_as_oracle_query,
                                             http_request,
+                                            scheduled_operation,
                                             maximum_wasm_fuel_per_block,
                                             maximum_evm_fuel_per_block,
                                             maximum_service_oracle_execution_ms,
@@ -670,6 +674,8 @@ impl Runnable for Job {
                                             .unwrap_or(existing_policy.service_as_oracle_query),
                                         http_request: http_request
                                             .unwrap_or(existing_policy.http_request),
+                                        scheduled_operation: scheduled_operation
+                                            .unwrap_or(existing_policy.scheduled_operation),
                                         maximum_wasm_fuel_per_block: maximum_wasm_fuel_per_block
                                             .unwrap_or(existing_policy.maximum_wasm_fuel_per_block),
                                         maximum_evm_fuel_per_block: maximum_evm_fuel_per_block
@@ -1713,6 +1719,56 @@ impl Runnable for Job {
                 debug!("{:?}", certificate);
             }
 
+            Estimate {
+                json_operations,
+                json_operations_path,
+                chain_id,
+            } => {
+                let operations: Vec<Operation> =
+                    serde_json::from_slice(&read_json(json_operations, json_operations_path)?)
+                        .context("expected a JSON list of operations")?;
+                let context = options
+                    .create_client_context(storage, wallet, keystore)
+                    .await?;
+                let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
+                let chain_client = context.make_chain_client(chain_id).await?;
+                let resources = chain_client
+                    .estimate_block(operations, vec![])
+                    .await
+                    .context("Failed to execute the operations")?;
+                println!("{resources}");
+            }
+
+            ProfileTransaction {
+                height,
+                transaction_index,
+                chain_id,
+                output,
+                host_time_output,
+            } => {
+                let context = options
+                    .create_client_context(storage, wallet, keystore)
+                    .await?;
+                let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
+                let chain_client = context.make_chain_client(chain_id).await?;
+                let profile = chain_client
+                    .profile_wasm_transaction(height, transaction_index)
+                    .await
+                    .context("Failed to re-execute the transaction")?;
+                std::fs::write(&output, profile.folded_fuel())
+                    .with_context(|| format!("Failed to write {}", output.display()))?;
+                if let Some(host_time_output) = host_time_output {
+                    std::fs::write(&host_time_output, profile.folded_host_time()).with_context(
+                        || format!("Failed to write {}", host_time_output.display()),
+                    )?;
+                }
+                info!(
+                    "Profiled {} units of fuel, written to {}",
+                    profile.total_fuel(),
+                    output.display()
+                );
+            }
+
             Project(project_command) => match project_command {
                 ProjectCommand::PublishAndCreate {
                     path,
@@ -2117,6 +2173,77 @@ impl RunnableWithStore for DatabaseToolJob<'_> {
                     println!("{id}");
                 }
             }
+            DatabaseToolCommand::Export { output } => {
+                let database = D::connect(&config, &namespace).await?;
+                let writer = io::BufWriter::new(fs_err::File::create(output)?);
+                let summary = match archive::export_namespace(&database, writer).await {
+                    Ok(summary) => summary,
+                    Err(error) => {
+                        // The archive may be incomplete or inconsistent.
+                        fs_err::remove_file(output)?;
+                        return Err(error.into());
+                    }
+                };
+                info!(
+                    "Exported {} entries in {} partitions to {} in {} ms",
+                    summary.entries,
+                    summary.partitions,
+                    output.display(),
+                    start_time.elapsed().as_millis()
+                );
+            }
+            DatabaseToolCommand::Import { input, checkpoint } => {
+                // Checks the whole archive first, so that a corrupted one is not partially
+                // imported.
+                let reader = io::BufReader::new(fs_err::File::open(input)?);
+                archive::verify_archive(reader)?;
+                let progress = match checkpoint {
+                    Some(path) if path.exists() => {
+                        info!("Resuming the import from {}", path.display());
+                        Some(util::read_json(path)?)
+                    }
+                    _ => None,
+                };
+                let database = D::maybe_create_and_connect(&config, &namespace).await?;
+                let reader = io::BufReader::new(fs_err::File::open(input)?);
+                let summary = archive::import_namespace(&database, reader, progress, |progress| {
+                    if let Some(path) = checkpoint {
+                        // Writes to a temporary file first, so that an interruption
+                        // cannot leave a partially written checkpoint.
+                        let temporary_path = path.with_extension("tmp");
+                        let bytes = serde_json::to_vec(progress).map_err(io::Error::from)?;
+                        fs_err::write(&temporary_path, bytes)?;
+                        fs_err::rename(temporary_path, path)?;
+                    }
+                    Ok(())
+                })
+                .await?;
+                if let Some(path) = checkpoint {
+                    if path.exists() {
+                        fs_err::remove_file(path)?;
+                    }
+                }
+                info!(
+                    "Imported {} entries in {} partitions from {} in {} ms",
+                    summary.entries,
+                    summary.partitions,
+                    input.display(),
+                    start_time.elapsed().as_millis()
+                );
+            }
+            DatabaseToolCommand::Migrate { .. } => {
+                unreachable!("the migration does not run as a job against a single storage")
+            }
+            DatabaseToolCommand::Prune { .. } => {
+                unreachable!("the pruning does not run as a job against the store")
+            }
+            DatabaseToolCommand::Check { .. } => {
+                unreachable!("the check does not run as a job against the store")
+            }
+            #[cfg(feature = "encryption")]
+            DatabaseToolCommand::RotateEncryptionKeys => {
+                unreachable!("the rotation of the encryption keys does not run as a job")
+            }
         }
         Ok(0)
     }
@@ -2320,6 +2447,7 @@ async fn run(options: &Options) -> Result<i32, Error> {
             message_byte_price,
             service_as_oracle_query_price,
             http_request_price,
+            scheduled_operation_price,
             maximum_wasm_fuel_per_block,
             maximum_evm_fuel_per_block,
             maximum_service_oracle_execution_ms,
@@ -2363,6 +2491,8 @@ async fn run(options: &Options) -> Result<i32, Error> {
                 service_as_oracle_query: service_as_oracle_query_price
                     .unwrap_or(existing_policy.service_as_oracle_query),
                 http_request: http_request_price.unwrap_or(existing_policy.http_request),
+                scheduled_operation: scheduled_operation_price
+                    .unwrap_or(existing_policy.scheduled_operation),
                 maximum_wasm_fuel_per_block: maximum_wasm_fuel_per_block
                     .unwrap_or(existing_policy.maximum_wasm_fuel_per_block),
                 maximum_evm_fuel_per_block: maximum_evm_fuel_per_block
@@ -2582,6 +2712,62 @@ async fn run(options: &Options) -> Result<i32, Error> {
             }
         },
 
+        #[cfg(feature = "encryption")]
+        ClientCommand::Storage(DatabaseToolCommand::RotateEncryptionKeys) => {
+            let start_time = Instant::now();
+            options.rotate_encryption_keys().await?;
+            info!(
+                "Encryption keys rotated in {} ms",
+                start_time.elapsed().as_millis()
+            );
+            Ok(0)
+        }
+
+        ClientCommand::Storage(DatabaseToolCommand::Migrate {
+            from,
+            to,
+            checkpoint,
+            skip_verification,
+        }) => {
+            let start_time = Instant::now();
+            options
+                .migrate_storage(from, to, checkpoint.clone(), !skip_verification)
+                .await?;
+            info!(
+                "Storage migrated in {} ms",
+                start_time.elapsed().as_millis()
+            );
+            Ok(0)
+        }
+
+        ClientCommand::Storage(DatabaseToolCommand::Prune { dry_run }) => {
+            let start_time = Instant::now();
+            let report = options.prune_storage(*dry_run).await?;
+            info!(
+                "Pruning finished in {} ms",
+                start_time.elapsed().as_millis()
+            );
+            let verb = if *dry_run { "Can prune" } else { "Pruned" };
+            println!(
+                "{verb} {} certificates and {} events in {} chains, reclaiming {} bytes",
+                report.certificates, report.events, report.chains, report.bytes
+            );
+            Ok(0)
+        }
+
+        ClientCommand::Storage(DatabaseToolCommand::Check { repair }) => {
+            let start_time = Instant::now();
+            let report = options.check_storage(*repair).await?;
+            info!(
+                "Checked {} certificates in {} chains in {} ms",
+                report.certificates,
+                report.chains,
+                start_time.elapsed().as_millis()
+            );
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            Ok(if report.is_consistent() { 0 } else { 1 })
+        }
+
         ClientCommand::Storage(command) => {
             Ok(options.run_with_store(DatabaseToolJob(command)).await?)
         }
@@ -8,9 +8,13 @@ use linera_client::{client_context::ClientContext, config::GenesisConfig};
 use linera_execution::WithWasmDefault as _;
 use linera_service::{
     cli::{command::ClientCommand, common_options::CommonCliOptions},
-    storage::{Runnable, RunnableWithStore, StorageConfig},
+    storage::{
+        NamespaceMigration, PruningJob, Runnable, RunnableWithStore, StorageCheck, StorageConfig,
+        StoragePruning,
+    },
     Wallet,
 };
+use linera_storage::{PruningConfig, PruningReport, StorageCheckReport};
 use tracing::debug;
 
 #[derive(Clone, clap::Parser)]
@@ -106,12 +110,19 @@ impl Options {
         .await?)
     }
 
-    pub async fn run_with_storage<R: Runnable>(&self, job: R) -> Result<R::Output, Error> {
+    pub async fn run_with_storage<R: Runnable + Send>(&self, job: R) -> Result<R::Output, Error> {
         let storage_config = self.storage_config()?;
         debug!("Running command using storage configuration: {storage_config}");
         let store_config =
             storage_config.add_common_storage_options(&self.common.common_storage_options)?;
         let cache_sizes = self.common.common_storage_options.storage_cache_config();
+        let job = PruningJob {
+            config: self
+                .common
+                .common_storage_options
+                .background_pruning_config(),
+            job,
+        };
         let output = Box::pin(store_config.run_with_storage(
             self.common.wasm_runtime.with_wasm_default(),
             self.common.application_logs,
@@ -122,7 +133,10 @@ impl Options {
         Ok(output)
     }
 
-    pub async fn run_with_store<R: RunnableWithStore>(&self, job: R) -> Result<R::Output, Error> {
+    pub async fn run_with_store<R: RunnableWithStore + Send>(
+        &self,
+        job: R,
+    ) -> Result<R::Output, Error> {
         let storage_config = self.storage_config()?;
         debug!("Running command using storage configuration: {storage_config}");
         let store_config =
@@ -132,6 +146,69 @@ impl Options {
         Ok(output)
     }
 
+    #[cfg(feature = "encryption")]
+    pub async fn rotate_encryption_keys(&self) -> Result<(), Error> {
+        let storage_config = self.storage_config()?;
+        debug!("Rotating the encryption keys of storage: {storage_config}");
+        let store_config =
+            storage_config.add_common_storage_options(&self.common.common_storage_options)?;
+        let cache_sizes = self.common.common_storage_options.storage_cache_config();
+        Box::pin(store_config.rotate_encryption_keys(cache_sizes)).await?;
+        Ok(())
+    }
+
+    pub async fn migrate_storage(
+        &self,
+        from: &StorageConfig,
+        to: &StorageConfig,
+        checkpoint_path: Option<PathBuf>,
+        verify: bool,
+    ) -> Result<(), Error> {
+        debug!("Migrating storage {from} to storage {to}");
+        let common_storage_options = &self.common.common_storage_options;
+        let source = from.add_common_storage_options(common_storage_options)?;
+        let target = to.add_common_storage_options(common_storage_options)?;
+        let cache_sizes = common_storage_options.storage_cache_config();
+        let job = NamespaceMigration {
+            target,
+            checkpoint_path,
+            verify,
+        };
+        Box::pin(source.run_with_store(cache_sizes, job)).await?;
+        Ok(())
+    }
+
+    pub async fn prune_storage(&self, dry_run: bool) -> Result<PruningReport, Error> {
+        let storage_config = self.storage_config()?;
+        debug!("Pruning storage: {storage_config}");
+        let common_storage_options = &self.common.common_storage_options;
+        let store_config = storage_config.add_common_storage_options(common_storage_options)?;
+        let cache_sizes = common_storage_options.storage_cache_config();
+        // Unlike the background pruning, this prunes everything it can at once.
+        let job = StoragePruning {
+            config: PruningConfig {
+                max_blocks_per_pass: u64::MAX,
+                ..common_storage_options.pruning_config()
+            },
+            dry_run,
+        };
+        let report =
+            Box::pin(store_config.run_with_storage(None, false, cache_sizes, job)).await??;
+        Ok(report)
+    }
+
+    pub async fn check_storage(&self, repair: bool) -> Result<StorageCheckReport, Error> {
+        let storage_config = self.storage_config()?;
+        debug!("Checking storage: {storage_config}");
+        let common_storage_options = &self.common.common_storage_options;
+        let store_config = storage_config.add_common_storage_options(common_storage_options)?;
+        let cache_sizes = common_storage_options.storage_cache_config();
+        let job = StorageCheck { repair };
+        let report =
+            Box::pin(store_config.run_with_storage(None, false, cache_sizes, job)).await??;
+        Ok(report)
+    }
+
     pub async fn initialize_storage(&self) -> Result<(), Error> {
         let storage_config = self.storage_config()?;
         debug!("Initializing storage using configuration: {storage_config}");
@@ -1343,6 +1343,7 @@ impl ClientWrapper {
             message_byte,
             service_as_oracle_query,
             http_request,
+            scheduled_operation,
             maximum_wasm_fuel_per_block,
             maximum_evm_fuel_per_block,
             maximum_service_oracle_execution_ms,
@@ -1411,6 +1412,9 @@ impl ClientWrapper {
         if let Some(value) = http_request {
             command.args(["--http-request", &value.to_string()]);
         }
+        if let Some(value) = scheduled_operation {
+            command.args(["--scheduled-operation", &value.to_string()]);
+        }
         if let Some(value) = maximum_wasm_fuel_per_block {
             command.args(["--maximum-wasm-fuel-per-block", &value.to_string()]);
         }
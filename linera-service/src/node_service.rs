@@ -25,7 +25,7 @@ use linera_base::{
     crypto::{CryptoError, CryptoHash},
     data_types::{
         Amount, ApplicationDescription, ApplicationPermissions, BlockHeight, Bytecode, Epoch,
-        TimeDelta,
+        TimeDelta, VestingSchedule,
     },
     identifiers::{
         Account, AccountOwner, ApplicationId, ChainId, IndexAndEvent, ModuleId, StreamId,
@@ -46,7 +46,7 @@ use linera_core::{
 };
 use linera_execution::{
     committee::Committee, system::AdminOperation, Operation, Query, QueryOutcome, QueryResponse,
-    SystemOperation,
+    ResourceTracker, SystemOperation,
 };
 #[cfg(with_metrics)]
 use linera_metrics::monitoring_server;
@@ -397,6 +397,29 @@ where
         .await
     }
 
+    /// Transfers `amount` units of value from the given owner's account to the
+    /// beneficiary's account on the same chain, where they remain locked until they vest
+    /// according to the schedule.
+    async fn lock_tokens(
+        &self,
+        #[graphql(desc = "The chain on which the tokens are locked.")] chain_id: ChainId,
+        #[graphql(desc = "The account being debited on the chain.")] owner: AccountOwner,
+        #[graphql(desc = "The account on the chain receiving the locked tokens.")]
+        beneficiary: AccountOwner,
+        #[graphql(desc = "The amount being locked.")] amount: Amount,
+        #[graphql(desc = "When the tokens are released.")] schedule: VestingSchedule,
+    ) -> Result<CryptoHash, Error> {
+        self.apply_client_command(&chain_id, move |client| async move {
+            let result = client
+                .lock_tokens(owner, beneficiary, amount, schedule)
+                .await
+                .map_err(Error::from)
+                .map(|outcome| outcome.map(|certificate| certificate.hash()));
+            (result, client)
+        })
+        .await
+    }
+
     /// Claims `amount` units of value from the given owner's account in the remote
     /// `target` chain. Depending on its configuration, the `target` chain may refuse to
     /// process the message.
@@ -768,6 +791,7 @@ and no system operations."
     }
 
     /// Creates a new application.
+    #[expect(clippy::too_many_arguments)]
     async fn create_application(
         &self,
         #[graphql(desc = "The chain paying for the creation of the application")] chain_id: ChainId,
@@ -780,6 +804,8 @@ and no system operations."
         instantiation_argument: String,
         #[graphql(desc = "The dependencies of the application being created")]
         required_application_ids: Vec<ApplicationId>,
+        #[graphql(desc = "The owner or application allowed to upgrade the application, if any")]
+        upgrade_authority: Option<AccountOwner>,
     ) -> Result<ApplicationId, Error> {
         self.apply_client_command(&chain_id, move |client| {
             let parameters = parameters.as_bytes().to_vec();
@@ -787,11 +813,12 @@ and no system operations."
             let required_application_ids = required_application_ids.clone();
             async move {
                 let result = client
-                    .create_application_untyped(
+                    .create_upgradeable_application_untyped(
                         module_id,
                         parameters,
                         instantiation_argument,
                         required_application_ids,
+                        upgrade_authority,
                     )
                     .await
                     .map_err(Error::from)
@@ -801,6 +828,41 @@ and no system operations."
         })
         .await
     }
+
+    /// Upgrades an application created on this chain to a different module.
+    async fn upgrade_application(
+        &self,
+        #[graphql(desc = "The creator chain of the application")] chain_id: ChainId,
+        #[graphql(desc = "The application being upgraded")] application_id: ApplicationId,
+        #[graphql(desc = "The module ID the application is upgraded to")] module_id: ModuleId,
+    ) -> Result<CryptoHash, Error> {
+        self.apply_client_command(&chain_id, move |client| async move {
+            let result = client
+                .upgrade_application(application_id, module_id)
+                .await
+                .map_err(Error::from)
+                .map(|outcome| outcome.map(|certificate| certificate.hash()));
+            (result, client)
+        })
+        .await
+    }
+
+    /// Adopts the next upgrade of an application published by its creator chain.
+    async fn process_application_upgrade(
+        &self,
+        #[graphql(desc = "The chain adopting the upgrade")] chain_id: ChainId,
+        #[graphql(desc = "The application being upgraded")] application_id: ApplicationId,
+    ) -> Result<CryptoHash, Error> {
+        self.apply_client_command(&chain_id, move |client| async move {
+            let result = client
+                .process_application_upgrade(application_id)
+                .await
+                .map_err(Error::from)
+                .map(|outcome| outcome.map(|certificate| certificate.hash()));
+            (result, client)
+        })
+        .await
+    }
 }
 
 #[async_graphql::Object(cache_control(no_cache))]
@@ -930,6 +992,51 @@ where
         Ok(values)
     }
 
+    /// Executes the given operations in a new block on a throwaway copy of the chain
+    /// state, without proposing it, and returns the resources and fees it would consume.
+    async fn estimate_gas(
+        &self,
+        #[graphql(desc = "The chain on which the operations would be executed")] chain_id: ChainId,
+        #[graphql(desc = "The JSON serializations of the operations to execute")] operations: Vec<
+            String,
+        >,
+    ) -> Result<GasEstimate, Error> {
+        let operations = operations
+            .iter()
+            .map(|operation| serde_json::from_str::<Operation>(operation))
+            .collect::<Result<Vec<_>, _>>()?;
+        let client = self
+            .context
+            .lock()
+            .await
+            .make_chain_client(chain_id)
+            .await?;
+        let resources = client.estimate_block(operations, vec![]).await?;
+        Ok(GasEstimate::from(resources))
+    }
+
+    /// Re-executes the chain up to one of its confirmed blocks, without saving anything,
+    /// and returns the traces of the EVM executions of a transaction in that block, as a
+    /// JSON list. Each trace has the call tree in the format of geth's `callTracer`, the
+    /// executed opcodes and the storage reads and writes.
+    async fn evm_trace(
+        &self,
+        #[graphql(desc = "The chain of the block")] chain_id: ChainId,
+        #[graphql(desc = "The height of the block")] height: BlockHeight,
+        #[graphql(desc = "The index of the transaction in the block")] transaction_index: u32,
+    ) -> Result<RawJson, Error> {
+        let client = self
+            .context
+            .lock()
+            .await
+            .make_chain_client(chain_id)
+            .await?;
+        let traces = client
+            .trace_evm_transaction(height, transaction_index)
+            .await?;
+        Ok(RawJson(serde_json::to_string(&traces)?))
+    }
+
     /// Returns the version information on this node service.
     async fn version(&self) -> linera_version::VersionInfo {
         linera_version::VersionInfo::default()
@@ -1047,6 +1154,66 @@ impl ApplicationOverview {
     }
 }
 
+/// The resources and fees a block would consume, as estimated by executing it locally.
+#[derive(SimpleObject)]
+pub struct GasEstimate {
+    /// The size of the block.
+    block_size: u64,
+    /// The Wasm fuel used.
+    wasm_fuel: u64,
+    /// The EVM fuel used.
+    evm_fuel: u64,
+    /// The number of storage read operations.
+    read_operations: u32,
+    /// The number of storage write operations.
+    write_operations: u32,
+    /// The number of bytes read from storage.
+    bytes_read: u64,
+    /// The number of bytes written to storage.
+    bytes_written: u64,
+    /// The number of blobs read.
+    blobs_read: u32,
+    /// The number of blobs published.
+    blobs_published: u32,
+    /// The number of blob bytes read.
+    blob_bytes_read: u64,
+    /// The number of blob bytes published.
+    blob_bytes_published: u64,
+    /// The number of events published.
+    events_published: u32,
+    /// The number of outgoing messages created.
+    messages: u32,
+    /// The total size of the outgoing user messages.
+    message_bytes: u64,
+    /// The amount allocated to message grants.
+    grants: Amount,
+    /// The total fee, including message grants, under the current policy.
+    fee: Amount,
+}
+
+impl From<ResourceTracker> for GasEstimate {
+    fn from(tracker: ResourceTracker) -> Self {
+        Self {
+            block_size: tracker.block_size,
+            wasm_fuel: tracker.wasm_fuel,
+            evm_fuel: tracker.evm_fuel,
+            read_operations: tracker.read_operations,
+            write_operations: tracker.write_operations,
+            bytes_read: tracker.bytes_read,
+            bytes_written: tracker.bytes_written,
+            blobs_read: tracker.blobs_read,
+            blobs_published: tracker.blobs_published,
+            blob_bytes_read: tracker.blob_bytes_read,
+            blob_bytes_published: tracker.blob_bytes_published,
+            events_published: tracker.events_published,
+            messages: tracker.messages,
+            message_bytes: tracker.message_bytes,
+            grants: tracker.grants,
+            fee: tracker.fees,
+        }
+    }
+}
+
 /// Schema type that can be either full (with mutations) or read-only.
 pub enum NodeServiceSchema<C>
 where
@@ -0,0 +1,110 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Streaming of the certificates of a chain out of the proxy's storage.
+
+use std::future::Future;
+
+use futures::{stream, Stream, TryStreamExt as _};
+use linera_base::data_types::BlockHeight;
+use linera_sdk::views::ViewError;
+
+/// The number of certificates read from storage at once while streaming a chain's history.
+const BATCH_SIZE: u64 = 100;
+
+/// Streams the certificates at the heights from `from_height` up to `to_height` (excluded),
+/// stopping at the first height for which `read_batch` finds none.
+///
+/// Batches are read lazily: the next one is only read once the previous one was consumed, so a
+/// slow client holds back the storage reads instead of having them buffered in memory.
+pub fn chain_history<T, F, Fut>(
+    from_height: BlockHeight,
+    to_height: BlockHeight,
+    read_batch: F,
+) -> impl Stream<Item = Result<T, ViewError>>
+where
+    F: Fn(Vec<BlockHeight>) -> Fut,
+    Fut: Future<Output = Result<Vec<Option<T>>, ViewError>>,
+{
+    stream::try_unfold(Some(from_height), move |next_height| {
+        let start = next_height.filter(|height| *height < to_height);
+        let end =
+            start.map(|start| BlockHeight(to_height.0.min(start.0.saturating_add(BATCH_SIZE))));
+        let batch = start
+            .zip(end)
+            .map(|(start, end)| read_batch((start.0..end.0).map(BlockHeight).collect()));
+        async move {
+            let (Some(batch), Some(end)) = (batch, end) else {
+                return Ok::<_, ViewError>(None);
+            };
+            let certificates = batch.await?;
+            let complete = certificates.iter().all(Option::is_some);
+            let certificates = certificates
+                .into_iter()
+                .map_while(|certificate| certificate.map(Ok))
+                .collect::<Vec<_>>();
+            Ok(Some((stream::iter(certificates), complete.then_some(end))))
+        }
+    })
+    .try_flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use futures::{StreamExt as _, TryStreamExt as _};
+
+    use super::*;
+
+    /// Streams from a fake storage holding the heights below `stored`, and counts the reads.
+    fn history(
+        stored: u64,
+        from_height: u64,
+        to_height: u64,
+        reads: &AtomicUsize,
+    ) -> impl Stream<Item = Result<u64, ViewError>> + '_ {
+        chain_history(
+            BlockHeight(from_height),
+            BlockHeight(to_height),
+            move |heights| {
+                reads.fetch_add(1, Ordering::Relaxed);
+                let batch = heights
+                    .into_iter()
+                    .map(|height| (height.0 < stored).then_some(height.0))
+                    .collect();
+                async move { Ok(batch) }
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_chain_history_stops_at_missing_height() {
+        let reads = AtomicUsize::new(0);
+        let heights = history(150, 20, 300, &reads)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(heights, (20..150).collect::<Vec<_>>());
+        assert_eq!(reads.load(Ordering::Relaxed), 2);
+
+        let reads = AtomicUsize::new(0);
+        let heights = history(1000, 20, 300, &reads)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(heights, (20..300).collect::<Vec<_>>());
+        assert_eq!(reads.load(Ordering::Relaxed), 3);
+    }
+
+    #[tokio::test]
+    async fn test_chain_history_reads_lazily() {
+        let reads = AtomicUsize::new(0);
+        let mut stream = Box::pin(history(1000, 0, 1000, &reads));
+        assert_eq!(reads.load(Ordering::Relaxed), 0);
+        for expected in 0..=BATCH_SIZE {
+            assert_eq!(stream.next().await.unwrap().unwrap(), expected);
+        }
+        assert_eq!(reads.load(Ordering::Relaxed), 2);
+    }
+}
@@ -17,13 +17,15 @@ use std::{
 
 use anyhow::Result;
 use async_trait::async_trait;
-use futures::{future::BoxFuture, FutureExt as _};
+use futures::{future::BoxFuture, FutureExt as _, TryStreamExt as _};
 use linera_base::{
     data_types::Epoch,
     identifiers::{ChainId, StreamId},
 };
 use linera_core::{
-    data_types::CertificatesByHeightRequest, notifier::ChannelNotifier, JoinSetExt as _,
+    data_types::{CertificatesByHeightRequest, ChainHistoryRequest},
+    notifier::ChannelNotifier,
+    JoinSetExt as _,
 };
 use linera_execution::system::EPOCH_STREAM_NAME;
 #[cfg(with_metrics)]
@@ -52,8 +54,9 @@ use linera_rpc::{
             SubscriptionRequest, VersionInfo,
         },
         pool::GrpcConnectionPool,
+        tls::InternalTls,
         GrpcProtoConversionError, GrpcProxyable, GRPC_CHUNKED_MESSAGE_FILL_LIMIT,
-        GRPC_MAX_MESSAGE_SIZE,
+        GRPC_MAX_MESSAGE_SIZE, RETRY_AFTER_METADATA_KEY,
     },
 };
 use linera_sdk::{linera_base_types::Blob, views::ViewError};
@@ -63,13 +66,22 @@ use tokio::{select, task::JoinSet};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use tokio_util::sync::CancellationToken;
 use tonic::{
-    transport::{Channel, Identity, Server, ServerTlsConfig},
+    metadata::MetadataValue,
+    transport::{
+        server::{TcpConnectInfo, TlsConnectInfo},
+        Channel, Identity, Server, ServerTlsConfig,
+    },
     Request, Response, Status,
 };
 use tonic_web::GrpcWebLayer;
 use tower::{builder::ServiceBuilder, Layer, Service};
 use tracing::{debug, info, instrument, Instrument as _, Level};
 
+use crate::{
+    chain_history::chain_history,
+    rate_limit::{RateLimited, RateLimiter},
+};
+
 #[cfg(with_metrics)]
 pub(crate) mod metrics {
     use linera_base::prometheus_util::{
@@ -116,6 +128,14 @@ pub(crate) mod metrics {
                 "Proxy request error",
                 &[METHOD_NAME_LABEL, TRAFFIC_TYPE_LABEL, ERROR_TYPE_LABEL],
             );
+
+        /// Requests rejected by the rate limiter, by the limit they exceeded.
+        pub static PROXY_RATE_LIMITED: IntCounterVec =
+            register_int_counter_vec(
+                "proxy_rate_limited",
+                "Proxy requests rejected by the rate limiter",
+                &[METHOD_NAME_LABEL, "limit"],
+            );
     }
 }
 
@@ -216,11 +236,97 @@ where
     }
 }
 
+/// Rejects the requests exceeding the rate limits of their client, before they are decoded.
+#[derive(Clone)]
+pub struct RateLimitLayer(Option<Arc<RateLimiter>>);
+
+#[derive(Clone)]
+pub struct RateLimitService<T> {
+    service: T,
+    limiter: Option<Arc<RateLimiter>>,
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        RateLimitService {
+            service,
+            limiter: self.0.clone(),
+        }
+    }
+}
+
+impl<S, B, ResponseBody> Service<http::Request<B>> for RateLimitService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<ResponseBody>> + Send,
+    S::Future: Send + 'static,
+    S::Error: Send + 'static,
+    ResponseBody: Default + Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<S::Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        if let Some(limiter) = &self.limiter {
+            let method = linera_rpc::grpc::extract_grpc_method_name(request.uri().path());
+            let extensions = request.extensions();
+            let remote_address = extensions
+                .get::<TcpConnectInfo>()
+                .or_else(|| {
+                    extensions
+                        .get::<TlsConnectInfo<TcpConnectInfo>>()
+                        .map(TlsConnectInfo::get_ref)
+                })
+                .and_then(TcpConnectInfo::remote_addr);
+            if let Err(rejection) =
+                limiter.check_client(remote_address.map(|address| address.ip()), method)
+            {
+                let response = rate_limited_status(method, rejection).into_http();
+                return futures::future::ready(Ok(response)).boxed();
+            }
+        }
+        self.service.call(request).boxed()
+    }
+}
+
+/// Returns the status of a request rejected by the rate limiter, telling the client when to
+/// retry.
+fn rate_limited_status(
+    #[cfg_attr(not(with_metrics), allow(unused_variables))] method: &str,
+    rejection: RateLimited,
+) -> Status {
+    #[cfg(with_metrics)]
+    metrics::PROXY_RATE_LIMITED
+        .with_label_values(&[method, rejection.limit.as_str()])
+        .inc();
+    let retry_after_ms = u64::try_from(rejection.retry_after.as_micros().div_ceil(1000))
+        .unwrap_or(u64::MAX)
+        .max(1);
+    let mut status = Status::resource_exhausted(format!(
+        "rate limit per {} exceeded; retry in {retry_after_ms} ms",
+        rejection.limit
+    ));
+    status.metadata_mut().insert(
+        RETRY_AFTER_METADATA_KEY,
+        MetadataValue::from(retry_after_ms),
+    );
+    status
+}
+
 #[derive(Clone)]
 pub struct GrpcProxy<S>(Arc<GrpcProxyInner<S>>);
 
 struct GrpcProxyInner<S> {
     internal_config: ValidatorInternalNetworkConfig,
+    /// The certificates authenticating the links with the shards, if the internal protocol is
+    /// `grpcs`.
+    internal_tls: Option<InternalTls>,
     worker_connection_pool: GrpcConnectionPool,
     /// Connections to the other validators, used to carry requests on behalf of this validator's
     /// shards, which have no route to the internet of their own. Pooled, so a peer costs one
@@ -228,6 +334,8 @@ struct GrpcProxyInner<S> {
     peer_connection_pool: GrpcConnectionPool,
     notifier: ChannelNotifier<Result<Notification, Status>>,
     tls: TlsConfig,
+    /// The limits on the requests of the clients, if any.
+    rate_limiter: Option<Arc<RateLimiter>>,
     storage: S,
     id: usize,
     /// The validator addresses this proxy will relay to, and the next epoch it has yet to learn.
@@ -249,11 +357,14 @@ impl<S> GrpcProxy<S>
 where
     S: Storage + Clone + Send + Sync + 'static,
 {
+    #[expect(clippy::too_many_arguments)]
     pub fn new(
         internal_config: ValidatorInternalNetworkConfig,
+        internal_tls: Option<InternalTls>,
         connect_timeout: Duration,
         timeout: Duration,
         tls: TlsConfig,
+        rate_limiter: Option<RateLimiter>,
         storage: S,
         id: usize,
     ) -> Self {
@@ -261,12 +372,15 @@ where
             internal_config,
             worker_connection_pool: GrpcConnectionPool::default()
                 .with_connect_timeout(connect_timeout)
-                .with_timeout(timeout),
+                .with_timeout(timeout)
+                .with_internal_tls(internal_tls.clone()),
+            internal_tls,
             peer_connection_pool: GrpcConnectionPool::default()
                 .with_connect_timeout(connect_timeout)
                 .with_timeout(timeout),
             notifier: ChannelNotifier::default(),
             tls,
+            rate_limiter: rate_limiter.map(Arc::new),
             storage,
             id,
             relay_destinations: Arc::default(),
@@ -498,13 +612,26 @@ where
         health_reporter
             .set_serving::<ValidatorNodeServer<GrpcProxy<S>>>()
             .await;
-        let internal_server = join_set.spawn_task(
-            Server::builder()
-                .add_service(self.as_notifier_service())
-                .add_service(self.as_validator_relay())
-                .serve(self.internal_address())
-                .in_current_span(),
-        );
+        let internal_router = Server::builder()
+            .add_service(self.as_notifier_service())
+            .add_service(self.as_validator_relay());
+        // With internal TLS, only the shards of this validator can send notifications or
+        // have requests relayed.
+        let internal_server = match &self.0.internal_tls {
+            Some(tls) => {
+                let incoming = tls.incoming(self.internal_address()).await?;
+                join_set.spawn_task(
+                    internal_router
+                        .serve_with_incoming(incoming)
+                        .in_current_span(),
+                )
+            }
+            None => join_set.spawn_task(
+                internal_router
+                    .serve(self.internal_address())
+                    .in_current_span(),
+            ),
+        };
         let reflection_service = tonic_reflection::server::Builder::configure()
             .register_encoded_file_descriptor_set(linera_rpc::FILE_DESCRIPTOR_SET)
             .build_v1()?;
@@ -536,6 +663,7 @@ where
                     tower_http::cors::CorsLayer::permissive(),
                 )
                 .layer(GrpcWebLayer::new())
+                .layer(RateLimitLayer(self.0.rate_limiter.clone()))
                 .accept_http1(true)
                 .add_service(health_service)
                 .add_service(self.as_validator_node())
@@ -569,12 +697,17 @@ where
     fn worker_client<R>(
         &self,
         request: Request<R>,
+        method: &str,
     ) -> Result<(ValidatorWorkerClient<Channel>, R), Status>
     where
         R: Debug + GrpcProxyable,
     {
         debug!("proxying request from {:?}", request.remote_addr());
+        let remote_address = request.remote_addr();
         let inner = request.into_inner();
+        if let Some(chain_id) = inner.chain_id() {
+            self.check_chain_rate_limit(remote_address, method, chain_id)?;
+        }
         let shard = self
             .shard_for(&inner)
             .ok_or_else(|| Status::not_found("could not find shard for message"))?;
@@ -584,6 +717,22 @@ where
         Ok((client, inner))
     }
 
+    /// Rejects a request of the client at `remote_address` about `chain_id` if it exceeds the
+    /// client's rate limit for the chain.
+    fn check_chain_rate_limit(
+        &self,
+        remote_address: Option<SocketAddr>,
+        method: &str,
+        chain_id: ChainId,
+    ) -> Result<(), Status> {
+        let Some(limiter) = &self.0.rate_limiter else {
+            return Ok(());
+        };
+        limiter
+            .check_chain(remote_address.map(|address| address.ip()), method, chain_id)
+            .map_err(|rejection| rate_limited_status(method, rejection))
+    }
+
     /// Creates a tonic::Request with OpenTelemetry context injected for forwarding.
     ///
     /// Gets the context from the current tracing span (which has the parent set by
@@ -610,6 +759,7 @@ where
             | ViewError::InconsistentEntries
             | ViewError::PostLoadValuesError
             | ViewError::HasPendingChanges
+            | ViewError::UnknownIndex(_)
             | ViewError::IoError(_) => Status::internal(err.to_string()),
             ViewError::MalformedContent(_) => Status::invalid_argument(err.to_string()),
             ViewError::KeyTooLong | ViewError::ArithmeticError(_) => {
@@ -632,13 +782,15 @@ where
     type SubscribeStream = UnboundedReceiverStream<Result<Notification, Status>>;
     type DownloadBlobsStream =
         std::pin::Pin<Box<dyn futures::Stream<Item = Result<BlobContent, Status>> + Send>>;
+    type StreamChainHistoryStream =
+        std::pin::Pin<Box<dyn futures::Stream<Item = Result<RawCertificate, Status>> + Send>>;
 
     #[instrument(skip_all, err(Display), fields(method = "handle_block_proposal"))]
     async fn handle_block_proposal(
         &self,
         request: Request<BlockProposal>,
     ) -> Result<Response<ChainInfoResult>, Status> {
-        let (mut client, inner) = self.worker_client(request)?;
+        let (mut client, inner) = self.worker_client(request, "HandleBlockProposal")?;
         client
             .handle_block_proposal(Self::create_forwarding_request(inner))
             .await
@@ -649,7 +801,7 @@ where
         &self,
         request: Request<LiteCertificate>,
     ) -> Result<Response<ChainInfoResult>, Status> {
-        let (mut client, inner) = self.worker_client(request)?;
+        let (mut client, inner) = self.worker_client(request, "HandleLiteCertificate")?;
         client
             .handle_lite_certificate(Self::create_forwarding_request(inner))
             .await
@@ -664,7 +816,7 @@ where
         &self,
         request: Request<api::HandleConfirmedCertificateRequest>,
     ) -> Result<Response<ChainInfoResult>, Status> {
-        let (mut client, inner) = self.worker_client(request)?;
+        let (mut client, inner) = self.worker_client(request, "HandleConfirmedCertificate")?;
         client
             .handle_confirmed_certificate(Self::create_forwarding_request(inner))
             .await
@@ -679,7 +831,7 @@ where
         &self,
         request: Request<api::HandleValidatedCertificateRequest>,
     ) -> Result<Response<ChainInfoResult>, Status> {
-        let (mut client, inner) = self.worker_client(request)?;
+        let (mut client, inner) = self.worker_client(request, "HandleValidatedCertificate")?;
         client
             .handle_validated_certificate(Self::create_forwarding_request(inner))
             .await
@@ -690,7 +842,7 @@ where
         &self,
         request: Request<api::HandleTimeoutCertificateRequest>,
     ) -> Result<Response<ChainInfoResult>, Status> {
-        let (mut client, inner) = self.worker_client(request)?;
+        let (mut client, inner) = self.worker_client(request, "HandleTimeoutCertificate")?;
         client
             .handle_timeout_certificate(Self::create_forwarding_request(inner))
             .await
@@ -701,7 +853,7 @@ where
         &self,
         request: Request<api::ChainInfoQuery>,
     ) -> Result<Response<ChainInfoResult>, Status> {
-        let (mut client, inner) = self.worker_client(request)?;
+        let (mut client, inner) = self.worker_client(request, "HandleChainInfoQuery")?;
         client
             .handle_chain_info_query(Self::create_forwarding_request(inner))
             .await
@@ -826,7 +978,7 @@ where
         &self,
         request: Request<PendingBlobRequest>,
     ) -> Result<Response<PendingBlobResult>, Status> {
-        let (mut client, inner) = self.worker_client(request)?;
+        let (mut client, inner) = self.worker_client(request, "DownloadPendingBlob")?;
         client.download_pending_blob(inner).await
     }
 
@@ -835,7 +987,7 @@ where
         &self,
         request: Request<HandlePendingBlobRequest>,
     ) -> Result<Response<ChainInfoResult>, Status> {
-        let (mut client, inner) = self.worker_client(request)?;
+        let (mut client, inner) = self.worker_client(request, "HandlePendingBlob")?;
         client.handle_pending_blob(inner).await
     }
 
@@ -911,9 +1063,11 @@ where
         &self,
         request: Request<api::DownloadCertificatesByHeightsRequest>,
     ) -> Result<Response<CertificatesBatchResponse>, Status> {
+        let remote_address = request.remote_addr();
         let original_request: CertificatesByHeightRequest = request.into_inner().try_into()?;
         let chain_id = original_request.chain_id;
         let heights = original_request.heights;
+        self.check_chain_rate_limit(remote_address, "DownloadCertificatesByHeights", chain_id)?;
 
         let certificates_by_height: Vec<_> = self
             .0
@@ -944,9 +1098,11 @@ where
         &self,
         request: Request<api::DownloadCertificatesByHeightsRequest>,
     ) -> Result<Response<api::RawCertificatesBatch>, Status> {
+        let remote_address = request.remote_addr();
         let original_request: CertificatesByHeightRequest = request.into_inner().try_into()?;
         let chain_id = original_request.chain_id;
         let heights = original_request.heights;
+        self.check_chain_rate_limit(remote_address, "DownloadRawCertificatesByHeights", chain_id)?;
 
         let raw_certificates_by_height = self
             .0
@@ -974,6 +1130,39 @@ where
         Ok(Response::new(RawCertificatesBatch { certificates }))
     }
 
+    #[instrument(skip_all, err(Display), fields(method = "stream_chain_history"))]
+    async fn stream_chain_history(
+        &self,
+        request: Request<api::StreamChainHistoryRequest>,
+    ) -> Result<Response<Self::StreamChainHistoryStream>, Status> {
+        let remote_address = request.remote_addr();
+        let ChainHistoryRequest {
+            chain_id,
+            from_height,
+            to_height,
+        } = request.into_inner().try_into()?;
+        self.check_chain_rate_limit(remote_address, "StreamChainHistory", chain_id)?;
+
+        let storage = self.0.storage.clone();
+        let stream = chain_history(from_height, to_height, move |heights| {
+            let storage = storage.clone();
+            async move {
+                storage
+                    .read_certificates_by_heights_raw(chain_id, &heights)
+                    .await
+            }
+        })
+        .map_ok(|raw_certificate| {
+            let (lite_certificate, confirmed_block) = CacheArc::unwrap_or_clone(raw_certificate);
+            RawCertificate {
+                lite_certificate,
+                confirmed_block,
+            }
+        })
+        .map_err(Self::view_error_to_status);
+        Ok(Response::new(Box::pin(stream)))
+    }
+
     #[instrument(skip_all, err(level = Level::WARN), fields(
         method = "blob_last_used_by"
     ))]
@@ -17,6 +17,7 @@ use anyhow::{anyhow, bail, ensure, Result};
 use async_trait::async_trait;
 use futures::{stream::SelectAll, FutureExt as _, SinkExt, Stream, StreamExt};
 use linera_base::{
+    data_types::BlockHeight,
     identifiers::{BlobId, ChainId},
     listen_for_shutdown_signals,
 };
@@ -26,10 +27,11 @@ use linera_core::{node::NodeError, JoinSetExt as _};
 use linera_metrics::monitoring_server;
 use linera_rpc::{
     config::{
-        NetworkProtocol, ShardConfig, ValidatorInternalNetworkPreConfig,
+        InternalTlsOptions, NetworkProtocol, ShardConfig, ValidatorInternalNetworkPreConfig,
         ValidatorPublicNetworkPreConfig,
     },
-    simple::{MessageHandler, TransportProtocol},
+    grpc::tls::InternalTls,
+    simple::{MessageHandler, QuicConnections, QuicServerTls, TransportProtocol},
     RpcMessage,
 };
 use linera_sdk::linera_base_types::Blob;
@@ -42,8 +44,11 @@ use tokio::task::JoinSet;
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info, instrument};
 
+mod chain_history;
 mod grpc;
+mod rate_limit;
 use grpc::GrpcProxy;
+use rate_limit::{RateLimitOptions, RateLimiter};
 
 /// Options for running the proxy.
 #[derive(clap::Parser, Debug, Clone)]
@@ -90,6 +95,23 @@ pub struct ProxyOptions {
     #[arg(long)]
     id: Option<usize>,
 
+    /// Certificates for the links to the shards.
+    #[command(flatten)]
+    internal_tls_options: InternalTlsOptions,
+
+    /// The certificate chain, in PEM format, presented to the clients if the public protocol
+    /// is QUIC.
+    #[arg(long, env = "LINERA_PROXY_QUIC_CERTIFICATE")]
+    quic_certificate: Option<PathBuf>,
+
+    /// The private key, in PEM format, of the certificate given by `--quic-certificate`.
+    #[arg(long, env = "LINERA_PROXY_QUIC_KEY")]
+    quic_key: Option<PathBuf>,
+
+    /// Limits on the requests of the clients of the gRPC proxy.
+    #[command(flatten)]
+    rate_limit_options: RateLimitOptions,
+
     /// OpenTelemetry OTLP exporter endpoint (requires opentelemetry feature).
     #[arg(long, env = "LINERA_OTLP_EXPORTER_ENDPOINT")]
     otlp_exporter_endpoint: Option<String>,
@@ -129,6 +151,10 @@ struct ProxyContext {
     send_timeout: Duration,
     recv_timeout: Duration,
     id: usize,
+    internal_tls_options: InternalTlsOptions,
+    quic_certificate: Option<PathBuf>,
+    quic_key: Option<PathBuf>,
+    rate_limit_options: RateLimitOptions,
     enable_memory_profiling: bool,
 }
 
@@ -141,6 +167,10 @@ impl ProxyContext {
             send_timeout: options.send_timeout,
             recv_timeout: options.recv_timeout,
             id: options.id.unwrap_or(0),
+            internal_tls_options: options.internal_tls_options.clone(),
+            quic_certificate: options.quic_certificate.clone(),
+            quic_key: options.quic_key.clone(),
+            rate_limit_options: options.rate_limit_options.clone(),
             enable_memory_profiling: options.enable_memory_profiling(),
         })
     }
@@ -182,13 +212,18 @@ where
     fn from_context(context: ProxyContext, storage: S) -> Result<Self> {
         let internal_protocol = context.config.internal_network.protocol;
         let external_protocol = context.config.validator.network.protocol;
+        let rate_limiter = RateLimiter::new(&context.rate_limit_options);
         let proxy = match (internal_protocol, external_protocol) {
-            (NetworkProtocol::Grpc { .. }, NetworkProtocol::Grpc(tls)) => {
+            (NetworkProtocol::Grpc(internal_tls), NetworkProtocol::Grpc(tls)) => {
+                let internal_tls =
+                    InternalTls::for_protocol(internal_tls, &context.internal_tls_options)?;
                 Self::Grpc(GrpcProxy::new(
                     context.config.internal_network,
+                    internal_tls,
                     context.send_timeout,
                     context.recv_timeout,
                     tls,
+                    rate_limiter,
                     storage,
                     context.id,
                 ))
@@ -196,7 +231,32 @@ where
             (
                 NetworkProtocol::Simple(internal_transport),
                 NetworkProtocol::Simple(public_transport),
-            ) => Self::Simple(Box::new(SimpleProxy {
+            ) => {
+                ensure!(
+                    rate_limiter.is_none(),
+                    "rate limits are only supported by the gRPC proxy"
+                );
+                let quic_connections = match internal_transport {
+                    TransportProtocol::Quic => QuicConnections::internal(&InternalTls::load(
+                        &context.internal_tls_options,
+                    )?)?,
+                    _ => QuicConnections::default(),
+                };
+                let public_quic_tls = match public_transport {
+                    TransportProtocol::Quic => {
+                        let (Some(certificate), Some(key)) =
+                            (&context.quic_certificate, &context.quic_key)
+                        else {
+                            bail!(
+                                "the public protocol is `quic` but `--quic-certificate` or \
+                                `--quic-key` is missing"
+                            );
+                        };
+                        Some(QuicServerTls::from_pem_files(certificate, key)?)
+                    }
+                    _ => None,
+                };
+                Self::Simple(Box::new(SimpleProxy {
                 internal_config: context
                     .config
                     .internal_network
@@ -208,9 +268,12 @@ where
                     .clone_with_protocol(public_transport),
                 send_timeout: context.send_timeout,
                 recv_timeout: context.recv_timeout,
+                quic_connections,
+                public_quic_tls,
                 storage,
                 id: context.id,
-            })),
+            }))
+            }
             _ => bail!("network protocol mismatch: cannot have {internal_protocol} and {external_protocol} "),
         };
 
@@ -227,6 +290,10 @@ where
     internal_config: ValidatorInternalNetworkPreConfig<TransportProtocol>,
     send_timeout: Duration,
     recv_timeout: Duration,
+    /// The QUIC connections to the shards, if they use QUIC.
+    quic_connections: QuicConnections,
+    /// The certificate presented to the clients, if the public protocol is QUIC.
+    public_quic_tls: Option<QuicServerTls>,
     storage: S,
     id: usize,
 }
@@ -262,6 +329,7 @@ where
             message,
             shard.clone(),
             protocol,
+            &self.quic_connections,
             self.send_timeout,
             self.recv_timeout,
         )
@@ -302,6 +370,30 @@ where
         });
         Some(Box::pin(futures::stream::iter(messages)))
     }
+
+    async fn handle_stream_chain_history(
+        &mut self,
+        chain_id: ChainId,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+    ) -> Option<Pin<Box<dyn Stream<Item = RpcMessage> + Send>>> {
+        let storage = self.storage.clone();
+        let certificates = chain_history::chain_history(from_height, to_height, move |heights| {
+            let storage = storage.clone();
+            async move {
+                storage
+                    .read_certificates_by_heights(chain_id, &heights)
+                    .await
+            }
+        });
+        let messages = certificates.map(|result| match result {
+            Ok(certificate) => RpcMessage::ChainHistoryCertificate(Box::new(
+                CacheArc::unwrap_or_clone(certificate),
+            )),
+            Err(error) => RpcMessage::Error(Box::new(NodeError::from(error))),
+        });
+        Some(Box::pin(messages))
+    }
 }
 
 impl<S> SimpleProxy<S>
@@ -331,9 +423,16 @@ where
         )
         .await;
 
+        let public_quic_tls = self.public_quic_tls.clone();
         self.public_config
             .protocol
-            .spawn_server(address, self, shutdown_signal, &mut join_set)
+            .spawn_server(
+                address,
+                self,
+                public_quic_tls,
+                shutdown_signal,
+                &mut join_set,
+            )
             .join()
             .await?;
 
@@ -380,8 +479,10 @@ where
         // Only connect to shards that own at least one requested chain.
         for (shard_id, shard_chains) in chains_by_shard {
             let shard = &self.internal_config.shards[shard_id];
-            let address = (shard.host.clone(), shard.port);
-            match protocol.connect(address).await {
+            match protocol
+                .connect(&shard.host, shard.port, &self.quic_connections)
+                .await
+            {
                 Ok(mut connection) => {
                     let subscribe_msg = RpcMessage::SubscribeNotifications(shard_chains);
                     if let Err(error) = connection.send(subscribe_msg).await {
@@ -418,10 +519,13 @@ where
         message: RpcMessage,
         shard: ShardConfig,
         protocol: TransportProtocol,
+        quic_connections: &QuicConnections,
         send_timeout: Duration,
         recv_timeout: Duration,
     ) -> Result<Option<RpcMessage>> {
-        let mut connection = protocol.connect((shard.host, shard.port)).await?;
+        let mut connection = protocol
+            .connect(&shard.host, shard.port, quic_connections)
+            .await?;
         linera_base::time::timer::timeout(send_timeout, connection.send(message)).await??;
         let message = linera_base::time::timer::timeout(recv_timeout, connection.next())
             .await?
@@ -505,6 +609,7 @@ where
                     chain_info_query,
                     shard.clone(),
                     protocol,
+                    &self.quic_connections,
                     self.send_timeout,
                     self.recv_timeout,
                 )
@@ -574,6 +679,8 @@ where
             | ShardInfoResponse(_)
             | DownloadBlobResponse(_)
             | DownloadBlobs(_)
+            | StreamChainHistory(_, _, _)
+            | ChainHistoryCertificate(_)
             | DownloadPendingBlob(_)
             | DownloadPendingBlobResponse(_)
             | HandlePendingBlob(_)
@@ -0,0 +1,556 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Admission control for the public endpoint of the gRPC proxy.
+//!
+//! Requests are counted against token buckets: one per client IP address, one per client for
+//! streaming requests, one per client for each request type given its own limit, and one per
+//! client for each chain. A request is admitted only if all of its buckets have a token left,
+//! and then takes one token from each. Otherwise it is rejected with the time until it would
+//! be admitted.
+//!
+//! The chain of a request is only known once its body is decoded, after the other limits
+//! admitted it. If the chain limit then rejects the request, the tokens it took from the other
+//! buckets are given back, so a rejected request costs nothing. Since every bucket belongs to
+//! a single client, a client can only exhaust its own limits, and not those of the other
+//! clients of a chain.
+//!
+//! An IPv6 client is identified by the /64 network of its address, since a single host
+//! usually has a whole /64 to pick addresses from. Each limit keeps the buckets of at most
+//! [`MAX_BUCKETS`] clients or chains, forgetting the least recently used ones first.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    hash::Hash,
+    net::{IpAddr, Ipv6Addr},
+    num::NonZeroUsize,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use linera_base::identifiers::ChainId;
+use lru::LruCache;
+
+/// The requests streaming their responses, which may be limited separately.
+const STREAMING_METHODS: &[&str] = &["Subscribe", "DownloadBlobs", "StreamChainHistory"];
+
+/// The maximum number of buckets of one limit. Beyond it, the least recently used bucket is
+/// forgotten, which bounds the memory used by clients that stopped sending requests or that
+/// spread their requests over many addresses.
+const MAX_BUCKETS: NonZeroUsize = NonZeroUsize::new(100_000).unwrap();
+
+/// The rate limits of the gRPC proxy. Every limit is in requests per second, and is disabled if
+/// not set.
+#[derive(clap::Args, Clone, Debug)]
+pub struct RateLimitOptions {
+    /// The number of requests per second allowed from each client IP address, or from each
+    /// /64 network for IPv6 clients.
+    #[arg(long, value_parser = parse_rate, env = "LINERA_PROXY_RATE_LIMIT_PER_IP")]
+    rate_limit_per_ip: Option<f64>,
+
+    /// The number of requests per second allowed about each chain from each client IP
+    /// address, or from each /64 network for IPv6 clients.
+    #[arg(long, value_parser = parse_rate, env = "LINERA_PROXY_RATE_LIMIT_PER_CHAIN")]
+    rate_limit_per_chain: Option<f64>,
+
+    /// The number of streaming requests (`Subscribe`, `DownloadBlobs` and `StreamChainHistory`)
+    /// per second allowed from each client IP address.
+    #[arg(long, value_parser = parse_rate, env = "LINERA_PROXY_RATE_LIMIT_STREAMING_PER_IP")]
+    rate_limit_streaming_per_ip: Option<f64>,
+
+    /// The number of requests of one type per second allowed from each client IP address, as
+    /// `METHOD=RATE` with the gRPC method name, e.g. `DownloadCertificates=5`. Can be repeated.
+    #[arg(long, value_parser = parse_method_rate)]
+    rate_limit_per_method: Vec<(String, f64)>,
+
+    /// How many seconds' worth of requests a client can send at once before being limited.
+    #[arg(
+        long,
+        default_value = "2",
+        value_parser = parse_rate,
+        env = "LINERA_PROXY_RATE_LIMIT_BURST_SECONDS"
+    )]
+    rate_limit_burst_seconds: f64,
+}
+
+fn parse_rate(rate: &str) -> Result<f64, String> {
+    match rate.parse::<f64>() {
+        Ok(rate) if rate.is_finite() && rate > 0.0 => Ok(rate),
+        _ => Err(format!("expected a positive number, found {rate:?}")),
+    }
+}
+
+fn parse_method_rate(limit: &str) -> Result<(String, f64), String> {
+    let (method, rate) = limit
+        .split_once('=')
+        .ok_or_else(|| format!("expected `METHOD=RATE`, found {limit:?}"))?;
+    Ok((method.to_owned(), parse_rate(rate)?))
+}
+
+/// The limit a rejected request exceeded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Limit {
+    Ip,
+    Chain,
+    Streaming,
+    Method,
+}
+
+impl Limit {
+    /// Returns the name of the limit, as used in metric labels.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Limit::Ip => "ip",
+            Limit::Chain => "chain",
+            Limit::Streaming => "streaming",
+            Limit::Method => "method",
+        }
+    }
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A rejected request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimited {
+    /// The limit the request exceeded.
+    pub limit: Limit,
+    /// How long until the request would be admitted.
+    pub retry_after: Duration,
+}
+
+/// The token buckets of the configured limits.
+pub struct RateLimiter(Mutex<Buckets>);
+
+struct Buckets {
+    per_ip: Option<BucketMap<IpAddr>>,
+    per_chain: Option<BucketMap<(IpAddr, ChainId)>>,
+    streaming_per_ip: Option<BucketMap<IpAddr>>,
+    per_method: HashMap<String, BucketMap<IpAddr>>,
+}
+
+impl RateLimiter {
+    /// Creates the rate limiter for the given options, or `None` if no limit is set.
+    pub fn new(options: &RateLimitOptions) -> Option<Self> {
+        let burst = options.rate_limit_burst_seconds;
+        let buckets = Buckets {
+            per_ip: options
+                .rate_limit_per_ip
+                .map(|rate| BucketMap::new(rate, burst)),
+            per_chain: options
+                .rate_limit_per_chain
+                .map(|rate| BucketMap::new(rate, burst)),
+            streaming_per_ip: options
+                .rate_limit_streaming_per_ip
+                .map(|rate| BucketMap::new(rate, burst)),
+            per_method: options
+                .rate_limit_per_method
+                .iter()
+                .map(|(method, rate)| (method.clone(), BucketMap::new(*rate, burst)))
+                .collect(),
+        };
+        let is_unlimited = buckets.per_ip.is_none()
+            && buckets.per_chain.is_none()
+            && buckets.streaming_per_ip.is_none()
+            && buckets.per_method.is_empty();
+        (!is_unlimited).then(|| Self(Mutex::new(buckets)))
+    }
+
+    /// Admits or rejects a request of the client at `ip` calling the gRPC `method`. The
+    /// client-specific limits are skipped if the address of the client is unknown.
+    pub fn check_client(&self, ip: Option<IpAddr>, method: &str) -> Result<(), RateLimited> {
+        self.check_client_at(Instant::now(), ip, method)
+    }
+
+    /// Admits or rejects a request of the client at `ip` calling the gRPC `method` about
+    /// `chain_id`, after [`Self::check_client`] admitted it. If the request is rejected, the
+    /// tokens taken by [`Self::check_client`] are given back. The limit is skipped if the
+    /// address of the client is unknown.
+    pub fn check_chain(
+        &self,
+        ip: Option<IpAddr>,
+        method: &str,
+        chain_id: ChainId,
+    ) -> Result<(), RateLimited> {
+        self.check_chain_at(Instant::now(), ip, method, chain_id)
+    }
+
+    fn check_client_at(
+        &self,
+        now: Instant,
+        ip: Option<IpAddr>,
+        method: &str,
+    ) -> Result<(), RateLimited> {
+        let Some(ip) = ip else {
+            return Ok(());
+        };
+        let client = client_key(ip);
+        let mut buckets = self.0.lock().unwrap();
+        acquire(now, &client, buckets.client_maps(method))
+    }
+
+    fn check_chain_at(
+        &self,
+        now: Instant,
+        ip: Option<IpAddr>,
+        method: &str,
+        chain_id: ChainId,
+    ) -> Result<(), RateLimited> {
+        let Some(ip) = ip else {
+            return Ok(());
+        };
+        let client = client_key(ip);
+        let mut buckets = self.0.lock().unwrap();
+        let candidates = buckets
+            .per_chain
+            .as_mut()
+            .map(|map| (Limit::Chain, map))
+            .into_iter()
+            .collect();
+        let result = acquire(now, &(client, chain_id), candidates);
+        if result.is_err() {
+            for (_, map) in buckets.client_maps(method) {
+                map.give_back(now, &client);
+            }
+        }
+        result
+    }
+}
+
+impl Buckets {
+    /// Returns the maps of the client-specific limits that apply to the gRPC `method`.
+    fn client_maps(&mut self, method: &str) -> Vec<(Limit, &mut BucketMap<IpAddr>)> {
+        let Buckets {
+            per_ip,
+            streaming_per_ip,
+            per_method,
+            ..
+        } = self;
+        let is_streaming = STREAMING_METHODS.contains(&method);
+        let mut maps = Vec::new();
+        if let Some(map) = per_ip {
+            maps.push((Limit::Ip, map));
+        }
+        if let Some(map) = streaming_per_ip.as_mut().filter(|_| is_streaming) {
+            maps.push((Limit::Streaming, map));
+        }
+        if let Some(map) = per_method.get_mut(method) {
+            maps.push((Limit::Method, map));
+        }
+        maps
+    }
+}
+
+/// Returns the key of the buckets of the client at `ip`: the address itself for IPv4, and its
+/// /64 network for IPv6.
+fn client_key(ip: IpAddr) -> IpAddr {
+    match ip.to_canonical() {
+        IpAddr::V6(ip) => IpAddr::V6(Ipv6Addr::from(u128::from(ip) & !u128::from(u64::MAX))),
+        ip => ip,
+    }
+}
+
+/// Takes a token for `key` from each of the bucket maps, or none if one of them is empty.
+fn acquire<K: Eq + Hash + Clone>(
+    now: Instant,
+    key: &K,
+    candidates: Vec<(Limit, &mut BucketMap<K>)>,
+) -> Result<(), RateLimited> {
+    let rejection = candidates
+        .iter()
+        .filter_map(|(limit, map)| {
+            let retry_after = map.wait_time(now, key)?;
+            Some(RateLimited {
+                limit: *limit,
+                retry_after,
+            })
+        })
+        .max_by_key(|rejection| rejection.retry_after);
+    if let Some(rejection) = rejection {
+        return Err(rejection);
+    }
+    for (_, map) in candidates {
+        map.take(now, key);
+    }
+    Ok(())
+}
+
+/// The token buckets of one limit, indexed by key.
+struct BucketMap<K> {
+    /// The tokens added per second.
+    rate: f64,
+    /// The maximum number of tokens in a bucket.
+    capacity: f64,
+    buckets: LruCache<K, Bucket>,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl<K: Eq + Hash + Clone> BucketMap<K> {
+    fn new(rate: f64, burst_seconds: f64) -> Self {
+        Self {
+            rate,
+            capacity: (rate * burst_seconds).max(1.0),
+            buckets: LruCache::new(MAX_BUCKETS),
+        }
+    }
+
+    /// Returns the number of tokens in the bucket of `key` at time `now`.
+    fn tokens(&self, now: Instant, key: &K) -> f64 {
+        self.buckets.peek(key).map_or(self.capacity, |bucket| {
+            let elapsed = now.saturating_duration_since(bucket.updated);
+            (bucket.tokens + elapsed.as_secs_f64() * self.rate).min(self.capacity)
+        })
+    }
+
+    /// Returns how long until the bucket of `key` has a token, or `None` if it has one now.
+    fn wait_time(&self, now: Instant, key: &K) -> Option<Duration> {
+        let missing = 1.0 - self.tokens(now, key);
+        (missing > 0.0).then(|| Duration::from_secs_f64(missing / self.rate))
+    }
+
+    /// Takes a token from the bucket of `key`, making it the most recently used one.
+    fn take(&mut self, now: Instant, key: &K) {
+        let tokens = self.tokens(now, key) - 1.0;
+        self.buckets.put(
+            key.clone(),
+            Bucket {
+                tokens,
+                updated: now,
+            },
+        );
+    }
+
+    /// Gives back a token taken from the bucket of `key`, if the bucket is still known.
+    fn give_back(&mut self, now: Instant, key: &K) {
+        let tokens = (self.tokens(now, key) + 1.0).min(self.capacity);
+        if let Some(bucket) = self.buckets.peek_mut(key) {
+            *bucket = Bucket {
+                tokens,
+                updated: now,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser as _;
+    use linera_base::crypto::CryptoHash;
+
+    use super::*;
+
+    #[derive(clap::Parser)]
+    struct TestOptions {
+        #[command(flatten)]
+        options: RateLimitOptions,
+    }
+
+    fn limiter(args: &[&str]) -> RateLimiter {
+        let options = TestOptions::parse_from(std::iter::once("test").chain(args.iter().copied()));
+        RateLimiter::new(&options.options).expect("a limit is set")
+    }
+
+    #[test]
+    fn test_no_limits() {
+        let options = TestOptions::parse_from(["test"]);
+        assert!(RateLimiter::new(&options.options).is_none());
+    }
+
+    #[test]
+    fn test_per_ip_limit_refills() {
+        let limiter = limiter(&[
+            "--rate-limit-per-ip",
+            "2",
+            "--rate-limit-burst-seconds",
+            "1",
+        ]);
+        let start = Instant::now();
+        let ip = Some(IpAddr::from([10, 0, 0, 1]));
+        let other_ip = Some(IpAddr::from([10, 0, 0, 2]));
+
+        assert!(limiter.check_client_at(start, ip, "GetVersionInfo").is_ok());
+        assert!(limiter.check_client_at(start, ip, "GetVersionInfo").is_ok());
+        let rejection = limiter
+            .check_client_at(start, ip, "GetVersionInfo")
+            .unwrap_err();
+        assert_eq!(rejection.limit, Limit::Ip);
+        assert_eq!(rejection.retry_after, Duration::from_millis(500));
+        // Other clients and clients with an unknown address are not affected.
+        assert!(limiter
+            .check_client_at(start, other_ip, "GetVersionInfo")
+            .is_ok());
+        assert!(limiter
+            .check_client_at(start, None, "GetVersionInfo")
+            .is_ok());
+
+        let later = start + Duration::from_millis(500);
+        assert!(limiter.check_client_at(later, ip, "GetVersionInfo").is_ok());
+        assert!(limiter
+            .check_client_at(later, ip, "GetVersionInfo")
+            .is_err());
+    }
+
+    #[test]
+    fn test_streaming_and_method_limits() {
+        let limiter = limiter(&[
+            "--rate-limit-per-ip",
+            "100",
+            "--rate-limit-streaming-per-ip",
+            "1",
+            "--rate-limit-per-method",
+            "DownloadCertificates=1",
+            "--rate-limit-burst-seconds",
+            "1",
+        ]);
+        let now = Instant::now();
+        let ip = Some(IpAddr::from([10, 0, 0, 1]));
+
+        assert!(limiter.check_client_at(now, ip, "DownloadBlobs").is_ok());
+        let rejection = limiter.check_client_at(now, ip, "Subscribe").unwrap_err();
+        assert_eq!(rejection.limit, Limit::Streaming);
+        assert!(limiter
+            .check_client_at(now, ip, "DownloadCertificates")
+            .is_ok());
+        let rejection = limiter
+            .check_client_at(now, ip, "DownloadCertificates")
+            .unwrap_err();
+        assert_eq!(rejection.limit, Limit::Method);
+        // Unary requests of other types are only subject to the per-IP limit.
+        assert!(limiter.check_client_at(now, ip, "DownloadBlob").is_ok());
+    }
+
+    #[test]
+    fn test_rejected_requests_take_no_tokens() {
+        let limiter = limiter(&[
+            "--rate-limit-per-ip",
+            "2",
+            "--rate-limit-streaming-per-ip",
+            "1",
+            "--rate-limit-burst-seconds",
+            "1",
+        ]);
+        let now = Instant::now();
+        let ip = Some(IpAddr::from([10, 0, 0, 1]));
+
+        assert!(limiter.check_client_at(now, ip, "Subscribe").is_ok());
+        assert!(limiter.check_client_at(now, ip, "Subscribe").is_err());
+        // The rejected subscription did not use the last per-IP token.
+        assert!(limiter.check_client_at(now, ip, "GetVersionInfo").is_ok());
+    }
+
+    #[test]
+    fn test_ipv6_clients_are_limited_per_network() {
+        let limiter = limiter(&[
+            "--rate-limit-per-ip",
+            "1",
+            "--rate-limit-burst-seconds",
+            "1",
+        ]);
+        let now = Instant::now();
+        let ip = |address: &str| Some(address.parse::<IpAddr>().unwrap());
+
+        assert!(limiter
+            .check_client_at(now, ip("2001:db8:1:2::1"), "GetVersionInfo")
+            .is_ok());
+        // Another address of the same /64 network shares the bucket.
+        assert!(limiter
+            .check_client_at(now, ip("2001:db8:1:2:ffff::7"), "GetVersionInfo")
+            .is_err());
+        assert!(limiter
+            .check_client_at(now, ip("2001:db8:1:3::1"), "GetVersionInfo")
+            .is_ok());
+        // IPv4-mapped addresses are limited as the IPv4 addresses they map.
+        assert!(limiter
+            .check_client_at(now, ip("10.0.0.1"), "GetVersionInfo")
+            .is_ok());
+        assert!(limiter
+            .check_client_at(now, ip("::ffff:10.0.0.1"), "GetVersionInfo")
+            .is_err());
+        assert!(limiter
+            .check_client_at(now, ip("::ffff:10.0.0.2"), "GetVersionInfo")
+            .is_ok());
+    }
+
+    #[test]
+    fn test_buckets_are_bounded() {
+        let mut map = BucketMap::new(1.0, 1.0);
+        let now = Instant::now();
+        for key in 0..MAX_BUCKETS.get() + 10 {
+            map.take(now, &key);
+        }
+        assert_eq!(map.buckets.len(), MAX_BUCKETS.get());
+        // The least recently used buckets were forgotten.
+        assert!(map.wait_time(now, &0).is_none());
+        assert!(map.wait_time(now, &(MAX_BUCKETS.get() + 9)).is_some());
+    }
+
+    #[test]
+    fn test_per_chain_limit() {
+        let limiter = limiter(&[
+            "--rate-limit-per-chain",
+            "1",
+            "--rate-limit-burst-seconds",
+            "1",
+        ]);
+        let now = Instant::now();
+        let ip = Some(IpAddr::from([10, 0, 0, 1]));
+        let other_ip = Some(IpAddr::from([10, 0, 0, 2]));
+        let chain_id = ChainId(CryptoHash::test_hash("chain"));
+        let other_chain_id = ChainId(CryptoHash::test_hash("other chain"));
+        let method = "DownloadCertificatesByHeights";
+
+        assert!(limiter.check_chain_at(now, ip, method, chain_id).is_ok());
+        let rejection = limiter
+            .check_chain_at(now, ip, method, chain_id)
+            .unwrap_err();
+        assert_eq!(rejection.limit, Limit::Chain);
+        assert!(limiter
+            .check_chain_at(now, ip, method, other_chain_id)
+            .is_ok());
+        // A client exhausting the limit of a chain does not affect the other clients.
+        assert!(limiter
+            .check_chain_at(now, other_ip, method, chain_id)
+            .is_ok());
+        assert!(limiter.check_chain_at(now, None, method, chain_id).is_ok());
+        // Without a client limit, clients are not limited.
+        assert!(limiter.check_client_at(now, ip, "DownloadBlobs").is_ok());
+    }
+
+    #[test]
+    fn test_requests_rejected_per_chain_take_no_client_tokens() {
+        let limiter = limiter(&[
+            "--rate-limit-per-ip",
+            "2",
+            "--rate-limit-streaming-per-ip",
+            "1",
+            "--rate-limit-per-chain",
+            "1",
+            "--rate-limit-burst-seconds",
+            "1",
+        ]);
+        let now = Instant::now();
+        let ip = Some(IpAddr::from([10, 0, 0, 1]));
+        let chain_id = ChainId(CryptoHash::test_hash("chain"));
+        let method = "StreamChainHistory";
+
+        // Another request of the client used up the limit of the chain.
+        assert!(limiter.check_chain_at(now, ip, method, chain_id).is_ok());
+        assert!(limiter.check_client_at(now, ip, method).is_ok());
+        let rejection = limiter
+            .check_chain_at(now, ip, method, chain_id)
+            .unwrap_err();
+        assert_eq!(rejection.limit, Limit::Chain);
+        // The streaming token taken by the rejected request was given back.
+        assert!(limiter.check_client_at(now, ip, method).is_ok());
+        assert!(limiter.check_client_at(now, ip, method).is_err());
+    }
+}
@@ -150,6 +150,15 @@ impl ValidatorNode for DummyValidatorNode {
         Err(NodeError::UnexpectedMessage)
     }
 
+    async fn stream_chain_history(
+        &self,
+        _: ChainId,
+        _: BlockHeight,
+        _: BlockHeight,
+    ) -> Result<linera_core::node::CertificateStream, NodeError> {
+        Err(NodeError::UnexpectedMessage)
+    }
+
     async fn blob_last_used_by(&self, _: BlobId) -> Result<CryptoHash, NodeError> {
         Err(NodeError::UnexpectedMessage)
     }
@@ -23,7 +23,7 @@ use std::{
     time::Duration,
 };
 
-use anyhow::{bail, Context};
+use anyhow::Context;
 use async_trait::async_trait;
 use futures::{stream::FuturesUnordered, FutureExt as _, StreamExt, TryFutureExt as _};
 use linera_base::{
@@ -42,16 +42,17 @@ use linera_metrics::monitoring_server;
 use linera_persistent::{self as persistent, Persist};
 use linera_rpc::{
     config::{
-        CrossChainConfig, ExporterServiceConfig, NetworkProtocol, NotificationConfig, ProxyConfig,
-        ShardConfig, ShardId, TlsConfig, ValidatorInternalNetworkConfig,
+        CrossChainConfig, ExporterServiceConfig, InternalTlsOptions, NetworkProtocol,
+        NotificationConfig, ProxyConfig, ShardConfig, ShardId, ValidatorInternalNetworkConfig,
         ValidatorPublicNetworkConfig,
     },
-    grpc, simple, NodeOptions,
+    grpc::{self, tls::InternalTls},
+    simple, NodeOptions,
 };
 use linera_sdk::linera_base_types::{AccountSecretKey, ValidatorKeypair};
 use linera_service::{
     config::BlockExportTransport,
-    storage::{CommonStorageOptions, Runnable, StorageConfig},
+    storage::{CommonStorageOptions, PruningJob, Runnable, StorageConfig},
     util,
 };
 use linera_storage::Storage;
@@ -62,6 +63,9 @@ use tracing::{error, info};
 
 struct ServerContext {
     server_config: ValidatorServerConfig,
+    /// The certificates authenticating the links to the proxies and the other shards, if the
+    /// internal protocol is `grpcs` or `quic`.
+    internal_tls: Option<InternalTls>,
     cross_chain_config: CrossChainConfig,
     notification_config: NotificationConfig,
     shard: Option<usize>,
@@ -159,7 +163,10 @@ impl ServerContext {
                 );
                 spawn_block_export_queue(
                     storage,
-                    Arc::new(grpc::RelayNodeProvider::new(relay_addresses, node_options)),
+                    Arc::new(
+                        grpc::RelayNodeProvider::new(relay_addresses, node_options)
+                            .with_internal_tls(self.internal_tls.clone()),
+                    ),
                     export_config,
                     Some(own_public_key),
                 )
@@ -217,7 +224,9 @@ impl ServerContext {
                 shard_id,
                 cross_chain_config,
             )
-            .spawn(shutdown_signal.clone(), &mut join_set);
+            .with_internal_tls(self.internal_tls.clone())
+            .spawn(shutdown_signal.clone(), &mut join_set)
+            .expect("invalid internal TLS configuration");
 
             handles.push(
                 server_handle
@@ -265,6 +274,7 @@ impl ServerContext {
                 state,
                 shard_id,
                 self.server_config.internal_network.clone(),
+                self.internal_tls.clone(),
                 &self.cross_chain_config,
                 &self.notification_config,
                 shutdown_signal.clone(),
@@ -349,15 +359,12 @@ impl Runnable for ServerContext {
                 &shutdown_notifier,
                 enable_memory_profiling,
             ),
-            NetworkProtocol::Grpc(tls_config) => match tls_config {
-                TlsConfig::ClearText => self.spawn_grpc(
-                    &listen_address,
-                    states,
-                    &shutdown_notifier,
-                    enable_memory_profiling,
-                ),
-                TlsConfig::Tls => bail!("TLS not supported between proxy and shards."),
-            },
+            NetworkProtocol::Grpc(_) => self.spawn_grpc(
+                &listen_address,
+                states,
+                &shutdown_notifier,
+                enable_memory_profiling,
+            ),
         };
 
         join_set.await_all_tasks().await;
@@ -506,6 +513,10 @@ enum ServerCommand {
         #[command(flatten)]
         notification_config: NotificationConfig,
 
+        /// Certificates for the links to the proxies and the other shards
+        #[command(flatten)]
+        internal_tls_options: InternalTlsOptions,
+
         /// Runs a specific shard (from 0 to shards-1)
         #[arg(long)]
         shard: Option<usize>,
@@ -797,6 +808,7 @@ async fn run(options: ServerOptions) {
             common_storage_options,
             cross_chain_config,
             notification_config,
+            internal_tls_options,
             shard,
             block_time_grace_period,
             wasm_runtime,
@@ -828,8 +840,19 @@ async fn run(options: ServerOptions) {
             let server_config: ValidatorServerConfig =
                 util::read_json(&server_config_path).expect("Failed to read server config");
 
+            let internal_tls = match server_config.internal_network.protocol {
+                NetworkProtocol::Grpc(tls) => InternalTls::for_protocol(tls, &internal_tls_options)
+                    .expect("invalid internal TLS configuration"),
+                NetworkProtocol::Simple(simple::TransportProtocol::Quic) => Some(
+                    InternalTls::load(&internal_tls_options)
+                        .expect("invalid internal TLS configuration"),
+                ),
+                NetworkProtocol::Simple(_) => None,
+            };
+
             let job = ServerContext {
                 server_config,
+                internal_tls,
                 cross_chain_config,
                 notification_config,
                 shard,
@@ -875,6 +898,16 @@ async fn run(options: ServerOptions) {
             // Validators should not output contract logs.
             let allow_application_logs = false;
             let cache_sizes = common_storage_options.storage_cache_config();
+            // All the shards of a validator share its storage, which a single process must
+            // prune: the one running every shard, or the one running shard 0.
+            let pruning_config = match shard {
+                Some(shard) if shard != 0 => None,
+                _ => common_storage_options.background_pruning_config(),
+            };
+            let job = PruningJob {
+                config: pruning_config,
+                job,
+            };
             store_config
                 .run_with_storage(wasm_runtime, allow_application_logs, cache_sizes, job)
                 .boxed()
@@ -6,8 +6,9 @@ use linera_client::config::GenesisConfig;
 use linera_storage::DbStorage;
 pub use linera_storage::StorageCacheConfig;
 pub use linera_storage_runtime::{
-    AssertStorageV1, CommonStorageOptions, InnerStorageConfig, Runnable, RunnableWithStore,
-    StorageConfig, StorageMigration, StoreConfig,
+    AssertStorageV1, CommonStorageOptions, InnerStorageConfig, NamespaceMigration, PruningJob,
+    Runnable, RunnableWithStore, StorageCheck, StorageConfig, StorageMigration, StoragePruning,
+    StoreConfig,
 };
 use linera_views::store::{KeyValueDatabase, KeyValueStore};
 
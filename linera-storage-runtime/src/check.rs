@@ -0,0 +1,26 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use linera_storage::{Storage, StorageCheckReport};
+use linera_views::ViewError;
+
+use crate::Runnable;
+
+/// A [`Runnable`] job checking the consistency of every chain of the storage.
+pub struct StorageCheck {
+    /// Whether to repair the issues that can be fixed locally.
+    pub repair: bool,
+}
+
+#[async_trait]
+impl Runnable for StorageCheck {
+    type Output = Result<StorageCheckReport, ViewError>;
+
+    async fn run<S>(self, storage: S) -> Self::Output
+    where
+        S: Storage + Clone + Send + Sync + 'static,
+    {
+        storage.check(self.repair).await
+    }
+}
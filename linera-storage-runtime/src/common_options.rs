@@ -1,7 +1,18 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use linera_storage::{StorageCacheConfig, DEFAULT_CLEANUP_INTERVAL_SECS};
+use linera_base::time::Duration;
+use linera_storage::{
+    PruningConfig, StorageCacheConfig, DEFAULT_CLEANUP_INTERVAL_SECS,
+    DEFAULT_MAX_PRUNED_BLOCKS_PER_PASS, DEFAULT_PRUNING_RETENTION_BLOCKS,
+};
+#[cfg(feature = "compression")]
+use linera_views::compression::{
+    CompressionAlgorithm, CompressionOptions, DEFAULT_COMPRESSION_LEVEL,
+    DEFAULT_COMPRESSION_THRESHOLD,
+};
+#[cfg(feature = "encryption")]
+use linera_views::encryption::{EncryptionKeySource, EncryptionKeysConfig};
 use linera_views::lru_prefix_cache::StorageCacheConfig as ViewsStorageCacheConfig;
 #[cfg(feature = "rocksdb")]
 use {linera_views::rocks_db::RocksDbStatisticsLevel, std::str::FromStr as _};
@@ -100,6 +111,61 @@ pub struct CommonStorageOptions {
         global = true
     )]
     pub rocksdb_statistics_level: RocksDbStatisticsLevel,
+
+    /// Encrypt the stored data with the hex-encoded 32-byte key found at
+    /// `file:<path>` or `env:<variable>`.
+    #[cfg(feature = "encryption")]
+    #[arg(long, global = true)]
+    pub storage_encryption_key: Option<EncryptionKeySource>,
+
+    /// A key that encrypted the stored data previously, in the same format as
+    /// `--storage-encryption-key`. Data encrypted with it remains readable until the keys
+    /// are rotated.
+    #[cfg(feature = "encryption")]
+    #[arg(long, global = true)]
+    pub storage_previous_encryption_keys: Vec<EncryptionKeySource>,
+
+    /// Encrypt the keys of the stored data as well as the values. The keys then keep their
+    /// length and their common prefixes, and range queries list every key with the given
+    /// prefix. This cannot be changed once data has been written.
+    #[cfg(feature = "encryption")]
+    #[arg(long, global = true)]
+    pub storage_encrypt_keys: bool,
+
+    /// Compress the stored values with the given algorithm: `zstd` or `lz4`. This can be
+    /// enabled on existing storage.
+    #[cfg(feature = "compression")]
+    #[arg(long, global = true)]
+    pub storage_compression: Option<CompressionAlgorithm>,
+
+    /// The zstd compression level.
+    #[cfg(feature = "compression")]
+    #[arg(long, default_value_t = DEFAULT_COMPRESSION_LEVEL, global = true)]
+    pub storage_compression_level: i32,
+
+    /// The size in bytes from which the stored values are compressed.
+    #[cfg(feature = "compression")]
+    #[arg(long, default_value_t = DEFAULT_COMPRESSION_THRESHOLD, global = true)]
+    pub storage_compression_threshold: usize,
+
+    /// Prune the history of the chains below their latest checkpoint in the background.
+    /// Enable it on a single process per storage: validator servers started with `--shard`
+    /// only prune on shard 0.
+    #[arg(long, global = true)]
+    pub storage_pruning: bool,
+
+    /// The number of blocks kept below the latest checkpoint of each chain when pruning.
+    #[arg(long, default_value_t = DEFAULT_PRUNING_RETENTION_BLOCKS, global = true)]
+    pub storage_pruning_retention: u64,
+
+    /// The maximal number of blocks pruned per chain in each pass of the background
+    /// pruning.
+    #[arg(long, default_value_t = DEFAULT_MAX_PRUNED_BLOCKS_PER_PASS, global = true)]
+    pub storage_pruning_max_blocks_per_pass: u64,
+
+    /// The interval in seconds between two passes of the background pruning.
+    #[arg(long, default_value = "600", global = true)]
+    pub storage_pruning_interval_secs: u64,
 }
 
 impl CommonStorageOptions {
@@ -123,6 +189,43 @@ impl CommonStorageOptions {
         }
     }
 
+    /// Builds the configuration of the encryption keys from these options, if the storage
+    /// is encrypted.
+    #[cfg(feature = "encryption")]
+    pub fn encryption_keys_config(&self) -> Option<EncryptionKeysConfig> {
+        let key = self.storage_encryption_key.clone()?;
+        Some(EncryptionKeysConfig {
+            key,
+            previous_keys: self.storage_previous_encryption_keys.clone(),
+            encrypt_keys: self.storage_encrypt_keys,
+        })
+    }
+
+    /// Builds the options of the compression from these options, if the storage is
+    /// compressed.
+    #[cfg(feature = "compression")]
+    pub fn compression_options(&self) -> Option<CompressionOptions> {
+        Some(CompressionOptions {
+            algorithm: self.storage_compression?,
+            level: self.storage_compression_level,
+            threshold: self.storage_compression_threshold,
+        })
+    }
+
+    /// Builds the pruning configuration from these options.
+    pub fn pruning_config(&self) -> PruningConfig {
+        PruningConfig {
+            retention_blocks: self.storage_pruning_retention,
+            max_blocks_per_pass: self.storage_pruning_max_blocks_per_pass,
+            interval: Duration::from_secs(self.storage_pruning_interval_secs),
+        }
+    }
+
+    /// Returns the configuration of the background pruning, if it is enabled.
+    pub fn background_pruning_config(&self) -> Option<PruningConfig> {
+        self.storage_pruning.then(|| self.pruning_config())
+    }
+
     /// Builds the views storage cache configuration from these options.
     pub fn views_storage_cache_config(&self) -> ViewsStorageCacheConfig {
         ViewsStorageCacheConfig {
@@ -5,12 +5,18 @@
 
 #![deny(missing_docs)]
 
+mod check;
 mod common_options;
+mod migration;
+mod pruning;
 mod storage_config;
 mod store_config;
 
+pub use check::StorageCheck;
 pub use common_options::CommonStorageOptions;
 pub use linera_storage::StorageCacheConfig;
+pub use migration::NamespaceMigration;
+pub use pruning::{PruningJob, StoragePruning};
 pub use storage_config::{InnerStorageConfig, StorageConfig};
 pub use store_config::{
     AssertStorageV1, Runnable, RunnableWithStore, StorageMigration, StoreConfig,
@@ -0,0 +1,192 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use anyhow::ensure;
+use async_trait::async_trait;
+use linera_storage::{DbStorage, Storage, StorageCacheConfig};
+use linera_views::{
+    archive::{self, ArchiveSummary, CopyProgress},
+    store::{KeyValueDatabase, KeyValueStore},
+};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::{RunnableWithStore, StoreConfig};
+
+/// A [`RunnableWithStore`] job copying the content of the namespace it runs against to
+/// another store, e.g. to move from RocksDB to ScyllaDB.
+pub struct NamespaceMigration {
+    /// The configuration of the store receiving the data.
+    pub target: StoreConfig,
+    /// The file recording the progress of the copy. If the copy gets interrupted, running
+    /// the migration again with the same file resumes it.
+    pub checkpoint_path: Option<PathBuf>,
+    /// Whether to compare the states of the chains in both stores after the copy.
+    pub verify: bool,
+}
+
+#[async_trait]
+impl RunnableWithStore for NamespaceMigration {
+    type Output = ArchiveSummary;
+
+    async fn run<D>(
+        self,
+        config: D::Config,
+        namespace: String,
+        cache_sizes: StorageCacheConfig,
+    ) -> Result<Self::Output, anyhow::Error>
+    where
+        D: KeyValueDatabase + Clone + Send + Sync + 'static,
+        D::Store: KeyValueStore + Clone + Send + Sync + 'static,
+        D::Error: Send + Sync,
+    {
+        let job = MigrationTarget::<D> {
+            source_config: config,
+            source_namespace: namespace,
+            checkpoint_path: self.checkpoint_path,
+            verify: self.verify,
+        };
+        self.target.run_with_store(cache_sizes, job).await
+    }
+}
+
+/// A [`RunnableWithStore`] job copying the content of a namespace of the database `D` to
+/// the store it runs against.
+struct MigrationTarget<D: KeyValueDatabase> {
+    source_config: D::Config,
+    source_namespace: String,
+    checkpoint_path: Option<PathBuf>,
+    verify: bool,
+}
+
+/// The content of the checkpoint file of a migration.
+#[derive(Serialize, Deserialize)]
+struct MigrationCheckpoint {
+    source_namespace: String,
+    target_namespace: String,
+    progress: CopyProgress,
+}
+
+impl MigrationCheckpoint {
+    fn read(path: &Path) -> anyhow::Result<Self> {
+        Ok(serde_json::from_reader(fs_err::File::open(path)?)?)
+    }
+
+    /// Writes the checkpoint to a temporary file first, so that an interruption cannot
+    /// leave a partially written checkpoint.
+    fn write(&self, path: &Path) -> io::Result<()> {
+        let temporary_path = path.with_extension("tmp");
+        fs_err::write(&temporary_path, serde_json::to_vec(self)?)?;
+        fs_err::rename(temporary_path, path)
+    }
+}
+
+#[async_trait]
+impl<D1> RunnableWithStore for MigrationTarget<D1>
+where
+    D1: KeyValueDatabase + Clone + Send + Sync + 'static,
+    D1::Store: KeyValueStore + Clone + Send + Sync + 'static,
+    D1::Error: Send + Sync,
+{
+    type Output = ArchiveSummary;
+
+    async fn run<D2>(
+        self,
+        config: D2::Config,
+        namespace: String,
+        cache_sizes: StorageCacheConfig,
+    ) -> Result<Self::Output, anyhow::Error>
+    where
+        D2: KeyValueDatabase + Clone + Send + Sync + 'static,
+        D2::Store: KeyValueStore + Clone + Send + Sync + 'static,
+        D2::Error: Send + Sync,
+    {
+        let progress = match &self.checkpoint_path {
+            Some(path) if path.exists() => {
+                let checkpoint = MigrationCheckpoint::read(path)?;
+                ensure!(
+                    checkpoint.source_namespace == self.source_namespace
+                        && checkpoint.target_namespace == namespace,
+                    "The checkpoint {} belongs to the migration of namespace {} to namespace {}",
+                    path.display(),
+                    checkpoint.source_namespace,
+                    checkpoint.target_namespace,
+                );
+                info!("Resuming the migration from {}", path.display());
+                Some(checkpoint.progress)
+            }
+            _ => None,
+        };
+        let source = D1::connect(&self.source_config, &self.source_namespace).await?;
+        let target = D2::maybe_create_and_connect(&config, &namespace).await?;
+        let summary = archive::copy_namespace(&source, &target, progress, |progress| {
+            if let Some(path) = &self.checkpoint_path {
+                let checkpoint = MigrationCheckpoint {
+                    source_namespace: self.source_namespace.clone(),
+                    target_namespace: namespace.clone(),
+                    progress: progress.clone(),
+                };
+                checkpoint.write(path)?;
+            }
+            Ok(())
+        })
+        .await?;
+        info!(
+            "Copied {} entries in {} partitions, for a total of {} bytes",
+            summary.entries, summary.partitions, summary.bytes
+        );
+        if self.verify {
+            let source = DbStorage::<D1, _>::connect(
+                &self.source_config,
+                &self.source_namespace,
+                None,
+                cache_sizes,
+            )
+            .await?;
+            let target =
+                DbStorage::<D2, _>::connect(&config, &namespace, None, cache_sizes).await?;
+            verify_chain_states(&source, &target).await?;
+        }
+        if let Some(path) = &self.checkpoint_path {
+            if path.exists() {
+                fs_err::remove_file(path)?;
+            }
+        }
+        Ok(summary)
+    }
+}
+
+/// Checks that two storages contain the same chains, with the same execution state hashes
+/// and the same tips.
+async fn verify_chain_states<S1, S2>(source: &S1, target: &S2) -> anyhow::Result<()>
+where
+    S1: Storage,
+    S2: Storage,
+{
+    let mut chain_ids = source.list_chain_ids().await?;
+    let mut target_chain_ids = target.list_chain_ids().await?;
+    chain_ids.sort();
+    target_chain_ids.sort();
+    ensure!(
+        chain_ids == target_chain_ids,
+        "The storages do not contain the same chains"
+    );
+    for chain_id in &chain_ids {
+        let mut source_chain = source.load_chain(*chain_id).await?;
+        let mut target_chain = target.load_chain(*chain_id).await?;
+        let source_hash = source_chain.execution_state.crypto_hash_mut().await?;
+        let target_hash = target_chain.execution_state.crypto_hash_mut().await?;
+        ensure!(
+            source_hash == target_hash
+                && source_chain.tip_state.get() == target_chain.tip_state.get(),
+            "The state of chain {chain_id} differs between the storages"
+        );
+    }
+    info!("Verified the states of {} chains", chain_ids.len());
+    Ok(())
+}
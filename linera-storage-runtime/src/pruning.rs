@@ -0,0 +1,56 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use linera_base::task::Task;
+use linera_storage::{run_pruning, PruningConfig, PruningReport, Storage};
+use linera_views::ViewError;
+
+use crate::Runnable;
+
+/// A [`Runnable`] job running another one while the storage is pruned in the background.
+pub struct PruningJob<Job> {
+    /// The configuration of the pruning, or `None` to run the job without pruning.
+    pub config: Option<PruningConfig>,
+    /// The job to run.
+    pub job: Job,
+}
+
+#[async_trait]
+impl<Job> Runnable for PruningJob<Job>
+where
+    Job: Runnable + Send,
+{
+    type Output = Job::Output;
+
+    async fn run<S>(self, storage: S) -> Self::Output
+    where
+        S: Storage + Clone + Send + Sync + 'static,
+    {
+        // The pruning stops when the task is dropped, together with the job.
+        let _pruning_task = self
+            .config
+            .map(|config| Task::spawn(run_pruning(storage.clone(), config)));
+        self.job.run(storage).await
+    }
+}
+
+/// A [`Runnable`] job pruning the history of every chain of the storage once.
+pub struct StoragePruning {
+    /// The configuration of the pruning.
+    pub config: PruningConfig,
+    /// Whether to only report what would be pruned, without removing anything.
+    pub dry_run: bool,
+}
+
+#[async_trait]
+impl Runnable for StoragePruning {
+    type Output = Result<PruningReport, ViewError>;
+
+    async fn run<S>(self, storage: S) -> Self::Output
+    where
+        S: Storage + Clone + Send + Sync + 'static,
+    {
+        storage.prune(&self.config, self.dry_run).await
+    }
+}
@@ -5,8 +5,10 @@ use std::{fmt, path::PathBuf, str::FromStr};
 
 use anyhow::{anyhow, bail};
 use linera_storage::DEFAULT_NAMESPACE;
+#[cfg(any(feature = "rocksdb", feature = "redb"))]
+use linera_views::backends::PathWithGuard;
 #[cfg(feature = "rocksdb")]
-use linera_views::rocks_db::{PathWithGuard, RocksDbSpawnMode};
+use linera_views::rocks_db::RocksDbSpawnMode;
 use tracing::error;
 #[cfg(all(feature = "rocksdb", feature = "scylladb"))]
 use {linera_views::backends::dual::DualStoreConfig, std::path::Path};
@@ -39,6 +41,12 @@ pub enum InnerStorageConfig {
         /// Whether to use `block_in_place` or `spawn_blocking`.
         spawn_mode: RocksDbSpawnMode,
     },
+    /// The redb description.
+    #[cfg(feature = "redb")]
+    Redb {
+        /// The path used.
+        path: PathBuf,
+    },
     /// The ScyllaDB description.
     #[cfg(feature = "scylladb")]
     ScyllaDb {
@@ -72,6 +80,8 @@ const MEMORY: &str = "memory:";
 const STORAGE_SERVICE: &str = "service:";
 #[cfg(feature = "rocksdb")]
 const ROCKS_DB: &str = "rocksdb:";
+#[cfg(feature = "redb")]
+const REDB: &str = "redb:";
 #[cfg(feature = "scylladb")]
 const SCYLLA_DB: &str = "scylladb:";
 #[cfg(all(feature = "rocksdb", feature = "scylladb"))]
@@ -174,6 +184,25 @@ example service:tcp:127.0.0.1:7878:table_do_my_test"
             }
             bail!("We should have one, two or three parts");
         }
+        #[cfg(feature = "redb")]
+        if let Some(s) = input.strip_prefix(REDB) {
+            let parts = s.split(':').collect::<Vec<_>>();
+            if s.is_empty() || parts.len() > 2 {
+                bail!(
+                    "For redb, the formatting has to be redb:directory or redb:directory:namespace"
+                );
+            }
+            let path = parts[0].to_string().into();
+            let namespace = match parts.get(1) {
+                Some(namespace) => namespace.to_string(),
+                None => DEFAULT_NAMESPACE.to_string(),
+            };
+            let inner_storage_config = InnerStorageConfig::Redb { path };
+            return Ok(StorageConfig {
+                inner_storage_config,
+                namespace,
+            });
+        }
         #[cfg(feature = "scylladb")]
         if let Some(s) = input.strip_prefix(SCYLLA_DB) {
             let mut uri: Option<String> = None;
@@ -271,6 +300,8 @@ example service:tcp:127.0.0.1:7878:table_do_my_test"
         error!("Also available is linera-storage-service");
         #[cfg(feature = "rocksdb")]
         error!("Also available is RocksDB");
+        #[cfg(feature = "redb")]
+        error!("Also available is redb");
         #[cfg(feature = "scylladb")]
         error!("Also available is ScyllaDB");
         #[cfg(all(feature = "rocksdb", feature = "scylladb"))]
@@ -300,13 +331,54 @@ impl StorageConfig {
 
     /// The addition of the common config to get a full configuration
     pub fn add_common_storage_options(
+        &self,
+        options: &CommonStorageOptions,
+    ) -> Result<StoreConfig, anyhow::Error> {
+        let store_config = self.add_common_inner_storage_options(options);
+        #[cfg(feature = "encryption")]
+        let store_config = match options.encryption_keys_config() {
+            Some(keys_config) => {
+                if let StoreConfig::Memory { .. } = store_config {
+                    bail!("The memory storage cannot be encrypted");
+                }
+                StoreConfig::Encrypted {
+                    keys_config,
+                    inner: Box::new(store_config),
+                }
+            }
+            None => store_config,
+        };
+        // The values are compressed before being encrypted.
+        #[cfg(feature = "compression")]
+        let store_config = match options.compression_options() {
+            Some(options) => {
+                if let StoreConfig::Memory { .. } = store_config {
+                    bail!("The memory storage cannot be compressed");
+                }
+                StoreConfig::Compressed {
+                    options,
+                    inner: Box::new(store_config),
+                }
+            }
+            None => store_config,
+        };
+        Ok(store_config)
+    }
+
+    /// Builds the configuration of the store, before any compression or encryption.
+    fn add_common_inner_storage_options(
         &self,
         #[cfg_attr(
-            not(any(feature = "storage-service", feature = "rocksdb", feature = "scylladb")),
+            not(any(
+                feature = "storage-service",
+                feature = "rocksdb",
+                feature = "redb",
+                feature = "scylladb"
+            )),
             allow(unused_variables)
         )]
         options: &CommonStorageOptions,
-    ) -> Result<StoreConfig, anyhow::Error> {
+    ) -> StoreConfig {
         let namespace = self.namespace.clone();
         match &self.inner_storage_config {
             InnerStorageConfig::Memory { genesis_path } => {
@@ -314,11 +386,11 @@ impl StorageConfig {
                     kill_on_drop: false,
                 };
                 let genesis_path = genesis_path.clone();
-                Ok(StoreConfig::Memory {
+                StoreConfig::Memory {
                     config,
                     namespace,
                     genesis_path,
-                })
+                }
             }
             #[cfg(feature = "storage-service")]
             InnerStorageConfig::Service { endpoint } => {
@@ -331,7 +403,7 @@ impl StorageConfig {
                     inner_config,
                     storage_cache_config: options.views_storage_cache_config(),
                 };
-                Ok(StoreConfig::StorageService { config, namespace })
+                StoreConfig::StorageService { config, namespace }
             }
             #[cfg(feature = "rocksdb")]
             InnerStorageConfig::RocksDb { path, spawn_mode } => {
@@ -346,7 +418,17 @@ impl StorageConfig {
                     inner_config,
                     storage_cache_config: options.views_storage_cache_config(),
                 };
-                Ok(StoreConfig::RocksDb { config, namespace })
+                StoreConfig::RocksDb { config, namespace }
+            }
+            #[cfg(feature = "redb")]
+            InnerStorageConfig::Redb { path } => {
+                let path_with_guard = PathWithGuard::new(path.to_path_buf());
+                let inner_config = linera_views::redb::RedbStoreInternalConfig { path_with_guard };
+                let config = linera_views::redb::RedbStoreConfig {
+                    inner_config,
+                    storage_cache_config: options.views_storage_cache_config(),
+                };
+                StoreConfig::Redb { config, namespace }
             }
             #[cfg(feature = "scylladb")]
             InnerStorageConfig::ScyllaDb { uri } => {
@@ -359,7 +441,7 @@ impl StorageConfig {
                     inner_config,
                     storage_cache_config: options.views_storage_cache_config(),
                 };
-                Ok(StoreConfig::ScyllaDb { config, namespace })
+                StoreConfig::ScyllaDb { config, namespace }
             }
             #[cfg(all(feature = "rocksdb", feature = "scylladb"))]
             InnerStorageConfig::DualRocksDbScyllaDb {
@@ -392,7 +474,7 @@ impl StorageConfig {
                     first_config,
                     second_config,
                 };
-                Ok(StoreConfig::DualRocksDbScyllaDb { config, namespace })
+                StoreConfig::DualRocksDbScyllaDb { config, namespace }
             }
         }
     }
@@ -414,6 +496,10 @@ impl fmt::Display for StorageConfig {
                 let spawn_mode = spawn_mode.to_string();
                 write!(f, "rocksdb:{}:{}:{}", path.display(), spawn_mode, namespace)
             }
+            #[cfg(feature = "redb")]
+            InnerStorageConfig::Redb { path } => {
+                write!(f, "redb:{}:{}", path.display(), namespace)
+            }
             #[cfg(feature = "scylladb")]
             InnerStorageConfig::ScyllaDb { uri } => {
                 write!(f, "scylladb:tcp:{uri}:{namespace}")
@@ -512,6 +598,90 @@ fn test_rocks_db_storage_config_from_str() {
     );
 }
 
+#[cfg(feature = "redb")]
+#[test]
+fn test_redb_storage_config_from_str() {
+    assert!(StorageConfig::from_str("redb:").is_err());
+    assert!(StorageConfig::from_str("redb:foo:bar:baz").is_err());
+    assert_eq!(
+        StorageConfig::from_str("redb:foo").unwrap(),
+        StorageConfig {
+            inner_storage_config: InnerStorageConfig::Redb { path: "foo".into() },
+            namespace: DEFAULT_NAMESPACE.to_string()
+        }
+    );
+    let config = StorageConfig::from_str("redb:foo:chosen_namespace").unwrap();
+    assert_eq!(
+        config,
+        StorageConfig {
+            inner_storage_config: InnerStorageConfig::Redb { path: "foo".into() },
+            namespace: "chosen_namespace".into()
+        }
+    );
+    assert_eq!(config.to_string(), "redb:foo:chosen_namespace");
+}
+
+#[cfg(all(feature = "encryption", feature = "redb"))]
+#[test]
+fn test_encrypted_store_config() {
+    use clap::Parser as _;
+
+    let options = CommonStorageOptions::parse_from([
+        "test",
+        "--storage-encryption-key",
+        "env:STORAGE_KEY",
+        "--storage-previous-encryption-keys",
+        "file:old.key",
+        "--storage-encrypt-keys",
+    ]);
+    let config = StorageConfig::from_str("redb:foo").unwrap();
+    let StoreConfig::Encrypted { keys_config, inner } =
+        config.add_common_storage_options(&options).unwrap()
+    else {
+        panic!("the store should be encrypted");
+    };
+    assert_eq!(keys_config.key.to_string(), "env:STORAGE_KEY");
+    assert_eq!(keys_config.previous_keys.len(), 1);
+    assert!(keys_config.encrypt_keys);
+    assert!(matches!(*inner, StoreConfig::Redb { .. }));
+
+    let config = StorageConfig::from_str("memory:path/to/genesis.json").unwrap();
+    assert!(config.add_common_storage_options(&options).is_err());
+    let options = CommonStorageOptions::with_defaults();
+    let config = StorageConfig::from_str("redb:foo").unwrap();
+    assert!(matches!(
+        config.add_common_storage_options(&options).unwrap(),
+        StoreConfig::Redb { .. }
+    ));
+}
+
+#[cfg(all(feature = "compression", feature = "redb"))]
+#[test]
+fn test_compressed_store_config() {
+    use clap::Parser as _;
+    use linera_views::compression::CompressionAlgorithm;
+
+    let options = CommonStorageOptions::parse_from([
+        "test",
+        "--storage-compression",
+        "lz4",
+        "--storage-compression-threshold",
+        "1000",
+    ]);
+    let config = StorageConfig::from_str("redb:foo").unwrap();
+    let StoreConfig::Compressed { options, inner } =
+        config.add_common_storage_options(&options).unwrap()
+    else {
+        panic!("the store should be compressed");
+    };
+    assert_eq!(options.algorithm, CompressionAlgorithm::Lz4);
+    assert_eq!(options.threshold, 1000);
+    assert!(matches!(*inner, StoreConfig::Redb { .. }));
+    assert!(
+        CommonStorageOptions::try_parse_from(["test", "--storage-compression", "gzip"]).is_err()
+    );
+}
+
 #[cfg(feature = "scylladb")]
 #[test]
 fn test_scylla_db_storage_config_from_str() {
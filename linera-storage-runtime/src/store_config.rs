@@ -10,6 +10,12 @@ use linera_execution::WasmRuntime;
 use linera_storage::{DbStorage, Storage, StorageCacheConfig};
 #[cfg(feature = "storage-service")]
 use linera_storage_service::client::StorageServiceDatabase;
+#[cfg(feature = "compression")]
+use linera_views::compression::{CompressionConfig, CompressionDatabase, CompressionOptions};
+#[cfg(feature = "encryption")]
+use linera_views::encryption::{EncryptionConfig, EncryptionDatabase, EncryptionKeysConfig};
+#[cfg(feature = "redb")]
+use linera_views::redb::RedbDatabase;
 #[cfg(feature = "rocksdb")]
 use linera_views::rocks_db::RocksDbDatabase;
 #[cfg(feature = "scylladb")]
@@ -50,6 +56,14 @@ pub enum StoreConfig {
         /// The namespace used.
         namespace: String,
     },
+    /// The redb key value store
+    #[cfg(feature = "redb")]
+    Redb {
+        /// The store configuration.
+        config: linera_views::redb::RedbStoreConfig,
+        /// The namespace used.
+        namespace: String,
+    },
     /// The ScyllaDB key value store
     #[cfg(feature = "scylladb")]
     ScyllaDb {
@@ -69,6 +83,22 @@ pub enum StoreConfig {
         /// The namespace used.
         namespace: String,
     },
+    /// Any of the other key value stores, with its data encrypted
+    #[cfg(feature = "encryption")]
+    Encrypted {
+        /// The configuration of the encryption keys.
+        keys_config: EncryptionKeysConfig,
+        /// The configuration of the underlying store.
+        inner: Box<StoreConfig>,
+    },
+    /// Any of the other key value stores, possibly encrypted, with its values compressed
+    #[cfg(feature = "compression")]
+    Compressed {
+        /// The options of the compression.
+        options: CompressionOptions,
+        /// The configuration of the underlying store.
+        inner: Box<StoreConfig>,
+    },
 }
 
 /// A job that can be run against a high-level [`Storage`].
@@ -117,7 +147,7 @@ impl StoreConfig {
         job: Job,
     ) -> Result<Job::Output, anyhow::Error>
     where
-        Job: Runnable,
+        Job: Runnable + Send,
     {
         match self {
             StoreConfig::Memory {
@@ -162,6 +192,18 @@ impl StoreConfig {
                 .with_allow_application_logs(allow_application_logs);
                 Ok(job.run(storage).await)
             }
+            #[cfg(feature = "redb")]
+            StoreConfig::Redb { config, namespace } => {
+                let storage = DbStorage::<RedbDatabase, _>::connect(
+                    &config,
+                    &namespace,
+                    wasm_runtime,
+                    cache_sizes,
+                )
+                .await?
+                .with_allow_application_logs(allow_application_logs);
+                Ok(job.run(storage).await)
+            }
             #[cfg(feature = "scylladb")]
             StoreConfig::ScyllaDb { config, namespace } => {
                 let storage = DbStorage::<ScyllaDbDatabase, _>::connect(
@@ -185,17 +227,84 @@ impl StoreConfig {
                     .with_allow_application_logs(allow_application_logs);
                 Ok(job.run(storage).await)
             }
+            #[cfg(any(feature = "encryption", feature = "compression"))]
+            store_config => {
+                let job = StorageJob {
+                    wasm_runtime,
+                    allow_application_logs,
+                    job,
+                };
+                store_config.run_with_store(cache_sizes, job).await
+            }
         }
     }
 
     /// Connects to the configured key-value store and runs the given
     /// [`RunnableWithStore`] job against it.
-    #[allow(unused_variables)]
     pub async fn run_with_store<Job>(
         self,
         cache_sizes: StorageCacheConfig,
         job: Job,
     ) -> Result<Job::Output, anyhow::Error>
+    where
+        Job: RunnableWithStore + Send,
+    {
+        #[cfg(feature = "compression")]
+        if let StoreConfig::Compressed { options, inner } = self {
+            let job = CompressedStoreJob { options, job };
+            return inner.run_with_encrypted_store(cache_sizes, job).await;
+        }
+        self.run_with_encrypted_store(cache_sizes, job).await
+    }
+
+    /// Rewrites the encrypted data with the current encryption key, after which the
+    /// previous keys are no longer needed.
+    #[cfg(feature = "encryption")]
+    pub async fn rotate_encryption_keys(
+        self,
+        cache_sizes: StorageCacheConfig,
+    ) -> Result<(), anyhow::Error> {
+        // The compression happens before the encryption, so it is not affected.
+        #[cfg(feature = "compression")]
+        let store_config = match self {
+            StoreConfig::Compressed { inner, .. } => *inner,
+            store_config => store_config,
+        };
+        #[cfg(not(feature = "compression"))]
+        let store_config = self;
+        let StoreConfig::Encrypted { keys_config, inner } = store_config else {
+            return Err(anyhow!("The storage is not encrypted"));
+        };
+        let job = EncryptionKeyRotation { keys_config };
+        inner.run_with_inner_store(cache_sizes, job).await
+    }
+
+    /// Runs the given [`RunnableWithStore`] job against a store that is possibly encrypted,
+    /// but not compressed.
+    async fn run_with_encrypted_store<Job>(
+        self,
+        cache_sizes: StorageCacheConfig,
+        job: Job,
+    ) -> Result<Job::Output, anyhow::Error>
+    where
+        Job: RunnableWithStore + Send,
+    {
+        #[cfg(feature = "encryption")]
+        if let StoreConfig::Encrypted { keys_config, inner } = self {
+            let job = EncryptedStoreJob { keys_config, job };
+            return inner.run_with_inner_store(cache_sizes, job).await;
+        }
+        self.run_with_inner_store(cache_sizes, job).await
+    }
+
+    /// Runs the given [`RunnableWithStore`] job against a store that is not wrapped in
+    /// another one.
+    #[allow(unused_variables)]
+    async fn run_with_inner_store<Job>(
+        self,
+        cache_sizes: StorageCacheConfig,
+        job: Job,
+    ) -> Result<Job::Output, anyhow::Error>
     where
         Job: RunnableWithStore,
     {
@@ -211,6 +320,10 @@ impl StoreConfig {
             StoreConfig::RocksDb { config, namespace } => Ok(job
                 .run::<RocksDbDatabase>(config, namespace, cache_sizes)
                 .await?),
+            #[cfg(feature = "redb")]
+            StoreConfig::Redb { config, namespace } => Ok(job
+                .run::<RedbDatabase>(config, namespace, cache_sizes)
+                .await?),
             #[cfg(feature = "scylladb")]
             StoreConfig::ScyllaDb { config, namespace } => Ok(job
                 .run::<ScyllaDbDatabase>(config, namespace, cache_sizes)
@@ -223,10 +336,167 @@ impl StoreConfig {
                     cache_sizes,
                 )
                 .await?),
+            #[cfg(feature = "encryption")]
+            StoreConfig::Encrypted { .. } => {
+                Err(anyhow!("Cannot encrypt an encrypted store again"))
+            }
+            #[cfg(feature = "compression")]
+            StoreConfig::Compressed { .. } => Err(anyhow!(
+                "Cannot compress a compressed or encrypted store again"
+            )),
         }
     }
 }
 
+/// A [`RunnableWithStore`] job running a [`Runnable`] job against the storage built on
+/// the store.
+#[cfg(any(feature = "encryption", feature = "compression"))]
+struct StorageJob<Job> {
+    wasm_runtime: Option<WasmRuntime>,
+    allow_application_logs: bool,
+    job: Job,
+}
+
+#[cfg(any(feature = "encryption", feature = "compression"))]
+#[async_trait]
+impl<Job> RunnableWithStore for StorageJob<Job>
+where
+    Job: Runnable + Send,
+{
+    type Output = Job::Output;
+
+    async fn run<D>(
+        self,
+        config: D::Config,
+        namespace: String,
+        cache_sizes: StorageCacheConfig,
+    ) -> Result<Self::Output, anyhow::Error>
+    where
+        D: KeyValueDatabase + Clone + Send + Sync + 'static,
+        D::Store: KeyValueStore + Clone + Send + Sync + 'static,
+        D::Error: Send + Sync,
+    {
+        let storage =
+            DbStorage::<D, _>::connect(&config, &namespace, self.wasm_runtime, cache_sizes)
+                .await?
+                .with_allow_application_logs(self.allow_application_logs);
+        Ok(self.job.run(storage).await)
+    }
+}
+
+/// A [`RunnableWithStore`] job running another one against a compressed store.
+#[cfg(feature = "compression")]
+struct CompressedStoreJob<Job> {
+    options: CompressionOptions,
+    job: Job,
+}
+
+#[cfg(feature = "compression")]
+#[async_trait]
+impl<Job> RunnableWithStore for CompressedStoreJob<Job>
+where
+    Job: RunnableWithStore + Send,
+{
+    type Output = Job::Output;
+
+    async fn run<D>(
+        self,
+        config: D::Config,
+        namespace: String,
+        cache_sizes: StorageCacheConfig,
+    ) -> Result<Self::Output, anyhow::Error>
+    where
+        D: KeyValueDatabase + Clone + Send + Sync + 'static,
+        D::Store: KeyValueStore + Clone + Send + Sync + 'static,
+        D::Error: Send + Sync,
+    {
+        let config = CompressionConfig {
+            inner_config: config,
+            options: self.options,
+        };
+        self.job
+            .run::<CompressionDatabase<D>>(config, namespace, cache_sizes)
+            .await
+    }
+}
+
+/// A [`RunnableWithStore`] job running another one against an encrypted store.
+#[cfg(feature = "encryption")]
+struct EncryptedStoreJob<Job> {
+    keys_config: EncryptionKeysConfig,
+    job: Job,
+}
+
+#[cfg(feature = "encryption")]
+#[async_trait]
+impl<Job> RunnableWithStore for EncryptedStoreJob<Job>
+where
+    Job: RunnableWithStore + Send,
+{
+    type Output = Job::Output;
+
+    async fn run<D>(
+        self,
+        config: D::Config,
+        namespace: String,
+        cache_sizes: StorageCacheConfig,
+    ) -> Result<Self::Output, anyhow::Error>
+    where
+        D: KeyValueDatabase + Clone + Send + Sync + 'static,
+        D::Store: KeyValueStore + Clone + Send + Sync + 'static,
+        D::Error: Send + Sync,
+    {
+        let config = EncryptionConfig {
+            inner_config: config,
+            keys_config: self.keys_config,
+        };
+        self.job
+            .run::<EncryptionDatabase<D>>(config, namespace, cache_sizes)
+            .await
+    }
+}
+
+/// A [`RunnableWithStore`] job rewriting the data of an encrypted store with the current
+/// encryption key.
+#[cfg(feature = "encryption")]
+struct EncryptionKeyRotation {
+    keys_config: EncryptionKeysConfig,
+}
+
+#[cfg(feature = "encryption")]
+#[async_trait]
+impl RunnableWithStore for EncryptionKeyRotation {
+    type Output = ();
+
+    async fn run<D>(
+        self,
+        config: D::Config,
+        namespace: String,
+        _cache_sizes: StorageCacheConfig,
+    ) -> Result<Self::Output, anyhow::Error>
+    where
+        D: KeyValueDatabase + Clone + Send + Sync + 'static,
+        D::Store: KeyValueStore + Clone + Send + Sync + 'static,
+        D::Error: Send + Sync,
+    {
+        let config = EncryptionConfig {
+            inner_config: config,
+            keys_config: self.keys_config,
+        };
+        let database = EncryptionDatabase::<D>::connect(&config, &namespace).await?;
+        let mut root_keys = database.list_root_keys().await?;
+        // The data stored without a root key is not listed.
+        root_keys.push(Vec::new());
+        root_keys.sort();
+        root_keys.dedup();
+        for root_key in root_keys {
+            let store = database.open_exclusive(&root_key)?;
+            store.rotate_keys().await?;
+        }
+        Ok(())
+    }
+}
+
 /// A [`RunnableWithStore`] job that migrates the storage schema.
 pub struct StorageMigration;
 
@@ -19,7 +19,9 @@ use linera_views::store::TestKeyValueDatabase;
 use linera_views::{
     batch::{Batch, WriteOperation},
     lru_caching::LruCachingDatabase,
-    store::{KeyValueDatabase, ReadableKeyValueStore, WithError, WritableKeyValueStore},
+    store::{
+        self, KeyRange, KeyValueDatabase, ReadableKeyValueStore, WithError, WritableKeyValueStore,
+    },
 };
 use serde::de::DeserializeOwned;
 use tonic::transport::{Channel, Endpoint};
@@ -31,14 +33,15 @@ use crate::{
         KeyPrefix, StorageServiceStoreError, StorageServiceStoreInternalConfig, MAX_PAYLOAD_SIZE,
     },
     key_value_store::{
-        statement::Operation, storage_service_client::StorageServiceClient, KeyValue,
-        KeyValueAppend, ReplyContainsKey, ReplyContainsKeys, ReplyExistsNamespace,
-        ReplyFindKeyValuesByPrefix, ReplyFindKeysByPrefix, ReplyListAll, ReplyListRootKeys,
-        ReplyReadMultiValues, ReplyReadValue, ReplySpecificChunk, RequestContainsKey,
-        RequestContainsKeys, RequestCreateNamespace, RequestDeleteNamespace,
-        RequestExistsNamespace, RequestFindKeyValuesByPrefix, RequestFindKeysByPrefix,
-        RequestListRootKeys, RequestReadMultiValues, RequestReadValue, RequestSpecificChunk,
-        RequestWriteBatchExtended, Statement,
+        key_bound::Bound, statement::Operation, storage_service_client::StorageServiceClient,
+        KeyBound, KeyValue, KeyValueAppend, ReplyContainsKey, ReplyContainsKeys,
+        ReplyExistsNamespace, ReplyFindKeyValuesByPrefix, ReplyFindKeysByPrefix, ReplyListAll,
+        ReplyListRootKeys, ReplyReadMultiValues, ReplyReadValue, ReplySpecificChunk,
+        RequestContainsKey, RequestContainsKeys, RequestCreateNamespace, RequestDeleteNamespace,
+        RequestExistsNamespace, RequestFindKeyValuesByPrefix, RequestFindKeyValuesInRange,
+        RequestFindKeysByPrefix, RequestFindKeysInRange, RequestListRootKeys,
+        RequestReadMultiValues, RequestReadValue, RequestSpecificChunk, RequestWriteBatchExtended,
+        Statement,
     },
 };
 
@@ -274,6 +277,97 @@ impl ReadableKeyValueStore for StorageServiceStoreInternal {
             self.read_entries(message_index, num_chunks).await
         }
     }
+
+    async fn find_keys_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<Vec<u8>>, StorageServiceStoreError> {
+        ensure!(
+            key_prefix.len() <= MAX_KEY_SIZE,
+            StorageServiceStoreError::KeyTooLong
+        );
+        let mut full_key_prefix = self.start_key.clone();
+        full_key_prefix.extend(key_prefix);
+        let query = RequestFindKeysInRange {
+            key_prefix: full_key_prefix,
+            start: key_bound(&range.start),
+            end: key_bound(&range.end),
+            reverse: range.reverse,
+            limit: range.limit,
+        };
+        let request = tonic::Request::new(query);
+        let channel = self.channel.clone();
+        let mut client = StorageServiceClient::new(channel);
+        let _guard = self.acquire().await;
+        let response = client
+            .process_find_keys_in_range(request)
+            .make_sync()
+            .await?;
+        let response = response.into_inner();
+        let ReplyFindKeysByPrefix {
+            keys,
+            message_index,
+            num_chunks,
+        } = response;
+        if num_chunks == 0 {
+            Ok(keys)
+        } else {
+            self.read_entries(message_index, num_chunks).await
+        }
+    }
+
+    async fn find_key_values_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, StorageServiceStoreError> {
+        ensure!(
+            key_prefix.len() <= MAX_KEY_SIZE,
+            StorageServiceStoreError::KeyTooLong
+        );
+        let mut full_key_prefix = self.start_key.clone();
+        full_key_prefix.extend(key_prefix);
+        let query = RequestFindKeyValuesInRange {
+            key_prefix: full_key_prefix,
+            start: key_bound(&range.start),
+            end: key_bound(&range.end),
+            reverse: range.reverse,
+            limit: range.limit,
+        };
+        let request = tonic::Request::new(query);
+        let channel = self.channel.clone();
+        let mut client = StorageServiceClient::new(channel);
+        let _guard = self.acquire().await;
+        let response = client
+            .process_find_key_values_in_range(request)
+            .make_sync()
+            .await?;
+        let response = response.into_inner();
+        let ReplyFindKeyValuesByPrefix {
+            key_values,
+            message_index,
+            num_chunks,
+        } = response;
+        if num_chunks == 0 {
+            let key_values = key_values
+                .into_iter()
+                .map(|x| (x.key, x.value))
+                .collect::<Vec<_>>();
+            Ok(key_values)
+        } else {
+            self.read_entries(message_index, num_chunks).await
+        }
+    }
+}
+
+fn key_bound(bound: &store::KeyBound) -> Option<KeyBound> {
+    let bound = match bound {
+        store::KeyBound::Unbounded => return None,
+        store::KeyBound::Included(key) => Bound::Included(key.clone()),
+        store::KeyBound::Excluded(key) => Bound::Excluded(key.clone()),
+    };
+    Some(KeyBound { bound: Some(bound) })
 }
 
 impl WritableKeyValueStore for StorageServiceStoreInternal {
@@ -8,7 +8,7 @@ use linera_storage_service::common::{KeyPrefix, MAX_PAYLOAD_SIZE};
 use linera_views::{
     batch::Batch,
     memory::{MemoryDatabase, MemoryStoreConfig},
-    store::{KeyValueDatabase, ReadableKeyValueStore, WritableKeyValueStore},
+    store::{self, KeyRange, KeyValueDatabase, ReadableKeyValueStore, WritableKeyValueStore},
 };
 #[cfg(with_rocksdb)]
 use linera_views::{
@@ -24,14 +24,16 @@ use tracing::{info, instrument};
 use tracing_subscriber::fmt::format::FmtSpan;
 
 use crate::key_value_store::{
+    key_bound::Bound,
     statement::Operation,
     storage_service_server::{StorageService, StorageServiceServer},
-    KeyValue, OptValue, ReplyContainsKey, ReplyContainsKeys, ReplyExistsNamespace,
+    KeyBound, KeyValue, OptValue, ReplyContainsKey, ReplyContainsKeys, ReplyExistsNamespace,
     ReplyFindKeyValuesByPrefix, ReplyFindKeysByPrefix, ReplyListAll, ReplyListRootKeys,
     ReplyReadMultiValues, ReplyReadValue, ReplySpecificChunk, RequestContainsKey,
     RequestContainsKeys, RequestCreateNamespace, RequestDeleteNamespace, RequestExistsNamespace,
-    RequestFindKeyValuesByPrefix, RequestFindKeysByPrefix, RequestListRootKeys,
-    RequestReadMultiValues, RequestReadValue, RequestSpecificChunk, RequestWriteBatchExtended,
+    RequestFindKeyValuesByPrefix, RequestFindKeyValuesInRange, RequestFindKeysByPrefix,
+    RequestFindKeysInRange, RequestListRootKeys, RequestReadMultiValues, RequestReadValue,
+    RequestSpecificChunk, RequestWriteBatchExtended,
 };
 
 pub mod key_value_store {
@@ -45,6 +47,14 @@ enum LocalStore {
     RocksDb(<RocksDbDatabase as KeyValueDatabase>::Store),
 }
 
+fn key_bound(bound: Option<KeyBound>) -> store::KeyBound {
+    match bound.and_then(|bound| bound.bound) {
+        None => store::KeyBound::Unbounded,
+        Some(Bound::Included(key)) => store::KeyBound::Included(key),
+        Some(Bound::Excluded(key)) => store::KeyBound::Excluded(key),
+    }
+}
+
 #[derive(Default)]
 struct BigRead {
     num_processed_chunks: usize,
@@ -159,6 +169,46 @@ impl StorageServer {
         }
     }
 
+    pub async fn find_keys_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<Vec<u8>>, Status> {
+        match &self.store {
+            LocalStore::Memory(store) => store
+                .find_keys_in_range(key_prefix, range)
+                .await
+                .map_err(|e| Status::unknown(format!("Memory error {e:?} at find_keys_in_range"))),
+            #[cfg(with_rocksdb)]
+            LocalStore::RocksDb(store) => store
+                .find_keys_in_range(key_prefix, range)
+                .await
+                .map_err(|e| Status::unknown(format!("RocksDB error {e:?} at find_keys_in_range"))),
+        }
+    }
+
+    pub async fn find_key_values_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Status> {
+        match &self.store {
+            LocalStore::Memory(store) => store
+                .find_key_values_in_range(key_prefix, range)
+                .await
+                .map_err(|e| {
+                    Status::unknown(format!("Memory error {e:?} at find_key_values_in_range"))
+                }),
+            #[cfg(with_rocksdb)]
+            LocalStore::RocksDb(store) => store
+                .find_key_values_in_range(key_prefix, range)
+                .await
+                .map_err(|e| {
+                    Status::unknown(format!("RocksDB error {e:?} at find_key_values_in_range"))
+                }),
+        }
+    }
+
     pub async fn write_batch(&self, batch: Batch) -> Result<(), Status> {
         match &self.store {
             LocalStore::Memory(store) => store
@@ -456,6 +506,92 @@ impl StorageService for StorageServer {
         Ok(Response::new(response))
     }
 
+    #[instrument(target = "store_server", skip_all, err, fields(key_prefix_len = ?request.get_ref().key_prefix.len()))]
+    async fn process_find_keys_in_range(
+        &self,
+        request: Request<RequestFindKeysInRange>,
+    ) -> Result<Response<ReplyFindKeysByPrefix>, Status> {
+        let request = request.into_inner();
+        let RequestFindKeysInRange {
+            key_prefix,
+            start,
+            end,
+            reverse,
+            limit,
+        } = request;
+        let range = KeyRange {
+            start: key_bound(start),
+            end: key_bound(end),
+            reverse,
+            limit,
+        };
+        let keys = self.find_keys_in_range(&key_prefix, &range).await?;
+        let size = keys.iter().map(|x| x.len()).sum::<usize>();
+        let response = if size < MAX_PAYLOAD_SIZE {
+            ReplyFindKeysByPrefix {
+                keys,
+                message_index: 0,
+                num_chunks: 0,
+            }
+        } else {
+            let (message_index, num_chunks) = self.insert_pending_read(keys).await;
+            ReplyFindKeysByPrefix {
+                keys: Vec::default(),
+                message_index,
+                num_chunks,
+            }
+        };
+        Ok(Response::new(response))
+    }
+
+    #[instrument(target = "store_server", skip_all, err, fields(key_prefix_len = ?request.get_ref().key_prefix.len()))]
+    async fn process_find_key_values_in_range(
+        &self,
+        request: Request<RequestFindKeyValuesInRange>,
+    ) -> Result<Response<ReplyFindKeyValuesByPrefix>, Status> {
+        let request = request.into_inner();
+        let RequestFindKeyValuesInRange {
+            key_prefix,
+            start,
+            end,
+            reverse,
+            limit,
+        } = request;
+        let range = KeyRange {
+            start: key_bound(start),
+            end: key_bound(end),
+            reverse,
+            limit,
+        };
+        let key_values = self.find_key_values_in_range(&key_prefix, &range).await?;
+        let size = key_values
+            .iter()
+            .map(|x| x.0.len() + x.1.len())
+            .sum::<usize>();
+        let response = if size < MAX_PAYLOAD_SIZE {
+            let key_values = key_values
+                .into_iter()
+                .map(|x| KeyValue {
+                    key: x.0,
+                    value: x.1,
+                })
+                .collect::<Vec<_>>();
+            ReplyFindKeyValuesByPrefix {
+                key_values,
+                message_index: 0,
+                num_chunks: 0,
+            }
+        } else {
+            let (message_index, num_chunks) = self.insert_pending_read(key_values).await;
+            ReplyFindKeyValuesByPrefix {
+                key_values: Vec::default(),
+                message_index,
+                num_chunks,
+            }
+        };
+        Ok(Response::new(response))
+    }
+
     #[instrument(target = "store_server", skip_all, err, fields(n_statements = ?request.get_ref().statements.len()))]
     async fn process_write_batch_extended(
         &self,
@@ -0,0 +1,119 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Verification of the consistency of the stored chains, e.g. after a validator crashed in
+//! the middle of a write.
+//!
+//! For every chain, the check verifies that no journal is left to be resolved, that the
+//! certificates of its blocks and the blobs they require are stored, that the hash of the
+//! execution state matches the state hash of the latest block, that the cursors of the
+//! inboxes and the heights in the outboxes are coherent, and that the indices of the
+//! non-empty inboxes and outboxes match their queues.
+//!
+//! Only pending journals and stale indices can be repaired locally. The other issues
+//! require the missing data to be downloaded again, or the chain to be re-executed.
+
+use linera_base::{
+    crypto::CryptoHash,
+    data_types::BlockHeight,
+    identifiers::{BlobId, ChainId},
+};
+use serde::{Deserialize, Serialize};
+
+/// An inconsistency found in the storage.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StorageIssue {
+    /// The state of the chain has a journal that was not resolved. The rest of the chain
+    /// is only checked once the journal is resolved.
+    PendingJournal {
+        /// The chain.
+        chain_id: ChainId,
+    },
+    /// The certificate of a block of the chain is not in storage.
+    MissingCertificate {
+        /// The chain.
+        chain_id: ChainId,
+        /// The height of the block.
+        height: BlockHeight,
+        /// The hash of the block.
+        hash: CryptoHash,
+    },
+    /// Some blobs required by a block of the chain are not in storage.
+    MissingBlobs {
+        /// The chain.
+        chain_id: ChainId,
+        /// The height of the block.
+        height: BlockHeight,
+        /// The missing blobs.
+        blob_ids: Vec<BlobId>,
+    },
+    /// The hash of the execution state differs from the state hash of the latest block.
+    StateHashMismatch {
+        /// The chain.
+        chain_id: ChainId,
+        /// The height of the latest block.
+        height: BlockHeight,
+        /// The state hash in the latest block.
+        expected: CryptoHash,
+        /// The hash of the stored execution state.
+        actual: CryptoHash,
+    },
+    /// The cursors of an inbox are not coherent with its queues.
+    InconsistentInbox {
+        /// The chain.
+        chain_id: ChainId,
+        /// The sender of the messages in the inbox.
+        origin: ChainId,
+        /// What is wrong with the inbox.
+        reason: String,
+    },
+    /// The heights in an outbox are not coherent.
+    InconsistentOutbox {
+        /// The chain.
+        chain_id: ChainId,
+        /// The recipient of the messages in the outbox.
+        target: ChainId,
+        /// What is wrong with the outbox.
+        reason: String,
+    },
+    /// The index of the non-empty inboxes does not match the inboxes.
+    InconsistentInboxIndex {
+        /// The chain.
+        chain_id: ChainId,
+    },
+    /// The indices of the non-empty outboxes and of the outgoing messages per height do
+    /// not match the outboxes.
+    InconsistentOutboxIndex {
+        /// The chain.
+        chain_id: ChainId,
+    },
+}
+
+/// What a consistency check found, and what it repaired.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageCheckReport {
+    /// The number of checked chains.
+    pub chains: u64,
+    /// The number of checked certificates.
+    pub certificates: u64,
+    /// The issues that remain in storage.
+    pub issues: Vec<StorageIssue>,
+    /// The issues that were repaired.
+    pub repaired: Vec<StorageIssue>,
+}
+
+impl StorageCheckReport {
+    /// Adds the counts and issues of another report to this one.
+    pub fn add(&mut self, other: StorageCheckReport) {
+        self.chains += other.chains;
+        self.certificates += other.certificates;
+        self.issues.extend(other.issues);
+        self.repaired.extend(other.repaired);
+    }
+
+    /// Returns whether the storage is consistent, possibly after repairs.
+    pub fn is_consistent(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
@@ -2,8 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap},
     fmt::Debug,
+    ops::Range,
     sync::{Arc, OnceLock},
 };
 
@@ -32,7 +33,7 @@ use linera_views::{
     store::{
         KeyValueDatabase, KeyValueStore, ReadableKeyValueStore as _, WritableKeyValueStore as _,
     },
-    views::View,
+    views::{RootView as _, View},
     ViewError,
 };
 use serde::{Deserialize, Serialize};
@@ -44,7 +45,10 @@ use {
     std::cmp::Reverse,
 };
 
-use crate::{ChainRuntimeContext, Clock, Storage};
+use crate::{
+    ChainRuntimeContext, Clock, PruningConfig, PruningReport, Storage, StorageCheckReport,
+    StorageIssue,
+};
 
 /// Prometheus metrics for storage operations.
 #[cfg(with_metrics)]
@@ -284,6 +288,16 @@ const BLOCK_KEY: &[u8] = &[3];
 /// The key used for the network description.
 const NETWORK_DESCRIPTION_KEY: &[u8] = &[4];
 
+/// The key used for the height below which a chain has been pruned. The chain ID itself is
+/// contained in the root key.
+const PRUNED_BELOW_KEY: &[u8] = &[5];
+
+/// The number of block heights whose history is removed in a single batch when pruning.
+const PRUNING_CHUNK_SIZE: u64 = 100;
+
+/// The number of certificates read at once when checking the consistency of a chain.
+const CHECK_CHUNK_SIZE: usize = 100;
+
 fn get_block_keys() -> Vec<Vec<u8>> {
     vec![LITE_CERTIFICATE_KEY.to_vec(), BLOCK_KEY.to_vec()]
 }
@@ -539,6 +553,8 @@ pub enum RootKey {
     BlockByHeight(ChainId),
     /// The event-to-block-height index of a chain.
     EventBlockHeight(ChainId),
+    /// The pruning progress of a chain.
+    PruningProgress(ChainId),
 }
 
 const CHAIN_ID_TAG: u8 = 2;
@@ -1632,6 +1648,418 @@ where
         }
         Ok(event_ids)
     }
+
+    async fn prune(
+        &self,
+        config: &PruningConfig,
+        dry_run: bool,
+    ) -> Result<PruningReport, ViewError> {
+        let mut report = PruningReport::default();
+        for chain_id in self.list_chain_ids().await? {
+            let chain_report = self.prune_chain(chain_id, config, dry_run).await?;
+            report.add(&chain_report);
+        }
+        Ok(report)
+    }
+
+    async fn check(&self, repair: bool) -> Result<StorageCheckReport, ViewError> {
+        let mut report = StorageCheckReport::default();
+        for chain_id in self.list_chain_ids().await? {
+            let chain_report = self.check_chain(chain_id, repair).await?;
+            report.add(chain_report);
+        }
+        Ok(report)
+    }
+}
+
+impl<Database, C> DbStorage<Database, C>
+where
+    Database: KeyValueDatabase<
+            Store: KeyValueStore + Clone + linera_base::util::traits::AutoTraits + 'static,
+            Error: Send + Sync,
+        > + Clone
+        + linera_base::util::traits::AutoTraits
+        + 'static,
+    C: Clock + Clone + Send + Sync + 'static,
+{
+    /// Prunes the history of a chain below its floor, by at most
+    /// `config.max_blocks_per_pass` heights.
+    #[instrument(skip_all, fields(chain_id = %chain_id))]
+    async fn prune_chain(
+        &self,
+        chain_id: ChainId,
+        config: &PruningConfig,
+        dry_run: bool,
+    ) -> Result<PruningReport, ViewError> {
+        let mut report = PruningReport::default();
+        let chain = self.load_chain(chain_id).await?;
+        let Some(checkpoint_height) = *chain.latest_checkpoint_height.get() else {
+            return Ok(report);
+        };
+        let mut floor = checkpoint_height.0.saturating_sub(config.retention_blocks);
+        // The recipients of messages still in flight may need their blocks.
+        if let Some(height) = chain.outbox_counters.get().keys().next() {
+            floor = floor.min(height.0);
+        }
+        // So may the validators that some blocks have not been pushed to yet.
+        if let Some(height) = chain.exported_heights.get().values().min() {
+            floor = floor.min(height.0.saturating_add(1));
+        }
+        let progress_store = self
+            .database
+            .open_shared(&RootKey::PruningProgress(chain_id).bytes())?;
+        let pruned_below = progress_store
+            .read_value::<BlockHeight>(PRUNED_BELOW_KEY)
+            .await?
+            .unwrap_or_default()
+            .0;
+        let end = floor.min(pruned_below.saturating_add(config.max_blocks_per_pass));
+        let mut start = pruned_below;
+        while start < end {
+            let chunk_end = end.min(start.saturating_add(PRUNING_CHUNK_SIZE));
+            self.prune_heights(&chain, start..chunk_end, dry_run, &mut report)
+                .await?;
+            if !dry_run {
+                let mut batch = Batch::new();
+                batch.put_key_value(PRUNED_BELOW_KEY.to_vec(), &BlockHeight(chunk_end))?;
+                progress_store.write_batch(batch).await?;
+            }
+            start = chunk_end;
+        }
+        if !report.is_empty() {
+            report.chains = 1;
+        }
+        Ok(report)
+    }
+
+    /// Removes the certificates and the events under the readable floor of the given heights
+    /// of a chain, or only accounts for them in a dry run.
+    async fn prune_heights(
+        &self,
+        chain: &ChainStateView<<Self as Storage>::Context>,
+        heights: Range<u64>,
+        dry_run: bool,
+        report: &mut PruningReport,
+    ) -> Result<(), ViewError> {
+        let chain_id = chain.chain_id();
+        let heights = heights.map(BlockHeight).collect::<Vec<_>>();
+        let hashes = self
+            .read_certificate_hashes_by_heights(chain_id, &heights)
+            .await?;
+        let pruned = heights
+            .into_iter()
+            .zip(hashes)
+            .filter_map(|(height, hash)| Some((height, hash?)))
+            .collect::<Vec<_>>();
+        let blocks = self
+            .read_confirmed_blocks(pruned.iter().map(|(_, hash)| *hash))
+            .await?;
+        let mut deletions = BTreeMap::<Vec<u8>, BTreeSet<Vec<u8>>>::new();
+        let mut event_ids = Vec::new();
+        for ((height, hash), block) in pruned.iter().zip(blocks) {
+            deletions
+                .entry(RootKey::BlockByHeight(chain_id).bytes())
+                .or_default()
+                .insert(to_height_key(*height));
+            deletions.insert(
+                RootKey::BlockHash(*hash).bytes(),
+                get_block_keys().into_iter().collect(),
+            );
+            let Some(block) = block else {
+                continue;
+            };
+            report.certificates += 1;
+            let block = block.block();
+            for event in block.body.events.iter().flatten() {
+                event_ids.push(EventId {
+                    chain_id,
+                    stream_id: event.stream_id.clone(),
+                    index: event.index,
+                });
+            }
+        }
+
+        // Only the events under the readable floor of their stream are pruned.
+        let stream_ids = event_ids
+            .iter()
+            .map(|event_id| event_id.stream_id.clone())
+            .collect::<BTreeSet<_>>();
+        let counts = chain.next_expected_events.multi_get(&stream_ids).await?;
+        let first_indices = stream_ids
+            .into_iter()
+            .zip(counts)
+            .filter_map(|(stream_id, counts)| Some((stream_id, counts?.first_index)))
+            .collect::<BTreeMap<_, _>>();
+        event_ids.retain(|event_id| {
+            first_indices
+                .get(&event_id.stream_id)
+                .is_some_and(|first_index| event_id.index < *first_index)
+        });
+        for event_id in &event_ids {
+            let event_key = to_event_key(event_id);
+            deletions
+                .entry(RootKey::EventBlockHeight(chain_id).bytes())
+                .or_default()
+                .insert(event_key.clone());
+            deletions
+                .entry(RootKey::Event(chain_id).bytes())
+                .or_default()
+                .insert(event_key);
+        }
+
+        for (root_key, keys) in deletions {
+            let store = self.database.open_shared(&root_key)?;
+            let keys = keys.into_iter().collect::<Vec<_>>();
+            let values = store.read_multi_values_bytes(&keys).await?;
+            let mut batch = Batch::new();
+            for (key, value) in keys.into_iter().zip(values) {
+                let Some(value) = value else {
+                    continue;
+                };
+                if root_key[0] == EVENT_ID_TAG {
+                    report.events += 1;
+                }
+                report.bytes += (key.len() + value.len()) as u64;
+                batch.delete_key(key);
+            }
+            if !dry_run && !batch.is_empty() {
+                store.write_batch(batch).await?;
+            }
+        }
+
+        if !dry_run {
+            for (height, hash) in &pruned {
+                self.caches
+                    .block_hash_by_height
+                    .remove(&(chain_id, *height));
+                self.caches.certificate.remove(hash);
+                self.caches.certificate_raw.remove(hash);
+                self.caches.confirmed_block.remove(hash);
+            }
+            for event_id in &event_ids {
+                self.caches.event.remove(event_id);
+                self.caches.event_block_height.remove(event_id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the consistency of a chain and, with `repair`, fixes what can be fixed locally.
+    #[instrument(skip_all, fields(chain_id = %chain_id))]
+    async fn check_chain(
+        &self,
+        chain_id: ChainId,
+        repair: bool,
+    ) -> Result<StorageCheckReport, ViewError> {
+        let mut report = StorageCheckReport {
+            chains: 1,
+            ..StorageCheckReport::default()
+        };
+        // Loading the chain resolves its journal, so without `repair` we stop here.
+        let state_store = self
+            .database
+            .open_shared(&RootKey::ChainState(chain_id).bytes())?;
+        let has_journal = state_store.has_journal().await?;
+        if has_journal && !repair {
+            report
+                .issues
+                .push(StorageIssue::PendingJournal { chain_id });
+            return Ok(report);
+        }
+        let mut chain = self.load_chain(chain_id).await?;
+        if has_journal {
+            let issue = StorageIssue::PendingJournal { chain_id };
+            if !state_store.has_journal().await? {
+                report.repaired.push(issue);
+            } else {
+                report.issues.push(issue);
+                return Ok(report);
+            }
+        }
+
+        // The certificates below the pruning progress were removed on purpose, and the ones
+        // vouched for by a checkpoint are expected to be missing until they are received.
+        let pruned_below = self
+            .database
+            .open_shared(&RootKey::PruningProgress(chain_id).bytes())?
+            .read_value::<BlockHeight>(PRUNED_BELOW_KEY)
+            .await?
+            .unwrap_or_default();
+        let trusted_hashes = chain
+            .pre_checkpoint_block_trust
+            .indices()
+            .await?
+            .into_iter()
+            .collect::<BTreeSet<_>>();
+        let block_hashes = chain
+            .block_hashes
+            .index_values_in_range(pruned_below.., false, None)
+            .await?;
+        let tip_hash = chain.tip_state.get().block_hash;
+        let mut tip_state_hash = None;
+        for chunk in block_hashes.chunks(CHECK_CHUNK_SIZE) {
+            let hashes = chunk.iter().map(|(_, hash)| *hash).collect::<Vec<_>>();
+            let certificates = self.read_certificates(&hashes).await?;
+            for ((height, hash), certificate) in chunk.iter().zip(certificates) {
+                let Some(certificate) = certificate else {
+                    if !trusted_hashes.contains(hash) {
+                        report.issues.push(StorageIssue::MissingCertificate {
+                            chain_id,
+                            height: *height,
+                            hash: *hash,
+                        });
+                    }
+                    continue;
+                };
+                report.certificates += 1;
+                let block = certificate.block();
+                let blob_ids = block.required_blob_ids().into_iter().collect::<Vec<_>>();
+                let missing_blob_ids = self.missing_blobs(&blob_ids).await?;
+                if !missing_blob_ids.is_empty() {
+                    report.issues.push(StorageIssue::MissingBlobs {
+                        chain_id,
+                        height: *height,
+                        blob_ids: missing_blob_ids,
+                    });
+                }
+                if tip_hash == Some(*hash) {
+                    tip_state_hash = Some((*height, block.header.state_hash));
+                }
+            }
+        }
+        if let Some((height, expected)) = tip_state_hash {
+            let actual = chain.execution_state.crypto_hash_mut().await?;
+            if actual != expected {
+                report.issues.push(StorageIssue::StateHashMismatch {
+                    chain_id,
+                    height,
+                    expected,
+                    actual,
+                });
+            }
+        }
+
+        let mut nonempty_inboxes = BTreeSet::new();
+        for origin in chain.inboxes.indices().await? {
+            let Some(inbox) = chain.inboxes.try_load_entry(&origin).await? else {
+                continue;
+            };
+            let next_cursor_to_add = *inbox.next_cursor_to_add.get();
+            let next_cursor_to_remove = *inbox.next_cursor_to_remove.get();
+            let added_cursors = inbox
+                .added_bundles
+                .elements()
+                .await?
+                .iter()
+                .map(|bundle| bundle.cursor())
+                .collect::<Vec<_>>();
+            let removed_cursors = inbox
+                .removed_bundles
+                .elements()
+                .await?
+                .iter()
+                .map(|bundle| bundle.cursor())
+                .collect::<Vec<_>>();
+            if !added_cursors.is_empty() {
+                nonempty_inboxes.insert(origin);
+            }
+            let reason = if !added_cursors.is_empty() && !removed_cursors.is_empty() {
+                Some("both added and removed bundles are pending".to_string())
+            } else if !added_cursors.is_sorted_by(|a, b| a < b)
+                || !removed_cursors.is_sorted_by(|a, b| a < b)
+            {
+                Some("the cursors of the bundles are not increasing".to_string())
+            } else if added_cursors
+                .iter()
+                .any(|cursor| *cursor < next_cursor_to_remove || *cursor >= next_cursor_to_add)
+            {
+                Some(format!(
+                    "an added bundle is not between the next cursor to remove \
+                    {next_cursor_to_remove:?} and the next cursor to add {next_cursor_to_add:?}"
+                ))
+            } else if removed_cursors
+                .iter()
+                .any(|cursor| *cursor < next_cursor_to_add || *cursor >= next_cursor_to_remove)
+            {
+                Some(format!(
+                    "a removed bundle is not between the next cursor to add \
+                    {next_cursor_to_add:?} and the next cursor to remove {next_cursor_to_remove:?}"
+                ))
+            } else {
+                None
+            };
+            if let Some(reason) = reason {
+                report.issues.push(StorageIssue::InconsistentInbox {
+                    chain_id,
+                    origin,
+                    reason,
+                });
+            }
+        }
+
+        let mut nonempty_outboxes = BTreeSet::new();
+        let mut outbox_counters = BTreeMap::<BlockHeight, u32>::new();
+        for target in chain.outboxes.indices().await? {
+            let Some(outbox) = chain.outboxes.try_load_entry(&target).await? else {
+                continue;
+            };
+            let next_height_to_schedule = *outbox.next_height_to_schedule.get();
+            let heights = outbox.queue.elements().await?;
+            let reason = if !heights.is_sorted_by(|a, b| a < b) {
+                Some("the heights are not increasing".to_string())
+            } else if heights.last() >= Some(&next_height_to_schedule) {
+                Some(format!(
+                    "a height is not below the next height to schedule {next_height_to_schedule}"
+                ))
+            } else {
+                None
+            };
+            if let Some(reason) = reason {
+                report.issues.push(StorageIssue::InconsistentOutbox {
+                    chain_id,
+                    target,
+                    reason,
+                });
+            }
+            if !heights.is_empty() {
+                nonempty_outboxes.insert(target);
+            }
+            for height in heights {
+                *outbox_counters.entry(height).or_default() += 1;
+            }
+        }
+
+        let mut repaired_indices = false;
+        if *chain.nonempty_inboxes.get() != nonempty_inboxes {
+            let issue = StorageIssue::InconsistentInboxIndex { chain_id };
+            if repair {
+                chain.nonempty_inboxes.set(nonempty_inboxes);
+                report.repaired.push(issue);
+                repaired_indices = true;
+            } else {
+                report.issues.push(issue);
+            }
+        }
+        // On clients, the outbox indices only cover the tracked chains.
+        if chain.outbox_index_tracked_hash.get().is_none()
+            && (*chain.nonempty_outboxes.get() != nonempty_outboxes
+                || **chain.outbox_counters.get() != outbox_counters)
+        {
+            let issue = StorageIssue::InconsistentOutboxIndex { chain_id };
+            if repair {
+                chain.nonempty_outboxes.set(nonempty_outboxes);
+                chain.outbox_counters.set(outbox_counters.into());
+                report.repaired.push(issue);
+                repaired_indices = true;
+            } else {
+                report.issues.push(issue);
+            }
+        }
+        if repaired_indices {
+            chain.save().await?;
+        }
+        Ok(report)
+    }
 }
 
 impl<Database, C> DbStorage<Database, C>
@@ -1820,7 +2248,7 @@ where
 mod tests {
     use linera_base::{
         crypto::{CryptoHash, TestString},
-        data_types::{Amount, Blob, BlobContent, BlockHeight, Event, OracleResponse, Round},
+        data_types::{Amount, Blob, BlobContent, BlockHeight, Epoch, Event, OracleResponse, Round},
         identifiers::{
             Account, AccountOwner, ApplicationId, BlobId, BlobType, ChainId, EventId,
             GenericApplicationId, StreamId, StreamName,
@@ -1831,14 +2259,18 @@ mod tests {
         data_types::{OperationResult, Transaction},
         test::BlockBuilder,
         types::ConfirmedBlockCertificate,
+        StreamCounts,
     };
     use linera_execution::{
         system::{SystemMessage, SystemOperation},
-        Message, MessageKind, Operation, OutgoingMessage,
+        BlobOrigin, BlobState, Message, MessageKind, Operation, OutgoingMessage,
     };
     use linera_views::{
+        batch::Batch,
+        journaling::JOURNAL_TAG,
         memory::MemoryDatabase,
-        store::{KeyValueDatabase, ReadableKeyValueStore as _},
+        store::{KeyValueDatabase, ReadableKeyValueStore as _, WritableKeyValueStore as _},
+        views::RootView as _,
     };
 
     use crate::{
@@ -1846,7 +2278,7 @@ mod tests {
             to_event_key, to_height_key, MultiPartitionBatch, RootKey, BLOB_ID_TAG, CHAIN_ID_TAG,
             EVENT_ID_TAG,
         },
-        DbStorage, Storage, TestClock,
+        DbStorage, PruningConfig, Storage, StorageIssue, TestClock,
     };
 
     /// Builds a block populated with one item of each body kind, with values derived from the
@@ -2167,4 +2599,177 @@ mod tests {
             cert_by_height.value().block().header
         );
     }
+
+    #[cfg(with_testing)]
+    #[tokio::test]
+    async fn test_prune_below_checkpoint() {
+        let storage = DbStorage::<MemoryDatabase, TestClock>::make_test_storage(None).await;
+        let chain_id = ChainId(CryptoHash::test_hash("test_chain"));
+
+        // Every block emits event 0 of the same stream and creates the same data blob.
+        let mut batch = MultiPartitionBatch::new();
+        let mut hashes = Vec::new();
+        for height in 0..10 {
+            let block = populated_block(chain_id, height);
+            let cert =
+                ConfirmedBlockCertificate::new(ConfirmedBlock::new(block), Round::Fast, vec![]);
+            hashes.push(cert.hash());
+            batch.add_certificate(&cert).unwrap();
+        }
+        storage.write_batch(batch).await.unwrap();
+        let block = populated_block(chain_id, 0);
+        let event = block.body.events[0][0].clone();
+        let event_id = EventId {
+            chain_id,
+            stream_id: event.stream_id.clone(),
+            index: event.index,
+        };
+        storage
+            .write_events([(event_id.clone(), event.value)])
+            .await
+            .unwrap();
+        let blob = block.body.blobs[0][0].clone();
+        storage.write_blob(&blob).await.unwrap();
+        let blob_state = BlobState {
+            origin: BlobOrigin::Published {
+                chain_id,
+                block_height: BlockHeight(0),
+            },
+            last_used_by: Some(hashes[3]),
+            epoch: Some(Epoch::ZERO),
+        };
+        storage
+            .maybe_write_blob_states(&[blob.id()], blob_state)
+            .await
+            .unwrap();
+
+        // With a checkpoint at height 8 and a retention of 2 blocks, heights 0 to 5 go.
+        let mut chain = storage.load_chain(chain_id).await.unwrap();
+        chain.latest_checkpoint_height.set(Some(BlockHeight(8)));
+        let counts = StreamCounts {
+            first_index: 1,
+            next_index: 1,
+        };
+        chain
+            .next_expected_events
+            .insert(&event.stream_id, counts)
+            .unwrap();
+        chain.save().await.unwrap();
+        let config = PruningConfig {
+            retention_blocks: 2,
+            max_blocks_per_pass: 4,
+            ..PruningConfig::default()
+        };
+
+        let dry_run_report = storage
+            .prune(
+                &PruningConfig {
+                    max_blocks_per_pass: u64::MAX,
+                    ..config
+                },
+                true,
+            )
+            .await
+            .unwrap();
+        assert_eq!(dry_run_report.chains, 1);
+        assert_eq!(dry_run_report.certificates, 6);
+        assert_eq!(dry_run_report.events, 1);
+        assert!(storage.read_certificate(hashes[0]).await.unwrap().is_some());
+
+        // The pruning is incremental, and the passes add up to the dry run.
+        let mut report = storage.prune(&config, false).await.unwrap();
+        assert_eq!(report.certificates, 4);
+        report.add(&storage.prune(&config, false).await.unwrap());
+        report.chains = 1;
+        assert_eq!(report, dry_run_report);
+        assert!(storage.prune(&config, false).await.unwrap().is_empty());
+
+        let heights = (0..10).map(BlockHeight).collect::<Vec<_>>();
+        let certificates = storage
+            .read_certificates_by_heights(chain_id, &heights)
+            .await
+            .unwrap();
+        for (height, certificate) in certificates.iter().enumerate() {
+            assert_eq!(certificate.is_some(), height >= 6);
+        }
+        assert!(storage.read_certificate(hashes[0]).await.unwrap().is_none());
+        assert!(storage.read_event(event_id).await.unwrap().is_none());
+        // Other chains may still use the blobs of the pruned blocks, so they are kept.
+        assert!(storage.read_blob(blob.id()).await.unwrap().is_some());
+        assert!(storage.read_blob_state(blob.id()).await.unwrap().is_some());
+    }
+
+    #[cfg(with_testing)]
+    #[tokio::test]
+    async fn test_check_storage() {
+        let storage = DbStorage::<MemoryDatabase, TestClock>::make_test_storage(None).await;
+        let chain_id = ChainId(CryptoHash::test_hash("test_chain"));
+        let other_chain_id = ChainId(CryptoHash::test_hash("other_chain"));
+
+        // The certificate at height 3 is missing, and the inbox index refers to an inbox
+        // without any bundle. Blocks at height 0 require the chain description, so we start
+        // at height 1.
+        let mut batch = MultiPartitionBatch::new();
+        let mut hashes = Vec::new();
+        for height in 1..4 {
+            let block = populated_block(chain_id, height);
+            for blob in block.body.blobs.iter().flatten() {
+                storage.write_blob(blob).await.unwrap();
+            }
+            let cert =
+                ConfirmedBlockCertificate::new(ConfirmedBlock::new(block), Round::Fast, vec![]);
+            hashes.push(cert.hash());
+            if height < 3 {
+                batch.add_certificate(&cert).unwrap();
+            }
+        }
+        storage.write_batch(batch).await.unwrap();
+        let mut chain = storage.load_chain(chain_id).await.unwrap();
+        for (height, hash) in (1..).zip(&hashes) {
+            chain
+                .block_hashes
+                .insert(&BlockHeight(height), *hash)
+                .unwrap();
+        }
+        chain.nonempty_inboxes.get_mut().insert(other_chain_id);
+        chain.save().await.unwrap();
+
+        let missing_certificate = StorageIssue::MissingCertificate {
+            chain_id,
+            height: BlockHeight(3),
+            hash: hashes[2],
+        };
+        let inconsistent_index = StorageIssue::InconsistentInboxIndex { chain_id };
+        let report = storage.check(false).await.unwrap();
+        assert_eq!(report.chains, 1);
+        assert_eq!(report.certificates, 2);
+        assert_eq!(
+            report.issues,
+            vec![missing_certificate.clone(), inconsistent_index.clone()]
+        );
+        assert!(report.repaired.is_empty());
+
+        // Only the index can be repaired.
+        let report = storage.check(true).await.unwrap();
+        assert_eq!(report.issues, vec![missing_certificate.clone()]);
+        assert_eq!(report.repaired, vec![inconsistent_index]);
+        let report = storage.check(false).await.unwrap();
+        assert_eq!(report.issues, vec![missing_certificate]);
+        assert!(report.repaired.is_empty());
+
+        // A chain with a pending journal is not checked any further.
+        let store = storage
+            .database
+            .open_shared(&RootKey::ChainState(chain_id).bytes())
+            .unwrap();
+        let mut batch = Batch::new();
+        batch.put_key_value_bytes(vec![JOURNAL_TAG, 1, 0, 0, 0, 0], vec![0]);
+        store.write_batch(batch).await.unwrap();
+        let report = storage.check(false).await.unwrap();
+        assert_eq!(report.certificates, 0);
+        assert_eq!(
+            report.issues,
+            vec![StorageIssue::PendingJournal { chain_id }]
+        );
+    }
 }
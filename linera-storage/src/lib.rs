@@ -5,7 +5,9 @@
 
 #![deny(missing_docs)]
 
+mod check;
 mod db_storage;
+mod pruning;
 
 use std::sync::Arc as StdArc;
 
@@ -26,6 +28,8 @@ use linera_chain::{
     types::{ConfirmedBlock, ConfirmedBlockCertificate},
     ChainError, ChainStateView,
 };
+#[cfg(all(with_wasm_runtime, not(web)))]
+use linera_execution::ProfiledWasmContractModule;
 use linera_execution::{
     committee::Committee, BlobState, ExecutionError, ExecutionRuntimeConfig,
     ExecutionRuntimeContext, SharedCommittees, TransactionTracker, UserContractCode,
@@ -40,6 +44,7 @@ use linera_execution::{
 use linera_execution::{WasmContractModule, WasmServiceModule};
 use linera_views::{context::Context, views::RootView, ViewError};
 
+pub use crate::check::{StorageCheckReport, StorageIssue};
 #[cfg(with_metrics)]
 pub use crate::db_storage::metrics;
 pub use crate::db_storage::{
@@ -47,6 +52,10 @@ pub use crate::db_storage::{
 };
 #[cfg(with_testing)]
 pub use crate::db_storage::{TestClock, DEFAULT_STORAGE_CACHE_CONFIG};
+pub use crate::pruning::{
+    run_pruning, PruningConfig, PruningReport, DEFAULT_MAX_PRUNED_BLOCKS_PER_PASS,
+    DEFAULT_PRUNING_INTERVAL, DEFAULT_PRUNING_RETENTION_BLOCKS,
+};
 
 /// The default namespace to be used when none is specified
 pub const DEFAULT_NAMESPACE: &str = "default";
@@ -326,6 +335,15 @@ pub trait Storage: linera_base::util::traits::AutoTraits + Sized {
                         let Some(wasm_runtime) = self.wasm_runtime() else {
                             panic!("A Wasm runtime is required to load user applications.");
                         };
+                        #[cfg(not(web))]
+                        if let Some(wasm_profile) = txn_tracker.wasm_profile() {
+                            return Ok(ProfiledWasmContractModule::new(
+                                contract_bytecode,
+                                wasm_runtime,
+                                wasm_profile.clone(),
+                            )?
+                            .into());
+                        }
                         Ok(WasmContractModule::new(contract_bytecode, wasm_runtime)
                            .await?
                            .into())
@@ -507,6 +525,18 @@ pub trait Storage: linera_base::util::traits::AutoTraits + Sized {
 
     /// Lists the event IDs in storage.
     async fn list_event_ids(&self) -> Result<Vec<EventId>, ViewError>;
+
+    /// Prunes the history of every chain below its floor, as described in
+    /// [`PruningConfig`]. In a dry run, nothing is removed and the report says what would be.
+    async fn prune(
+        &self,
+        config: &PruningConfig,
+        dry_run: bool,
+    ) -> Result<PruningReport, ViewError>;
+
+    /// Checks the consistency of every chain, as described in [`StorageIssue`]. With
+    /// `repair`, the issues that can be fixed locally are fixed, and reported as repaired.
+    async fn check(&self, repair: bool) -> Result<StorageCheckReport, ViewError>;
 }
 
 /// The result of processing the obtained read certificates.
@@ -577,6 +607,11 @@ impl<S: Storage> ExecutionRuntimeContext for ChainRuntimeContext<S> {
         description: &ApplicationDescription,
         txn_tracker: &TransactionTracker,
     ) -> Result<UserContractCode, ExecutionError> {
+        // Profiled contracts are instrumented differently, so they are neither taken from
+        // nor added to the cache.
+        if txn_tracker.wasm_profile().is_some() {
+            return self.storage.load_contract(description, txn_tracker).await;
+        }
         let application_id = description.into();
         let pinned = self.user_contracts.pin_owned();
         if let Some(contract) = pinned.get(&application_id) {
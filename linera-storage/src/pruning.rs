@@ -0,0 +1,96 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Removal of the chain history that is no longer needed once a chain has a checkpoint.
+//!
+//! A chain's history is pruned below its *floor*: the height of its latest checkpoint minus
+//! the retention window, but never above a block with outgoing messages still in flight, nor
+//! above a block that has not yet been pushed to every validator. Below the floor, pruning
+//! removes the certificates and confirmed blocks, and the events under each stream's readable
+//! floor ([`StreamCounts::first_index`]). Blobs are kept: the unpruned blocks of any chain
+//! may use them, and nothing counts these references yet.
+//!
+//! [`StreamCounts::first_index`]: linera_chain::StreamCounts::first_index
+
+use linera_base::time::Duration;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::{Clock as _, Storage};
+
+/// The default number of blocks kept below the latest checkpoint of a chain.
+pub const DEFAULT_PRUNING_RETENTION_BLOCKS: u64 = 1000;
+
+/// The default number of blocks pruned per chain in each pass of the background pruner.
+pub const DEFAULT_MAX_PRUNED_BLOCKS_PER_PASS: u64 = 1000;
+
+/// The default interval between two passes of the background pruner.
+pub const DEFAULT_PRUNING_INTERVAL: Duration = Duration::from_secs(600);
+
+/// The configuration of the pruning of chain histories.
+#[derive(Clone, Copy, Debug)]
+pub struct PruningConfig {
+    /// The number of blocks to keep below the latest checkpoint of each chain.
+    pub retention_blocks: u64,
+    /// The maximum number of block heights to prune per chain in a single pass.
+    pub max_blocks_per_pass: u64,
+    /// The interval between two passes of the background pruner.
+    pub interval: Duration,
+}
+
+impl Default for PruningConfig {
+    fn default() -> Self {
+        Self {
+            retention_blocks: DEFAULT_PRUNING_RETENTION_BLOCKS,
+            max_blocks_per_pass: DEFAULT_MAX_PRUNED_BLOCKS_PER_PASS,
+            interval: DEFAULT_PRUNING_INTERVAL,
+        }
+    }
+}
+
+/// What a pruning pass removed, or would remove in a dry run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PruningReport {
+    /// The number of chains with some pruned history.
+    pub chains: u64,
+    /// The number of certificates, together with their confirmed blocks.
+    pub certificates: u64,
+    /// The number of events.
+    pub events: u64,
+    /// The total size of the removed keys and values, in bytes.
+    pub bytes: u64,
+}
+
+impl PruningReport {
+    /// Adds the counts of another report to this one.
+    pub fn add(&mut self, other: &PruningReport) {
+        self.chains += other.chains;
+        self.certificates += other.certificates;
+        self.events += other.events;
+        self.bytes += other.bytes;
+    }
+
+    /// Returns whether nothing was pruned.
+    pub fn is_empty(&self) -> bool {
+        self.certificates == 0 && self.events == 0
+    }
+}
+
+/// Prunes the storage periodically, until the returned future is dropped.
+///
+/// Passes are incremental: each one prunes at most
+/// [`max_blocks_per_pass`](PruningConfig::max_blocks_per_pass) heights of each chain. A
+/// single process should run the pruner for a given storage.
+pub async fn run_pruning<S: Storage>(storage: S, config: PruningConfig) {
+    loop {
+        match storage.prune(&config, false).await {
+            Ok(report) if !report.is_empty() => info!(
+                "Pruned {} certificates and {} events in {} chains ({} bytes)",
+                report.certificates, report.events, report.chains, report.bytes
+            ),
+            Ok(_) => {}
+            Err(error) => warn!("Failed to prune the storage: {error}"),
+        }
+        storage.clock().sleep_for(config.interval).await;
+    }
+}
@@ -327,13 +327,21 @@ fn generate_hash_view_code(input: &ItemStruct) -> Result<TokenStream2, Error> {
     } = Constraints::get(input);
     let struct_name = &input.ident;
 
-    let field_types = input.fields.iter().map(|field| &field.ty);
+    let field_types = input
+        .fields
+        .iter()
+        .map(|field| &field.ty)
+        .collect::<Vec<_>>();
     let mut field_hashes_mut = Vec::new();
     let mut field_hashes = Vec::new();
+    let mut field_names = Vec::new();
+    let mut field_hash_values = Vec::new();
     for e in &input.fields {
         let name = e.ident.as_ref().unwrap();
         field_hashes_mut.push(quote! { hasher.write_all(self.#name.hash_mut().await?.as_ref())?; });
         field_hashes.push(quote! { hasher.write_all(self.#name.hash().await?.as_ref())?; });
+        field_names.push(name.to_string());
+        field_hash_values.push(quote! { self.#name.hash().await? });
     }
 
     Ok(quote! {
@@ -361,6 +369,20 @@ fn generate_hash_view_code(input: &ItemStruct) -> Result<TokenStream2, Error> {
                 Ok(hasher.finalize())
             }
         }
+
+        impl #impl_generics linera_views::proof::HashableFields for #struct_name #type_generics
+        where
+            #(#field_types: linera_views::views::HashableView<Hasher = linera_views::sha3::Sha3_256>,)*
+            #(#input_constraints,)*
+            Self: linera_views::views::View,
+        {
+            const FIELD_NAMES: &'static [&'static str] = &[#(#field_names),*];
+
+            async fn field_hashes(&self) -> Result<Vec<linera_views::common::HasherOutput>, linera_views::ViewError> {
+                use linera_views::views::HashableView as _;
+                Ok(vec![#(#field_hash_values),*])
+            }
+        }
     })
 }
 
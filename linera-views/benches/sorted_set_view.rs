@@ -0,0 +1,229 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use linera_base::time::{Duration, Instant};
+#[cfg(with_rocksdb)]
+use linera_views::rocks_db::RocksDbDatabase;
+#[cfg(with_scylladb)]
+use linera_views::scylla_db::ScyllaDbDatabase;
+use linera_views::{
+    context::ViewContext,
+    memory::MemoryDatabase,
+    random::{make_deterministic_rng, DeterministicRng},
+    sorted_set_view::SortedSetView,
+    store::{ReadableKeyValueStore, TestKeyValueDatabase, WritableKeyValueStore},
+    views::{CryptoHashRootView, RootView, View},
+};
+use rand::Rng;
+use tokio::runtime::Runtime;
+
+/// The number of operations
+const N_OPERATIONS: usize = 1000;
+
+/// The number of distinct keys, so that some insertions update the score of a key.
+const N_KEYS: u32 = 200;
+
+enum Operations {
+    Save,
+    Insert(u32, u64),
+    Remove(u32),
+    PopMin,
+    PopMax,
+}
+
+fn generate_test_case(n_operation: usize, rng: &mut DeterministicRng) -> Vec<Operations> {
+    let mut operations = Vec::new();
+    for _ in 0..n_operation {
+        let choice = rng.gen_range(0..10);
+        let operation = match choice {
+            0 => Operations::Save,
+            1 => Operations::Remove(rng.gen_range(0..N_KEYS)),
+            2 => Operations::PopMin,
+            3 => Operations::PopMax,
+            _ => Operations::Insert(rng.gen_range(0..N_KEYS), rng.gen()),
+        };
+        operations.push(operation);
+    }
+    operations
+}
+
+#[derive(CryptoHashRootView)]
+pub struct SortedSetStateView<C> {
+    pub set: SortedSetView<C, u32, u64>,
+}
+
+pub async fn performance_sorted_set_view<D: TestKeyValueDatabase + Clone + 'static>(
+    iterations: u64,
+) -> Duration
+where
+    D::Store: ReadableKeyValueStore + WritableKeyValueStore + Clone + 'static,
+{
+    let database = D::connect_test_namespace().await.unwrap();
+    let store = database.open_shared(&[]).unwrap();
+    let context = ViewContext::<(), D::Store>::create_root_context(store, ())
+        .await
+        .unwrap();
+    let mut total_time = Duration::ZERO;
+    let mut rng = make_deterministic_rng();
+    for _ in 0..iterations {
+        let operations = generate_test_case(N_OPERATIONS, &mut rng);
+        let mut view = SortedSetStateView::load(context.clone()).await.unwrap();
+        let measurement = Instant::now();
+        for operation in operations {
+            match operation {
+                Operations::Save => {
+                    view.save().await.unwrap();
+                }
+                Operations::Insert(key, score) => {
+                    view.set.insert(&key, score).await.unwrap();
+                }
+                Operations::Remove(key) => {
+                    black_box(view.set.remove(&key).await.unwrap());
+                }
+                Operations::PopMin => {
+                    black_box(view.set.pop_min().await.unwrap());
+                }
+                Operations::PopMax => {
+                    black_box(view.set.pop_max().await.unwrap());
+                }
+            }
+        }
+        view.clear();
+        view.save().await.unwrap();
+        total_time += measurement.elapsed();
+    }
+
+    total_time
+}
+
+fn bench_sorted_set_view(criterion: &mut Criterion) {
+    criterion.bench_function("memory_sorted_set_view", |bencher| {
+        bencher
+            .to_async(Runtime::new().expect("Failed to create Tokio runtime"))
+            .iter_custom(|iterations| async move {
+                performance_sorted_set_view::<MemoryDatabase>(iterations).await
+            })
+    });
+
+    #[cfg(with_rocksdb)]
+    criterion.bench_function("rocksdb_sorted_set_view", |bencher| {
+        bencher
+            .to_async(Runtime::new().expect("Failed to create Tokio runtime"))
+            .iter_custom(|iterations| async move {
+                performance_sorted_set_view::<RocksDbDatabase>(iterations).await
+            })
+    });
+
+    #[cfg(with_scylladb)]
+    criterion.bench_function("scylladb_sorted_set_view", |bencher| {
+        bencher
+            .to_async(Runtime::new().expect("Failed to create Tokio runtime"))
+            .iter_custom(|iterations| async move {
+                performance_sorted_set_view::<ScyllaDbDatabase>(iterations).await
+            })
+    });
+}
+
+/// Benchmark of the rank queries and of the reads of the best scores, in a set that is
+/// already saved in storage.
+const INITIAL_SET_SIZE: u32 = 10000;
+const N_QUERIES: u32 = 100;
+
+pub async fn queries_sorted_set_view<D: TestKeyValueDatabase + Clone + 'static>(
+    iterations: u64,
+) -> Duration
+where
+    D::Store: ReadableKeyValueStore + WritableKeyValueStore + Clone + 'static,
+{
+    let mut total_time = Duration::ZERO;
+    let mut rng = make_deterministic_rng();
+
+    for _ in 0..iterations {
+        let database = D::connect_test_namespace().await.unwrap();
+        let store = database.open_shared(&[]).unwrap();
+        let context = ViewContext::<(), D::Store>::create_root_context(store, ())
+            .await
+            .unwrap();
+
+        let mut view = SortedSetStateView::load(context.clone()).await.unwrap();
+        for key in 0..INITIAL_SET_SIZE {
+            view.set.insert(&key, rng.gen()).await.unwrap();
+        }
+        view.save().await.unwrap();
+
+        let measurement = Instant::now();
+        for _ in 0..N_QUERIES {
+            let key = rng.gen_range(0..INITIAL_SET_SIZE);
+            black_box(view.set.rank(&key).await.unwrap());
+            black_box(view.set.range(.., true, Some(10)).await.unwrap());
+        }
+        total_time += measurement.elapsed();
+
+        view.clear();
+        view.save().await.unwrap();
+    }
+
+    total_time
+}
+
+/// Returns the number of bytes, keys and values included, that a set of
+/// `INITIAL_SET_SIZE` keys occupies in storage.
+pub async fn storage_sorted_set_view<D: TestKeyValueDatabase + Clone + 'static>() -> usize
+where
+    D::Store: ReadableKeyValueStore + WritableKeyValueStore + Clone + 'static,
+{
+    let mut rng = make_deterministic_rng();
+    let database = D::connect_test_namespace().await.unwrap();
+    let store = database.open_shared(&[]).unwrap();
+    let context = ViewContext::<(), D::Store>::create_root_context(store.clone(), ())
+        .await
+        .unwrap();
+
+    let mut view = SortedSetStateView::load(context).await.unwrap();
+    for key in 0..INITIAL_SET_SIZE {
+        view.set.insert(&key, rng.gen()).await.unwrap();
+    }
+    view.save().await.unwrap();
+
+    let size = store
+        .find_key_values_by_prefix(&[])
+        .await
+        .unwrap()
+        .iter()
+        .map(|(key, value)| key.len() + value.len())
+        .sum();
+    view.clear();
+    view.save().await.unwrap();
+    size
+}
+
+fn bench_queries(criterion: &mut Criterion) {
+    let size = Runtime::new()
+        .expect("Failed to create Tokio runtime")
+        .block_on(storage_sorted_set_view::<MemoryDatabase>());
+    println!(
+        "sorted_set_view storage: {size} bytes for {INITIAL_SET_SIZE} keys, {} bytes per key",
+        size / INITIAL_SET_SIZE as usize
+    );
+
+    criterion.bench_function("memory_queries_sorted_set_view", |bencher| {
+        bencher
+            .to_async(Runtime::new().expect("Failed to create Tokio runtime"))
+            .iter_custom(|iterations| async move {
+                queries_sorted_set_view::<MemoryDatabase>(iterations).await
+            })
+    });
+
+    #[cfg(with_rocksdb)]
+    criterion.bench_function("rocksdb_queries_sorted_set_view", |bencher| {
+        bencher
+            .to_async(Runtime::new().expect("Failed to create Tokio runtime"))
+            .iter_custom(|iterations| async move {
+                queries_sorted_set_view::<RocksDbDatabase>(iterations).await
+            })
+    });
+}
+
+criterion_group!(benches, bench_sorted_set_view, bench_queries);
+criterion_main!(benches);
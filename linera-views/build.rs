@@ -8,6 +8,9 @@ fn main() {
         with_metrics: { all(not(target_arch = "wasm32"), feature = "metrics") },
         with_indexeddb: { all(web, feature = "indexeddb") },
         with_rocksdb: { all(not(target_arch = "wasm32"), feature = "rocksdb") },
+        with_redb: { all(not(target_arch = "wasm32"), feature = "redb") },
+        with_encryption: { all(not(target_arch = "wasm32"), feature = "encryption") },
+        with_compression: { all(not(target_arch = "wasm32"), feature = "compression") },
         with_scylladb: { all(not(target_arch = "wasm32"), feature = "scylladb") },
         with_graphql: { not(web) },
     };
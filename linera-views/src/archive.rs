@@ -0,0 +1,828 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The content of a namespace is read partition by partition, following
+//! [`KeyValueDatabase::list_root_keys`], and each partition is scanned by chunks of
+//! entries. It can be written to a portable archive or copied directly to a namespace of
+//! another database.
+//!
+//! An archive starts with a magic prefix and a version byte. It is followed by frames
+//! serialized with BCS, each prefixed by its length as a 32-bit big-endian integer. A
+//! frame either starts a partition or contains entries of the current partition. The last
+//! frame contains the number of partitions and entries of the archive and a SHA3-256
+//! checksum of the previous frames, so that truncated or corrupted archives are detected.
+//!
+//! The partitions are read one chunk at a time, so the result is consistent only if the
+//! namespace is not written to in the meantime. An export reads the namespace a second
+//! time once the archive is written and fails with [`ArchiveError::ConcurrentWrites`] if
+//! the content changed: both reads then saw every partition as it was between them, so a
+//! successful export is a snapshot of the namespace. It can run against a live validator,
+//! but only succeeds while the validator is idle. A copy is not checked this way, and
+//! should be made from a stopped validator.
+
+use std::io::{self, Read, Write};
+
+use serde::{Deserialize, Serialize};
+use sha3::{Digest as _, Sha3_256};
+use thiserror::Error;
+
+use crate::{
+    batch::Batch,
+    store::{
+        KeyRange, KeyValueDatabase, KeyValueStore, KeyValueStoreError, ReadableKeyValueStore,
+        WritableKeyValueStore,
+    },
+};
+
+/// The prefix of archives.
+const ARCHIVE_MAGIC: [u8; 8] = *b"LINERAKV";
+
+/// The version of the archive format.
+const ARCHIVE_VERSION: u8 = 1;
+
+/// The maximal number of entries read at once from a partition.
+const CHUNK_SIZE: u32 = 1000;
+
+/// The error type for exporting, importing and copying namespaces.
+#[derive(Error, Debug)]
+pub enum ArchiveError {
+    /// An error occurred while reading or writing the archive.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    /// A frame of the archive could not be serialized or deserialized.
+    #[error(transparent)]
+    BcsError(#[from] bcs::Error),
+
+    /// An error occurred in the database.
+    #[error("database error: {0}")]
+    Database(Box<dyn std::error::Error + Send + Sync>),
+
+    /// The data is not an archive.
+    #[error("the data is not a storage archive")]
+    InvalidHeader,
+
+    /// The archive was written with an unsupported version of the format.
+    #[error("unsupported archive version {0}")]
+    UnsupportedVersion(u8),
+
+    /// The archive ends before its last frame.
+    #[error("the archive is truncated")]
+    Truncated,
+
+    /// A frame is too large to be written.
+    #[error("a frame of {0} bytes is too large")]
+    FrameTooLarge(usize),
+
+    /// Entries were written or read before the start of any partition.
+    #[error("the entries do not belong to a partition")]
+    MissingPartition,
+
+    /// The checksum of the archive does not match its content.
+    #[error("the checksum of the archive does not match its content")]
+    ChecksumMismatch,
+
+    /// The last frame of the archive does not match its content.
+    #[error("the archive should contain {expected:?} but contains {found:?}")]
+    SummaryMismatch {
+        /// The summary recorded in the archive.
+        expected: ArchiveSummary,
+        /// The summary of the content of the archive.
+        found: ArchiveSummary,
+    },
+
+    /// There is data after the last frame of the archive.
+    #[error("unexpected data after the end of the archive")]
+    TrailingData,
+
+    /// The namespace receiving the data already contains some.
+    #[error("the target namespace is not empty")]
+    NamespaceNotEmpty,
+
+    /// The namespace was written to while it was being exported.
+    #[error(
+        "the namespace was written to during the export; stop the validator using it and retry"
+    )]
+    ConcurrentWrites,
+}
+
+impl ArchiveError {
+    fn database(error: impl KeyValueStoreError) -> Self {
+        ArchiveError::Database(Box::new(error))
+    }
+}
+
+/// The amount of data in an archive or in a copy.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveSummary {
+    /// The number of non-empty partitions.
+    pub partitions: u64,
+    /// The number of entries.
+    pub entries: u64,
+    /// The total size of the keys and values.
+    pub bytes: u64,
+}
+
+impl ArchiveSummary {
+    fn add_entries(&mut self, entries: &[(Vec<u8>, Vec<u8>)]) {
+        self.entries += entries.len() as u64;
+        self.bytes += entries
+            .iter()
+            .map(|(key, value)| (key.len() + value.len()) as u64)
+            .sum::<u64>();
+    }
+}
+
+/// A frame of an archive.
+#[derive(Serialize, Deserialize)]
+enum Frame {
+    /// Starts the partition with the given root key.
+    Partition(Vec<u8>),
+    /// Entries of the current partition.
+    Entries(Vec<(Vec<u8>, Vec<u8>)>),
+    /// Ends the archive.
+    End {
+        summary: ArchiveSummary,
+        checksum: [u8; 32],
+    },
+}
+
+/// A record read from an archive.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ArchiveRecord {
+    /// The start of the partition with the given root key.
+    Partition(Vec<u8>),
+    /// Entries of the current partition.
+    Entries(Vec<(Vec<u8>, Vec<u8>)>),
+}
+
+/// Writes an archive.
+pub struct ArchiveWriter<W> {
+    writer: W,
+    hasher: Sha3_256,
+    summary: ArchiveSummary,
+    in_partition: bool,
+}
+
+impl<W: Write> ArchiveWriter<W> {
+    /// Starts an archive written to `writer`.
+    pub fn new(mut writer: W) -> Result<Self, ArchiveError> {
+        writer.write_all(&ARCHIVE_MAGIC)?;
+        writer.write_all(&[ARCHIVE_VERSION])?;
+        Ok(ArchiveWriter {
+            writer,
+            hasher: Sha3_256::new(),
+            summary: ArchiveSummary::default(),
+            in_partition: false,
+        })
+    }
+
+    /// Starts the partition with the given root key.
+    pub fn start_partition(&mut self, root_key: &[u8]) -> Result<(), ArchiveError> {
+        self.write_frame(&Frame::Partition(root_key.to_vec()))?;
+        self.summary.partitions += 1;
+        self.in_partition = true;
+        Ok(())
+    }
+
+    /// Writes entries of the current partition.
+    pub fn write_entries(&mut self, entries: Vec<(Vec<u8>, Vec<u8>)>) -> Result<(), ArchiveError> {
+        if !self.in_partition {
+            return Err(ArchiveError::MissingPartition);
+        }
+        self.summary.add_entries(&entries);
+        self.write_frame(&Frame::Entries(entries))
+    }
+
+    /// Ends the archive and returns the writer together with the amount of data written.
+    pub fn finish(mut self) -> Result<(W, ArchiveSummary), ArchiveError> {
+        let frame = Frame::End {
+            summary: self.summary,
+            checksum: self.hasher.clone().finalize().into(),
+        };
+        self.write_frame(&frame)?;
+        self.writer.flush()?;
+        Ok((self.writer, self.summary))
+    }
+
+    fn write_frame(&mut self, frame: &Frame) -> Result<(), ArchiveError> {
+        let payload = bcs::to_bytes(frame)?;
+        let length = u32::try_from(payload.len())
+            .map_err(|_| ArchiveError::FrameTooLarge(payload.len()))?
+            .to_be_bytes();
+        self.hasher.update(length);
+        self.hasher.update(&payload);
+        self.writer.write_all(&length)?;
+        self.writer.write_all(&payload)?;
+        Ok(())
+    }
+}
+
+/// Reads an archive and checks its integrity.
+///
+/// The checksum is only verified once the last frame is read, so the records should not be
+/// trusted before [`ArchiveReader::next_record`] returns `None`.
+pub struct ArchiveReader<R> {
+    reader: R,
+    hasher: Sha3_256,
+    summary: ArchiveSummary,
+    in_partition: bool,
+    finished: bool,
+}
+
+impl<R: Read> ArchiveReader<R> {
+    /// Starts reading an archive from `reader`.
+    pub fn new(mut reader: R) -> Result<Self, ArchiveError> {
+        let mut header = [0; ARCHIVE_MAGIC.len() + 1];
+        reader.read_exact(&mut header).map_err(|error| {
+            if error.kind() == io::ErrorKind::UnexpectedEof {
+                ArchiveError::InvalidHeader
+            } else {
+                error.into()
+            }
+        })?;
+        let (magic, version) = header.split_at(ARCHIVE_MAGIC.len());
+        if magic != ARCHIVE_MAGIC {
+            return Err(ArchiveError::InvalidHeader);
+        }
+        if version[0] != ARCHIVE_VERSION {
+            return Err(ArchiveError::UnsupportedVersion(version[0]));
+        }
+        Ok(ArchiveReader {
+            reader,
+            hasher: Sha3_256::new(),
+            summary: ArchiveSummary::default(),
+            in_partition: false,
+            finished: false,
+        })
+    }
+
+    /// Reads the next record, or returns `None` after checking the last frame of the
+    /// archive.
+    pub fn next_record(&mut self) -> Result<Option<ArchiveRecord>, ArchiveError> {
+        if self.finished {
+            return Ok(None);
+        }
+        let (length, payload) = self.read_frame()?;
+        match bcs::from_bytes(&payload)? {
+            Frame::Partition(root_key) => {
+                self.update_hasher(&length, &payload);
+                self.summary.partitions += 1;
+                self.in_partition = true;
+                Ok(Some(ArchiveRecord::Partition(root_key)))
+            }
+            Frame::Entries(entries) => {
+                if !self.in_partition {
+                    return Err(ArchiveError::MissingPartition);
+                }
+                self.update_hasher(&length, &payload);
+                self.summary.add_entries(&entries);
+                Ok(Some(ArchiveRecord::Entries(entries)))
+            }
+            Frame::End { summary, checksum } => {
+                if checksum != <[u8; 32]>::from(self.hasher.clone().finalize()) {
+                    return Err(ArchiveError::ChecksumMismatch);
+                }
+                if summary != self.summary {
+                    return Err(ArchiveError::SummaryMismatch {
+                        expected: summary,
+                        found: self.summary,
+                    });
+                }
+                if self.reader.read(&mut [0])? != 0 {
+                    return Err(ArchiveError::TrailingData);
+                }
+                self.finished = true;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Returns the amount of data read so far.
+    pub fn summary(&self) -> ArchiveSummary {
+        self.summary
+    }
+
+    fn read_frame(&mut self) -> Result<([u8; 4], Vec<u8>), ArchiveError> {
+        let mut length = [0; 4];
+        self.reader.read_exact(&mut length).map_err(|error| {
+            if error.kind() == io::ErrorKind::UnexpectedEof {
+                ArchiveError::Truncated
+            } else {
+                error.into()
+            }
+        })?;
+        let expected_length = u32::from_be_bytes(length) as usize;
+        // The buffer grows with the data actually read, so that a corrupted length does
+        // not cause a large allocation.
+        let mut payload = Vec::new();
+        (&mut self.reader)
+            .take(expected_length as u64)
+            .read_to_end(&mut payload)?;
+        if payload.len() < expected_length {
+            return Err(ArchiveError::Truncated);
+        }
+        Ok((length, payload))
+    }
+
+    fn update_hasher(&mut self, length: &[u8], payload: &[u8]) {
+        self.hasher.update(length);
+        self.hasher.update(payload);
+    }
+}
+
+/// Reads a whole archive and checks its integrity, without importing it.
+pub fn verify_archive<R: Read>(reader: R) -> Result<ArchiveSummary, ArchiveError> {
+    let mut archive = ArchiveReader::new(reader)?;
+    while archive.next_record()?.is_some() {}
+    Ok(archive.summary())
+}
+
+/// Lists the root keys of the namespace in increasing order, including the empty one,
+/// which is not necessarily listed by the database.
+async fn list_root_keys<D: KeyValueDatabase>(database: &D) -> Result<Vec<Vec<u8>>, ArchiveError> {
+    let mut root_keys = database
+        .list_root_keys()
+        .await
+        .map_err(ArchiveError::database)?;
+    root_keys.push(Vec::new());
+    root_keys.sort();
+    root_keys.dedup();
+    Ok(root_keys)
+}
+
+/// Tests whether a namespace contains no entries.
+async fn is_empty<D>(database: &D) -> Result<bool, ArchiveError>
+where
+    D: KeyValueDatabase,
+    D::Store: ReadableKeyValueStore,
+{
+    let range = KeyRange::default().with_limit(1);
+    for root_key in list_root_keys(database).await? {
+        let store = database
+            .open_shared(&root_key)
+            .map_err(ArchiveError::database)?;
+        let keys = store
+            .find_keys_in_range(&[], &range)
+            .await
+            .map_err(ArchiveError::database)?;
+        if !keys.is_empty() {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Reads the next chunk of entries of a partition, after `last_key` if any.
+async fn read_chunk<S: ReadableKeyValueStore>(
+    store: &S,
+    last_key: Option<&[u8]>,
+) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ArchiveError> {
+    let range = KeyRange::default().with_limit(CHUNK_SIZE);
+    let range = match last_key {
+        Some(last_key) => range.after(last_key),
+        None => range,
+    };
+    store
+        .find_key_values_in_range(&[], &range)
+        .await
+        .map_err(ArchiveError::database)
+}
+
+/// Writes entries to a store.
+async fn write_entries<S: WritableKeyValueStore>(
+    store: &S,
+    entries: Vec<(Vec<u8>, Vec<u8>)>,
+) -> Result<(), ArchiveError> {
+    let mut batch = Batch::new();
+    for (key, value) in entries {
+        batch.put_key_value_bytes(key, value);
+    }
+    store
+        .write_batch(batch)
+        .await
+        .map_err(ArchiveError::database)
+}
+
+/// Reads the content of the namespace of `database` chunk by chunk, passing each non-empty
+/// chunk to `visit` together with the root key of its partition and whether it is the
+/// first chunk of the partition. Returns a digest of the whole content.
+async fn scan_namespace<D>(
+    database: &D,
+    mut visit: impl FnMut(&[u8], bool, Vec<(Vec<u8>, Vec<u8>)>) -> Result<(), ArchiveError>,
+) -> Result<[u8; 32], ArchiveError>
+where
+    D: KeyValueDatabase,
+    D::Store: ReadableKeyValueStore,
+{
+    let mut hasher = Sha3_256::new();
+    for root_key in list_root_keys(database).await? {
+        let store = database
+            .open_shared(&root_key)
+            .map_err(ArchiveError::database)?;
+        let mut last_key = None;
+        loop {
+            let entries = read_chunk(&store, last_key.as_deref()).await?;
+            let Some((key, _)) = entries.last() else {
+                break;
+            };
+            let is_first_chunk = last_key.is_none();
+            let is_last_chunk = entries.len() < CHUNK_SIZE as usize;
+            last_key = Some(key.clone());
+            hasher.update(bcs::to_bytes(&(&root_key, &entries))?);
+            visit(&root_key, is_first_chunk, entries)?;
+            if is_last_chunk {
+                break;
+            }
+        }
+    }
+    Ok(hasher.finalize().into())
+}
+
+/// Exports the content of the namespace of `database` to an archive written to `writer`.
+///
+/// The namespace is read again once the archive is written, and the export fails with
+/// [`ArchiveError::ConcurrentWrites`] if it changed, in which case the archive must be
+/// discarded.
+pub async fn export_namespace<D, W>(database: &D, writer: W) -> Result<ArchiveSummary, ArchiveError>
+where
+    D: KeyValueDatabase,
+    D::Store: ReadableKeyValueStore,
+    W: Write,
+{
+    let mut archive = ArchiveWriter::new(writer)?;
+    let digest = scan_namespace(database, |root_key, is_first_chunk, entries| {
+        if is_first_chunk {
+            archive.start_partition(root_key)?;
+        }
+        archive.write_entries(entries)
+    })
+    .await?;
+    let (_, summary) = archive.finish()?;
+    if scan_namespace(database, |_, _, _| Ok(())).await? != digest {
+        return Err(ArchiveError::ConcurrentWrites);
+    }
+    Ok(summary)
+}
+
+/// Imports an archive read from `reader` into the namespace of `database`.
+///
+/// Without `progress`, the namespace must be empty. Otherwise, the import resumes from the
+/// given progress: the archive is read from the start again, but the entries that were
+/// already imported are skipped. After each chunk of entries written to the namespace,
+/// `save_progress` is called so that the import can be resumed if it gets interrupted.
+///
+/// The entries are written as they are read, so a namespace into which a corrupted archive
+/// was partially imported should be discarded. Use [`verify_archive`] beforehand to avoid
+/// that.
+pub async fn import_namespace<D, R>(
+    database: &D,
+    reader: R,
+    progress: Option<CopyProgress>,
+    mut save_progress: impl FnMut(&CopyProgress) -> Result<(), ArchiveError>,
+) -> Result<ArchiveSummary, ArchiveError>
+where
+    D: KeyValueDatabase,
+    D::Store: KeyValueStore,
+    R: Read,
+{
+    let mut progress = match progress {
+        Some(progress) => progress,
+        None => {
+            if !is_empty(database).await? {
+                return Err(ArchiveError::NamespaceNotEmpty);
+            }
+            CopyProgress::default()
+        }
+    };
+    let mut archive = ArchiveReader::new(reader)?;
+    let mut store = None;
+    while let Some(record) = archive.next_record()? {
+        match record {
+            ArchiveRecord::Partition(root_key) => {
+                store = None;
+                if root_key < progress.root_key {
+                    continue;
+                }
+                if root_key > progress.root_key {
+                    progress.root_key = root_key.clone();
+                    progress.last_key = None;
+                }
+                store = Some(
+                    database
+                        .open_exclusive(&root_key)
+                        .map_err(ArchiveError::database)?,
+                );
+            }
+            ArchiveRecord::Entries(mut entries) => {
+                let Some(store) = &store else {
+                    // The partition was already imported.
+                    continue;
+                };
+                if let Some(last_key) = &progress.last_key {
+                    entries.retain(|(key, _)| key > last_key);
+                }
+                let Some((key, _)) = entries.last() else {
+                    continue;
+                };
+                if progress.last_key.is_none() {
+                    progress.summary.partitions += 1;
+                }
+                progress.last_key = Some(key.clone());
+                progress.summary.add_entries(&entries);
+                write_entries(store, entries).await?;
+                save_progress(&progress)?;
+            }
+        }
+    }
+    Ok(archive.summary())
+}
+
+/// The progress of a copy between namespaces or of an import, from which it can be resumed.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CopyProgress {
+    /// The root key of the partition being copied. The partitions with smaller root keys
+    /// are completely copied.
+    pub root_key: Vec<u8>,
+    /// The last key copied in the partition of `root_key`, if any.
+    pub last_key: Option<Vec<u8>>,
+    /// The amount of data copied so far.
+    pub summary: ArchiveSummary,
+}
+
+/// Copies the content of the namespace of `source` to the namespace of `target`.
+///
+/// Without `progress`, the target namespace must be empty. Otherwise, the copy resumes
+/// from the given progress. After each chunk of entries written to the target,
+/// `save_progress` is called so that the copy can be resumed if it gets interrupted.
+pub async fn copy_namespace<D1, D2>(
+    source: &D1,
+    target: &D2,
+    progress: Option<CopyProgress>,
+    mut save_progress: impl FnMut(&CopyProgress) -> Result<(), ArchiveError>,
+) -> Result<ArchiveSummary, ArchiveError>
+where
+    D1: KeyValueDatabase,
+    D1::Store: ReadableKeyValueStore,
+    D2: KeyValueDatabase,
+    D2::Store: KeyValueStore,
+{
+    let mut progress = match progress {
+        Some(progress) => progress,
+        None => {
+            if !is_empty(target).await? {
+                return Err(ArchiveError::NamespaceNotEmpty);
+            }
+            CopyProgress::default()
+        }
+    };
+    for root_key in list_root_keys(source).await? {
+        if root_key < progress.root_key {
+            continue;
+        }
+        if root_key > progress.root_key {
+            progress.root_key = root_key.clone();
+            progress.last_key = None;
+        }
+        let source_store = source
+            .open_shared(&root_key)
+            .map_err(ArchiveError::database)?;
+        let target_store = target
+            .open_exclusive(&root_key)
+            .map_err(ArchiveError::database)?;
+        loop {
+            let entries = read_chunk(&source_store, progress.last_key.as_deref()).await?;
+            let Some((key, _)) = entries.last() else {
+                break;
+            };
+            if progress.last_key.is_none() {
+                progress.summary.partitions += 1;
+            }
+            let is_last_chunk = entries.len() < CHUNK_SIZE as usize;
+            progress.last_key = Some(key.clone());
+            progress.summary.add_entries(&entries);
+            write_entries(&target_store, entries).await?;
+            save_progress(&progress)?;
+            if is_last_chunk {
+                break;
+            }
+        }
+    }
+    Ok(progress.summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::{
+        memory::{MemoryDatabase, MemoryStoreConfig},
+        random::generate_test_namespace,
+    };
+
+    type Content = BTreeMap<Vec<u8>, Vec<(Vec<u8>, Vec<u8>)>>;
+
+    async fn new_database() -> MemoryDatabase {
+        let config = MemoryStoreConfig {
+            kill_on_drop: false,
+        };
+        let namespace = generate_test_namespace();
+        MemoryDatabase::recreate_and_connect(&config, &namespace)
+            .await
+            .unwrap()
+    }
+
+    /// Fills a namespace with partitions of various sizes, one of them larger than a chunk.
+    async fn fill_database(database: &MemoryDatabase) {
+        for (root_key, count) in [(vec![], 3), (vec![1], 2500), (vec![1, 2], 1), (vec![7], 10)] {
+            let entries = (0..count)
+                .map(|i: u32| {
+                    (
+                        i.to_be_bytes().to_vec(),
+                        i.to_le_bytes().repeat(i as usize % 10),
+                    )
+                })
+                .collect();
+            let store = database.open_exclusive(&root_key).unwrap();
+            write_entries(&store, entries).await.unwrap();
+        }
+    }
+
+    async fn read_content(database: &MemoryDatabase) -> Content {
+        let mut content = Content::new();
+        for root_key in list_root_keys(database).await.unwrap() {
+            let store = database.open_shared(&root_key).unwrap();
+            let entries = store.find_key_values_by_prefix(&[]).await.unwrap();
+            if !entries.is_empty() {
+                content.insert(root_key, entries);
+            }
+        }
+        content
+    }
+
+    async fn export(database: &MemoryDatabase) -> Vec<u8> {
+        let mut archive = Vec::new();
+        export_namespace(database, &mut archive).await.unwrap();
+        archive
+    }
+
+    #[tokio::test]
+    async fn test_export_import() {
+        let source = new_database().await;
+        fill_database(&source).await;
+        let archive = export(&source).await;
+        let summary = verify_archive(archive.as_slice()).unwrap();
+        assert_eq!(summary.partitions, 4);
+        assert_eq!(summary.entries, 2514);
+
+        let target = new_database().await;
+        let imported = import_namespace(&target, archive.as_slice(), None, |_| Ok(()))
+            .await
+            .unwrap();
+        assert_eq!(imported, summary);
+        assert_eq!(read_content(&source).await, read_content(&target).await);
+
+        let result = import_namespace(&target, archive.as_slice(), None, |_| Ok(())).await;
+        assert!(matches!(result, Err(ArchiveError::NamespaceNotEmpty)));
+    }
+
+    /// Writes an archive to a buffer, adding an entry to the already exported first
+    /// partition once some entries were written, as a live validator would.
+    struct WriteDuringExport<'a> {
+        archive: Vec<u8>,
+        database: Option<&'a MemoryDatabase>,
+    }
+
+    impl Write for WriteDuringExport<'_> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.archive.len() > 1000 {
+                if let Some(database) = self.database.take() {
+                    let store = database.open_exclusive(&[]).unwrap();
+                    let entries = vec![(vec![0xff], vec![1])];
+                    futures::executor::block_on(write_entries(&store, entries)).unwrap();
+                }
+            }
+            self.archive.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_export_detects_concurrent_writes() {
+        let source = new_database().await;
+        fill_database(&source).await;
+        let writer = WriteDuringExport {
+            archive: Vec::new(),
+            database: Some(&source),
+        };
+        let result = export_namespace(&source, writer).await;
+        assert!(matches!(result, Err(ArchiveError::ConcurrentWrites)));
+
+        // Once the namespace is no longer written to, the export succeeds.
+        let archive = export(&source).await;
+        assert_eq!(verify_archive(archive.as_slice()).unwrap().entries, 2515);
+    }
+
+    #[tokio::test]
+    async fn test_corrupted_archives() {
+        let source = new_database().await;
+        fill_database(&source).await;
+        let archive = export(&source).await;
+
+        let result = verify_archive(&archive[..archive.len() - 1]);
+        assert!(matches!(result, Err(ArchiveError::Truncated)));
+
+        let result = verify_archive([archive.as_slice(), &[0]].concat().as_slice());
+        assert!(matches!(result, Err(ArchiveError::TrailingData)));
+
+        let result = verify_archive(&archive[1..]);
+        assert!(matches!(result, Err(ArchiveError::InvalidHeader)));
+
+        // Flips a bit in the middle of the archive.
+        let mut corrupted = archive.clone();
+        let position = corrupted.len() / 2;
+        corrupted[position] ^= 1;
+        assert!(verify_archive(corrupted.as_slice()).is_err());
+
+        let mut tampered = archive;
+        let position = tampered
+            .windows(4)
+            .position(|window| window == 2499u32.to_be_bytes())
+            .unwrap();
+        tampered[position + 3] = 0;
+        let result = verify_archive(tampered.as_slice());
+        assert!(matches!(result, Err(ArchiveError::ChecksumMismatch)));
+    }
+
+    #[tokio::test]
+    async fn test_resumed_copy() {
+        let source = new_database().await;
+        fill_database(&source).await;
+        let target = new_database().await;
+
+        // Interrupts the copy in the middle of the large partition.
+        let mut saved_progress = None;
+        let result = copy_namespace(&source, &target, None, |progress| {
+            saved_progress = Some(progress.clone());
+            if progress.root_key == [1] && progress.last_key.is_some() {
+                return Err(ArchiveError::Truncated);
+            }
+            Ok(())
+        })
+        .await;
+        assert!(matches!(result, Err(ArchiveError::Truncated)));
+        let progress = saved_progress.unwrap();
+        assert_eq!(progress.summary.entries, 1003);
+
+        let result = copy_namespace(&source, &target, None, |_| Ok(())).await;
+        assert!(matches!(result, Err(ArchiveError::NamespaceNotEmpty)));
+
+        let summary = copy_namespace(&source, &target, Some(progress), |_| Ok(()))
+            .await
+            .unwrap();
+        assert_eq!(summary.partitions, 4);
+        assert_eq!(summary.entries, 2514);
+        assert_eq!(read_content(&source).await, read_content(&target).await);
+    }
+
+    #[tokio::test]
+    async fn test_resumed_import() {
+        let source = new_database().await;
+        fill_database(&source).await;
+        let archive = export(&source).await;
+        let target = new_database().await;
+
+        // Interrupts the import in the middle of the large partition.
+        let mut saved_progress = None;
+        let result = import_namespace(&target, archive.as_slice(), None, |progress| {
+            saved_progress = Some(progress.clone());
+            if progress.root_key == [1] && progress.summary.entries > 1003 {
+                return Err(ArchiveError::Truncated);
+            }
+            Ok(())
+        })
+        .await;
+        assert!(matches!(result, Err(ArchiveError::Truncated)));
+        let progress = saved_progress.unwrap();
+        assert_eq!(progress.summary.entries, 2003);
+
+        let result = import_namespace(&target, archive.as_slice(), None, |_| Ok(())).await;
+        assert!(matches!(result, Err(ArchiveError::NamespaceNotEmpty)));
+
+        let mut final_progress = None;
+        let summary = import_namespace(&target, archive.as_slice(), Some(progress), |progress| {
+            final_progress = Some(progress.clone());
+            Ok(())
+        })
+        .await
+        .unwrap();
+        assert_eq!(summary.partitions, 4);
+        assert_eq!(summary.entries, 2514);
+        assert_eq!(final_progress.unwrap().summary, summary);
+        assert_eq!(read_content(&source).await, read_content(&target).await);
+    }
+}
@@ -0,0 +1,586 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Adds compression of the values to a given store.
+//!
+//! The values at least as large as a threshold are compressed with zstd or LZ4, and stored
+//! compressed if this makes them smaller. Compressed values start with a header made of a
+//! magic prefix and a byte identifying the algorithm. Other values are stored unchanged,
+//! unless they happen to start with the magic prefix, in which case they get a header as
+//! well. Compression can thus be enabled on an existing namespace: the values written
+//! before remain readable as long as none of them starts with the magic prefix.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+#[cfg(with_metrics)]
+use {
+    crate::metrics::{
+        COMPRESSED_BYTES, COMPRESSION_LATENCY, COMPRESSION_RATIO, DECOMPRESSION_LATENCY,
+        UNCOMPRESSED_BYTES,
+    },
+    linera_base::prometheus_util::MeasureLatency as _,
+};
+
+#[cfg(with_testing)]
+use crate::store::TestKeyValueDatabase;
+use crate::{
+    batch::{Batch, WriteOperation},
+    store::{
+        KeyRange, KeyValueDatabase, KeyValueStoreError, ReadableKeyValueStore, WithError,
+        WritableKeyValueStore,
+    },
+};
+
+/// The prefix of the values that have a compression header.
+const HEADER_MAGIC: [u8; 4] = [0xff, b'L', b'V', b'C'];
+
+/// The length of the compression header.
+const HEADER_LENGTH: usize = HEADER_MAGIC.len() + 1;
+
+/// The header byte of the values stored uncompressed.
+const UNCOMPRESSED_TAG: u8 = 0;
+
+/// The default size from which values are compressed.
+pub const DEFAULT_COMPRESSION_THRESHOLD: usize = 256;
+
+/// The default zstd compression level.
+pub const DEFAULT_COMPRESSION_LEVEL: i32 = 3;
+
+/// The compression algorithms.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Deserialize,
+    Serialize,
+    strum::Display,
+    strum::EnumString,
+    strum::IntoStaticStr,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum CompressionAlgorithm {
+    /// Zstandard, favoring the compression ratio.
+    #[default]
+    Zstd,
+    /// LZ4, favoring the speed.
+    Lz4,
+}
+
+impl CompressionAlgorithm {
+    /// The header byte of the values compressed with this algorithm.
+    fn tag(self) -> u8 {
+        match self {
+            CompressionAlgorithm::Zstd => 1,
+            CompressionAlgorithm::Lz4 => 2,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            1 => Some(CompressionAlgorithm::Zstd),
+            2 => Some(CompressionAlgorithm::Lz4),
+            _ => None,
+        }
+    }
+
+    fn compress<E>(self, level: i32, value: &[u8]) -> Result<Vec<u8>, CompressionError<E>> {
+        #[cfg(with_metrics)]
+        let latency = COMPRESSION_LATENCY.with_label_values(&[self.into()]);
+        #[cfg(with_metrics)]
+        let _latency = latency.measure_latency_us();
+        match self {
+            CompressionAlgorithm::Zstd => {
+                zstd::bulk::compress(value, level).map_err(CompressionError::Zstd)
+            }
+            CompressionAlgorithm::Lz4 => Ok(lz4_flex::compress_prepend_size(value)),
+        }
+    }
+
+    fn decompress<E>(self, value: &[u8]) -> Result<Vec<u8>, CompressionError<E>> {
+        #[cfg(with_metrics)]
+        let latency = DECOMPRESSION_LATENCY.with_label_values(&[self.into()]);
+        #[cfg(with_metrics)]
+        let _latency = latency.measure_latency_us();
+        match self {
+            CompressionAlgorithm::Zstd => zstd::decode_all(value).map_err(CompressionError::Zstd),
+            CompressionAlgorithm::Lz4 => {
+                lz4_flex::decompress_size_prepended(value).map_err(CompressionError::Lz4)
+            }
+        }
+    }
+}
+
+/// The options of the compression.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct CompressionOptions {
+    /// The compression algorithm.
+    pub algorithm: CompressionAlgorithm,
+    /// The compression level. It is only used by zstd.
+    pub level: i32,
+    /// The size from which values are compressed.
+    pub threshold: usize,
+}
+
+impl Default for CompressionOptions {
+    fn default() -> Self {
+        CompressionOptions {
+            algorithm: CompressionAlgorithm::default(),
+            level: DEFAULT_COMPRESSION_LEVEL,
+            threshold: DEFAULT_COMPRESSION_THRESHOLD,
+        }
+    }
+}
+
+/// The configuration of a [`CompressionDatabase`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CompressionConfig<C> {
+    /// The inner configuration of the `CompressionDatabase`.
+    pub inner_config: C,
+    /// The options of the compression.
+    pub options: CompressionOptions,
+}
+
+/// A key-value database compressing the stored values.
+#[derive(Clone)]
+pub struct CompressionDatabase<D> {
+    /// The underlying database.
+    database: D,
+    /// The options of the compression.
+    options: CompressionOptions,
+}
+
+/// A key-value store compressing the stored values.
+#[derive(Clone)]
+pub struct CompressionStore<S> {
+    /// The underlying store.
+    store: S,
+    /// The options of the compression.
+    options: CompressionOptions,
+}
+
+/// The composed error type built from the inner error type.
+#[derive(Error, Debug)]
+pub enum CompressionError<E> {
+    /// inner store error
+    #[error(transparent)]
+    InnerStoreError(#[from] E),
+
+    /// A zstd compression or decompression failed.
+    #[error("zstd failed: {0}")]
+    Zstd(#[source] std::io::Error),
+
+    /// An LZ4 decompression failed.
+    #[error("LZ4 decompression failed: {0}")]
+    Lz4(#[source] lz4_flex::block::DecompressError),
+
+    /// A value has a compression header with an unknown algorithm.
+    #[error("unknown compression algorithm {0}")]
+    UnknownAlgorithm(u8),
+}
+
+impl<E: KeyValueStoreError> From<bcs::Error> for CompressionError<E> {
+    fn from(error: bcs::Error) -> Self {
+        let error = E::from(error);
+        CompressionError::InnerStoreError(error)
+    }
+}
+
+impl<E: KeyValueStoreError + 'static> KeyValueStoreError for CompressionError<E> {
+    const BACKEND: &'static str = "compression";
+
+    fn must_reload_view(&self) -> bool {
+        match self {
+            CompressionError::InnerStoreError(e) => e.must_reload_view(),
+            _ => false,
+        }
+    }
+}
+
+impl<D> WithError for CompressionDatabase<D>
+where
+    D: WithError,
+    D::Error: 'static,
+{
+    type Error = CompressionError<D::Error>;
+}
+
+impl<S> WithError for CompressionStore<S>
+where
+    S: WithError,
+    S::Error: 'static,
+{
+    type Error = CompressionError<S::Error>;
+}
+
+impl<S> CompressionStore<S>
+where
+    S: WithError,
+    S::Error: 'static,
+{
+    /// Returns the stored form of a value.
+    fn compress_value(&self, value: Vec<u8>) -> Result<Vec<u8>, CompressionError<S::Error>> {
+        let algorithm = self.options.algorithm;
+        if value.len() >= self.options.threshold {
+            let compressed = algorithm.compress(self.options.level, &value)?;
+            #[cfg(with_metrics)]
+            {
+                let label: &str = algorithm.into();
+                let stored_length = (compressed.len() + HEADER_LENGTH).min(value.len());
+                UNCOMPRESSED_BYTES
+                    .with_label_values(&[label])
+                    .inc_by(value.len() as u64);
+                COMPRESSED_BYTES
+                    .with_label_values(&[label])
+                    .inc_by(stored_length as u64);
+                COMPRESSION_RATIO
+                    .with_label_values(&[label])
+                    .observe(stored_length as f64 / value.len().max(1) as f64);
+            }
+            if compressed.len() + HEADER_LENGTH < value.len() {
+                return Ok([&HEADER_MAGIC[..], &[algorithm.tag()], &compressed].concat());
+            }
+        }
+        if value.starts_with(&HEADER_MAGIC) {
+            return Ok([&HEADER_MAGIC[..], &[UNCOMPRESSED_TAG], &value].concat());
+        }
+        Ok(value)
+    }
+
+    /// Returns a value from its stored form.
+    fn decompress_value(&self, value: Vec<u8>) -> Result<Vec<u8>, CompressionError<S::Error>> {
+        if !value.starts_with(&HEADER_MAGIC) {
+            return Ok(value);
+        }
+        // A value written before the compression was enabled may be the magic prefix.
+        let Some(&tag) = value.get(HEADER_MAGIC.len()) else {
+            return Ok(value);
+        };
+        if tag == UNCOMPRESSED_TAG {
+            return Ok(value[HEADER_LENGTH..].to_vec());
+        }
+        let algorithm =
+            CompressionAlgorithm::from_tag(tag).ok_or(CompressionError::UnknownAlgorithm(tag))?;
+        algorithm.decompress(&value[HEADER_LENGTH..])
+    }
+}
+
+impl<S> ReadableKeyValueStore for CompressionStore<S>
+where
+    S: ReadableKeyValueStore,
+    S::Error: 'static,
+{
+    const MAX_KEY_SIZE: usize = S::MAX_KEY_SIZE;
+
+    fn root_key(&self) -> Result<Vec<u8>, Self::Error> {
+        Ok(self.store.root_key()?)
+    }
+
+    async fn read_value_bytes(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+        self.store
+            .read_value_bytes(key)
+            .await?
+            .map(|value| self.decompress_value(value))
+            .transpose()
+    }
+
+    async fn contains_key(&self, key: &[u8]) -> Result<bool, Self::Error> {
+        Ok(self.store.contains_key(key).await?)
+    }
+
+    async fn contains_keys(&self, keys: &[Vec<u8>]) -> Result<Vec<bool>, Self::Error> {
+        Ok(self.store.contains_keys(keys).await?)
+    }
+
+    async fn read_multi_values_bytes(
+        &self,
+        keys: &[Vec<u8>],
+    ) -> Result<Vec<Option<Vec<u8>>>, Self::Error> {
+        self.store
+            .read_multi_values_bytes(keys)
+            .await?
+            .into_iter()
+            .map(|value| value.map(|value| self.decompress_value(value)).transpose())
+            .collect()
+    }
+
+    async fn find_keys_by_prefix(&self, key_prefix: &[u8]) -> Result<Vec<Vec<u8>>, Self::Error> {
+        Ok(self.store.find_keys_by_prefix(key_prefix).await?)
+    }
+
+    async fn find_key_values_by_prefix(
+        &self,
+        key_prefix: &[u8],
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error> {
+        self.store
+            .find_key_values_by_prefix(key_prefix)
+            .await?
+            .into_iter()
+            .map(|(key, value)| Ok((key, self.decompress_value(value)?)))
+            .collect()
+    }
+
+    async fn find_keys_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<Vec<u8>>, Self::Error> {
+        Ok(self.store.find_keys_in_range(key_prefix, range).await?)
+    }
+
+    async fn find_key_values_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error> {
+        self.store
+            .find_key_values_in_range(key_prefix, range)
+            .await?
+            .into_iter()
+            .map(|(key, value)| Ok((key, self.decompress_value(value)?)))
+            .collect()
+    }
+}
+
+impl<S> WritableKeyValueStore for CompressionStore<S>
+where
+    S: WritableKeyValueStore,
+    S::Error: 'static,
+{
+    const MAX_VALUE_SIZE: usize = S::MAX_VALUE_SIZE - HEADER_LENGTH;
+
+    async fn write_batch(&self, batch: Batch) -> Result<(), Self::Error> {
+        let mut stored_batch = Batch::new();
+        for operation in batch.operations {
+            match operation {
+                WriteOperation::Delete { key } => stored_batch.delete_key(key),
+                WriteOperation::Put { key, value } => {
+                    let value = self.compress_value(value)?;
+                    stored_batch.put_key_value_bytes(key, value);
+                }
+                WriteOperation::DeletePrefix { key_prefix } => {
+                    stored_batch.delete_key_prefix(key_prefix)
+                }
+            }
+        }
+        Ok(self.store.write_batch(stored_batch).await?)
+    }
+
+    async fn clear_journal(&self) -> Result<(), Self::Error> {
+        Ok(self.store.clear_journal().await?)
+    }
+}
+
+impl<D> KeyValueDatabase for CompressionDatabase<D>
+where
+    D: KeyValueDatabase,
+    D::Error: 'static,
+{
+    type Config = CompressionConfig<D::Config>;
+
+    type Store = CompressionStore<D::Store>;
+
+    fn get_name() -> String {
+        format!("compression {}", D::get_name())
+    }
+
+    async fn connect(config: &Self::Config, namespace: &str) -> Result<Self, Self::Error> {
+        let database = D::connect(&config.inner_config, namespace).await?;
+        Ok(CompressionDatabase {
+            database,
+            options: config.options,
+        })
+    }
+
+    fn open_shared(&self, root_key: &[u8]) -> Result<Self::Store, Self::Error> {
+        let store = self.database.open_shared(root_key)?;
+        Ok(CompressionStore {
+            store,
+            options: self.options,
+        })
+    }
+
+    fn open_exclusive(&self, root_key: &[u8]) -> Result<Self::Store, Self::Error> {
+        let store = self.database.open_exclusive(root_key)?;
+        Ok(CompressionStore {
+            store,
+            options: self.options,
+        })
+    }
+
+    async fn list_all(config: &Self::Config) -> Result<Vec<String>, Self::Error> {
+        Ok(D::list_all(&config.inner_config).await?)
+    }
+
+    async fn list_root_keys(&self) -> Result<Vec<Vec<u8>>, Self::Error> {
+        Ok(self.database.list_root_keys().await?)
+    }
+
+    async fn delete_all(config: &Self::Config) -> Result<(), Self::Error> {
+        Ok(D::delete_all(&config.inner_config).await?)
+    }
+
+    async fn exists(config: &Self::Config, namespace: &str) -> Result<bool, Self::Error> {
+        Ok(D::exists(&config.inner_config, namespace).await?)
+    }
+
+    async fn create(config: &Self::Config, namespace: &str) -> Result<(), Self::Error> {
+        Ok(D::create(&config.inner_config, namespace).await?)
+    }
+
+    async fn delete(config: &Self::Config, namespace: &str) -> Result<(), Self::Error> {
+        Ok(D::delete(&config.inner_config, namespace).await?)
+    }
+}
+
+#[cfg(with_testing)]
+impl<D> TestKeyValueDatabase for CompressionDatabase<D>
+where
+    D: TestKeyValueDatabase,
+    D::Error: 'static,
+{
+    async fn new_test_config() -> Result<CompressionConfig<D::Config>, Self::Error> {
+        let inner_config = D::new_test_config().await?;
+        // Compress all the values, to exercise the compression in the generic tests.
+        let options = CompressionOptions {
+            threshold: 0,
+            ..CompressionOptions::default()
+        };
+        Ok(CompressionConfig {
+            inner_config,
+            options,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        memory::{MemoryDatabase, MemoryStoreConfig},
+        random::generate_test_namespace,
+    };
+
+    fn config(algorithm: CompressionAlgorithm) -> CompressionConfig<MemoryStoreConfig> {
+        CompressionConfig {
+            inner_config: MemoryStoreConfig {
+                kill_on_drop: false,
+            },
+            options: CompressionOptions {
+                algorithm,
+                ..CompressionOptions::default()
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compression_of_values() {
+        let compressible = vec![7; 1000];
+        let small = vec![7; 10];
+        let incompressible = (0..=255).collect::<Vec<u8>>();
+        let escaped = [&HEADER_MAGIC[..], &[1, 2, 3]].concat();
+        for algorithm in [CompressionAlgorithm::Zstd, CompressionAlgorithm::Lz4] {
+            let namespace = generate_test_namespace();
+            let database = CompressionDatabase::<MemoryDatabase>::recreate_and_connect(
+                &config(algorithm),
+                &namespace,
+            )
+            .await
+            .unwrap();
+            let store = database.open_shared(&[]).unwrap();
+            let mut batch = Batch::new();
+            batch.put_key_value_bytes(vec![0], compressible.clone());
+            batch.put_key_value_bytes(vec![1], small.clone());
+            batch.put_key_value_bytes(vec![2], incompressible.clone());
+            batch.put_key_value_bytes(vec![3], escaped.clone());
+            store.write_batch(batch).await.unwrap();
+
+            let stored = database
+                .database
+                .open_shared(&[])
+                .unwrap()
+                .find_key_values_by_prefix(&[])
+                .await
+                .unwrap();
+            assert!(stored[0].1.len() < 100);
+            assert_eq!(stored[0].1[..HEADER_LENGTH - 1], HEADER_MAGIC);
+            assert_eq!(stored[0].1[HEADER_LENGTH - 1], algorithm.tag());
+            assert_eq!(stored[1].1, small);
+            assert_eq!(stored[2].1, incompressible);
+            assert_eq!(stored[3].1.len(), escaped.len() + HEADER_LENGTH);
+
+            let values = store
+                .read_multi_values_bytes(&[vec![0], vec![1], vec![2], vec![3]])
+                .await
+                .unwrap();
+            assert_eq!(
+                values,
+                vec![
+                    Some(compressible.clone()),
+                    Some(small.clone()),
+                    Some(incompressible.clone()),
+                    Some(escaped.clone())
+                ]
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_enabling_compression_on_existing_namespace() {
+        let namespace = generate_test_namespace();
+        let inner_config = MemoryStoreConfig {
+            kill_on_drop: false,
+        };
+        let database = MemoryDatabase::recreate_and_connect(&inner_config, &namespace)
+            .await
+            .unwrap();
+        let mut batch = Batch::new();
+        batch.put_key_value_bytes(vec![0], vec![1; 1000]);
+        batch.put_key_value_bytes(vec![1], HEADER_MAGIC.to_vec());
+        database
+            .open_shared(&[])
+            .unwrap()
+            .write_batch(batch)
+            .await
+            .unwrap();
+
+        let database = CompressionDatabase::<MemoryDatabase>::connect(
+            &config(CompressionAlgorithm::Zstd),
+            &namespace,
+        )
+        .await
+        .unwrap();
+        let store = database.open_shared(&[]).unwrap();
+        let mut batch = Batch::new();
+        batch.put_key_value_bytes(vec![2], vec![2; 1000]);
+        store.write_batch(batch).await.unwrap();
+        let key_values = store.find_key_values_by_prefix(&[]).await.unwrap();
+        assert_eq!(
+            key_values,
+            vec![
+                (vec![0], vec![1; 1000]),
+                (vec![1], HEADER_MAGIC.to_vec()),
+                (vec![2], vec![2; 1000]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compression_algorithm_from_str() {
+        use std::str::FromStr as _;
+
+        assert_eq!(
+            CompressionAlgorithm::from_str("zstd"),
+            Ok(CompressionAlgorithm::Zstd)
+        );
+        assert_eq!(
+            CompressionAlgorithm::from_str("lz4"),
+            Ok(CompressionAlgorithm::Lz4)
+        );
+        assert_eq!(CompressionAlgorithm::Lz4.to_string(), "lz4");
+        assert!(CompressionAlgorithm::from_str("gzip").is_err());
+    }
+}
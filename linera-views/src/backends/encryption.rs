@@ -0,0 +1,1193 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Adds encryption at rest to a given store.
+//!
+//! Values are encrypted with XChaCha20-Poly1305 under a random nonce, and are bound to
+//! their key and partition so that they cannot be moved around. Every value starts with
+//! the identifier of the key that encrypted it, so that the data encrypted with a
+//! previous key remains readable until it is rewritten with the current one.
+//!
+//! Optionally, the keys are encrypted as well, deterministically and byte by byte: every
+//! byte is encrypted by a small Feistel network keyed by the secret and by the bytes
+//! preceding it. Encrypted keys thus share a prefix exactly when the keys do, so that prefix
+//! queries keep working, but their order is unrelated to the order of the keys. Range
+//! queries on encrypted keys list the keys sharing the common prefix of the bounds and
+//! filter them after decryption. With a limit, these keys are listed one value of their
+//! next byte at a time, in order, until the limit is reached: a page then costs up to 256
+//! prefix queries per encryption key, but only lists the keys around it. This hides the
+//! content and the order of the keys, but not their length or which of them share a prefix.
+//! The root keys of the partitions are not encrypted.
+
+use std::{fmt, path::PathBuf, str::FromStr, sync::Arc};
+
+use chacha20poly1305::{
+    aead::{Aead as _, AeadCore as _, KeyInit as _, OsRng, Payload},
+    XChaCha20Poly1305, XNonce,
+};
+use serde::{Deserialize, Serialize};
+use sha3::{
+    digest::{ExtendableOutput as _, Update as _, XofReader as _},
+    Digest as _, Sha3_256, Shake256,
+};
+use thiserror::Error;
+
+pub use crate::backends::PathWithGuard;
+#[cfg(with_testing)]
+use crate::store::TestKeyValueDatabase;
+use crate::{
+    batch::{Batch, WriteOperation},
+    store::{
+        KeyBound, KeyRange, KeyValueDatabase, KeyValueStoreError, ReadableKeyValueStore, WithError,
+        WritableKeyValueStore,
+    },
+};
+
+/// The length of the secrets from which the encryption keys are derived.
+pub const ENCRYPTION_SECRET_LENGTH: usize = 32;
+
+/// The length of the identifier of a key, prepended to the values and the encrypted keys.
+const KEY_ID_LENGTH: usize = 4;
+
+/// The length of the XChaCha20-Poly1305 nonces.
+const NONCE_LENGTH: usize = 24;
+
+/// The length of the XChaCha20-Poly1305 authentication tags.
+const TAG_LENGTH: usize = 16;
+
+/// The number of rounds of the Feistel network encrypting every byte of the keys, as in the
+/// FF1 mode of format-preserving encryption.
+const FEISTEL_ROUNDS: usize = 10;
+
+/// The number of bytes added to every value.
+const VALUE_OVERHEAD: usize = KEY_ID_LENGTH + NONCE_LENGTH + TAG_LENGTH;
+
+/// Where the secret of an encryption key is loaded from.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub enum EncryptionKeySource {
+    /// A file containing the hex-encoded secret.
+    File(PathWithGuard),
+    /// An environment variable containing the hex-encoded secret.
+    Env(String),
+}
+
+impl EncryptionKeySource {
+    /// Loads the key.
+    pub fn load(&self) -> Result<EncryptionKey, EncryptionKeyError> {
+        let secret = match self {
+            EncryptionKeySource::File(path_with_guard) => {
+                let path = &path_with_guard.path_buf;
+                std::fs::read_to_string(path)
+                    .map_err(|error| EncryptionKeyError::Io(path.clone(), error))?
+            }
+            EncryptionKeySource::Env(name) => std::env::var(name)
+                .map_err(|_| EncryptionKeyError::MissingEnvironmentVariable(name.clone()))?,
+        };
+        let secret = hex::decode(secret.trim())?;
+        let secret = <[u8; ENCRYPTION_SECRET_LENGTH]>::try_from(secret)
+            .map_err(|secret| EncryptionKeyError::InvalidLength(secret.len()))?;
+        Ok(EncryptionKey::new(secret))
+    }
+}
+
+impl FromStr for EncryptionKeySource {
+    type Err = EncryptionKeyError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if let Some(path) = input.strip_prefix("file:") {
+            return Ok(EncryptionKeySource::File(PathWithGuard::new(
+                PathBuf::from(path),
+            )));
+        }
+        if let Some(name) = input.strip_prefix("env:") {
+            return Ok(EncryptionKeySource::Env(name.to_string()));
+        }
+        Err(EncryptionKeyError::InvalidSource(input.to_string()))
+    }
+}
+
+impl fmt::Display for EncryptionKeySource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EncryptionKeySource::File(path_with_guard) => {
+                write!(f, "file:{}", path_with_guard.path_buf.display())
+            }
+            EncryptionKeySource::Env(name) => write!(f, "env:{name}"),
+        }
+    }
+}
+
+/// A key encrypting the stored data.
+#[derive(Clone)]
+pub struct EncryptionKey {
+    /// The identifier of the key.
+    id: [u8; KEY_ID_LENGTH],
+    /// The cipher for the values.
+    cipher: XChaCha20Poly1305,
+    /// The secret of the encoding of the keys.
+    key_secret: [u8; 32],
+}
+
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("EncryptionKey")
+            .field("id", &hex::encode(self.id))
+            .finish_non_exhaustive()
+    }
+}
+
+impl EncryptionKey {
+    /// Derives the key from the given secret.
+    pub fn new(secret: [u8; ENCRYPTION_SECRET_LENGTH]) -> Self {
+        let derive = |domain: &[u8]| -> [u8; 32] {
+            Sha3_256::digest([b"linera-views encryption ", domain, &secret].concat()).into()
+        };
+        let id = derive(b"id")[..KEY_ID_LENGTH]
+            .try_into()
+            .expect("the digest is longer than the identifier");
+        let cipher = XChaCha20Poly1305::new(&derive(b"value").into());
+        let key_secret = derive(b"key");
+        EncryptionKey {
+            id,
+            cipher,
+            key_secret,
+        }
+    }
+
+    fn key_encoder(&self) -> KeyEncoder {
+        let mut state = Shake256::default();
+        state.update(&self.key_secret);
+        KeyEncoder { state }
+    }
+
+    /// Returns the stored form of an encrypted key.
+    fn encrypt_key(&self, key: &[u8]) -> Vec<u8> {
+        let mut stored_key = self.id.to_vec();
+        self.key_encoder().encode(key, &mut stored_key);
+        stored_key
+    }
+
+    fn encrypt_value(&self, associated_data: &[u8], value: &[u8]) -> Vec<u8> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let payload = Payload {
+            msg: value,
+            aad: associated_data,
+        };
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, payload)
+            .expect("the values are small enough to be encrypted");
+        [&self.id[..], &nonce, &ciphertext].concat()
+    }
+
+    fn decrypt_value(&self, associated_data: &[u8], value: &[u8]) -> Option<Vec<u8>> {
+        let nonce = XNonce::from_slice(value.get(KEY_ID_LENGTH..KEY_ID_LENGTH + NONCE_LENGTH)?);
+        let payload = Payload {
+            msg: &value[KEY_ID_LENGTH + NONCE_LENGTH..],
+            aad: associated_data,
+        };
+        self.cipher.decrypt(nonce, payload).ok()
+    }
+}
+
+/// A deterministic encoding of the keys preserving their prefixes.
+///
+/// Every byte is encrypted by a Feistel network on its two halves, whose round functions
+/// are drawn from the hash of the secret and of the bytes preceding it. The encoding of a
+/// prefix of a key is thus a prefix of the encoding of the key, and nothing else about the
+/// keys can be compared.
+#[derive(Clone)]
+struct KeyEncoder {
+    /// The hash of the secret and of the bytes encoded so far.
+    state: Shake256,
+}
+
+/// The round functions of the Feistel network encrypting a byte, as tables of half-bytes.
+type RoundFunctions = [[u8; 16]; FEISTEL_ROUNDS];
+
+impl KeyEncoder {
+    /// Returns the round functions encrypting the next byte.
+    fn next_round_functions(&self) -> RoundFunctions {
+        let mut random_bytes = [0u8; FEISTEL_ROUNDS * 8];
+        self.state.clone().finalize_xof().read(&mut random_bytes);
+        let mut round_functions = [[0u8; 16]; FEISTEL_ROUNDS];
+        for (function, bytes) in round_functions.iter_mut().zip(random_bytes.chunks_exact(8)) {
+            for (outputs, byte) in function.chunks_exact_mut(2).zip(bytes) {
+                outputs[0] = byte >> 4;
+                outputs[1] = byte & 0x0f;
+            }
+        }
+        round_functions
+    }
+
+    fn encode(&mut self, key: &[u8], output: &mut Vec<u8>) {
+        for byte in key {
+            let (mut left, mut right) = (byte >> 4, byte & 0x0f);
+            for function in self.next_round_functions() {
+                (left, right) = (right, left ^ function[usize::from(right)]);
+            }
+            output.push((left << 4) | right);
+            self.state.update(&[*byte]);
+        }
+    }
+
+    fn decode(mut self, encoded: &[u8]) -> Vec<u8> {
+        let mut key = Vec::with_capacity(encoded.len());
+        for code in encoded {
+            let (mut left, mut right) = (code >> 4, code & 0x0f);
+            for function in self.next_round_functions().iter().rev() {
+                (left, right) = (right ^ function[usize::from(left)], left);
+            }
+            let byte = (left << 4) | right;
+            key.push(byte);
+            self.state.update(&[byte]);
+        }
+        key
+    }
+}
+
+/// A part of the keys of a range, listed at once. The keys of a part all come before the
+/// keys of the next part.
+enum ScanPart {
+    /// The key with the given suffix.
+    Key(Vec<u8>),
+    /// The keys starting with the given suffix.
+    Prefix(Vec<u8>),
+}
+
+impl ScanPart {
+    /// Returns the parts of the keys of `range`, in the direction of the range.
+    ///
+    /// The keys of the range all start with the common prefix of its bounds. If the range
+    /// has a limit, they are split according to their next byte, so that the scan can stop
+    /// once the limit is reached.
+    fn split(range: &KeyRange) -> Vec<ScanPart> {
+        let prefix = match (&range.start, &range.end) {
+            (
+                KeyBound::Included(start) | KeyBound::Excluded(start),
+                KeyBound::Included(end) | KeyBound::Excluded(end),
+            ) => {
+                let length = start.iter().zip(end).take_while(|(a, b)| a == b).count();
+                start[..length].to_vec()
+            }
+            _ => Vec::new(),
+        };
+        if range.limit.is_none() {
+            return vec![ScanPart::Prefix(prefix)];
+        }
+        let first_byte = match &range.start {
+            KeyBound::Unbounded => 0,
+            KeyBound::Included(start) | KeyBound::Excluded(start) => {
+                start.get(prefix.len()).copied().unwrap_or(0)
+            }
+        };
+        let last_byte = match &range.end {
+            KeyBound::Unbounded => Some(u8::MAX),
+            KeyBound::Included(end) | KeyBound::Excluded(end) => end.get(prefix.len()).copied(),
+        };
+        let mut parts = Vec::new();
+        if range.contains(&prefix) {
+            parts.push(ScanPart::Key(prefix.clone()));
+        }
+        if let Some(last_byte) = last_byte {
+            for byte in first_byte..=last_byte {
+                parts.push(ScanPart::Prefix([&prefix[..], &[byte]].concat()));
+            }
+        }
+        if range.reverse {
+            parts.reverse();
+        }
+        parts
+    }
+}
+
+/// The configuration of the encryption keys.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct EncryptionKeysConfig {
+    /// The key encrypting the new data.
+    pub key: EncryptionKeySource,
+    /// The keys that encrypted the data previously, and can still decrypt it.
+    pub previous_keys: Vec<EncryptionKeySource>,
+    /// Whether the keys are encrypted as well as the values. This cannot be changed once
+    /// data has been written.
+    pub encrypt_keys: bool,
+}
+
+impl EncryptionKeysConfig {
+    fn load(&self) -> Result<EncryptionKeys, EncryptionKeyError> {
+        let keys = std::iter::once(&self.key)
+            .chain(&self.previous_keys)
+            .map(EncryptionKeySource::load)
+            .collect::<Result<_, _>>()?;
+        Ok(EncryptionKeys {
+            keys,
+            encrypt_keys: self.encrypt_keys,
+        })
+    }
+}
+
+/// The loaded encryption keys.
+struct EncryptionKeys {
+    /// The key encrypting the new data, followed by the previous keys.
+    keys: Vec<EncryptionKey>,
+    /// Whether the keys are encrypted as well as the values.
+    encrypt_keys: bool,
+}
+
+impl EncryptionKeys {
+    fn current(&self) -> &EncryptionKey {
+        &self.keys[0]
+    }
+}
+
+/// The configuration of an [`EncryptionDatabase`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct EncryptionConfig<C> {
+    /// The inner configuration of the `EncryptionDatabase`.
+    pub inner_config: C,
+    /// The configuration of the encryption keys.
+    pub keys_config: EncryptionKeysConfig,
+}
+
+/// A key-value database encrypting the stored data.
+#[derive(Clone)]
+pub struct EncryptionDatabase<D> {
+    /// The underlying database.
+    database: D,
+    /// The encryption keys.
+    keys: Arc<EncryptionKeys>,
+}
+
+/// A key-value store encrypting the stored data.
+#[derive(Clone)]
+pub struct EncryptionStore<S> {
+    /// The underlying store.
+    store: S,
+    /// The encryption keys.
+    keys: Arc<EncryptionKeys>,
+    /// The root key of the partition, which the values are bound to.
+    root_key: Vec<u8>,
+}
+
+/// The error raised when loading an encryption key.
+#[derive(Error, Debug)]
+pub enum EncryptionKeyError {
+    /// The key source is not `file:<path>` or `env:<variable>`.
+    #[error("the encryption key source {0:?} is neither file:<path> nor env:<variable>")]
+    InvalidSource(String),
+
+    /// The key file cannot be read.
+    #[error("failed to read the encryption key from {0}: {1}")]
+    Io(PathBuf, std::io::Error),
+
+    /// The environment variable holding the key is not set.
+    #[error("the environment variable {0} holding the encryption key is not set")]
+    MissingEnvironmentVariable(String),
+
+    /// The key is not hex-encoded.
+    #[error("the encryption key is not hex-encoded: {0}")]
+    InvalidHex(#[from] hex::FromHexError),
+
+    /// The key does not have the right length.
+    #[error("the encryption key has {0} bytes instead of {ENCRYPTION_SECRET_LENGTH}")]
+    InvalidLength(usize),
+}
+
+/// The composed error type built from the inner error type.
+#[derive(Error, Debug)]
+pub enum EncryptionError<E> {
+    /// inner store error
+    #[error(transparent)]
+    InnerStoreError(#[from] E),
+
+    /// An encryption key could not be loaded.
+    #[error(transparent)]
+    Key(EncryptionKeyError),
+
+    /// A value was encrypted with a key that is not configured.
+    #[error("a value was encrypted with an unknown key")]
+    UnknownKey,
+
+    /// A value could not be decrypted.
+    #[error("a value could not be decrypted")]
+    DecryptionFailed,
+}
+
+impl<E: KeyValueStoreError> From<bcs::Error> for EncryptionError<E> {
+    fn from(error: bcs::Error) -> Self {
+        let error = E::from(error);
+        EncryptionError::InnerStoreError(error)
+    }
+}
+
+impl<E: KeyValueStoreError + 'static> KeyValueStoreError for EncryptionError<E> {
+    const BACKEND: &'static str = "encryption";
+
+    fn must_reload_view(&self) -> bool {
+        match self {
+            EncryptionError::InnerStoreError(e) => e.must_reload_view(),
+            _ => false,
+        }
+    }
+}
+
+impl<D> WithError for EncryptionDatabase<D>
+where
+    D: WithError,
+    D::Error: 'static,
+{
+    type Error = EncryptionError<D::Error>;
+}
+
+impl<S> WithError for EncryptionStore<S>
+where
+    S: WithError,
+    S::Error: 'static,
+{
+    type Error = EncryptionError<S::Error>;
+}
+
+impl<S> EncryptionStore<S>
+where
+    S: WithError,
+    S::Error: 'static,
+{
+    /// Returns the number of keys under which a key may be stored.
+    fn stored_key_count(&self) -> usize {
+        if self.keys.encrypt_keys {
+            self.keys.keys.len()
+        } else {
+            1
+        }
+    }
+
+    /// Returns the keys under which a key may be stored, starting with the current one.
+    fn stored_keys(&self, key: &[u8]) -> Vec<Vec<u8>> {
+        if self.keys.encrypt_keys {
+            self.keys
+                .keys
+                .iter()
+                .map(|encryption_key| encryption_key.encrypt_key(key))
+                .collect()
+        } else {
+            vec![key.to_vec()]
+        }
+    }
+
+    /// Returns the data the value of `key` is bound to.
+    fn associated_data(&self, key: &[u8]) -> Result<Vec<u8>, EncryptionError<S::Error>> {
+        Ok(bcs::to_bytes(&(&self.root_key, key))?)
+    }
+
+    fn encrypt_value(
+        &self,
+        key: &[u8],
+        value: &[u8],
+    ) -> Result<Vec<u8>, EncryptionError<S::Error>> {
+        let associated_data = self.associated_data(key)?;
+        Ok(self.keys.current().encrypt_value(&associated_data, value))
+    }
+
+    fn decrypt_value(
+        &self,
+        key: &[u8],
+        value: &[u8],
+    ) -> Result<Vec<u8>, EncryptionError<S::Error>> {
+        let id = value
+            .get(..KEY_ID_LENGTH)
+            .ok_or(EncryptionError::DecryptionFailed)?;
+        let encryption_key = self
+            .keys
+            .keys
+            .iter()
+            .find(|encryption_key| encryption_key.id == id)
+            .ok_or(EncryptionError::UnknownKey)?;
+        let associated_data = self.associated_data(key)?;
+        encryption_key
+            .decrypt_value(&associated_data, value)
+            .ok_or(EncryptionError::DecryptionFailed)
+    }
+
+    /// Returns the prefix of the stored keys for an encryption key, with the encoder of
+    /// their suffixes.
+    fn stored_prefix(encryption_key: &EncryptionKey, key_prefix: &[u8]) -> (Vec<u8>, KeyEncoder) {
+        let mut encoder = encryption_key.key_encoder();
+        let mut stored_prefix = encryption_key.id.to_vec();
+        encoder.encode(key_prefix, &mut stored_prefix);
+        (stored_prefix, encoder)
+    }
+
+    /// Keeps the decrypted entries that are in the range, in the order of the range, and
+    /// applies its limit.
+    fn filter_entries<T>(entries: &mut Vec<T>, range: &KeyRange, key: impl Fn(&T) -> &[u8]) {
+        entries.retain(|entry| range.contains(key(entry)));
+        entries.sort_by(|entry1, entry2| key(entry1).cmp(key(entry2)));
+        if range.reverse {
+            entries.reverse();
+        }
+        entries.truncate(range.max_entries());
+    }
+}
+
+impl<S> EncryptionStore<S>
+where
+    S: ReadableKeyValueStore,
+    S::Error: 'static,
+{
+    /// Finds the encrypted keys of a part of a range, in no particular order.
+    async fn find_keys_in_part(
+        &self,
+        key_prefix: &[u8],
+        part: &ScanPart,
+    ) -> Result<Vec<Vec<u8>>, EncryptionError<S::Error>> {
+        match part {
+            ScanPart::Key(key) => {
+                let found = self.contains_key(&[key_prefix, key].concat()).await?;
+                Ok(if found { vec![key.clone()] } else { Vec::new() })
+            }
+            ScanPart::Prefix(prefix) => {
+                let mut keys = Vec::new();
+                for encryption_key in &self.keys.keys {
+                    let (stored_prefix, encoder) =
+                        Self::stored_prefix(encryption_key, &[key_prefix, prefix].concat());
+                    for stored_key in self.store.find_keys_by_prefix(&stored_prefix).await? {
+                        keys.push([&prefix[..], &encoder.clone().decode(&stored_key)].concat());
+                    }
+                }
+                Ok(keys)
+            }
+        }
+    }
+
+    /// Finds the encrypted keys of a part of a range with their values, in no particular
+    /// order. Only the values of the keys in the range are decrypted.
+    async fn find_key_values_in_part(
+        &self,
+        key_prefix: &[u8],
+        part: &ScanPart,
+        range: &KeyRange,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, EncryptionError<S::Error>> {
+        match part {
+            ScanPart::Key(key) => {
+                let value = self.read_value_bytes(&[key_prefix, key].concat()).await?;
+                Ok(value
+                    .map(|value| (key.clone(), value))
+                    .into_iter()
+                    .collect())
+            }
+            ScanPart::Prefix(prefix) => {
+                let mut key_values = Vec::new();
+                for encryption_key in &self.keys.keys {
+                    let (stored_prefix, encoder) =
+                        Self::stored_prefix(encryption_key, &[key_prefix, prefix].concat());
+                    let stored_key_values =
+                        self.store.find_key_values_by_prefix(&stored_prefix).await?;
+                    for (stored_key, value) in stored_key_values {
+                        let key = [&prefix[..], &encoder.clone().decode(&stored_key)].concat();
+                        if range.contains(&key) {
+                            let value = self.decrypt_value(&[key_prefix, &key].concat(), &value)?;
+                            key_values.push((key, value));
+                        }
+                    }
+                }
+                Ok(key_values)
+            }
+        }
+    }
+}
+
+impl<S> ReadableKeyValueStore for EncryptionStore<S>
+where
+    S: ReadableKeyValueStore,
+    S::Error: 'static,
+{
+    // Encrypted keys start with the key identifier.
+    const MAX_KEY_SIZE: usize = S::MAX_KEY_SIZE - KEY_ID_LENGTH;
+
+    fn root_key(&self) -> Result<Vec<u8>, Self::Error> {
+        Ok(self.store.root_key()?)
+    }
+
+    async fn read_value_bytes(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+        let mut values = self.read_multi_values_bytes(&[key.to_vec()]).await?;
+        Ok(values.pop().flatten())
+    }
+
+    async fn contains_key(&self, key: &[u8]) -> Result<bool, Self::Error> {
+        let results = self.contains_keys(&[key.to_vec()]).await?;
+        Ok(results[0])
+    }
+
+    async fn contains_keys(&self, keys: &[Vec<u8>]) -> Result<Vec<bool>, Self::Error> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let stored_keys = keys
+            .iter()
+            .flat_map(|key| self.stored_keys(key))
+            .collect::<Vec<_>>();
+        let results = self.store.contains_keys(&stored_keys).await?;
+        Ok(results
+            .chunks(self.stored_key_count())
+            .map(|results| results.iter().any(|result| *result))
+            .collect())
+    }
+
+    async fn read_multi_values_bytes(
+        &self,
+        keys: &[Vec<u8>],
+    ) -> Result<Vec<Option<Vec<u8>>>, Self::Error> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let stored_keys = keys
+            .iter()
+            .flat_map(|key| self.stored_keys(key))
+            .collect::<Vec<_>>();
+        let values = self.store.read_multi_values_bytes(&stored_keys).await?;
+        keys.iter()
+            .zip(values.chunks(self.stored_key_count()))
+            .map(|(key, values)| {
+                values
+                    .iter()
+                    .flatten()
+                    .next()
+                    .map(|value| self.decrypt_value(key, value))
+                    .transpose()
+            })
+            .collect()
+    }
+
+    async fn find_keys_by_prefix(&self, key_prefix: &[u8]) -> Result<Vec<Vec<u8>>, Self::Error> {
+        if !self.keys.encrypt_keys {
+            return Ok(self.store.find_keys_by_prefix(key_prefix).await?);
+        }
+        self.find_keys_in_range(key_prefix, &KeyRange::default())
+            .await
+    }
+
+    async fn find_key_values_by_prefix(
+        &self,
+        key_prefix: &[u8],
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error> {
+        if !self.keys.encrypt_keys {
+            let key_values = self.store.find_key_values_by_prefix(key_prefix).await?;
+            return key_values
+                .into_iter()
+                .map(|(key, value)| {
+                    let value = self.decrypt_value(&[key_prefix, &key].concat(), &value)?;
+                    Ok((key, value))
+                })
+                .collect();
+        }
+        self.find_key_values_in_range(key_prefix, &KeyRange::default())
+            .await
+    }
+
+    async fn find_keys_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<Vec<u8>>, Self::Error> {
+        if !self.keys.encrypt_keys {
+            return Ok(self.store.find_keys_in_range(key_prefix, range).await?);
+        }
+        let mut keys = Vec::new();
+        for part in ScanPart::split(range) {
+            if keys.len() >= range.max_entries() {
+                break;
+            }
+            let mut part_keys = self.find_keys_in_part(key_prefix, &part).await?;
+            Self::filter_entries(&mut part_keys, range, |key| key);
+            keys.extend(part_keys);
+        }
+        keys.truncate(range.max_entries());
+        Ok(keys)
+    }
+
+    async fn find_key_values_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error> {
+        let mut key_values = Vec::new();
+        if !self.keys.encrypt_keys {
+            for (key, value) in self
+                .store
+                .find_key_values_in_range(key_prefix, range)
+                .await?
+            {
+                let value = self.decrypt_value(&[key_prefix, &key].concat(), &value)?;
+                key_values.push((key, value));
+            }
+            return Ok(key_values);
+        }
+        for part in ScanPart::split(range) {
+            if key_values.len() >= range.max_entries() {
+                break;
+            }
+            let mut part_key_values = self
+                .find_key_values_in_part(key_prefix, &part, range)
+                .await?;
+            Self::filter_entries(&mut part_key_values, range, |(key, _)| key);
+            key_values.extend(part_key_values);
+        }
+        key_values.truncate(range.max_entries());
+        Ok(key_values)
+    }
+
+    async fn has_journal(&self) -> Result<bool, Self::Error> {
+        // The journal is written in plaintext by the store below.
+        Ok(self.store.has_journal().await?)
+    }
+}
+
+impl<S> WritableKeyValueStore for EncryptionStore<S>
+where
+    S: WritableKeyValueStore,
+    S::Error: 'static,
+{
+    const MAX_VALUE_SIZE: usize = S::MAX_VALUE_SIZE - VALUE_OVERHEAD;
+
+    async fn write_batch(&self, batch: Batch) -> Result<(), Self::Error> {
+        let mut stored_batch = Batch::new();
+        for operation in batch.operations {
+            match operation {
+                WriteOperation::Delete { key } => {
+                    for stored_key in self.stored_keys(&key) {
+                        stored_batch.delete_key(stored_key);
+                    }
+                }
+                WriteOperation::Put { key, value } => {
+                    let value = self.encrypt_value(&key, &value)?;
+                    let mut stored_keys = self.stored_keys(&key).into_iter();
+                    let stored_key = stored_keys.next().expect("the current key comes first");
+                    stored_batch.put_key_value_bytes(stored_key, value);
+                    // Remove the copies under the previous keys so that the entry is
+                    // listed only once.
+                    for stored_key in stored_keys {
+                        stored_batch.delete_key(stored_key);
+                    }
+                }
+                WriteOperation::DeletePrefix { key_prefix } => {
+                    for stored_prefix in self.stored_keys(&key_prefix) {
+                        stored_batch.delete_key_prefix(stored_prefix);
+                    }
+                }
+            }
+        }
+        Ok(self.store.write_batch(stored_batch).await?)
+    }
+
+    async fn clear_journal(&self) -> Result<(), Self::Error> {
+        Ok(self.store.clear_journal().await?)
+    }
+}
+
+impl<S> EncryptionStore<S>
+where
+    S: ReadableKeyValueStore + WritableKeyValueStore,
+    S::Error: 'static,
+{
+    /// Rewrites all the data of the partition with the current key, after which the
+    /// previous keys are no longer needed for it.
+    pub async fn rotate_keys(&self) -> Result<(), EncryptionError<S::Error>> {
+        let mut batch = Batch::new();
+        for (key, value) in self.find_key_values_by_prefix(&[]).await? {
+            batch.put_key_value_bytes(key, value);
+        }
+        self.write_batch(batch).await
+    }
+}
+
+impl<D> KeyValueDatabase for EncryptionDatabase<D>
+where
+    D: KeyValueDatabase,
+    D::Error: 'static,
+{
+    type Config = EncryptionConfig<D::Config>;
+
+    type Store = EncryptionStore<D::Store>;
+
+    fn get_name() -> String {
+        format!("encryption {}", D::get_name())
+    }
+
+    async fn connect(config: &Self::Config, namespace: &str) -> Result<Self, Self::Error> {
+        let keys = config.keys_config.load().map_err(EncryptionError::Key)?;
+        let database = D::connect(&config.inner_config, namespace).await?;
+        Ok(EncryptionDatabase {
+            database,
+            keys: Arc::new(keys),
+        })
+    }
+
+    fn open_shared(&self, root_key: &[u8]) -> Result<Self::Store, Self::Error> {
+        let store = self.database.open_shared(root_key)?;
+        Ok(self.wrap_store(store, root_key))
+    }
+
+    fn open_exclusive(&self, root_key: &[u8]) -> Result<Self::Store, Self::Error> {
+        let store = self.database.open_exclusive(root_key)?;
+        Ok(self.wrap_store(store, root_key))
+    }
+
+    async fn list_all(config: &Self::Config) -> Result<Vec<String>, Self::Error> {
+        Ok(D::list_all(&config.inner_config).await?)
+    }
+
+    async fn list_root_keys(&self) -> Result<Vec<Vec<u8>>, Self::Error> {
+        Ok(self.database.list_root_keys().await?)
+    }
+
+    async fn delete_all(config: &Self::Config) -> Result<(), Self::Error> {
+        Ok(D::delete_all(&config.inner_config).await?)
+    }
+
+    async fn exists(config: &Self::Config, namespace: &str) -> Result<bool, Self::Error> {
+        Ok(D::exists(&config.inner_config, namespace).await?)
+    }
+
+    async fn create(config: &Self::Config, namespace: &str) -> Result<(), Self::Error> {
+        Ok(D::create(&config.inner_config, namespace).await?)
+    }
+
+    async fn delete(config: &Self::Config, namespace: &str) -> Result<(), Self::Error> {
+        Ok(D::delete(&config.inner_config, namespace).await?)
+    }
+}
+
+impl<D> EncryptionDatabase<D>
+where
+    D: KeyValueDatabase,
+    D::Error: 'static,
+{
+    fn wrap_store(&self, store: D::Store, root_key: &[u8]) -> EncryptionStore<D::Store> {
+        EncryptionStore {
+            store,
+            keys: self.keys.clone(),
+            root_key: root_key.to_vec(),
+        }
+    }
+}
+
+#[cfg(with_testing)]
+impl<D> TestKeyValueDatabase for EncryptionDatabase<D>
+where
+    D: TestKeyValueDatabase,
+    D::Error: 'static,
+{
+    async fn new_test_config() -> Result<EncryptionConfig<D::Config>, Self::Error> {
+        use rand::RngCore as _;
+
+        let inner_config = D::new_test_config().await?;
+        let mut secret = [0u8; ENCRYPTION_SECRET_LENGTH];
+        crate::random::make_nondeterministic_rng().fill_bytes(&mut secret);
+        let mut path_with_guard = PathWithGuard::new_testing();
+        path_with_guard.path_buf.push("encryption.key");
+        std::fs::write(&path_with_guard.path_buf, hex::encode(secret)).map_err(|error| {
+            EncryptionError::Key(EncryptionKeyError::Io(
+                path_with_guard.path_buf.clone(),
+                error,
+            ))
+        })?;
+        let keys_config = EncryptionKeysConfig {
+            key: EncryptionKeySource::File(path_with_guard),
+            previous_keys: Vec::new(),
+            encrypt_keys: true,
+        };
+        Ok(EncryptionConfig {
+            inner_config,
+            keys_config,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeSet, path::Path};
+
+    use super::*;
+    use crate::{
+        journaling::JOURNAL_TAG,
+        memory::{MemoryDatabase, MemoryStoreConfig},
+        random::generate_test_namespace,
+    };
+
+    fn write_key(dir: &Path, name: &str, byte: u8) -> EncryptionKeySource {
+        let path = dir.join(name);
+        std::fs::write(&path, hex::encode([byte; ENCRYPTION_SECRET_LENGTH])).unwrap();
+        EncryptionKeySource::File(PathWithGuard::new(path))
+    }
+
+    fn config(
+        key: &EncryptionKeySource,
+        previous_keys: &[&EncryptionKeySource],
+        encrypt_keys: bool,
+    ) -> EncryptionConfig<MemoryStoreConfig> {
+        EncryptionConfig {
+            inner_config: MemoryStoreConfig {
+                kill_on_drop: false,
+            },
+            keys_config: EncryptionKeysConfig {
+                key: key.clone(),
+                previous_keys: previous_keys.iter().map(|key| (*key).clone()).collect(),
+                encrypt_keys,
+            },
+        }
+    }
+
+    #[test]
+    fn test_key_encoding_preserves_prefixes_only() {
+        let encryption_key = EncryptionKey::new([7; ENCRYPTION_SECRET_LENGTH]);
+        let keys = [
+            vec![],
+            vec![0],
+            vec![0, 0],
+            vec![0, 255],
+            vec![1],
+            vec![1, 2, 3],
+            vec![1, 2, 4],
+            vec![255],
+            vec![255, 0],
+        ];
+        let encoded = keys
+            .iter()
+            .map(|key| encryption_key.encrypt_key(key))
+            .collect::<Vec<_>>();
+        assert!(encoded[5].starts_with(&encryption_key.encrypt_key(&[1, 2])));
+        assert!(!encoded[5].starts_with(&encryption_key.encrypt_key(&[1, 3])));
+        for (key, encoded) in keys.iter().zip(&encoded) {
+            assert_eq!(encoded.len(), KEY_ID_LENGTH + key.len());
+            let decoded = encryption_key
+                .key_encoder()
+                .decode(&encoded[KEY_ID_LENGTH..]);
+            assert_eq!(&decoded, key);
+        }
+        let other_key = EncryptionKey::new([8; ENCRYPTION_SECRET_LENGTH]);
+        assert_ne!(other_key.encrypt_key(&[1, 2, 3]), encoded[5]);
+
+        // The bytes are encrypted by a permutation that doesn't preserve their order.
+        let codes = (0..=u8::MAX)
+            .map(|byte| encryption_key.encrypt_key(&[byte])[KEY_ID_LENGTH])
+            .collect::<Vec<_>>();
+        assert_eq!(codes.iter().collect::<BTreeSet<_>>().len(), 256);
+        assert!(!codes.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[tokio::test]
+    async fn test_encryption_hides_the_data() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = write_key(dir.path(), "key", 1);
+        let namespace = generate_test_namespace();
+        for encrypt_keys in [false, true] {
+            let config = config(&key, &[], encrypt_keys);
+            let database =
+                EncryptionDatabase::<MemoryDatabase>::recreate_and_connect(&config, &namespace)
+                    .await
+                    .unwrap();
+            let store = database.open_exclusive(&[5]).unwrap();
+            let mut batch = Batch::new();
+            batch.put_key_value_bytes(b"secret key".to_vec(), b"secret value".to_vec());
+            store.write_batch(batch).await.unwrap();
+            let value = store.read_value_bytes(b"secret key").await.unwrap();
+            assert_eq!(value.as_deref(), Some(&b"secret value"[..]));
+
+            let inner_store = database.database.open_exclusive(&[5]).unwrap();
+            let stored = inner_store.find_key_values_by_prefix(&[]).await.unwrap();
+            assert_eq!(stored.len(), 1);
+            let (stored_key, stored_value) = &stored[0];
+            assert_eq!(stored_key == b"secret key", !encrypt_keys);
+            assert!(!stored_value
+                .windows(b"secret value".len())
+                .any(|window| window == b"secret value"));
+
+            // A value cannot be moved to another partition.
+            let other_store = database.open_exclusive(&[6]).unwrap();
+            let mut batch = Batch::new();
+            batch.put_key_value_bytes(stored_key.clone(), stored_value.clone());
+            database
+                .database
+                .open_exclusive(&[6])
+                .unwrap()
+                .write_batch(batch)
+                .await
+                .unwrap();
+            assert!(matches!(
+                other_store.read_value_bytes(b"secret key").await,
+                Err(EncryptionError::DecryptionFailed)
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_key_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let old_key = write_key(dir.path(), "old", 1);
+        let new_key = write_key(dir.path(), "new", 2);
+        for encrypt_keys in [false, true] {
+            let namespace = generate_test_namespace();
+            let old_config = config(&old_key, &[], encrypt_keys);
+            let database =
+                EncryptionDatabase::<MemoryDatabase>::recreate_and_connect(&old_config, &namespace)
+                    .await
+                    .unwrap();
+            let store = database.open_exclusive(&[]).unwrap();
+            let mut batch = Batch::new();
+            for i in 0..10u8 {
+                batch.put_key_value_bytes(vec![0, i], vec![i]);
+            }
+            store.write_batch(batch).await.unwrap();
+
+            // During the rotation, both keys are readable and the data is not duplicated.
+            let rotation_config = config(&new_key, &[&old_key], encrypt_keys);
+            let database =
+                EncryptionDatabase::<MemoryDatabase>::connect(&rotation_config, &namespace)
+                    .await
+                    .unwrap();
+            let store = database.open_exclusive(&[]).unwrap();
+            let mut batch = Batch::new();
+            batch.put_key_value_bytes(vec![0, 3], vec![30]);
+            batch.put_key_value_bytes(vec![0, 20], vec![20]);
+            store.write_batch(batch).await.unwrap();
+            let range = KeyRange::default().reversed().with_limit(3);
+            let keys = store.find_keys_in_range(&[0], &range).await.unwrap();
+            assert_eq!(keys, vec![vec![20], vec![9], vec![8]]);
+            let key_values = store.find_key_values_by_prefix(&[0]).await.unwrap();
+            assert_eq!(key_values.len(), 11);
+            assert_eq!(key_values[3], (vec![3], vec![30]));
+            store.rotate_keys().await.unwrap();
+
+            // After the rotation, the previous key is no longer needed.
+            let new_config = config(&new_key, &[], encrypt_keys);
+            let database = EncryptionDatabase::<MemoryDatabase>::connect(&new_config, &namespace)
+                .await
+                .unwrap();
+            let store = database.open_exclusive(&[]).unwrap();
+            let key_values = store.find_key_values_by_prefix(&[0]).await.unwrap();
+            assert_eq!(key_values.len(), 11);
+            assert_eq!(key_values[3], (vec![3], vec![30]));
+            let inner_store = database.database.open_exclusive(&[]).unwrap();
+            assert_eq!(
+                inner_store.find_keys_by_prefix(&[]).await.unwrap().len(),
+                11
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_range_pages_only_list_the_keys_around_them() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = write_key(dir.path(), "key", 1);
+        let namespace = generate_test_namespace();
+        let config = config(&key, &[], true);
+        let database =
+            EncryptionDatabase::<MemoryDatabase>::recreate_and_connect(&config, &namespace)
+                .await
+                .unwrap();
+        let store = database.open_exclusive(&[]).unwrap();
+        let mut batch = Batch::new();
+        for first in [1u8, 5, 9] {
+            for second in 0..4u8 {
+                batch.put_key_value_bytes(vec![7, first, second], vec![first, second]);
+            }
+        }
+        batch.put_key_value_bytes(vec![7], vec![0]);
+        store.write_batch(batch).await.unwrap();
+
+        // Corrupt the values of the keys starting with 9.
+        let inner_store = database.database.open_exclusive(&[]).unwrap();
+        let stored_prefix = database.keys.current().encrypt_key(&[7, 9]);
+        let mut batch = Batch::new();
+        let stored = inner_store
+            .find_key_values_by_prefix(&stored_prefix)
+            .await
+            .unwrap();
+        for (stored_key, mut value) in stored {
+            *value.last_mut().unwrap() ^= 1;
+            batch.put_key_value_bytes([&stored_prefix[..], &stored_key].concat(), value);
+        }
+        inner_store.write_batch(batch).await.unwrap();
+
+        // The first pages in either direction don't reach them.
+        let range = KeyRange::default().with_limit(3);
+        let key_values = store.find_key_values_in_range(&[7], &range).await.unwrap();
+        assert_eq!(
+            key_values,
+            vec![
+                (vec![], vec![0]),
+                (vec![1, 0], vec![1, 0]),
+                (vec![1, 1], vec![1, 1]),
+            ]
+        );
+        let range = range.after(&[1, 1]);
+        let keys = store.find_keys_in_range(&[7], &range).await.unwrap();
+        assert_eq!(keys, vec![vec![1, 2], vec![1, 3], vec![5, 0]]);
+        let range = KeyRange::new(KeyBound::Unbounded, KeyBound::Excluded(vec![9]))
+            .reversed()
+            .with_limit(2);
+        let key_values = store.find_key_values_in_range(&[7], &range).await.unwrap();
+        assert_eq!(
+            key_values,
+            vec![(vec![5, 3], vec![5, 3]), (vec![5, 2], vec![5, 2])]
+        );
+        let range = KeyRange::new(
+            KeyBound::Included(vec![5, 1]),
+            KeyBound::Included(vec![5, 2]),
+        );
+        let keys = store.find_keys_in_range(&[7], &range).await.unwrap();
+        assert_eq!(keys, vec![vec![5, 1], vec![5, 2]]);
+
+        // Listing the whole prefix decrypts them.
+        assert!(matches!(
+            store.find_key_values_by_prefix(&[7]).await,
+            Err(EncryptionError::DecryptionFailed)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_journal_is_found_below_the_encryption() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = write_key(dir.path(), "key", 1);
+        for encrypt_keys in [false, true] {
+            let namespace = generate_test_namespace();
+            let config = config(&key, &[], encrypt_keys);
+            let database =
+                EncryptionDatabase::<MemoryDatabase>::recreate_and_connect(&config, &namespace)
+                    .await
+                    .unwrap();
+            let store = database.open_exclusive(&[5]).unwrap();
+            let mut batch = Batch::new();
+            batch.put_key_value_bytes(vec![JOURNAL_TAG + 1], vec![1]);
+            store.write_batch(batch).await.unwrap();
+            assert!(!store.has_journal().await.unwrap());
+
+            // The journal is written in plaintext by the store below.
+            let mut batch = Batch::new();
+            batch.put_key_value_bytes(vec![JOURNAL_TAG, 1, 0, 0, 0, 0], vec![0]);
+            database
+                .database
+                .open_exclusive(&[5])
+                .unwrap()
+                .write_batch(batch)
+                .await
+                .unwrap();
+            assert!(store.has_journal().await.unwrap());
+        }
+    }
+
+    #[test]
+    fn test_key_source_from_str() {
+        assert_eq!(
+            EncryptionKeySource::from_str("env:STORAGE_KEY").unwrap(),
+            EncryptionKeySource::Env("STORAGE_KEY".to_string())
+        );
+        let source = EncryptionKeySource::from_str("file:/path/to/key").unwrap();
+        assert_eq!(
+            source,
+            EncryptionKeySource::File(PathWithGuard::new(PathBuf::from("/path/to/key")))
+        );
+        assert_eq!(source.to_string(), "file:/path/to/key");
+        assert!(EncryptionKeySource::from_str("/path/to/key").is_err());
+    }
+}
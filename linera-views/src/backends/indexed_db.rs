@@ -17,7 +17,7 @@ use crate::{
     batch::{Batch, WriteOperation},
     common::get_upper_bound_option,
     store::{
-        KeyValueDatabase, KeyValueStoreError, ReadableKeyValueStore, WithError,
+        KeyRange, KeyValueDatabase, KeyValueStoreError, ReadableKeyValueStore, WithError,
         WritableKeyValueStore,
     },
 };
@@ -92,6 +92,69 @@ fn prefix_to_range(prefix: &[u8]) -> (Bound<JsValue>, Bound<JsValue>) {
     (lower, upper)
 }
 
+/// Returns the bounds of the keys starting with `prefix` whose remainder belongs to the
+/// `range`, or `None` if there are no such keys.
+fn key_range_to_range(prefix: &[u8], range: &KeyRange) -> Option<(Bound<JsValue>, Bound<JsValue>)> {
+    let (lower, upper) = range.bounds_with_prefix(prefix)?;
+    let lower = Bound::Included(js_sys::Uint8Array::from(&lower[..]).into());
+    let upper = if let Some(upper) = upper {
+        Bound::Excluded(js_sys::Uint8Array::from(&upper[..]).into())
+    } else {
+        Bound::Unbounded
+    };
+    Some((lower, upper))
+}
+
+impl IndexedDbStore {
+    async fn find_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+        with_values: bool,
+    ) -> Result<Vec<(Vec<u8>, Option<Vec<u8>>)>> {
+        let key_prefix = self.full_key(key_prefix);
+        let Some(bounds) = key_range_to_range(&key_prefix, range) else {
+            return Ok(Vec::new());
+        };
+        let direction = if range.reverse {
+            indexed_db::CursorDirection::Prev
+        } else {
+            indexed_db::CursorDirection::Next
+        };
+        let max_entries = range.max_entries();
+        self.with_object_store(move |object_store| async move {
+            let mut entries = vec![];
+            let builder = object_store.cursor().range(bounds)?.direction(direction);
+            let mut cursor = if with_values {
+                builder.open().await?
+            } else {
+                builder.open_key().await?
+            };
+
+            while let Some(key) = cursor.primary_key() {
+                if entries.len() == max_entries {
+                    break;
+                }
+                let key = js_sys::Uint8Array::new(&key);
+                let value = with_values.then(|| {
+                    let value = cursor
+                        .value()
+                        .expect("we should have a value because we have a key");
+                    js_sys::Uint8Array::new(&value).to_vec()
+                });
+                entries.push((
+                    key.subarray(key_prefix.len() as u32, key.length()).to_vec(),
+                    value,
+                ));
+                cursor.advance(1).await?;
+            }
+
+            Ok(entries)
+        })
+        .await?
+    }
+}
+
 impl WithError for IndexedDbStore {
     type Error = IndexedDbStoreError;
 }
@@ -182,6 +245,27 @@ impl ReadableKeyValueStore for IndexedDbStore {
         })
         .await?
     }
+
+    async fn find_keys_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<Vec<u8>>> {
+        let entries = self.find_in_range(key_prefix, range, false).await?;
+        Ok(entries.into_iter().map(|(key, _)| key).collect())
+    }
+
+    async fn find_key_values_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        let entries = self.find_in_range(key_prefix, range, true).await?;
+        Ok(entries
+            .into_iter()
+            .map(|(key, value)| (key, value.unwrap_or_default()))
+            .collect())
+    }
 }
 
 impl WritableKeyValueStore for IndexedDbStore {
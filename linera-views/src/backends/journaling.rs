@@ -26,7 +26,7 @@ use thiserror::Error;
 use crate::{
     batch::{Batch, BatchValueWriter, DeletePrefixExpander, SimplifiedBatch},
     store::{
-        DirectKeyValueStore, KeyValueDatabase, KeyValueStoreError, ReadableKeyValueStore,
+        DirectKeyValueStore, KeyRange, KeyValueDatabase, KeyValueStoreError, ReadableKeyValueStore,
         WithError, WritableKeyValueStore,
     },
     views::MIN_VIEW_TAG,
@@ -108,8 +108,9 @@ impl<E: KeyValueStoreError> From<bcs::Error> for JournalingResolutionError<E> {
     }
 }
 
-/// The tag used for the journal stuff.
-const JOURNAL_TAG: u8 = 0;
+/// The tag used for the journal stuff. A store has a pending journal if and only if it
+/// has keys starting with this tag.
+pub const JOURNAL_TAG: u8 = 0;
 // To prevent collisions, the tag value 0 is reserved for journals.
 // The tags used by views must be greater or equal than `MIN_VIEW_TAG`.
 sa::const_assert!(JOURNAL_TAG < MIN_VIEW_TAG);
@@ -202,6 +203,25 @@ where
     ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error> {
         Ok(self.store.find_key_values_by_prefix(key_prefix).await?)
     }
+
+    async fn find_keys_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<Vec<u8>>, Self::Error> {
+        Ok(self.store.find_keys_in_range(key_prefix, range).await?)
+    }
+
+    async fn find_key_values_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error> {
+        Ok(self
+            .store
+            .find_key_values_in_range(key_prefix, range)
+            .await?)
+    }
 }
 
 impl<D> KeyValueDatabase for JournalingKeyValueDatabase<D>
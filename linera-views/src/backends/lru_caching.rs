@@ -14,7 +14,7 @@ use crate::store::TestKeyValueDatabase;
 use crate::{
     batch::{Batch, WriteOperation},
     lru_prefix_cache::{LruPrefixCache, StorageCacheConfig},
-    store::{KeyValueDatabase, ReadableKeyValueStore, WithError, WritableKeyValueStore},
+    store::{KeyRange, KeyValueDatabase, ReadableKeyValueStore, WithError, WritableKeyValueStore},
 };
 
 #[cfg(with_metrics)]
@@ -328,6 +328,54 @@ where
         cache.insert_find_key_values(key_prefix.to_vec(), &key_values);
         Ok(key_values)
     }
+
+    async fn find_keys_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<Vec<u8>>, Self::Error> {
+        // Only the scans of a whole prefix are cached, so a range can be served from the
+        // cache if the keys of the prefix are known already, but its result isn't inserted.
+        if let Some(cache) = self.get_exclusive_cache() {
+            let mut cache = cache.lock().unwrap();
+            if let Some(keys) = cache.query_find_keys(key_prefix) {
+                return Ok(select_in_range(keys, |key| key, range));
+            }
+        }
+        self.store.find_keys_in_range(key_prefix, range).await
+    }
+
+    async fn find_key_values_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error> {
+        if let Some(cache) = self.get_exclusive_cache() {
+            let mut cache = cache.lock().unwrap();
+            if let Some(key_values) = cache.query_find_key_values(key_prefix) {
+                return Ok(select_in_range(key_values, |(key, _)| key, range));
+            }
+        }
+        self.store.find_key_values_in_range(key_prefix, range).await
+    }
+
+    async fn has_journal(&self) -> Result<bool, Self::Error> {
+        // The journal is written by the store below, bypassing the cache.
+        self.store.has_journal().await
+    }
+}
+
+/// Selects the entries whose keys belong to the `range`, in the direction and up to the
+/// limit of the range. The entries must be sorted by increasing keys.
+fn select_in_range<T>(entries: Vec<T>, key: impl Fn(&T) -> &Vec<u8>, range: &KeyRange) -> Vec<T> {
+    let entries = entries
+        .into_iter()
+        .filter(|entry| range.contains(key(entry)));
+    if range.reverse {
+        entries.rev().take(range.max_entries()).collect()
+    } else {
+        entries.take(range.max_entries()).collect()
+    }
 }
 
 impl<K> WritableKeyValueStore for LruCachingStore<K>
@@ -15,9 +15,9 @@ use thiserror::Error;
 use crate::store::TestKeyValueDatabase;
 use crate::{
     batch::{Batch, WriteOperation},
-    common::get_key_range_for_prefix,
+    common::{get_entries_in_range, get_key_range_for_prefix},
     store::{
-        KeyValueDatabase, KeyValueStoreError, ReadableKeyValueStore, WithError,
+        KeyRange, KeyValueDatabase, KeyValueStoreError, ReadableKeyValueStore, WithError,
         WritableKeyValueStore,
     },
 };
@@ -195,6 +195,38 @@ impl ReadableKeyValueStore for MemoryStore {
         }
         Ok(key_values)
     }
+
+    async fn find_keys_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<Vec<u8>>, MemoryStoreError> {
+        let map = self
+            .map
+            .read()
+            .expect("MemoryStore lock should not be poisoned");
+        let len = key_prefix.len();
+        Ok(get_entries_in_range(&map, key_prefix, range)
+            .take(range.max_entries())
+            .map(|(key, _value)| key[len..].to_vec())
+            .collect())
+    }
+
+    async fn find_key_values_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, MemoryStoreError> {
+        let map = self
+            .map
+            .read()
+            .expect("MemoryStore lock should not be poisoned");
+        let len = key_prefix.len();
+        Ok(get_entries_in_range(&map, key_prefix, range)
+            .take(range.max_entries())
+            .map(|(key, value)| (key[len..].to_vec(), value.clone()))
+            .collect())
+    }
 }
 
 impl WritableKeyValueStore for MemoryStore {
@@ -19,7 +19,7 @@ use prometheus::{exponential_buckets, HistogramVec, IntCounterVec};
 use crate::store::TestKeyValueDatabase;
 use crate::{
     batch::Batch,
-    store::{KeyValueDatabase, ReadableKeyValueStore, WithError, WritableKeyValueStore},
+    store::{KeyRange, KeyValueDatabase, ReadableKeyValueStore, WithError, WritableKeyValueStore},
 };
 
 #[derive(Clone)]
@@ -31,6 +31,8 @@ pub struct KeyValueStoreMetrics {
     read_multi_values_bytes_latency: HistogramVec,
     find_keys_by_prefix_latency: HistogramVec,
     find_key_values_by_prefix_latency: HistogramVec,
+    find_keys_in_range_latency: HistogramVec,
+    find_key_values_in_range_latency: HistogramVec,
     write_batch_latency: HistogramVec,
     clear_journal_latency: HistogramVec,
     connect_latency: HistogramVec,
@@ -56,6 +58,8 @@ pub struct KeyValueStoreMetrics {
     find_key_values_by_prefix_prefix_size: HistogramVec,
     find_key_values_by_prefix_num_keys: HistogramVec,
     find_key_values_by_prefix_key_values_size: HistogramVec,
+    find_keys_in_range_num_keys: HistogramVec,
+    find_key_values_in_range_num_keys: HistogramVec,
     write_batch_size: HistogramVec,
     list_all_sizes: HistogramVec,
     exists_true_cases: IntCounterVec,
@@ -133,6 +137,16 @@ impl KeyValueStoreMetrics {
         let find_key_values_by_prefix_latency =
             register_histogram_vec(&entry1, &entry2, &[], latency_buckets.clone());
 
+        let entry1 = format!("{var_name}_find_keys_in_range_latency");
+        let entry2 = format!("{title_name} find keys in range latency");
+        let find_keys_in_range_latency =
+            register_histogram_vec(&entry1, &entry2, &[], latency_buckets.clone());
+
+        let entry1 = format!("{var_name}_find_key_values_in_range_latency");
+        let entry2 = format!("{title_name} find key values in range latency");
+        let find_key_values_in_range_latency =
+            register_histogram_vec(&entry1, &entry2, &[], latency_buckets.clone());
+
         let entry1 = format!("{var_name}_write_batch_latency");
         let entry2 = format!("{title_name} write batch latency");
         let write_batch_latency =
@@ -254,6 +268,16 @@ impl KeyValueStoreMetrics {
         let find_key_values_by_prefix_key_values_size =
             register_histogram_vec(&entry1, &entry2, &[], size_buckets.clone());
 
+        let entry1 = format!("{var_name}_find_keys_in_range_num_keys");
+        let entry2 = format!("{title_name} find keys in range num keys");
+        let find_keys_in_range_num_keys =
+            register_histogram_vec(&entry1, &entry2, &[], count_buckets.clone());
+
+        let entry1 = format!("{var_name}_find_key_values_in_range_num_keys");
+        let entry2 = format!("{title_name} find key values in range num keys");
+        let find_key_values_in_range_num_keys =
+            register_histogram_vec(&entry1, &entry2, &[], count_buckets.clone());
+
         let entry1 = format!("{var_name}_write_batch_size");
         let entry2 = format!("{title_name} write batch size");
         let write_batch_size = register_histogram_vec(&entry1, &entry2, &[], size_buckets);
@@ -273,6 +297,8 @@ impl KeyValueStoreMetrics {
             read_multi_values_bytes_latency,
             find_keys_by_prefix_latency,
             find_key_values_by_prefix_latency,
+            find_keys_in_range_latency,
+            find_key_values_in_range_latency,
             write_batch_latency,
             clear_journal_latency,
             connect_latency,
@@ -298,6 +324,8 @@ impl KeyValueStoreMetrics {
             find_key_values_by_prefix_prefix_size,
             find_key_values_by_prefix_num_keys,
             find_key_values_by_prefix_key_values_size,
+            find_keys_in_range_num_keys,
+            find_key_values_in_range_num_keys,
             write_batch_size,
             list_all_sizes,
             exists_true_cases,
@@ -461,6 +489,40 @@ where
             .observe(key_values_size as f64);
         Ok(result)
     }
+
+    async fn find_keys_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<Vec<u8>>, Self::Error> {
+        let _latency = self.counter.find_keys_in_range_latency.measure_latency();
+        let result = self.store.find_keys_in_range(key_prefix, range).await?;
+        self.counter
+            .find_keys_in_range_num_keys
+            .with_label_values(&[])
+            .observe(result.len() as f64);
+        Ok(result)
+    }
+
+    async fn find_key_values_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error> {
+        let _latency = self
+            .counter
+            .find_key_values_in_range_latency
+            .measure_latency();
+        let result = self
+            .store
+            .find_key_values_in_range(key_prefix, range)
+            .await?;
+        self.counter
+            .find_key_values_in_range_num_keys
+            .with_label_values(&[])
+            .observe(result.len() as f64);
+        Ok(result)
+    }
 }
 
 impl<S> WritableKeyValueStore for MeteredStore<S>
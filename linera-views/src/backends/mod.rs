@@ -8,18 +8,33 @@ pub mod metering;
 
 pub mod value_splitting;
 
+#[cfg(with_encryption)]
+pub mod encryption;
+
+#[cfg(with_compression)]
+pub mod compression;
+
 pub mod memory;
 
 pub mod lru_caching;
 
 pub mod dual;
 
+#[cfg(any(with_rocksdb, with_redb, with_encryption))]
+mod path_with_guard;
+
+#[cfg(any(with_rocksdb, with_redb, with_encryption))]
+pub use path_with_guard::PathWithGuard;
+
 #[cfg(with_scylladb)]
 pub mod scylla_db;
 
 #[cfg(with_rocksdb)]
 pub mod rocks_db;
 
+#[cfg(with_redb)]
+pub mod redb;
+
 #[cfg(with_indexeddb)]
 pub mod indexed_db;
 
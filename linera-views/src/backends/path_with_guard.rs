@@ -0,0 +1,49 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The location of the databases stored in local files.
+
+use std::{path::PathBuf, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use tempfile::TempDir;
+
+/// A path and the guard for the temporary directory if needed
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PathWithGuard {
+    /// The path to the data
+    pub path_buf: PathBuf,
+    /// The guard for the directory if one is needed
+    #[serde(skip)]
+    _dir_guard: Option<Arc<TempDir>>,
+}
+
+impl PathWithGuard {
+    /// Creates a `PathWithGuard` from an existing path.
+    pub fn new(path_buf: PathBuf) -> Self {
+        Self {
+            path_buf,
+            _dir_guard: None,
+        }
+    }
+
+    /// Returns the path to a new temporary directory, which is removed once the last clone
+    /// is dropped.
+    #[cfg(with_testing)]
+    pub(crate) fn new_testing() -> PathWithGuard {
+        let dir = TempDir::new().unwrap();
+        let path_buf = dir.path().to_path_buf();
+        let dir_guard = Some(Arc::new(dir));
+        PathWithGuard {
+            path_buf,
+            _dir_guard: dir_guard,
+        }
+    }
+}
+
+impl PartialEq for PathWithGuard {
+    fn eq(&self, other: &Self) -> bool {
+        self.path_buf == other.path_buf
+    }
+}
+impl Eq for PathWithGuard {}
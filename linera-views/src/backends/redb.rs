@@ -0,0 +1,531 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Implements [`crate::store::KeyValueStore`] for redb, an embedded transactional
+//! database written in pure Rust.
+//!
+//! Each namespace is a directory containing a single database file, where all the
+//! partitions share one table. Batches are written in a single write transaction.
+
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    ops::Bound,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, LazyLock, Mutex, Weak,
+    },
+};
+
+use itertools::Either;
+use linera_base::ensure;
+use redb::{Database, ReadableDatabase as _, TableDefinition};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+pub use crate::backends::PathWithGuard;
+#[cfg(with_metrics)]
+use crate::metering::MeteredDatabase;
+#[cfg(with_testing)]
+use crate::store::TestKeyValueDatabase;
+use crate::{
+    batch::{Batch, WriteOperation},
+    common::get_upper_bound_option,
+    lru_caching::{LruCachingConfig, LruCachingDatabase},
+    store::{
+        KeyRange, KeyValueDatabase, KeyValueStoreError, ReadableKeyValueStore, WithError,
+        WritableKeyValueStore,
+    },
+};
+
+/// The prefixes being used in the system
+static ROOT_KEY_DOMAIN: [u8; 1] = [0];
+static STORED_ROOT_KEYS_PREFIX: u8 = 1;
+
+// The maximum size of values in redb is 3 GiB
+// For offset reasons we decrease by 400
+const MAX_VALUE_SIZE: usize = 3 * 1024 * 1024 * 1024 - 400;
+
+// Keys and values are stored in the same pages, so we use the same limit as RocksDB to
+// keep the pages of the B-tree reasonably sized.
+const MAX_KEY_SIZE: usize = 8 * 1024 * 1024 - 400;
+
+/// The table containing the keys and values of all the partitions of a namespace.
+const TABLE: TableDefinition<&[u8], &[u8]> = TableDefinition::new("linera");
+
+/// The name of the database file inside the directory of a namespace.
+const DATABASE_FILE: &str = "store.redb";
+
+/// The databases opened by this process, by path. A redb database can only be opened
+/// once at a time, so the connections to the same namespace share it.
+static OPEN_DATABASES: LazyLock<Mutex<HashMap<PathBuf, Weak<Database>>>> =
+    LazyLock::new(Default::default);
+
+/// Opens the database at `path`, creating it if needed, or returns the already open one.
+fn open_database(path: &Path) -> Result<Arc<Database>, RedbStoreInternalError> {
+    let mut databases = OPEN_DATABASES.lock().unwrap();
+    if let Some(db) = databases.get(path).and_then(Weak::upgrade) {
+        return Ok(db);
+    }
+    let db = Arc::new(Database::create(path)?);
+    // Creating the table upfront lets read transactions always open it.
+    let transaction = db.begin_write()?;
+    transaction.open_table(TABLE)?;
+    transaction.commit()?;
+    databases.insert(path.to_path_buf(), Arc::downgrade(&db));
+    Ok(db)
+}
+
+/// Runs a blocking database operation outside of the asynchronous runtime.
+async fn spawn_blocking<F, O>(f: F) -> Result<O, RedbStoreInternalError>
+where
+    F: FnOnce() -> Result<O, RedbStoreInternalError> + Send + 'static,
+    O: Send + 'static,
+{
+    tokio::task::spawn_blocking(f).await?
+}
+
+fn check_key_size(key: &[u8]) -> Result<(), RedbStoreInternalError> {
+    ensure!(
+        key.len() <= MAX_KEY_SIZE,
+        RedbStoreInternalError::KeyTooLong
+    );
+    Ok(())
+}
+
+#[derive(Clone)]
+struct RedbStoreExecutor {
+    db: Arc<Database>,
+    start_key: Vec<u8>,
+}
+
+impl RedbStoreExecutor {
+    fn full_key(&self, key: &[u8]) -> Result<Vec<u8>, RedbStoreInternalError> {
+        check_key_size(key)?;
+        Ok([self.start_key.as_slice(), key].concat())
+    }
+
+    fn read_multi_values_bytes_internal(
+        &self,
+        keys: Vec<Vec<u8>>,
+    ) -> Result<Vec<Option<Vec<u8>>>, RedbStoreInternalError> {
+        let transaction = self.db.begin_read()?;
+        let table = transaction.open_table(TABLE)?;
+        keys.into_iter()
+            .map(|key| {
+                let full_key = self.full_key(&key)?;
+                let value = table.get(full_key.as_slice())?;
+                Ok(value.map(|value| value.value().to_vec()))
+            })
+            .collect()
+    }
+
+    fn contains_keys_internal(
+        &self,
+        keys: Vec<Vec<u8>>,
+    ) -> Result<Vec<bool>, RedbStoreInternalError> {
+        let transaction = self.db.begin_read()?;
+        let table = transaction.open_table(TABLE)?;
+        keys.into_iter()
+            .map(|key| {
+                let full_key = self.full_key(&key)?;
+                Ok(table.get(full_key.as_slice())?.is_some())
+            })
+            .collect()
+    }
+
+    /// Returns the entries whose keys start with `key_prefix` and whose remainder belongs
+    /// to the `range`, in the direction of the range, as computed by `entry` from the
+    /// remainder of the key and the value.
+    fn find_entries_in_range_internal<T>(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+        mut entry: impl FnMut(&[u8], &[u8]) -> T,
+    ) -> Result<Vec<T>, RedbStoreInternalError> {
+        let prefix = self.full_key(key_prefix)?;
+        let mut entries = Vec::new();
+        let Some((lower_bound, upper_bound)) = range.bounds_with_prefix(&prefix) else {
+            return Ok(entries);
+        };
+        let upper_bound = match &upper_bound {
+            Some(upper_bound) => Bound::Excluded(upper_bound.as_slice()),
+            None => Bound::Unbounded,
+        };
+        let transaction = self.db.begin_read()?;
+        let table = transaction.open_table(TABLE)?;
+        let iter = table.range::<&[u8]>((Bound::Included(lower_bound.as_slice()), upper_bound))?;
+        let iter = if range.reverse {
+            Either::Left(iter.rev())
+        } else {
+            Either::Right(iter)
+        };
+        let len = prefix.len();
+        for item in iter.take(range.max_entries()) {
+            let (key, value) = item?;
+            entries.push(entry(&key.value()[len..], value.value()));
+        }
+        Ok(entries)
+    }
+
+    fn write_batch_internal(
+        &self,
+        batch: Batch,
+        write_root_key: bool,
+    ) -> Result<(), RedbStoreInternalError> {
+        let transaction = self.db.begin_write()?;
+        {
+            let mut table = transaction.open_table(TABLE)?;
+            for operation in batch.operations {
+                match operation {
+                    WriteOperation::Delete { key } => {
+                        let full_key = self.full_key(&key)?;
+                        table.remove(full_key.as_slice())?;
+                    }
+                    WriteOperation::Put { key, value } => {
+                        let full_key = self.full_key(&key)?;
+                        table.insert(full_key.as_slice(), value.as_slice())?;
+                    }
+                    WriteOperation::DeletePrefix { key_prefix } => {
+                        let full_key1 = self.full_key(&key_prefix)?;
+                        let full_key2 = get_upper_bound_option(&full_key1)
+                            .expect("the first entry cannot be 255");
+                        table.retain_in::<&[u8], _>(
+                            full_key1.as_slice()..full_key2.as_slice(),
+                            |_, _| false,
+                        )?;
+                    }
+                }
+            }
+            if write_root_key {
+                let mut full_key = self.start_key.to_vec();
+                full_key[0] = STORED_ROOT_KEYS_PREFIX;
+                table.insert(full_key.as_slice(), &[][..])?;
+            }
+        }
+        // If any of the operations failed, dropping the transaction aborts it.
+        transaction.commit()?;
+        Ok(())
+    }
+}
+
+/// The inner client
+#[derive(Clone)]
+pub struct RedbStoreInternal {
+    executor: RedbStoreExecutor,
+    root_key_written: Arc<AtomicBool>,
+}
+
+/// Database-level connection to redb for managing namespaces and partitions.
+#[derive(Clone)]
+pub struct RedbDatabaseInternal {
+    db: Arc<Database>,
+}
+
+impl WithError for RedbDatabaseInternal {
+    type Error = RedbStoreInternalError;
+}
+
+/// The initial configuration of the system
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RedbStoreInternalConfig {
+    /// The path to the storage containing the namespaces
+    pub path_with_guard: PathWithGuard,
+}
+
+impl RedbStoreInternalConfig {
+    fn namespace_path(&self, namespace: &str) -> Result<PathBuf, RedbStoreInternalError> {
+        RedbDatabaseInternal::check_namespace(namespace)?;
+        Ok(self.path_with_guard.path_buf.join(namespace))
+    }
+}
+
+impl RedbDatabaseInternal {
+    fn check_namespace(namespace: &str) -> Result<(), RedbStoreInternalError> {
+        if !namespace
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || character == '_')
+        {
+            return Err(RedbStoreInternalError::InvalidNamespace);
+        }
+        Ok(())
+    }
+}
+
+impl WithError for RedbStoreInternal {
+    type Error = RedbStoreInternalError;
+}
+
+impl ReadableKeyValueStore for RedbStoreInternal {
+    const MAX_KEY_SIZE: usize = MAX_KEY_SIZE;
+
+    fn root_key(&self) -> Result<Vec<u8>, RedbStoreInternalError> {
+        assert!(self.executor.start_key.starts_with(&ROOT_KEY_DOMAIN));
+        let root_key = bcs::from_bytes(&self.executor.start_key[ROOT_KEY_DOMAIN.len()..])?;
+        Ok(root_key)
+    }
+
+    async fn read_value_bytes(
+        &self,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>, RedbStoreInternalError> {
+        let mut values = self.read_multi_values_bytes(&[key.to_vec()]).await?;
+        Ok(values.pop().flatten())
+    }
+
+    async fn contains_key(&self, key: &[u8]) -> Result<bool, RedbStoreInternalError> {
+        let results = self.contains_keys(&[key.to_vec()]).await?;
+        Ok(results[0])
+    }
+
+    async fn contains_keys(&self, keys: &[Vec<u8>]) -> Result<Vec<bool>, RedbStoreInternalError> {
+        let executor = self.executor.clone();
+        let keys = keys.to_vec();
+        spawn_blocking(move || executor.contains_keys_internal(keys)).await
+    }
+
+    async fn read_multi_values_bytes(
+        &self,
+        keys: &[Vec<u8>],
+    ) -> Result<Vec<Option<Vec<u8>>>, RedbStoreInternalError> {
+        let executor = self.executor.clone();
+        let keys = keys.to_vec();
+        spawn_blocking(move || executor.read_multi_values_bytes_internal(keys)).await
+    }
+
+    async fn find_keys_by_prefix(
+        &self,
+        key_prefix: &[u8],
+    ) -> Result<Vec<Vec<u8>>, RedbStoreInternalError> {
+        self.find_keys_in_range(key_prefix, &KeyRange::default())
+            .await
+    }
+
+    async fn find_key_values_by_prefix(
+        &self,
+        key_prefix: &[u8],
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, RedbStoreInternalError> {
+        self.find_key_values_in_range(key_prefix, &KeyRange::default())
+            .await
+    }
+
+    async fn find_keys_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<Vec<u8>>, RedbStoreInternalError> {
+        let executor = self.executor.clone();
+        let key_prefix = key_prefix.to_vec();
+        let range = range.clone();
+        spawn_blocking(move || {
+            executor.find_entries_in_range_internal(&key_prefix, &range, |key, _| key.to_vec())
+        })
+        .await
+    }
+
+    async fn find_key_values_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, RedbStoreInternalError> {
+        let executor = self.executor.clone();
+        let key_prefix = key_prefix.to_vec();
+        let range = range.clone();
+        spawn_blocking(move || {
+            executor.find_entries_in_range_internal(&key_prefix, &range, |key, value| {
+                (key.to_vec(), value.to_vec())
+            })
+        })
+        .await
+    }
+}
+
+impl WritableKeyValueStore for RedbStoreInternal {
+    const MAX_VALUE_SIZE: usize = MAX_VALUE_SIZE;
+
+    async fn write_batch(&self, batch: Batch) -> Result<(), RedbStoreInternalError> {
+        let write_root_key = !self.root_key_written.fetch_or(true, Ordering::SeqCst);
+        let executor = self.executor.clone();
+        spawn_blocking(move || executor.write_batch_internal(batch, write_root_key)).await
+    }
+
+    async fn clear_journal(&self) -> Result<(), RedbStoreInternalError> {
+        Ok(())
+    }
+}
+
+impl KeyValueDatabase for RedbDatabaseInternal {
+    type Config = RedbStoreInternalConfig;
+    type Store = RedbStoreInternal;
+
+    fn get_name() -> String {
+        "redb internal".to_string()
+    }
+
+    async fn connect(
+        config: &Self::Config,
+        namespace: &str,
+    ) -> Result<Self, RedbStoreInternalError> {
+        let path = config.namespace_path(namespace)?;
+        std::fs::create_dir_all(&path)?;
+        let db = open_database(&path.join(DATABASE_FILE))?;
+        Ok(RedbDatabaseInternal { db })
+    }
+
+    fn open_shared(&self, root_key: &[u8]) -> Result<Self::Store, RedbStoreInternalError> {
+        let mut start_key = ROOT_KEY_DOMAIN.to_vec();
+        start_key.extend(bcs::to_bytes(root_key)?);
+        let executor = RedbStoreExecutor {
+            db: self.db.clone(),
+            start_key,
+        };
+        Ok(RedbStoreInternal {
+            executor,
+            root_key_written: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    fn open_exclusive(&self, root_key: &[u8]) -> Result<Self::Store, RedbStoreInternalError> {
+        self.open_shared(root_key)
+    }
+
+    async fn list_all(config: &Self::Config) -> Result<Vec<String>, RedbStoreInternalError> {
+        let entries = std::fs::read_dir(&config.path_with_guard.path_buf)?;
+        let mut namespaces = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() {
+                return Err(RedbStoreInternalError::NonDirectoryNamespace);
+            }
+            let namespace = entry
+                .file_name()
+                .into_string()
+                .map_err(RedbStoreInternalError::IntoStringError)?;
+            namespaces.push(namespace);
+        }
+        Ok(namespaces)
+    }
+
+    async fn list_root_keys(&self) -> Result<Vec<Vec<u8>>, RedbStoreInternalError> {
+        let mut store = self.open_shared(&[])?;
+        store.executor.start_key = vec![STORED_ROOT_KEYS_PREFIX];
+        let bcs_root_keys = store.find_keys_by_prefix(&[]).await?;
+        let mut root_keys = Vec::new();
+        for bcs_root_key in bcs_root_keys {
+            let root_key = bcs::from_bytes::<Vec<u8>>(&bcs_root_key)?;
+            root_keys.push(root_key);
+        }
+        Ok(root_keys)
+    }
+
+    async fn exists(
+        config: &Self::Config,
+        namespace: &str,
+    ) -> Result<bool, RedbStoreInternalError> {
+        let path = config.namespace_path(namespace)?;
+        Ok(path.exists())
+    }
+
+    async fn create(config: &Self::Config, namespace: &str) -> Result<(), RedbStoreInternalError> {
+        let path = config.namespace_path(namespace)?;
+        if path.exists() {
+            return Err(RedbStoreInternalError::StoreAlreadyExists);
+        }
+        std::fs::create_dir_all(path)?;
+        Ok(())
+    }
+
+    async fn delete(config: &Self::Config, namespace: &str) -> Result<(), RedbStoreInternalError> {
+        let path = config.namespace_path(namespace)?;
+        // Connections that are still open keep using the deleted file, but new ones must
+        // not reuse it.
+        OPEN_DATABASES
+            .lock()
+            .unwrap()
+            .remove(&path.join(DATABASE_FILE));
+        std::fs::remove_dir_all(path)?;
+        Ok(())
+    }
+}
+
+#[cfg(with_testing)]
+impl TestKeyValueDatabase for RedbDatabaseInternal {
+    async fn new_test_config() -> Result<RedbStoreInternalConfig, RedbStoreInternalError> {
+        let path_with_guard = PathWithGuard::new_testing();
+        Ok(RedbStoreInternalConfig { path_with_guard })
+    }
+}
+
+/// The error type for [`RedbStoreInternal`]
+#[derive(Error, Debug)]
+pub enum RedbStoreInternalError {
+    /// Store already exists
+    #[error("Store already exists")]
+    StoreAlreadyExists,
+
+    /// Tokio join error in redb.
+    #[error("tokio join error: {0}")]
+    TokioJoinError(#[from] tokio::task::JoinError),
+
+    /// Error when opening a redb database.
+    #[error("redb database error: {0}")]
+    Database(#[from] redb::DatabaseError),
+
+    /// Error when starting a redb transaction.
+    #[error("redb transaction error: {0}")]
+    Transaction(#[from] redb::TransactionError),
+
+    /// Error when opening the redb table.
+    #[error("redb table error: {0}")]
+    Table(#[from] redb::TableError),
+
+    /// Error when accessing the redb storage.
+    #[error("redb storage error: {0}")]
+    Storage(#[from] redb::StorageError),
+
+    /// Error when committing a redb transaction.
+    #[error("redb commit error: {0}")]
+    Commit(#[from] redb::CommitError),
+
+    /// The database contains a file which is not a directory
+    #[error("Namespaces should be directories")]
+    NonDirectoryNamespace,
+
+    /// Error converting `OsString` to `String`
+    #[error("error in the conversion from OsString: {0:?}")]
+    IntoStringError(OsString),
+
+    /// The key must have at most 8 MiB
+    #[error("The key must have at most 8 MiB")]
+    KeyTooLong,
+
+    /// Namespace contains forbidden characters
+    #[error("Namespace contains forbidden characters")]
+    InvalidNamespace,
+
+    /// Filesystem error
+    #[error("Filesystem error: {0}")]
+    FsError(#[from] std::io::Error),
+
+    /// BCS serialization error.
+    #[error(transparent)]
+    BcsError(#[from] bcs::Error),
+}
+
+impl KeyValueStoreError for RedbStoreInternalError {
+    const BACKEND: &'static str = "redb";
+}
+
+/// The composed error type for the `RedbStore`
+pub type RedbStoreError = RedbStoreInternalError;
+
+/// The composed config type for the `RedbStore`
+pub type RedbStoreConfig = LruCachingConfig<RedbStoreInternalConfig>;
+
+/// The `RedbDatabase` composed type with metrics
+#[cfg(with_metrics)]
+pub type RedbDatabase = MeteredDatabase<LruCachingDatabase<MeteredDatabase<RedbDatabaseInternal>>>;
+/// The `RedbDatabase` composed type
+#[cfg(not(with_metrics))]
+pub type RedbDatabase = LruCachingDatabase<RedbDatabaseInternal>;
@@ -14,7 +14,6 @@
 use std::{
     ffi::OsString,
     fmt::Display,
-    path::PathBuf,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
@@ -25,9 +24,9 @@ use linera_base::ensure;
 use rocksdb::{BlockBasedOptions, Cache, DBCompactionStyle, SliceTransform, WriteBufferManager};
 use serde::{Deserialize, Serialize};
 use sysinfo::{MemoryRefreshKind, RefreshKind, System};
-use tempfile::TempDir;
 use thiserror::Error;
 
+pub use crate::backends::PathWithGuard;
 #[cfg(with_metrics)]
 use crate::metering::MeteredDatabase;
 #[cfg(with_testing)]
@@ -37,7 +36,7 @@ use crate::{
     common::get_upper_bound_option,
     lru_caching::{LruCachingConfig, LruCachingDatabase},
     store::{
-        KeyValueDatabase, KeyValueStoreError, ReadableKeyValueStore, WithError,
+        KeyRange, KeyValueDatabase, KeyValueStoreError, ReadableKeyValueStore, WithError,
         WritableKeyValueStore,
     },
 };
@@ -242,6 +241,89 @@ impl RocksDbStoreExecutor {
         Ok(key_values)
     }
 
+    /// Returns an iterator positioned at the first key starting with `prefix` whose
+    /// remainder belongs to the `range`, in the direction of the range, or `None` if there
+    /// are no such keys.
+    fn get_find_range_iterator(
+        &self,
+        prefix: &[u8],
+        range: &KeyRange,
+    ) -> Option<rocksdb::DBRawIteratorWithThreadMode<'_, DB>> {
+        let (lower_bound, upper_bound) = range.bounds_with_prefix(prefix)?;
+        let mut read_opts = rocksdb::ReadOptions::default();
+        read_opts.set_async_io(true);
+        read_opts.set_iterate_lower_bound(lower_bound);
+        if let Some(upper_bound) = upper_bound {
+            read_opts.set_iterate_upper_bound(upper_bound);
+        }
+        let mut iter = self.db.raw_iterator_opt(read_opts);
+        if range.reverse {
+            iter.seek_to_last();
+        } else {
+            iter.seek_to_first();
+        }
+        Some(iter)
+    }
+
+    fn find_keys_in_range_internal(
+        &self,
+        key_prefix: Vec<u8>,
+        range: KeyRange,
+    ) -> Result<Vec<Vec<u8>>, RocksDbStoreInternalError> {
+        check_key_size(&key_prefix)?;
+        let mut prefix = self.start_key.clone();
+        prefix.extend(key_prefix);
+        let len = prefix.len();
+
+        let mut keys = Vec::new();
+        let Some(mut iter) = self.get_find_range_iterator(&prefix, &range) else {
+            return Ok(keys);
+        };
+        while let Some(key) = iter.key() {
+            if keys.len() == range.max_entries() {
+                break;
+            }
+            keys.push(key[len..].to_vec());
+            if range.reverse {
+                iter.prev();
+            } else {
+                iter.next();
+            }
+        }
+        iter.status()?;
+        Ok(keys)
+    }
+
+    #[expect(clippy::type_complexity)]
+    fn find_key_values_in_range_internal(
+        &self,
+        key_prefix: Vec<u8>,
+        range: KeyRange,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, RocksDbStoreInternalError> {
+        check_key_size(&key_prefix)?;
+        let mut prefix = self.start_key.clone();
+        prefix.extend(key_prefix);
+        let len = prefix.len();
+
+        let mut key_values = Vec::new();
+        let Some(mut iter) = self.get_find_range_iterator(&prefix, &range) else {
+            return Ok(key_values);
+        };
+        while let Some((key, value)) = iter.item() {
+            if key_values.len() == range.max_entries() {
+                break;
+            }
+            key_values.push((key[len..].to_vec(), value.to_vec()));
+            if range.reverse {
+                iter.prev();
+            } else {
+                iter.next();
+            }
+        }
+        iter.status()?;
+        Ok(key_values)
+    }
+
     fn write_batch_internal(
         &self,
         batch: Batch,
@@ -866,6 +948,38 @@ impl ReadableKeyValueStore for RocksDbStoreInternal {
             )
             .await
     }
+
+    async fn find_keys_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<Vec<u8>>, RocksDbStoreInternalError> {
+        let executor = self.executor.clone();
+        let input = (key_prefix.to_vec(), range.clone());
+        self.spawn_mode
+            .spawn(
+                move |(key_prefix, range)| executor.find_keys_in_range_internal(key_prefix, range),
+                input,
+            )
+            .await
+    }
+
+    async fn find_key_values_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, RocksDbStoreInternalError> {
+        let executor = self.executor.clone();
+        let input = (key_prefix.to_vec(), range.clone());
+        self.spawn_mode
+            .spawn(
+                move |(key_prefix, range)| {
+                    executor.find_key_values_in_range_internal(key_prefix, range)
+                },
+                input,
+            )
+            .await
+    }
 }
 
 impl WritableKeyValueStore for RocksDbStoreInternal {
@@ -1052,45 +1166,6 @@ pub enum RocksDbStoreInternalError {
     BcsError(#[from] bcs::Error),
 }
 
-/// A path and the guard for the temporary directory if needed
-#[derive(Clone, Debug, Deserialize, Serialize)]
-pub struct PathWithGuard {
-    /// The path to the data
-    pub path_buf: PathBuf,
-    /// The guard for the directory if one is needed
-    #[serde(skip)]
-    _dir_guard: Option<Arc<TempDir>>,
-}
-
-impl PathWithGuard {
-    /// Creates a `PathWithGuard` from an existing path.
-    pub fn new(path_buf: PathBuf) -> Self {
-        Self {
-            path_buf,
-            _dir_guard: None,
-        }
-    }
-
-    /// Returns the test path for RocksDB without common config.
-    #[cfg(with_testing)]
-    fn new_testing() -> PathWithGuard {
-        let dir = TempDir::new().unwrap();
-        let path_buf = dir.path().to_path_buf();
-        let dir_guard = Some(Arc::new(dir));
-        PathWithGuard {
-            path_buf,
-            _dir_guard: dir_guard,
-        }
-    }
-}
-
-impl PartialEq for PathWithGuard {
-    fn eq(&self, other: &Self) -> bool {
-        self.path_buf == other.path_buf
-    }
-}
-impl Eq for PathWithGuard {}
-
 impl KeyValueStoreError for RocksDbStoreInternalError {
     const BACKEND: &'static str = "rocks_db";
 }
@@ -53,8 +53,8 @@ use crate::{
     journaling::{JournalingError, JournalingKeyValueDatabase},
     lru_caching::{LruCachingConfig, LruCachingDatabase},
     store::{
-        DirectWritableKeyValueStore, KeyValueDatabase, KeyValueStoreError, ReadableKeyValueStore,
-        WithError,
+        DirectWritableKeyValueStore, KeyRange, KeyValueDatabase, KeyValueStoreError,
+        ReadableKeyValueStore, WithError,
     },
 };
 
@@ -135,6 +135,9 @@ struct ScyllaDbClient {
     find_key_values_by_prefix_bounded: PreparedStatement,
     multi_key_values: papaya::HashMap<usize, PreparedStatement>,
     multi_keys: papaya::HashMap<usize, PreparedStatement>,
+    /// The range queries, keyed by whether they read the values, have an upper bound and
+    /// are in decreasing order.
+    range_queries: papaya::HashMap<(bool, bool, bool), PreparedStatement>,
 }
 
 impl ScyllaDbClient {
@@ -256,6 +259,7 @@ impl ScyllaDbClient {
             find_key_values_by_prefix_bounded,
             multi_key_values: papaya::HashMap::new(),
             multi_keys: papaya::HashMap::new(),
+            range_queries: papaya::HashMap::new(),
         })
     }
 
@@ -334,6 +338,66 @@ impl ScyllaDbClient {
         Ok(prepared_statement)
     }
 
+    async fn get_range_query_statement(
+        &self,
+        with_values: bool,
+        is_bounded: bool,
+        reverse: bool,
+    ) -> Result<PreparedStatement, ScyllaDbStoreInternalError> {
+        let query_key = (with_values, is_bounded, reverse);
+        if let Some(prepared_statement) = self.range_queries.pin().get(&query_key) {
+            return Ok(prepared_statement.clone());
+        }
+        let columns = if with_values { "k,v" } else { "k" };
+        let upper_bound = if is_bounded { " AND k < ?" } else { "" };
+        let order = if reverse { " ORDER BY k DESC" } else { "" };
+        let prepared_statement = self
+            .session
+            .prepare(format!(
+                "SELECT {} FROM {}.\"{}\" WHERE root_key = ? AND k >= ?{}{} LIMIT ?",
+                columns, KEYSPACE, self.namespace, upper_bound, order
+            ))
+            .await?;
+        self.range_queries
+            .pin()
+            .insert(query_key, prepared_statement.clone());
+        Ok(prepared_statement)
+    }
+
+    /// Returns the statement and the values of a query of the keys starting with
+    /// `key_prefix` whose remainder belongs to the `range`, or `None` if there are no such
+    /// keys.
+    async fn get_range_query(
+        &self,
+        root_key: &[u8],
+        key_prefix: &[u8],
+        range: &KeyRange,
+        with_values: bool,
+    ) -> Result<Option<(PreparedStatement, Vec<CqlValue>)>, ScyllaDbStoreInternalError> {
+        Self::check_key_size(key_prefix)?;
+        let Some((mut lower_bound, upper_bound)) = range.bounds_with_prefix(key_prefix) else {
+            return Ok(None);
+        };
+        // The reserved timestamp sentinel is the only empty key, so starting from the
+        // smallest non-empty key skips it without counting it towards the limit.
+        if lower_bound.is_empty() {
+            lower_bound.push(0);
+        }
+        let statement = self
+            .get_range_query_statement(with_values, upper_bound.is_some(), range.reverse)
+            .await?;
+        let mut values = vec![
+            CqlValue::Blob(root_key.to_vec()),
+            CqlValue::Blob(lower_bound),
+        ];
+        values.extend(upper_bound.map(CqlValue::Blob));
+        let limit = range
+            .limit
+            .map_or(i32::MAX, |limit| i32::try_from(limit).unwrap_or(i32::MAX));
+        values.push(CqlValue::Int(limit));
+        Ok(Some((statement, values)))
+    }
+
     fn check_key_size(key: &[u8]) -> Result<(), ScyllaDbStoreInternalError> {
         ensure!(
             key.len() <= MAX_KEY_SIZE,
@@ -723,6 +787,52 @@ impl ScyllaDbClient {
         }
         Ok(key_values)
     }
+
+    async fn find_keys_in_range_internal(
+        &self,
+        root_key: &[u8],
+        key_prefix: Vec<u8>,
+        range: KeyRange,
+    ) -> Result<Vec<Vec<u8>>, ScyllaDbStoreInternalError> {
+        let Some((statement, values)) = self
+            .get_range_query(root_key, &key_prefix, &range, false)
+            .await?
+        else {
+            return Ok(Vec::new());
+        };
+        let len = key_prefix.len();
+        let rows = Box::pin(self.session.execute_iter(statement, values)).await?;
+        let mut rows = rows.rows_stream::<(Vec<u8>,)>()?;
+        let mut keys = Vec::new();
+        while let Some(row) = rows.next().await {
+            let (key,) = row?;
+            keys.push(key[len..].to_vec());
+        }
+        Ok(keys)
+    }
+
+    async fn find_key_values_in_range_internal(
+        &self,
+        root_key: &[u8],
+        key_prefix: Vec<u8>,
+        range: KeyRange,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ScyllaDbStoreInternalError> {
+        let Some((statement, values)) = self
+            .get_range_query(root_key, &key_prefix, &range, true)
+            .await?
+        else {
+            return Ok(Vec::new());
+        };
+        let len = key_prefix.len();
+        let rows = Box::pin(self.session.execute_iter(statement, values)).await?;
+        let mut rows = rows.rows_stream::<(Vec<u8>, Vec<u8>)>()?;
+        let mut key_values = Vec::new();
+        while let Some(row) = rows.next().await {
+            let (key, value) = row?;
+            key_values.push((key[len..].to_vec(), value));
+        }
+        Ok(key_values)
+    }
 }
 
 /// The client itself and the keeping of the count of active connections.
@@ -914,6 +1024,36 @@ impl ReadableKeyValueStore for ScyllaDbStoreInternal {
         Box::pin(store.find_key_values_by_prefix_internal(&self.root_key, key_prefix.to_vec()))
             .await
     }
+
+    async fn find_keys_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<Vec<u8>>, ScyllaDbStoreInternalError> {
+        let store = self.store.deref();
+        let _guard = self.acquire().await;
+        Box::pin(store.find_keys_in_range_internal(
+            &self.root_key,
+            key_prefix.to_vec(),
+            range.clone(),
+        ))
+        .await
+    }
+
+    async fn find_key_values_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ScyllaDbStoreInternalError> {
+        let store = self.store.deref();
+        let _guard = self.acquire().await;
+        Box::pin(store.find_key_values_in_range_internal(
+            &self.root_key,
+            key_prefix.to_vec(),
+            range.clone(),
+        ))
+        .await
+    }
 }
 
 impl DirectWritableKeyValueStore for ScyllaDbStoreInternal {
@@ -9,7 +9,7 @@ use thiserror::Error;
 use crate::{
     batch::{Batch, WriteOperation},
     store::{
-        KeyValueDatabase, KeyValueStoreError, ReadableKeyValueStore, WithError,
+        KeyBound, KeyRange, KeyValueDatabase, KeyValueStoreError, ReadableKeyValueStore, WithError,
         WritableKeyValueStore,
     },
 };
@@ -245,6 +245,69 @@ where
         }
         Ok(key_values)
     }
+
+    async fn find_keys_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<Vec<u8>>, Self::Error> {
+        // Appending the index of the first segment preserves the order of the keys, so the
+        // logical range is the range of the first segments. The other segments it contains
+        // are skipped, which may require several queries to reach the limit.
+        let mut big_range = KeyRange {
+            start: Self::get_segment_bound(&range.start)?,
+            end: Self::get_segment_bound(&range.end)?,
+            ..range.clone()
+        };
+        let mut keys = Vec::new();
+        loop {
+            let big_keys = self
+                .store
+                .find_keys_in_range(key_prefix, &big_range)
+                .await?;
+            let is_complete = range
+                .limit
+                .is_none_or(|limit| big_keys.len() < limit as usize);
+            let Some(last_big_key) = big_keys.last().cloned() else {
+                break;
+            };
+            for big_key in big_keys {
+                let len = big_key.len();
+                if Self::read_index_from_key(&big_key)? == 0 {
+                    keys.push(big_key[0..len - 4].to_vec());
+                    if keys.len() == range.max_entries() {
+                        return Ok(keys);
+                    }
+                }
+            }
+            if is_complete {
+                break;
+            }
+            big_range = big_range.after(&last_big_key);
+        }
+        Ok(keys)
+    }
+
+    async fn find_key_values_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error> {
+        let keys = self.find_keys_in_range(key_prefix, range).await?;
+        let full_keys = keys
+            .iter()
+            .map(|key| [key_prefix, key].concat())
+            .collect::<Vec<_>>();
+        let values = self.read_multi_values_bytes(&full_keys).await?;
+        keys.into_iter()
+            .zip(values)
+            .map(|(key, value)| Ok((key, value.ok_or(ValueSplittingError::MissingSegment)?)))
+            .collect()
+    }
+
+    async fn has_journal(&self) -> Result<bool, Self::Error> {
+        Ok(self.store.has_journal().await?)
+    }
 }
 
 impl<K> WritableKeyValueStore for ValueSplittingStore<K>
@@ -382,6 +445,14 @@ where
         Ok(big_key_segment)
     }
 
+    fn get_segment_bound(bound: &KeyBound) -> Result<KeyBound, ValueSplittingError<D::Error>> {
+        Ok(match bound {
+            KeyBound::Unbounded => KeyBound::Unbounded,
+            KeyBound::Included(key) => KeyBound::Included(Self::get_segment_key(key, 0)?),
+            KeyBound::Excluded(key) => KeyBound::Excluded(Self::get_segment_key(key, 0)?),
+        })
+    }
+
     fn get_initial_count_first_chunk(
         count: u32,
         first_chunk: &[u8],
@@ -473,6 +544,22 @@ impl ReadableKeyValueStore for LimitedTestMemoryStore {
     ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, MemoryStoreError> {
         self.inner.find_key_values_by_prefix(key_prefix).await
     }
+
+    async fn find_keys_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<Vec<u8>>, MemoryStoreError> {
+        self.inner.find_keys_in_range(key_prefix, range).await
+    }
+
+    async fn find_key_values_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, MemoryStoreError> {
+        self.inner.find_key_values_in_range(key_prefix, range).await
+    }
 }
 
 #[cfg(with_testing)]
@@ -4,10 +4,12 @@
 //! This provides some common code for the linera-views.
 
 use std::{
-    collections::BTreeSet,
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, VecDeque},
     ops::{
         Bound,
         Bound::{Excluded, Included, Unbounded},
+        RangeBounds,
     },
     slice::ChunksExact,
 };
@@ -16,7 +18,10 @@ use allocative::Allocative;
 use itertools::Either;
 use serde::de::DeserializeOwned;
 
-use crate::ViewError;
+use crate::{
+    store::{KeyBound, KeyRange, ReadableKeyValueStore},
+    ViewError,
+};
 
 type HasherOutputSize = <sha3::Sha3_256 as sha3::digest::OutputSizeUser>::OutputSize;
 #[doc(hidden)]
@@ -106,6 +111,147 @@ pub(crate) fn get_key_range_for_prefix(key_prefix: Vec<u8>) -> (Bound<Vec<u8>>,
     (Included(key_prefix), upper_bound)
 }
 
+/// Returns the entries of `map` whose keys start with `key_prefix` and whose remainder
+/// belongs to the `range`, in the direction of the range. The limit of the range is not
+/// applied.
+pub(crate) fn get_entries_in_range<'a, V>(
+    map: &'a BTreeMap<Vec<u8>, V>,
+    key_prefix: &[u8],
+    range: &KeyRange,
+) -> impl DoubleEndedIterator<Item = (&'a Vec<u8>, &'a V)> + 'a {
+    let entries = match range.bounds_with_prefix(key_prefix) {
+        None => None,
+        Some((lower, upper)) => {
+            let upper = match upper {
+                None => Unbounded,
+                Some(upper) => Excluded(upper),
+            };
+            Some(map.range((Included(lower), upper)))
+        }
+    };
+    let entries = entries.into_iter().flatten();
+    if range.reverse {
+        Either::Left(entries.rev())
+    } else {
+        Either::Right(entries)
+    }
+}
+
+/// Builds the [`KeyRange`] of the serialized indices of a view that are within `range`.
+pub(crate) fn get_key_range<Q: ?Sized>(
+    range: impl RangeBounds<Q>,
+    reverse: bool,
+    limit: Option<u32>,
+    serialize: impl Fn(&Q) -> Result<Vec<u8>, ViewError>,
+) -> Result<KeyRange, ViewError> {
+    let get_bound = |bound: Bound<&Q>| -> Result<KeyBound, ViewError> {
+        Ok(match bound {
+            Unbounded => KeyBound::Unbounded,
+            Included(index) => KeyBound::Included(serialize(index)?),
+            Excluded(index) => KeyBound::Excluded(serialize(index)?),
+        })
+    };
+    Ok(KeyRange {
+        start: get_bound(range.start_bound())?,
+        end: get_bound(range.end_bound())?,
+        reverse,
+        limit,
+    })
+}
+
+/// An entry found by [`read_entries_in_range`].
+pub(crate) enum RangeEntry<'a, T> {
+    /// The entry is set by a pending update.
+    Pending(&'a T),
+    /// The entry is in storage, with its value if the values were requested.
+    Stored(Option<Vec<u8>>),
+}
+
+/// Finds the entries of a view whose indices start with `key_prefix` and whose remainder
+/// belongs to the `range`, in the direction and up to the limit of the range.
+///
+/// The pending `updates` are indexed by the full indices and take precedence over the
+/// entries in storage, which are read page by page under `storage_prefix`. The stored
+/// entries whose index satisfies `is_deleted` are skipped. The returned keys don't include
+/// `key_prefix`.
+pub(crate) async fn read_entries_in_range<'a, S, T>(
+    store: &S,
+    storage_prefix: &[u8],
+    key_prefix: &[u8],
+    range: &KeyRange,
+    updates: &'a BTreeMap<Vec<u8>, Update<T>>,
+    is_deleted: impl Fn(&[u8]) -> bool,
+    with_values: bool,
+) -> Result<Vec<(Vec<u8>, RangeEntry<'a, T>)>, ViewError>
+where
+    S: ReadableKeyValueStore,
+{
+    let len = key_prefix.len();
+    let limit = range.max_entries();
+    let mut pending = get_entries_in_range(updates, key_prefix, range).peekable();
+    let mut stored = VecDeque::new();
+    let mut storage_range = (!is_deleted(key_prefix)).then(|| range.clone());
+    let mut entries = Vec::new();
+    while entries.len() < limit {
+        if stored.is_empty() {
+            if let Some(mut page_range) = storage_range.take() {
+                if range.limit.is_some() {
+                    page_range.limit = u32::try_from(limit - entries.len()).ok();
+                }
+                let page = if with_values {
+                    store
+                        .find_key_values_in_range(storage_prefix, &page_range)
+                        .await?
+                        .into_iter()
+                        .map(|(key, value)| (key, Some(value)))
+                        .collect::<Vec<_>>()
+                } else {
+                    store
+                        .find_keys_in_range(storage_prefix, &page_range)
+                        .await?
+                        .into_iter()
+                        .map(|key| (key, None))
+                        .collect()
+                };
+                if let (Some(page_limit), Some((last_key, _))) = (page_range.limit, page.last()) {
+                    if page.len() == page_limit as usize {
+                        storage_range = Some(page_range.after(last_key));
+                    }
+                }
+                stored.extend(page);
+            }
+        }
+        let ordering = match (pending.peek(), stored.front()) {
+            (None, None) => break,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some((index, _)), Some((key, _))) => {
+                let ordering = index[len..].cmp(key);
+                if range.reverse {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            }
+        };
+        if ordering != Ordering::Greater {
+            let (index, update) = pending.next().expect("a pending entry was found");
+            if ordering == Ordering::Equal {
+                stored.pop_front();
+            }
+            if let Update::Set(value) = update {
+                entries.push((index[len..].to_vec(), RangeEntry::Pending(value)));
+            }
+        } else {
+            let (key, value) = stored.pop_front().expect("a stored entry was found");
+            if !is_deleted(&[key_prefix, &key].concat()) {
+                entries.push((key, RangeEntry::Stored(value)));
+            }
+        }
+    }
+    Ok(entries)
+}
+
 /// Deserializes an optional vector of `u8`
 pub fn from_bytes_option<V: DeserializeOwned>(
     key_opt: &Option<Vec<u8>>,
@@ -303,6 +449,19 @@ impl CustomSerialize for u128 {
     }
 }
 
+impl CustomSerialize for u64 {
+    fn to_custom_bytes(&self) -> Result<Vec<u8>, ViewError> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+
+    fn from_custom_bytes(bytes: &[u8]) -> Result<Self, ViewError> {
+        let array: [u8; 8] = bytes
+            .try_into()
+            .map_err(|_| ViewError::PostLoadValuesError)?;
+        Ok(u64::from_be_bytes(array))
+    }
+}
+
 impl CustomSerialize for linera_base::data_types::BlockHeight {
     fn to_custom_bytes(&self) -> Result<Vec<u8>, ViewError> {
         Ok(self.0.to_be_bytes().to_vec())
@@ -66,6 +66,10 @@ pub enum ViewError {
     /// The canonical byte stream is malformed.
     #[error("malformed canonical content stream: {0}")]
     MalformedContent(&'static str),
+
+    /// The index is not one of the secondary indices of the view.
+    #[error("unknown secondary index: {0}")]
+    UnknownIndex(&'static str),
 }
 
 impl ViewError {
@@ -22,6 +22,9 @@ We provide support for the following databases:
 * `ScyllaDbDatabase` is a cloud-based Cassandra-compatible database.
 * `StorageServiceDatabase` is a gRPC-based storage that uses either memory or RocksDB. It is available in `linera-storage-service`.
 
+Any of them can be wrapped in an `EncryptionDatabase` to encrypt the stored data, and
+in a `CompressionDatabase` to compress the stored values.
+
 The corresponding trait in the code is the [`crate::store::KeyValueDatabase`](https://docs.rs/linera-views/latest/linera_views/store/trait.KeyValueDatabase.html).
 as well as [`crate::store::KeyValueStore`](https://docs.rs/linera-views/latest/linera_views/store/trait.KeyValueStore.html).
 
@@ -85,6 +88,9 @@ pub mod views;
 /// Backend implementing the [`crate::store::KeyValueStore`] trait.
 pub mod backends;
 
+/// Exporting, importing and copying the content of a namespace.
+pub mod archive;
+
 /// Support for metrics.
 #[cfg(with_metrics)]
 pub mod metrics;
@@ -101,10 +107,16 @@ pub mod random;
 #[cfg(with_testing)]
 pub mod test_utils;
 
+#[cfg(with_compression)]
+pub use backends::compression;
+#[cfg(with_encryption)]
+pub use backends::encryption;
 #[cfg(with_indexeddb)]
 pub use backends::indexed_db;
 #[cfg(with_metrics)]
 pub use backends::metering;
+#[cfg(with_redb)]
+pub use backends::redb;
 #[cfg(with_rocksdb)]
 pub use backends::rocks_db;
 #[cfg(with_scylladb)]
@@ -118,8 +130,8 @@ pub use generic_array;
 pub use sha3;
 pub use views::{
     bucket_queue_view, collection_view, hashable_wrapper, historical_hash_wrapper,
-    key_value_store_view, lazy_register_view, log_view, map_view, queue_view,
-    reentrant_collection_view, register_view, set_view,
+    indexed_map_view, key_value_store_view, lazy_register_view, log_view, map_view, proof,
+    queue_view, reentrant_collection_view, register_view, set_view, sorted_set_view,
 };
 
 /// Registers every metric this crate declares.
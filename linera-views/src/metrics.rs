@@ -53,6 +53,55 @@ linera_base::declare_metrics! {
             &["type"],
             exponential_bucket_latencies(1000.0),
         );
+
+    /// The metric tracking the time spent compressing values.
+    #[doc(hidden)]
+    pub static COMPRESSION_LATENCY: prometheus::HistogramVec =
+        prometheus_util::register_histogram_vec(
+            "compression_latency",
+            "Compression latency in microseconds",
+            &["algorithm"],
+            prometheus_util::exponential_bucket_interval(1.0, 100_000.0),
+        );
+
+    /// The metric tracking the time spent decompressing values.
+    #[doc(hidden)]
+    pub static DECOMPRESSION_LATENCY: prometheus::HistogramVec =
+        prometheus_util::register_histogram_vec(
+            "decompression_latency",
+            "Decompression latency in microseconds",
+            &["algorithm"],
+            prometheus_util::exponential_bucket_interval(1.0, 100_000.0),
+        );
+
+    /// The metric tracking the ratio between the stored and the original sizes of the
+    /// compressed values.
+    #[doc(hidden)]
+    pub static COMPRESSION_RATIO: prometheus::HistogramVec =
+        prometheus_util::register_histogram_vec(
+            "compression_ratio",
+            "Ratio between the stored and the original sizes of the compressed values",
+            &["algorithm"],
+            prometheus_util::linear_bucket_interval(0.1, 0.1, 1.0),
+        );
+
+    /// The metric counting the bytes of the values given to the compression.
+    #[doc(hidden)]
+    pub static UNCOMPRESSED_BYTES: IntCounterVec =
+        prometheus_util::register_int_counter_vec(
+            "compression_uncompressed_bytes",
+            "The bytes of the values given to the compression",
+            &["algorithm"],
+        );
+
+    /// The metric counting the bytes stored for the values given to the compression.
+    #[doc(hidden)]
+    pub static COMPRESSED_BYTES: IntCounterVec =
+        prometheus_util::register_int_counter_vec(
+            "compression_compressed_bytes",
+            "The bytes stored for the values given to the compression",
+            &["algorithm"],
+        );
 }
 
 #[cfg(test)]
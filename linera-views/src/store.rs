@@ -5,13 +5,15 @@
 
 use std::{fmt::Debug, future::Future};
 
-use serde::{de::DeserializeOwned, Serialize};
+use linera_witty::{WitLoad, WitStore, WitType};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 #[cfg(with_testing)]
 use crate::random::generate_test_namespace;
 use crate::{
     batch::{Batch, SimplifiedBatch},
-    common::from_bytes_option,
+    common::{from_bytes_option, get_upper_bound_option},
+    journaling::JOURNAL_TAG,
     ViewError,
 };
 
@@ -46,6 +48,125 @@ pub trait WithError {
     type Error: KeyValueStoreError;
 }
 
+/// A bound of a [`KeyRange`].
+#[derive(
+    Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, WitType, WitLoad, WitStore,
+)]
+pub enum KeyBound {
+    /// There is no bound.
+    #[default]
+    Unbounded,
+    /// The bound includes the key.
+    Included(Vec<u8>),
+    /// The bound excludes the key.
+    Excluded(Vec<u8>),
+}
+
+/// A range of keys to scan, together with the direction of the scan and the maximal number
+/// of entries to return.
+///
+/// The bounds apply to the keys without the prefix of the query. Large ranges can be read
+/// page by page by passing a `limit` and continuing with [`KeyRange::after`] the last key
+/// returned.
+#[derive(
+    Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, WitType, WitLoad, WitStore,
+)]
+pub struct KeyRange {
+    /// The lower bound of the keys.
+    pub start: KeyBound,
+    /// The upper bound of the keys.
+    pub end: KeyBound,
+    /// Whether the keys are returned in decreasing order.
+    pub reverse: bool,
+    /// The maximal number of entries to return.
+    pub limit: Option<u32>,
+}
+
+impl KeyRange {
+    /// Creates a range of the keys between `start` and `end`, in increasing order.
+    pub fn new(start: KeyBound, end: KeyBound) -> Self {
+        KeyRange {
+            start,
+            end,
+            reverse: false,
+            limit: None,
+        }
+    }
+
+    /// Returns the same range, scanned in decreasing order.
+    pub fn reversed(self) -> Self {
+        KeyRange {
+            reverse: true,
+            ..self
+        }
+    }
+
+    /// Returns the same range, returning at most `limit` entries.
+    pub fn with_limit(self, limit: u32) -> Self {
+        KeyRange {
+            limit: Some(limit),
+            ..self
+        }
+    }
+
+    /// Returns the range of the keys that come after `key` in the direction of the scan.
+    pub fn after(&self, key: &[u8]) -> Self {
+        let mut range = self.clone();
+        if self.reverse {
+            range.end = KeyBound::Excluded(key.to_vec());
+        } else {
+            range.start = KeyBound::Excluded(key.to_vec());
+        }
+        range
+    }
+
+    /// Tests whether `key` belongs to the range, ignoring the limit.
+    pub fn contains(&self, key: &[u8]) -> bool {
+        let above_start = match &self.start {
+            KeyBound::Unbounded => true,
+            KeyBound::Included(start) => key >= start.as_slice(),
+            KeyBound::Excluded(start) => key > start.as_slice(),
+        };
+        let below_end = match &self.end {
+            KeyBound::Unbounded => true,
+            KeyBound::Included(end) => key <= end.as_slice(),
+            KeyBound::Excluded(end) => key < end.as_slice(),
+        };
+        above_start && below_end
+    }
+
+    /// Returns the maximal number of entries to return.
+    pub(crate) fn max_entries(&self) -> usize {
+        self.limit.map_or(usize::MAX, |limit| limit as usize)
+    }
+
+    /// Returns the bounds `[lower, upper)` of the full keys starting with `key_prefix` whose
+    /// suffix belongs to the range, or `None` if there are no such keys.
+    pub(crate) fn bounds_with_prefix(
+        &self,
+        key_prefix: &[u8],
+    ) -> Option<(Vec<u8>, Option<Vec<u8>>)> {
+        if self.limit == Some(0) {
+            return None;
+        }
+        let with_prefix = |key: &[u8], extra: &[u8]| [key_prefix, key, extra].concat();
+        let lower = match &self.start {
+            KeyBound::Unbounded => key_prefix.to_vec(),
+            KeyBound::Included(start) => with_prefix(start, &[]),
+            KeyBound::Excluded(start) => with_prefix(start, &[0]),
+        };
+        let upper = match &self.end {
+            KeyBound::Unbounded => get_upper_bound_option(key_prefix),
+            KeyBound::Included(end) => Some(with_prefix(end, &[0])),
+            KeyBound::Excluded(end) => Some(with_prefix(end, &[])),
+        };
+        match &upper {
+            Some(upper) if lower >= *upper => None,
+            _ => Some((lower, upper)),
+        }
+    }
+}
+
 /// Asynchronous read key-value operations.
 #[cfg_attr(not(web), trait_variant::make(Send + Sync))]
 pub trait ReadableKeyValueStore: WithError {
@@ -79,6 +200,24 @@ pub trait ReadableKeyValueStore: WithError {
         key_prefix: &[u8],
     ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error>;
 
+    /// Finds the keys matching the prefix whose remainder belongs to the `range`, in the
+    /// direction and up to the limit of the range. The prefix is not included in the returned
+    /// keys.
+    async fn find_keys_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<Vec<u8>>, Self::Error>;
+
+    /// Finds the `(key,value)` pairs matching the prefix whose remainder belongs to the
+    /// `range`, in the direction and up to the limit of the range. The prefix is not included
+    /// in the returned keys.
+    async fn find_key_values_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error>;
+
     // We can't use `async fn` here in the below implementations due to
     // https://github.com/rust-lang/impl-trait-utils/issues/17, but once that bug is fixed
     // we can revert them to `async fn` syntax, which is neater.
@@ -104,6 +243,13 @@ pub trait ReadableKeyValueStore: WithError {
             Ok(values)
         }
     }
+
+    /// Tests whether a journal remains at the root key, to be resolved by
+    /// [`WritableKeyValueStore::clear_journal`]. Stores that change the keys of the store
+    /// they wrap must forward this to it.
+    fn has_journal(&self) -> impl Future<Output = Result<bool, Self::Error>> {
+        async { Ok(!self.find_keys_by_prefix(&[JOURNAL_TAG]).await?.is_empty()) }
+    }
 }
 
 /// Asynchronous write key-value operations.
@@ -334,6 +480,22 @@ pub mod inactive_store {
         ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error> {
             panic!("attempt to read from an inactive store!")
         }
+
+        async fn find_keys_in_range(
+            &self,
+            _key_prefix: &[u8],
+            _range: &KeyRange,
+        ) -> Result<Vec<Vec<u8>>, Self::Error> {
+            panic!("attempt to read from an inactive store!")
+        }
+
+        async fn find_key_values_in_range(
+            &self,
+            _key_prefix: &[u8],
+            _range: &KeyRange,
+        ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error> {
+            panic!("attempt to read from an inactive store!")
+        }
     }
 
     impl WritableKeyValueStore for InactiveStore {
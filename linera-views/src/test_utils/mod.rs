@@ -20,8 +20,8 @@ use crate::{
     },
     random::{generate_test_namespace, make_deterministic_rng, make_nondeterministic_rng},
     store::{
-        KeyValueDatabase, KeyValueStore, ReadableKeyValueStore, TestKeyValueDatabase,
-        WritableKeyValueStore,
+        KeyBound, KeyRange, KeyValueDatabase, KeyValueStore, ReadableKeyValueStore,
+        TestKeyValueDatabase, WritableKeyValueStore,
     },
 };
 
@@ -162,9 +162,10 @@ pub fn span_random_reordering_put_delete<R: Rng>(
 /// which is then followed by a number of reading tests. The functionalities being
 /// tested are all the reading functionalities:
 /// * `read_value_bytes`
-/// * `read_multi_values_bytes`
+/// * `read_multi_values_bytes` and `contains_keys`, including with no keys
 /// * `find_keys_by_prefix` / `find_key_values_by_prefix`
 /// * The ordering of keys returned by `find_keys_by_prefix` and `find_key_values_by_prefix`
+/// * `find_keys_in_range` / `find_key_values_in_range`
 pub async fn run_reads<S: KeyValueStore>(store: S, key_values: Vec<(Vec<u8>, Vec<u8>)>) {
     // We need a nontrivial key_prefix because some stores require a non-trivial prefix
     let mut batch = Batch::new();
@@ -176,6 +177,10 @@ pub async fn run_reads<S: KeyValueStore>(store: S, key_values: Vec<(Vec<u8>, Vec
         batch.put_key_value_bytes(key.clone(), value.clone());
     }
     store.write_batch(batch).await.unwrap();
+    // Reading no keys at all returns nothing.
+    assert!(store.contains_keys(&[]).await.unwrap().is_empty());
+    assert!(store.read_multi_values_bytes(&[]).await.unwrap().is_empty());
+    let mut range_rng = make_deterministic_rng();
     for key_prefix in keys
         .iter()
         .flat_map(|key| (0..=key.len()).map(|u| &key[..u]))
@@ -204,6 +209,30 @@ pub async fn run_reads<S: KeyValueStore>(store: S, key_values: Vec<(Vec<u8>, Vec
             }
         }
         assert_eq!(set_key_value1, set_key_value2);
+        // Check some ranges of the keys having the prefix
+        let key_values_by_prefix = set_key_value2.into_iter().collect::<BTreeMap<_, _>>();
+        for _ in 0..3 {
+            let range = get_random_key_range(&mut range_rng, &keys_request);
+            let mut expected = key_values_by_prefix
+                .iter()
+                .filter(|(key, _)| range.contains(key))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect::<Vec<_>>();
+            if range.reverse {
+                expected.reverse();
+            }
+            if let Some(limit) = range.limit {
+                expected.truncate(limit as usize);
+            }
+            let key_values_in_range = store
+                .find_key_values_in_range(key_prefix, &range)
+                .await
+                .unwrap();
+            assert_eq!(key_values_in_range, expected);
+            let keys_in_range = store.find_keys_in_range(key_prefix, &range).await.unwrap();
+            let expected_keys = expected.into_iter().map(|(key, _)| key).collect::<Vec<_>>();
+            assert_eq!(keys_in_range, expected_keys);
+        }
     }
     // Now checking the read_multi_values_bytes
     let mut rng = make_deterministic_rng();
@@ -245,6 +274,35 @@ pub async fn run_reads<S: KeyValueStore>(store: S, key_values: Vec<(Vec<u8>, Vec
     }
 }
 
+/// Returns a random [`KeyRange`] whose bounds are either among the given `keys` or close to
+/// them.
+pub fn get_random_key_range<R: Rng>(rng: &mut R, keys: &[Vec<u8>]) -> KeyRange {
+    let get_bound = |rng: &mut R| {
+        let mut key = match keys.choose(rng) {
+            Some(key) => key.clone(),
+            None => return KeyBound::Unbounded,
+        };
+        if rng.gen_bool(0.2) {
+            key.push(rng.gen());
+        }
+        match rng.gen_range(0..3) {
+            0 => KeyBound::Unbounded,
+            1 => KeyBound::Included(key),
+            _ => KeyBound::Excluded(key),
+        }
+    };
+    let start = get_bound(rng);
+    let end = get_bound(rng);
+    let mut range = KeyRange::new(start, end);
+    if rng.gen() {
+        range = range.reversed();
+    }
+    if rng.gen() {
+        range = range.with_limit(rng.gen_range(0..5));
+    }
+    range
+}
+
 /// Generates a list of random key-values with no duplicates
 pub fn get_random_key_values_with_sizes(
     num_entries: usize,
@@ -7,7 +7,7 @@ use std::{
     io::Write,
     marker::PhantomData,
     mem,
-    ops::Deref,
+    ops::{Deref, RangeBounds},
 };
 
 use allocative::{Allocative, Key, Visitor};
@@ -19,11 +19,14 @@ use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{
     batch::Batch,
-    common::{CustomSerialize, HasherOutput, SliceExt as _, Update},
+    common::{
+        get_key_range, read_entries_in_range, CustomSerialize, HasherOutput, SliceExt as _, Update,
+    },
     context::{BaseKey, Context},
     hashable_wrapper::WrappedHashableContainerView,
     historical_hash_wrapper::HistoricallyHashableView,
-    store::ReadableKeyValueStore as _,
+    proof::{ProofStep, ViewProof},
+    store::{KeyRange, ReadableKeyValueStore as _},
     views::{ClonableView, HashableView, Hasher, View, ViewError, MIN_VIEW_TAG},
 };
 
@@ -797,6 +800,41 @@ impl<W: View> ByteCollectionView<W::Context, W> {
         Ok(keys)
     }
 
+    /// Returns the keys in the collection that belong to the `range`, in the direction and
+    /// up to the limit of the range.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::collection_view::ByteCollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::store::{KeyBound, KeyRange};
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view: ByteCollectionView<_, RegisterView<_, String>> =
+    ///     ByteCollectionView::load(context).await.unwrap();
+    /// view.load_entry_mut(&[0, 1]).await.unwrap();
+    /// view.load_entry_mut(&[0, 2]).await.unwrap();
+    /// view.load_entry_mut(&[1, 0]).await.unwrap();
+    /// let range = KeyRange::new(KeyBound::Unbounded, KeyBound::Excluded(vec![1])).reversed();
+    /// let keys = view.keys_in_range(&range).await.unwrap();
+    /// assert_eq!(keys, vec![vec![0, 2], vec![0, 1]]);
+    /// # })
+    /// ```
+    pub async fn keys_in_range(&self, range: &KeyRange) -> Result<Vec<Vec<u8>>, ViewError> {
+        let updates = self.updates.read().await;
+        let entries = read_entries_in_range(
+            self.context.store(),
+            &self.get_index_key(&[]),
+            &[],
+            range,
+            &updates,
+            |_| self.delete_storage_first,
+            false,
+        )
+        .await?;
+        Ok(entries.into_iter().map(|(key, _)| key).collect())
+    }
+
     /// Returns the number of entries in the collection.
     /// ```rust
     /// # tokio_test::block_on(async {
@@ -823,6 +861,76 @@ impl<W: View> ByteCollectionView<W::Context, W> {
     }
 }
 
+impl<W> ByteCollectionView<W::Context, W>
+where
+    W: HashableView,
+    W::Hasher: Hasher<Output = HasherOutput>,
+{
+    /// Extends a proof for the content of the entry with the given short key into a proof
+    /// for the collection.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::collection_view::ByteCollectionView;
+    /// # use linera_views::proof::ProofPosition;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view: ByteCollectionView<_, RegisterView<_, String>> =
+    ///     ByteCollectionView::load(context).await.unwrap();
+    /// let subview = view.load_entry_mut(&[0, 1]).await.unwrap();
+    /// subview.set(String::from("Hello"));
+    /// let subview = view.try_load_entry(&[0, 1]).await.unwrap().unwrap();
+    /// let proof = view.extend_proof(&[0, 1], subview.proof().unwrap()).await.unwrap();
+    /// let hash = view.hash().await.unwrap();
+    /// let value = proof
+    ///     .verify_value::<String>(&hash, &[ProofPosition::Entry(vec![0, 1])])
+    ///     .unwrap();
+    /// assert_eq!(value, "Hello");
+    /// # })
+    /// ```
+    pub async fn extend_proof(
+        &self,
+        short_key: &[u8],
+        mut proof: ViewProof,
+    ) -> Result<ViewProof, ViewError> {
+        let updates = self.updates.read().await; // Acquire the lock to prevent writes.
+        let keys = self.keys().await?;
+        if !keys.iter().any(|key| key == short_key) {
+            return Err(ViewError::NotFound(format!("entry {short_key:?}")));
+        }
+        let mut other_entries = Vec::with_capacity(keys.len() - 1);
+        for key in keys {
+            if key == short_key {
+                continue;
+            }
+            let hash = match updates.get(&key) {
+                Some(entry) => {
+                    let Update::Set(view) = entry else {
+                        unreachable!("Loaded entries in updates should always be Update::Set");
+                    };
+                    view.hash().await?
+                }
+                None => {
+                    let key = self
+                        .context
+                        .base_key()
+                        .base_tag_index(KeyTag::Subview as u8, &key);
+                    let context = self.context.clone_with_base_key(key);
+                    let view = W::load(context).await?;
+                    view.hash().await?
+                }
+            };
+            other_entries.push((key, hash));
+        }
+        proof.path.push(ProofStep::Entry {
+            key: short_key.to_vec(),
+            other_entries,
+        });
+        Ok(proof)
+    }
+}
+
 impl<W: HashableView> HashableView for ByteCollectionView<W::Context, W> {
     type Hasher = sha3::Sha3_256;
 
@@ -1212,6 +1320,48 @@ where
         Ok(indices)
     }
 
+    /// Returns the indices within `range`, in the order determined by the serialization, or
+    /// in the reverse order. At most `limit` indices are returned, and the next ones can be
+    /// obtained by starting after the last index returned.
+    ///
+    /// Indices are compared by their BCS serialization, which for instance doesn't sort
+    /// integers other than `u8` numerically. A [`CustomCollectionView`] can be used where the
+    /// natural order is needed.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::collection_view::CollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view: CollectionView<_, u8, RegisterView<_, String>> =
+    ///     CollectionView::load(context).await.unwrap();
+    /// view.load_entry_mut(&23).await.unwrap();
+    /// view.load_entry_mut(&25).await.unwrap();
+    /// view.load_entry_mut(&27).await.unwrap();
+    /// let indices = view.indices_in_range(24.., false, Some(1)).await.unwrap();
+    /// assert_eq!(indices, vec![25]);
+    /// # })
+    /// ```
+    pub async fn indices_in_range<Q>(
+        &self,
+        range: impl RangeBounds<Q>,
+        reverse: bool,
+        limit: Option<u32>,
+    ) -> Result<Vec<I>, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        let range = get_key_range(range, reverse, limit, |index| {
+            Ok(BaseKey::derive_short_key(index)?)
+        })?;
+        let keys = self.collection.keys_in_range(&range).await?;
+        keys.iter()
+            .map(|key| Ok(BaseKey::deserialize_value(key)?))
+            .collect()
+    }
+
     /// Returns the number of entries in the collection.
     /// ```rust
     /// # tokio_test::block_on(async {
@@ -1307,6 +1457,45 @@ impl<I: DeserializeOwned, W: View> CollectionView<W::Context, I, W> {
     }
 }
 
+impl<I, W> CollectionView<W::Context, I, W>
+where
+    I: Serialize,
+    W: HashableView,
+    W::Hasher: Hasher<Output = HasherOutput>,
+{
+    /// Extends a proof for the content of the entry with the given index into a proof for
+    /// the collection. The position of the entry is given by
+    /// [`ProofPosition::entry`](crate::proof::ProofPosition::entry).
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::collection_view::CollectionView;
+    /// # use linera_views::proof::ProofPosition;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view: CollectionView<_, u64, RegisterView<_, String>> =
+    ///     CollectionView::load(context).await.unwrap();
+    /// let subview = view.load_entry_mut(&23).await.unwrap();
+    /// subview.set(String::from("Hello"));
+    /// let subview = view.try_load_entry(&23).await.unwrap().unwrap();
+    /// let proof = view.extend_proof(&23, subview.proof().unwrap()).await.unwrap();
+    /// let hash = view.hash().await.unwrap();
+    /// let position = ProofPosition::entry(&23u64).unwrap();
+    /// let value = proof.verify_value::<String>(&hash, &[position]).unwrap();
+    /// assert_eq!(value, "Hello");
+    /// # })
+    /// ```
+    pub async fn extend_proof<Q>(&self, index: &Q, proof: ViewProof) -> Result<ViewProof, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        let short_key = BaseKey::derive_short_key(index)?;
+        self.collection.extend_proof(&short_key, proof).await
+    }
+}
+
 impl<I, W: HashableView> HashableView for CollectionView<W::Context, I, W>
 where
     I: Send + Sync + Serialize + DeserializeOwned,
@@ -1631,6 +1820,40 @@ impl<I: CustomSerialize + Send, W: View> CustomCollectionView<W::Context, I, W>
         Ok(indices)
     }
 
+    /// Returns the indices within `range`, in the order determined by the custom
+    /// serialization, or in the reverse order. At most `limit` indices are returned, and the
+    /// next ones can be obtained by starting after the last index returned.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use std::ops::Bound;
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::collection_view::CustomCollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view: CustomCollectionView<_, u128, RegisterView<_, String>> =
+    ///     CustomCollectionView::load(context).await.unwrap();
+    /// view.load_entry_mut(&23).await.unwrap();
+    /// view.load_entry_mut(&25).await.unwrap();
+    /// view.load_entry_mut(&300).await.unwrap();
+    /// let indices = view.indices_in_range(.., true, Some(2)).await.unwrap();
+    /// assert_eq!(indices, vec![300, 25]);
+    /// let range = (Bound::Unbounded, Bound::Excluded(25));
+    /// let indices = view.indices_in_range(range, true, Some(2)).await.unwrap();
+    /// assert_eq!(indices, vec![23]);
+    /// # })
+    /// ```
+    pub async fn indices_in_range(
+        &self,
+        range: impl RangeBounds<I>,
+        reverse: bool,
+        limit: Option<u32>,
+    ) -> Result<Vec<I>, ViewError> {
+        let range = get_key_range(range, reverse, limit, I::to_custom_bytes)?;
+        let keys = self.collection.keys_in_range(&range).await?;
+        keys.iter().map(|key| I::from_custom_bytes(key)).collect()
+    }
+
     /// Returns the number of entries in the collection.
     /// ```rust
     /// # tokio_test::block_on(async {
@@ -1729,6 +1952,24 @@ impl<I: CustomSerialize, W: View> CustomCollectionView<W::Context, I, W> {
     }
 }
 
+impl<I, W> CustomCollectionView<W::Context, I, W>
+where
+    I: CustomSerialize,
+    W: HashableView,
+    W::Hasher: Hasher<Output = HasherOutput>,
+{
+    /// Extends a proof for the content of the entry with the given index into a proof for
+    /// the collection. The position of the entry is the custom serialization of the index.
+    pub async fn extend_proof<Q>(&self, index: &Q, proof: ViewProof) -> Result<ViewProof, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: CustomSerialize,
+    {
+        let short_key = index.to_custom_bytes()?;
+        self.collection.extend_proof(&short_key, proof).await
+    }
+}
+
 impl<I, W: HashableView> HashableView for CustomCollectionView<W::Context, I, W>
 where
     Self: View,
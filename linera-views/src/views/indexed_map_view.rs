@@ -0,0 +1,642 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A map view that maintains secondary indices over its entries.
+//!
+//! The indices of an [`IndexedMapView`] are declared as a tuple of types implementing
+//! [`MapIndex`], each of which extracts the index keys of an entry. The view keeps an
+//! entry `(index key, map index)` for every index key of every entry, and updates these
+//! entries together with the map in [`IndexedMapView::insert`] and
+//! [`IndexedMapView::remove`], so that they are saved in the same batch.
+//!
+//! ```rust
+//! # tokio_test::block_on(async {
+//! # use linera_views::context::MemoryContext;
+//! # use linera_views::indexed_map_view::{IndexedMapView, MapIndex};
+//! # use linera_views::views::View;
+//! # let context = MemoryContext::new_for_testing(());
+//! struct ByOwner;
+//!
+//! impl MapIndex<u64, (String, u64)> for ByOwner {
+//!     type Key = String;
+//!
+//!     fn keys(_index: &u64, value: &(String, u64)) -> Vec<String> {
+//!         vec![value.0.clone()]
+//!     }
+//! }
+//!
+//! let mut map: IndexedMapView<_, u64, (String, u64), (ByOwner,)> =
+//!     IndexedMapView::load(context).await.unwrap();
+//! map.insert(&1, ("alice".to_string(), 10)).await.unwrap();
+//! map.insert(&2, ("bob".to_string(), 20)).await.unwrap();
+//! map.insert(&3, ("alice".to_string(), 30)).await.unwrap();
+//! let keys = map
+//!     .indices_by::<ByOwner>(&"alice".to_string(), None, None)
+//!     .await
+//!     .unwrap();
+//! assert_eq!(keys, vec![1, 3]);
+//! # })
+//! ```
+
+use std::{any::TypeId, marker::PhantomData};
+
+use allocative::Allocative;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    batch::Batch,
+    common::{get_upper_bound_option, HasherOutput},
+    context::{BaseKey, Context},
+    hashable_wrapper::WrappedHashableContainerView,
+    historical_hash_wrapper::HistoricallyHashableView,
+    map_view::{ByteMapView, MapView},
+    store::{KeyBound, KeyRange},
+    views::{ClonableView, HashableView, Hasher, ReplaceContext, View, ViewError, MIN_VIEW_TAG},
+};
+
+/// A secondary index of an [`IndexedMapView`] with indices of type `I` and values of type
+/// `V`.
+pub trait MapIndex<I, V>: 'static {
+    /// The type of the index keys.
+    type Key: Serialize;
+
+    /// Returns the keys under which the entry `(index, value)` can be found. An entry may
+    /// have no key, or several ones.
+    fn keys(index: &I, value: &V) -> Vec<Self::Key>;
+}
+
+/// The list of the secondary indices of an [`IndexedMapView`]. This is implemented for
+/// tuples of up to four types implementing [`MapIndex`].
+pub trait MapIndices<I, V>: 'static {
+    /// Returns the position of the index `X` in the list, if it is part of it.
+    fn position<X: 'static>() -> Option<u8>;
+
+    /// Returns the keys of the entry `(index, value)` in all the indices. Each serialized
+    /// key is prefixed by the position of its index.
+    fn serialized_keys(index: &I, value: &V) -> Result<Vec<Vec<u8>>, ViewError>;
+}
+
+macro_rules! impl_map_indices {
+    ($($index:ident: $position:literal),*) => {
+        impl<I, V, $($index: MapIndex<I, V>),*> MapIndices<I, V> for ($($index,)*) {
+            fn position<X: 'static>() -> Option<u8> {
+                $(
+                    if TypeId::of::<X>() == TypeId::of::<$index>() {
+                        return Some($position);
+                    }
+                )*
+                None
+            }
+
+            fn serialized_keys(index: &I, value: &V) -> Result<Vec<Vec<u8>>, ViewError> {
+                let mut keys = Vec::new();
+                $(
+                    for key in $index::keys(index, value) {
+                        let mut bytes = vec![$position];
+                        bcs::serialize_into(&mut bytes, &key)?;
+                        keys.push(bytes);
+                    }
+                )*
+                Ok(keys)
+            }
+        }
+    };
+}
+
+impl_map_indices!(X0: 0);
+impl_map_indices!(X0: 0, X1: 1);
+impl_map_indices!(X0: 0, X1: 1, X2: 2);
+impl_map_indices!(X0: 0, X1: 1, X2: 2, X3: 3);
+
+/// Key tags to create the sub-keys of an `IndexedMapView` on top of the base key.
+#[repr(u8)]
+enum KeyTag {
+    /// Prefix for the entries of the map.
+    Map = MIN_VIEW_TAG,
+    /// Prefix for the entries of the secondary indices.
+    Index,
+}
+
+/// A `MapView` together with secondary indices `X` over its entries, declared as a tuple
+/// of [`MapIndex`] types.
+///
+/// The entries of the secondary indices are derived from the entries of the map, so they
+/// are not part of the hash of the view.
+#[derive(Debug, Allocative)]
+#[allocative(bound = "C, I, V: Allocative, X")]
+pub struct IndexedMapView<C, I, V, X> {
+    /// The map of the entries.
+    map: MapView<C, I, V>,
+    /// The secondary indices, with keys made of the position of the index, the index key
+    /// and the serialized map index.
+    index: ByteMapView<C, ()>,
+    /// Phantom data for the list of indices.
+    #[allocative(skip)]
+    _phantom: PhantomData<fn() -> X>,
+}
+
+impl<C, C2, I, V, X> ReplaceContext<C2> for IndexedMapView<C, I, V, X>
+where
+    C: Context,
+    C2: Context,
+    I: Send + Sync,
+    V: Send + Sync + Serialize + Clone,
+{
+    type Target = IndexedMapView<C2, I, V, X>;
+
+    async fn with_context(
+        &mut self,
+        ctx: impl FnOnce(&Self::Context) -> C2 + Clone,
+    ) -> Self::Target {
+        IndexedMapView {
+            map: self.map.with_context(ctx.clone()).await,
+            index: self.index.with_context(ctx).await,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<C, I, V, X> View for IndexedMapView<C, I, V, X>
+where
+    C: Context,
+    I: Send + Sync,
+    V: Send + Sync + Serialize,
+{
+    const NUM_INIT_KEYS: usize =
+        MapView::<C, I, V>::NUM_INIT_KEYS + ByteMapView::<C, ()>::NUM_INIT_KEYS;
+
+    type Context = C;
+
+    fn context(&self) -> C {
+        // The map context has our base key + the KeyTag::Map byte
+        self.map.context().clone_with_trimmed_key(1)
+    }
+
+    fn pre_load(context: &C) -> Result<Vec<Vec<u8>>, ViewError> {
+        let map_context =
+            context.clone_with_base_key(context.base_key().base_tag(KeyTag::Map as u8));
+        let index_context =
+            context.clone_with_base_key(context.base_key().base_tag(KeyTag::Index as u8));
+        let mut keys = MapView::<C, I, V>::pre_load(&map_context)?;
+        keys.extend(ByteMapView::<C, ()>::pre_load(&index_context)?);
+        Ok(keys)
+    }
+
+    fn post_load(context: C, values: &[Option<Vec<u8>>]) -> Result<Self, ViewError> {
+        let map_context =
+            context.clone_with_base_key(context.base_key().base_tag(KeyTag::Map as u8));
+        let index_context =
+            context.clone_with_base_key(context.base_key().base_tag(KeyTag::Index as u8));
+        let split = MapView::<C, I, V>::NUM_INIT_KEYS;
+        let map = MapView::post_load(
+            map_context,
+            values.get(..split).ok_or(ViewError::PostLoadValuesError)?,
+        )?;
+        let index = ByteMapView::post_load(
+            index_context,
+            values.get(split..).ok_or(ViewError::PostLoadValuesError)?,
+        )?;
+        Ok(IndexedMapView {
+            map,
+            index,
+            _phantom: PhantomData,
+        })
+    }
+
+    fn rollback(&mut self) {
+        self.map.rollback();
+        self.index.rollback();
+    }
+
+    async fn has_pending_changes(&self) -> bool {
+        self.map.has_pending_changes().await || self.index.has_pending_changes().await
+    }
+
+    fn pre_save(&self, batch: &mut Batch) -> Result<bool, ViewError> {
+        let delete_map = self.map.pre_save(batch)?;
+        let delete_index = self.index.pre_save(batch)?;
+        Ok(delete_map && delete_index)
+    }
+
+    fn post_save(&mut self) {
+        self.map.post_save();
+        self.index.post_save();
+    }
+
+    fn clear(&mut self) {
+        self.map.clear();
+        self.index.clear();
+    }
+}
+
+impl<C, I, V, X> ClonableView for IndexedMapView<C, I, V, X>
+where
+    Self: View,
+    MapView<C, I, V>: ClonableView,
+    ByteMapView<C, ()>: ClonableView,
+{
+    fn clone_unchecked(&mut self) -> Result<Self, ViewError> {
+        Ok(IndexedMapView {
+            map: self.map.clone_unchecked()?,
+            index: self.index.clone_unchecked()?,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<C, I, V, X> IndexedMapView<C, I, V, X>
+where
+    C: Context,
+    I: Serialize,
+    V: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+    X: MapIndices<I, V>,
+{
+    /// Inserts or resets a value at an index, and updates the secondary indices
+    /// accordingly.
+    pub async fn insert(&mut self, index: &I, value: V) -> Result<(), ViewError> {
+        let short_key = BaseKey::derive_short_key(index)?;
+        if let Some(old_value) = self.map.get(index).await? {
+            for key in X::serialized_keys(index, &old_value)? {
+                self.index.remove([key, short_key.clone()].concat());
+            }
+        }
+        for key in X::serialized_keys(index, &value)? {
+            self.index.insert([key, short_key.clone()].concat(), ());
+        }
+        self.map.insert(index, value)
+    }
+
+    /// Removes the value at an index, together with its entries in the secondary indices.
+    /// If absent then the operation does nothing.
+    pub async fn remove(&mut self, index: &I) -> Result<(), ViewError> {
+        let Some(old_value) = self.map.get(index).await? else {
+            return Ok(());
+        };
+        let short_key = BaseKey::derive_short_key(index)?;
+        for key in X::serialized_keys(index, &old_value)? {
+            self.index.remove([key, short_key.clone()].concat());
+        }
+        self.map.remove(index)
+    }
+
+    /// Reads the value at the given index, if any.
+    pub async fn get(&self, index: &I) -> Result<Option<V>, ViewError> {
+        self.map.get(index).await
+    }
+
+    /// Reads the values at the given indices, if any.
+    pub async fn multi_get(&self, indices: &[I]) -> Result<Vec<Option<V>>, ViewError> {
+        self.map.multi_get(indices).await
+    }
+
+    /// Returns `true` if the map contains a value for the specified index.
+    pub async fn contains_key(&self, index: &I) -> Result<bool, ViewError> {
+        self.map.contains_key(index).await
+    }
+
+    /// Obtains the extra data.
+    pub fn extra(&self) -> &C::Extra {
+        self.map.extra()
+    }
+}
+
+impl<C, I, V, X> IndexedMapView<C, I, V, X>
+where
+    C: Context,
+    I: Send + Serialize + DeserializeOwned,
+    V: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+    X: MapIndices<I, V>,
+{
+    /// Returns the list of indices in the map. The order is determined by serialization.
+    pub async fn indices(&self) -> Result<Vec<I>, ViewError> {
+        self.map.indices().await
+    }
+
+    /// Obtains all the `(index, value)` pairs.
+    pub async fn index_values(&self) -> Result<Vec<(I, V)>, ViewError> {
+        self.map.index_values().await
+    }
+
+    /// Obtains the number of entries in the map.
+    pub async fn iterative_count(&self) -> Result<usize, ViewError> {
+        self.map.iterative_count().await
+    }
+
+    /// Returns the indices of the entries that have the key `key` in the secondary index
+    /// `Y`, in the order determined by the serialization of the indices. The scan starts
+    /// after the index `after`, if any, and returns at most `limit` indices, so that the
+    /// next page can be obtained by starting after the last index returned.
+    pub async fn indices_by<Y>(
+        &self,
+        key: &Y::Key,
+        after: Option<&I>,
+        limit: Option<u32>,
+    ) -> Result<Vec<I>, ViewError>
+    where
+        Y: MapIndex<I, V>,
+    {
+        let position =
+            X::position::<Y>().ok_or(ViewError::UnknownIndex(std::any::type_name::<Y>()))?;
+        let mut prefix = vec![position];
+        bcs::serialize_into(&mut prefix, key)?;
+        let start = match after {
+            None => KeyBound::Included(prefix.clone()),
+            Some(after) => {
+                let mut start = prefix.clone();
+                bcs::serialize_into(&mut start, after)?;
+                KeyBound::Excluded(start)
+            }
+        };
+        let end = match get_upper_bound_option(&prefix) {
+            None => KeyBound::Unbounded,
+            Some(upper_bound) => KeyBound::Excluded(upper_bound),
+        };
+        let mut range = KeyRange::new(start, end);
+        if let Some(limit) = limit {
+            range = range.with_limit(limit);
+        }
+        self.index
+            .keys_in_range(&range)
+            .await?
+            .into_iter()
+            .map(|key| Ok(BaseKey::deserialize_value(&key[prefix.len()..])?))
+            .collect()
+    }
+
+    /// Returns the `(index, value)` pairs of the entries that have the key `key` in the
+    /// secondary index `Y`. See [`IndexedMapView::indices_by`] for the order and the
+    /// pagination.
+    pub async fn index_values_by<Y>(
+        &self,
+        key: &Y::Key,
+        after: Option<&I>,
+        limit: Option<u32>,
+    ) -> Result<Vec<(I, V)>, ViewError>
+    where
+        Y: MapIndex<I, V>,
+    {
+        let indices = self.indices_by::<Y>(key, after, limit).await?;
+        let values = self.map.multi_get(&indices).await?;
+        indices
+            .into_iter()
+            .zip(values)
+            .map(|(index, value)| Ok((index, value.ok_or(ViewError::InconsistentEntries)?)))
+            .collect()
+    }
+}
+
+impl<C, I, V, X> HashableView for IndexedMapView<C, I, V, X>
+where
+    Self: View,
+    MapView<C, I, V>: HashableView,
+{
+    type Hasher = <MapView<C, I, V> as HashableView>::Hasher;
+
+    async fn hash_mut(&mut self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
+        self.map.hash_mut().await
+    }
+
+    async fn hash(&self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
+        self.map.hash().await
+    }
+}
+
+/// Type wrapping `IndexedMapView` while memoizing the hash.
+pub type HashedIndexedMapView<C, I, V, X> =
+    WrappedHashableContainerView<C, IndexedMapView<C, I, V, X>, HasherOutput>;
+
+/// Wrapper around `IndexedMapView` to compute hashes based on the history of changes.
+pub type HistoricallyHashedIndexedMapView<C, I, V, X> =
+    HistoricallyHashableView<C, IndexedMapView<C, I, V, X>>;
+
+#[cfg(with_graphql)]
+mod graphql {
+    use std::borrow::Cow;
+
+    use super::{IndexedMapView, MapIndices};
+    use crate::{
+        context::Context,
+        graphql::{hash_name, mangle, Entry, MapInput},
+    };
+
+    impl<C: Send + Sync, I: async_graphql::OutputType, V: async_graphql::OutputType, X>
+        async_graphql::TypeName for IndexedMapView<C, I, V, X>
+    {
+        fn type_name() -> Cow<'static, str> {
+            format!(
+                "IndexedMapView_{}_{}_{:08x}",
+                mangle(I::type_name()),
+                mangle(V::type_name()),
+                hash_name::<(I, V)>(),
+            )
+            .into()
+        }
+    }
+
+    #[async_graphql::Object(cache_control(no_cache), name_type)]
+    impl<C, I, V, X> IndexedMapView<C, I, V, X>
+    where
+        C: Context,
+        I: async_graphql::OutputType
+            + async_graphql::InputType
+            + serde::ser::Serialize
+            + serde::de::DeserializeOwned
+            + std::fmt::Debug
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+        V: async_graphql::OutputType
+            + serde::ser::Serialize
+            + serde::de::DeserializeOwned
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+        X: MapIndices<I, V>,
+    {
+        async fn keys(&self, count: Option<usize>) -> Result<Vec<I>, async_graphql::Error> {
+            let indices = self.indices().await?;
+            let it = indices.iter().cloned();
+            Ok(if let Some(count) = count {
+                it.take(count).collect()
+            } else {
+                it.collect()
+            })
+        }
+
+        #[graphql(derived(name = "count"))]
+        async fn count_(&self) -> Result<u32, async_graphql::Error> {
+            let count = self.iterative_count().await?;
+            u32::try_from(count).map_err(|_| async_graphql::Error::new("count exceeds u32"))
+        }
+
+        async fn entry(&self, key: I) -> Result<Entry<I, Option<V>>, async_graphql::Error> {
+            Ok(Entry {
+                value: self.get(&key).await?,
+                key,
+            })
+        }
+
+        async fn entries(
+            &self,
+            input: Option<MapInput<I>>,
+        ) -> Result<Vec<Entry<I, Option<V>>>, async_graphql::Error> {
+            let keys = input
+                .and_then(|input| input.filters)
+                .and_then(|filters| filters.keys);
+            let keys = if let Some(keys) = keys {
+                keys
+            } else {
+                self.indices().await?
+            };
+
+            let values = self.multi_get(&keys).await?;
+            Ok(values
+                .into_iter()
+                .zip(keys)
+                .map(|(value, key)| Entry { value, key })
+                .collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{context::MemoryContext, store::WritableKeyValueStore as _};
+
+    struct ByOwner;
+
+    impl MapIndex<u32, (String, Vec<String>)> for ByOwner {
+        type Key = String;
+
+        fn keys(_index: &u32, value: &(String, Vec<String>)) -> Vec<String> {
+            vec![value.0.clone()]
+        }
+    }
+
+    struct ByTag;
+
+    impl MapIndex<u32, (String, Vec<String>)> for ByTag {
+        type Key = String;
+
+        fn keys(_index: &u32, value: &(String, Vec<String>)) -> Vec<String> {
+            value.1.clone()
+        }
+    }
+
+    struct Unused;
+
+    impl MapIndex<u32, (String, Vec<String>)> for Unused {
+        type Key = ();
+
+        fn keys(_index: &u32, _value: &(String, Vec<String>)) -> Vec<()> {
+            Vec::new()
+        }
+    }
+
+    type Entries = IndexedMapView<MemoryContext<()>, u32, (String, Vec<String>), (ByOwner, ByTag)>;
+
+    fn entry(owner: &str, tags: &[&str]) -> (String, Vec<String>) {
+        (
+            owner.to_string(),
+            tags.iter().map(|tag| tag.to_string()).collect(),
+        )
+    }
+
+    async fn save(view: &mut Entries) -> Result<(), ViewError> {
+        let mut batch = Batch::new();
+        view.pre_save(&mut batch)?;
+        view.context().store().write_batch(batch).await?;
+        view.post_save();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_indexed_map_view_updates_indices() -> Result<(), ViewError> {
+        let context = MemoryContext::new_for_testing(());
+        let mut view = Entries::load(context).await?;
+        view.insert(&1, entry("alice", &["red", "blue"])).await?;
+        view.insert(&2, entry("bob", &["red"])).await?;
+        view.insert(&3, entry("alice", &[])).await?;
+        let alice = "alice".to_string();
+        let red = "red".to_string();
+        assert_eq!(
+            view.indices_by::<ByOwner>(&alice, None, None).await?,
+            [1, 3]
+        );
+        assert_eq!(view.indices_by::<ByTag>(&red, None, None).await?, [1, 2]);
+
+        // Replacing an entry moves it between index keys.
+        view.insert(&1, entry("bob", &["blue"])).await?;
+        assert_eq!(view.indices_by::<ByOwner>(&alice, None, None).await?, [3]);
+        assert_eq!(view.indices_by::<ByTag>(&red, None, None).await?, [2]);
+        save(&mut view).await?;
+
+        view.remove(&2).await?;
+        view.remove(&4).await?;
+        assert!(view.indices_by::<ByTag>(&red, None, None).await?.is_empty());
+        assert_eq!(
+            view.index_values_by::<ByOwner>(&"bob".to_string(), None, None)
+                .await?,
+            [(1, entry("bob", &["blue"]))]
+        );
+        save(&mut view).await?;
+
+        let view = Entries::load(view.context()).await?;
+        assert_eq!(view.indices().await?, [1, 3]);
+        assert!(view.indices_by::<ByTag>(&red, None, None).await?.is_empty());
+        assert_eq!(
+            view.indices_by::<ByTag>(&"blue".to_string(), None, None)
+                .await?,
+            [1]
+        );
+        assert!(matches!(
+            view.indices_by::<Unused>(&(), None, None).await,
+            Err(ViewError::UnknownIndex(_))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_indexed_map_view_pagination() -> Result<(), ViewError> {
+        let context = MemoryContext::new_for_testing(());
+        let mut view = Entries::load(context).await?;
+        for index in 0..10 {
+            let owner = if index % 2 == 0 { "alice" } else { "bob" };
+            view.insert(&index, entry(owner, &[])).await?;
+        }
+        save(&mut view).await?;
+        view.insert(&10, entry("alice", &[])).await?;
+
+        let alice = "alice".to_string();
+        let mut pages = Vec::new();
+        let mut after = None;
+        loop {
+            let page = view
+                .indices_by::<ByOwner>(&alice, after.as_ref(), Some(4))
+                .await?;
+            let Some(last) = page.last() else {
+                break;
+            };
+            after = Some(*last);
+            pages.push(page);
+        }
+        assert_eq!(pages, [vec![0, 2, 4, 6], vec![8, 10]]);
+
+        view.clear();
+        assert!(view
+            .indices_by::<ByOwner>(&alice, None, None)
+            .await?
+            .is_empty());
+        save(&mut view).await?;
+        let view = Entries::load(view.context()).await?;
+        assert_eq!(view.iterative_count().await?, 0);
+        assert!(view
+            .indices_by::<ByOwner>(&alice, None, None)
+            .await?
+            .is_empty());
+        Ok(())
+    }
+}
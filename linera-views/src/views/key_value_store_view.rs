@@ -23,13 +23,13 @@ use linera_base::{ensure, visit_allocative_simple};
 use crate::{
     batch::{Batch, WriteOperation},
     common::{
-        from_bytes_option, get_key_range_for_prefix, get_upper_bound, DeletionSet, HasherOutput,
-        SuffixClosedSetIterator, Update,
+        from_bytes_option, get_key_range_for_prefix, get_upper_bound, read_entries_in_range,
+        DeletionSet, HasherOutput, RangeEntry, SuffixClosedSetIterator, Update,
     },
     context::Context,
     hashable_wrapper::WrappedHashableContainerView,
     historical_hash_wrapper::HistoricallyHashableView,
-    store::ReadableKeyValueStore,
+    store::{KeyRange, ReadableKeyValueStore},
     views::{ClonableView, HashableView, Hasher, ReplaceContext, View, ViewError, MIN_VIEW_TAG},
 };
 
@@ -102,6 +102,24 @@ pub(crate) mod metrics {
                 exponential_bucket_latencies(5.0),
             );
 
+        /// The latency of find keys in range operation
+        pub static KEY_VALUE_STORE_VIEW_FIND_KEYS_IN_RANGE_LATENCY: HistogramVec =
+            register_histogram_vec(
+                "key_value_store_view_find_keys_in_range_latency",
+                "KeyValueStoreView find keys in range latency",
+                &[],
+                exponential_bucket_latencies(5.0),
+            );
+
+        /// The latency of find key values in range operation
+        pub static KEY_VALUE_STORE_VIEW_FIND_KEY_VALUES_IN_RANGE_LATENCY: HistogramVec =
+            register_histogram_vec(
+                "key_value_store_view_find_key_values_in_range_latency",
+                "KeyValueStoreView find key values in range latency",
+                &[],
+                exponential_bucket_latencies(5.0),
+            );
+
         /// The latency of write batch operation
         pub static KEY_VALUE_STORE_VIEW_WRITE_BATCH_LATENCY: HistogramVec =
             register_histogram_vec(
@@ -1016,6 +1034,102 @@ impl<C: Context> KeyValueStoreView<C> {
         Ok(key_values)
     }
 
+    /// Returns the keys matching the given prefix whose remainder belongs to the `range`,
+    /// in the direction and up to the limit of the range. The prefix is not included in the
+    /// returned keys.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::key_value_store_view::KeyValueStoreView;
+    /// # use linera_views::store::{KeyBound, KeyRange};
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view = KeyValueStoreView::load(context).await.unwrap();
+    /// for i in 0..5 {
+    ///     view.insert(vec![0, i], vec![i]).await.unwrap();
+    /// }
+    /// let range = KeyRange::new(KeyBound::Included(vec![1]), KeyBound::Unbounded)
+    ///     .reversed()
+    ///     .with_limit(2);
+    /// let keys = view.find_keys_in_range(&[0], &range).await.unwrap();
+    /// assert_eq!(keys, vec![vec![4], vec![3]]);
+    /// let keys = view.find_keys_in_range(&[0], &range.after(&[3])).await.unwrap();
+    /// assert_eq!(keys, vec![vec![2], vec![1]]);
+    /// # })
+    /// ```
+    pub async fn find_keys_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<Vec<u8>>, ViewError> {
+        #[cfg(with_metrics)]
+        let _latency = metrics::KEY_VALUE_STORE_VIEW_FIND_KEYS_IN_RANGE_LATENCY.measure_latency();
+        let entries = self.read_entries_in_range(key_prefix, range, false).await?;
+        Ok(entries.into_iter().map(|(key, _)| key).collect())
+    }
+
+    /// Returns the key-value pairs whose keys match the given prefix and whose remainder
+    /// belongs to the `range`, in the direction and up to the limit of the range. The
+    /// prefix is not included in the returned keys.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::key_value_store_view::KeyValueStoreView;
+    /// # use linera_views::store::{KeyBound, KeyRange};
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view = KeyValueStoreView::load(context).await.unwrap();
+    /// view.insert(vec![0, 1], vec![34]).await.unwrap();
+    /// view.insert(vec![0, 2], vec![42]).await.unwrap();
+    /// let range = KeyRange::new(KeyBound::Excluded(vec![1]), KeyBound::Unbounded);
+    /// let key_values = view.find_key_values_in_range(&[0], &range).await.unwrap();
+    /// assert_eq!(key_values, vec![(vec![2], vec![42])]);
+    /// # })
+    /// ```
+    pub async fn find_key_values_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ViewError> {
+        #[cfg(with_metrics)]
+        let _latency =
+            metrics::KEY_VALUE_STORE_VIEW_FIND_KEY_VALUES_IN_RANGE_LATENCY.measure_latency();
+        let entries = self.read_entries_in_range(key_prefix, range, true).await?;
+        Ok(entries
+            .into_iter()
+            .map(|(key, entry)| match entry {
+                RangeEntry::Pending(value) => (key, value.clone()),
+                RangeEntry::Stored(value) => (key, value.unwrap_or_default()),
+            })
+            .collect())
+    }
+
+    async fn read_entries_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+        with_values: bool,
+    ) -> Result<Vec<(Vec<u8>, RangeEntry<'_, Vec<u8>>)>, ViewError> {
+        ensure!(
+            key_prefix.len() <= self.max_key_size(),
+            ViewError::KeyTooLong
+        );
+        let key_prefix_full = self
+            .context
+            .base_key()
+            .base_tag_index(KeyTag::Index as u8, key_prefix);
+        read_entries_in_range(
+            self.context.store(),
+            &key_prefix_full,
+            key_prefix,
+            range,
+            &self.updates,
+            |index| self.deletion_set.contains_prefix_of(index),
+            with_values,
+        )
+        .await
+    }
+
     async fn compute_hash(&self) -> Result<<sha3::Sha3_256 as Hasher>::Output, ViewError> {
         #[cfg(with_metrics)]
         let _hash_latency = metrics::KEY_VALUE_STORE_VIEW_HASH_LATENCY.measure_latency();
@@ -1146,6 +1260,24 @@ impl<C: Context> ReadableKeyValueStore for ViewContainer<C> {
         let view = self.view.read().await;
         Ok(view.find_key_values_by_prefix(key_prefix).await?)
     }
+
+    async fn find_keys_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<Vec<u8>>, ViewContainerError> {
+        let view = self.view.read().await;
+        Ok(view.find_keys_in_range(key_prefix, range).await?)
+    }
+
+    async fn find_key_values_in_range(
+        &self,
+        key_prefix: &[u8],
+        range: &KeyRange,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ViewContainerError> {
+        let view = self.view.read().await;
+        Ok(view.find_key_values_in_range(key_prefix, range).await?)
+    }
 }
 
 #[cfg(with_testing)]
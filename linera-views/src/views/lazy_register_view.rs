@@ -13,6 +13,7 @@ use crate::{
     common::{from_bytes_option_or_default, HasherOutput},
     context::Context,
     hashable_wrapper::WrappedHashableContainerView,
+    proof::{ProofLeaf, ViewProof},
     store::ReadableKeyValueStore,
     views::{ClonableView, HashableView, Hasher, ReplaceContext, View},
     ViewError,
@@ -238,6 +239,12 @@ where
         Ok(self.update.as_mut().unwrap())
     }
 
+    /// Creates a proof of the value in the register, for the hash of the register.
+    pub async fn proof(&self) -> Result<ViewProof, ViewError> {
+        let value = bcs::to_bytes(self.get().await?)?;
+        Ok(ViewProof::new(ProofLeaf::Value(value)))
+    }
+
     async fn compute_hash(&self) -> Result<<sha3::Sha3_256 as Hasher>::Output, ViewError> {
         #[cfg(with_metrics)]
         let _hash_latency = metrics::LAZY_REGISTER_VIEW_HASH_RUNTIME.measure_latency();
@@ -18,6 +18,7 @@ use crate::{
     context::Context,
     hashable_wrapper::WrappedHashableContainerView,
     historical_hash_wrapper::HistoricallyHashableView,
+    proof::{ProofLeaf, ViewProof},
     store::ReadableKeyValueStore as _,
     views::{ClonableView, HashableView, Hasher, View, ViewError, MIN_VIEW_TAG},
 };
@@ -402,6 +403,23 @@ where
     }
 }
 
+impl<C, T> LogView<C, T>
+where
+    C: Context,
+    T: Send + Sync + Clone + Serialize + DeserializeOwned,
+{
+    /// Creates a proof of the elements of the log, for the hash of the log.
+    pub async fn proof(&self) -> Result<ViewProof, ViewError> {
+        let elements = self
+            .read(..)
+            .await?
+            .iter()
+            .map(bcs::to_bytes)
+            .collect::<Result<_, _>>()?;
+        Ok(ViewProof::new(ProofLeaf::Elements(elements)))
+    }
+}
+
 impl<C, T> HashableView for LogView<C, T>
 where
     C: Context,
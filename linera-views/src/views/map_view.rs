@@ -40,6 +40,7 @@ use std::{
     borrow::{Borrow, Cow},
     collections::{btree_map::Entry, BTreeMap},
     marker::PhantomData,
+    ops::RangeBounds,
 };
 
 use allocative::Allocative;
@@ -48,13 +49,14 @@ use serde::{de::DeserializeOwned, Serialize};
 use crate::{
     batch::Batch,
     common::{
-        from_bytes_option, get_key_range_for_prefix, CustomSerialize, DeletionSet, HasherOutput,
-        SuffixClosedSetIterator, Update,
+        from_bytes_option, get_key_range, get_key_range_for_prefix, read_entries_in_range,
+        CustomSerialize, DeletionSet, HasherOutput, RangeEntry, SuffixClosedSetIterator, Update,
     },
     context::{BaseKey, Context},
     hashable_wrapper::WrappedHashableContainerView,
     historical_hash_wrapper::HistoricallyHashableView,
-    store::ReadableKeyValueStore as _,
+    proof::{ProofLeaf, ViewProof},
+    store::{KeyRange, ReadableKeyValueStore as _},
     views::{ClonableView, HashableView, Hasher, ReplaceContext, View, ViewError},
 };
 
@@ -599,6 +601,48 @@ where
         Ok(keys)
     }
 
+    /// Returns the keys of the map that belong to the `range`, in the direction and up to
+    /// the limit of the range.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::ByteMapView;
+    /// # use linera_views::store::{KeyBound, KeyRange};
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map = ByteMapView::load(context).await.unwrap();
+    /// map.insert(vec![0, 1], String::from("Hello"));
+    /// map.insert(vec![1, 2], String::from("Bonjour"));
+    /// map.insert(vec![2, 2], String::from("Hallo"));
+    /// let range = KeyRange::new(KeyBound::Included(vec![1]), KeyBound::Unbounded).reversed();
+    /// assert_eq!(
+    ///     map.keys_in_range(&range).await.unwrap(),
+    ///     vec![vec![2, 2], vec![1, 2]]
+    /// );
+    /// # })
+    /// ```
+    pub async fn keys_in_range(&self, range: &KeyRange) -> Result<Vec<Vec<u8>>, ViewError> {
+        let entries = self.read_entries_in_range(range, false).await?;
+        Ok(entries.into_iter().map(|(key, _)| key).collect())
+    }
+
+    async fn read_entries_in_range(
+        &self,
+        range: &KeyRange,
+        with_values: bool,
+    ) -> Result<Vec<(Vec<u8>, RangeEntry<'_, V>)>, ViewError> {
+        read_entries_in_range(
+            self.context.store(),
+            &self.context.base_key().bytes,
+            &[],
+            range,
+            &self.updates,
+            |key| self.deletion_set.contains_prefix_of(key),
+            with_values,
+        )
+        .await
+    }
+
     /// Returns the list of keys of the map having a specified prefix
     /// in lexicographic order.
     /// ```rust
@@ -902,6 +946,42 @@ where
     pub async fn key_values(&self) -> Result<Vec<(Vec<u8>, V)>, ViewError> {
         self.key_values_by_prefix(Vec::new()).await
     }
+
+    /// Returns the keys and values of the map whose keys belong to the `range`, in the
+    /// direction and up to the limit of the range.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::ByteMapView;
+    /// # use linera_views::store::{KeyBound, KeyRange};
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map = ByteMapView::load(context).await.unwrap();
+    /// map.insert(vec![1, 2], String::from("Hello"));
+    /// map.insert(vec![1, 3], String::from("Hallo"));
+    /// let range = KeyRange::default().with_limit(1);
+    /// assert_eq!(
+    ///     map.key_values_in_range(&range).await.unwrap(),
+    ///     vec![(vec![1, 2], String::from("Hello"))]
+    /// );
+    /// # })
+    /// ```
+    pub async fn key_values_in_range(
+        &self,
+        range: &KeyRange,
+    ) -> Result<Vec<(Vec<u8>, V)>, ViewError> {
+        let entries = self.read_entries_in_range(range, true).await?;
+        entries
+            .into_iter()
+            .map(|(key, entry)| {
+                let value = match entry {
+                    RangeEntry::Pending(value) => value.clone(),
+                    RangeEntry::Stored(bytes) => bcs::from_bytes(&bytes.unwrap_or_default())?,
+                };
+                Ok((key, value))
+            })
+            .collect()
+    }
 }
 
 impl<C, V> ByteMapView<C, V>
@@ -959,6 +1039,28 @@ where
     }
 }
 
+impl<C, V> ByteMapView<C, V>
+where
+    C: Context,
+    V: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+{
+    /// Creates a proof of the entries of the map, for the hash of the map. The proof
+    /// contains all the entries. It can only be verified if the keys are BCS-serialized, as
+    /// in a `MapView`.
+    pub(crate) async fn proof(&self) -> Result<ViewProof, ViewError> {
+        let mut entries = Vec::new();
+        self.for_each_key_value_or_bytes(
+            |index, value| {
+                entries.push((index.to_vec(), value.into_bytes()?));
+                Ok(())
+            },
+            Vec::new(),
+        )
+        .await?;
+        Ok(ViewProof::new(ProofLeaf::Entries(entries)))
+    }
+}
+
 impl<C, V> HashableView for ByteMapView<C, V>
 where
     C: Context,
@@ -1324,6 +1426,52 @@ where
         Ok(indices)
     }
 
+    /// Returns the indices within `range`, in the order determined by the serialization, or
+    /// in the reverse order. At most `limit` indices are returned, and the next ones can be
+    /// obtained by starting after the last index returned.
+    ///
+    /// Indices are compared by their BCS serialization, which for instance doesn't sort
+    /// integers other than `u8` numerically. A [`CustomMapView`] can be used where the
+    /// natural order is needed.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use std::ops::Bound;
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::MapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map: MapView<_, u8, String> = MapView::load(context).await.unwrap();
+    /// for index in 0..10 {
+    ///     map.insert(&index, format!("Hello {index}"));
+    /// }
+    /// let page = map.indices_in_range(2..8, true, Some(3)).await.unwrap();
+    /// assert_eq!(page, vec![7, 6, 5]);
+    /// let next_page = map
+    ///     .indices_in_range((Bound::Included(2), Bound::Excluded(5)), true, Some(3))
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(next_page, vec![4, 3, 2]);
+    /// # })
+    /// ```
+    pub async fn indices_in_range<Q>(
+        &self,
+        range: impl RangeBounds<Q>,
+        reverse: bool,
+        limit: Option<u32>,
+    ) -> Result<Vec<I>, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        let range = get_key_range(range, reverse, limit, |index| {
+            Ok(BaseKey::derive_short_key(index)?)
+        })?;
+        let keys = self.map.keys_in_range(&range).await?;
+        keys.iter()
+            .map(|key| Ok(BaseKey::deserialize_value(key)?))
+            .collect()
+    }
+
     /// Applies a function f on each index. Indices are visited in an order
     /// determined by the serialization. If the function returns false, then
     /// the loop ends prematurely.
@@ -1512,6 +1660,46 @@ where
         Ok(key_values)
     }
 
+    /// Obtains the `(index,value)` pairs whose index is within `range`, in the order
+    /// determined by the serialization, or in the reverse order. At most `limit` pairs are
+    /// returned. See [`MapView::indices_in_range`] for the order of the indices.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::MapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map: MapView<_, u8, _> = MapView::load(context).await.unwrap();
+    /// map.insert(&1, String::from("Ciao"));
+    /// map.insert(&2, String::from("Hallo"));
+    /// map.insert(&3, String::from("Hola"));
+    /// let index_values = map.index_values_in_range(2.., false, None).await.unwrap();
+    /// assert_eq!(
+    ///     index_values,
+    ///     vec![(2, "Hallo".to_string()), (3, "Hola".to_string())]
+    /// );
+    /// # })
+    /// ```
+    pub async fn index_values_in_range<Q>(
+        &self,
+        range: impl RangeBounds<Q>,
+        reverse: bool,
+        limit: Option<u32>,
+    ) -> Result<Vec<(I, V)>, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        let range = get_key_range(range, reverse, limit, |index| {
+            Ok(BaseKey::derive_short_key(index)?)
+        })?;
+        let key_values = self.map.key_values_in_range(&range).await?;
+        key_values
+            .into_iter()
+            .map(|(key, value)| Ok((BaseKey::deserialize_value(&key)?, value)))
+            .collect()
+    }
+
     /// Obtains the number of entries in the map
     /// ```rust
     /// # tokio_test::block_on(async {
@@ -1559,6 +1747,32 @@ where
     }
 }
 
+impl<C, I, V> MapView<C, I, V>
+where
+    C: Context,
+    V: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+{
+    /// Creates a proof of the entries of the map, for the hash of the map. The proof
+    /// contains all the entries.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::MapView;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map: MapView<_, u32, String> = MapView::load(context).await.unwrap();
+    /// map.insert(&37, String::from("Hello")).unwrap();
+    /// let proof = map.proof().await.unwrap();
+    /// let hash = map.hash().await.unwrap();
+    /// let entries = proof.verify_entries::<u32, String>(&hash, &[]).unwrap();
+    /// assert_eq!(entries, [(37, String::from("Hello"))]);
+    /// # })
+    /// ```
+    pub async fn proof(&self) -> Result<ViewProof, ViewError> {
+        self.map.proof().await
+    }
+}
+
 impl<C, I, V> HashableView for MapView<C, I, V>
 where
     Self: View,
@@ -1878,6 +2092,34 @@ where
         Ok(indices)
     }
 
+    /// Returns the indices within `range`, in the order determined by the custom
+    /// serialization, or in the reverse order. At most `limit` indices are returned, and the
+    /// next ones can be obtained by starting after the last index returned.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::CustomMapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map = CustomMapView::<_, u128, _>::load(context).await.unwrap();
+    /// map.insert(&(34 as u128), String::from("Hello"));
+    /// map.insert(&(37 as u128), String::from("Bonjour"));
+    /// map.insert(&(300 as u128), String::from("Hola"));
+    /// let indices = map.indices_in_range(35.., false, Some(5)).await.unwrap();
+    /// assert_eq!(indices, vec![37 as u128, 300 as u128]);
+    /// # })
+    /// ```
+    pub async fn indices_in_range(
+        &self,
+        range: impl RangeBounds<I>,
+        reverse: bool,
+        limit: Option<u32>,
+    ) -> Result<Vec<I>, ViewError> {
+        let range = get_key_range(range, reverse, limit, I::to_custom_bytes)?;
+        let keys = self.map.keys_in_range(&range).await?;
+        keys.iter().map(|key| I::from_custom_bytes(key)).collect()
+    }
+
     /// Applies a function f on each index. Indices are visited in an order
     /// determined by the custom serialization. If the function returns false,
     /// then the loop ends prematurely.
@@ -2065,6 +2307,36 @@ where
         Ok(key_values)
     }
 
+    /// Obtains the `(index,value)` pairs whose index is within `range`, in the order
+    /// determined by the custom serialization, or in the reverse order. At most `limit`
+    /// pairs are returned.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::CustomMapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map = CustomMapView::<_, u128, _>::load(context).await.unwrap();
+    /// map.insert(&(24 as u128), String::from("Ciao"));
+    /// map.insert(&(37 as u128), String::from("Bonjour"));
+    /// let index_values = map.index_values_in_range(.., true, Some(1)).await.unwrap();
+    /// assert_eq!(index_values, vec![(37 as u128, "Bonjour".to_string())]);
+    /// # })
+    /// ```
+    pub async fn index_values_in_range(
+        &self,
+        range: impl RangeBounds<I>,
+        reverse: bool,
+        limit: Option<u32>,
+    ) -> Result<Vec<(I, V)>, ViewError> {
+        let range = get_key_range(range, reverse, limit, I::to_custom_bytes)?;
+        let key_values = self.map.key_values_in_range(&range).await?;
+        key_values
+            .into_iter()
+            .map(|(key, value)| Ok((I::from_custom_bytes(&key)?, value)))
+            .collect()
+    }
+
     /// Obtains the number of entries in the map
     /// ```rust
     /// # tokio_test::block_on(async {
@@ -36,6 +36,12 @@ pub mod map_view;
 /// The `SetView` implements a set with ordered entries.
 pub mod set_view;
 
+/// The `SortedSetView` implements a set of keys ordered by a score.
+pub mod sorted_set_view;
+
+/// The `IndexedMapView` implements a map with ordered keys and secondary indices.
+pub mod indexed_map_view;
+
 /// The `CollectionView` implements a map structure whose keys are ordered and the values are views.
 pub mod collection_view;
 
@@ -51,6 +57,9 @@ pub mod hashable_wrapper;
 /// Wrapping a view to compute hash based on the history of modifications to the view.
 pub mod historical_hash_wrapper;
 
+/// Proofs that some data is part of the content of a hashable view.
+pub mod proof;
+
 /// The minimum value for the view tags. Values in `0..MIN_VIEW_TAG` are used for other purposes.
 pub const MIN_VIEW_TAG: u8 = 1;
 
@@ -0,0 +1,336 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use linera_base::data_types::ArithmeticError;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha3::{Digest as _, Sha3_256};
+use thiserror::Error;
+
+use crate::{common::HasherOutput, context::BaseKey, views::HashableView, ViewError};
+
+/// A proof that some data is part of the content of a view with a given hash, as computed
+/// by [`HashableView::hash`].
+///
+/// The proof contains the content of the innermost view that is needed to recompute its
+/// hash, e.g. all the entries of a `MapView`, and then, for each view containing it, the
+/// hashes of the other fields or entries. A proof for a map entry is therefore as large as
+/// the map, while a proof through a collection only grows with the number of its entries.
+///
+/// Proofs can't be checked against the hash of a `HistoricallyHashableView`, which depends
+/// on the history of the changes rather than on the content of the view. The state hash of
+/// a block therefore also commits to the content hash of the system state, and
+/// `linera_execution::ExecutionStateProof` extends a proof for the system state so that it
+/// can be checked against it.
+///
+/// Views whose keys are raw bytes, like `ByteMapView` or `KeyValueStoreView`, can't be
+/// proven: their hashes don't delimit keys from values, so the entries can't be recovered
+/// unambiguously from a proof.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ViewProof {
+    /// The content of the innermost view.
+    pub leaf: ProofLeaf,
+    /// The views containing the innermost view, innermost first.
+    pub path: Vec<ProofStep>,
+}
+
+/// The content of the innermost view of a [`ViewProof`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ProofLeaf {
+    /// The serialized value of a `RegisterView` or a `LazyRegisterView`.
+    Value(Vec<u8>),
+    /// The entries of a `MapView`, as serialized keys and values, in key order.
+    Entries(Vec<(Vec<u8>, Vec<u8>)>),
+    /// The serialized elements of a `LogView` or a `QueueView`.
+    Elements(Vec<Vec<u8>>),
+}
+
+/// How a view is contained in the next view of a [`ViewProof`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ProofStep {
+    /// The view is a field of a view deriving `HashableView`.
+    Field {
+        /// The position of the field.
+        index: u32,
+        /// The hashes of the other fields, in order.
+        other_hashes: Vec<HasherOutput>,
+    },
+    /// The view is an entry of a `CollectionView` or a `ReentrantCollectionView`.
+    Entry {
+        /// The short key of the entry.
+        key: Vec<u8>,
+        /// The short keys and hashes of the other entries, in key order.
+        other_entries: Vec<(Vec<u8>, HasherOutput)>,
+    },
+}
+
+/// The position of a view inside the view containing it, as expected by a verifier.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ProofPosition {
+    /// The field with the given position.
+    Field(u32),
+    /// The entry with the given short key.
+    Entry(Vec<u8>),
+}
+
+/// A view deriving `HashableView`, whose hash combines the hashes of its fields.
+#[cfg_attr(not(web), trait_variant::make(Send))]
+pub trait HashableFields: HashableView {
+    /// The names of the fields, in the order in which their hashes are combined.
+    const FIELD_NAMES: &'static [&'static str];
+
+    /// Computes the hashes of the fields.
+    async fn field_hashes(&self) -> Result<Vec<HasherOutput>, ViewError>;
+}
+
+/// An error when verifying a [`ViewProof`].
+#[derive(Debug, Error)]
+pub enum ProofError {
+    /// The proof doesn't match the expected hash.
+    #[error("the proof doesn't match the expected hash")]
+    HashMismatch,
+
+    /// The proof isn't for the expected position in the view.
+    #[error("the proof isn't for the expected position in the view")]
+    WrongPosition,
+
+    /// The proof isn't for the expected kind of view.
+    #[error("the proof isn't for a view with {0}")]
+    WrongLeaf(&'static str),
+
+    /// The entries of the proof aren't sorted by key.
+    #[error("the entries of the proof aren't sorted by key")]
+    UnsortedEntries,
+
+    /// The position of a field is larger than the number of fields.
+    #[error("the position of the field is larger than the number of fields")]
+    InvalidFieldIndex,
+
+    /// The proof has too many entries.
+    #[error("the proof has too many entries")]
+    TooManyEntries,
+
+    /// BCS deserialization error.
+    #[error(transparent)]
+    BcsError(#[from] bcs::Error),
+}
+
+impl ProofPosition {
+    /// Returns the position of the field `field` of the views of type `V`.
+    pub fn field<V: HashableFields>(field: &str) -> Option<Self> {
+        let index = V::FIELD_NAMES.iter().position(|name| *name == field)?;
+        Some(ProofPosition::Field(u32::try_from(index).ok()?))
+    }
+
+    /// Returns the position of the entry with the given index in a `CollectionView`.
+    pub fn entry<I: Serialize + ?Sized>(index: &I) -> Result<Self, bcs::Error> {
+        Ok(ProofPosition::Entry(BaseKey::derive_short_key(index)?))
+    }
+}
+
+impl ViewProof {
+    /// Creates a proof for the content of a view that isn't contained in another one.
+    pub fn new(leaf: ProofLeaf) -> Self {
+        ViewProof {
+            leaf,
+            path: Vec::new(),
+        }
+    }
+
+    /// Extends a proof for the content of the field named `field` of `view` into a proof
+    /// for `view`.
+    pub async fn in_field<V: HashableFields>(
+        mut self,
+        view: &V,
+        field: &str,
+    ) -> Result<Self, ViewError> {
+        let index = V::FIELD_NAMES
+            .iter()
+            .position(|name| *name == field)
+            .ok_or_else(|| ViewError::NotFound(format!("field {field}")))?;
+        let mut other_hashes = view.field_hashes().await?;
+        other_hashes.remove(index);
+        self.path.push(ProofStep::Field {
+            index: u32::try_from(index).map_err(|_| ArithmeticError::Overflow)?,
+            other_hashes,
+        });
+        Ok(self)
+    }
+
+    /// Checks that the proof is for a `RegisterView` or a `LazyRegisterView` of values of
+    /// type `T`, with the given hash once contained at the given positions, outermost first,
+    /// and returns its value.
+    pub fn verify_value<T: DeserializeOwned>(
+        &self,
+        hash: &HasherOutput,
+        positions: &[ProofPosition],
+    ) -> Result<T, ProofError> {
+        let ProofLeaf::Value(bytes) = self.verify_leaf(hash, positions)? else {
+            return Err(ProofError::WrongLeaf("a value"));
+        };
+        Ok(bcs::from_bytes(bytes)?)
+    }
+
+    /// Checks that the proof is for a `MapView` from `I` to `V`, with the given hash once
+    /// contained at the given positions, outermost first, and returns its entries in the
+    /// order of their serialized keys.
+    pub fn verify_entries<I, V>(
+        &self,
+        hash: &HasherOutput,
+        positions: &[ProofPosition],
+    ) -> Result<Vec<(I, V)>, ProofError>
+    where
+        I: DeserializeOwned,
+        V: DeserializeOwned,
+    {
+        let ProofLeaf::Entries(entries) = self.verify_leaf(hash, positions)? else {
+            return Err(ProofError::WrongLeaf("entries"));
+        };
+        entries
+            .iter()
+            .map(|(key, value)| Ok((bcs::from_bytes(key)?, bcs::from_bytes(value)?)))
+            .collect()
+    }
+
+    /// Checks that the proof is for a `LogView` or a `QueueView` of elements of type `T`,
+    /// with the given hash once contained at the given positions, outermost first, and
+    /// returns its elements.
+    pub fn verify_elements<T: DeserializeOwned>(
+        &self,
+        hash: &HasherOutput,
+        positions: &[ProofPosition],
+    ) -> Result<Vec<T>, ProofError> {
+        let ProofLeaf::Elements(elements) = self.verify_leaf(hash, positions)? else {
+            return Err(ProofError::WrongLeaf("elements"));
+        };
+        elements
+            .iter()
+            .map(|bytes| Ok(bcs::from_bytes(bytes)?))
+            .collect()
+    }
+
+    /// Checks that the proof is for a view with the given hash and for data at the given
+    /// positions, and returns the content of the innermost view.
+    ///
+    /// The hashes of the views don't delimit the keys and values of their entries, so the
+    /// content is only proven once each of them is deserialized exactly: BCS encodings
+    /// can't be prefixes of each other, so no other split of the same bytes would be
+    /// accepted.
+    fn verify_leaf(
+        &self,
+        hash: &HasherOutput,
+        positions: &[ProofPosition],
+    ) -> Result<&ProofLeaf, ProofError> {
+        let path_positions = self.path.iter().rev().map(ProofStep::position);
+        if !path_positions.eq(positions.iter().cloned()) {
+            return Err(ProofError::WrongPosition);
+        }
+        if self.root_hash()? != *hash {
+            return Err(ProofError::HashMismatch);
+        }
+        Ok(&self.leaf)
+    }
+
+    /// Computes the hash of the outermost view.
+    pub fn root_hash(&self) -> Result<HasherOutput, ProofError> {
+        let mut hash = self.leaf.hash()?;
+        for step in &self.path {
+            hash = step.hash(hash)?;
+        }
+        Ok(hash)
+    }
+}
+
+impl ProofLeaf {
+    /// Computes the hash of the view, in the same way as its [`HashableView`]
+    /// implementation.
+    fn hash(&self) -> Result<HasherOutput, ProofError> {
+        let mut hasher = Sha3_256::new();
+        match self {
+            ProofLeaf::Value(bytes) => hasher.update(bytes),
+            ProofLeaf::Entries(entries) => {
+                // Entries aren't delimited in the hash, so they must at least be in the
+                // order in which the view hashes them.
+                if !entries.windows(2).all(|pair| pair[0].0 < pair[1].0) {
+                    return Err(ProofError::UnsortedEntries);
+                }
+                for (key, value) in entries {
+                    hasher.update(key);
+                    hasher.update(value);
+                }
+                let count = u32::try_from(entries.len()).map_err(|_| ProofError::TooManyEntries)?;
+                hasher.update(bcs::to_bytes(&count)?);
+            }
+            ProofLeaf::Elements(elements) => {
+                // This is the BCS serialization of the sequence of elements.
+                let count =
+                    u32::try_from(elements.len()).map_err(|_| ProofError::TooManyEntries)?;
+                hasher.update(uleb128(count));
+                for element in elements {
+                    hasher.update(element);
+                }
+            }
+        }
+        Ok(hasher.finalize())
+    }
+}
+
+impl ProofStep {
+    /// Returns the position of the view inside the next one.
+    fn position(&self) -> ProofPosition {
+        match self {
+            ProofStep::Field { index, .. } => ProofPosition::Field(*index),
+            ProofStep::Entry { key, .. } => ProofPosition::Entry(key.clone()),
+        }
+    }
+
+    /// Computes the hash of the next view from the hash of the view it contains.
+    fn hash(&self, inner_hash: HasherOutput) -> Result<HasherOutput, ProofError> {
+        let mut hasher = Sha3_256::new();
+        match self {
+            ProofStep::Field {
+                index,
+                other_hashes,
+            } => {
+                let index = *index as usize;
+                if index > other_hashes.len() {
+                    return Err(ProofError::InvalidFieldIndex);
+                }
+                let (before, after) = other_hashes.split_at(index);
+                for hash in before.iter().chain([&inner_hash]).chain(after) {
+                    hasher.update(hash);
+                }
+            }
+            ProofStep::Entry { key, other_entries } => {
+                let position = other_entries.partition_point(|(other_key, _)| other_key < key);
+                let (before, after) = other_entries.split_at(position);
+                let entry = (key.clone(), inner_hash);
+                let entries = before
+                    .iter()
+                    .chain([&entry])
+                    .chain(after)
+                    .collect::<Vec<_>>();
+                if !entries.windows(2).all(|pair| pair[0].0 < pair[1].0) {
+                    return Err(ProofError::UnsortedEntries);
+                }
+                let count = u32::try_from(entries.len()).map_err(|_| ProofError::TooManyEntries)?;
+                hasher.update(bcs::to_bytes(&count)?);
+                for (key, hash) in entries {
+                    hasher.update(key);
+                    hasher.update(hash);
+                }
+            }
+        }
+        Ok(hasher.finalize())
+    }
+}
+
+/// Returns the ULEB128 encoding of a length, as used by BCS.
+fn uleb128(mut value: u32) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    while value >= 0x80 {
+        bytes.push(value.to_le_bytes()[0] | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value.to_le_bytes()[0]);
+    bytes
+}
@@ -18,6 +18,7 @@ use crate::{
     context::Context,
     hashable_wrapper::WrappedHashableContainerView,
     historical_hash_wrapper::HistoricallyHashableView,
+    proof::{ProofLeaf, ViewProof},
     store::ReadableKeyValueStore as _,
     views::{ClonableView, HashableView, Hasher, View, ViewError, MIN_VIEW_TAG},
 };
@@ -478,6 +479,23 @@ where
     }
 }
 
+impl<C, T> QueueView<C, T>
+where
+    C: Context,
+    T: Send + Sync + Clone + Serialize + DeserializeOwned,
+{
+    /// Creates a proof of the elements of the queue, for the hash of the queue.
+    pub async fn proof(&self) -> Result<ViewProof, ViewError> {
+        let elements = self
+            .elements()
+            .await?
+            .iter()
+            .map(bcs::to_bytes)
+            .collect::<Result<_, _>>()?;
+        Ok(ViewProof::new(ProofLeaf::Elements(elements)))
+    }
+}
+
 impl<C, T> HashableView for QueueView<C, T>
 where
     C: Context,
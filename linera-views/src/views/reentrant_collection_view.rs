@@ -24,6 +24,7 @@ use crate::{
     context::{BaseKey, Context},
     hashable_wrapper::WrappedHashableContainerView,
     historical_hash_wrapper::HistoricallyHashableView,
+    proof::{ProofStep, ViewProof},
     store::ReadableKeyValueStore as _,
     views::{ClonableView, HashableView, Hasher, ReplaceContext, View, ViewError, MIN_VIEW_TAG},
 };
@@ -1052,6 +1053,54 @@ impl<W: View> ReentrantByteCollectionView<W::Context, W> {
     }
 }
 
+impl<W> ReentrantByteCollectionView<W::Context, W>
+where
+    W: HashableView,
+    W::Hasher: Hasher<Output = HasherOutput>,
+{
+    /// Extends a proof for the content of the entry with the given short key into a proof
+    /// for the collection.
+    pub async fn extend_proof(
+        &self,
+        short_key: &[u8],
+        mut proof: ViewProof,
+    ) -> Result<ViewProof, ViewError> {
+        let keys = self.keys().await?;
+        if !keys.iter().any(|key| key == short_key) {
+            return Err(ViewError::NotFound(format!("entry {short_key:?}")));
+        }
+        let mut other_entries = Vec::with_capacity(keys.len() - 1);
+        for key in keys {
+            if key == short_key {
+                continue;
+            }
+            let hash = if let Some(entry) = self.updates.get(&key) {
+                let Update::Set(view) = entry else {
+                    unreachable!("Loaded entries in updates should always be Update::Set");
+                };
+                let view = view
+                    .try_read_arc()
+                    .ok_or_else(|| ViewError::TryLockError(key.clone()))?;
+                view.hash().await?
+            } else {
+                let key = self
+                    .context
+                    .base_key()
+                    .base_tag_index(KeyTag::Subview as u8, &key);
+                let context = self.context.clone_with_base_key(key);
+                let view = W::load(context).await?;
+                view.hash().await?
+            };
+            other_entries.push((key, hash));
+        }
+        proof.path.push(ProofStep::Entry {
+            key: short_key.to_vec(),
+            other_entries,
+        });
+        Ok(proof)
+    }
+}
+
 impl<W: HashableView> HashableView for ReentrantByteCollectionView<W::Context, W> {
     type Hasher = sha3::Sha3_256;
 
@@ -1686,6 +1735,46 @@ where
     }
 }
 
+impl<I, W> ReentrantCollectionView<W::Context, I, W>
+where
+    W: HashableView,
+    W::Hasher: Hasher<Output = HasherOutput>,
+    I: Sync + Send + Serialize + DeserializeOwned,
+{
+    /// Extends a proof for the content of the entry with the given index into a proof for
+    /// the collection. The position of the entry is given by
+    /// [`ProofPosition::entry`](crate::proof::ProofPosition::entry).
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::reentrant_collection_view::ReentrantCollectionView;
+    /// # use linera_views::proof::ProofPosition;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view: ReentrantCollectionView<_, u64, RegisterView<_, String>> =
+    ///     ReentrantCollectionView::load(context).await.unwrap();
+    /// let mut subview = view.try_load_entry_mut(&23).await.unwrap();
+    /// subview.set(String::from("Hello"));
+    /// let subview_proof = subview.proof().unwrap();
+    /// drop(subview);
+    /// let proof = view.extend_proof(&23, subview_proof).await.unwrap();
+    /// let hash = view.hash().await.unwrap();
+    /// let position = ProofPosition::entry(&23u64).unwrap();
+    /// let value = proof.verify_value::<String>(&hash, &[position]).unwrap();
+    /// assert_eq!(value, "Hello");
+    /// # })
+    /// ```
+    pub async fn extend_proof<Q>(&self, index: &Q, proof: ViewProof) -> Result<ViewProof, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        let short_key = BaseKey::derive_short_key(index)?;
+        self.collection.extend_proof(&short_key, proof).await
+    }
+}
+
 impl<I, W> HashableView for ReentrantCollectionView<W::Context, I, W>
 where
     W: HashableView,
@@ -2251,6 +2340,24 @@ where
     }
 }
 
+impl<I, W> ReentrantCustomCollectionView<W::Context, I, W>
+where
+    W: HashableView,
+    W::Hasher: Hasher<Output = HasherOutput>,
+    I: Sync + Send + CustomSerialize,
+{
+    /// Extends a proof for the content of the entry with the given index into a proof for
+    /// the collection. The position of the entry is the custom serialization of the index.
+    pub async fn extend_proof<Q>(&self, index: &Q, proof: ViewProof) -> Result<ViewProof, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: CustomSerialize,
+    {
+        let short_key = index.to_custom_bytes()?;
+        self.collection.extend_proof(&short_key, proof).await
+    }
+}
+
 impl<I, W> HashableView for ReentrantCustomCollectionView<W::Context, I, W>
 where
     W: HashableView,
@@ -11,6 +11,7 @@ use crate::{
     common::{from_bytes_option_or_default, HasherOutput},
     context::Context,
     hashable_wrapper::WrappedHashableContainerView,
+    proof::{ProofLeaf, ViewProof},
     views::{ClonableView, HashableView, Hasher, ReplaceContext, View},
     ViewError,
 };
@@ -221,6 +222,25 @@ where
         self.update.get_or_insert_with(|| self.stored_value.clone())
     }
 
+    /// Creates a proof of the value in the register, for the hash of the register.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut register = RegisterView::load(context).await.unwrap();
+    /// register.set(42u32);
+    /// let proof = register.proof().unwrap();
+    /// let hash = register.hash().await.unwrap();
+    /// assert_eq!(proof.verify_value::<u32>(&hash, &[]).unwrap(), 42);
+    /// # })
+    /// ```
+    pub fn proof(&self) -> Result<ViewProof, ViewError> {
+        let value = bcs::to_bytes(self.get())?;
+        Ok(ViewProof::new(ProofLeaf::Value(value)))
+    }
+
     fn compute_hash(&self) -> Result<<sha3::Sha3_256 as Hasher>::Output, ViewError> {
         #[cfg(with_metrics)]
         let _hash_latency = metrics::REGISTER_VIEW_HASH_RUNTIME.measure_latency();
@@ -668,6 +668,22 @@ pub struct CustomSetView<C, I> {
     _phantom: PhantomData<I>,
 }
 
+impl<C: Context, I: Send + Sync + CustomSerialize, C2: Context> ReplaceContext<C2>
+    for CustomSetView<C, I>
+{
+    type Target = CustomSetView<C2, I>;
+
+    async fn with_context(
+        &mut self,
+        ctx: impl FnOnce(&Self::Context) -> C2 + Clone,
+    ) -> Self::Target {
+        CustomSetView {
+            set: self.set.with_context(ctx).await,
+            _phantom: self._phantom,
+        }
+    }
+}
+
 impl<C, I> View for CustomSetView<C, I>
 where
     C: Context,
@@ -0,0 +1,706 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A set of keys ordered by a score, such as the orders of a matching engine or the bids
+//! of an auction.
+//!
+//! A [`SortedSetView`] stores the score of each key, and an entry for each key ordered by
+//! `(score, key)`. Scores are ordered by their [`CustomSerialize`] serialization, which must
+//! preserve the order of the scores and must not be a prefix of the serialization of
+//! another score. Keys with equal scores are ordered by their BCS serialization.
+//!
+//! To answer rank queries without scanning the set, the view also counts the entries under
+//! every prefix of their `(score, key)` serialization, as in a trie with a branch per byte.
+//! The count of a prefix is stored under a fixed-size digest of the prefix without its
+//! last byte, followed by that byte, so the counts of the children of a prefix are
+//! contiguous. The digests are chained, byte after byte, so inserting or removing a key
+//! reads and writes one count of a constant size per byte of its serialization, and a rank
+//! query sums at most 255 counts per byte, whatever the size of the set.
+//!
+//! ```rust
+//! # tokio_test::block_on(async {
+//! # use linera_views::context::MemoryContext;
+//! # use linera_views::sorted_set_view::SortedSetView;
+//! # use linera_views::views::View;
+//! # let context = MemoryContext::new_for_testing(());
+//! let mut bids: SortedSetView<_, String, u128> = SortedSetView::load(context).await.unwrap();
+//! bids.insert(&"alice".to_string(), 30).await.unwrap();
+//! bids.insert(&"bob".to_string(), 10).await.unwrap();
+//! bids.insert(&"carol".to_string(), 20).await.unwrap();
+//! assert_eq!(bids.pop_max().await.unwrap(), Some(("alice".to_string(), 30)));
+//! assert_eq!(bids.rank(&"carol".to_string()).await.unwrap(), Some(1));
+//! # })
+//! ```
+
+use std::{
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+};
+
+use allocative::Allocative;
+use futures::future;
+use serde::{de::DeserializeOwned, Serialize};
+use sha3::Digest as _;
+
+use crate::{
+    batch::Batch,
+    common::{get_upper_bound_option, CustomSerialize, HasherOutput},
+    context::{BaseKey, Context},
+    hashable_wrapper::WrappedHashableContainerView,
+    historical_hash_wrapper::HistoricallyHashableView,
+    map_view::{ByteMapView, MapView},
+    store::{KeyBound, KeyRange},
+    views::{ClonableView, HashableView, Hasher, ReplaceContext, View, ViewError, MIN_VIEW_TAG},
+};
+
+/// Key tags to create the sub-keys of a `SortedSetView` on top of the base key.
+#[repr(u8)]
+enum KeyTag {
+    /// Prefix for the scores of the keys.
+    Score = MIN_VIEW_TAG,
+    /// Prefix for the keys ordered by score.
+    Order,
+    /// Prefix for the number of entries under each prefix of the ordered keys.
+    Count,
+}
+
+/// A set of keys of type `K` ordered by a score of type `S`.
+///
+/// Only the scores of the keys are part of the hash of the view: the order of the keys and
+/// their counts are derived from them.
+#[derive(Debug, Allocative)]
+#[allocative(bound = "C, K: Allocative, S: Allocative")]
+pub struct SortedSetView<C, K, S> {
+    /// The score of each key.
+    scores: MapView<C, K, S>,
+    /// The keys, under the serialized score followed by the serialized key.
+    order: ByteMapView<C, K>,
+    /// The number of keys of `order` starting with each prefix, under the digest of the
+    /// prefix without its last byte, followed by that byte.
+    counts: ByteMapView<C, usize>,
+    /// Phantom data for the score type.
+    #[allocative(skip)]
+    _phantom: PhantomData<S>,
+}
+
+impl<C, C2, K, S> ReplaceContext<C2> for SortedSetView<C, K, S>
+where
+    C: Context,
+    C2: Context,
+    K: Send + Sync + Serialize + Clone,
+    S: Send + Sync + Serialize + Clone,
+{
+    type Target = SortedSetView<C2, K, S>;
+
+    async fn with_context(
+        &mut self,
+        ctx: impl FnOnce(&Self::Context) -> C2 + Clone,
+    ) -> Self::Target {
+        SortedSetView {
+            scores: self.scores.with_context(ctx.clone()).await,
+            order: self.order.with_context(ctx.clone()).await,
+            counts: self.counts.with_context(ctx).await,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<C, K, S> View for SortedSetView<C, K, S>
+where
+    C: Context,
+    K: Send + Sync + Serialize,
+    S: Send + Sync + Serialize,
+{
+    const NUM_INIT_KEYS: usize = MapView::<C, K, S>::NUM_INIT_KEYS
+        + ByteMapView::<C, K>::NUM_INIT_KEYS
+        + ByteMapView::<C, usize>::NUM_INIT_KEYS;
+
+    type Context = C;
+
+    fn context(&self) -> C {
+        // The scores context has our base key + the KeyTag::Score byte
+        self.scores.context().clone_with_trimmed_key(1)
+    }
+
+    fn pre_load(context: &C) -> Result<Vec<Vec<u8>>, ViewError> {
+        let scores_context =
+            context.clone_with_base_key(context.base_key().base_tag(KeyTag::Score as u8));
+        let order_context =
+            context.clone_with_base_key(context.base_key().base_tag(KeyTag::Order as u8));
+        let counts_context =
+            context.clone_with_base_key(context.base_key().base_tag(KeyTag::Count as u8));
+        let mut keys = MapView::<C, K, S>::pre_load(&scores_context)?;
+        keys.extend(ByteMapView::<C, K>::pre_load(&order_context)?);
+        keys.extend(ByteMapView::<C, usize>::pre_load(&counts_context)?);
+        Ok(keys)
+    }
+
+    fn post_load(context: C, values: &[Option<Vec<u8>>]) -> Result<Self, ViewError> {
+        let scores_context =
+            context.clone_with_base_key(context.base_key().base_tag(KeyTag::Score as u8));
+        let order_context =
+            context.clone_with_base_key(context.base_key().base_tag(KeyTag::Order as u8));
+        let counts_context =
+            context.clone_with_base_key(context.base_key().base_tag(KeyTag::Count as u8));
+        let split1 = MapView::<C, K, S>::NUM_INIT_KEYS;
+        let split2 = split1 + ByteMapView::<C, K>::NUM_INIT_KEYS;
+        let scores = MapView::post_load(
+            scores_context,
+            values.get(..split1).ok_or(ViewError::PostLoadValuesError)?,
+        )?;
+        let order = ByteMapView::post_load(
+            order_context,
+            values
+                .get(split1..split2)
+                .ok_or(ViewError::PostLoadValuesError)?,
+        )?;
+        let counts = ByteMapView::post_load(
+            counts_context,
+            values.get(split2..).ok_or(ViewError::PostLoadValuesError)?,
+        )?;
+        Ok(SortedSetView {
+            scores,
+            order,
+            counts,
+            _phantom: PhantomData,
+        })
+    }
+
+    fn rollback(&mut self) {
+        self.scores.rollback();
+        self.order.rollback();
+        self.counts.rollback();
+    }
+
+    async fn has_pending_changes(&self) -> bool {
+        self.scores.has_pending_changes().await
+            || self.order.has_pending_changes().await
+            || self.counts.has_pending_changes().await
+    }
+
+    fn pre_save(&self, batch: &mut Batch) -> Result<bool, ViewError> {
+        let delete_scores = self.scores.pre_save(batch)?;
+        let delete_order = self.order.pre_save(batch)?;
+        let delete_counts = self.counts.pre_save(batch)?;
+        Ok(delete_scores && delete_order && delete_counts)
+    }
+
+    fn post_save(&mut self) {
+        self.scores.post_save();
+        self.order.post_save();
+        self.counts.post_save();
+    }
+
+    fn clear(&mut self) {
+        self.scores.clear();
+        self.order.clear();
+        self.counts.clear();
+    }
+}
+
+impl<C, K, S> ClonableView for SortedSetView<C, K, S>
+where
+    Self: View,
+    MapView<C, K, S>: ClonableView,
+    ByteMapView<C, K>: ClonableView,
+    ByteMapView<C, usize>: ClonableView,
+{
+    fn clone_unchecked(&mut self) -> Result<Self, ViewError> {
+        Ok(SortedSetView {
+            scores: self.scores.clone_unchecked()?,
+            order: self.order.clone_unchecked()?,
+            counts: self.counts.clone_unchecked()?,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<C, K, S> SortedSetView<C, K, S>
+where
+    C: Context,
+    K: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+    S: Clone + Send + Sync + Serialize + DeserializeOwned + CustomSerialize + 'static,
+{
+    /// Inserts a key with the given score, or updates the score of the key if it is
+    /// already present.
+    pub async fn insert(&mut self, key: &K, score: S) -> Result<(), ViewError> {
+        let order_key = Self::order_key(key, &score)?;
+        if let Some(old_score) = self.scores.get(key).await? {
+            let old_order_key = Self::order_key(key, &old_score)?;
+            if old_order_key == order_key {
+                return Ok(());
+            }
+            self.order.remove(old_order_key.clone());
+            self.update_counts(&old_order_key, false).await?;
+        }
+        self.update_counts(&order_key, true).await?;
+        self.order.insert(order_key, key.clone());
+        self.scores.insert(key, score)
+    }
+
+    /// Removes a key, returning its score if it was present.
+    pub async fn remove(&mut self, key: &K) -> Result<Option<S>, ViewError> {
+        let Some(score) = self.scores.get(key).await? else {
+            return Ok(None);
+        };
+        self.remove_entry(key, &score).await?;
+        Ok(Some(score))
+    }
+
+    /// Returns the score of a key, if it is present.
+    pub async fn score(&self, key: &K) -> Result<Option<S>, ViewError> {
+        self.scores.get(key).await
+    }
+
+    /// Returns `true` if the set contains the key.
+    pub async fn contains(&self, key: &K) -> Result<bool, ViewError> {
+        self.scores.contains_key(key).await
+    }
+
+    /// Returns the key with the lowest score, if any, together with its score.
+    pub async fn min(&self) -> Result<Option<(K, S)>, ViewError> {
+        Ok(self.range(.., false, Some(1)).await?.pop())
+    }
+
+    /// Returns the key with the highest score, if any, together with its score.
+    pub async fn max(&self) -> Result<Option<(K, S)>, ViewError> {
+        Ok(self.range(.., true, Some(1)).await?.pop())
+    }
+
+    /// Removes the key with the lowest score, if any, and returns it with its score.
+    pub async fn pop_min(&mut self) -> Result<Option<(K, S)>, ViewError> {
+        let entry = self.min().await?;
+        if let Some((key, score)) = &entry {
+            self.remove_entry(key, score).await?;
+        }
+        Ok(entry)
+    }
+
+    /// Removes the key with the highest score, if any, and returns it with its score.
+    pub async fn pop_max(&mut self) -> Result<Option<(K, S)>, ViewError> {
+        let entry = self.max().await?;
+        if let Some((key, score)) = &entry {
+            self.remove_entry(key, score).await?;
+        }
+        Ok(entry)
+    }
+
+    /// Returns the keys whose score is within `range`, with their scores, by increasing
+    /// score, or by decreasing score if `reverse` is set. At most `limit` keys are
+    /// returned.
+    pub async fn range(
+        &self,
+        range: impl RangeBounds<S>,
+        reverse: bool,
+        limit: Option<u32>,
+    ) -> Result<Vec<(K, S)>, ViewError> {
+        let start = match range.start_bound() {
+            Bound::Unbounded => KeyBound::Unbounded,
+            Bound::Included(score) => KeyBound::Included(score.to_custom_bytes()?),
+            Bound::Excluded(score) => {
+                // All the keys with this score come before the upper bound of the prefix.
+                match get_upper_bound_option(&score.to_custom_bytes()?) {
+                    None => return Ok(Vec::new()),
+                    Some(upper_bound) => KeyBound::Included(upper_bound),
+                }
+            }
+        };
+        let end = match range.end_bound() {
+            Bound::Unbounded => KeyBound::Unbounded,
+            Bound::Included(score) => match get_upper_bound_option(&score.to_custom_bytes()?) {
+                None => KeyBound::Unbounded,
+                Some(upper_bound) => KeyBound::Excluded(upper_bound),
+            },
+            Bound::Excluded(score) => KeyBound::Excluded(score.to_custom_bytes()?),
+        };
+        let range = KeyRange {
+            start,
+            end,
+            reverse,
+            limit,
+        };
+        self.order
+            .key_values_in_range(&range)
+            .await?
+            .into_iter()
+            .map(|(order_key, key)| {
+                let key_size = bcs::serialized_size(&key)?;
+                let score_bytes = order_key
+                    .get(..order_key.len().saturating_sub(key_size))
+                    .ok_or(ViewError::InconsistentEntries)?;
+                Ok((key, S::from_custom_bytes(score_bytes)?))
+            })
+            .collect()
+    }
+
+    /// Returns the number of keys that come before `key` in the order, or `None` if the
+    /// key is not present.
+    ///
+    /// For each byte of the serialized score and key, this reads the counts of the
+    /// prefixes that share the previous bytes and end with a smaller byte, so the cost
+    /// does not depend on the size of the set.
+    pub async fn rank(&self, key: &K) -> Result<Option<usize>, ViewError> {
+        let Some(score) = self.scores.get(key).await? else {
+            return Ok(None);
+        };
+        let order_key = Self::order_key(key, &score)?;
+        // The serializations are prefix-free, so the keys before `key` are exactly those
+        // that differ from it at some byte, where theirs is smaller.
+        let ranges = Self::prefix_digests(&order_key)
+            .iter()
+            .zip(&order_key)
+            .map(|(digest, byte)| {
+                KeyRange::new(
+                    KeyBound::Included(Self::count_key(digest, 0)),
+                    KeyBound::Excluded(Self::count_key(digest, *byte)),
+                )
+            })
+            .collect::<Vec<_>>();
+        let counts = future::try_join_all(
+            ranges
+                .iter()
+                .map(|range| self.counts.key_values_in_range(range)),
+        )
+        .await?;
+        let rank = counts.into_iter().flatten().map(|(_, count)| count).sum();
+        Ok(Some(rank))
+    }
+
+    /// Returns all the keys with their scores, by increasing score.
+    pub async fn entries(&self) -> Result<Vec<(K, S)>, ViewError> {
+        self.range(.., false, None).await
+    }
+
+    /// Returns the number of keys in the set.
+    pub async fn count(&self) -> Result<usize, ViewError> {
+        let count = self.counts.get(&Self::root_count_key()).await?;
+        Ok(count.unwrap_or_default())
+    }
+
+    /// Obtains the extra data.
+    pub fn extra(&self) -> &C::Extra {
+        self.scores.extra()
+    }
+
+    /// Removes a key that is present with the given score.
+    async fn remove_entry(&mut self, key: &K, score: &S) -> Result<(), ViewError> {
+        let order_key = Self::order_key(key, score)?;
+        self.update_counts(&order_key, false).await?;
+        self.order.remove(order_key);
+        self.scores.remove(key)
+    }
+
+    /// Increments or decrements the counts of all the prefixes of `order_key`.
+    async fn update_counts(&mut self, order_key: &[u8], increment: bool) -> Result<(), ViewError> {
+        let mut count_keys = vec![Self::root_count_key()];
+        count_keys.extend(
+            Self::prefix_digests(order_key)
+                .iter()
+                .zip(order_key)
+                .map(|(digest, byte)| Self::count_key(digest, *byte)),
+        );
+        let counts = self.counts.multi_get(count_keys.clone()).await?;
+        for (count_key, count) in count_keys.into_iter().zip(counts) {
+            let count = count.unwrap_or_default();
+            if increment {
+                self.counts.insert(count_key, count + 1);
+            } else if count > 1 {
+                self.counts.insert(count_key, count - 1);
+            } else {
+                self.counts.remove(count_key);
+            }
+        }
+        Ok(())
+    }
+
+    fn order_key(key: &K, score: &S) -> Result<Vec<u8>, ViewError> {
+        let mut order_key = score.to_custom_bytes()?;
+        order_key.extend(BaseKey::derive_short_key(key)?);
+        Ok(order_key)
+    }
+
+    /// The digests of the prefixes of `order_key`, from the empty prefix to the one
+    /// without the last byte. Each digest hashes the previous one with the next byte, so
+    /// the cost is linear in the length of `order_key`.
+    fn prefix_digests(order_key: &[u8]) -> Vec<HasherOutput> {
+        let mut digests = Vec::with_capacity(order_key.len());
+        let mut digest = HasherOutput::default();
+        for byte in order_key {
+            let hasher = sha3::Sha3_256::new()
+                .chain_update(digest)
+                .chain_update([*byte]);
+            let next = Hasher::finalize(hasher);
+            digests.push(std::mem::replace(&mut digest, next));
+        }
+        digests
+    }
+
+    /// The key of the count of the entries starting with a prefix, given the digest of the
+    /// prefix without its last byte, and that byte.
+    fn count_key(digest: &HasherOutput, byte: u8) -> Vec<u8> {
+        let mut count_key = digest.to_vec();
+        count_key.push(byte);
+        count_key
+    }
+
+    /// The key of the number of entries in the set. It is shorter than the other keys of
+    /// the counts, so it is outside of the ranges of children of any prefix.
+    fn root_count_key() -> Vec<u8> {
+        HasherOutput::default().to_vec()
+    }
+}
+
+impl<C, K, S> HashableView for SortedSetView<C, K, S>
+where
+    Self: View,
+    MapView<C, K, S>: HashableView,
+{
+    type Hasher = <MapView<C, K, S> as HashableView>::Hasher;
+
+    async fn hash_mut(&mut self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
+        self.scores.hash_mut().await
+    }
+
+    async fn hash(&self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
+        self.scores.hash().await
+    }
+}
+
+/// Type wrapping `SortedSetView` while memoizing the hash.
+pub type HashedSortedSetView<C, K, S> =
+    WrappedHashableContainerView<C, SortedSetView<C, K, S>, HasherOutput>;
+
+/// Wrapper around `SortedSetView` to compute hashes based on the history of changes.
+pub type HistoricallyHashedSortedSetView<C, K, S> =
+    HistoricallyHashableView<C, SortedSetView<C, K, S>>;
+
+#[cfg(with_graphql)]
+mod graphql {
+    use std::borrow::Cow;
+
+    use super::SortedSetView;
+    use crate::{
+        common::CustomSerialize,
+        context::Context,
+        graphql::{hash_name, mangle, Entry},
+    };
+
+    impl<C: Send + Sync, K: async_graphql::OutputType, S: async_graphql::OutputType>
+        async_graphql::TypeName for SortedSetView<C, K, S>
+    {
+        fn type_name() -> Cow<'static, str> {
+            format!(
+                "SortedSetView_{}_{}_{:08x}",
+                mangle(K::type_name()),
+                mangle(S::type_name()),
+                hash_name::<(K, S)>(),
+            )
+            .into()
+        }
+    }
+
+    #[async_graphql::Object(cache_control(no_cache), name_type)]
+    impl<C, K, S> SortedSetView<C, K, S>
+    where
+        C: Context,
+        K: async_graphql::OutputType
+            + async_graphql::InputType
+            + serde::ser::Serialize
+            + serde::de::DeserializeOwned
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+        S: async_graphql::OutputType
+            + async_graphql::InputType
+            + serde::ser::Serialize
+            + serde::de::DeserializeOwned
+            + CustomSerialize
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+    {
+        #[graphql(derived(name = "count"))]
+        async fn count_(&self) -> Result<u32, async_graphql::Error> {
+            let count = self.count().await?;
+            u32::try_from(count).map_err(|_| async_graphql::Error::new("count exceeds u32"))
+        }
+
+        #[graphql(derived(name = "score"))]
+        async fn score_(&self, key: K) -> Result<Option<S>, async_graphql::Error> {
+            Ok(self.score(&key).await?)
+        }
+
+        #[graphql(derived(name = "rank"))]
+        async fn rank_(&self, key: K) -> Result<Option<u32>, async_graphql::Error> {
+            let Some(rank) = self.rank(&key).await? else {
+                return Ok(None);
+            };
+            let rank =
+                u32::try_from(rank).map_err(|_| async_graphql::Error::new("rank exceeds u32"))?;
+            Ok(Some(rank))
+        }
+
+        /// Returns the keys with a score between `min` and `max`, both included, with
+        /// their scores.
+        #[graphql(derived(name = "entries"))]
+        async fn entries_(
+            &self,
+            min: Option<S>,
+            max: Option<S>,
+            reverse: Option<bool>,
+            limit: Option<u32>,
+        ) -> Result<Vec<Entry<K, S>>, async_graphql::Error> {
+            use std::ops::Bound;
+
+            let start = min.map_or(Bound::Unbounded, Bound::Included);
+            let end = max.map_or(Bound::Unbounded, Bound::Included);
+            let entries = self
+                .range((start, end), reverse.unwrap_or(false), limit)
+                .await?;
+            Ok(entries
+                .into_iter()
+                .map(|(key, value)| Entry { key, value })
+                .collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{context::MemoryContext, store::WritableKeyValueStore as _};
+
+    type Bids = SortedSetView<MemoryContext<()>, String, u64>;
+
+    async fn save(view: &mut Bids) -> Result<(), ViewError> {
+        let mut batch = Batch::new();
+        view.pre_save(&mut batch)?;
+        view.context().store().write_batch(batch).await?;
+        view.post_save();
+        Ok(())
+    }
+
+    fn entries(entries: &[(&str, u64)]) -> Vec<(String, u64)> {
+        entries
+            .iter()
+            .map(|(key, score)| (key.to_string(), *score))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_sorted_set_view_order() -> Result<(), ViewError> {
+        let context = MemoryContext::new_for_testing(());
+        let mut view = Bids::load(context).await?;
+        // Scores above 255 check that the order is numeric, unlike the BCS order.
+        for (key, score) in [("a", 300), ("b", 2), ("c", 300), ("d", 1000), ("e", 40)] {
+            view.insert(&key.to_string(), score).await?;
+        }
+        save(&mut view).await?;
+        view.insert(&"b".to_string(), 500).await?;
+        assert_eq!(
+            view.entries().await?,
+            entries(&[("e", 40), ("a", 300), ("c", 300), ("b", 500), ("d", 1000)])
+        );
+        assert_eq!(
+            view.range(40..=300, true, None).await?,
+            entries(&[("c", 300), ("a", 300), ("e", 40)])
+        );
+        assert_eq!(
+            view.range((Bound::Excluded(40), Bound::Unbounded), false, Some(2))
+                .await?,
+            entries(&[("a", 300), ("c", 300)])
+        );
+        assert_eq!(view.rank(&"b".to_string()).await?, Some(3));
+        assert_eq!(view.rank(&"z".to_string()).await?, None);
+
+        assert_eq!(view.pop_min().await?, Some(("e".to_string(), 40)));
+        assert_eq!(view.pop_max().await?, Some(("d".to_string(), 1000)));
+        assert_eq!(view.remove(&"a".to_string()).await?, Some(300));
+        assert_eq!(view.remove(&"a".to_string()).await?, None);
+        save(&mut view).await?;
+
+        let mut view = Bids::load(view.context()).await?;
+        assert_eq!(view.count().await?, 2);
+        assert_eq!(view.score(&"b".to_string()).await?, Some(500));
+        assert_eq!(view.entries().await?, entries(&[("c", 300), ("b", 500)]));
+        view.clear();
+        assert_eq!(view.min().await?, None);
+        view.rollback();
+        assert_eq!(view.min().await?, Some(("c".to_string(), 300)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sorted_set_view_hash_ignores_order_entries() -> Result<(), ViewError> {
+        let context = MemoryContext::new_for_testing(());
+        let mut view = Bids::load(context.clone()).await?;
+        let mut map = MapView::<_, String, u64>::load(context).await?;
+        for (key, score) in [("a", 3), ("b", 2)] {
+            view.insert(&key.to_string(), score).await?;
+            map.insert(key, score)?;
+        }
+        assert_eq!(view.hash().await?, map.hash().await?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sorted_set_view_counts_grow_linearly() -> Result<(), ViewError> {
+        use crate::store::ReadableKeyValueStore as _;
+
+        let context = MemoryContext::new_for_testing(());
+        let mut view = Bids::load(context.clone()).await?;
+        let key = "k".repeat(1000);
+        view.insert(&key, 7).await?;
+        save(&mut view).await?;
+        let order_key = Bids::order_key(&key, &7)?;
+        let counts = context
+            .store()
+            .find_key_values_by_prefix(&[KeyTag::Count as u8])
+            .await?;
+        // One count per byte, plus the number of entries, each under a key of a
+        // constant size.
+        assert_eq!(counts.len(), order_key.len() + 1);
+        assert!(counts
+            .iter()
+            .all(|(count_key, _)| count_key.len() <= HasherOutput::default().len() + 2));
+        assert_eq!(view.rank(&key).await?, Some(0));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sorted_set_view_rank_and_count() -> Result<(), ViewError> {
+        use rand::Rng as _;
+
+        let mut rng = crate::random::make_deterministic_rng();
+        let context = MemoryContext::new_for_testing(());
+        let mut view = Bids::load(context).await?;
+        let mut model = std::collections::BTreeMap::new();
+        for step in 0..500 {
+            // Keys of equal lengths, so that their BCS order is their string order.
+            let key = format!("k{:02}", rng.gen_range(0..50));
+            // Few distinct scores, so that many keys are ordered by their tie-breaker.
+            let score = rng.gen_range(0..20) * 100;
+            if rng.gen_range(0..4) == 0 {
+                assert_eq!(view.remove(&key).await?, model.remove(&key));
+            } else {
+                view.insert(&key, score).await?;
+                model.insert(key, score);
+            }
+            if step % 50 == 0 {
+                save(&mut view).await?;
+            }
+            let mut expected = model
+                .iter()
+                .map(|(key, score)| (*score, key.clone()))
+                .collect::<Vec<_>>();
+            expected.sort();
+            assert_eq!(view.count().await?, expected.len());
+            for (rank, (_, key)) in expected.iter().enumerate() {
+                assert_eq!(view.rank(key).await?, Some(rank));
+            }
+        }
+        Ok(())
+    }
+}
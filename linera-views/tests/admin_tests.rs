@@ -3,6 +3,12 @@
 
 use std::marker::PhantomData;
 
+#[cfg(with_compression)]
+use linera_views::compression::CompressionDatabase;
+#[cfg(with_encryption)]
+use linera_views::encryption::EncryptionDatabase;
+#[cfg(with_redb)]
+use linera_views::redb::RedbDatabase;
 #[cfg(with_rocksdb)]
 use linera_views::rocks_db::RocksDbDatabase;
 #[cfg(with_scylladb)]
@@ -16,6 +22,9 @@ use test_case::test_case;
 
 #[test_case(PhantomData::<MemoryDatabase>; "MemoryDatabase")]
 #[cfg_attr(with_rocksdb, test_case(PhantomData::<RocksDbDatabase>; "RocksDbDatabase"))]
+#[cfg_attr(with_redb, test_case(PhantomData::<RedbDatabase>; "RedbDatabase"))]
+#[cfg_attr(with_encryption, test_case(PhantomData::<EncryptionDatabase<MemoryDatabase>>; "EncryptionDatabase"))]
+#[cfg_attr(with_compression, test_case(PhantomData::<CompressionDatabase<MemoryDatabase>>; "CompressionDatabase"))]
 #[cfg_attr(with_scylladb, test_case(PhantomData::<ScyllaDbDatabase>; "ScyllaDbDatabase"))]
 #[tokio::test]
 async fn namespace_admin_test_cases<K: TestKeyValueDatabase>(_view_type: PhantomData<K>)
@@ -27,6 +36,9 @@ where
 
 #[test_case(PhantomData::<MemoryDatabase>; "MemoryDatabase")]
 #[cfg_attr(with_rocksdb, test_case(PhantomData::<RocksDbDatabase>; "RocksDbDatabase"))]
+#[cfg_attr(with_redb, test_case(PhantomData::<RedbDatabase>; "RedbDatabase"))]
+#[cfg_attr(with_encryption, test_case(PhantomData::<EncryptionDatabase<MemoryDatabase>>; "EncryptionDatabase"))]
+#[cfg_attr(with_compression, test_case(PhantomData::<CompressionDatabase<MemoryDatabase>>; "CompressionDatabase"))]
 #[cfg_attr(with_scylladb, test_case(PhantomData::<ScyllaDbDatabase>; "ScyllaDbDatabase"))]
 #[tokio::test]
 async fn root_key_admin_test_cases<K: TestKeyValueDatabase>(_view_type: PhantomData<K>)
@@ -16,6 +16,8 @@ use linera_views::{
     random::make_deterministic_rng,
     reentrant_collection_view::{HashedReentrantCollectionView, ReentrantCollectionView},
     register_view::RegisterView,
+    store::KeyRange,
+    test_utils::get_random_key_range,
     views::{CryptoHashRootView, CryptoHashView, HashableView as _, RootView, View},
 };
 use rand::{distributions::Uniform, Rng, RngCore};
@@ -142,10 +144,26 @@ fn remove_by_prefix<V>(map: &mut BTreeMap<Vec<u8>, V>, key_prefix: &[u8]) {
     map.retain(|key, _| !key.starts_with(key_prefix));
 }
 
+fn select_in_range<V: Clone>(map: &BTreeMap<Vec<u8>, V>, range: &KeyRange) -> Vec<(Vec<u8>, V)> {
+    let mut entries = map
+        .iter()
+        .filter(|(key, _)| range.contains(key))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect::<Vec<_>>();
+    if range.reverse {
+        entries.reverse();
+    }
+    if let Some(limit) = range.limit {
+        entries.truncate(limit as usize);
+    }
+    entries
+}
+
 #[tokio::test]
 async fn key_value_store_view_mutability() -> Result<()> {
     let context = MemoryContext::new_for_testing(());
     let mut rng = make_deterministic_rng();
+    let mut range_rng = make_deterministic_rng();
     let mut state_map = BTreeMap::new();
     let n = 40;
     let mut all_keys = BTreeSet::new();
@@ -213,6 +231,9 @@ async fn key_value_store_view_mutability() -> Result<()> {
             let new_key_values = view.store.index_values().await?;
             assert_eq!(new_state_vec, new_key_values);
             let all_keys_vec = all_keys.clone().into_iter().collect::<Vec<_>>();
+            let range = get_random_key_range(&mut range_rng, &all_keys_vec);
+            let key_values_in_range = view.store.find_key_values_in_range(&[], &range).await?;
+            assert_eq!(select_in_range(&new_state_map, &range), key_values_in_range);
             let tests_multi_get = view.store.multi_get(&all_keys_vec).await?;
             for (i, key) in all_keys.clone().into_iter().enumerate() {
                 let test_map = new_state_map.contains_key(&key);
@@ -241,6 +262,7 @@ pub struct ByteMapStateView<C> {
 
 async fn run_map_view_mutability<R: RngCore + Clone>(rng: &mut R) -> Result<()> {
     let context = MemoryContext::new_for_testing(());
+    let mut range_rng = make_deterministic_rng();
     let mut state_map = BTreeMap::new();
     let mut all_keys = BTreeSet::new();
     let n = 10;
@@ -356,6 +378,12 @@ async fn run_map_view_mutability<R: RngCore + Clone>(rng: &mut R) -> Result<()>
                 assert_eq!(part_state_vec, part_key_values);
             }
             let keys_vec = all_keys.iter().cloned().collect::<Vec<_>>();
+            let range = get_random_key_range(&mut range_rng, &keys_vec);
+            let key_values_in_range = view.map.key_values_in_range(&range).await?;
+            assert_eq!(select_in_range(&new_state_map, &range), key_values_in_range);
+            let keys_in_range = view.map.keys_in_range(&range).await?;
+            let expected_keys = key_values_in_range.into_iter().map(|(key, _)| key);
+            assert!(keys_in_range.into_iter().eq(expected_keys));
             let values = view.map.multi_get(keys_vec.clone()).await?;
             for i in 0..keys_vec.len() {
                 let key = &keys_vec[i];
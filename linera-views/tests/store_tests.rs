@@ -66,6 +66,41 @@ async fn test_reads_rocks_db() {
     }
 }
 
+#[cfg(with_redb)]
+#[tokio::test]
+async fn test_reads_redb() {
+    for scenario in get_random_test_scenarios() {
+        let store = linera_views::redb::RedbDatabase::new_test_store()
+            .await
+            .unwrap();
+        run_reads(store, scenario).await;
+    }
+}
+
+#[cfg(with_encryption)]
+#[tokio::test]
+async fn test_reads_encryption() {
+    for scenario in get_random_test_scenarios() {
+        let store =
+            linera_views::encryption::EncryptionDatabase::<MemoryDatabase>::new_test_store()
+                .await
+                .unwrap();
+        run_reads(store, scenario).await;
+    }
+}
+
+#[cfg(with_compression)]
+#[tokio::test]
+async fn test_reads_compression() {
+    for scenario in get_random_test_scenarios() {
+        let store =
+            linera_views::compression::CompressionDatabase::<MemoryDatabase>::new_test_store()
+                .await
+                .unwrap();
+        run_reads(store, scenario).await;
+    }
+}
+
 #[cfg(with_scylladb)]
 #[tokio::test]
 async fn test_reads_scylla_db() {
@@ -149,6 +184,33 @@ async fn test_rocks_db_writes_from_blank() {
     run_writes_from_blank(&store).await;
 }
 
+#[cfg(with_redb)]
+#[tokio::test]
+async fn test_redb_writes_from_blank() {
+    let store = linera_views::redb::RedbDatabase::new_test_store()
+        .await
+        .unwrap();
+    run_writes_from_blank(&store).await;
+}
+
+#[cfg(with_encryption)]
+#[tokio::test]
+async fn test_encryption_writes_from_blank() {
+    let store = linera_views::encryption::EncryptionDatabase::<MemoryDatabase>::new_test_store()
+        .await
+        .unwrap();
+    run_writes_from_blank(&store).await;
+}
+
+#[cfg(with_compression)]
+#[tokio::test]
+async fn test_compression_writes_from_blank() {
+    let store = linera_views::compression::CompressionDatabase::<MemoryDatabase>::new_test_store()
+        .await
+        .unwrap();
+    run_writes_from_blank(&store).await;
+}
+
 #[cfg(with_scylladb)]
 #[tokio::test]
 async fn test_scylla_db_writes_from_blank() {
@@ -211,6 +273,15 @@ async fn rocks_db_tombstone_triggering_test() {
     linera_views::test_utils::tombstone_triggering_test(store).await;
 }
 
+#[cfg(with_redb)]
+#[tokio::test]
+async fn redb_tombstone_triggering_test() {
+    let store = linera_views::redb::RedbDatabase::new_test_store()
+        .await
+        .unwrap();
+    linera_views::test_utils::tombstone_triggering_test(store).await;
+}
+
 #[cfg(with_scylladb)]
 #[tokio::test]
 async fn test_scylla_db_big_write_read() {
@@ -244,6 +315,28 @@ async fn test_rocks_db_big_write_read() {
     run_big_write_read(store, target_size, value_sizes).await;
 }
 
+#[cfg(with_redb)]
+#[tokio::test]
+async fn test_redb_big_write_read() {
+    let store = linera_views::redb::RedbDatabase::new_test_store()
+        .await
+        .unwrap();
+    let value_sizes = vec![100, 1000, 200000, 5000000];
+    let target_size = 20000000;
+    run_big_write_read(store, target_size, value_sizes).await;
+}
+
+#[cfg(with_compression)]
+#[tokio::test]
+async fn test_compression_big_write_read() {
+    let store = linera_views::compression::CompressionDatabase::<MemoryDatabase>::new_test_store()
+        .await
+        .unwrap();
+    let value_sizes = vec![100, 1000, 200000, 5000000];
+    let target_size = 20000000;
+    run_big_write_read(store, target_size, value_sizes).await;
+}
+
 #[cfg(with_indexeddb)]
 #[wasm_bindgen_test]
 async fn test_indexed_db_big_write_read() {
@@ -268,6 +361,33 @@ async fn test_rocks_db_writes_from_state() {
     run_writes_from_state(&store).await;
 }
 
+#[cfg(with_redb)]
+#[tokio::test]
+async fn test_redb_writes_from_state() {
+    let store = linera_views::redb::RedbDatabase::new_test_store()
+        .await
+        .unwrap();
+    run_writes_from_state(&store).await;
+}
+
+#[cfg(with_encryption)]
+#[tokio::test]
+async fn test_encryption_writes_from_state() {
+    let store = linera_views::encryption::EncryptionDatabase::<MemoryDatabase>::new_test_store()
+        .await
+        .unwrap();
+    run_writes_from_state(&store).await;
+}
+
+#[cfg(with_compression)]
+#[tokio::test]
+async fn test_compression_writes_from_state() {
+    let store = linera_views::compression::CompressionDatabase::<MemoryDatabase>::new_test_store()
+        .await
+        .unwrap();
+    run_writes_from_state(&store).await;
+}
+
 #[cfg(with_indexeddb)]
 #[wasm_bindgen_test]
 async fn test_indexed_db_writes_from_state() {
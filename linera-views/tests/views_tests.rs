@@ -17,11 +17,13 @@ use linera_views::{
     },
     collection_view::HashedCollectionView,
     context::{Context, MemoryContext, ViewContext},
+    indexed_map_view::{IndexedMapView, MapIndex},
     key_value_store_view::{KeyValueStoreView, ViewContainer},
-    log_view::HashedLogView,
+    log_view::{HashedLogView, LogView},
     lru_caching::LruCachingMemoryDatabase,
-    map_view::{ByteMapView, HashedMapView},
+    map_view::{ByteMapView, HashedMapView, MapView},
     memory::MemoryDatabase,
+    proof::{ProofError, ProofLeaf, ProofPosition, ViewProof},
     queue_view::HashedQueueView,
     random::make_deterministic_rng,
     reentrant_collection_view::HashedReentrantCollectionView,
@@ -618,6 +620,91 @@ async fn test_byte_map_view() -> Result<()> {
     Ok(())
 }
 
+pub struct ByParity;
+
+impl MapIndex<u8, String> for ByParity {
+    type Key = bool;
+
+    fn keys(index: &u8, _value: &String) -> Vec<bool> {
+        vec![index.is_multiple_of(2)]
+    }
+}
+
+pub struct ByWord;
+
+impl MapIndex<u8, String> for ByWord {
+    type Key = String;
+
+    fn keys(_index: &u8, value: &String) -> Vec<String> {
+        value.split(' ').map(str::to_string).collect()
+    }
+}
+
+#[derive(CryptoHashRootView)]
+pub struct IndexedMapStateView<C> {
+    pub map: IndexedMapView<C, u8, String, (ByParity, ByWord)>,
+}
+
+#[tokio::test]
+async fn test_indexed_map_view() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let hash = {
+        let mut view = IndexedMapStateView::load(context.clone()).await?;
+        view.map.insert(&1, "hello world".to_string()).await?;
+        view.map.insert(&2, "hello there".to_string()).await?;
+        view.map.insert(&3, "goodbye world".to_string()).await?;
+        view.save().await?;
+        view.hash().await?
+    };
+    {
+        let mut view = IndexedMapStateView::load(context.clone()).await?;
+        assert_eq!(view.hash().await?, hash);
+        let hello = "hello".to_string();
+        assert_eq!(
+            view.map.indices_by::<ByWord>(&hello, None, None).await?,
+            [1, 2]
+        );
+        assert_eq!(
+            view.map.indices_by::<ByParity>(&false, None, None).await?,
+            [1, 3]
+        );
+        assert_eq!(
+            view.map
+                .indices_by::<ByParity>(&false, Some(&1), Some(1))
+                .await?,
+            [3]
+        );
+        view.map.insert(&2, "bye there".to_string()).await?;
+        view.map.remove(&3).await?;
+        assert_eq!(
+            view.map.indices_by::<ByWord>(&hello, None, None).await?,
+            [1]
+        );
+        view.rollback();
+        assert_eq!(
+            view.map.indices_by::<ByWord>(&hello, None, None).await?,
+            [1, 2]
+        );
+        view.map.remove(&1).await?;
+        view.save().await?;
+    }
+    {
+        let view = IndexedMapStateView::load(context.clone()).await?;
+        let world = "world".to_string();
+        assert_eq!(
+            view.map.indices_by::<ByWord>(&world, None, None).await?,
+            [3]
+        );
+        assert_eq!(
+            view.map
+                .index_values_by::<ByParity>(&false, None, None)
+                .await?,
+            [(3, "goodbye world".to_string())]
+        );
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 async fn test_views_in_lru_memory_param(config: &TestConfig) -> Result<()> {
     tracing::warn!("Testing config {:?} with lru memory", config);
@@ -1155,3 +1242,164 @@ async fn check_large_write_memory() -> Result<()> {
     let mut store = MemoryTestStorage::new().await;
     check_large_write(&mut store, vector).await
 }
+
+#[tokio::test]
+async fn test_view_proofs() -> Result<()> {
+    let mut store = MemoryTestStorage::new().await;
+    {
+        let mut view = store.load(1).await?;
+        view.x1.set(37);
+        view.x2.set(42);
+        view.log.push(4);
+        view.log.push(8);
+        view.map.insert("Hello", 5)?;
+        view.map.insert("World", 6)?;
+        view.queue.push_back(15);
+        view.queue.push_back(16);
+        view.queue.delete_front();
+        view.collection.load_entry_mut("a").await?.push(23);
+        for (index1, index2, value) in [("a", "b", 1), ("a", "c", 2), ("d", "e", 3)] {
+            view.collection2
+                .load_entry_mut(index1)
+                .await?
+                .load_entry_mut(index2)
+                .await?
+                .set(value);
+        }
+        view.key_value_store.insert(vec![0, 1], vec![42]).await?;
+        view.save().await?;
+    }
+    let view = store.load(1).await?;
+    let hash = view.hash().await?;
+    let field = |name| ProofPosition::field::<StateView<MemoryContext<usize>>>(name).unwrap();
+
+    let proof = view.x2.proof()?.in_field(&view, "x2").await?;
+    assert_eq!(proof.verify_value::<u32>(&hash, &[field("x2")])?, 42);
+
+    let proof = view.map.proof().await?.in_field(&view, "map").await?;
+    let entries = proof.verify_entries::<String, usize>(&hash, &[field("map")])?;
+    assert_eq!(
+        entries,
+        [("Hello".to_string(), 5), ("World".to_string(), 6)]
+    );
+
+    let proof = view.log.proof().await?.in_field(&view, "log").await?;
+    assert_eq!(
+        proof.verify_elements::<u32>(&hash, &[field("log")])?,
+        [4, 8]
+    );
+
+    let proof = view.queue.proof().await?.in_field(&view, "queue").await?;
+    assert_eq!(
+        proof.verify_elements::<u64>(&hash, &[field("queue")])?,
+        [16]
+    );
+
+    // The proven content must have the expected type.
+    assert!(matches!(
+        proof.verify_entries::<u64, u64>(&hash, &[field("queue")]),
+        Err(ProofError::WrongLeaf(_))
+    ));
+    assert!(matches!(
+        proof.verify_elements::<u128>(&hash, &[field("queue")]),
+        Err(ProofError::BcsError(_))
+    ));
+
+    let inner = view.collection2.try_load_entry("a").await?.unwrap();
+    let register_proof = inner.try_load_entry("c").await?.unwrap().proof()?;
+    let inner_proof = inner.extend_proof("c", register_proof).await?;
+    let proof = view
+        .collection2
+        .extend_proof("a", inner_proof)
+        .await?
+        .in_field(&view, "collection2")
+        .await?;
+    let positions = [
+        field("collection2"),
+        ProofPosition::entry("a")?,
+        ProofPosition::entry("c")?,
+    ];
+    assert_eq!(proof.verify_value::<u32>(&hash, &positions)?, 2);
+    assert_eq!(proof.root_hash()?, hash);
+
+    // The proof doesn't hold for other positions.
+    let wrong_positions = [
+        field("collection2"),
+        ProofPosition::entry("a")?,
+        ProofPosition::entry("b")?,
+    ];
+    assert!(matches!(
+        proof.verify_value::<u32>(&hash, &wrong_positions),
+        Err(ProofError::WrongPosition)
+    ));
+    let mut wrong_proof = proof.clone();
+    let last_step = wrong_proof.path.pop().unwrap();
+    assert!(matches!(
+        wrong_proof.verify_value::<u32>(&hash, &positions[1..]),
+        Err(ProofError::HashMismatch)
+    ));
+    wrong_proof.path.push(last_step);
+
+    // The proof doesn't hold for other values.
+    wrong_proof.leaf = ProofLeaf::Value(bcs::to_bytes(&3u32)?);
+    assert!(matches!(
+        wrong_proof.verify_value::<u32>(&hash, &positions),
+        Err(ProofError::HashMismatch)
+    ));
+
+    // Proofs survive serialization.
+    let proof = bcs::from_bytes::<ViewProof>(&bcs::to_bytes(&proof)?)?;
+    assert_eq!(proof.verify_value::<u32>(&hash, &positions)?, 2);
+
+    // Entries have to exist to be part of a proof.
+    assert!(view
+        .collection
+        .extend_proof("z", ViewProof::new(ProofLeaf::Elements(Vec::new())))
+        .await
+        .is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_view_proofs_reject_resplit_content() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+
+    // The hash of a map doesn't delimit keys from values: moving the first byte of the
+    // serialized value `[1, 3]`, i.e. its length, into the key `[1]` gives the same hash.
+    let mut map = MapView::<_, u8, Vec<u8>>::load(context.clone()).await?;
+    map.insert(&1, vec![3])?;
+    let hash = map.hash().await?;
+    let proof = map.proof().await?;
+    assert_eq!(
+        proof.verify_entries::<u8, Vec<u8>>(&hash, &[])?,
+        [(1, vec![3])]
+    );
+    let ProofLeaf::Entries(entries) = &proof.leaf else {
+        panic!("a map proof has entries");
+    };
+    assert_eq!(entries, &[(vec![1], vec![1, 3])]);
+    let resplit_proof = ViewProof::new(ProofLeaf::Entries(vec![(vec![1, 1], vec![3])]));
+    assert_eq!(resplit_proof.root_hash()?, hash);
+    assert!(matches!(
+        resplit_proof.verify_entries::<u8, Vec<u8>>(&hash, &[]),
+        Err(ProofError::BcsError(_))
+    ));
+
+    // Likewise, the elements of a log can be split differently for the same hash.
+    let mut log = LogView::<_, Vec<u8>>::load(context).await?;
+    log.push(vec![5]);
+    log.push(vec![]);
+    let hash = log.hash().await?;
+    let proof = log.proof().await?;
+    assert_eq!(
+        proof.verify_elements::<Vec<u8>>(&hash, &[])?,
+        [vec![5], vec![]]
+    );
+    let resplit_proof = ViewProof::new(ProofLeaf::Elements(vec![vec![1], vec![5, 0]]));
+    assert_eq!(resplit_proof.root_hash()?, hash);
+    assert!(matches!(
+        resplit_proof.verify_elements::<Vec<u8>>(&hash, &[]),
+        Err(ProofError::BcsError(_))
+    ));
+    Ok(())
+}
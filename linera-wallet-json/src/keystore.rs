@@ -6,7 +6,9 @@
 use std::path::Path;
 
 use linera_base::{
-    crypto::{AccountPublicKey, AccountSignature, CryptoHash, InMemorySigner, Signer},
+    crypto::{
+        AccountPublicKey, AccountSignature, CryptoHash, InMemorySigner, MultisigPolicy, Signer,
+    },
     identifiers::AccountOwner,
 };
 use linera_persistent::{self as persistent, Persist as _};
@@ -66,6 +68,39 @@ impl Keystore {
         Ok(keys)
     }
 
+    /// Registers a multi-signature owner, persists the keystore, and returns the owner.
+    pub async fn add_multisig_policy(
+        &mut self,
+        policy: MultisigPolicy,
+    ) -> Result<AccountOwner, persistent::file::Error> {
+        let owner = self.0.add_multisig_policy(policy);
+        self.0.persist().await?;
+        Ok(owner)
+    }
+
+    /// Records a co-signer's signature of `value` for the multi-signature `owner` and
+    /// persists the keystore.
+    pub async fn add_partial_signature(
+        &mut self,
+        owner: AccountOwner,
+        value: CryptoHash,
+        signature: AccountSignature,
+    ) -> anyhow::Result<()> {
+        self.0.add_partial_signature(owner, value, signature)?;
+        self.0.persist().await?;
+        Ok(())
+    }
+
+    /// Discards the partial signatures collected for the multi-signature `owner` and
+    /// persists the keystore.
+    pub async fn clear_partial_signatures(
+        &mut self,
+        owner: &AccountOwner,
+    ) -> Result<(), persistent::file::Error> {
+        self.0.clear_partial_signatures(owner);
+        self.0.persist().await
+    }
+
     /// Saves the keystore to disk.
     pub async fn save(&mut self) -> Result<(), persistent::file::Error> {
         self.0.persist().await